@@ -0,0 +1,46 @@
+//! Microbenchmark comparing signature recovery backends.
+//!
+//! Run with `cargo bench --bench signature_recovery --features secp256k1` to
+//! include libsecp256k1; without the feature only `k256` is measured.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use db_test::recovery::RecoveryBackend;
+use db_test::{Workload, WorkloadConfig};
+
+/// Recovers the signer of every transaction in a block with each backend.
+fn bench_recovery_backends(c: &mut Criterion) {
+    let mut group = c.benchmark_group("signature_recovery");
+
+    let num_transactions = 1000;
+    let workload = Workload::generate(WorkloadConfig {
+        num_accounts: 1000,
+        num_transactions,
+        hot_accounts: 1000,
+        seed: 42,
+        chain_id: 1,
+        transactions_per_block: num_transactions,
+        ..Default::default()
+    });
+
+    group.throughput(Throughput::Elements(num_transactions as u64));
+    for backend in RecoveryBackend::available() {
+        group.bench_with_input(
+            BenchmarkId::new("recover", backend.name()),
+            &workload,
+            |b, workload| {
+                b.iter(|| {
+                    black_box(workload)
+                        .transactions
+                        .iter()
+                        .filter(|tx| backend.verify(tx))
+                        .count()
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_recovery_backends);
+criterion_main!(benches);
@@ -0,0 +1,115 @@
+//! Microbenchmarks for the allocations on Block-STM's execution hot path:
+//! recording reads, writing versions that invalidate readers, and whole
+//! blocks at several conflict levels.
+//!
+//! Run with: cargo bench --bench stm_hot_path
+
+use alloy_primitives::{Address, Signature, B256, U256};
+use block_stm_executor::mvhashmap::MVHashMap;
+use block_stm_executor::{AccountState, ExecutorConfig, ParallelExecutor, Transaction, TxnIndex};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::collections::HashMap;
+
+const TRANSACTIONS: u64 = 1000;
+
+/// Transfers between `num_accounts` accounts, each sender's nonces in order.
+fn transfers(num_accounts: u64) -> (Vec<Transaction>, HashMap<Address, AccountState>) {
+    let accounts: Vec<Address> = (0..num_accounts)
+        .map(|index| Address::left_padding_from(&index.to_be_bytes()))
+        .collect();
+    let mut nonces = vec![0u64; accounts.len()];
+    // Signatures are not verified, so a placeholder will do
+    let transactions = (0..TRANSACTIONS)
+        .map(|index| {
+            let sender = (index % num_accounts) as usize;
+            let receiver = ((index * 7 + 1) % num_accounts) as usize;
+            nonces[sender] += 1;
+            Transaction {
+                from: accounts[sender],
+                to: accounts[receiver],
+                value: U256::from(1),
+                token: None,
+                nonce: nonces[sender] - 1,
+                signature: Signature::new(U256::from(1), U256::from(1), false),
+                tx_hash: B256::left_padding_from(&index.to_be_bytes()),
+                compute_cost: 0,
+                dependencies: Vec::new(),
+            }
+        })
+        .collect();
+    let initial_states = accounts
+        .iter()
+        .map(|&address| (address, AccountState::new(0, U256::from(1_000_000))))
+        .collect();
+    (transactions, initial_states)
+}
+
+/// Each transaction reads then writes one of a few hot accounts, so every
+/// write invalidates the handful of readers of the version before it.
+fn bench_mvhashmap(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stm_hot_path/mvhashmap");
+    group.throughput(Throughput::Elements(TRANSACTIONS));
+
+    for hot_accounts in [1u8, 4, 64] {
+        let addresses: Vec<Address> = (0..hot_accounts).map(Address::repeat_byte).collect();
+        group.bench_with_input(BenchmarkId::new("read_write", hot_accounts), &addresses, |b, addresses| {
+            b.iter(|| {
+                let mv = MVHashMap::new();
+                let mut invalidated = 0;
+                for txn_idx in 0..TRANSACTIONS as TxnIndex {
+                    let address = addresses[txn_idx as usize % addresses.len()];
+                    // Later transactions read first, as they would under speculation
+                    for reader in 1..=3 {
+                        mv.read_recorded(address, txn_idx + reader);
+                    }
+                    let state = AccountState::new(u64::from(txn_idx), U256::from(txn_idx));
+                    invalidated += mv.write(address, txn_idx, 0, state).invalidated_readers.len();
+                }
+                black_box(invalidated)
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Whole blocks of transfers on four threads, from every transaction
+/// conflicting to almost none.
+///
+/// Prints each level's executions and the invalidations skipped because a
+/// re-execution wrote what it wrote before.
+fn bench_blocks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stm_hot_path/execute_block");
+    group.throughput(Throughput::Elements(TRANSACTIONS));
+
+    for num_accounts in [2u64, 16, 1000] {
+        let (transactions, initial_states) = transfers(num_accounts);
+        let executor = ParallelExecutor::new(ExecutorConfig {
+            num_threads: 4,
+            verify_signatures: false,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        });
+        let result = executor.execute_block(&transactions);
+        println!(
+            "{} accounts: {} executions for {} transactions, {} invalidations skipped",
+            num_accounts,
+            result.total_executions,
+            transactions.len(),
+            result.skipped_invalidations
+        );
+        group.bench_with_input(
+            BenchmarkId::new("accounts", num_accounts),
+            &transactions,
+            |b, transactions| b.iter(|| executor.execute_block(black_box(transactions)).successful),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_mvhashmap, bench_blocks);
+criterion_main!(benches);
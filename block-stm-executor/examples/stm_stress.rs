@@ -0,0 +1,88 @@
+//! Example: Block-STM correctness stress test
+//!
+//! Executes one small, heavily contended block hundreds of times across
+//! varying thread counts with random worker delays injected, and checks that
+//! every run commits the same state as a single-threaded reference.
+//!
+//! Run with: cargo run --example stm_stress [iterations] [seed]
+
+use alloy_primitives::{Address, Signature, B256, U256};
+use block_stm_executor::{stress, AccountState, ExecutorConfig, StressConfig, Transaction};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Accounts transferring to each other; few enough that most transactions conflict.
+const ACCOUNTS: u8 = 8;
+const TRANSACTIONS: u64 = 64;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut stress_config = StressConfig::default();
+    if let Some(iterations) = args.next() {
+        stress_config.iterations = iterations.parse().expect("iterations must be a number");
+    }
+    if let Some(seed) = args.next() {
+        stress_config.seed = seed.parse().expect("seed must be a number");
+    }
+
+    // Signatures are not verified, so a placeholder will do
+    let accounts: Vec<Address> = (1..=ACCOUNTS).map(Address::repeat_byte).collect();
+    let mut nonces = vec![0u64; accounts.len()];
+    let transactions: Vec<Transaction> = (0..TRANSACTIONS)
+        .map(|index| {
+            let sender = (index * 3 % ACCOUNTS as u64) as usize;
+            let receiver = (sender + 1 + index as usize % 3) % accounts.len();
+            nonces[sender] += 1;
+            Transaction {
+                from: accounts[sender],
+                to: accounts[receiver],
+                value: U256::from(index + 1),
+                token: None,
+                nonce: nonces[sender] - 1,
+                signature: Signature::new(U256::from(1), U256::from(1), false),
+                tx_hash: B256::with_last_byte(index as u8),
+                compute_cost: 0,
+                dependencies: Vec::new(),
+            }
+        })
+        .collect();
+    let config = ExecutorConfig {
+        num_threads: 4,
+        verify_signatures: false,
+        initial_states: accounts
+            .iter()
+            .map(|&address| (address, AccountState::new(0, U256::from(1_000_000))))
+            .collect::<HashMap<_, _>>(),
+        lookahead: None,
+        memory_budget: None,
+        dependency_hints: Vec::new(),
+        checkpoints: Vec::new(),
+    };
+
+    println!("=== Block-STM Stress Test ===\n");
+    println!(
+        "{} transactions over {} accounts, {} runs on {:?} threads, seed {}",
+        transactions.len(),
+        accounts.len(),
+        stress_config.iterations,
+        stress_config.thread_counts,
+        stress_config.seed
+    );
+
+    let start = Instant::now();
+    match stress(&config, &transactions, &stress_config) {
+        Ok(report) => {
+            println!(
+                "All {} runs matched the reference in {:?} ({} executions single-threaded, up to {} perturbed)",
+                report.runs,
+                start.elapsed(),
+                report.reference_executions,
+                report.max_executions
+            );
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
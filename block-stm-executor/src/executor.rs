@@ -1,12 +1,15 @@
 //! Parallel transaction executor with Block-STM.
 
 use crate::mvhashmap::{MVHashMap, ReadResult};
+use crate::schedule::Schedule;
 use crate::scheduler::{Scheduler, Task};
-use crate::types::{AccountState, Incarnation, TxnIndex, Version};
-use alloy_primitives::{Address, Signature, B256, U256};
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use crate::sync::{Arc, AtomicBool, AtomicUsize, Mutex, Ordering};
+use crate::types::{AccountState, Incarnation, TokenId, TxnIndex, TxnIndices, Version};
+use alloy_primitives::{keccak256, Address, Signature, B256, U256};
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -18,6 +21,18 @@ pub enum ExecutionError {
     /// Retry needed - a dependency hasn't been resolved yet.
     /// The transaction should be re-executed after invalidation.
     Retry,
+    /// Execution panicked with this message; treated as a permanent failure.
+    Panicked(String),
+}
+
+/// What a successful incarnation read and wrote, kept so a re-execution
+/// whose reads are unchanged can write the same values without executing.
+#[derive(Debug, Clone)]
+struct ExecutionRecord {
+    /// Each read account and the version it resolved to; `None` for storage.
+    reads: [(Address, Option<Version>); 2],
+    /// Each written account and its new state.
+    writes: [(Address, AccountState); 2],
 }
 
 /// A simplified transaction for execution.
@@ -29,11 +44,21 @@ pub struct Transaction {
     pub from: Address,
     pub to: Address,
     pub value: U256,
+    /// Token moved by the transfer; `None` moves the native balance.
+    pub token: Option<TokenId>,
     pub nonce: u64,
     /// The ECDSA signature for verification (done in parallel).
     pub signature: Signature,
     /// The hash that was signed.
     pub tx_hash: B256,
+    /// Keccak iterations each execution runs before the transfer, to model
+    /// heterogeneous execution costs.
+    pub compute_cost: u64,
+    /// Earlier transactions of the same block this one is declared to depend
+    /// on, e.g. by a static analysis of the accounts it touches. It is not
+    /// scheduled until they have executed once, as with
+    /// [`ExecutorConfig::dependency_hints`]; empty declares nothing.
+    pub dependencies: Vec<TxnIndex>,
 }
 
 impl Transaction {
@@ -51,6 +76,15 @@ impl Transaction {
             .map(|addr| addr == self.from)
             .unwrap_or(false)
     }
+
+    /// Runs the transaction's synthetic work and returns the last digest.
+    pub fn burn_compute(&self) -> B256 {
+        let mut digest = self.tx_hash;
+        for _ in 0..self.compute_cost {
+            digest = keccak256(digest);
+        }
+        std::hint::black_box(digest)
+    }
 }
 
 /// Configuration for parallel execution.
@@ -62,6 +96,22 @@ pub struct ExecutorConfig {
     pub verify_signatures: bool,
     /// Initial account states (address -> (nonce, balance)).
     pub initial_states: HashMap<Address, AccountState>,
+    /// Maximum distance past the commit point a transaction may be scheduled
+    /// at; `None` schedules the whole block at once.
+    pub lookahead: Option<usize>,
+    /// Bytes of speculative state the multi-version map may hold; `None` is unbounded.
+    ///
+    /// Over budget, versions no longer readable are pruned first; if that is not
+    /// enough, scheduling is throttled to one transaction per thread past the
+    /// commit point until usage drops back under the budget.
+    pub memory_budget: Option<usize>,
+    /// Predicted `(earlier, later)` dependencies, e.g. conflicts observed in a
+    /// previous run; `later` is not scheduled until `earlier` has executed.
+    pub dependency_hints: Vec<(TxnIndex, TxnIndex)>,
+    /// Increasing exclusive end indices at which to snapshot state, e.g. block
+    /// boundaries. Ignored when `memory_budget` is set, since pruning drops the
+    /// older versions a snapshot needs.
+    pub checkpoints: Vec<TxnIndex>,
 }
 
 /// Result of parallel block execution.
@@ -73,138 +123,720 @@ pub struct BlockExecutionResult {
     pub failed: usize,
     /// Total number of transaction executions (including re-executions).
     pub total_executions: usize,
+    /// Re-executions skipped because every read of the previous incarnation
+    /// still resolved to the same version; not counted in `total_executions`.
+    pub revalidated: usize,
+    /// Queued executions dropped because a later incarnation of the same
+    /// transaction was queued since.
+    pub suppressed_duplicates: usize,
+    /// Whether each transaction (by index) succeeded in its final incarnation.
+    pub tx_success: Vec<bool>,
     /// Final account states after execution.
     pub final_states: Vec<(Address, AccountState)>,
+    /// Average number of transactions executing at once, sampled while the
+    /// workers ran.
+    pub avg_concurrency: f64,
+    /// Largest number of transactions executed past the commit point.
+    pub max_commit_lag: usize,
+    /// Average number of transactions executed past the commit point.
+    pub avg_commit_lag: f64,
+    /// Observed `(writer, reader)` conflicts: each write that invalidated a
+    /// speculative read, sorted and deduplicated.
+    pub conflicts: Vec<(TxnIndex, TxnIndex)>,
+    /// Highest estimated memory held by the multi-version map, in bytes.
+    pub peak_memory_usage: usize,
+    /// Readers not invalidated because a re-execution rewrote the value they
+    /// read unchanged.
+    pub skipped_invalidations: usize,
+    /// Whether execution was cancelled before every transaction committed.
+    /// If so, `tx_success` and `final_states` only cover the committed prefix.
+    pub cancelled: bool,
+    /// Whether the block was discarded because a transaction failed, with
+    /// [`atomic blocks`](ParallelExecutor::with_atomic_blocks). If so,
+    /// `final_states` and `checkpoint_states` are empty; the counts and
+    /// `tx_success` still report how each transaction fared on its own.
+    pub discarded: bool,
+    /// For each reached checkpoint, the states written since the previous one
+    /// as of the checkpoint.
+    pub checkpoint_states: Vec<Vec<(Address, AccountState)>>,
+    /// Committed transactions that failed because their final execution
+    /// panicked, by index.
+    pub poisoned: Vec<PoisonedTransaction>,
+    /// The run's dispatch order, if recorded.
+    pub schedule: Option<Schedule>,
+    /// Index of the first event of the replayed schedule that this run could
+    /// not follow; `None` if it followed the whole schedule or replayed none.
+    pub replay_divergence: Option<usize>,
     /// Execution time.
     pub duration: Duration,
 }
 
+/// A transaction whose execution panicked.
+///
+/// The panic is caught on the worker, so the rest of the block still
+/// executes; the transaction fails without writing anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoisonedTransaction {
+    pub txn_idx: TxnIndex,
+    pub incarnation: Incarnation,
+    /// The panic message.
+    pub message: String,
+}
+
+/// Extracts the message of a caught panic.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => (*message).to_string(),
+            Err(_) => "non-string panic payload".to_string(),
+        },
+    }
+}
+
+/// Samples how many transactions are executing at once on a background thread.
+struct ConcurrencySampler {
+    done: Arc<AtomicBool>,
+    handle: thread::JoinHandle<f64>,
+}
+
+impl ConcurrencySampler {
+    /// Interval between samples of the active counter.
+    const INTERVAL: Duration = Duration::from_micros(20);
+
+    fn start(active: Arc<AtomicUsize>) -> Self {
+        let done = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let done = done.clone();
+            thread::spawn(move || {
+                let (mut sum, mut samples) = (0usize, 0usize);
+                while !done.load(Ordering::Acquire) {
+                    sum += active.load(Ordering::Relaxed);
+                    samples += 1;
+                    thread::sleep(Self::INTERVAL);
+                }
+                if samples == 0 {
+                    0.0
+                } else {
+                    sum as f64 / samples as f64
+                }
+            })
+        };
+        Self { done, handle }
+    }
+
+    /// Stops sampling and returns the average number of active transactions.
+    fn finish(self) -> f64 {
+        self.done.store(true, Ordering::Release);
+        self.handle.join().expect("Sampler thread panicked")
+    }
+}
+
+/// Cooperative cancellation flag shared between a caller and running workers.
+///
+/// Workers check it between tasks, so a cancelled block stops after the
+/// transactions currently executing finish.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation; every clone observes it.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Returns true once cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Random delays injected into workers to vary how their tasks interleave.
+///
+/// Only meant for testing: a schedule that is rare on an idle machine, such as
+/// a reader running between a writer's execution and its validation, becomes
+/// common once workers stall at random points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Perturbation {
+    /// Seed for the delays; each worker derives its own stream from it.
+    pub seed: u64,
+    /// Probability of a delay at each injection point.
+    pub probability: f64,
+    /// Longest delay.
+    pub max_delay: Duration,
+}
+
+impl Perturbation {
+    /// Draws the next delay from a worker's `state`, if one is due.
+    fn next_delay(&self, state: &mut u64) -> Option<Duration> {
+        let roll = splitmix64(state);
+        if (roll >> 11) as f64 / (1u64 << 53) as f64 >= self.probability {
+            return None;
+        }
+        let max_nanos = self.max_delay.as_nanos().max(1) as u64;
+        Some(Duration::from_nanos(splitmix64(state) % max_nanos))
+    }
+
+    /// Sleeps for the next delay drawn from `state`, if one is due.
+    fn stall(&self, state: &mut u64) {
+        if let Some(delay) = self.next_delay(state) {
+            thread::sleep(delay);
+        }
+    }
+}
+
+/// Advances a SplitMix64 `state` and returns the next output.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Receives live progress from the workers, e.g. to drive a dashboard.
+///
+/// Methods are called from worker threads between transactions, so they
+/// should do little more than update counters.
+pub trait ExecutionObserver: Send + Sync {
+    /// `worker` spent `busy` executing one incarnation of a transaction.
+    fn executed(&self, worker: usize, busy: Duration);
+
+    /// `count` more transactions of the current block committed.
+    fn committed(&self, count: usize);
+}
+
+/// A worker's handle on the executor's observer.
+struct WorkerObserver<'a> {
+    observer: &'a dyn ExecutionObserver,
+    worker: usize,
+    /// Committed count already reported, shared by every worker so each
+    /// commit is reported once.
+    reported: &'a AtomicUsize,
+}
+
+impl WorkerObserver<'_> {
+    fn executed(&self, busy: Duration, scheduler: &Scheduler) {
+        self.observer.executed(self.worker, busy);
+        let committed = scheduler.committed_count();
+        let previous = self.reported.fetch_max(committed, Ordering::AcqRel);
+        if committed > previous {
+            self.observer.committed(committed - previous);
+        }
+    }
+}
+
+/// State of one block run, borrowed by every worker.
+struct BlockRun<'a> {
+    scheduler: &'a Scheduler,
+    mv_hashmap: &'a MVHashMap,
+    transactions: &'a [Transaction],
+    initial_states: &'a HashMap<Address, AccountState>,
+    verify_signatures: bool,
+    /// Memory budget and the worker count to throttle back to.
+    memory_budget: Option<(usize, usize)>,
+    /// In nonce-less mode, whether each transaction repeats an earlier id.
+    replayed_ids: Option<Vec<bool>>,
+    write_hook: Option<fn(&Transaction)>,
+    cancel: &'a CancellationToken,
+    execution_count: AtomicUsize,
+    revalidated_count: AtomicUsize,
+    success_count: AtomicUsize,
+    failed_flags: Vec<AtomicBool>,
+    /// Reads and writes of each transaction's last incarnation, if it succeeded.
+    records: Vec<Mutex<Option<ExecutionRecord>>>,
+    /// Workers currently executing, also read by the concurrency sampler.
+    active: Arc<AtomicUsize>,
+    conflicts: Mutex<Vec<(TxnIndex, TxnIndex)>>,
+    poisoned: Mutex<Vec<PoisonedTransaction>>,
+    reported: AtomicUsize,
+}
+
 /// Parallel Block-STM executor.
 pub struct ParallelExecutor {
     config: ExecutorConfig,
+    perturbation: Option<Perturbation>,
+    observer: Option<std::sync::Arc<dyn ExecutionObserver>>,
+    /// Transaction ids used before the block, in nonce-less mode.
+    seen_ids: Option<HashSet<u64>>,
+    /// Whether to record each block's dispatch order.
+    record_schedule: bool,
+    /// Schedules to replay, one per block.
+    replay: Vec<Schedule>,
+    /// Whether a block with any failed transaction is discarded whole.
+    atomic_blocks: bool,
+    /// Transactions rejected before reaching the engine, one count per block.
+    rejected: Vec<usize>,
+    /// Called between each execution's sender and receiver writes.
+    write_hook: Option<fn(&Transaction)>,
 }
 
 impl ParallelExecutor {
     /// Creates a new parallel executor.
     pub fn new(config: ExecutorConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            perturbation: None,
+            observer: None,
+            seen_ids: None,
+            record_schedule: false,
+            replay: Vec::new(),
+            atomic_blocks: false,
+            rejected: Vec::new(),
+            write_hook: None,
+        }
+    }
+
+    /// Injects random delays into the workers; see [`Perturbation`].
+    pub fn with_perturbation(mut self, perturbation: Perturbation) -> Self {
+        self.perturbation = Some(perturbation);
+        self
+    }
+
+    /// Reports worker activity and commits to `observer` while executing.
+    pub fn with_observer(mut self, observer: std::sync::Arc<dyn ExecutionObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Treats [`Transaction::nonce`] as a unique transaction id rather than a
+    /// sequence number.
+    ///
+    /// Transactions skip the nonce check and leave the sender's nonce as it
+    /// is, so a sender's transactions only depend on each other through the
+    /// balances they touch. A transaction fails if its id is in `seen_ids` or
+    /// was used by an earlier transaction of the block, or of an earlier
+    /// block in [`execute_blocks`](Self::execute_blocks).
+    pub fn with_nonceless(mut self, seen_ids: HashSet<u64>) -> Self {
+        self.seen_ids = Some(seen_ids);
+        self
+    }
+
+    /// Records each block's task dispatch order in
+    /// [`BlockExecutionResult::schedule`].
+    pub fn with_schedule_recording(mut self) -> Self {
+        self.record_schedule = true;
+        self
+    }
+
+    /// Replays recorded dispatch orders, the first for
+    /// [`execute_block`](Self::execute_block) and the i-th for block i of
+    /// [`execute_blocks`](Self::execute_blocks); blocks past the end schedule
+    /// freely.
+    ///
+    /// A replay only forces the same interleaving on the same transactions,
+    /// initial states and configuration. Where it cannot follow the
+    /// schedule, it schedules freely from there and reports the event in
+    /// [`BlockExecutionResult::replay_divergence`].
+    pub fn with_schedule_replay(mut self, schedules: Vec<Schedule>) -> Self {
+        self.replay = schedules;
+        self
+    }
+
+    /// Makes every block all-or-nothing, as a rollup treats a batch: if any
+    /// transaction fails, none of the block's writes are kept and the result
+    /// is marked [`discarded`](BlockExecutionResult::discarded).
+    ///
+    /// In [`execute_blocks`](Self::execute_blocks), the next block starts
+    /// from the state before the discarded one, and in nonce-less mode the
+    /// discarded block's ids count as unused.
+    pub fn with_atomic_blocks(mut self) -> Self {
+        self.atomic_blocks = true;
+        self
+    }
+
+    /// Counts transactions the caller rejected before handing each block to
+    /// the engine, the first for [`execute_block`](Self::execute_block) and
+    /// the i-th for block i of [`execute_blocks`](Self::execute_blocks).
+    ///
+    /// With [atomic blocks](Self::with_atomic_blocks), a block with any
+    /// rejected transaction is discarded as if one of its own had failed.
+    pub fn with_rejected(mut self, rejected: Vec<usize>) -> Self {
+        self.rejected = rejected;
+        self
+    }
+
+    /// Calls `hook` between each execution's sender and receiver writes, so
+    /// tests can panic mid-execution.
+    #[cfg(all(test, not(loom)))]
+    fn with_write_hook(mut self, hook: fn(&Transaction)) -> Self {
+        self.write_hook = Some(hook);
+        self
     }
 
     /// Executes a block of transactions in parallel.
-    pub fn execute_block(&self, transactions: Vec<Transaction>) -> BlockExecutionResult {
+    ///
+    /// Workers borrow the transactions, so a `Vec`, a slice or an
+    /// `Arc<[Transaction]>` is executed in place without being copied.
+    ///
+    /// # Panics
+    ///
+    /// If the block holds more than [`MAX_BLOCK_SIZE`](crate::types::MAX_BLOCK_SIZE) transactions,
+    /// or a replayed schedule dispatches to more workers than `num_threads`.
+    pub fn execute_block(&self, transactions: impl AsRef<[Transaction]>) -> BlockExecutionResult {
+        self.execute_block_with_cancel(transactions, &CancellationToken::new())
+    }
+
+    /// Executes consecutive blocks, each as its own Block-STM run, carrying
+    /// the state every block commits into the next.
+    ///
+    /// Each block commits before the next starts, as on a node, so a block's
+    /// duration and memory are its own rather than a share of one merged run.
+    /// Each result covers one block: indices restart at zero and
+    /// `final_states` lists only the accounts that block wrote.
+    /// `dependency_hints` index the concatenated blocks; hints across blocks
+    /// are dropped, since blocks run in order anyway. Declared
+    /// [`dependencies`](Transaction::dependencies) index the transaction's own
+    /// block. `checkpoints` is ignored.
+    /// Blocks are taken as in [`execute_block`](Self::execute_block).
+    pub fn execute_blocks<B>(&self, blocks: impl IntoIterator<Item = B>) -> Vec<BlockExecutionResult>
+    where
+        B: AsRef<[Transaction]>,
+    {
+        self.execute_blocks_with(blocks, |_| {})
+    }
+
+    /// Executes consecutive blocks like [`execute_blocks`](Self::execute_blocks),
+    /// calling `on_block` with each block's result as soon as it commits.
+    ///
+    /// The next block starts once `on_block` returns, so a caller can hand a
+    /// block's writes off to be persisted while the next block executes.
+    pub fn execute_blocks_with<B>(
+        &self,
+        blocks: impl IntoIterator<Item = B>,
+        mut on_block: impl FnMut(&BlockExecutionResult),
+    ) -> Vec<BlockExecutionResult>
+    where
+        B: AsRef<[Transaction]>,
+    {
+        let mut initial_states = self.config.initial_states.clone();
+        let mut seen_ids = self.seen_ids.clone();
+        let mut results = Vec::new();
+        let mut start = 0;
+        for (block, transactions) in blocks.into_iter().enumerate() {
+            let transactions = transactions.as_ref();
+            let end = start + transactions.len();
+            let dependency_hints = self
+                .config
+                .dependency_hints
+                .iter()
+                .filter(|&&(earlier, later)| start <= earlier as usize && (later as usize) < end)
+                .map(|&(earlier, later)| (earlier - start as TxnIndex, later - start as TxnIndex))
+                .collect();
+            let executor = Self {
+                config: ExecutorConfig {
+                    num_threads: self.config.num_threads,
+                    verify_signatures: self.config.verify_signatures,
+                    initial_states,
+                    lookahead: self.config.lookahead,
+                    memory_budget: self.config.memory_budget,
+                    dependency_hints,
+                    checkpoints: Vec::new(),
+                },
+                perturbation: self.perturbation,
+                observer: self.observer.clone(),
+                seen_ids: seen_ids.clone(),
+                record_schedule: self.record_schedule,
+                replay: self.replay.get(block).cloned().into_iter().collect(),
+                atomic_blocks: self.atomic_blocks,
+                rejected: self.rejected.get(block).copied().into_iter().collect(),
+                write_hook: self.write_hook,
+            };
+            let result = executor.execute_block(transactions);
+            if let Some(seen_ids) = seen_ids.as_mut().filter(|_| !result.discarded) {
+                seen_ids.extend(transactions.iter().map(|tx| tx.nonce));
+            }
+            initial_states = executor.config.initial_states;
+            initial_states.extend(result.final_states.iter().cloned());
+            on_block(&result);
+            results.push(result);
+            start = end;
+        }
+        results
+    }
+
+    /// Executes a block of transactions in parallel until done or `cancel` fires.
+    ///
+    /// A cancelled run returns the transactions committed so far and the state
+    /// they produced, with `cancelled` set.
+    pub fn execute_block_with_cancel(
+        &self,
+        transactions: impl AsRef<[Transaction]>,
+        cancel: &CancellationToken,
+    ) -> BlockExecutionResult {
         let start = Instant::now();
+        let transactions = transactions.as_ref();
         let num_txns = transactions.len();
-        
-        // Create shared state
-        let scheduler = Scheduler::new(num_txns);
-        let mv_hashmap = Arc::new(MVHashMap::new());
-        let transactions = Arc::new(transactions);
-        let execution_count = Arc::new(AtomicUsize::new(0));
-        let success_count = Arc::new(AtomicUsize::new(0));
-        let fail_count = Arc::new(AtomicUsize::new(0));
-        
-        // Spawn worker threads
-        let mut handles = Vec::new();
-        for _ in 0..self.config.num_threads {
-            let scheduler = scheduler.clone();
-            let mv_hashmap = mv_hashmap.clone();
-            let transactions = transactions.clone();
-            let initial_states = self.config.initial_states.clone();
-            let verify_signatures = self.config.verify_signatures;
-            let execution_count = execution_count.clone();
-            let success_count = success_count.clone();
-            let fail_count = fail_count.clone();
-            
-            let handle = thread::spawn(move || {
-                Self::worker_loop(
-                    scheduler,
-                    mv_hashmap,
-                    transactions,
-                    initial_states,
-                    verify_signatures,
-                    execution_count,
-                    success_count,
-                    fail_count,
-                );
-            });
-            
-            handles.push(handle);
+        let replay = self.replay.first().cloned();
+        if let Some(replay) = &replay {
+            assert!(
+                replay.num_workers() <= self.config.num_threads,
+                "Replayed schedule needs {} workers, executor has {}",
+                replay.num_workers(),
+                self.config.num_threads
+            );
         }
         
-        // Wait for all workers to finish
-        for handle in handles {
-            handle.join().expect("Worker thread panicked");
+        // Declared dependencies hold transactions back like hints
+        let mut dependency_hints = Cow::Borrowed(self.config.dependency_hints.as_slice());
+        for (later, tx) in transactions.iter().enumerate() {
+            for &earlier in &tx.dependencies {
+                dependency_hints.to_mut().push((earlier, later as TxnIndex));
+            }
         }
+
+        // Create shared state
+        let scheduler = Scheduler::with_schedule(
+            num_txns,
+            self.config.lookahead,
+            &dependency_hints,
+            self.record_schedule,
+            replay,
+        );
+        // In nonce-less mode, whether each transaction repeats an earlier id
+        let replayed_ids = self.seen_ids.as_ref().map(|seen_ids| {
+            let mut seen_ids = seen_ids.clone();
+            transactions.iter().map(|tx| !seen_ids.insert(tx.nonce)).collect()
+        });
+        let run = BlockRun {
+            scheduler: &scheduler,
+            mv_hashmap: scheduler.mv_hashmap(),
+            transactions,
+            initial_states: &self.config.initial_states,
+            verify_signatures: self.config.verify_signatures,
+            memory_budget: self.config.memory_budget.map(|budget| (budget, self.config.num_threads)),
+            replayed_ids,
+            write_hook: self.write_hook,
+            cancel,
+            execution_count: AtomicUsize::new(0),
+            revalidated_count: AtomicUsize::new(0),
+            success_count: AtomicUsize::new(0),
+            failed_flags: (0..num_txns).map(|_| AtomicBool::new(false)).collect(),
+            records: (0..num_txns).map(|_| Mutex::new(None)).collect(),
+            active: Arc::new(AtomicUsize::new(0)),
+            conflicts: Mutex::new(Vec::new()),
+            poisoned: Mutex::new(Vec::new()),
+            reported: AtomicUsize::new(0),
+        };
+        let sampler = ConcurrencySampler::start(run.active.clone());
         
-        let duration = start.elapsed();
+        // Spawn worker threads, which borrow the block run and are all joined
+        // when the scope ends
+        thread::scope(|scope| {
+            for worker in 0..self.config.num_threads {
+                let run = &run;
+                let perturbation = self
+                    .perturbation
+                    .map(|perturbation| (perturbation, perturbation.seed ^ (worker as u64).rotate_left(32)));
+                let observer = self.observer.as_deref().map(|observer| WorkerObserver {
+                    observer,
+                    worker,
+                    reported: &run.reported,
+                });
+                scope.spawn(move || Self::worker_loop(worker, run, perturbation, observer));
+            }
+        });
+        let avg_concurrency = sampler.finish();
+        let BlockRun {
+            mv_hashmap,
+            execution_count,
+            revalidated_count,
+            failed_flags,
+            conflicts,
+            poisoned,
+            reported,
+            ..
+        } = run;
+        let mut conflicts = std::mem::take(&mut *conflicts.lock());
+        conflicts.sort_unstable();
+        conflicts.dedup();
         
-        // Collect final states
-        let final_states = mv_hashmap.get_committed_states();
+        let duration = start.elapsed();
         
         // The number of committed transactions is the final committed index
         let committed_count = scheduler.committed_count();
+        let commit_lag = scheduler.commit_lag();
+        let cancelled = committed_count < num_txns;
+        if let Some(observer) = &self.observer {
+            let unreported = committed_count.saturating_sub(reported.load(Ordering::Acquire));
+            if unreported > 0 {
+                observer.committed(unreported);
+            }
+        }
+        
+        let tx_success: Vec<bool> = failed_flags[..committed_count]
+            .iter()
+            .map(|failed| !failed.load(Ordering::Acquire))
+            .collect();
+        // Counted from final outcomes: an incarnation that failed and then
+        // re-executed successfully is no failure
+        let failed = tx_success.iter().filter(|&&success| !success).count();
+        let rejected = self.rejected.first().copied().unwrap_or(0);
+        let discarded = self.atomic_blocks && !cancelled && failed + rejected > 0;
+        
+        // Collect final states from committed incarnations only; a cancelled
+        // run only keeps the committed prefix, and a discarded one nothing
+        let final_states = if discarded {
+            Vec::new()
+        } else {
+            mv_hashmap.get_committed_states(&scheduler.committed_versions())
+        };
+        let checkpoint_states = if self.config.memory_budget.is_some() || discarded {
+            Vec::new()
+        } else {
+            let ends = self.config.checkpoints.iter().copied().take_while(|&end| end as usize <= committed_count);
+            let starts = std::iter::once(0).chain(self.config.checkpoints.iter().copied());
+            starts
+                .zip(ends)
+                .map(|(start, end)| mv_hashmap.get_written_states(start, end))
+                .collect()
+        };
+        
+        // Keep each transaction's last panic, if it still failed in the end
+        let mut poisoned = std::mem::take(&mut *poisoned.lock());
+        poisoned.sort_by_key(|poison| (poison.txn_idx, std::cmp::Reverse(poison.incarnation)));
+        poisoned.dedup_by_key(|poison| poison.txn_idx);
+        poisoned.retain(|poison| tx_success.get(poison.txn_idx as usize) == Some(&false));
         
         BlockExecutionResult {
-            successful: committed_count - fail_count.load(Ordering::Acquire),
-            failed: fail_count.load(Ordering::Acquire),
+            successful: committed_count - failed,
+            failed,
             total_executions: execution_count.load(Ordering::Acquire),
+            revalidated: revalidated_count.load(Ordering::Acquire),
+            suppressed_duplicates: scheduler.suppressed_duplicates(),
+            tx_success,
             final_states,
+            avg_concurrency,
+            max_commit_lag: commit_lag.max,
+            avg_commit_lag: commit_lag.average,
+            conflicts,
+            peak_memory_usage: mv_hashmap.peak_memory_usage(),
+            skipped_invalidations: mv_hashmap.skipped_invalidations(),
+            cancelled,
+            discarded,
+            checkpoint_states,
+            poisoned,
+            schedule: scheduler.recorded_schedule(),
+            replay_divergence: scheduler.replay_divergence(),
             duration,
         }
     }
 
     /// Worker thread main loop.
     fn worker_loop(
-        scheduler: Arc<Scheduler>,
-        mv_hashmap: Arc<MVHashMap>,
-        transactions: Arc<Vec<Transaction>>,
-        initial_states: HashMap<Address, AccountState>,
-        verify_signatures: bool,
-        execution_count: Arc<AtomicUsize>,
-        success_count: Arc<AtomicUsize>,
-        fail_count: Arc<AtomicUsize>,
+        worker: usize,
+        run: &BlockRun<'_>,
+        mut perturbation: Option<(Perturbation, u64)>,
+        observer: Option<WorkerObserver<'_>>,
     ) {
-        loop {
-            match scheduler.next_task() {
+        while !run.cancel.is_cancelled() {
+            if let Some((budget, num_threads)) = run.memory_budget {
+                if run.mv_hashmap.memory_usage() > budget {
+                    run.mv_hashmap.prune_committed(run.scheduler.committed_count() as TxnIndex);
+                }
+                let over_budget = run.mv_hashmap.memory_usage() > budget;
+                run.scheduler.throttle(over_budget.then_some(num_threads));
+            }
+            
+            match run.scheduler.next_task_for(worker) {
                 Task::Execute(txn_idx, incarnation) => {
-                    execution_count.fetch_add(1, Ordering::Relaxed);
+                    if let Some(invalidated) = Self::revalidate(run, txn_idx, incarnation) {
+                        run.revalidated_count.fetch_add(1, Ordering::Relaxed);
+                        if !invalidated.is_empty() {
+                            run.conflicts
+                                .lock()
+                                .extend(invalidated.iter().map(|&reader| (txn_idx, reader)));
+                        }
+                        run.scheduler.finish_execution(txn_idx, incarnation, invalidated);
+                        continue;
+                    }
                     
-                    let tx = &transactions[txn_idx];
+                    run.execution_count.fetch_add(1, Ordering::Relaxed);
+                    if let Some((perturbation, state)) = &mut perturbation {
+                        perturbation.stall(state);
+                    }
+                    
+                    let tx = &run.transactions[txn_idx as usize];
+                    let replayed_id = run.replayed_ids.as_ref().map(|replayed| replayed[txn_idx as usize]);
                     
-                    // Execute the transaction
-                    let result = Self::execute_transaction(
-                        tx,
-                        txn_idx,
-                        incarnation,
-                        &mv_hashmap,
-                        &initial_states,
-                        verify_signatures,
-                    );
+                    // Execute the transaction; a panic fails it rather than the worker
+                    run.active.fetch_add(1, Ordering::Relaxed);
+                    let started = Instant::now();
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        Self::execute_transaction(run, tx, txn_idx, incarnation, replayed_id)
+                    }))
+                    .unwrap_or_else(|payload| Err(ExecutionError::Panicked(panic_message(payload))));
+                    run.active.fetch_sub(1, Ordering::Relaxed);
+                    let busy = started.elapsed();
+                    // Widen the window between writing and validating
+                    if let Some((perturbation, state)) = &mut perturbation {
+                        perturbation.stall(state);
+                    }
                     
+                    // Only a successful incarnation's reads and writes can be reused
+                    *run.records[txn_idx as usize].lock() = None;
                     match result {
-                        Ok((_read_addrs, _write_addrs, invalidated)) => {
+                        Ok((record, invalidated)) => {
                             // Execution succeeded
-                            success_count.fetch_add(1, Ordering::Relaxed);
+                            run.success_count.fetch_add(1, Ordering::Relaxed);
+                            *run.records[txn_idx as usize].lock() = Some(record);
+                            run.failed_flags[txn_idx as usize].store(false, Ordering::Release);
+                            if !invalidated.is_empty() {
+                                run.conflicts
+                                    .lock()
+                                    .extend(invalidated.iter().map(|&reader| (txn_idx, reader)));
+                            }
                             
                             // Notify scheduler
-                            scheduler.finish_execution(txn_idx, incarnation, invalidated);
+                            run.scheduler.finish_execution(txn_idx, incarnation, invalidated);
                         }
                         Err(ExecutionError::Retry) => {
                             // Transaction couldn't execute due to unmet dependencies.
                             // The reads have been recorded, so when the dependency writes,
                             // this transaction will be invalidated and re-executed.
-                            // Mark as "executed" so it can be invalidated.
-                            scheduler.finish_execution(txn_idx, incarnation, vec![]);
+                            // Mark as "executed" so it can be invalidated; if nothing
+                            // invalidates it, it commits as failed (e.g. a nonce gap),
+                            // without what a previous incarnation wrote
+                            let invalidated = match incarnation {
+                                0 => TxnIndices::new(),
+                                _ => run.mv_hashmap.clear_transaction(txn_idx),
+                            };
+                            run.failed_flags[txn_idx as usize].store(true, Ordering::Release);
+                            run.scheduler.finish_execution(txn_idx, incarnation, invalidated);
                         }
                         Err(ExecutionError::Permanent(_reason)) => {
-                            // Execution failed permanently (e.g., invalid signature)
-                            fail_count.fetch_add(1, Ordering::Relaxed);
+                            // Execution failed permanently (e.g., invalid signature or
+                            // overflow); drop whatever a previous incarnation wrote
+                            let invalidated = match incarnation {
+                                0 => TxnIndices::new(),
+                                _ => run.mv_hashmap.clear_transaction(txn_idx),
+                            };
+                            run.failed_flags[txn_idx as usize].store(true, Ordering::Release);
                             
-                            // Mark as executed with no invalidations
-                            scheduler.finish_execution(txn_idx, incarnation, vec![]);
+                            run.scheduler.finish_execution(txn_idx, incarnation, invalidated);
+                        }
+                        Err(ExecutionError::Panicked(message)) => {
+                            // Drop whatever it wrote before panicking
+                            let invalidated = run.mv_hashmap.clear_transaction(txn_idx);
+                            run.failed_flags[txn_idx as usize].store(true, Ordering::Release);
+                            run.poisoned.lock().push(PoisonedTransaction {
+                                txn_idx,
+                                incarnation,
+                                message,
+                            });
+                            
+                            run.scheduler.finish_execution(txn_idx, incarnation, invalidated);
                         }
                     }
                     
+                    if let Some(observer) = &observer {
+                        observer.executed(busy, run.scheduler);
+                    }
                 }
                 Task::Wait => {
                     // No task available, sleep briefly
@@ -217,113 +849,160 @@ impl ParallelExecutor {
         }
     }
 
-    /// Executes a single transaction and returns read/write sets and invalidations.
+    /// Writes the values `txn_idx`'s last successful incarnation wrote as
+    /// `incarnation` instead of executing, if each of its reads still
+    /// resolves to the same version.
+    ///
+    /// Executing again would read and so write the same values. Returns the
+    /// readers the writes invalidated, or `None` if the transaction must
+    /// execute.
+    fn revalidate(run: &BlockRun<'_>, txn_idx: TxnIndex, incarnation: Incarnation) -> Option<TxnIndices> {
+        if incarnation == 0 {
+            return None;
+        }
+        let record = run.records[txn_idx as usize].lock();
+        let record = record.as_ref()?;
+        if !record
+            .reads
+            .iter()
+            .all(|&(address, version)| run.mv_hashmap.validate_read(address, txn_idx, version))
+        {
+            return None;
+        }
+        
+        let mut invalidated = TxnIndices::new();
+        for (address, state) in &record.writes {
+            invalidated.extend(run.mv_hashmap.write(*address, txn_idx, incarnation, state.clone()).invalidated_readers);
+        }
+        invalidated.sort_unstable();
+        invalidated.dedup();
+        Some(invalidated)
+    }
+
+    /// Executes a single transaction and returns its read/write sets and invalidations.
+    ///
+    /// `replayed_id` is `None` for transactions with nonces, and otherwise
+    /// whether the transaction's id repeats an earlier one.
     /// 
     /// Returns:
     /// - Ok(...) - Transaction executed successfully
-    /// - Err(ExecutionError::Permanent) - Transaction failed permanently (bad signature)
-    /// - Err(ExecutionError::Retry) - Transaction should be retried (nonce/balance dependency)
+    /// - Err(ExecutionError::Permanent) - Transaction failed permanently (bad signature,
+    ///   replayed id, nonce or balance overflow)
+    /// - Err(ExecutionError::Retry) - Transaction should be retried (nonce/balance dependency,
+    ///   or a read of an aborted transaction's estimate)
     fn execute_transaction(
+        run: &BlockRun<'_>,
         tx: &Transaction,
         txn_idx: TxnIndex,
         incarnation: Incarnation,
-        mv_hashmap: &MVHashMap,
-        initial_states: &HashMap<Address, AccountState>,
-        verify_signatures: bool,
-    ) -> Result<(Vec<Address>, Vec<Address>, Vec<TxnIndex>), ExecutionError> {
+        replayed_id: Option<bool>,
+    ) -> Result<(ExecutionRecord, TxnIndices), ExecutionError> {
+        let BlockRun {
+            mv_hashmap,
+            initial_states,
+            verify_signatures,
+            write_hook,
+            ..
+        } = *run;
         // Verify signature if enabled - this is the expensive operation that
         // benefits from parallelization (~50-200μs per signature recovery)
         if verify_signatures && !tx.verify_signature() {
             return Err(ExecutionError::Permanent("Invalid signature".to_string()));
         }
+        if replayed_id == Some(true) {
+            return Err(ExecutionError::Permanent("Replayed transaction id".to_string()));
+        }
+        // Every incarnation pays the synthetic work again
+        tx.burn_compute();
         
         // Read sender account
-        let sender_state = Self::read_account(tx.from, txn_idx, mv_hashmap, initial_states);
+        let (sender_state, sender_version) = Self::read_account(tx.from, txn_idx, mv_hashmap, initial_states)?;
         
-        // Validate nonce - if wrong, we need to retry (dependency not ready)
-        if sender_state.nonce != tx.nonce {
+        // Validate nonce - if wrong, we need to retry (dependency not ready).
+        // Nonce-less transactions were checked against the seen ids instead.
+        if replayed_id.is_none() && sender_state.nonce != tx.nonce {
             // This means a lower-indexed transaction that updates this account
             // hasn't executed yet. We should retry later.
             return Err(ExecutionError::Retry);
         }
         
         // Validate balance - if insufficient, retry (might be updated by another tx)
-        if sender_state.balance < tx.value {
+        let sender_balance = sender_state.balance_of(tx.token);
+        if sender_balance < tx.value {
             return Err(ExecutionError::Retry);
         }
         
         // Read receiver account
-        let receiver_state = Self::read_account(tx.to, txn_idx, mv_hashmap, initial_states);
+        let (receiver_state, receiver_version) = Self::read_account(tx.to, txn_idx, mv_hashmap, initial_states)?;
         
-        // Execute transfer
-        let new_sender_state = AccountState::new(
-            sender_state.nonce + 1,
-            sender_state.balance - tx.value,
-        );
-        let new_receiver_state = AccountState::new(
-            receiver_state.nonce,
-            receiver_state.balance + tx.value,
-        );
+        // Execute transfer of the native balance or the transaction's token.
+        // Overflow fails the transaction; if it came from a speculative read,
+        // that read's invalidation re-executes it.
+        let new_sender_state = sender_state
+            .checked_debit(tx.token, tx.value, replayed_id.is_none())
+            .ok_or_else(|| ExecutionError::Permanent("Sender nonce overflow".to_string()))?;
+        let new_receiver_state = receiver_state
+            .checked_credit(tx.token, tx.value)
+            .ok_or_else(|| ExecutionError::Permanent("Receiver balance overflow".to_string()))?;
         
         // Write updates to multi-version hashmap
-        let mut invalidated = Vec::new();
+        let mut invalidated = TxnIndices::new();
         
-        let write_result_sender = mv_hashmap.write(tx.from, txn_idx, incarnation, new_sender_state);
+        let write_result_sender = mv_hashmap.write(tx.from, txn_idx, incarnation, new_sender_state.clone());
         invalidated.extend(write_result_sender.invalidated_readers);
         
-        let write_result_receiver = mv_hashmap.write(tx.to, txn_idx, incarnation, new_receiver_state);
+        // A test hook may panic here, with the sender already written
+        if let Some(hook) = write_hook {
+            hook(tx);
+        }
+        
+        let write_result_receiver = mv_hashmap.write(tx.to, txn_idx, incarnation, new_receiver_state.clone());
         invalidated.extend(write_result_receiver.invalidated_readers);
         
         // Remove duplicates and sort
         invalidated.sort_unstable();
         invalidated.dedup();
         
-        Ok((
-            vec![tx.from, tx.to],  // read addresses
-            vec![tx.from, tx.to],  // write addresses
-            invalidated,
-        ))
+        let record = ExecutionRecord {
+            reads: [(tx.from, sender_version), (tx.to, receiver_version)],
+            writes: [(tx.from, new_sender_state), (tx.to, new_receiver_state)],
+        };
+        Ok((record, invalidated))
     }
 
-    /// Reads an account from the multi-version hashmap or initial state.
+    /// Reads an account from the multi-version hashmap or initial state, with
+    /// the version read or `None` for initial state.
+    ///
+    /// Fails with `Retry` if the account was last written by an aborted
+    /// transaction that has not re-executed yet.
     fn read_account(
         address: Address,
         reader_txn_idx: TxnIndex,
         mv_hashmap: &MVHashMap,
         initial_states: &HashMap<Address, AccountState>,
-    ) -> AccountState {
-        match mv_hashmap.read(address, reader_txn_idx) {
-            ReadResult::Versioned(version, state) => {
-                // Record this read for push-based invalidation
-                mv_hashmap.record_read(address, reader_txn_idx, version);
-                state
-            }
+    ) -> Result<(AccountState, Option<Version>), ExecutionError> {
+        // The read is recorded for push-based invalidation as it happens:
+        // when a lower-indexed tx writes to this address, we must be invalidated.
+        match mv_hashmap.read_recorded(address, reader_txn_idx) {
+            ReadResult::Versioned(version, state) => Ok((state, Some(version))),
             ReadResult::Storage => {
-                // Record storage read for push-based invalidation!
-                // When a lower-indexed tx writes to this address, we must be invalidated.
-                mv_hashmap.record_storage_read(address, reader_txn_idx);
-                
                 // Read from initial state
-                initial_states
-                    .get(&address)
-                    .copied()
-                    .unwrap_or(AccountState::new(0, U256::ZERO))
-            }
-            ReadResult::Dependency(_) => {
-                // This shouldn't happen in our implementation
-                initial_states
+                let state = initial_states
                     .get(&address)
-                    .copied()
-                    .unwrap_or(AccountState::new(0, U256::ZERO))
+                    .cloned()
+                    .unwrap_or(AccountState::new(0, U256::ZERO));
+                Ok((state, None))
             }
+            // The estimate's re-execution invalidates this read once it writes
+            ReadResult::Dependency(_) => Err(ExecutionError::Retry),
         }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(loom)))]
 mod tests {
     use super::*;
-    use alloy_primitives::keccak256;
+    use crate::schedule::ScheduleEvent;
     use k256::ecdsa::{SigningKey, VerifyingKey};
     use rand::{rngs::StdRng, Rng, SeedableRng};
 
@@ -356,9 +1035,12 @@ mod tests {
                 from: self.address,
                 to,
                 value,
+                token: None,
                 nonce,
                 signature,
                 tx_hash,
+                compute_cost: 0,
+                dependencies: Vec::new(),
             }
         }
     }
@@ -400,6 +1082,10 @@ mod tests {
             num_threads: 2,
             verify_signatures: true,
             initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
         };
         
         let executor = ParallelExecutor::new(config);
@@ -408,5 +1094,691 @@ mod tests {
         assert_eq!(result.successful, 2);
         assert_eq!(result.failed, 0);
     }
-}
 
+    #[test]
+    fn test_checkpoint_states_hold_writes_since_last() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        let acc3 = TestAccount::from_seed(3);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        initial_states.insert(acc2.address, AccountState::new(0, U256::from(1000)));
+        initial_states.insert(acc3.address, AccountState::new(0, U256::from(1000)));
+        
+        let transactions = vec![
+            acc1.sign_tx(acc2.address, U256::from(10), 0),
+            acc2.sign_tx(acc3.address, U256::from(5), 0),
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: vec![1, 2],
+        };
+        
+        let result = ParallelExecutor::new(config).execute_block(transactions);
+        
+        // Each checkpoint holds only the sender and receiver written since the last
+        let mut second = result.checkpoint_states[1].clone();
+        second.sort_by_key(|(address, _)| *address);
+        let mut expected = vec![
+            (acc2.address, AccountState::new(1, U256::from(1005))),
+            (acc3.address, AccountState::new(0, U256::from(1005))),
+        ];
+        expected.sort_by_key(|(address, _)| *address);
+        assert_eq!(result.checkpoint_states.len(), 2);
+        assert_eq!(result.checkpoint_states[0].len(), 2);
+        assert_eq!(second, expected);
+    }
+
+    #[test]
+    fn test_transfers_keep_receiver_code_hash() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        // acc2 holds code, which transfers to it must keep
+        let code_hash = B256::repeat_byte(0xc0);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        initial_states.insert(acc2.address, AccountState::new(0, U256::from(1000)).with_code_hash(code_hash));
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let result = ParallelExecutor::new(config).execute_block(vec![acc1.sign_tx(acc2.address, U256::from(5), 0)]);
+        
+        assert_eq!(result.successful, 1);
+        let receiver = result.final_states.iter().find(|(address, _)| *address == acc2.address).map(|(_, state)| state);
+        assert_eq!(receiver, Some(&AccountState::new(0, U256::from(1005)).with_code_hash(code_hash)));
+    }
+
+    #[test]
+    fn test_tx_success_follows_block_order() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        
+        // The middle transfer claims acc1 as its sender but is signed by acc2
+        let mut forged = acc2.sign_tx(acc2.address, U256::from(20), 1);
+        forged.from = acc1.address;
+        let transactions = vec![
+            acc1.sign_tx(acc2.address, U256::from(10), 0),
+            forged,
+            acc1.sign_tx(acc2.address, U256::from(30), 1),
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let result = ParallelExecutor::new(config).execute_block(transactions);
+        
+        assert_eq!(result.tx_success, vec![true, false, true]);
+        assert_eq!(result.successful, 2);
+        assert_eq!(result.failed, 1);
+    }
+
+    #[test]
+    fn test_blocks_carry_state() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        
+        // acc2 can only pay in the second block with what it received in the first
+        let blocks = vec![
+            vec![acc1.sign_tx(acc2.address, U256::from(100), 0)],
+            vec![
+                acc2.sign_tx(acc1.address, U256::from(60), 0),
+                acc1.sign_tx(acc2.address, U256::from(10), 1),
+            ],
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: vec![(0, 1), (1, 2)],
+            checkpoints: Vec::new(),
+        };
+        
+        let results = ParallelExecutor::new(config).execute_blocks(blocks);
+        
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].tx_success, vec![true]);
+        assert_eq!(results[1].tx_success, vec![true, true]);
+        let mut second = results[1].final_states.clone();
+        second.sort_by_key(|(address, _)| *address);
+        let mut expected = vec![
+            (acc1.address, AccountState::new(2, U256::from(950))),
+            (acc2.address, AccountState::new(1, U256::from(50))),
+        ];
+        expected.sort_by_key(|(address, _)| *address);
+        assert_eq!(second, expected);
+    }
+
+    #[test]
+    fn test_atomic_blocks_discard_failed_blocks() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        
+        // The second block's nonce gap discards its valid transfer too, so the
+        // third block replays that transfer from the first block's state
+        let blocks = vec![
+            vec![acc1.sign_tx(acc2.address, U256::from(100), 0)],
+            vec![
+                acc2.sign_tx(acc1.address, U256::from(60), 0),
+                acc1.sign_tx(acc2.address, U256::from(10), 5),
+            ],
+            vec![acc2.sign_tx(acc1.address, U256::from(60), 0)],
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let results = ParallelExecutor::new(config).with_atomic_blocks().execute_blocks(blocks);
+        
+        assert!(!results[0].discarded);
+        assert!(results[1].discarded);
+        assert_eq!(results[1].tx_success, vec![true, false]);
+        assert!(results[1].final_states.is_empty());
+        assert!(!results[2].discarded);
+        let mut third = results[2].final_states.clone();
+        third.sort_by_key(|(address, _)| *address);
+        let mut expected = vec![
+            (acc1.address, AccountState::new(1, U256::from(960))),
+            (acc2.address, AccountState::new(1, U256::from(40))),
+        ];
+        expected.sort_by_key(|(address, _)| *address);
+        assert_eq!(third, expected);
+    }
+
+    #[test]
+    fn test_atomic_blocks_discard_rejected_blocks() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        
+        // The second block's own transfer succeeds, but the caller rejected
+        // another of its transactions
+        let blocks = vec![
+            vec![acc1.sign_tx(acc2.address, U256::from(100), 0)],
+            vec![acc1.sign_tx(acc2.address, U256::from(100), 1)],
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let results = ParallelExecutor::new(config)
+            .with_atomic_blocks()
+            .with_rejected(vec![0, 1])
+            .execute_blocks(blocks);
+        
+        assert!(!results[0].discarded);
+        assert!(results[1].discarded);
+        assert_eq!((results[1].successful, results[1].failed), (1, 0));
+        assert!(results[1].final_states.is_empty());
+    }
+
+    #[test]
+    fn test_declared_dependencies_avoid_aborts() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        
+        // A chain of transfers between the same accounts, each declaring the
+        // one before it, runs one at a time without a wasted execution
+        let transactions: Vec<Transaction> = (0..8u64)
+            .map(|nonce| Transaction {
+                dependencies: nonce.checked_sub(1).map(|earlier| earlier as TxnIndex).into_iter().collect(),
+                ..acc1.sign_tx(acc2.address, U256::from(10), nonce)
+            })
+            .collect();
+        
+        let config = ExecutorConfig {
+            num_threads: 4,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let result = ParallelExecutor::new(config).execute_block(transactions);
+        
+        assert_eq!(result.successful, 8);
+        assert_eq!(result.total_executions, 8);
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_nonceless_ids() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        
+        // Ids in any order; the second block repeats id 9 and a seen id
+        let blocks = vec![
+            vec![
+                acc1.sign_tx(acc2.address, U256::from(10), 9),
+                acc1.sign_tx(acc2.address, U256::from(10), 3),
+            ],
+            vec![
+                acc1.sign_tx(acc2.address, U256::from(20), 9),
+                acc1.sign_tx(acc2.address, U256::from(10), 7),
+                acc1.sign_tx(acc2.address, U256::from(10), 1),
+            ],
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let results = ParallelExecutor::new(config)
+            .with_nonceless(HashSet::from([1]))
+            .execute_blocks(blocks);
+        
+        assert_eq!(results[0].tx_success, vec![true, true]);
+        assert_eq!(results[1].tx_success, vec![false, true, false]);
+        let mut second = results[1].final_states.clone();
+        second.sort_by_key(|(address, _)| *address);
+        let mut expected = vec![
+            (acc1.address, AccountState::new(0, U256::from(970))),
+            (acc2.address, AccountState::new(0, U256::from(30))),
+        ];
+        expected.sort_by_key(|(address, _)| *address);
+        assert_eq!(second, expected);
+    }
+
+    #[test]
+    fn test_token_transfers() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        let tokens = vec![U256::from(100), U256::from(100)];
+
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)).with_tokens(tokens.clone()));
+        initial_states.insert(acc2.address, AccountState::new(0, U256::from(1000)));
+
+        // acc2 holds no tokens, so it can only pay token 1 with what acc1 sent it
+        let token_tx = |acc: &TestAccount, to: Address, value: u64, nonce: u64, token: TokenId| Transaction {
+            token: Some(token),
+            ..acc.sign_tx(to, U256::from(value), nonce)
+        };
+        let transactions = vec![
+            token_tx(&acc1, acc2.address, 40, 0, 1),
+            acc1.sign_tx(acc2.address, U256::from(10), 1),
+            token_tx(&acc2, acc1.address, 30, 0, 1),
+        ];
+
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: false,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+
+        let result = ParallelExecutor::new(config).execute_block(transactions);
+
+        assert_eq!(result.tx_success, vec![true, true, true]);
+        let mut states = result.final_states.clone();
+        states.sort_by_key(|(address, _)| *address);
+        let mut expected = vec![
+            (acc1.address, AccountState::new(2, U256::from(990)).with_tokens(vec![U256::from(100), U256::from(90)])),
+            (acc2.address, AccountState::new(1, U256::from(1010)).with_tokens(vec![U256::ZERO, U256::from(10)])),
+        ];
+        expected.sort_by_key(|(address, _)| *address);
+        assert_eq!(states, expected);
+    }
+
+    #[test]
+    fn test_shared_and_borrowed_blocks() {
+        let accounts: Vec<_> = (0..4).map(TestAccount::from_seed).collect();
+        let mut initial_states = HashMap::new();
+        for account in &accounts {
+            initial_states.insert(account.address, AccountState::new(0, U256::from(1000)));
+        }
+        let transactions: Vec<_> = (0..12)
+            .map(|i| accounts[i % 4].sign_tx(accounts[(i + 1) % 4].address, U256::from(1), (i / 4) as u64))
+            .collect();
+        let config = ExecutorConfig {
+            num_threads: 4,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        let executor = ParallelExecutor::new(config);
+        let sorted = |result: BlockExecutionResult| {
+            let mut states = result.final_states;
+            states.sort_by_key(|(address, _)| *address);
+            (result.tx_success, states)
+        };
+
+        // A borrowed slice, a shared slice reused across runs and an owned vector run the same block
+        let shared: std::sync::Arc<[Transaction]> = transactions.clone().into();
+        let expected = sorted(executor.execute_block(transactions.as_slice()));
+        assert_eq!(expected.0, vec![true; 12]);
+        assert_eq!(sorted(executor.execute_block(shared.clone())), expected);
+        assert_eq!(sorted(executor.execute_block(shared)), expected);
+        assert_eq!(sorted(executor.execute_block(transactions)), expected);
+    }
+
+    #[test]
+    fn test_schedule_replay() {
+        let accounts: Vec<_> = (0..4).map(TestAccount::from_seed).collect();
+        let mut initial_states = HashMap::new();
+        for account in &accounts {
+            initial_states.insert(account.address, AccountState::new(0, U256::from(1000)));
+        }
+        
+        // Everyone pays the first account, so most transactions conflict
+        let transactions: Vec<_> = (0..24)
+            .map(|i| accounts[i % 4].sign_tx(accounts[0].address, U256::from(1), (i / 4) as u64))
+            .collect();
+        let config = ExecutorConfig {
+            num_threads: 4,
+            verify_signatures: false,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        let perturbation = Perturbation {
+            seed: 7,
+            probability: 0.5,
+            max_delay: Duration::from_micros(200),
+        };
+        
+        let recorded = ParallelExecutor::new(config.clone())
+            .with_perturbation(perturbation)
+            .with_schedule_recording()
+            .execute_block(transactions.clone());
+        let schedule = recorded.schedule.clone().expect("recorded schedule");
+        let replayed = ParallelExecutor::new(config.clone())
+            .with_schedule_recording()
+            .with_schedule_replay(vec![schedule.clone()])
+            .execute_block(transactions);
+        
+        // Everything up to a divergence is dispatched in the recorded order
+        let followed = Schedule {
+            events: schedule.events[..replayed.replay_divergence.unwrap_or(schedule.events.len())].to_vec(),
+        };
+        let replayed_dispatches = replayed.schedule.expect("recorded replay").dispatches();
+        assert!(replayed_dispatches.starts_with(&followed.dispatches()));
+        if replayed.replay_divergence.is_none() {
+            assert_eq!(replayed_dispatches, schedule.dispatches());
+        }
+        let sorted = |mut states: Vec<(Address, AccountState)>| {
+            states.sort_by_key(|(address, _)| *address);
+            states
+        };
+        assert_eq!(sorted(replayed.final_states), sorted(recorded.final_states));
+        
+        // A re-execution that never gets aborted cannot be replayed
+        let account = TestAccount::from_seed(1);
+        let config = ExecutorConfig {
+            num_threads: 1,
+            initial_states: HashMap::from([(account.address, AccountState::new(0, U256::from(1000)))]),
+            ..config
+        };
+        let schedule = Schedule {
+            events: vec![
+                ScheduleEvent::Dispatch { txn_idx: 0, incarnation: 0, worker: 0 },
+                ScheduleEvent::Finish { txn_idx: 0, incarnation: 0 },
+                ScheduleEvent::Dispatch { txn_idx: 0, incarnation: 1, worker: 0 },
+            ],
+        };
+        let result = ParallelExecutor::new(config)
+            .with_schedule_replay(vec![schedule])
+            .execute_block(vec![account.sign_tx(accounts[0].address, U256::from(1), 0)]);
+        assert_eq!(result.replay_divergence, Some(2));
+        assert_eq!(result.tx_success, vec![true]);
+    }
+
+    #[test]
+    fn test_observer_sees_every_commit() {
+        #[derive(Default)]
+        struct Counts {
+            executions: AtomicUsize,
+            committed: AtomicUsize,
+            workers: Mutex<Vec<usize>>,
+        }
+
+        impl ExecutionObserver for Counts {
+            fn executed(&self, worker: usize, _busy: Duration) {
+                self.executions.fetch_add(1, Ordering::Relaxed);
+                self.workers.lock().push(worker);
+            }
+
+            fn committed(&self, count: usize) {
+                self.committed.fetch_add(count, Ordering::Relaxed);
+            }
+        }
+
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        let blocks: Vec<Vec<Transaction>> = vec![
+            (0..4).map(|nonce| acc1.sign_tx(acc2.address, U256::from(10), nonce)).collect(),
+            (4..6).map(|nonce| acc1.sign_tx(acc2.address, U256::from(10), nonce)).collect(),
+        ];
+
+        let config = ExecutorConfig {
+            num_threads: 3,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        let counts = std::sync::Arc::new(Counts::default());
+        let results = ParallelExecutor::new(config)
+            .with_observer(counts.clone())
+            .execute_blocks(blocks);
+
+        let total_executions: usize = results.iter().map(|result| result.total_executions).sum();
+        assert_eq!(counts.committed.load(Ordering::Relaxed), 6);
+        assert_eq!(counts.executions.load(Ordering::Relaxed), total_executions);
+        assert!(counts.workers.lock().iter().all(|&worker| worker < 3));
+    }
+
+    #[test]
+    fn test_cancelled_block_returns_committed_prefix() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        initial_states.insert(acc2.address, AccountState::new(0, U256::from(1000)));
+        
+        let transactions = vec![
+            acc1.sign_tx(acc2.address, U256::from(10), 0),
+            acc1.sign_tx(acc2.address, U256::from(10), 1),
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = ParallelExecutor::new(config).execute_block_with_cancel(transactions, &cancel);
+        
+        assert!(result.cancelled);
+        assert_eq!(result.successful, 0);
+        assert_eq!(result.failed, 0);
+        assert!(result.tx_success.is_empty());
+        assert!(result.final_states.is_empty());
+    }
+
+    #[test]
+    fn test_uncancelled_token_runs_block_to_completion() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        
+        let transactions = vec![
+            acc1.sign_tx(acc2.address, U256::from(10), 0),
+            acc1.sign_tx(acc2.address, U256::from(10), 1),
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let cancel = CancellationToken::new();
+        let result = ParallelExecutor::new(config).execute_block_with_cancel(transactions, &cancel);
+        
+        assert!(!result.cancelled);
+        assert_eq!(result.successful, 2);
+        assert_eq!(result.tx_success, vec![true, true]);
+    }
+
+    #[test]
+    fn test_panicking_transaction_is_poisoned() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        
+        // The transfer to the panicking receiver panics mid-execution
+        const PANICKING_RECEIVER: Address = Address::new([0xee; 20]);
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        initial_states.insert(acc2.address, AccountState::new(0, U256::from(1000)));
+        
+        let transactions = vec![
+            acc1.sign_tx(acc2.address, U256::from(10), 0),
+            acc2.sign_tx(PANICKING_RECEIVER, U256::from(5), 0),
+            acc1.sign_tx(acc2.address, U256::from(10), 1),
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let result = ParallelExecutor::new(config)
+            .with_write_hook(|tx| {
+                if tx.to == PANICKING_RECEIVER {
+                    panic!("transfer to the panicking receiver");
+                }
+            })
+            .execute_block(transactions);
+        
+        assert!(!result.cancelled);
+        assert_eq!(result.tx_success, vec![true, false, true]);
+        assert_eq!(result.poisoned.len(), 1);
+        assert_eq!(result.poisoned[0].txn_idx, 1);
+        assert!(result.poisoned[0].message.contains("panicking receiver"));
+        
+        // The poisoned transaction left no writes behind
+        let acc2_state = result
+            .final_states
+            .iter()
+            .find(|(address, _)| *address == acc2.address)
+            .map(|(_, state)| state.clone());
+        assert_eq!(acc2_state, Some(AccountState::new(0, U256::from(1020))));
+    }
+
+    #[test]
+    fn test_overflowing_credit_fails_permanently() {
+        let acc1 = TestAccount::from_seed(1);
+        let acc2 = TestAccount::from_seed(2);
+        let acc3 = TestAccount::from_seed(3);
+        
+        // Crediting acc3 would overflow its balance
+        let mut initial_states = HashMap::new();
+        initial_states.insert(acc1.address, AccountState::new(0, U256::from(1000)));
+        initial_states.insert(acc2.address, AccountState::new(0, U256::from(1000)));
+        initial_states.insert(acc3.address, AccountState::new(0, U256::MAX - U256::from(3)));
+        
+        let transactions = vec![
+            acc1.sign_tx(acc2.address, U256::from(10), 0),
+            acc2.sign_tx(acc3.address, U256::from(5), 0),
+            acc1.sign_tx(acc2.address, U256::from(10), 1),
+        ];
+        
+        let config = ExecutorConfig {
+            num_threads: 2,
+            verify_signatures: true,
+            initial_states,
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        
+        let result = ParallelExecutor::new(config).execute_block(transactions);
+        
+        assert!(!result.cancelled);
+        assert!(result.poisoned.is_empty());
+        assert_eq!(result.tx_success, vec![true, false, true]);
+        
+        // The failed transaction left no writes behind
+        let state = |account: &TestAccount| {
+            result
+                .final_states
+                .iter()
+                .find(|(address, _)| *address == account.address)
+                .map(|(_, state)| state.clone())
+        };
+        assert_eq!(state(&acc2), Some(AccountState::new(0, U256::from(1020))));
+        assert_eq!(state(&acc3), None);
+    }
+
+    #[test]
+    fn test_checked_account_arithmetic() {
+        let state = AccountState::new(u64::MAX, U256::from(10)).with_tokens(vec![U256::MAX]);
+        
+        let debited = state.clone().checked_debit(None, U256::from(10), false);
+        assert_eq!(debited.map(|state| (state.nonce, state.balance)), Some((u64::MAX, U256::ZERO)));
+        assert_eq!(state.clone().checked_debit(None, U256::from(1), true), None);
+        assert_eq!(state.clone().checked_debit(None, U256::from(11), false), None);
+        
+        assert_eq!(state.clone().checked_credit(Some(0), U256::from(1)), None);
+        let credited = state.clone().checked_credit(Some(1), U256::from(1));
+        assert_eq!(credited.map(|state| state.tokens), Some(vec![U256::MAX, U256::from(1)]));
+        let credited = state.checked_credit(None, U256::MAX - U256::from(10));
+        assert_eq!(credited.map(|state| state.balance), Some(U256::MAX));
+    }
+}
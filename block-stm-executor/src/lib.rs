@@ -10,13 +10,20 @@
 //! - **MVHashMap**: Multi-version data structure storing versioned account states
 //! - **Scheduler**: Coordinates parallel execution and handles push-based invalidation
 //! - **ParallelExecutor**: Orchestrates worker threads and transaction execution
+//! - **schedule**: Records a run's task dispatch order and replays it to force
+//!   the same interleaving
+//! - **simulation**: Replays the protocol in virtual time on one thread, to
+//!   compare scheduling policies without multi-core noise
+//! - **stress**: Re-executes a block under randomized thread interleaving to
+//!   check that every run commits the same state
 //!
 //! # Algorithm Overview
 //!
 //! 1. Transactions are executed speculatively in parallel
 //! 2. Each write records which transactions have read from the previous version
 //! 3. When a transaction writes, readers with higher indices are immediately aborted
-//! 4. Aborted transactions are re-executed with incremented incarnation numbers
+//! 4. Aborted transactions have their writes marked as estimates, which readers
+//!    wait on, and are re-executed with incremented incarnation numbers
 //! 5. Transactions commit in order once all lower-indexed transactions are done
 //!
 //! # Example
@@ -30,6 +37,10 @@
 //!     num_threads: 4,
 //!     verify_signatures: true,
 //!     initial_states: HashMap::new(),
+//!     lookahead: None,
+//!     memory_budget: None,
+//!     dependency_hints: Vec::new(),
+//!     checkpoints: Vec::new(),
 //! };
 //!
 //! let executor = ParallelExecutor::new(config);
@@ -41,8 +52,18 @@
 
 pub mod executor;
 pub mod mvhashmap;
+pub mod schedule;
 pub mod scheduler;
+pub mod simulation;
+pub mod stress;
+mod sync;
 pub mod types;
 
-pub use executor::{BlockExecutionResult, ExecutorConfig, ParallelExecutor, Transaction};
-pub use types::{AccountState, Incarnation, TxnIndex, Version};
+pub use executor::{
+    BlockExecutionResult, CancellationToken, ExecutionObserver, ExecutorConfig, ParallelExecutor, Perturbation,
+    PoisonedTransaction, Transaction,
+};
+pub use schedule::{Schedule, ScheduleEvent};
+pub use simulation::{simulate, SchedulingPolicy, SimulationConfig, SimulationReport};
+pub use stress::{stress, StressConfig, StressError, StressReport};
+pub use types::{AccountState, Incarnation, TokenId, TxnIndex, TxnIndices, Version, MAX_BLOCK_SIZE};
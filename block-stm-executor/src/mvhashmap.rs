@@ -5,11 +5,18 @@
 //! history of account states, allowing transactions to read from the correct
 //! version based on transaction ordering.
 
-use crate::types::{AccountState, Incarnation, TxnIndex, Version};
-use alloy_primitives::Address;
-use dashmap::DashMap;
+use crate::sync::{AtomicUsize, ConcurrentMap, Ordering};
+use crate::types::{AccountState, Incarnation, TxnIndex, TxnIndices, Version};
+use alloy_primitives::{Address, U256};
 use std::collections::BTreeMap;
-use std::sync::Arc;
+use std::mem::size_of;
+
+/// Estimated bytes held by one version: its map key and entry.
+const VERSION_BYTES: usize = size_of::<TxnIndex>() + size_of::<VersionedEntry>();
+/// Estimated bytes held by one recorded reader.
+const READER_BYTES: usize = size_of::<TxnIndex>();
+/// Estimated bytes held by one token balance of a version's state.
+const TOKEN_BYTES: usize = size_of::<U256>();
 
 /// Entry in the version history for an address.
 #[derive(Debug, Clone)]
@@ -17,7 +24,13 @@ pub struct VersionedEntry {
     pub version: Version,
     pub state: AccountState,
     /// Transactions that have read from this version (for push-based invalidation).
-    pub readers: Vec<TxnIndex>,
+    pub readers: TxnIndices,
+    /// Whether the writing incarnation was aborted: the value is stale and the
+    /// transaction is expected to write here again when it re-executes.
+    pub estimate: bool,
+    /// Number of leading `readers` that read the value before it became an
+    /// estimate; the rest read a dependency instead.
+    pub value_readers: usize,
 }
 
 /// Multi-version hashmap storing versioned account states.
@@ -28,11 +41,28 @@ pub struct VersionedEntry {
 /// the latest version written by a transaction with index < current_txn_idx.
 pub struct MVHashMap {
     /// Map from address to version history.
-    data: DashMap<Address, BTreeMap<TxnIndex, VersionedEntry>>,
+    data: ConcurrentMap<Address, BTreeMap<TxnIndex, VersionedEntry>>,
     /// Transactions that have read from storage (initial state) for each address.
     /// When a transaction writes to an address, all storage readers with higher
     /// indices must be invalidated.
-    storage_readers: DashMap<Address, Vec<TxnIndex>>,
+    ///
+    /// Always locked after, never before, the same address's `data` entry.
+    storage_readers: ConcurrentMap<Address, Vec<TxnIndex>>,
+    /// Addresses each transaction has a version at, so aborting, clearing
+    /// or pruning it only visits its own writes rather than every address.
+    ///
+    /// Only changed while the address's `data` entry is locked, and always
+    /// locked after it.
+    write_sets: ConcurrentMap<TxnIndex, Vec<Address>>,
+    /// Transactions below this index have had their versions pruned.
+    pruned: AtomicUsize,
+    /// Estimated heap bytes held by versions and reader lists.
+    memory_usage: AtomicUsize,
+    /// Highest value `memory_usage` has reached.
+    peak_memory_usage: AtomicUsize,
+    /// Readers kept rather than invalidated because a re-execution rewrote
+    /// the value they read unchanged.
+    skipped_invalidations: AtomicUsize,
 }
 
 /// Result of reading from the MVHashMap.
@@ -50,15 +80,20 @@ pub enum ReadResult {
 #[derive(Debug, Clone)]
 pub struct WriteResult {
     /// Transactions that had read the previous version and need to be invalidated.
-    pub invalidated_readers: Vec<TxnIndex>,
+    pub invalidated_readers: TxnIndices,
 }
 
 impl MVHashMap {
     /// Creates a new empty multi-version hashmap.
     pub fn new() -> Self {
         Self {
-            data: DashMap::new(),
-            storage_readers: DashMap::new(),
+            data: ConcurrentMap::new(),
+            storage_readers: ConcurrentMap::new(),
+            write_sets: ConcurrentMap::new(),
+            pruned: AtomicUsize::new(0),
+            memory_usage: AtomicUsize::new(0),
+            peak_memory_usage: AtomicUsize::new(0),
+            skipped_invalidations: AtomicUsize::new(0),
         }
     }
 
@@ -67,29 +102,56 @@ impl MVHashMap {
     /// Returns:
     /// - `ReadResult::Versioned` if a version exists from a lower transaction
     /// - `ReadResult::Storage` if no version exists (read from base storage)
-    /// - `ReadResult::Dependency` if the latest write is from a higher or equal transaction
+    /// - `ReadResult::Dependency` if the latest version is an estimate left by
+    ///   an aborted transaction that has not re-executed yet
     pub fn read(&self, address: Address, reader_txn_idx: TxnIndex) -> ReadResult {
-        let entry = self.data.get(&address);
-        
-        if let Some(versions) = entry {
-            // Find the latest version written by a transaction with txn_idx < reader_txn_idx
-            if let Some((_write_txn_idx, entry)) = versions
-                .range(..reader_txn_idx)
-                .next_back()
-            {
-                return ReadResult::Versioned(entry.version, entry.state);
-            }
-            
-            // Check if there's a write from a higher transaction (shouldn't happen in correct execution)
-            if let Some((_write_txn_idx, _)) = versions.range(reader_txn_idx..).next() {
-                // This is a dependency on a later transaction - should not happen
-                // as we execute in order, but return Storage to be safe
-                return ReadResult::Storage;
+        // Find the latest version written by a transaction with txn_idx < reader_txn_idx;
+        // writes from higher transactions are invisible, so fall back to storage
+        self.data
+            .read(&address, |versions| {
+                versions
+                    .range(..reader_txn_idx)
+                    .next_back()
+                    .map(|(_, entry)| entry.read_result())
+            })
+            .flatten()
+            .unwrap_or(ReadResult::Storage)
+    }
+
+    /// Reads like [`read`](Self::read) and records the read for invalidation
+    /// in the same step.
+    ///
+    /// A write landing between a separate `read` and `record_read` would not
+    /// see the reader and so never invalidate it; here the address stays
+    /// locked until the read is recorded. Reads of an estimate are recorded
+    /// too, so the re-execution that replaces it invalidates the reader.
+    pub fn read_recorded(&self, address: Address, reader_txn_idx: TxnIndex) -> ReadResult {
+        self.data.upsert(address, |versions| {
+            match versions.range_mut(..reader_txn_idx).next_back() {
+                Some((_, entry)) => {
+                    if entry.record_reader(reader_txn_idx) {
+                        self.reserve(READER_BYTES);
+                    }
+                    entry.read_result()
+                }
+                None => {
+                    self.record_storage_read(address, reader_txn_idx);
+                    ReadResult::Storage
+                }
             }
+        })
+    }
+
+    /// Reads like [`read_recorded`](Self::read_recorded) and checks that the
+    /// read resolves to `expected`, a version or `None` for storage.
+    ///
+    /// Used to check a previous incarnation's read before reusing its writes.
+    pub fn validate_read(&self, address: Address, reader_txn_idx: TxnIndex, expected: Option<Version>) -> bool {
+        match self.read_recorded(address, reader_txn_idx) {
+            ReadResult::Versioned(version, _) => expected == Some(version),
+            ReadResult::Storage => expected.is_none(),
+            ReadResult::Dependency(_) => false,
         }
-        
-        // No version found, read from storage
-        ReadResult::Storage
     }
 
     /// Writes a new version of an account state.
@@ -97,6 +159,12 @@ impl MVHashMap {
     /// This invalidates:
     /// 1. Transactions that read from the previous version (from a lower-indexed tx)
     /// 2. Transactions that read from storage (if this is the first write to this address)
+    /// 3. Transactions that read the version or estimate this one replaces
+    ///
+    /// A re-execution that writes the same state as the incarnation it
+    /// replaces short-circuits: readers of the old value read what they would
+    /// read now and are kept, and only readers of its estimate are invalidated.
+    /// Readers of the version below were invalidated by the first write.
     ///
     /// Returns the list of transaction indices that need to be invalidated.
     pub fn write(
@@ -106,66 +174,66 @@ impl MVHashMap {
         incarnation: Incarnation,
         state: AccountState,
     ) -> WriteResult {
-        let mut invalidated = Vec::new();
-        
-        // Check if there are any lower-indexed versions
-        let has_lower_version = self.data.get(&address).map_or(false, |versions| {
-            versions.range(..writer_txn_idx).next_back().is_some()
-        });
-        
-        // If no lower version exists, invalidate storage readers
-        // (transactions that read from initial state for this address)
-        if !has_lower_version {
-            if let Some(storage_readers) = self.storage_readers.get(&address) {
-                invalidated.extend(
-                    storage_readers
+        // The address stays locked throughout, so no reader can record a
+        // read of the previous version after its readers are collected
+        let invalidated = self.data.upsert(address, |versions| {
+            if let Some(previous) = versions.get_mut(&writer_txn_idx) {
+                if previous.version.incarnation < incarnation && previous.state == state {
+                    let value_readers = if previous.estimate { previous.value_readers } else { previous.readers.len() };
+                    let invalidated: TxnIndices = previous.readers.drain(value_readers..).collect();
+                    self.release(invalidated.len() * READER_BYTES);
+                    self.skipped_invalidations.fetch_add(previous.readers.len(), Ordering::Relaxed);
+                    previous.version = Version::new(writer_txn_idx, incarnation);
+                    previous.estimate = false;
+                    previous.value_readers = previous.readers.len();
+                    return invalidated;
+                }
+            }
+            
+            let mut invalidated = TxnIndices::new();
+            match versions.range(..writer_txn_idx).next_back() {
+                // Any reader of the previous version with txn_idx > writer_txn_idx
+                // needs to be invalidated
+                Some((_prev_txn_idx, prev_entry)) => invalidated.extend(
+                    prev_entry
+                        .readers
                         .iter()
                         .filter(|&&reader_idx| reader_idx > writer_txn_idx)
                         .copied(),
-                );
+                ),
+                // No lower version: invalidate storage readers
+                // (transactions that read from initial state for this address)
+                None => {
+                    self.storage_readers.read(&address, |storage_readers| {
+                        invalidated.extend(
+                            storage_readers
+                                .iter()
+                                .filter(|&&reader_idx| reader_idx > writer_txn_idx)
+                                .copied(),
+                        )
+                    });
+                }
             }
-        }
-        
-        self.data
-            .entry(address)
-            .and_modify(|versions| {
-                // Collect readers from the previous version that need to be invalidated
-                if let Some((_prev_txn_idx, prev_entry)) = versions
-                    .range(..writer_txn_idx)
-                    .next_back()
-                {
-                    // Any reader with txn_idx > writer_txn_idx needs to be invalidated
-                    invalidated.extend(
-                        prev_entry
-                            .readers
-                            .iter()
-                            .filter(|&&reader_idx| reader_idx > writer_txn_idx)
-                            .copied(),
-                    );
+            
+            // Insert or update the version for this transaction
+            let entry = VersionedEntry {
+                version: Version::new(writer_txn_idx, incarnation),
+                state,
+                readers: TxnIndices::new(),
+                estimate: false,
+                value_readers: 0,
+            };
+            let entry_bytes = entry.memory_usage();
+            match versions.insert(writer_txn_idx, entry) {
+                Some(previous) => {
+                    self.release(previous.memory_usage());
+                    invalidated.extend(previous.readers);
                 }
-                
-                // Insert or update the version for this transaction
-                versions.insert(
-                    writer_txn_idx,
-                    VersionedEntry {
-                        version: Version::new(writer_txn_idx, incarnation),
-                        state,
-                        readers: Vec::new(),
-                    },
-                );
-            })
-            .or_insert_with(|| {
-                let mut versions = BTreeMap::new();
-                versions.insert(
-                    writer_txn_idx,
-                    VersionedEntry {
-                        version: Version::new(writer_txn_idx, incarnation),
-                        state,
-                        readers: Vec::new(),
-                    },
-                );
-                versions
-            });
+                None => self.write_sets.upsert(writer_txn_idx, |addresses| addresses.push(address)),
+            }
+            self.reserve(entry_bytes);
+            invalidated
+        });
         
         WriteResult {
             invalidated_readers: invalidated,
@@ -177,13 +245,13 @@ impl MVHashMap {
     /// This is used for push-based invalidation: when a transaction writes,
     /// we can immediately identify which readers need to be invalidated.
     pub fn record_read(&self, address: Address, reader_txn_idx: TxnIndex, version: Version) {
-        if let Some(mut versions) = self.data.get_mut(&address) {
+        self.data.upsert(address, |versions| {
             if let Some(entry) = versions.get_mut(&version.txn_idx) {
-                if entry.version == version && !entry.readers.contains(&reader_txn_idx) {
-                    entry.readers.push(reader_txn_idx);
+                if entry.version == version && entry.record_reader(reader_txn_idx) {
+                    self.reserve(READER_BYTES);
                 }
             }
-        }
+        });
     }
 
     /// Records that a transaction has read from storage (initial state).
@@ -193,31 +261,193 @@ impl MVHashMap {
     /// read from storage for that address (with higher indices) must be invalidated.
     pub fn record_storage_read(&self, address: Address, reader_txn_idx: TxnIndex) {
         self.storage_readers
-            .entry(address)
-            .or_default()
-            .push(reader_txn_idx);
+            .upsert(address, |readers| readers.push(reader_txn_idx));
+        self.reserve(READER_BYTES);
+    }
+
+    /// Marks every version written by `txn_idx` as an estimate, the first
+    /// phase of aborting it.
+    ///
+    /// Readers then wait for the re-execution instead of reading values it
+    /// may not write again; see [`clear_stale`](Self::clear_stale) for what
+    /// it does not rewrite.
+    pub fn mark_estimate(&self, txn_idx: TxnIndex) {
+        for address in self.write_set(txn_idx) {
+            self.data.upsert(address, |versions| {
+                if let Some(entry) = versions.get_mut(&txn_idx) {
+                    if !entry.estimate {
+                        entry.estimate = true;
+                        entry.value_readers = entry.readers.len();
+                    }
+                }
+            });
+        }
+    }
+
+    /// Removes the versions an earlier incarnation of `txn_idx` wrote that
+    /// `incarnation` did not overwrite.
+    ///
+    /// Returns their readers, which read values that no longer exist.
+    pub fn clear_stale(&self, txn_idx: TxnIndex, incarnation: Incarnation) -> TxnIndices {
+        self.remove_versions(txn_idx, |entry| entry.version.incarnation < incarnation)
     }
 
     /// Clears all versions for a transaction (used when aborting/re-executing).
-    pub fn clear_transaction(&self, txn_idx: TxnIndex) {
-        for mut entry in self.data.iter_mut() {
-            entry.value_mut().remove(&txn_idx);
+    ///
+    /// Returns their readers, which read values that no longer exist.
+    pub fn clear_transaction(&self, txn_idx: TxnIndex) -> TxnIndices {
+        self.remove_versions(txn_idx, |_| true)
+    }
+
+    /// Removes the versions of `txn_idx` that `stale` holds for, returning
+    /// their readers.
+    fn remove_versions(&self, txn_idx: TxnIndex, stale: impl Fn(&VersionedEntry) -> bool) -> TxnIndices {
+        let mut invalidated = TxnIndices::new();
+        for address in self.write_set(txn_idx) {
+            self.data.upsert(address, |versions| {
+                if !versions.get(&txn_idx).is_some_and(&stale) {
+                    return;
+                }
+                if let Some(removed) = versions.remove(&txn_idx) {
+                    self.release(removed.memory_usage());
+                    invalidated.extend(removed.readers);
+                }
+                self.write_sets.upsert(txn_idx, |addresses| addresses.retain(|&written| written != address));
+            });
         }
+        invalidated.sort_unstable();
+        invalidated.dedup();
+        invalidated
     }
 
-    /// Gets the committed state for final output (after all transactions are done).
-    pub fn get_committed_states(&self) -> Vec<(Address, AccountState)> {
-        let mut result = Vec::new();
+    /// Addresses `txn_idx` currently has a version at.
+    fn write_set(&self, txn_idx: TxnIndex) -> Vec<Address> {
+        self.write_sets.read(&txn_idx, Vec::clone).unwrap_or_default()
+    }
+
+    /// Drops versions that no transaction at or after `committed` can read:
+    /// for each address, everything older than its latest version below
+    /// `committed`.
+    ///
+    /// Only addresses written by transactions committed since the last
+    /// prune can have gained such versions, so only those are visited.
+    /// Returns the estimated number of bytes freed.
+    pub fn prune_committed(&self, committed: TxnIndex) -> usize {
+        let start = self.pruned.fetch_max(committed as usize, Ordering::AcqRel) as TxnIndex;
+        let mut addresses: Vec<Address> = (start..committed).flat_map(|txn_idx| self.write_set(txn_idx)).collect();
+        addresses.sort_unstable();
+        addresses.dedup();
         
-        for entry in self.data.iter() {
-            let address = *entry.key();
-            if let Some((_, versioned)) = entry.value().iter().next_back() {
-                result.push((address, versioned.state));
-            }
+        let mut freed = 0;
+        for address in addresses {
+            self.data.upsert(address, |versions| {
+                let Some((&latest_committed, _)) = versions.range(..committed).next_back() else {
+                    return;
+                };
+                let kept = versions.split_off(&latest_committed);
+                freed += versions.values().map(VersionedEntry::memory_usage).sum::<usize>();
+                *versions = kept;
+            });
         }
-        
+        self.release(freed);
+        freed
+    }
+
+    /// Estimated bytes currently held by versions and reader lists.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage.load(Ordering::Relaxed)
+    }
+
+    /// Highest estimated memory usage so far.
+    pub fn peak_memory_usage(&self) -> usize {
+        self.peak_memory_usage.load(Ordering::Relaxed)
+    }
+
+    /// Number of readers kept rather than invalidated because a re-execution
+    /// rewrote the value they read unchanged.
+    pub fn skipped_invalidations(&self) -> usize {
+        self.skipped_invalidations.load(Ordering::Relaxed)
+    }
+
+    fn reserve(&self, bytes: usize) {
+        let usage = self.memory_usage.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.peak_memory_usage.fetch_max(usage, Ordering::Relaxed);
+    }
+
+    fn release(&self, bytes: usize) {
+        self.memory_usage.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    /// Gets each address's final state from the versions `committed` lists,
+    /// the final incarnation of every committed transaction by index.
+    ///
+    /// Versions of uncommitted transactions and of superseded incarnations
+    /// are skipped, so an aborted execution's writes never surface; an
+    /// address only such versions wrote keeps its storage state and is left
+    /// out. A cancelled run passes its committed prefix.
+    pub fn get_committed_states(&self, committed: &[Version]) -> Vec<(Address, AccountState)> {
+        let mut result = Vec::new();
+        self.data.for_each(|&address, versions| {
+            let latest = versions
+                .range(..committed.len() as TxnIndex)
+                .rev()
+                .find(|(&txn_idx, entry)| !entry.estimate && entry.version == committed[txn_idx as usize]);
+            if let Some((_, versioned)) = latest {
+                result.push((address, versioned.state.clone()));
+            }
+        });
         result
     }
+
+    /// Gets the state as of `end` of every address written by a transaction
+    /// in `start..end`.
+    pub fn get_written_states(&self, start: TxnIndex, end: TxnIndex) -> Vec<(Address, AccountState)> {
+        let mut result = Vec::new();
+        self.data.for_each(|&address, versions| {
+            if let Some((&txn_idx, versioned)) = versions.range(..end).next_back() {
+                if txn_idx >= start {
+                    result.push((address, versioned.state.clone()));
+                }
+            }
+        });
+        result
+    }
+}
+
+impl VersionedEntry {
+    /// What a later transaction reading this entry sees.
+    fn read_result(&self) -> ReadResult {
+        if self.estimate {
+            ReadResult::Dependency(self.version.txn_idx)
+        } else {
+            ReadResult::Versioned(self.version, self.state.clone())
+        }
+    }
+
+    /// Records `reader_txn_idx` as a reader, returning whether it was added.
+    ///
+    /// A reader of an estimate is moved past the readers of the value, even
+    /// if an earlier incarnation of it read the value.
+    fn record_reader(&mut self, reader_txn_idx: TxnIndex) -> bool {
+        match self.readers.iter().position(|&reader| reader == reader_txn_idx) {
+            Some(position) if self.estimate && position < self.value_readers => {
+                self.readers.remove(position);
+                self.readers.push(reader_txn_idx);
+                self.value_readers -= 1;
+                false
+            }
+            Some(_) => false,
+            None => {
+                self.readers.push(reader_txn_idx);
+                true
+            }
+        }
+    }
+
+    /// Estimated bytes held by this version, its token balances and its readers.
+    fn memory_usage(&self) -> usize {
+        VERSION_BYTES + self.state.tokens.len() * TOKEN_BYTES + self.readers.len() * READER_BYTES
+    }
 }
 
 impl Default for MVHashMap {
@@ -226,10 +456,9 @@ impl Default for MVHashMap {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(loom)))]
 mod tests {
     use super::*;
-    use alloy_primitives::U256;
 
     #[test]
     fn test_read_write() {
@@ -242,7 +471,7 @@ mod tests {
         
         // Write from transaction 0
         let state0 = AccountState::new(1, U256::from(100));
-        mv.write(addr, 0, 0, state0);
+        mv.write(addr, 0, 0, state0.clone());
         
         // Transaction 1 should see transaction 0's write
         let result = mv.read(addr, 1);
@@ -276,5 +505,179 @@ mod tests {
         assert_eq!(write_result.invalidated_readers.len(), 1);
         assert_eq!(write_result.invalidated_readers[0], 2);
     }
+
+    #[test]
+    fn test_prune_committed_versions() {
+        let mv = MVHashMap::new();
+        let addr = Address::random();
+        
+        for txn_idx in 0..4 {
+            mv.write(addr, txn_idx, 0, AccountState::new(txn_idx as u64 + 1, U256::from(100)));
+        }
+        assert_eq!(mv.memory_usage(), 4 * VERSION_BYTES);
+        
+        // With 0..3 committed, only version 2 and the speculative version 3 remain readable
+        assert_eq!(mv.prune_committed(3), 2 * VERSION_BYTES);
+        assert_eq!(mv.memory_usage(), 2 * VERSION_BYTES);
+        assert_eq!(mv.peak_memory_usage(), 4 * VERSION_BYTES);
+        assert!(matches!(mv.read(addr, 3), ReadResult::Versioned(version, _) if version.txn_idx == 2));
+        
+        mv.clear_transaction(3);
+        assert_eq!(mv.memory_usage(), VERSION_BYTES);
+        
+        // Token balances widen the version they are written in
+        let tokens = vec![U256::from(5); 3];
+        mv.write(addr, 4, 0, AccountState::new(4, U256::from(100)).with_tokens(tokens));
+        assert_eq!(mv.memory_usage(), 2 * VERSION_BYTES + 3 * TOKEN_BYTES);
+    }
+
+    #[test]
+    fn test_read_after_abort_sees_estimate() {
+        let mv = MVHashMap::new();
+        let (rewritten, dropped) = (Address::random(), Address::random());
+        mv.write(rewritten, 1, 0, AccountState::new(1, U256::from(100)));
+        mv.write(dropped, 1, 0, AccountState::new(1, U256::from(100)));
+        
+        // Once transaction 1 aborts, readers wait on it instead of reading its stale writes
+        mv.mark_estimate(1);
+        assert!(matches!(mv.read_recorded(rewritten, 2), ReadResult::Dependency(1)));
+        assert!(matches!(mv.read_recorded(dropped, 3), ReadResult::Dependency(1)));
+        
+        // Its re-execution rewrites one address, invalidating the waiting reader
+        let write_result = mv.write(rewritten, 1, 1, AccountState::new(1, U256::from(90)));
+        assert_eq!(write_result.invalidated_readers.as_slice(), [2]);
+        assert!(matches!(mv.read(rewritten, 2), ReadResult::Versioned(version, _) if version == Version::new(1, 1)));
+        
+        // and drops the other, whose estimate and reader are cleared
+        assert_eq!(mv.clear_stale(1, 1).as_slice(), [3]);
+        assert!(matches!(mv.read(dropped, 3), ReadResult::Storage));
+        assert_eq!(mv.memory_usage(), VERSION_BYTES);
+    }
+
+    #[test]
+    fn test_committed_states_skip_aborted_versions() {
+        let mv = MVHashMap::new();
+        let (rewritten, stale, speculative) = (Address::random(), Address::random(), Address::random());
+        mv.write(rewritten, 0, 0, AccountState::new(1, U256::from(100)));
+        mv.write(rewritten, 1, 0, AccountState::new(2, U256::from(50)));
+        
+        // Transaction 1 committed at incarnation 1; its incarnation 0 write
+        // to `stale` was never rewritten, and transaction 2 is uncommitted
+        mv.write(stale, 1, 0, AccountState::new(1, U256::from(10)));
+        mv.mark_estimate(1);
+        mv.write(rewritten, 1, 1, AccountState::new(2, U256::from(60)));
+        mv.write(speculative, 2, 0, AccountState::new(1, U256::from(5)));
+        
+        let mut states = mv.get_committed_states(&[Version::new(0, 0), Version::new(1, 1)]);
+        states.sort_by_key(|(address, _)| *address);
+        assert_eq!(states, vec![(rewritten, AccountState::new(2, U256::from(60)))]);
+        
+        // A committed prefix that ends before the rewrite falls back to transaction 0
+        let states = mv.get_committed_states(&[Version::new(0, 0)]);
+        assert_eq!(states, vec![(rewritten, AccountState::new(1, U256::from(100)))]);
+    }
+
+    #[test]
+    fn test_validate_read_after_stale_invalidation() {
+        let mv = MVHashMap::new();
+        let addr = Address::random();
+        mv.write(addr, 0, 0, AccountState::new(1, U256::from(100)));
+        assert!(matches!(mv.read_recorded(addr, 3), ReadResult::Versioned(..)));
+        
+        // Transaction 3 re-executes after transaction 2's write and reads it
+        assert_eq!(mv.write(addr, 2, 0, AccountState::new(2, U256::from(90))).invalidated_readers.as_slice(), [3]);
+        assert!(mv.validate_read(addr, 3, Some(Version::new(2, 0))));
+        
+        // Transaction 1 invalidates its stale read of version 0, but the read
+        // it made since still resolves to the same version
+        assert_eq!(mv.write(addr, 1, 0, AccountState::new(2, U256::from(95))).invalidated_readers.as_slice(), [3]);
+        assert!(mv.validate_read(addr, 3, Some(Version::new(2, 0))));
+        assert!(!mv.validate_read(addr, 3, None));
+        
+        mv.mark_estimate(2);
+        assert!(!mv.validate_read(addr, 3, Some(Version::new(2, 0))));
+    }
+
+    #[test]
+    fn test_unchanged_rewrite_keeps_value_readers() {
+        let mv = MVHashMap::new();
+        let addr = Address::random();
+        let state = AccountState::new(1, U256::from(100));
+        mv.write(addr, 1, 0, state.clone());
+        assert!(matches!(mv.read_recorded(addr, 2), ReadResult::Versioned(..)));
+        assert!(matches!(mv.read_recorded(addr, 3), ReadResult::Versioned(..)));
+        
+        // After the abort one reader re-executes and hits the estimate, another is new
+        mv.mark_estimate(1);
+        assert!(matches!(mv.read_recorded(addr, 3), ReadResult::Dependency(1)));
+        assert!(matches!(mv.read_recorded(addr, 4), ReadResult::Dependency(1)));
+        
+        // Rewriting the same state only invalidates the readers of the estimate
+        assert_eq!(mv.write(addr, 1, 1, state.clone()).invalidated_readers.as_slice(), [3, 4]);
+        assert_eq!(mv.skipped_invalidations(), 1);
+        assert!(matches!(mv.read(addr, 2), ReadResult::Versioned(version, _) if version == Version::new(1, 1)));
+        assert_eq!(mv.memory_usage(), VERSION_BYTES + READER_BYTES);
+        
+        // A changed state invalidates every reader
+        mv.mark_estimate(1);
+        let write_result = mv.write(addr, 1, 2, AccountState::new(1, U256::from(90)));
+        assert_eq!(write_result.invalidated_readers.as_slice(), [2]);
+    }
+
+    #[test]
+    fn test_write_sets_follow_cleared_versions() {
+        let mv = MVHashMap::new();
+        let (kept, dropped) = (Address::random(), Address::random());
+        mv.write(kept, 1, 0, AccountState::new(1, U256::from(100)));
+        mv.write(dropped, 1, 0, AccountState::new(1, U256::from(100)));
+        assert_eq!(mv.write_set(1).len(), 2);
+        
+        // The re-execution only rewrites `kept`, so `dropped` leaves the write set
+        mv.mark_estimate(1);
+        mv.write(kept, 1, 1, AccountState::new(1, U256::from(90)));
+        mv.clear_stale(1, 1);
+        assert_eq!(mv.write_set(1), vec![kept]);
+        
+        // Clearing the transaction empties it, and a later write records the address again
+        mv.clear_transaction(1);
+        assert!(mv.write_set(1).is_empty());
+        assert_eq!(mv.memory_usage(), 0);
+        mv.write(dropped, 1, 2, AccountState::new(1, U256::from(80)));
+        mv.mark_estimate(1);
+        assert!(matches!(mv.read(dropped, 2), ReadResult::Dependency(1)));
+    }
 }
 
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::*;
+    use crate::sync::Arc;
+    use loom::thread;
+
+    #[test]
+    fn test_racing_reader_is_seen_or_invalidated() {
+        loom::model(|| {
+            let mv = Arc::new(MVHashMap::new());
+            let addr = Address::ZERO;
+            mv.write(addr, 0, 0, AccountState::new(1, U256::from(100)));
+            
+            // Transaction 2 reads while transaction 1 writes below it
+            let reader = {
+                let mv = mv.clone();
+                thread::spawn(move || mv.read_recorded(addr, 2))
+            };
+            let invalidated = mv.write(addr, 1, 0, AccountState::new(2, U256::from(50))).invalidated_readers;
+            
+            // A read of the older version must be invalidated; a read of the new one must not
+            match reader.join().unwrap() {
+                ReadResult::Versioned(version, _) if version.txn_idx == 0 => assert_eq!(invalidated.as_slice(), [2]),
+                ReadResult::Versioned(version, _) => {
+                    assert_eq!(version.txn_idx, 1);
+                    assert!(invalidated.is_empty());
+                }
+                other => panic!("Expected Versioned result, got {:?}", other),
+            }
+        });
+    }
+}
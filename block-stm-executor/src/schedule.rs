@@ -0,0 +1,57 @@
+//! Recorded task dispatch orders, for replaying a run's interleaving.
+//!
+//! A recording run logs every task the scheduler hands out, and every
+//! execution that finishes, in the order they happen. Replaying the
+//! [`Schedule`] on the same block hands out the same incarnations to the
+//! same workers in the same order, each only after the executions that had
+//! finished before it in the recording. Executions that overlapped can still
+//! overlap differently, so a replay may reach a point the recording never
+//! did, e.g. a transaction that is not aborted this time; from there it
+//! schedules freely and reports where it diverged.
+
+use crate::types::{Incarnation, TxnIndex};
+
+/// One scheduler event of a recorded run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleEvent {
+    /// `worker` was handed `incarnation` of `txn_idx`.
+    Dispatch {
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        worker: usize,
+    },
+    /// That incarnation finished executing, aborts it caused included.
+    Finish { txn_idx: TxnIndex, incarnation: Incarnation },
+}
+
+/// The events of one block's run, in the order they happened.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schedule {
+    pub events: Vec<ScheduleEvent>,
+}
+
+impl Schedule {
+    /// The dispatches, in order, as `(txn_idx, incarnation, worker)`.
+    pub fn dispatches(&self) -> Vec<(TxnIndex, Incarnation, usize)> {
+        self.events
+            .iter()
+            .filter_map(|event| match *event {
+                ScheduleEvent::Dispatch {
+                    txn_idx,
+                    incarnation,
+                    worker,
+                } => Some((txn_idx, incarnation, worker)),
+                ScheduleEvent::Finish { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Number of workers a replay needs: one past the highest worker dispatched to.
+    pub fn num_workers(&self) -> usize {
+        self.dispatches()
+            .iter()
+            .map(|&(_, _, worker)| worker + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
@@ -1,10 +1,11 @@
 //! Scheduler for coordinating parallel transaction execution with push-based invalidation.
 
-use crate::types::{ExecutionStatus, Incarnation, TxnIndex, Version};
-use parking_lot::{Mutex, RwLock};
-use std::collections::{BTreeSet, VecDeque};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::Arc;
+use crate::mvhashmap::MVHashMap;
+use crate::schedule::{Schedule, ScheduleEvent};
+use crate::sync::{Arc, AtomicBool, AtomicU64, AtomicUsize, Mutex, Ordering, RwLock};
+use crate::types::{ExecutionStatus, Incarnation, TxnIndex, TxnIndices, Version, MAX_BLOCK_SIZE};
+use smallvec::SmallVec;
+use std::collections::{BTreeSet, HashSet, VecDeque};
 
 /// Task for a worker thread to execute.
 #[derive(Debug, Clone)]
@@ -22,32 +23,150 @@ pub struct Scheduler {
     /// Number of transactions in the block.
     num_txns: usize,
     
-    /// Execution status for each transaction.
+    /// Execution status for each transaction. A transaction's status lock is
+    /// also its abort guard: it is held across both phases of an abort.
     statuses: Vec<RwLock<ExecutionStatus>>,
     
+    /// Versioned writes of the block, whose stale entries aborts clear.
+    mv_hashmap: Arc<MVHashMap>,
+    
     /// Queue of transactions ready to execute.
     ready_queue: Mutex<VecDeque<(TxnIndex, Incarnation)>>,
     
+    /// Latest incarnation queued for each transaction. A queue entry for an
+    /// older incarnation is stale and dropped when it comes up.
+    scheduled_incarnations: Vec<AtomicUsize>,
+    
+    /// Stale queue entries dropped instead of executed.
+    suppressed_duplicates: AtomicUsize,
+    
     /// Highest transaction index that has been committed.
     committed_idx: AtomicUsize,
     
+    /// Incarnation each committed transaction was committed at.
+    committed_incarnations: Vec<AtomicUsize>,
+    
     /// Number of transactions that have been executed at least once.
     executed_once_count: AtomicUsize,
     
+    /// One past the highest transaction index that has finished executing.
+    executed_frontier: AtomicUsize,
+    
+    /// Commit lag observed at each finished execution: sum, count and maximum.
+    lag_sum: AtomicU64,
+    lag_samples: AtomicU64,
+    max_lag: AtomicUsize,
+    
     /// Whether execution is done.
     done: AtomicBool,
     
     /// Lock for committing transactions (only one thread can commit at a time).
     commit_lock: Mutex<()>,
+    
+    /// How far past the commit point transactions may be scheduled.
+    lookahead: usize,
+    
+    /// Tighter window imposed while over a memory budget; `usize::MAX` when unthrottled.
+    throttle_window: AtomicUsize,
+    
+    /// Hinted earlier dependencies per transaction; empty without hints.
+    hinted_dependencies: Vec<Vec<TxnIndex>>,
+    
+    /// Whether each transaction has finished executing at least once; only
+    /// tracked with hints.
+    executed_once: Vec<AtomicBool>,
+    
+    /// Number of dispatched executions that have not finished yet.
+    in_flight: AtomicUsize,
+    
+    /// Dispatch and finish events so far, when recording.
+    recording: Option<Mutex<Vec<ScheduleEvent>>>,
+    
+    /// Schedule being replayed, if any.
+    replay: Option<Replay>,
+}
+
+/// Progress through a replayed schedule.
+struct Replay {
+    schedule: Schedule,
+    /// Index of the next event to reach.
+    cursor: Mutex<usize>,
+    /// Incarnations that have finished executing in this run.
+    finished: Mutex<HashSet<(TxnIndex, Incarnation)>>,
+    /// Index of the first event that could not be followed; `usize::MAX`
+    /// while following.
+    divergence: AtomicUsize,
+}
+
+impl Replay {
+    fn is_following(&self) -> bool {
+        self.divergence.load(Ordering::Acquire) == usize::MAX
+    }
 }
 
 impl Scheduler {
     /// Creates a new scheduler for a block of transactions.
     pub fn new(num_txns: usize) -> Arc<Self> {
+        Self::with_lookahead(num_txns, None)
+    }
+
+    /// Creates a scheduler that only hands out transactions within `lookahead`
+    /// positions of the lowest uncommitted transaction.
+    ///
+    /// A smaller window wastes less work on speculative executions that are
+    /// likely to be invalidated, at the cost of less parallelism. `None`
+    /// schedules the whole block at once.
+    pub fn with_lookahead(num_txns: usize, lookahead: Option<usize>) -> Arc<Self> {
+        Self::with_hints(num_txns, lookahead, &[])
+    }
+
+    /// Creates a scheduler that also holds back each transaction until the
+    /// earlier transactions it is hinted to depend on have executed once.
+    ///
+    /// `hints` are `(earlier, later)` pairs; pairs that are out of range or not
+    /// ordered are ignored. Hints only delay scheduling, so wrong hints cost
+    /// parallelism but never correctness.
+    ///
+    /// # Panics
+    ///
+    /// If `num_txns` exceeds [`MAX_BLOCK_SIZE`].
+    pub fn with_hints(num_txns: usize, lookahead: Option<usize>, hints: &[(TxnIndex, TxnIndex)]) -> Arc<Self> {
+        Self::with_schedule(num_txns, lookahead, hints, false, None)
+    }
+
+    /// Creates a scheduler that records its dispatch order if `record` is set,
+    /// and forces the dispatch order of `replay` if given (see
+    /// [`crate::schedule`]).
+    ///
+    /// While a replay is followed, the lookahead window, hints and throttling
+    /// are ignored: the recorded order already respected them.
+    ///
+    /// # Panics
+    ///
+    /// If `num_txns` exceeds [`MAX_BLOCK_SIZE`].
+    pub fn with_schedule(
+        num_txns: usize,
+        lookahead: Option<usize>,
+        hints: &[(TxnIndex, TxnIndex)],
+        record: bool,
+        replay: Option<Schedule>,
+    ) -> Arc<Self> {
+        assert!(
+            num_txns <= MAX_BLOCK_SIZE,
+            "block of {} transactions exceeds MAX_BLOCK_SIZE",
+            num_txns
+        );
+        let mut hinted_dependencies = vec![Vec::new(); if hints.is_empty() { 0 } else { num_txns }];
+        for &(earlier, later) in hints {
+            if earlier < later && (later as usize) < num_txns {
+                hinted_dependencies[later as usize].push(earlier);
+            }
+        }
+        
         let mut ready_queue = VecDeque::new();
         
         // Initially, all transactions are ready to execute for the first time
-        for idx in 0..num_txns {
+        for idx in 0..num_txns as TxnIndex {
             ready_queue.push_back((idx, 0));
         }
         
@@ -56,90 +175,394 @@ impl Scheduler {
             statuses: (0..num_txns)
                 .map(|_| RwLock::new(ExecutionStatus::Pending))
                 .collect(),
+            mv_hashmap: Arc::new(MVHashMap::new()),
             ready_queue: Mutex::new(ready_queue),
+            scheduled_incarnations: (0..num_txns).map(|_| AtomicUsize::new(0)).collect(),
+            suppressed_duplicates: AtomicUsize::new(0),
             committed_idx: AtomicUsize::new(0),
+            committed_incarnations: (0..num_txns).map(|_| AtomicUsize::new(0)).collect(),
             executed_once_count: AtomicUsize::new(0),
+            executed_frontier: AtomicUsize::new(0),
+            lag_sum: AtomicU64::new(0),
+            lag_samples: AtomicU64::new(0),
+            max_lag: AtomicUsize::new(0),
             done: AtomicBool::new(false),
             commit_lock: Mutex::new(()),
+            lookahead: lookahead.map_or(usize::MAX, |window| window.max(1)),
+            throttle_window: AtomicUsize::new(usize::MAX),
+            executed_once: hinted_dependencies.iter().map(|_| AtomicBool::new(false)).collect(),
+            hinted_dependencies,
+            in_flight: AtomicUsize::new(0),
+            recording: record.then(|| Mutex::new(Vec::new())),
+            replay: replay.map(|schedule| Replay {
+                schedule,
+                cursor: Mutex::new(0),
+                finished: Mutex::new(HashSet::new()),
+                divergence: AtomicUsize::new(usize::MAX),
+            }),
         })
     }
 
+    /// The multi-version map transactions scheduled here read and write.
+    pub fn mv_hashmap(&self) -> &Arc<MVHashMap> {
+        &self.mv_hashmap
+    }
+
     /// Gets the next task for a worker thread.
     pub fn next_task(&self) -> Task {
+        self.next_task_for(0)
+    }
+
+    /// Gets the next task for worker `worker`, which only matters when
+    /// recording or replaying a schedule.
+    pub fn next_task_for(&self, worker: usize) -> Task {
         // Check if we're done
         if self.done.load(Ordering::Acquire) {
             return Task::Done;
         }
         
-        // Try to get a task from the ready queue
+        // Follow the replayed schedule until it diverges
+        if let Some(replay) = self.replay.as_ref().filter(|replay| replay.is_following()) {
+            if let Some(task) = self.next_replayed_task(replay, worker) {
+                return task;
+            }
+        }
+        
+        // Try to get a task within the lookahead window from the ready queue
+        let window = self.lookahead.min(self.throttle_window.load(Ordering::Relaxed));
+        let limit = self
+            .committed_idx
+            .load(Ordering::Acquire)
+            .saturating_add(window);
         let mut queue = self.ready_queue.lock();
+        let next = loop {
+            let Some((txn_idx, incarnation)) = queue
+                .iter()
+                .position(|&(txn_idx, _)| (txn_idx as usize) < limit && self.hints_satisfied(txn_idx))
+                .and_then(|position| queue.remove(position))
+            else {
+                break None;
+            };
+            // A later incarnation was queued since; that entry runs instead
+            if self.is_stale(txn_idx, incarnation) {
+                self.suppressed_duplicates.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            break Some((txn_idx, incarnation));
+        };
         
-        if let Some((txn_idx, incarnation)) = queue.pop_front() {
-            // Mark as executing
-            *self.statuses[txn_idx].write() = ExecutionStatus::Executing(incarnation);
+        if let Some((txn_idx, incarnation)) = next {
+            // Mark as executing; recorded under the queue lock to keep dispatch order
+            self.dispatch(txn_idx, incarnation, worker);
             drop(queue);
             
             return Task::Execute(txn_idx, incarnation);
         }
         
         drop(queue);
-        
+        self.wait()
+    }
+
+    /// Whether a later incarnation of `txn_idx` than `incarnation` was queued.
+    fn is_stale(&self, txn_idx: TxnIndex, incarnation: Incarnation) -> bool {
+        incarnation < self.scheduled_incarnations[txn_idx as usize].load(Ordering::Acquire)
+    }
+
+    /// Marks a dequeued incarnation as executing on `worker`.
+    fn dispatch(&self, txn_idx: TxnIndex, incarnation: Incarnation, worker: usize) {
+        *self.statuses[txn_idx as usize].write() = ExecutionStatus::Executing(incarnation);
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        if let Some(recording) = &self.recording {
+            recording.lock().push(ScheduleEvent::Dispatch {
+                txn_idx,
+                incarnation,
+                worker,
+            });
+        }
+    }
+
+    /// Returns `Done` if every transaction has committed, and `Wait` otherwise.
+    fn wait(&self) -> Task {
         // Check if all transactions are committed
         if self.committed_idx.load(Ordering::Acquire) >= self.num_txns {
             self.done.store(true, Ordering::Release);
             return Task::Done;
         }
         
+        // Executed transactions may be waiting on a commit whose lock holder
+        // already gave up; committing here keeps the window moving.
+        if let Some(_guard) = self.commit_lock.try_lock() {
+            self.try_commit_transactions();
+        }
+        
         // No task available right now
         Task::Wait
     }
 
-    /// Marks a transaction as executed successfully.
+    /// Hands `worker` the replayed schedule's next dispatch if it is that
+    /// worker's turn, skipping finish events that have happened.
+    ///
+    /// Returns `None` once the next event can never happen: nothing is
+    /// executing, so no abort can still queue the awaited incarnation or
+    /// finish the awaited execution. The replay then stops being followed.
+    fn next_replayed_task(&self, replay: &Replay, worker: usize) -> Option<Task> {
+        let mut cursor = replay.cursor.lock();
+        // Another worker may have stopped following while this one waited
+        if !replay.is_following() {
+            return None;
+        }
+        loop {
+            // Read first: executions queue their aborts and record their
+            // finish before they stop counting as in flight
+            let idle = self.in_flight.load(Ordering::Acquire) == 0;
+            let stuck = match replay.schedule.events.get(*cursor) {
+                Some(&ScheduleEvent::Finish { txn_idx, incarnation }) => {
+                    if replay.finished.lock().contains(&(txn_idx, incarnation)) {
+                        *cursor += 1;
+                        continue;
+                    }
+                    idle
+                }
+                Some(&ScheduleEvent::Dispatch {
+                    txn_idx,
+                    incarnation,
+                    worker: owner,
+                }) => {
+                    let mut queue = self.ready_queue.lock();
+                    match queue.iter().position(|&entry| entry == (txn_idx, incarnation)) {
+                        Some(position) if owner == worker => {
+                            queue.remove(position);
+                            self.dispatch(txn_idx, incarnation, worker);
+                            *cursor += 1;
+                            return Some(Task::Execute(txn_idx, incarnation));
+                        }
+                        Some(_) => false,
+                        None => idle,
+                    }
+                }
+                // The recording ended with the block; only commits are left
+                None => idle && self.committed_idx.load(Ordering::Acquire) < self.num_txns && {
+                    if let Some(_guard) = self.commit_lock.try_lock() {
+                        self.try_commit_transactions();
+                    }
+                    self.committed_idx.load(Ordering::Acquire) < self.num_txns
+                },
+            };
+            if !stuck {
+                drop(cursor);
+                return Some(self.wait());
+            }
+            let _ = replay
+                .divergence
+                .compare_exchange(usize::MAX, *cursor, Ordering::AcqRel, Ordering::Acquire);
+            return None;
+        }
+    }
+
+    /// The events recorded so far, if recording.
+    pub fn recorded_schedule(&self) -> Option<Schedule> {
+        self.recording.as_ref().map(|recording| Schedule {
+            events: recording.lock().clone(),
+        })
+    }
+
+    /// Index of the first replayed event that could not be followed, if the
+    /// replay diverged.
+    ///
+    /// A run that commits every transaction before reaching the end of the
+    /// schedule diverged at the first event it never reached: the recording
+    /// dispatched more than this run needed, so its order was not followed
+    /// to the end.
+    pub fn replay_divergence(&self) -> Option<usize> {
+        let replay = self.replay.as_ref()?;
+        let divergence = replay.divergence.load(Ordering::Acquire);
+        if divergence != usize::MAX {
+            return Some(divergence);
+        }
+        let cursor = *replay.cursor.lock();
+        let finished = replay.finished.lock();
+        replay.schedule.events[cursor..]
+            .iter()
+            .position(|event| match *event {
+                ScheduleEvent::Finish { txn_idx, incarnation } => !finished.contains(&(txn_idx, incarnation)),
+                ScheduleEvent::Dispatch { .. } => true,
+            })
+            .map(|offset| cursor + offset)
+    }
+
+    /// Marks a transaction as executed successfully, aborting the
+    /// transactions its writes invalidated.
     ///
-    /// Returns the list of transactions that were invalidated by this execution.
+    /// Readers are aborted before the transaction is marked executed: once it
+    /// is, a concurrent commit may sweep past it and commit a reader that
+    /// has not been aborted yet. Versions an earlier incarnation wrote and
+    /// this one did not are cleared first, aborting their readers too.
+    ///
+    /// An execution aborted while it ran completes its abort here, now that
+    /// its writes are in place, and is rescheduled.
     pub fn finish_execution(
         &self,
         txn_idx: TxnIndex,
         incarnation: Incarnation,
-        invalidated: Vec<TxnIndex>,
+        mut invalidated: TxnIndices,
     ) {
+        if incarnation > 0 {
+            invalidated.extend(self.mv_hashmap.clear_stale(txn_idx, incarnation));
+        }
+        
+        // Abort invalidated transactions
+        self.abort_many(&invalidated);
+        
         // Update status
-        *self.statuses[txn_idx].write() = ExecutionStatus::Executed(incarnation);
+        {
+            let mut status = self.statuses[txn_idx as usize].write();
+            match *status {
+                ExecutionStatus::Executing(current) if current == incarnation => {
+                    *status = ExecutionStatus::Executed(incarnation);
+                }
+                ExecutionStatus::Aborting(current) if current == incarnation => {
+                    self.reschedule(txn_idx, incarnation, &mut status);
+                }
+                _ => {}
+            }
+        }
+        if let Some(executed) = self.executed_once.get(txn_idx as usize) {
+            executed.store(true, Ordering::Release);
+        }
         
         // Track if this was the first execution
         if incarnation == 0 {
             self.executed_once_count.fetch_add(1, Ordering::AcqRel);
         }
         
-        // Abort invalidated transactions
-        for &invalid_idx in &invalidated {
-            self.abort_transaction(invalid_idx);
-        }
-        
         // Try to acquire commit lock and commit transactions
         // Only one thread should do this at a time to avoid contention
         if let Some(_guard) = self.commit_lock.try_lock() {
             self.try_commit_transactions();
         }
+        
+        self.record_commit_lag(txn_idx);
+        
+        // Everything this execution caused is in place; only now may a replay
+        // move past its finish or conclude that nothing is executing
+        if let Some(recording) = &self.recording {
+            recording.lock().push(ScheduleEvent::Finish { txn_idx, incarnation });
+        }
+        if let Some(replay) = &self.replay {
+            replay.finished.lock().insert((txn_idx, incarnation));
+        }
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    /// Samples the gap between the executed frontier and the commit point.
+    fn record_commit_lag(&self, txn_idx: TxnIndex) {
+        let next = txn_idx as usize + 1;
+        let frontier = self.executed_frontier.fetch_max(next, Ordering::AcqRel).max(next);
+        let lag = frontier.saturating_sub(self.committed_idx.load(Ordering::Acquire));
+        
+        self.lag_sum.fetch_add(lag as u64, Ordering::Relaxed);
+        self.lag_samples.fetch_add(1, Ordering::Relaxed);
+        self.max_lag.fetch_max(lag, Ordering::Relaxed);
+    }
+
+    /// Returns the maximum and average number of transactions executed past
+    /// the commit point, sampled after every execution.
+    ///
+    /// Everything in that gap is speculative state held in memory; a large
+    /// lag is what the lookahead window bounds.
+    pub fn commit_lag(&self) -> CommitLag {
+        let samples = self.lag_samples.load(Ordering::Acquire);
+        CommitLag {
+            max: self.max_lag.load(Ordering::Acquire),
+            average: if samples == 0 {
+                0.0
+            } else {
+                self.lag_sum.load(Ordering::Acquire) as f64 / samples as f64
+            },
+        }
+    }
+
+    /// Whether every hinted dependency of `txn_idx` has executed at least once.
+    fn hints_satisfied(&self, txn_idx: TxnIndex) -> bool {
+        self.hinted_dependencies.get(txn_idx as usize).map_or(true, |dependencies| {
+            dependencies
+                .iter()
+                .all(|&dependency| self.executed_once[dependency as usize].load(Ordering::Acquire))
+        })
+    }
+
+    /// Narrows scheduling to `window` transactions past the commit point until
+    /// called again with `None`, e.g. while speculative state is over budget.
+    pub fn throttle(&self, window: Option<usize>) {
+        let window = window.map_or(usize::MAX, |window| window.max(1));
+        self.throttle_window.store(window, Ordering::Relaxed);
     }
 
     /// Aborts a transaction and schedules it for re-execution.
+    ///
+    /// An abort has two phases, both under the transaction's abort guard:
+    /// its writes are marked as estimates, so readers wait for the
+    /// re-execution instead of reading stale values, and only then is it
+    /// rescheduled. A transaction still executing is marked aborting and
+    /// finishes both phases in [`finish_execution`](Self::finish_execution),
+    /// so no write of the old incarnation can land after its estimates.
     pub fn abort_transaction(&self, txn_idx: TxnIndex) {
-        let mut status = self.statuses[txn_idx].write();
-        
-        match *status {
-            ExecutionStatus::Executing(incarnation) | ExecutionStatus::Executed(incarnation) => {
-                // Re-schedule for execution with incremented incarnation
-                let new_incarnation = incarnation + 1;
-                *status = ExecutionStatus::Pending;
-                
-                let mut queue = self.ready_queue.lock();
-                queue.push_back((txn_idx, new_incarnation));
-            }
-            _ => {
-                // Already pending or committed, nothing to do
+        self.abort_many(&[txn_idx]);
+    }
+
+    /// Aborts every transaction in `txn_indices` like
+    /// [`abort_transaction`](Self::abort_transaction), aborting duplicates
+    /// once and taking the ready-queue lock once for the whole batch.
+    ///
+    /// Each transaction is marked under its own abort guard; the next
+    /// incarnations are queued after the guards are released. In between a
+    /// rescheduled transaction is pending but not queued, so nothing can
+    /// dispatch, abort or commit it.
+    pub fn abort_many(&self, txn_indices: &[TxnIndex]) {
+        let mut txn_indices = TxnIndices::from_slice(txn_indices);
+        txn_indices.sort_unstable();
+        txn_indices.dedup();
+        
+        let mut rescheduled: SmallVec<[(TxnIndex, Incarnation); 4]> = SmallVec::new();
+        for &txn_idx in &txn_indices {
+            let mut status = self.statuses[txn_idx as usize].write();
+            match *status {
+                ExecutionStatus::Executing(incarnation) => {
+                    *status = ExecutionStatus::Aborting(incarnation);
+                }
+                ExecutionStatus::Executed(incarnation) => {
+                    rescheduled.push(self.mark_aborted(txn_idx, incarnation, &mut status));
+                }
+                _ => {
+                    // Already pending, aborting or committed, nothing to do
+                }
             }
         }
+        
+        if !rescheduled.is_empty() {
+            self.ready_queue.lock().extend(rescheduled);
+        }
+    }
+
+    /// Marks an aborted incarnation's writes as estimates and queues the next
+    /// incarnation; `status` is the transaction's held abort guard.
+    fn reschedule(&self, txn_idx: TxnIndex, incarnation: Incarnation, status: &mut ExecutionStatus) {
+        let next = self.mark_aborted(txn_idx, incarnation, status);
+        self.ready_queue.lock().push_back(next);
+    }
+
+    /// First phase of an abort: marks the incarnation's writes as estimates
+    /// and the transaction pending, returning the incarnation to queue next.
+    fn mark_aborted(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        status: &mut ExecutionStatus,
+    ) -> (TxnIndex, Incarnation) {
+        self.mv_hashmap.mark_estimate(txn_idx);
+        *status = ExecutionStatus::Pending;
+        self.scheduled_incarnations[txn_idx as usize].fetch_max(incarnation + 1, Ordering::AcqRel);
+        (txn_idx, incarnation + 1)
     }
 
     /// Tries to commit transactions in order.
@@ -148,14 +571,15 @@ impl Scheduler {
         
         // Commit transactions in order as long as they're executed
         while committed_idx < self.num_txns {
-            let status = self.statuses[committed_idx].read();
+            // Check and commit under one write lock, so an abort cannot land in between
+            let mut status = self.statuses[committed_idx].write();
             
             match *status {
-                ExecutionStatus::Executed(_) => {
-                    drop(status);
-                    
+                ExecutionStatus::Executed(incarnation) => {
                     // Commit this transaction
-                    *self.statuses[committed_idx].write() = ExecutionStatus::Committed;
+                    *status = ExecutionStatus::Committed;
+                    self.committed_incarnations[committed_idx].store(incarnation, Ordering::Release);
+                    drop(status);
                     
                     // Move to next
                     committed_idx += 1;
@@ -177,14 +601,14 @@ impl Scheduler {
     /// Checks if a transaction has been committed.
     pub fn is_committed(&self, txn_idx: TxnIndex) -> bool {
         matches!(
-            *self.statuses[txn_idx].read(),
+            *self.statuses[txn_idx as usize].read(),
             ExecutionStatus::Committed
         )
     }
 
     /// Gets the current status of a transaction.
     pub fn get_status(&self, txn_idx: TxnIndex) -> ExecutionStatus {
-        *self.statuses[txn_idx].read()
+        *self.statuses[txn_idx as usize].read()
     }
 
     /// Checks if all transactions are done.
@@ -197,6 +621,21 @@ impl Scheduler {
         self.committed_idx.load(Ordering::Acquire)
     }
 
+    /// Returns the number of stale queue entries dropped instead of executed.
+    pub fn suppressed_duplicates(&self) -> usize {
+        self.suppressed_duplicates.load(Ordering::Acquire)
+    }
+
+    /// Returns the version each committed transaction was committed at, by
+    /// transaction index.
+    pub fn committed_versions(&self) -> Vec<Version> {
+        self.committed_incarnations[..self.committed_count()]
+            .iter()
+            .enumerate()
+            .map(|(txn_idx, incarnation)| Version::new(txn_idx as TxnIndex, incarnation.load(Ordering::Acquire)))
+            .collect()
+    }
+
     /// Gets statistics about execution progress.
     pub fn stats(&self) -> SchedulerStats {
         let mut pending = 0;
@@ -208,7 +647,7 @@ impl Scheduler {
         for status_lock in &self.statuses {
             match *status_lock.read() {
                 ExecutionStatus::Pending => pending += 1,
-                ExecutionStatus::Executing(inc) => {
+                ExecutionStatus::Executing(inc) | ExecutionStatus::Aborting(inc) => {
                     executing += 1;
                     total_incarnations += inc + 1;
                 }
@@ -226,10 +665,20 @@ impl Scheduler {
             executed,
             committed,
             total_incarnations,
+            suppressed_duplicates: self.suppressed_duplicates.load(Ordering::Acquire),
         }
     }
 }
 
+/// Gap between the highest executed transaction and the commit point.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CommitLag {
+    /// Largest gap observed.
+    pub max: usize,
+    /// Average gap over all samples.
+    pub average: f64,
+}
+
 /// Statistics about scheduler state.
 #[derive(Debug, Clone)]
 pub struct SchedulerStats {
@@ -238,11 +687,14 @@ pub struct SchedulerStats {
     pub executed: usize,
     pub committed: usize,
     pub total_incarnations: usize,
+    /// Queue entries for superseded incarnations dropped instead of executed.
+    pub suppressed_duplicates: usize,
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(loom)))]
 mod tests {
     use super::*;
+    use smallvec::smallvec;
 
     #[test]
     fn test_scheduler_basic() {
@@ -272,13 +724,13 @@ mod tests {
         
         // Execute transaction 0
         let _ = scheduler.next_task();
-        scheduler.finish_execution(0, 0, vec![]);
+        scheduler.finish_execution(0, 0, TxnIndices::new());
         
         // Execute transaction 1
         let _ = scheduler.next_task();
         
         // Transaction 1 invalidates transaction 0
-        scheduler.finish_execution(1, 0, vec![0]);
+        scheduler.finish_execution(1, 0, smallvec![0]);
         
         // Should get transaction 0 again with incarnation 1
         match scheduler.next_task() {
@@ -289,5 +741,164 @@ mod tests {
             _ => panic!("Expected Execute task for re-execution"),
         }
     }
+
+    #[test]
+    fn test_lookahead_window() {
+        let scheduler = Scheduler::with_lookahead(4, Some(2));
+        
+        // Only transactions 0 and 1 are inside the window
+        assert!(matches!(scheduler.next_task(), Task::Execute(0, 0)));
+        assert!(matches!(scheduler.next_task(), Task::Execute(1, 0)));
+        assert!(matches!(scheduler.next_task(), Task::Wait));
+        
+        // Committing transaction 0 slides the window forward by one
+        scheduler.finish_execution(0, 0, TxnIndices::new());
+        assert!(matches!(scheduler.next_task(), Task::Execute(2, 0)));
+        assert!(matches!(scheduler.next_task(), Task::Wait));
+    }
+
+    #[test]
+    fn test_dependency_hints_delay_scheduling() {
+        let scheduler = Scheduler::with_hints(3, None, &[(0, 2)]);
+        
+        // Transaction 2 is held back until transaction 0 has executed
+        assert!(matches!(scheduler.next_task(), Task::Execute(0, 0)));
+        assert!(matches!(scheduler.next_task(), Task::Execute(1, 0)));
+        assert!(matches!(scheduler.next_task(), Task::Wait));
+        
+        scheduler.finish_execution(0, 0, TxnIndices::new());
+        assert!(matches!(scheduler.next_task(), Task::Execute(2, 0)));
+    }
+
+    #[test]
+    fn test_commit_lag() {
+        let scheduler = Scheduler::new(3);
+        for _ in 0..3 {
+            let _ = scheduler.next_task();
+        }
+        
+        // Transaction 2 finishes first: nothing commits, lag is 3
+        scheduler.finish_execution(2, 0, TxnIndices::new());
+        // Transaction 1 finishes: still blocked on 0
+        scheduler.finish_execution(1, 0, TxnIndices::new());
+        // Transaction 0 finishes: everything commits
+        scheduler.finish_execution(0, 0, TxnIndices::new());
+        
+        let lag = scheduler.commit_lag();
+        assert_eq!(lag.max, 3);
+        assert_eq!(lag.average, 2.0);
+    }
+
+    #[test]
+    fn test_abort_hides_stale_writes_until_reexecution() {
+        use crate::mvhashmap::ReadResult;
+        use crate::types::AccountState;
+        use alloy_primitives::{Address, U256};
+        
+        let scheduler = Scheduler::new(3);
+        let mv = scheduler.mv_hashmap().clone();
+        let addr = Address::random();
+        for _ in 0..3 {
+            let _ = scheduler.next_task();
+        }
+        
+        // Transaction 1 executes and writes, then is aborted
+        mv.write(addr, 1, 0, AccountState::new(1, U256::from(100)));
+        scheduler.finish_execution(1, 0, TxnIndices::new());
+        scheduler.abort_transaction(1);
+        assert!(matches!(mv.read(addr, 2), ReadResult::Dependency(1)));
+        
+        // Transaction 2 is aborted mid-execution: it is only rescheduled once it finishes
+        scheduler.abort_transaction(2);
+        assert!(matches!(scheduler.next_task(), Task::Execute(1, 1)));
+        assert!(matches!(scheduler.next_task(), Task::Wait));
+        scheduler.finish_execution(2, 0, TxnIndices::new());
+        assert_eq!(scheduler.get_status(2), ExecutionStatus::Pending);
+        assert!(matches!(scheduler.next_task(), Task::Execute(2, 1)));
+        
+        // Re-executing without the write clears the estimate
+        scheduler.finish_execution(1, 1, TxnIndices::new());
+        assert!(matches!(mv.read(addr, 2), ReadResult::Storage));
+    }
+
+    #[test]
+    fn test_abort_many_coalesces_duplicates() {
+        let scheduler = Scheduler::new(4);
+        for _ in 0..4 {
+            let _ = scheduler.next_task();
+        }
+        for txn_idx in [1, 2] {
+            scheduler.finish_execution(txn_idx, 0, TxnIndices::new());
+        }
+        
+        // Executed transactions are requeued once each, in index order; the
+        // executing one is only marked aborting
+        scheduler.abort_many(&[2, 1, 2, 3, 1]);
+        assert_eq!(scheduler.get_status(1), ExecutionStatus::Pending);
+        assert_eq!(scheduler.get_status(3), ExecutionStatus::Aborting(0));
+        assert!(matches!(scheduler.next_task(), Task::Execute(1, 1)));
+        assert!(matches!(scheduler.next_task(), Task::Execute(2, 1)));
+        assert!(matches!(scheduler.next_task(), Task::Wait));
+    }
+
+    #[test]
+    fn test_stale_queue_entries_are_suppressed() {
+        let scheduler = Scheduler::new(2);
+        let _ = scheduler.next_task();
+        let _ = scheduler.next_task();
+        scheduler.finish_execution(1, 0, TxnIndices::new());
+        
+        // Abort transaction 1 twice around a queue entry for an incarnation
+        // that a later abort supersedes
+        scheduler.abort_transaction(1);
+        scheduler.ready_queue.lock().push_front((1, 0));
+        assert!(matches!(scheduler.next_task(), Task::Execute(1, 1)));
+        assert!(matches!(scheduler.next_task(), Task::Wait));
+        assert_eq!(scheduler.stats().suppressed_duplicates, 1);
+    }
+
+    #[test]
+    fn test_committed_versions_track_final_incarnations() {
+        let scheduler = Scheduler::new(3);
+        for _ in 0..3 {
+            let _ = scheduler.next_task();
+        }
+        scheduler.finish_execution(1, 0, TxnIndices::new());
+        scheduler.abort_transaction(1);
+        assert!(matches!(scheduler.next_task(), Task::Execute(1, 1)));
+        scheduler.finish_execution(1, 1, TxnIndices::new());
+        scheduler.finish_execution(0, 0, TxnIndices::new());
+        
+        // Transaction 2 is still executing, so only the prefix is committed
+        assert_eq!(scheduler.committed_versions(), vec![Version::new(0, 0), Version::new(1, 1)]);
+    }
 }
 
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::*;
+    use smallvec::smallvec;
+    use loom::thread;
+
+    #[test]
+    fn test_invalidated_reader_never_commits_stale() {
+        loom::model(|| {
+            let scheduler = Scheduler::new(2);
+            assert!(matches!(scheduler.next_task(), Task::Execute(0, 0)));
+            assert!(matches!(scheduler.next_task(), Task::Execute(1, 0)));
+            
+            // Transaction 0's write invalidates the read transaction 1 is
+            // finishing with, while either worker may sweep commits
+            let writer = {
+                let scheduler = scheduler.clone();
+                thread::spawn(move || scheduler.finish_execution(0, 0, smallvec![1]))
+            };
+            scheduler.finish_execution(1, 0, TxnIndices::new());
+            writer.join().unwrap();
+            
+            assert!(!scheduler.is_committed(1));
+            assert!(matches!(scheduler.next_task(), Task::Execute(1, 1)));
+        });
+    }
+}
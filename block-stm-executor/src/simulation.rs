@@ -0,0 +1,424 @@
+//! Discrete-event simulation of Block-STM in virtual time.
+//!
+//! [`simulate`] runs the protocol on one thread: `num_workers` virtual
+//! workers take transactions from a ready queue, each execution takes its
+//! transaction's cost in virtual time, and aborts and in-order commits follow
+//! the real scheduler. Nothing depends on the machine, so a run is exactly
+//! reproducible and scheduling policies can be compared at any worker count
+//! without multi-core noise.
+//!
+//! The model keeps the dependency structure and drops the values: every
+//! transaction reads and writes its sender and recipient, reads happen when
+//! an execution starts and writes when it ends. A write aborts every higher
+//! transaction that read an older version of the account. A read of an
+//! aborted transaction's write ends the execution as a retry, which writes
+//! nothing and waits to be aborted again once the writer re-executes.
+
+use crate::executor::Transaction;
+use crate::types::{Incarnation, TxnIndex};
+use alloy_primitives::Address;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// Order in which ready transactions are handed to idle workers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchedulingPolicy {
+    /// In the order they became ready, like the threaded scheduler.
+    Fifo,
+    /// Lowest transaction index first.
+    Priority,
+    /// Lowest transaction index whose predicted dependencies have executed:
+    /// the nearest earlier transaction touching each of its accounts. Workers
+    /// idle rather than start a transaction that would read a stale version.
+    DependencyAware,
+}
+
+impl SchedulingPolicy {
+    /// Every policy.
+    pub const ALL: [Self; 3] = [Self::Fifo, Self::Priority, Self::DependencyAware];
+
+    /// Short name, as accepted by [`FromStr`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fifo => "fifo",
+            Self::Priority => "priority",
+            Self::DependencyAware => "dependency-aware",
+        }
+    }
+}
+
+impl fmt::Display for SchedulingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SchedulingPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.name() == s)
+            .ok_or_else(|| format!("unknown scheduling policy '{}' (expected fifo, priority or dependency-aware)", s))
+    }
+}
+
+/// Virtual machine to simulate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationConfig {
+    pub num_workers: usize,
+    pub policy: SchedulingPolicy,
+}
+
+/// Outcome of a simulated block, in virtual time units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub num_workers: usize,
+    pub policy: SchedulingPolicy,
+    /// Transactions in the block.
+    pub num_txns: usize,
+    /// Time until the last commit.
+    pub makespan: u64,
+    /// Sum of every transaction's cost: the makespan of one worker.
+    pub sequential_time: u64,
+    /// Executions, re-executions included.
+    pub executions: usize,
+    /// Executions that read an aborted transaction's write.
+    pub retries: usize,
+    /// Time workers spent executing, summed over workers.
+    pub busy_time: u64,
+}
+
+impl SimulationReport {
+    /// Sequential time over the makespan.
+    pub fn speedup(&self) -> f64 {
+        if self.makespan == 0 {
+            1.0
+        } else {
+            self.sequential_time as f64 / self.makespan as f64
+        }
+    }
+
+    /// Fraction of worker time spent executing.
+    pub fn utilization(&self) -> f64 {
+        let capacity = self.makespan.saturating_mul(self.num_workers as u64);
+        if capacity == 0 {
+            0.0
+        } else {
+            self.busy_time as f64 / capacity as f64
+        }
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on {} workers: makespan {}, speedup {:.2}x, {} executions for {} transactions ({} retries), \
+             utilization {:.1}%",
+            self.policy,
+            self.num_workers,
+            self.makespan,
+            self.speedup(),
+            self.executions,
+            self.num_txns,
+            self.retries,
+            self.utilization() * 100.0
+        )
+    }
+}
+
+/// Simulates executing `transactions` as one block, transaction `i` taking
+/// `costs[i]` per execution.
+///
+/// # Panics
+///
+/// If `config.num_workers` is zero or `costs` is shorter than `transactions`.
+pub fn simulate(transactions: &[Transaction], costs: &[u64], config: SimulationConfig) -> SimulationReport {
+    assert!(config.num_workers > 0, "Simulation needs at least one worker");
+    assert!(costs.len() >= transactions.len(), "Missing execution costs");
+    Simulation::new(transactions, costs, config.policy).run(config.num_workers)
+}
+
+/// Where a simulated transaction stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Executing,
+    /// Aborted while executing; re-queued once the execution ends.
+    Aborting,
+    /// Executed; a retry still has to be aborted and re-executed.
+    Executed { retry: bool },
+    Committed,
+}
+
+/// A running execution, ordered by end time and then start order.
+type Event = Reverse<(u64, u64, TxnIndex, Incarnation, bool)>;
+
+struct Simulation<'a> {
+    costs: &'a [u64],
+    policy: SchedulingPolicy,
+    /// Dense ids of the accounts each transaction touches.
+    accounts: Vec<Vec<usize>>,
+    /// Nearest earlier transaction touching each account of a transaction.
+    predecessors: Vec<Vec<TxnIndex>>,
+    status: Vec<Status>,
+    incarnations: Vec<Incarnation>,
+    /// Per account, each writer and whether its write is an estimate.
+    writes: Vec<BTreeMap<TxnIndex, bool>>,
+    /// Per account, each reader and the writer its last read resolved to.
+    readers: Vec<BTreeMap<TxnIndex, Option<TxnIndex>>>,
+    /// Ready transactions keyed by policy order.
+    ready: BTreeSet<(u64, TxnIndex)>,
+    /// Order in which transactions became ready, for FIFO.
+    sequence: u64,
+    committed: usize,
+}
+
+impl<'a> Simulation<'a> {
+    fn new(transactions: &[Transaction], costs: &'a [u64], policy: SchedulingPolicy) -> Self {
+        let mut ids: HashMap<Address, usize> = HashMap::new();
+        let mut last_toucher: HashMap<usize, TxnIndex> = HashMap::new();
+        let mut accounts = Vec::with_capacity(transactions.len());
+        let mut predecessors = Vec::with_capacity(transactions.len());
+        for (txn_idx, tx) in transactions.iter().enumerate() {
+            let mut touched = Vec::with_capacity(2);
+            for address in [tx.from, tx.to] {
+                let next_id = ids.len();
+                let id = *ids.entry(address).or_insert(next_id);
+                if !touched.contains(&id) {
+                    touched.push(id);
+                }
+            }
+            predecessors.push(
+                touched
+                    .iter()
+                    .filter_map(|id| last_toucher.insert(*id, txn_idx as TxnIndex))
+                    .collect(),
+            );
+            accounts.push(touched);
+        }
+
+        let mut simulation = Self {
+            costs,
+            policy,
+            accounts,
+            predecessors,
+            status: vec![Status::Pending; transactions.len()],
+            incarnations: vec![0; transactions.len()],
+            writes: vec![BTreeMap::new(); ids.len()],
+            readers: vec![BTreeMap::new(); ids.len()],
+            ready: BTreeSet::new(),
+            sequence: 0,
+            committed: 0,
+        };
+        for txn_idx in 0..transactions.len() {
+            simulation.push_ready(txn_idx as TxnIndex);
+        }
+        simulation
+    }
+
+    fn run(mut self, num_workers: usize) -> SimulationReport {
+        let mut report = SimulationReport {
+            num_workers,
+            policy: self.policy,
+            num_txns: self.status.len(),
+            makespan: 0,
+            sequential_time: self.costs[..self.status.len()].iter().sum(),
+            executions: 0,
+            retries: 0,
+            busy_time: 0,
+        };
+        let mut events: BinaryHeap<Event> = BinaryHeap::new();
+        let mut started = 0u64;
+        let mut idle = num_workers;
+        let mut now = 0;
+
+        loop {
+            // Fill idle workers, unless the policy prefers to wait for a
+            // running execution
+            while idle > 0 {
+                let Some(txn_idx) = self.next_ready(events.is_empty()) else {
+                    break;
+                };
+                let retry = self.dispatch(txn_idx);
+                let cost = self.costs[txn_idx as usize];
+                events.push(Reverse((now + cost, started, txn_idx, self.incarnations[txn_idx as usize], retry)));
+                started += 1;
+                idle -= 1;
+                report.executions += 1;
+                report.retries += retry as usize;
+                report.busy_time += cost;
+            }
+
+            let Some(Reverse((end, _, txn_idx, incarnation, retry))) = events.pop() else {
+                break;
+            };
+            now = end;
+            idle += 1;
+            self.finish(txn_idx, incarnation, retry);
+        }
+
+        assert_eq!(self.committed, self.status.len(), "Simulation stalled before every commit");
+        report.makespan = now;
+        report
+    }
+
+    fn push_ready(&mut self, txn_idx: TxnIndex) {
+        let key = match self.policy {
+            SchedulingPolicy::Fifo => {
+                self.sequence += 1;
+                self.sequence
+            }
+            SchedulingPolicy::Priority | SchedulingPolicy::DependencyAware => txn_idx as u64,
+        };
+        self.ready.insert((key, txn_idx));
+    }
+
+    /// Takes the next transaction to start. Waiting policies still take one
+    /// when nothing is running, which would otherwise never change.
+    fn next_ready(&mut self, nothing_running: bool) -> Option<TxnIndex> {
+        let entry = match self.policy {
+            SchedulingPolicy::Fifo | SchedulingPolicy::Priority => self.ready.first().copied(),
+            SchedulingPolicy::DependencyAware => self
+                .ready
+                .iter()
+                .find(|&&(_, txn_idx)| {
+                    self.predecessors[txn_idx as usize].iter().all(|&predecessor| {
+                        matches!(
+                            self.status[predecessor as usize],
+                            Status::Executed { retry: false } | Status::Committed
+                        )
+                    })
+                })
+                .or_else(|| self.ready.first().filter(|_| nothing_running))
+                .copied(),
+        }?;
+        self.ready.remove(&entry);
+        Some(entry.1)
+    }
+
+    /// Starts an execution, reading the latest version of every account.
+    /// Returns whether it read an estimate and will retry.
+    fn dispatch(&mut self, txn_idx: TxnIndex) -> bool {
+        self.status[txn_idx as usize] = Status::Executing;
+        let mut retry = false;
+        for &account in &self.accounts[txn_idx as usize] {
+            let version = self.writes[account].range(..txn_idx).next_back();
+            retry |= version.is_some_and(|(_, &estimate)| estimate);
+            self.readers[account].insert(txn_idx, version.map(|(&writer, _)| writer));
+        }
+        retry
+    }
+
+    /// Ends an execution: writes, aborts stale readers and commits.
+    fn finish(&mut self, txn_idx: TxnIndex, incarnation: Incarnation, retry: bool) {
+        debug_assert_eq!(self.incarnations[txn_idx as usize], incarnation);
+        if !retry {
+            let mut invalidated = Vec::new();
+            for &account in &self.accounts[txn_idx as usize] {
+                self.writes[account].insert(txn_idx, false);
+                invalidated.extend(
+                    self.readers[account]
+                        .range(txn_idx + 1..)
+                        .filter(|(_, &writer)| writer.map_or(true, |writer| writer <= txn_idx))
+                        .map(|(&reader, _)| reader),
+                );
+            }
+            for reader in invalidated {
+                self.abort(reader);
+            }
+        }
+
+        match self.status[txn_idx as usize] {
+            Status::Executing => self.status[txn_idx as usize] = Status::Executed { retry },
+            Status::Aborting => self.reschedule(txn_idx),
+            _ => {}
+        }
+
+        while self.committed < self.status.len() && self.status[self.committed] == (Status::Executed { retry: false })
+        {
+            self.status[self.committed] = Status::Committed;
+            self.committed += 1;
+        }
+    }
+
+    fn abort(&mut self, txn_idx: TxnIndex) {
+        match self.status[txn_idx as usize] {
+            Status::Executing => self.status[txn_idx as usize] = Status::Aborting,
+            Status::Executed { .. } => self.reschedule(txn_idx),
+            Status::Pending | Status::Aborting | Status::Committed => {}
+        }
+    }
+
+    /// Marks an aborted transaction's writes as estimates and queues its next incarnation.
+    fn reschedule(&mut self, txn_idx: TxnIndex) {
+        for &account in &self.accounts[txn_idx as usize] {
+            if let Some(estimate) = self.writes[account].get_mut(&txn_idx) {
+                *estimate = true;
+            }
+        }
+        self.incarnations[txn_idx as usize] += 1;
+        self.status[txn_idx as usize] = Status::Pending;
+        self.push_ready(txn_idx);
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Signature, B256, U256};
+
+    fn transfer(index: u64, from: Address, to: Address) -> Transaction {
+        Transaction {
+            from,
+            to,
+            value: U256::from(1),
+            token: None,
+            nonce: 0,
+            signature: Signature::new(U256::from(1), U256::from(1), false),
+            tx_hash: B256::with_last_byte(index as u8),
+            compute_cost: 0,
+            dependencies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_policies() {
+        // Independent transfers between distinct pairs
+        let independent: Vec<Transaction> = (0..8u64)
+            .map(|i| transfer(i, Address::repeat_byte(2 * i as u8 + 1), Address::repeat_byte(2 * i as u8 + 2)))
+            .collect();
+        // Every transfer pays the same account
+        let hot = Address::repeat_byte(0xff);
+        let chain: Vec<Transaction> = (0..8u64)
+            .map(|i| transfer(i, Address::repeat_byte(i as u8 + 1), hot))
+            .collect();
+        let costs = vec![10; 8];
+
+        for policy in SchedulingPolicy::ALL {
+            let config = SimulationConfig { num_workers: 4, policy };
+            let report = simulate(&independent, &costs, config);
+            assert_eq!(report.makespan, 20, "{}", policy);
+            assert_eq!(report.executions, 8, "{}", policy);
+            assert_eq!(report.speedup(), 4.0);
+
+            // The chain is serial whatever the policy; only waiting avoids re-executions
+            let report = simulate(&chain, &costs, config);
+            assert_eq!(report.sequential_time, 80);
+            assert!(report.makespan >= 80, "{}", policy);
+            if policy == SchedulingPolicy::DependencyAware {
+                assert_eq!(report.executions, 8);
+                assert_eq!(report.makespan, 80);
+            } else {
+                assert!(report.executions > 8, "{}", policy);
+            }
+        }
+
+        assert_eq!("dependency-aware".parse(), Ok(SchedulingPolicy::DependencyAware));
+        assert!("lifo".parse::<SchedulingPolicy>().is_err());
+    }
+}
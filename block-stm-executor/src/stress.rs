@@ -0,0 +1,229 @@
+//! Correctness stress testing under randomized thread interleaving.
+//!
+//! Block-STM must commit the same state however its workers interleave.
+//! [`stress`] executes one block many times, varying the thread count and
+//! injecting random worker delays through [`Perturbation`], and checks every
+//! run against a single-threaded, unperturbed reference.
+
+use crate::executor::{BlockExecutionResult, ExecutorConfig, ParallelExecutor, Perturbation, Transaction};
+use crate::types::AccountState;
+use alloy_primitives::Address;
+use std::fmt;
+use std::time::Duration;
+
+/// How many runs to make and how hard to perturb them.
+#[derive(Debug, Clone)]
+pub struct StressConfig {
+    /// Number of perturbed runs.
+    pub iterations: usize,
+    /// Thread counts cycled through by successive runs.
+    pub thread_counts: Vec<usize>,
+    /// Probability of a delay each time a worker starts or finishes a transaction.
+    pub delay_probability: f64,
+    /// Longest injected delay.
+    pub max_delay: Duration,
+    /// Base seed; run `i` perturbs with `seed + i`.
+    pub seed: u64,
+}
+
+impl Default for StressConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 200,
+            thread_counts: vec![2, 4, 8, 16],
+            delay_probability: 0.2,
+            max_delay: Duration::from_micros(50),
+            seed: 0,
+        }
+    }
+}
+
+/// Summary of a stress run in which every run matched the reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StressReport {
+    /// Perturbed runs made, excluding the reference.
+    pub runs: usize,
+    /// Executions of the reference run.
+    pub reference_executions: usize,
+    /// Most executions, re-executions included, of any perturbed run.
+    pub max_executions: usize,
+    /// Accounts in the final state.
+    pub final_accounts: usize,
+}
+
+/// A perturbed run whose outcome differs from the reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StressError {
+    /// Zero-based index of the run.
+    pub iteration: usize,
+    pub num_threads: usize,
+    /// Perturbation seed, for replaying the run.
+    pub seed: u64,
+    /// What differed.
+    pub reason: String,
+}
+
+impl fmt::Display for StressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "run {} ({} threads, seed {}) diverged from the reference: {}",
+            self.iteration, self.num_threads, self.seed, self.reason
+        )
+    }
+}
+
+impl std::error::Error for StressError {}
+
+/// Executes `transactions` repeatedly under `stress` and checks that every
+/// run commits the same transactions and final state.
+///
+/// `config` supplies the initial state and every other executor setting;
+/// only `num_threads` is overridden. Returns at the first divergent run.
+pub fn stress(
+    config: &ExecutorConfig,
+    transactions: &[Transaction],
+    stress: &StressConfig,
+) -> Result<StressReport, StressError> {
+    let reference = ParallelExecutor::new(ExecutorConfig {
+        num_threads: 1,
+        ..config.clone()
+    })
+    .execute_block(transactions);
+    let expected_states = sorted_states(&reference);
+
+    let mut max_executions = 0;
+    for iteration in 0..stress.iterations {
+        let num_threads = match stress.thread_counts.as_slice() {
+            [] => config.num_threads,
+            counts => counts[iteration % counts.len()],
+        };
+        let seed = stress.seed.wrapping_add(iteration as u64);
+        let failure = |reason: String| StressError {
+            iteration,
+            num_threads,
+            seed,
+            reason,
+        };
+
+        let result = ParallelExecutor::new(ExecutorConfig {
+            num_threads,
+            ..config.clone()
+        })
+        .with_perturbation(Perturbation {
+            seed,
+            probability: stress.delay_probability,
+            max_delay: stress.max_delay,
+        })
+        .execute_block(transactions);
+        max_executions = max_executions.max(result.total_executions);
+
+        if let Some(index) = (0..reference.tx_success.len().max(result.tx_success.len()))
+            .find(|&index| reference.tx_success.get(index) != result.tx_success.get(index))
+        {
+            return Err(failure(format!(
+                "transaction {} succeeded: expected {:?}, got {:?}",
+                index,
+                reference.tx_success.get(index),
+                result.tx_success.get(index)
+            )));
+        }
+        let states = sorted_states(&result);
+        if states != expected_states {
+            let (address, expected, actual) = first_state_difference(&expected_states, &states);
+            return Err(failure(format!(
+                "account {}: expected {:?}, got {:?}",
+                address, expected, actual
+            )));
+        }
+    }
+
+    Ok(StressReport {
+        runs: stress.iterations,
+        reference_executions: reference.total_executions,
+        max_executions,
+        final_accounts: expected_states.len(),
+    })
+}
+
+fn sorted_states(result: &BlockExecutionResult) -> Vec<(Address, AccountState)> {
+    let mut states = result.final_states.clone();
+    states.sort_by_key(|(address, _)| *address);
+    states
+}
+
+/// First account whose state differs between two sorted state lists.
+fn first_state_difference(
+    expected: &[(Address, AccountState)],
+    actual: &[(Address, AccountState)],
+) -> (Address, Option<AccountState>, Option<AccountState>) {
+    let lookup = |states: &[(Address, AccountState)], address: Address| {
+        states
+            .binary_search_by_key(&address, |(address, _)| *address)
+            .ok()
+            .map(|index| states[index].1.clone())
+    };
+    expected
+        .iter()
+        .chain(actual)
+        .map(|&(address, _)| address)
+        .find(|&address| lookup(expected, address) != lookup(actual, address))
+        .map(|address| (address, lookup(expected, address), lookup(actual, address)))
+        .expect("state lists differ")
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Signature, B256, U256};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_contended_block_is_deterministic() {
+        // Unsigned transfers around a ring of four accounts, so every
+        // transaction conflicts with its neighbours
+        let accounts: Vec<Address> = (1..=4u8).map(Address::repeat_byte).collect();
+        let transactions: Vec<Transaction> = (0..40u64)
+            .map(|index| {
+                let sender = (index % 4) as usize;
+                Transaction {
+                    from: accounts[sender],
+                    to: accounts[(sender + 1) % 4],
+                    value: U256::from(index + 1),
+                    token: None,
+                    nonce: index / 4,
+                    signature: Signature::new(U256::from(1), U256::from(1), false),
+                    tx_hash: B256::with_last_byte(index as u8),
+                    compute_cost: 0,
+                    dependencies: Vec::new(),
+                }
+            })
+            .collect();
+        let config = ExecutorConfig {
+            num_threads: 4,
+            verify_signatures: false,
+            initial_states: accounts
+                .iter()
+                .map(|&address| (address, AccountState::new(0, U256::from(1000))))
+                .collect::<HashMap<_, _>>(),
+            lookahead: None,
+            memory_budget: None,
+            dependency_hints: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+
+        let report = stress(
+            &config,
+            &transactions,
+            &StressConfig {
+                iterations: 20,
+                ..Default::default()
+            },
+        )
+        .expect("every run matches the reference");
+
+        assert_eq!(report.runs, 20);
+        assert_eq!(report.final_accounts, 4);
+        assert!(report.max_executions >= 40);
+    }
+}
@@ -0,0 +1,128 @@
+//! Synchronization primitives shared by the scheduler and multi-version map.
+//!
+//! Normal builds use `parking_lot` locks, `std` atomics and `DashMap`. Built
+//! with `RUSTFLAGS="--cfg loom"`, every primitive comes from `loom` instead,
+//! so the `loom_tests` modules can exhaustively explore the interleavings of
+//! the commit/abort/invalidate protocol on blocks of a few transactions:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --lib loom_tests
+//! ```
+//!
+//! The lock wrappers expose `parking_lot`'s API: `lock`, `read` and `write`
+//! return guards directly and `try_lock` returns an `Option`.
+
+#[cfg(not(loom))]
+pub(crate) use parking_lot::{Mutex, RwLock};
+#[cfg(not(loom))]
+pub(crate) use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+#[cfg(not(loom))]
+pub(crate) use std::sync::Arc;
+
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+#[cfg(loom)]
+pub(crate) use loom::sync::Arc;
+
+/// `loom` mutex behind `parking_lot`'s API.
+#[cfg(loom)]
+pub(crate) struct Mutex<T>(loom::sync::Mutex<T>);
+
+#[cfg(loom)]
+impl<T> Mutex<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self(loom::sync::Mutex::new(value))
+    }
+
+    pub(crate) fn lock(&self) -> loom::sync::MutexGuard<'_, T> {
+        self.0.lock().expect("poisoned mutex")
+    }
+
+    pub(crate) fn try_lock(&self) -> Option<loom::sync::MutexGuard<'_, T>> {
+        self.0.try_lock().ok()
+    }
+}
+
+/// `loom` read-write lock behind `parking_lot`'s API.
+#[cfg(loom)]
+pub(crate) struct RwLock<T>(loom::sync::RwLock<T>);
+
+#[cfg(loom)]
+impl<T> RwLock<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self(loom::sync::RwLock::new(value))
+    }
+
+    pub(crate) fn read(&self) -> loom::sync::RwLockReadGuard<'_, T> {
+        self.0.read().expect("poisoned lock")
+    }
+
+    pub(crate) fn write(&self) -> loom::sync::RwLockWriteGuard<'_, T> {
+        self.0.write().expect("poisoned lock")
+    }
+}
+
+/// Concurrent hash map with per-key locking.
+///
+/// Entries are only reached through closures, so the map never hands out a
+/// guard and the `loom` build can back it with a single mutex.
+#[cfg(not(loom))]
+pub(crate) struct ConcurrentMap<K, V>(dashmap::DashMap<K, V>);
+
+#[cfg(not(loom))]
+impl<K: Eq + std::hash::Hash, V> ConcurrentMap<K, V> {
+    pub(crate) fn new() -> Self {
+        Self(dashmap::DashMap::new())
+    }
+
+    /// Calls `f` with the value at `key`, if any.
+    pub(crate) fn read<R>(&self, key: &K, f: impl FnOnce(&V) -> R) -> Option<R> {
+        self.0.get(key).map(|value| f(&value))
+    }
+
+    /// Calls `f` with the value at `key`, inserting the default first if absent.
+    ///
+    /// The entry stays locked while `f` runs; `f` may lock entries of other
+    /// maps, but never of this one.
+    pub(crate) fn upsert<R>(&self, key: K, f: impl FnOnce(&mut V) -> R) -> R
+    where
+        V: Default,
+    {
+        f(&mut self.0.entry(key).or_default())
+    }
+
+    /// Calls `f` with every entry.
+    pub(crate) fn for_each(&self, mut f: impl FnMut(&K, &V)) {
+        for entry in self.0.iter() {
+            f(entry.key(), entry.value());
+        }
+    }
+}
+
+/// `loom` stand-in for the concurrent map: one mutex over the whole map.
+#[cfg(loom)]
+pub(crate) struct ConcurrentMap<K, V>(Mutex<std::collections::HashMap<K, V>>);
+
+#[cfg(loom)]
+impl<K: Eq + std::hash::Hash, V> ConcurrentMap<K, V> {
+    pub(crate) fn new() -> Self {
+        Self(Mutex::new(std::collections::HashMap::new()))
+    }
+
+    pub(crate) fn read<R>(&self, key: &K, f: impl FnOnce(&V) -> R) -> Option<R> {
+        self.0.lock().get(key).map(f)
+    }
+
+    pub(crate) fn upsert<R>(&self, key: K, f: impl FnOnce(&mut V) -> R) -> R
+    where
+        V: Default,
+    {
+        f(self.0.lock().entry(key).or_default())
+    }
+
+    pub(crate) fn for_each(&self, mut f: impl FnMut(&K, &V)) {
+        for (key, value) in self.0.lock().iter() {
+            f(key, value);
+        }
+    }
+}
@@ -1,10 +1,34 @@
 //! Core types for Block-STM execution.
 
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, B256, KECCAK256_EMPTY, U256};
+use smallvec::SmallVec;
 use std::fmt;
 
 /// Transaction index in the block (0-based).
-pub type TxnIndex = usize;
+///
+/// 32 bits wide, so reader lists and versions take half the memory of
+/// `usize` indices on 64-bit targets; a block holds at most
+/// [`MAX_BLOCK_SIZE`] transactions.
+pub type TxnIndex = u32;
+
+/// Transaction indices collected on the execution hot path: a version's
+/// readers, or the readers a write invalidates.
+///
+/// A transfer touches two accounts, so these rarely hold more than a few
+/// entries; up to four are stored inline, without a heap allocation.
+pub type TxnIndices = SmallVec<[TxnIndex; 4]>;
+
+/// Largest number of transactions in a block, so that every index fits a [`TxnIndex`].
+pub const MAX_BLOCK_SIZE: usize = TxnIndex::MAX as usize;
+
+// Indices widen losslessly to `usize` for indexing
+const _: () = assert!(std::mem::size_of::<TxnIndex>() <= std::mem::size_of::<usize>());
+
+/// Identifies a token balance held next to an account's native balance.
+///
+/// Token ids index [`AccountState::tokens`]; transactions that carry none
+/// move the native balance.
+pub type TokenId = u32;
 
 /// Incarnation number (how many times a transaction has been re-executed).
 pub type Incarnation = usize;
@@ -29,15 +53,88 @@ impl fmt::Display for Version {
 }
 
 /// Account state in the EVM.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AccountState {
     pub nonce: u64,
     pub balance: U256,
+    /// Hash of the account's bytecode; `KECCAK256_EMPTY` for accounts without code.
+    pub code_hash: B256,
+    /// Token balances by [`TokenId`]; tokens past the end have a zero balance.
+    pub tokens: Vec<U256>,
 }
 
 impl AccountState {
+    /// Creates the state of an account without code.
     pub fn new(nonce: u64, balance: U256) -> Self {
-        Self { nonce, balance }
+        Self {
+            nonce,
+            balance,
+            code_hash: KECCAK256_EMPTY,
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Sets the hash of the account's bytecode.
+    pub fn with_code_hash(mut self, code_hash: B256) -> Self {
+        self.code_hash = code_hash;
+        self
+    }
+
+    /// Sets the account's token balances.
+    pub fn with_tokens(mut self, tokens: Vec<U256>) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    /// Whether the account has code.
+    pub fn has_code(&self) -> bool {
+        self.code_hash != KECCAK256_EMPTY
+    }
+
+    /// Balance of `token`, or of the native asset for `None`.
+    pub fn balance_of(&self, token: Option<TokenId>) -> U256 {
+        match token {
+            None => self.balance,
+            Some(token) => self.tokens.get(token as usize).copied().unwrap_or(U256::ZERO),
+        }
+    }
+
+    /// Sets the balance of `token`, or of the native asset for `None`,
+    /// growing the token list as needed.
+    pub fn set_balance_of(&mut self, token: Option<TokenId>, balance: U256) {
+        match token {
+            None => self.balance = balance,
+            Some(token) => {
+                let token = token as usize;
+                if token >= self.tokens.len() {
+                    self.tokens.resize(token + 1, U256::ZERO);
+                }
+                self.tokens[token] = balance;
+            }
+        }
+    }
+
+    /// Debits `value` of `token`, or of the native asset for `None`, and
+    /// bumps the nonce if `bump_nonce`.
+    ///
+    /// Returns `None` if the balance is insufficient or the nonce is already
+    /// `u64::MAX`, instead of wrapping.
+    pub fn checked_debit(mut self, token: Option<TokenId>, value: U256, bump_nonce: bool) -> Option<Self> {
+        let balance = self.balance_of(token).checked_sub(value)?;
+        if bump_nonce {
+            self.nonce = self.nonce.checked_add(1)?;
+        }
+        self.set_balance_of(token, balance);
+        Some(self)
+    }
+
+    /// Credits `value` of `token`, or of the native asset for `None`.
+    ///
+    /// Returns `None` if the balance would overflow, instead of wrapping.
+    pub fn checked_credit(mut self, token: Option<TokenId>, value: U256) -> Option<Self> {
+        let balance = self.balance_of(token).checked_add(value)?;
+        self.set_balance_of(token, balance);
+        Some(self)
     }
 }
 
@@ -81,6 +178,9 @@ pub enum ExecutionStatus {
     Pending,
     /// Currently executing.
     Executing(Incarnation),
+    /// Aborted while executing; rescheduled once the running incarnation
+    /// has finished writing.
+    Aborting(Incarnation),
     /// Finished execution, result available.
     Executed(Incarnation),
     /// Committed to final state.
@@ -0,0 +1,230 @@
+//! Static workload statistics.
+//!
+//! [`WorkloadStats`] describes a workload without executing it: its shape,
+//! how concentrated its traffic is, and how much parallelism its conflicts
+//! allow. The parallelism bound assumes every transaction except injected
+//! replays commits (see [`ConcurrencyStats`]). A second bound counts only
+//! the order sender nonces impose, which is all that
+//! [`nonceless`](crate::WorkloadConfig::nonceless) workloads drop.
+//!
+//! [`declared_dependencies`] derives the same conflicts per transaction, for
+//! executors that schedule on dependencies declared up front rather than
+//! discovered while running.
+
+use crate::executor::{ConcurrencyStats, ExecutionResult};
+use crate::Workload;
+use alloy_primitives::Address;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Shape and contention of one workload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkloadStats {
+    pub num_transactions: usize,
+    pub num_blocks: usize,
+    pub num_accounts: usize,
+    /// Injected duplicates and double-spends.
+    pub num_replays: usize,
+    pub distinct_senders: usize,
+    pub distinct_receivers: usize,
+    /// Share of transactions touching the most frequently touched account.
+    pub hottest_account_share: f64,
+    /// Average number of distinct accounts touched per block.
+    pub avg_accounts_per_block: f64,
+    /// Longest chain of transactions linked through shared accounts.
+    pub max_chain_length: usize,
+    /// Highest average parallelism the workload's conflicts allow.
+    pub workload_bound: f64,
+    /// Longest chain of transactions that sender nonces alone put in order:
+    /// the most transactions from one sender, or 1 for nonce-less workloads.
+    pub nonce_chain_length: usize,
+    /// Highest average parallelism sender nonces alone allow.
+    pub nonce_bound: f64,
+}
+
+impl WorkloadStats {
+    /// Computes statistics for `workload`.
+    pub fn compute(workload: &Workload) -> Self {
+        let mut senders = HashSet::new();
+        let mut receivers = HashSet::new();
+        let mut touches: HashMap<Address, usize> = HashMap::new();
+        for tx in &workload.transactions {
+            senders.insert(tx.from);
+            receivers.insert(tx.to);
+            *touches.entry(tx.from).or_default() += 1;
+            *touches.entry(tx.to).or_default() += 1;
+        }
+
+        let accounts_per_block: usize = workload
+            .blocks
+            .iter()
+            .map(|block| block.iter().flat_map(|tx| [tx.from, tx.to]).collect::<HashSet<_>>().len())
+            .sum();
+
+        let concurrency = ConcurrencyStats::measure(workload, &ExecutionResult::default());
+        let num_transactions = workload.transactions.len();
+
+        let mut sent: HashMap<Address, usize> = HashMap::new();
+        for (tx, replay) in workload.transactions.iter().zip(workload.replay_mask()) {
+            if !replay {
+                *sent.entry(tx.from).or_default() += 1;
+            }
+        }
+        let committed: usize = sent.values().sum();
+        let nonce_chain_length = match sent.values().max() {
+            Some(_) if workload.config.nonceless => 1,
+            Some(&max) => max,
+            None => 0,
+        };
+
+        Self {
+            num_transactions,
+            num_blocks: workload.blocks.len(),
+            num_accounts: workload.accounts.len(),
+            num_replays: workload.num_replays(),
+            distinct_senders: senders.len(),
+            distinct_receivers: receivers.len(),
+            hottest_account_share: match touches.values().max() {
+                Some(&max) => max as f64 / num_transactions as f64,
+                None => 0.0,
+            },
+            avg_accounts_per_block: if workload.blocks.is_empty() {
+                0.0
+            } else {
+                accounts_per_block as f64 / workload.blocks.len() as f64
+            },
+            max_chain_length: concurrency.max_chain_length,
+            workload_bound: concurrency.workload_bound(),
+            nonce_chain_length,
+            nonce_bound: if nonce_chain_length == 0 {
+                0.0
+            } else {
+                committed as f64 / nonce_chain_length as f64
+            },
+        }
+    }
+}
+
+/// For each transaction of `workload`, the latest earlier transaction
+/// touching each account it touches, as ascending indices into
+/// `workload.transactions`.
+///
+/// Transfers read and write both their accounts, so every conflict a run can
+/// discover follows from these through the chain of transactions touching
+/// the shared account. Replays are left out on both sides, as no executor
+/// applies them, and have no dependencies.
+pub fn declared_dependencies(workload: &Workload) -> Vec<Vec<usize>> {
+    let mut last_toucher: HashMap<Address, usize> = HashMap::new();
+    workload
+        .transactions
+        .iter()
+        .zip(workload.replay_mask())
+        .enumerate()
+        .map(|(index, (tx, replay))| {
+            if replay {
+                return Vec::new();
+            }
+            let mut dependencies: Vec<usize> = [tx.from, tx.to]
+                .into_iter()
+                .filter_map(|address| last_toucher.insert(address, index))
+                .collect();
+            dependencies.sort_unstable();
+            dependencies.dedup();
+            dependencies
+        })
+        .collect()
+}
+
+impl fmt::Display for WorkloadStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} txs in {} blocks over {} accounts ({} replays); senders: {}, receivers: {}, \
+             hottest account: {:.1}% of txs, accounts per block: {:.1}, max chain: {} (bound {:.1}x), \
+             nonce chain: {} (bound {:.1}x)",
+            self.num_transactions,
+            self.num_blocks,
+            self.num_accounts,
+            self.num_replays,
+            self.distinct_senders,
+            self.distinct_receivers,
+            self.hottest_account_share * 100.0,
+            self.avg_accounts_per_block,
+            self.max_chain_length,
+            self.workload_bound,
+            self.nonce_chain_length,
+            self.nonce_bound
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_two_hot_accounts_serialize() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 2,
+            fake_signatures: true,
+            ..Default::default()
+        });
+        let stats = WorkloadStats::compute(&workload);
+
+        assert_eq!(stats.num_transactions, 20);
+        assert_eq!(stats.num_blocks, 2);
+        assert_eq!(stats.distinct_senders, 2);
+        assert_eq!(stats.hottest_account_share, 1.0);
+        assert_eq!(stats.avg_accounts_per_block, 2.0);
+        assert_eq!(stats.max_chain_length, 20);
+        assert_eq!(stats.workload_bound, 1.0);
+        assert!(stats.nonce_chain_length > 1);
+    }
+
+    #[test]
+    fn test_nonceless_drops_nonce_chains() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 2,
+            fake_signatures: true,
+            nonceless: true,
+            ..Default::default()
+        });
+        let stats = WorkloadStats::compute(&workload);
+
+        // The two accounts still conflict; only the nonce order is gone
+        assert_eq!(stats.max_chain_length, 20);
+        assert_eq!(stats.nonce_chain_length, 1);
+        assert_eq!(stats.nonce_bound, 20.0);
+    }
+
+    #[test]
+    fn test_declared_dependencies() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 2,
+            fake_signatures: true,
+            double_spends: 2,
+            ..Default::default()
+        });
+        let dependencies = declared_dependencies(&workload);
+        let replays = workload.replay_mask();
+
+        // Two accounts chain every applied transaction to the previous one
+        let (applied, replayed): (Vec<usize>, Vec<usize>) = (0..replays.len()).partition(|&index| !replays[index]);
+        assert!(dependencies[applied[0]].is_empty());
+        for pair in applied.windows(2) {
+            assert_eq!(dependencies[pair[1]], vec![pair[0]]);
+        }
+        assert!(!replayed.is_empty());
+        assert!(replayed.iter().all(|&index| dependencies[index].is_empty()));
+    }
+}
@@ -0,0 +1,84 @@
+//! Background state: accounts no workload transaction touches.
+//!
+//! Persistent backends behave very differently once their B-tree or LSM holds
+//! tens of millions of accounts. [`BackgroundAccounts`] describes such a
+//! population without generating it up front. Backends key accounts by
+//! `keccak256(address)` and untouched accounts are never looked up by
+//! address, so only the hashed keys are synthesized: no signing keys, no
+//! addresses and no hashing. Keys come out in ascending order, so backends
+//! can bulk-load them with sequential appends or contiguous range writes.
+
+use alloy_primitives::{B256, U256};
+use std::ops::Range;
+
+/// Balance of every background account (1 ETH).
+pub const BACKGROUND_BALANCE: u128 = 1_000_000_000_000_000_000;
+
+/// A deterministic population of untouched accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackgroundAccounts {
+    /// Number of accounts.
+    pub count: u64,
+    /// Seed for the key offsets.
+    pub seed: u64,
+}
+
+impl BackgroundAccounts {
+    /// Creates a population of `count` accounts.
+    pub fn new(count: u64, seed: u64) -> Self {
+        Self { count, seed }
+    }
+
+    /// Balance of every account.
+    pub fn balance(&self) -> U256 {
+        U256::from(BACKGROUND_BALANCE)
+    }
+
+    /// Hashed key of the `index`-th account, strictly increasing in `index`.
+    ///
+    /// The key space is split into `count` equal slots and each key sits at a
+    /// seed-dependent offset inside its slot, so keys spread over the whole
+    /// space the way real hashed addresses do.
+    pub fn hashed_key(&self, index: u64) -> B256 {
+        let stride = U256::MAX / U256::from(self.count.max(1));
+        let offset = U256::from(splitmix64(self.seed ^ splitmix64(index)));
+        B256::from(stride * U256::from(index) + offset)
+    }
+
+    /// Hashed keys of the accounts in `range`, in ascending order.
+    pub fn hashed_keys(&self, range: Range<u64>) -> impl Iterator<Item = B256> + '_ {
+        range.map(|index| self.hashed_key(index))
+    }
+
+    /// Splits the population into consecutive index ranges of at most `size`.
+    pub fn chunks(&self, size: u64) -> impl Iterator<Item = Range<u64>> {
+        let (count, size) = (self.count, size.max(1));
+        (0..count.div_ceil(size)).map(move |chunk| chunk * size..((chunk + 1) * size).min(count))
+    }
+}
+
+/// SplitMix64 finalizer: a cheap, well-mixed 64-bit hash.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_ascend_and_depend_on_seed() {
+        let background = BackgroundAccounts::new(1000, 7);
+        let keys: Vec<B256> = background.chunks(300).flat_map(|range| background.hashed_keys(range)).collect();
+
+        assert_eq!(keys.len(), 1000);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(background.hashed_key(42), keys[42]);
+        assert_ne!(BackgroundAccounts::new(1000, 8).hashed_key(42), keys[42]);
+        // Keys cover the whole space rather than clustering at the start
+        assert!(keys[999] > B256::from(U256::MAX / U256::from(2)));
+    }
+}
@@ -0,0 +1,58 @@
+//! Standalone server exposing a state backend to `RemoteDbExecutor` clients.
+
+use clap::{Parser, ValueEnum};
+use db_test::executor::{MemoryBackend, RemoteDbServer};
+use std::net::TcpListener;
+
+/// Backend served by this process.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Backend {
+    /// Plain in-memory hashmap
+    Memory,
+    /// MDBX environment (requires --features mdbx)
+    Mdbx,
+}
+
+/// Remote database server for network-attached storage benchmarks
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Address to listen on
+    #[arg(short = 'l', long, default_value = "127.0.0.1:9000")]
+    listen: String,
+
+    /// Storage backend to serve
+    #[arg(long, value_enum, default_value_t = Backend::Memory)]
+    backend: Backend,
+
+    /// Directory for the MDBX environment (defaults to a temporary directory)
+    #[arg(long)]
+    path: Option<std::path::PathBuf>,
+}
+
+fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+    let listener = TcpListener::bind(&args.listen)?;
+    println!("Serving {:?} backend on {}", args.backend, listener.local_addr()?);
+
+    match args.backend {
+        Backend::Memory => RemoteDbServer::new(MemoryBackend::default()).serve(listener),
+        #[cfg(feature = "mdbx")]
+        Backend::Mdbx => {
+            let tempdir = tempfile::tempdir()?;
+            let path = args.path.unwrap_or_else(|| tempdir.path().to_path_buf());
+            let db = db_test::MdbxDatabase::create(&path)
+                .map_err(|e| std::io::Error::other(e.to_string()))?;
+            for migration in db.migrations() {
+                println!("Migrated MDBX schema to version {}: {}", migration.to, migration.description);
+            }
+            RemoteDbServer::new(db).serve(listener)
+        }
+        #[cfg(not(feature = "mdbx"))]
+        Backend::Mdbx => {
+            let _ = args.path;
+            eprintln!("⚠️  MDBX backend not available (rebuild with --features mdbx)");
+            std::process::exit(1);
+        }
+    }
+}
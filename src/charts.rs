@@ -0,0 +1,322 @@
+//! Line charts of benchmark results.
+//!
+//! [`write_charts`] draws two charts from a [`BenchReport`] with `plotters`:
+//! mean TPS against the number of hot accounts, one line per executor
+//! configuration, and speedup over the sequential baseline against the
+//! thread count, one line per threaded executor and conflict level. Both
+//! come from [`BenchReport::ranking`], so they show the same numbers as the
+//! ranking table.
+
+use crate::harness::{BenchReport, BASELINE_EXECUTOR};
+use plotters::coord::ranged1d::{DefaultFormatting, Ranged, ValueFormatter};
+use plotters::coord::types::RangedCoordf64;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const CHART_SIZE: (u32, u32) = (1024, 640);
+
+/// Image format of the written charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    Svg,
+    Png,
+}
+
+impl ChartFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+        }
+    }
+}
+
+impl FromStr for ChartFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "svg" => Ok(Self::Svg),
+            "png" => Ok(Self::Png),
+            _ => Err(format!("unknown chart format '{}' (expected svg or png)", s)),
+        }
+    }
+}
+
+/// One line of a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub label: String,
+    /// Points in increasing x order.
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Mean TPS per hot-account count, one series per executor configuration.
+///
+/// Conflict levels whose label does not start with a number are skipped.
+pub fn tps_vs_conflict(report: &BenchReport) -> Vec<Series> {
+    let mut series: Vec<Series> = Vec::new();
+    for ranking in report.ranking(BASELINE_EXECUTOR) {
+        let Some(hot_accounts) = leading_number(&ranking.hot_accounts_label) else {
+            continue;
+        };
+        for executor in ranking.executors {
+            push_point(&mut series, executor.label, (hot_accounts, executor.avg_tps));
+        }
+    }
+    sort_points(&mut series);
+    series
+}
+
+/// Speedup over the baseline per thread count, one series per threaded
+/// executor and conflict level.
+///
+/// Only cells grouped by thread count, at conflict levels where the baseline
+/// ran, are included.
+pub fn speedup_vs_threads(report: &BenchReport) -> Vec<Series> {
+    let mut series: Vec<Series> = Vec::new();
+    for ranking in report.ranking(BASELINE_EXECUTOR) {
+        for executor in ranking.executors {
+            let Some(speedup) = executor.speedup else {
+                continue;
+            };
+            // Ranking labels are "name (group)"; threaded groups are "N threads"
+            let Some((name, threads)) = executor
+                .label
+                .strip_suffix(" threads)")
+                .and_then(|label| label.rsplit_once(" ("))
+            else {
+                continue;
+            };
+            let Ok(threads) = threads.parse::<f64>() else {
+                continue;
+            };
+            let label = format!("{} @ {}", name, ranking.hot_accounts_label);
+            push_point(&mut series, label, (threads, speedup));
+        }
+    }
+    sort_points(&mut series);
+    series
+}
+
+/// Writes the TPS-vs-conflict and speedup-vs-threads charts of `report`
+/// as `{prefix}-tps-vs-conflict.{ext}` and `{prefix}-speedup-vs-threads.{ext}`,
+/// returning the paths written. A chart with no data is not written.
+pub fn write_charts(report: &BenchReport, prefix: &Path, format: ChartFormat) -> io::Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+
+    let tps = tps_vs_conflict(report);
+    if !tps.is_empty() {
+        let path = chart_path(prefix, "tps-vs-conflict", format);
+        let chart = Chart {
+            title: "Throughput vs conflict",
+            x_desc: "Hot accounts",
+            y_desc: "TPS",
+            log_x: true,
+            series: &tps,
+        };
+        draw_to(&path, format, &chart)?;
+        written.push(path);
+    }
+
+    let speedup = speedup_vs_threads(report);
+    if !speedup.is_empty() {
+        let path = chart_path(prefix, "speedup-vs-threads", format);
+        let chart = Chart {
+            title: "Speedup over sequential vs threads",
+            x_desc: "Threads",
+            y_desc: "Speedup (x)",
+            log_x: false,
+            series: &speedup,
+        };
+        draw_to(&path, format, &chart)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+struct Chart<'a> {
+    title: &'a str,
+    x_desc: &'a str,
+    y_desc: &'a str,
+    /// Hot-account counts span orders of magnitude; thread counts do not.
+    log_x: bool,
+    series: &'a [Series],
+}
+
+fn chart_path(prefix: &Path, name: &str, format: ChartFormat) -> PathBuf {
+    let mut file_name = prefix.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!("-{}.{}", name, format.extension()));
+    prefix.with_file_name(file_name)
+}
+
+fn draw_to(path: &Path, format: ChartFormat, chart: &Chart<'_>) -> io::Result<()> {
+    match format {
+        ChartFormat::Svg => draw(SVGBackend::new(path, CHART_SIZE).into_drawing_area(), chart),
+        ChartFormat::Png => draw(BitMapBackend::new(path, CHART_SIZE).into_drawing_area(), chart),
+    }
+}
+
+fn draw<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, chart: &Chart<'_>) -> io::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let points = || chart.series.iter().flat_map(|series| series.points.iter());
+    let x_min = points().map(|&(x, _)| x).fold(f64::INFINITY, f64::min);
+    let x_max = points().map(|&(x, _)| x).fold(f64::NEG_INFINITY, f64::max);
+    let y_max = points().map(|&(_, y)| y).fold(0.0, f64::max);
+    let x_range = padded(x_min, x_max);
+    let y_range = 0.0..y_max * 1.1 + f64::EPSILON;
+
+    root.fill(&WHITE).map_err(chart_error)?;
+    let mut builder = ChartBuilder::on(&root);
+    builder
+        .caption(chart.title, ("sans-serif", 24))
+        .margin(16)
+        .x_label_area_size(40)
+        .y_label_area_size(70);
+
+    if chart.log_x {
+        let context = builder
+            .build_cartesian_2d(x_range.log_scale(), y_range)
+            .map_err(chart_error)?;
+        draw_lines(context, chart)?;
+    } else {
+        let context = builder.build_cartesian_2d(x_range, y_range).map_err(chart_error)?;
+        draw_lines(context, chart)?;
+    }
+
+    root.present().map_err(chart_error)
+}
+
+/// Draws the mesh, one line per series and the legend.
+fn draw_lines<DB, X>(mut context: ChartContext<'_, DB, Cartesian2d<X, RangedCoordf64>>, chart: &Chart<'_>) -> io::Result<()>
+where
+    DB: DrawingBackend,
+    DB::ErrorType: 'static,
+    X: Ranged<ValueType = f64, FormatOption = DefaultFormatting> + ValueFormatter<f64>,
+{
+    context
+        .configure_mesh()
+        .x_desc(chart.x_desc)
+        .y_desc(chart.y_desc)
+        .draw()
+        .map_err(chart_error)?;
+    for (index, series) in chart.series.iter().enumerate() {
+        let color = Palette99::pick(index).to_rgba();
+        context
+            .draw_series(LineSeries::new(series.points.iter().copied(), color.stroke_width(2)).point_size(3))
+            .map_err(chart_error)?
+            .label(series.label.as_str())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 16, y)], color.stroke_width(2)));
+    }
+    context
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(chart_error)
+}
+
+fn chart_error<E: std::error::Error + Send + Sync>(err: DrawingAreaErrorKind<E>) -> io::Error {
+    io::Error::other(format!("failed to draw chart: {}", err))
+}
+
+/// Widens a single-point range so it has something to span.
+fn padded(min: f64, max: f64) -> Range<f64> {
+    if min < max {
+        min..max
+    } else {
+        min * 0.5..max * 1.5 + 1.0
+    }
+}
+
+fn leading_number(label: &str) -> Option<f64> {
+    label.split_whitespace().next()?.parse().ok()
+}
+
+fn push_point(series: &mut Vec<Series>, label: String, point: (f64, f64)) {
+    match series.iter_mut().find(|series| series.label == label) {
+        Some(series) => series.points.push(point),
+        None => series.push(Series {
+            label,
+            points: vec![point],
+        }),
+    }
+}
+
+fn sort_points(series: &mut [Series]) {
+    for series in series {
+        series.points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::{BenchResult, CellReport};
+
+    fn cell(executor_name: &str, group: Option<&str>, hot_accounts: usize, throughput_tps: f64) -> CellReport {
+        CellReport {
+            index: 0,
+            section: "Section".to_string(),
+            group: group.map(str::to_string),
+            repetition: 0,
+            result: BenchResult {
+                hot_accounts_label: format!("{} accounts", hot_accounts),
+                executor_name: executor_name.to_string(),
+                preserves_order: true,
+                successful: 10,
+                failed: 0,
+                duration_ms: 1.0,
+                throughput_tps,
+            },
+            details: Vec::new(),
+            frequency: None,
+            unstable: false,
+        }
+    }
+
+    #[test]
+    fn test_series_and_files() {
+        let report = BenchReport {
+            cells: vec![
+                cell(BASELINE_EXECUTOR, None, 1000, 100.0),
+                cell(BASELINE_EXECUTOR, None, 2, 100.0),
+                cell("parallel", Some("4 threads"), 1000, 400.0),
+                cell("parallel", Some("2 threads"), 1000, 200.0),
+                cell("parallel", Some("2 threads"), 2, 50.0),
+            ],
+            frequency_threshold: None,
+        };
+
+        let tps = tps_vs_conflict(&report);
+        let baseline = tps.iter().find(|series| series.label == BASELINE_EXECUTOR).unwrap();
+        assert_eq!(baseline.points, vec![(2.0, 100.0), (1000.0, 100.0)]);
+
+        let speedup = speedup_vs_threads(&report);
+        assert_eq!(speedup.len(), 2);
+        assert_eq!(speedup[0].label, "parallel @ 1000 accounts");
+        assert_eq!(speedup[0].points, vec![(2.0, 2.0), (4.0, 4.0)]);
+        assert_eq!(speedup[1].points, vec![(2.0, 0.5)]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let written = write_charts(&report, &dir.path().join("run"), ChartFormat::Svg).unwrap();
+        assert_eq!(
+            written,
+            vec![
+                dir.path().join("run-tps-vs-conflict.svg"),
+                dir.path().join("run-speedup-vs-threads.svg")
+            ]
+        );
+        assert!(written.iter().all(|path| path.exists()));
+        assert!(write_charts(&BenchReport::default(), &dir.path().join("empty"), ChartFormat::Svg)
+            .unwrap()
+            .is_empty());
+    }
+}
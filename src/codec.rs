@@ -0,0 +1,664 @@
+//! Account value encodings for key-value backends.
+//!
+//! KV backends that store raw bytes (FoundationDB, and any future byte-oriented
+//! store) encode each account's nonce, balance and code hash through an
+//! [`AccountCodec`]. The codecs trade value size for encode/decode cost:
+//!
+//! - [`RawCodec`]: fixed 40 bytes, 8-byte nonce and 32-byte balance, big-endian
+//! - [`RlpCodec`]: Ethereum RLP list `[nonce, balance]`
+//! - [`CompactCodec`]: reth-style 2-byte length header followed by
+//!   leading-zero-stripped nonce and balance
+//! - [`VarintCodec`]: LEB128 nonce followed by LEB128 balance
+//!
+//! Every codec has three value formats. Version 1, above, predates contract
+//! code and holds only nonce and balance; version 2 adds the 32-byte code
+//! hash, marked the way each codec allows (see its docs); version 3 adds
+//! the account's token balances. Accounts are written in the oldest format
+//! that holds them, so values stored before code or token support still
+//! decode, and plain native transfers cost no extra bytes.
+
+use alloy_primitives::{B256, KECCAK256_EMPTY, U256};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// An account value as stored by a KV backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountValue {
+    pub nonce: u64,
+    pub balance: U256,
+    /// Hash of the account's bytecode; `KECCAK256_EMPTY` for accounts without code.
+    pub code_hash: B256,
+    /// Token balances by token id; tokens past the end have a zero balance.
+    pub tokens: Vec<U256>,
+}
+
+impl AccountValue {
+    /// Creates the value of an account without code.
+    pub fn new(nonce: u64, balance: U256) -> Self {
+        Self {
+            nonce,
+            balance,
+            code_hash: KECCAK256_EMPTY,
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Sets the hash of the account's bytecode.
+    pub fn with_code_hash(mut self, code_hash: B256) -> Self {
+        self.code_hash = code_hash;
+        self
+    }
+
+    /// Sets the account's token balances.
+    pub fn with_tokens(mut self, tokens: Vec<U256>) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    /// Whether the account has code, and so needs at least the version 2 format.
+    pub fn has_code(&self) -> bool {
+        self.code_hash != KECCAK256_EMPTY
+    }
+
+    /// Whether the account holds token balances, and so needs the version 3 format.
+    pub fn has_tokens(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// Balance of `token`, or of the native asset for `None`.
+    pub fn balance_of(&self, token: Option<u32>) -> U256 {
+        match token {
+            None => self.balance,
+            Some(token) => self.tokens.get(token as usize).copied().unwrap_or(U256::ZERO),
+        }
+    }
+
+    /// Sets the balance of `token`, or of the native asset for `None`,
+    /// growing the token list as needed.
+    pub fn set_balance_of(&mut self, token: Option<u32>, balance: U256) {
+        match token {
+            None => self.balance = balance,
+            Some(token) => {
+                let token = token as usize;
+                if token >= self.tokens.len() {
+                    self.tokens.resize(token + 1, U256::ZERO);
+                }
+                self.tokens[token] = balance;
+            }
+        }
+    }
+
+    /// Debits `value` of `token`, or of the native asset for `None`, and
+    /// bumps the nonce if `bump_nonce` is set.
+    ///
+    /// Returns `None` if the balance is insufficient or the nonce is already
+    /// `u64::MAX`, instead of wrapping.
+    pub fn checked_debit(mut self, token: Option<u32>, value: U256, bump_nonce: bool) -> Option<Self> {
+        let balance = self.balance_of(token).checked_sub(value)?;
+        if bump_nonce {
+            self.nonce = self.nonce.checked_add(1)?;
+        }
+        self.set_balance_of(token, balance);
+        Some(self)
+    }
+
+    /// Credits `value` of `token`, or of the native asset for `None`.
+    ///
+    /// Returns `None` if the balance would overflow, instead of wrapping.
+    pub fn checked_credit(mut self, token: Option<u32>, value: U256) -> Option<Self> {
+        let balance = self.balance_of(token).checked_add(value)?;
+        self.set_balance_of(token, balance);
+        Some(self)
+    }
+}
+
+/// Encodes and decodes an [`AccountValue`] to and from bytes.
+pub trait AccountCodec: Send + Sync {
+    /// Short name used on the command line and in reports.
+    fn name(&self) -> &'static str;
+
+    /// Encodes an account, as version 3 if it has tokens, otherwise as
+    /// version 2 only if it has code.
+    fn encode(&self, account: &AccountValue) -> Vec<u8>;
+
+    /// Decodes an account in either format, returning `None` if `data` is malformed.
+    fn decode(&self, data: &[u8]) -> Option<AccountValue>;
+}
+
+/// Every built-in codec, in reporting order.
+pub const ALL_CODECS: [&dyn AccountCodec; 4] = [&RawCodec, &RlpCodec, &CompactCodec, &VarintCodec];
+
+/// Looks up a built-in codec by its [`name`](AccountCodec::name).
+pub fn codec_by_name(name: &str) -> Option<&'static dyn AccountCodec> {
+    ALL_CODECS.into_iter().find(|codec| codec.name() == name)
+}
+
+/// Size and speed of a codec over a set of account values.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CodecReport {
+    /// Codec name.
+    pub name: &'static str,
+    /// Number of accounts measured.
+    pub accounts: usize,
+    /// Total encoded size in bytes.
+    pub total_bytes: usize,
+    /// Time spent encoding every account once.
+    pub encode_time: Duration,
+    /// Time spent decoding every account once.
+    pub decode_time: Duration,
+}
+
+impl CodecReport {
+    /// Encodes and decodes every account once, recording sizes and timings.
+    ///
+    /// Panics if an account does not roundtrip.
+    pub fn measure(codec: &dyn AccountCodec, accounts: &[AccountValue]) -> Self {
+        let start = Instant::now();
+        let encoded: Vec<Vec<u8>> = accounts.iter().map(|account| codec.encode(account)).collect();
+        let encode_time = start.elapsed();
+
+        let start = Instant::now();
+        for (data, expected) in encoded.iter().zip(accounts) {
+            let decoded = black_box(codec.decode(data));
+            assert_eq!(decoded.as_ref(), Some(expected), "{} failed to roundtrip", codec.name());
+        }
+        let decode_time = start.elapsed();
+
+        Self {
+            name: codec.name(),
+            accounts: accounts.len(),
+            total_bytes: encoded.iter().map(Vec::len).sum(),
+            encode_time,
+            decode_time,
+        }
+    }
+
+    /// Mean encoded value size in bytes.
+    pub fn avg_value_size(&self) -> f64 {
+        if self.accounts == 0 {
+            0.0
+        } else {
+            self.total_bytes as f64 / self.accounts as f64
+        }
+    }
+
+    /// Encodes per second.
+    pub fn encode_throughput(&self) -> f64 {
+        self.accounts as f64 / self.encode_time.as_secs_f64()
+    }
+
+    /// Decodes per second.
+    pub fn decode_throughput(&self) -> f64 {
+        self.accounts as f64 / self.decode_time.as_secs_f64()
+    }
+}
+
+/// Returns `bytes` without leading zero bytes.
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+/// Parses a big-endian nonce of at most 8 bytes.
+fn nonce_from_be(bytes: &[u8]) -> Option<u64> {
+    if bytes.len() > 8 {
+        return None;
+    }
+    let mut buf = [0u8; 8];
+    buf[8 - bytes.len()..].copy_from_slice(bytes);
+    Some(u64::from_be_bytes(buf))
+}
+
+/// Builds an account from decoded fields, the code hash absent in version 1
+/// and the tokens empty before version 3.
+fn account_value(nonce: u64, balance: U256, code_hash: Option<&[u8]>, tokens: Vec<U256>) -> Option<AccountValue> {
+    let account = AccountValue::new(nonce, balance).with_tokens(tokens);
+    match code_hash {
+        None => Some(account),
+        Some(code_hash) => Some(account.with_code_hash(B256::try_from(code_hash).ok()?)),
+    }
+}
+
+/// Fixed-width encoding: 8-byte nonce followed by 32-byte balance, both big-endian.
+///
+/// Version 2 appends the code hash, and version 3 the code hash followed by
+/// a 32-byte big-endian balance per token, so the value length tells the
+/// formats apart: 40 bytes for version 1, 72 for version 2 and 72 plus 32
+/// per token for version 3.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RawCodec;
+
+impl AccountCodec for RawCodec {
+    fn name(&self) -> &'static str {
+        "raw"
+    }
+
+    fn encode(&self, account: &AccountValue) -> Vec<u8> {
+        let mut data = Vec::with_capacity(72 + 32 * account.tokens.len());
+        data.extend_from_slice(&account.nonce.to_be_bytes());
+        data.extend_from_slice(&account.balance.to_be_bytes::<32>());
+        if account.has_code() || account.has_tokens() {
+            data.extend_from_slice(account.code_hash.as_slice());
+        }
+        for token in &account.tokens {
+            data.extend_from_slice(&token.to_be_bytes::<32>());
+        }
+        data
+    }
+
+    fn decode(&self, data: &[u8]) -> Option<AccountValue> {
+        let code_hash = match data.len() {
+            40 => None,
+            len if len >= 72 && (len - 72) % 32 == 0 => Some(&data[40..72]),
+            _ => return None,
+        };
+        let nonce = u64::from_be_bytes(data[0..8].try_into().ok()?);
+        let balance = U256::from_be_slice(&data[8..40]);
+        let tokens = data.get(72..).unwrap_or_default().chunks(32).map(U256::from_be_slice).collect();
+        account_value(nonce, balance, code_hash, tokens)
+    }
+}
+
+/// RLP encoding of the list `[nonce, balance]`.
+///
+/// Version 2 is the list `[nonce, balance, code_hash]`, and version 3
+/// `[nonce, balance, code_hash, [token, ...]]`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RlpCodec;
+
+impl RlpCodec {
+    fn encode_integer(out: &mut Vec<u8>, be_bytes: &[u8]) {
+        let bytes = strip_leading_zeros(be_bytes);
+        match bytes {
+            [] => out.push(0x80),
+            [b] if *b < 0x80 => out.push(*b),
+            _ => {
+                out.push(0x80 + bytes.len() as u8);
+                out.extend_from_slice(bytes);
+            }
+        }
+    }
+
+    /// Writes a string (`offset` 0x80) or list (`offset` 0xc0) header for a
+    /// payload of `len` bytes.
+    fn encode_header(out: &mut Vec<u8>, offset: u8, len: usize) {
+        if len < 56 {
+            out.push(offset + len as u8);
+        } else {
+            let len_bytes = len.to_be_bytes();
+            let len_bytes = strip_leading_zeros(&len_bytes);
+            out.push(offset + 55 + len_bytes.len() as u8);
+            out.extend_from_slice(len_bytes);
+        }
+    }
+
+    /// Decodes one list header, returning the list's payload and the rest of the input.
+    fn decode_list(data: &[u8]) -> Option<(&[u8], &[u8])> {
+        let (&prefix, rest) = data.split_first()?;
+        let (len, rest) = match prefix {
+            0xc0..=0xf7 => ((prefix - 0xc0) as usize, rest),
+            0xf8..=0xff => {
+                let len_len = (prefix - 0xf7) as usize;
+                if rest.len() < len_len {
+                    return None;
+                }
+                let (len, rest) = rest.split_at(len_len);
+                let len = usize::try_from(nonce_from_be(len)?).ok()?;
+                if len < 56 {
+                    return None;
+                }
+                (len, rest)
+            }
+            _ => return None,
+        };
+        (rest.len() >= len).then(|| rest.split_at(len))
+    }
+
+    /// Decodes one integer item, returning its big-endian bytes and the rest of the input.
+    fn decode_integer(data: &[u8]) -> Option<(&[u8], &[u8])> {
+        let (&prefix, rest) = data.split_first()?;
+        match prefix {
+            0x00..=0x7f => Some((&data[..1], rest)),
+            0x80..=0xa0 => {
+                let len = (prefix - 0x80) as usize;
+                (rest.len() >= len).then(|| rest.split_at(len))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl AccountCodec for RlpCodec {
+    fn name(&self) -> &'static str {
+        "rlp"
+    }
+
+    fn encode(&self, account: &AccountValue) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(75 + 33 * account.tokens.len());
+        Self::encode_integer(&mut payload, &account.nonce.to_be_bytes());
+        Self::encode_integer(&mut payload, &account.balance.to_be_bytes::<32>());
+        if account.has_code() || account.has_tokens() {
+            payload.push(0xa0);
+            payload.extend_from_slice(account.code_hash.as_slice());
+        }
+        if account.has_tokens() {
+            let mut tokens = Vec::with_capacity(33 * account.tokens.len());
+            for token in &account.tokens {
+                Self::encode_integer(&mut tokens, &token.to_be_bytes::<32>());
+            }
+            Self::encode_header(&mut payload, 0xc0, tokens.len());
+            payload.extend_from_slice(&tokens);
+        }
+
+        let mut data = Vec::with_capacity(payload.len() + 9);
+        Self::encode_header(&mut data, 0xc0, payload.len());
+        data.extend_from_slice(&payload);
+        data
+    }
+
+    fn decode(&self, data: &[u8]) -> Option<AccountValue> {
+        let (payload, rest) = Self::decode_list(data)?;
+        if !rest.is_empty() {
+            return None;
+        }
+
+        let (nonce, rest) = Self::decode_integer(payload)?;
+        let (balance, rest) = Self::decode_integer(rest)?;
+        let (code_hash, rest) = match rest {
+            [] => (None, rest),
+            [0xa0, rest @ ..] if rest.len() >= 32 => {
+                let (code_hash, rest) = rest.split_at(32);
+                (Some(code_hash), rest)
+            }
+            _ => return None,
+        };
+        let mut tokens = Vec::new();
+        if !rest.is_empty() {
+            let (mut items, rest) = Self::decode_list(rest)?;
+            if !rest.is_empty() {
+                return None;
+            }
+            while !items.is_empty() {
+                let (token, rest) = Self::decode_integer(items)?;
+                tokens.push(U256::try_from_be_slice(token)?);
+                items = rest;
+            }
+        }
+
+        account_value(nonce_from_be(nonce)?, U256::try_from_be_slice(balance)?, code_hash, tokens)
+    }
+}
+
+/// reth-style compact encoding: a 2-byte header holding the nonce length
+/// (bits 0-3) and balance length (bits 4-9), followed by both values with
+/// leading zero bytes stripped.
+///
+/// Version 2 sets header bit 10 and appends the code hash, like reth's flag
+/// for an optional bytecode hash. Version 3 sets header bit 11 and appends,
+/// after any code hash, a 2-byte token count followed by each token balance
+/// as a length byte and its leading-zero-stripped bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompactCodec;
+
+impl CompactCodec {
+    /// Header bit marking a trailing code hash.
+    const CODE_HASH_FLAG: u16 = 1 << 10;
+    /// Header bit marking trailing token balances.
+    const TOKENS_FLAG: u16 = 1 << 11;
+}
+
+impl AccountCodec for CompactCodec {
+    fn name(&self) -> &'static str {
+        "compact"
+    }
+
+    fn encode(&self, account: &AccountValue) -> Vec<u8> {
+        let nonce_bytes = account.nonce.to_be_bytes();
+        let balance_bytes = account.balance.to_be_bytes::<32>();
+        let nonce = strip_leading_zeros(&nonce_bytes);
+        let balance = strip_leading_zeros(&balance_bytes);
+
+        let mut header = nonce.len() as u16 | ((balance.len() as u16) << 4);
+        if account.has_code() {
+            header |= Self::CODE_HASH_FLAG;
+        }
+        if account.has_tokens() {
+            header |= Self::TOKENS_FLAG;
+        }
+        let mut data = Vec::with_capacity(2 + nonce.len() + balance.len() + 32 + 2 + 33 * account.tokens.len());
+        data.extend_from_slice(&header.to_be_bytes());
+        data.extend_from_slice(nonce);
+        data.extend_from_slice(balance);
+        if account.has_code() {
+            data.extend_from_slice(account.code_hash.as_slice());
+        }
+        if account.has_tokens() {
+            let count = u16::try_from(account.tokens.len()).expect("at most 65535 tokens per account");
+            data.extend_from_slice(&count.to_be_bytes());
+            for token in &account.tokens {
+                let token_bytes = token.to_be_bytes::<32>();
+                let token = strip_leading_zeros(&token_bytes);
+                data.push(token.len() as u8);
+                data.extend_from_slice(token);
+            }
+        }
+        data
+    }
+
+    fn decode(&self, data: &[u8]) -> Option<AccountValue> {
+        if data.len() < 2 {
+            return None;
+        }
+        let header = u16::from_be_bytes([data[0], data[1]]);
+        if header & !(Self::CODE_HASH_FLAG | Self::TOKENS_FLAG | 0x3ff) != 0 {
+            return None;
+        }
+        let nonce_len = (header & 0x0f) as usize;
+        let balance_len = ((header >> 4) & 0x3f) as usize;
+        let code_hash_len = if header & Self::CODE_HASH_FLAG != 0 { 32 } else { 0 };
+        let has_tokens = header & Self::TOKENS_FLAG != 0;
+        let body = &data[2..];
+        let fixed_len = nonce_len + balance_len + code_hash_len;
+        if body.len() < fixed_len || (!has_tokens && body.len() != fixed_len) {
+            return None;
+        }
+
+        let (nonce, rest) = body.split_at(nonce_len);
+        let (balance, rest) = rest.split_at(balance_len);
+        let (code_hash, mut rest) = rest.split_at(code_hash_len);
+        let mut tokens = Vec::new();
+        if has_tokens {
+            let [count_hi, count_lo, tail @ ..] = rest else {
+                return None;
+            };
+            let count = u16::from_be_bytes([*count_hi, *count_lo]) as usize;
+            rest = tail;
+            tokens.reserve(count);
+            for _ in 0..count {
+                let (&len, tail) = rest.split_first()?;
+                if tail.len() < len as usize {
+                    return None;
+                }
+                let (token, tail) = tail.split_at(len as usize);
+                tokens.push(U256::try_from_be_slice(token)?);
+                rest = tail;
+            }
+            if !rest.is_empty() {
+                return None;
+            }
+        }
+        account_value(
+            nonce_from_be(nonce)?,
+            U256::try_from_be_slice(balance)?,
+            (code_hash_len > 0).then_some(code_hash),
+            tokens,
+        )
+    }
+}
+
+/// LEB128 varint encoding of the nonce followed by the balance.
+///
+/// Version 2 appends the raw code hash after the balance varint, and version
+/// 3 the code hash followed by a varint token count and a varint per token.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VarintCodec;
+
+impl VarintCodec {
+    fn encode_varint(out: &mut Vec<u8>, mut value: U256) {
+        loop {
+            let byte = (value.as_limbs()[0] & 0x7f) as u8;
+            value >>= 7;
+            if value.is_zero() {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    /// Decodes one varint, returning it and the rest of the input.
+    fn decode_varint(data: &[u8]) -> Option<(U256, &[u8])> {
+        let mut value = U256::ZERO;
+        for (i, &byte) in data.iter().enumerate() {
+            let shift = 7 * i;
+            if shift >= 256 {
+                return None;
+            }
+            value |= U256::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some((value, &data[i + 1..]));
+            }
+        }
+        None
+    }
+}
+
+impl AccountCodec for VarintCodec {
+    fn name(&self) -> &'static str {
+        "varint"
+    }
+
+    fn encode(&self, account: &AccountValue) -> Vec<u8> {
+        let mut data = Vec::with_capacity(48 + 10 * account.tokens.len());
+        Self::encode_varint(&mut data, U256::from(account.nonce));
+        Self::encode_varint(&mut data, account.balance);
+        if account.has_code() || account.has_tokens() {
+            data.extend_from_slice(account.code_hash.as_slice());
+        }
+        if account.has_tokens() {
+            Self::encode_varint(&mut data, U256::from(account.tokens.len()));
+            for &token in &account.tokens {
+                Self::encode_varint(&mut data, token);
+            }
+        }
+        data
+    }
+
+    fn decode(&self, data: &[u8]) -> Option<AccountValue> {
+        let (nonce, rest) = Self::decode_varint(data)?;
+        let (balance, rest) = Self::decode_varint(rest)?;
+        let (code_hash, mut rest) = match rest.len() {
+            0 => (None, rest),
+            32.. => {
+                let (code_hash, rest) = rest.split_at(32);
+                (Some(code_hash), rest)
+            }
+            _ => return None,
+        };
+        let mut tokens = Vec::new();
+        if !rest.is_empty() {
+            let (count, tail) = Self::decode_varint(rest)?;
+            rest = tail;
+            for _ in 0..usize::try_from(count).ok()? {
+                let (token, tail) = Self::decode_varint(rest)?;
+                tokens.push(token);
+                rest = tail;
+            }
+            if !rest.is_empty() {
+                return None;
+            }
+        }
+        account_value(u64::try_from(nonce).ok()?, balance, code_hash, tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codecs_roundtrip() {
+        let code_hash = B256::repeat_byte(0xc0);
+        let cases = [
+            AccountValue::new(0, U256::ZERO),
+            AccountValue::new(1, U256::from(0x7f)),
+            AccountValue::new(127, U256::from(0x80)),
+            AccountValue::new(1_000, U256::from(1_000_000_000_000_000_000_000u128)),
+            AccountValue::new(u64::MAX, U256::MAX),
+            AccountValue::new(0, U256::ZERO).with_code_hash(code_hash),
+            AccountValue::new(u64::MAX, U256::MAX).with_code_hash(code_hash),
+            AccountValue::new(3, U256::from(1_000)).with_tokens(vec![U256::ZERO, U256::from(0x80), U256::MAX]),
+            AccountValue::new(3, U256::from(1_000)).with_code_hash(code_hash).with_tokens(vec![U256::from(1); 64]),
+        ];
+
+        for codec in ALL_CODECS {
+            for account in &cases {
+                let encoded = codec.encode(account);
+                assert_eq!(
+                    codec.decode(&encoded).as_ref(),
+                    Some(account),
+                    "{} failed to roundtrip {:?}",
+                    codec.name(),
+                    account
+                );
+            }
+            assert_eq!(codec_by_name(codec.name()).unwrap().name(), codec.name());
+        }
+
+        assert!(codec_by_name("bogus").is_none());
+    }
+
+    #[test]
+    fn test_codec_sizes() {
+        let account = AccountValue::new(0, U256::from(1_000_000_000_000_000_000_000u128)); // 1000 ETH
+
+        assert_eq!(RawCodec.encode(&account).len(), 40);
+        assert_eq!(RlpCodec.encode(&account).len(), 12);
+        assert_eq!(CompactCodec.encode(&account).len(), 11);
+        assert_eq!(VarintCodec.encode(&account).len(), 11);
+
+        // Version 2 costs the code hash, plus the RLP string prefix.
+        let contract = account.with_code_hash(B256::repeat_byte(0xc0));
+        assert_eq!(RawCodec.encode(&contract).len(), 72);
+        assert_eq!(RlpCodec.encode(&contract).len(), 45);
+        assert_eq!(CompactCodec.encode(&contract).len(), 43);
+        assert_eq!(VarintCodec.encode(&contract).len(), 43);
+
+        // Version 3 costs the code hash and each token balance, plus framing.
+        let holder = AccountValue::new(0, U256::from(1_000_000_000_000_000_000_000u128))
+            .with_tokens(vec![U256::from(1_000_000u64); 2]);
+        assert_eq!(RawCodec.encode(&holder).len(), 136);
+        assert_eq!(RlpCodec.encode(&holder).len(), 54);
+        assert_eq!(CompactCodec.encode(&holder).len(), 21);
+        assert_eq!(VarintCodec.encode(&holder).len(), 50);
+
+        // Malformed input is rejected rather than misread.
+        assert!(RawCodec.decode(&[0; 39]).is_none());
+        assert!(RlpCodec.decode(&[0xc2, 0x80]).is_none());
+        assert!(CompactCodec.decode(&[0x00, 0x01]).is_none());
+        assert!(VarintCodec.decode(&[0x80]).is_none());
+    }
+
+    #[test]
+    fn test_codec_report() {
+        let accounts: Vec<_> = (0..100u64).map(|i| AccountValue::new(i, U256::from(i * 1_000))).collect();
+
+        let raw = CodecReport::measure(&RawCodec, &accounts);
+        let compact = CodecReport::measure(&CompactCodec, &accounts);
+
+        assert_eq!(raw.accounts, 100);
+        assert_eq!(raw.avg_value_size(), 40.0);
+        assert!(compact.avg_value_size() < raw.avg_value_size());
+    }
+}
@@ -0,0 +1,169 @@
+//! Synthetic per-transaction execution cost.
+//!
+//! Every workload transaction is a plain transfer, so every transaction
+//! costs about the same to execute. Real blocks mix cheap transfers with
+//! expensive contract calls, and how well a scheduler packs that mix is
+//! where parallel executors differ most. [`ComputeCost`] draws a number of
+//! keccak iterations per transaction ([`SignedTransaction::compute_cost`]),
+//! which executors [`burn`] before applying the transfer, re-executions
+//! included.
+//!
+//! [`SignedTransaction::compute_cost`]: crate::SignedTransaction::compute_cost
+
+use alloy_primitives::{keccak256, B256};
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Distribution of keccak iterations per transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ComputeCost {
+    /// The same number of iterations for every transaction; 0 adds no work.
+    Fixed(u64),
+    /// Uniform between `min` and `max` iterations, inclusive.
+    Uniform { min: u64, max: u64 },
+    /// `cheap` iterations, except `expensive` for `expensive_percent` percent
+    /// of transactions, like transfers mixed with swaps.
+    Bimodal {
+        cheap: u64,
+        expensive: u64,
+        expensive_percent: u8,
+    },
+}
+
+impl Default for ComputeCost {
+    fn default() -> Self {
+        Self::Fixed(0)
+    }
+}
+
+impl ComputeCost {
+    /// Returns true if no transaction gets any work.
+    pub fn is_none(&self) -> bool {
+        *self == Self::Fixed(0)
+    }
+
+    /// Draws one transaction's iterations. Fixed costs draw nothing from `rng`.
+    pub fn sample(&self, rng: &mut impl Rng) -> u64 {
+        match *self {
+            Self::Fixed(iterations) => iterations,
+            Self::Uniform { min, max } => rng.gen_range(min..=max),
+            Self::Bimodal {
+                cheap,
+                expensive,
+                expensive_percent,
+            } => {
+                if rng.gen_range(0..100) < expensive_percent {
+                    expensive
+                } else {
+                    cheap
+                }
+            }
+        }
+    }
+
+    /// Mean iterations per transaction.
+    pub fn mean(&self) -> f64 {
+        match *self {
+            Self::Fixed(iterations) => iterations as f64,
+            Self::Uniform { min, max } => (min as f64 + max as f64) / 2.0,
+            Self::Bimodal {
+                cheap,
+                expensive,
+                expensive_percent,
+            } => {
+                let share = expensive_percent as f64 / 100.0;
+                cheap as f64 * (1.0 - share) + expensive as f64 * share
+            }
+        }
+    }
+}
+
+impl fmt::Display for ComputeCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(iterations) => write!(f, "fixed:{}", iterations),
+            Self::Uniform { min, max } => write!(f, "uniform:{}:{}", min, max),
+            Self::Bimodal {
+                cheap,
+                expensive,
+                expensive_percent,
+            } => write!(f, "bimodal:{}:{}:{}", cheap, expensive, expensive_percent),
+        }
+    }
+}
+
+impl FromStr for ComputeCost {
+    type Err = String;
+
+    /// Parses `fixed:N`, `uniform:MIN:MAX` or `bimodal:CHEAP:EXPENSIVE:PERCENT`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid compute cost '{}' (expected fixed:N, uniform:MIN:MAX or bimodal:CHEAP:EXPENSIVE:PERCENT)",
+                s
+            )
+        };
+        let fields: Vec<&str> = s.split(':').collect();
+        let number = |field: &str| field.parse::<u64>().map_err(|_| invalid());
+        match fields.as_slice() {
+            ["fixed", iterations] => Ok(Self::Fixed(number(iterations)?)),
+            ["uniform", min, max] => {
+                let (min, max) = (number(min)?, number(max)?);
+                if min > max {
+                    return Err(invalid());
+                }
+                Ok(Self::Uniform { min, max })
+            }
+            ["bimodal", cheap, expensive, percent] => Ok(Self::Bimodal {
+                cheap: number(cheap)?,
+                expensive: number(expensive)?,
+                expensive_percent: percent.parse().ok().filter(|&percent| percent <= 100).ok_or_else(invalid)?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Runs `iterations` keccak rounds starting from `seed` and returns the last
+/// digest, which the optimizer cannot skip.
+pub fn burn(seed: B256, iterations: u64) -> B256 {
+    let mut digest = seed;
+    for _ in 0..iterations {
+        digest = keccak256(digest);
+    }
+    std::hint::black_box(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_parse_and_sample() {
+        for text in ["fixed:0", "uniform:10:20", "bimodal:5:500:10"] {
+            let cost: ComputeCost = text.parse().unwrap();
+            assert_eq!(cost.to_string(), text);
+        }
+        assert!("uniform:20:10".parse::<ComputeCost>().is_err());
+        assert!("bimodal:5:500:101".parse::<ComputeCost>().is_err());
+        assert!("spin:5".parse::<ComputeCost>().is_err());
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let bimodal = ComputeCost::Bimodal {
+            cheap: 5,
+            expensive: 500,
+            expensive_percent: 10,
+        };
+        let samples: Vec<u64> = (0..1000).map(|_| bimodal.sample(&mut rng)).collect();
+        let expensive = samples.iter().filter(|&&iterations| iterations == 500).count();
+        assert!(samples.iter().all(|&iterations| iterations == 5 || iterations == 500));
+        assert!((50..150).contains(&expensive));
+        assert_eq!(bimodal.mean(), 54.5);
+
+        assert_eq!(burn(B256::ZERO, 0), B256::ZERO);
+        assert_eq!(burn(B256::ZERO, 2), keccak256(keccak256(B256::ZERO)));
+    }
+}
@@ -0,0 +1,360 @@
+//! Differences between two recorded benchmark runs.
+//!
+//! `compare` tests two executors within one run; a [`RunDiff`] instead sets
+//! two runs side by side, typically the same command before and after a
+//! change, and reports for every cell the change in mean TPS, the change in
+//! failed transactions and the failure reasons that appear only in the later
+//! run. [`RunDiff::render`] formats it as Markdown for a PR description.
+//!
+//! Cells are matched by section, group, executor and hot-account label, not
+//! by plan index, so runs with different executor selections still line up;
+//! cells only one run has are listed as added or removed. Repetitions of a
+//! cell are averaged. Failure reasons come from the `failures:` detail line
+//! registry executors record, so executors without one show none.
+
+use crate::manifest::{CellRecord, RunManifest};
+use std::fmt::Write;
+
+/// Prefix of the detail line holding a cell's failure breakdown.
+const FAILURES_PREFIX: &str = "failures: ";
+
+/// Identity of a cell across runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellKey {
+    pub section: String,
+    pub group: Option<String>,
+    pub executor_name: String,
+    pub hot_accounts_label: String,
+}
+
+impl CellKey {
+    fn of(cell: &CellRecord) -> Self {
+        Self {
+            section: cell.section.clone(),
+            group: cell.group.clone(),
+            executor_name: cell.executor_name.clone(),
+            hot_accounts_label: cell.hot_accounts_label.clone(),
+        }
+    }
+}
+
+/// Every repetition of one cell in one run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellSummary {
+    pub runs: usize,
+    pub mean_tps: f64,
+    /// Most transactions any repetition failed.
+    pub failed: usize,
+    /// Failure reasons any repetition recorded, with their largest count, in
+    /// order of first appearance.
+    pub failure_reasons: Vec<(String, usize)>,
+}
+
+impl CellSummary {
+    fn new(cells: &[&CellRecord]) -> Self {
+        let mut failure_reasons: Vec<(String, usize)> = Vec::new();
+        for detail in cells.iter().flat_map(|cell| &cell.details) {
+            let Some(breakdown) = detail.strip_prefix(FAILURES_PREFIX) else {
+                continue;
+            };
+            for (reason, count) in parse_failures(breakdown) {
+                match failure_reasons.iter_mut().find(|(existing, _)| *existing == reason) {
+                    Some((_, max)) => *max = (*max).max(count),
+                    None => failure_reasons.push((reason, count)),
+                }
+            }
+        }
+
+        Self {
+            runs: cells.len(),
+            mean_tps: cells.iter().map(|cell| cell.throughput_tps).sum::<f64>() / cells.len() as f64,
+            failed: cells.iter().map(|cell| cell.failed).max().unwrap_or(0),
+            failure_reasons,
+        }
+    }
+}
+
+/// One cell in either or both runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellDiff {
+    pub key: CellKey,
+    /// The cell in the earlier run, if it ran there.
+    pub before: Option<CellSummary>,
+    /// The cell in the later run, if it ran there.
+    pub after: Option<CellSummary>,
+}
+
+impl CellDiff {
+    /// Change in mean TPS in percent, if both runs have the cell.
+    pub fn tps_change_pct(&self) -> Option<f64> {
+        let (before, after) = (self.before.as_ref()?, self.after.as_ref()?);
+        (before.mean_tps > 0.0).then(|| (after.mean_tps / before.mean_tps - 1.0) * 100.0)
+    }
+
+    /// Change in failed transactions, if both runs have the cell.
+    pub fn failed_change(&self) -> Option<i64> {
+        Some(self.after.as_ref()?.failed as i64 - self.before.as_ref()?.failed as i64)
+    }
+
+    /// Failure reasons of the later run the earlier one never recorded, with
+    /// their counts. Empty unless both runs have the cell.
+    pub fn new_failure_reasons(&self) -> Vec<(&str, usize)> {
+        let (Some(before), Some(after)) = (&self.before, &self.after) else {
+            return Vec::new();
+        };
+        after
+            .failure_reasons
+            .iter()
+            .filter(|(reason, _)| !before.failure_reasons.iter().any(|(existing, _)| existing == reason))
+            .map(|(reason, count)| (reason.as_str(), *count))
+            .collect()
+    }
+}
+
+/// Cell-by-cell differences between two runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunDiff {
+    pub before_git_hash: String,
+    pub after_git_hash: String,
+    /// Cells of the earlier run in its order, then cells only the later run has.
+    pub cells: Vec<CellDiff>,
+}
+
+impl RunDiff {
+    /// Matches the cells of `before` and `after`.
+    pub fn new(before: &RunManifest, after: &RunManifest) -> Self {
+        let mut keys: Vec<CellKey> = Vec::new();
+        for cell in before.cells.iter().chain(&after.cells) {
+            let key = CellKey::of(cell);
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        let summary = |manifest: &RunManifest, key: &CellKey| {
+            let cells: Vec<&CellRecord> = manifest.cells.iter().filter(|cell| CellKey::of(cell) == *key).collect();
+            (!cells.is_empty()).then(|| CellSummary::new(&cells))
+        };
+        Self {
+            before_git_hash: before.git_hash.clone(),
+            after_git_hash: after.git_hash.clone(),
+            cells: keys
+                .into_iter()
+                .map(|key| CellDiff {
+                    before: summary(before, &key),
+                    after: summary(after, &key),
+                    key,
+                })
+                .collect(),
+        }
+    }
+
+    /// Cells whose TPS changed by more than `threshold_pct` either way, whose
+    /// failure count changed, or that gained a failure reason.
+    pub fn notable(&self, threshold_pct: f64) -> impl Iterator<Item = &CellDiff> {
+        self.cells.iter().filter(move |cell| {
+            cell.tps_change_pct().is_some_and(|change| change.abs() > threshold_pct)
+                || cell.failed_change().is_some_and(|change| change != 0)
+                || !cell.new_failure_reasons().is_empty()
+        })
+    }
+
+    /// Renders the diff as Markdown: one table per section, then the cells
+    /// only one run has. TPS changes beyond `threshold_pct` are bold.
+    pub fn render(&self, threshold_pct: f64) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "### Benchmark diff: `{}` → `{}`\n",
+            self.before_git_hash, self.after_git_hash
+        );
+        let _ = writeln!(
+            out,
+            "{} of {} cells in both runs moved TPS by more than {}% or changed their failures.",
+            self.notable(threshold_pct).count(),
+            self.cells.iter().filter(|cell| cell.before.is_some() && cell.after.is_some()).count(),
+            threshold_pct
+        );
+
+        let mut sections: Vec<&str> = Vec::new();
+        for cell in &self.cells {
+            if cell.before.is_some() && cell.after.is_some() && !sections.contains(&cell.key.section.as_str()) {
+                sections.push(&cell.key.section);
+            }
+        }
+        for section in sections {
+            let _ = write!(out, "\n#### {}\n\n", escape(section));
+            out.push_str("| Hot Accounts | Executor | Group | TPS before | TPS after | Δ TPS | Failed | New failure reasons |\n");
+            out.push_str("|---|---|---|--:|--:|--:|--:|---|\n");
+            for cell in self.cells.iter().filter(|cell| cell.key.section == section) {
+                let (Some(before), Some(after)) = (&cell.before, &cell.after) else {
+                    continue;
+                };
+                let change = match cell.tps_change_pct() {
+                    Some(change) if change.abs() > threshold_pct => format!("**{:+.1}%**", change),
+                    Some(change) => format!("{:+.1}%", change),
+                    None => "-".to_string(),
+                };
+                let failed = if before.failed == after.failed {
+                    after.failed.to_string()
+                } else {
+                    format!("**{} → {}**", before.failed, after.failed)
+                };
+                let reasons: Vec<String> = cell
+                    .new_failure_reasons()
+                    .into_iter()
+                    .map(|(reason, count)| format!("{} {}", reason, count))
+                    .collect();
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {:.0} | {:.0} | {} | {} | {} |",
+                    escape(&cell.key.hot_accounts_label),
+                    escape(&cell.key.executor_name),
+                    escape(cell.key.group.as_deref().unwrap_or("")),
+                    before.mean_tps,
+                    after.mean_tps,
+                    change,
+                    failed,
+                    escape(&reasons.join(", "))
+                );
+            }
+        }
+
+        for (title, added) in [("Removed cells", false), ("Added cells", true)] {
+            let cells: Vec<&CellDiff> = self
+                .cells
+                .iter()
+                .filter(|cell| if added { cell.before.is_none() } else { cell.after.is_none() })
+                .collect();
+            if cells.is_empty() {
+                continue;
+            }
+            let _ = write!(out, "\n#### {}\n\n", title);
+            for cell in cells {
+                let summary = cell.before.as_ref().or(cell.after.as_ref()).expect("cell ran in one run");
+                let group = cell.key.group.as_deref().map(|group| format!(", {}", group)).unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "- {} / {} ({}{}): {:.0} TPS, {} failed",
+                    escape(&cell.key.section),
+                    escape(&cell.key.executor_name),
+                    escape(&cell.key.hot_accounts_label),
+                    escape(&group),
+                    summary.mean_tps,
+                    summary.failed
+                );
+            }
+        }
+
+        out
+    }
+}
+
+/// Parses a breakdown written by
+/// [`format_failures`](crate::executor::format_failures), e.g.
+/// `nonce too low 3, replayed 2, 1 unattributed`.
+fn parse_failures(breakdown: &str) -> Vec<(String, usize)> {
+    if breakdown == "none" {
+        return Vec::new();
+    }
+    breakdown
+        .split(", ")
+        .filter_map(|part| {
+            if let Some(count) = part.strip_suffix(" unattributed") {
+                return Some(("unattributed".to_string(), count.parse().ok()?));
+            }
+            let (reason, count) = part.rsplit_once(' ')?;
+            Some((reason.to_string(), count.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Escapes characters that would break a table cell.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    fn cell(executor_name: &str, repetition: usize, throughput_tps: f64, failures: &str) -> CellRecord {
+        let failed = parse_failures(failures).iter().map(|(_, count)| count).sum();
+        CellRecord {
+            index: 0,
+            repetition,
+            section: "Block-STM".to_string(),
+            group: Some("4 threads".to_string()),
+            executor_name: executor_name.to_string(),
+            hot_accounts_label: "10 accounts".to_string(),
+            preserves_order: true,
+            successful: 100 - failed,
+            failed,
+            duration_ms: 100_000.0 / throughput_tps,
+            throughput_tps,
+            result_hash: B256::ZERO,
+            details: vec![format!("{}{}", FAILURES_PREFIX, failures)],
+        }
+    }
+
+    fn manifest(git_hash: &str, cells: Vec<CellRecord>) -> RunManifest {
+        RunManifest {
+            git_hash: git_hash.to_string(),
+            cells,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_failures() {
+        assert_eq!(parse_failures("none"), Vec::new());
+        assert_eq!(
+            parse_failures("nonce too low 3, replayed 2, 1 unattributed"),
+            vec![
+                ("nonce too low".to_string(), 3),
+                ("replayed".to_string(), 2),
+                ("unattributed".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_matches_cells_across_runs() {
+        let before = manifest(
+            "aaaa",
+            vec![
+                cell("block_stm_parallel", 0, 1000.0, "replayed 2"),
+                cell("block_stm_parallel", 1, 1200.0, "replayed 2"),
+                cell("fdb_parallel", 0, 500.0, "none"),
+            ],
+        );
+        let after = manifest(
+            "bbbb",
+            vec![
+                cell("block_stm_parallel", 0, 1650.0, "nonce too high 1, replayed 2"),
+                cell("mdbx_batched", 0, 800.0, "none"),
+            ],
+        );
+        let diff = RunDiff::new(&before, &after);
+        assert_eq!(diff.cells.len(), 3);
+
+        let block_stm = &diff.cells[0];
+        assert_eq!(block_stm.before.as_ref().unwrap().runs, 2);
+        assert_eq!(block_stm.tps_change_pct(), Some(50.0));
+        assert_eq!(block_stm.failed_change(), Some(1));
+        assert_eq!(block_stm.new_failure_reasons(), vec![("nonce too high", 1)]);
+
+        // Cells of one run have no change
+        assert!(diff.cells[1].after.is_none() && diff.cells[1].tps_change_pct().is_none());
+        assert!(diff.cells[2].before.is_none());
+        assert_eq!(diff.notable(5.0).count(), 1);
+        assert_eq!(diff.notable(60.0).count(), 1);
+
+        let markdown = diff.render(5.0);
+        assert!(markdown.contains("`aaaa` → `bbbb`"));
+        assert!(markdown.contains("| 10 accounts | block_stm_parallel | 4 threads | 1100 | 1650 | **+50.0%** | **2 → 3** | nonce too high 1 |"));
+        assert!(markdown.contains("#### Removed cells\n\n- Block-STM / fdb_parallel"));
+        assert!(markdown.contains("#### Added cells\n\n- Block-STM / mdbx_batched"));
+    }
+}
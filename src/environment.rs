@@ -0,0 +1,127 @@
+//! Machine and build metadata recorded alongside benchmark results.
+//!
+//! Numbers from different machines (or different builds on the same machine)
+//! are only comparable when the hardware, kernel, compiler, source revision,
+//! and enabled backends are known. [`EnvironmentInfo::capture`] collects all of
+//! them; anything that cannot be determined is reported as `"unknown"`.
+
+use std::fs;
+
+/// Where and how a benchmark binary ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentInfo {
+    /// CPU model name.
+    pub cpu_model: String,
+    /// Logical cores available to this process.
+    pub cores: usize,
+    /// Total physical memory in bytes, if known.
+    pub memory_bytes: Option<u64>,
+    /// Operating system and kernel release.
+    pub kernel: String,
+    /// Version of the compiler that built this binary.
+    pub rustc_version: String,
+    /// Git revision the binary was built from, suffixed with `-dirty` for
+    /// uncommitted changes.
+    pub git_hash: String,
+    /// Cargo features enabled at build time.
+    pub features: Vec<&'static str>,
+}
+
+impl EnvironmentInfo {
+    /// Collects metadata about the current machine and build.
+    pub fn capture() -> Self {
+        Self {
+            cpu_model: cpu_model().unwrap_or_else(|| "unknown".to_string()),
+            cores: std::thread::available_parallelism().map_or(1, |cores| cores.get()),
+            memory_bytes: memory_bytes(),
+            kernel: kernel().unwrap_or_else(|| std::env::consts::OS.to_string()),
+            rustc_version: env!("DB_TEST_RUSTC_VERSION").to_string(),
+            git_hash: env!("DB_TEST_GIT_HASH").to_string(),
+            features: enabled_features(),
+        }
+    }
+
+    /// Total memory in GiB, for display.
+    pub fn memory_gib(&self) -> Option<f64> {
+        self.memory_bytes
+            .map(|bytes| bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    }
+}
+
+/// Cargo features this crate was built with.
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "mdbx") {
+        features.push("mdbx");
+    }
+    if cfg!(feature = "fdb") {
+        features.push("fdb");
+    }
+    if cfg!(feature = "block-stm") {
+        features.push("block-stm");
+    }
+    if cfg!(feature = "verkle") {
+        features.push("verkle");
+    }
+    if cfg!(feature = "remote") {
+        features.push("remote");
+    }
+    if cfg!(feature = "io-uring") {
+        features.push("io-uring");
+    }
+    if cfg!(feature = "mmap") {
+        features.push("mmap");
+    }
+    if cfg!(feature = "secp256k1") {
+        features.push("secp256k1");
+    }
+    if cfg!(feature = "keystore") {
+        features.push("keystore");
+    }
+    if cfg!(feature = "mnemonic") {
+        features.push("mnemonic");
+    }
+    if cfg!(feature = "oracle") {
+        features.push("oracle");
+    }
+    if cfg!(feature = "reth-compare") {
+        features.push("reth-compare");
+    }
+    features
+}
+
+fn cpu_model() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        // x86 reports "model name"; many ARM kernels only report "Model"
+        matches!(key.trim(), "model name" | "Model").then(|| value.trim().to_string())
+    })
+}
+
+fn memory_bytes() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn kernel() -> Option<String> {
+    let release = fs::read_to_string("/proc/sys/kernel/osrelease").ok()?;
+    Some(format!("{} {}", std::env::consts::OS, release.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_environment() {
+        let env = EnvironmentInfo::capture();
+
+        assert!(env.cores >= 1);
+        assert!(!env.rustc_version.is_empty());
+        assert!(!env.git_hash.is_empty());
+        assert_eq!(env.features.contains(&"mdbx"), cfg!(feature = "mdbx"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Cross-executor equivalence checks.
+//!
+//! Every executor must commit the same transactions for the same workload.
+//! [`check_equivalence`] runs a candidate next to a reference executor and
+//! compares their success and failure counts and, where both index their
+//! outcomes, the status of every transaction. Where both emit per-block state
+//! checkpoints, the first block whose state differs is reported as well.
+//! Failure reasons recorded by either executor explain the mismatches.
+
+use crate::executor::{first_divergent_block, format_failures, AnyExecutor, FailureReason, TxStatus};
+use crate::Workload;
+use alloy_primitives::B256;
+use std::fmt;
+
+/// A transaction whose outcome differs from the reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusMismatch {
+    /// Position in `workload.transactions`.
+    pub position: usize,
+    pub tx_hash: B256,
+    pub reference: TxStatus,
+    pub candidate: TxStatus,
+    /// Why the reference failed the transaction, if it recorded a reason.
+    pub reference_reason: Option<FailureReason>,
+    /// Why the candidate failed the transaction, if it recorded a reason.
+    pub candidate_reason: Option<FailureReason>,
+}
+
+impl StatusMismatch {
+    /// The reference's outcome, with its failure reason if known.
+    pub fn reference_outcome(&self) -> String {
+        describe_outcome(self.reference, self.reference_reason)
+    }
+
+    /// The candidate's outcome, with its failure reason if known.
+    pub fn candidate_outcome(&self) -> String {
+        describe_outcome(self.candidate, self.candidate_reason)
+    }
+}
+
+fn describe_outcome(status: TxStatus, reason: Option<FailureReason>) -> String {
+    match reason {
+        Some(reason) if !status.is_success() => format!("{:?} ({})", status, reason),
+        _ => format!("{:?}", status),
+    }
+}
+
+/// Outcome of comparing one executor against the reference on one workload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equivalence {
+    pub executor_name: String,
+    pub successful: (usize, usize),
+    pub failed: (usize, usize),
+    /// Failures by reason, as in [`ExecutionResult::failure_breakdown`](crate::executor::ExecutionResult::failure_breakdown).
+    pub failure_reasons: (Vec<(FailureReason, usize)>, Vec<(FailureReason, usize)>),
+    /// Whether both executors indexed every outcome, so statuses were compared.
+    pub statuses_compared: bool,
+    pub mismatches: Vec<StatusMismatch>,
+    /// Whether both executors emitted per-block checkpoints, so they were compared.
+    pub checkpoints_compared: bool,
+    /// First block whose checkpoint differs from the reference.
+    pub divergent_block: Option<usize>,
+}
+
+impl Equivalence {
+    /// The reference's and the candidate's failures by reason, as one line each.
+    pub fn failure_summaries(&self) -> (String, String) {
+        (
+            format_failures(&self.failure_reasons.0, self.failed.0),
+            format_failures(&self.failure_reasons.1, self.failed.1),
+        )
+    }
+
+    /// Whether the candidate matched the reference.
+    pub fn agrees(&self) -> bool {
+        self.successful.0 == self.successful.1
+            && self.failed.0 == self.failed.1
+            && self.mismatches.is_empty()
+            && self.divergent_block.is_none()
+    }
+}
+
+impl fmt::Display for Equivalence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: successful {}/{}, failed {}/{} (reference/candidate), ",
+            self.executor_name, self.successful.0, self.successful.1, self.failed.0, self.failed.1
+        )?;
+        if self.statuses_compared {
+            write!(f, "{} status mismatches", self.mismatches.len())?;
+        } else {
+            f.write_str("statuses not indexed")?;
+        }
+        match (self.checkpoints_compared, self.divergent_block) {
+            (false, _) => f.write_str(", no checkpoints"),
+            (true, None) => f.write_str(", checkpoints match"),
+            (true, Some(block)) => write!(f, ", state diverges at block {}", block),
+        }
+    }
+}
+
+/// Runs `reference` and `candidate` on `workload` and compares their outcomes.
+pub fn check_equivalence(reference: &dyn AnyExecutor, candidate: &dyn AnyExecutor, workload: &Workload) -> Equivalence {
+    let expected = reference.execute_workload(workload).result;
+    let actual = candidate.execute_workload(workload).result;
+
+    let expected_statuses = expected.tx_index.statuses(workload);
+    let actual_statuses = actual.tx_index.statuses(workload);
+    let statuses_compared = expected_statuses.iter().chain(&actual_statuses).all(Option::is_some);
+
+    let mismatches = if statuses_compared {
+        expected_statuses
+            .iter()
+            .zip(&actual_statuses)
+            .enumerate()
+            .filter_map(|(position, (&expected, &actual))| match (expected, actual) {
+                (Some(reference), Some(candidate)) if reference != candidate => Some(StatusMismatch {
+                    position,
+                    tx_hash: workload.transactions[position].tx_hash,
+                    reference,
+                    candidate,
+                    reference_reason: expected.failure_reasons.get(position).copied().flatten(),
+                    candidate_reason: actual.failure_reasons.get(position).copied().flatten(),
+                }),
+                _ => None,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let checkpoints_compared = !expected.checkpoints.is_empty() && !actual.checkpoints.is_empty();
+    let divergent_block = if checkpoints_compared {
+        first_divergent_block(&expected.checkpoints, &actual.checkpoints)
+    } else {
+        None
+    };
+
+    Equivalence {
+        executor_name: candidate.name(),
+        successful: (expected.successful, actual.successful),
+        failed: (expected.failed, actual.failed),
+        failure_reasons: (expected.failure_breakdown(), actual.failure_breakdown()),
+        statuses_compared,
+        mismatches,
+        checkpoints_compared,
+        divergent_block,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::SequentialExecutor;
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_verification_mismatch_is_reported() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            fake_signatures: true,
+            ..Default::default()
+        });
+        let reference = SequentialExecutor::with_verification(false);
+
+        let same = check_equivalence(&reference, &SequentialExecutor::with_verification(false), &workload);
+        assert!(same.agrees());
+        assert!(same.statuses_compared);
+        assert!(same.checkpoints_compared);
+
+        // Placeholder signatures never verify, so every transaction fails
+        let verifying = check_equivalence(&reference, &SequentialExecutor::with_verification(true), &workload);
+        assert!(!verifying.agrees());
+        assert_eq!(verifying.failed, (0, 20));
+        assert_eq!(verifying.mismatches.len(), 20);
+        assert_eq!(verifying.mismatches[0].candidate, TxStatus::Failed);
+        assert_eq!(verifying.mismatches[0].candidate_reason, Some(FailureReason::InvalidSignature));
+        assert_eq!(verifying.mismatches[0].candidate_outcome(), "Failed (bad signature)");
+        assert_eq!(verifying.failure_reasons.1, vec![(FailureReason::InvalidSignature, 20)]);
+        assert_eq!(verifying.failure_summaries(), ("none".to_string(), "bad signature 20".to_string()));
+        assert_eq!(verifying.divergent_block, Some(0));
+    }
+}
@@ -0,0 +1,180 @@
+//! Object-safe executor interface.
+//!
+//! [`Executor`] has an associated `Database` type, so executors over different
+//! backends cannot share a `Vec<Box<dyn Executor>>`. [`AnyExecutor`] hides the
+//! database behind the call: each implementation sets up its own backend for
+//! the workload and reports only the outcome. Every in-memory [`Executor`]
+//! implements it automatically.
+
+use crate::executor::{ExecutionResult, Executor};
+use crate::{GenesisState, Workload};
+use revm::database::CacheDB;
+use std::time::{Duration, Instant};
+
+/// Outcome of one [`AnyExecutor::execute_workload`] call.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExecutionReport {
+    pub result: ExecutionResult,
+    /// Time spent executing, excluding backend setup.
+    pub elapsed: Duration,
+    /// Executor-specific detail lines, e.g. commit or proof timings.
+    pub details: Vec<String>,
+}
+
+impl ExecutionReport {
+    /// Creates a report without detail lines.
+    pub fn new(result: ExecutionResult, elapsed: Duration) -> Self {
+        Self {
+            result,
+            elapsed,
+            details: Vec::new(),
+        }
+    }
+
+    /// Appends a detail line.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.details.push(detail.into());
+        self
+    }
+
+    /// Transactions per second over the execution time.
+    pub fn throughput_tps(&self, num_transactions: usize) -> f64 {
+        num_transactions as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Object-safe executor that owns its backend setup.
+pub trait AnyExecutor {
+    /// Name used in result tables.
+    fn name(&self) -> String;
+
+    /// Whether the executor preserves strict transaction ordering.
+    fn preserves_order(&self) -> bool;
+
+    /// Whether the executor can run workloads with token transfers.
+    fn supports_tokens(&self) -> bool {
+        false
+    }
+
+    /// Whether the executor can run nonce-less workloads.
+    fn supports_nonceless(&self) -> bool {
+        false
+    }
+
+    /// Whether the executor can hold balances of 2^128 wei or more, as
+    /// near-max balance workloads need.
+    fn supports_large_balances(&self) -> bool {
+        true
+    }
+
+    /// Sets up a fresh backend and executes the workload on it.
+    fn execute_workload(&self, workload: &Workload) -> ExecutionReport;
+}
+
+impl<E> AnyExecutor for E
+where
+    E: Executor<Database = CacheDB<GenesisState>>,
+{
+    fn name(&self) -> String {
+        Executor::name(self).to_string()
+    }
+
+    fn preserves_order(&self) -> bool {
+        Executor::preserves_order(self)
+    }
+
+    fn supports_tokens(&self) -> bool {
+        Executor::supports_tokens(self)
+    }
+
+    fn supports_nonceless(&self) -> bool {
+        Executor::supports_nonceless(self)
+    }
+
+    fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+        let db = workload.create_db();
+
+        let start = Instant::now();
+        let (_, result) = self.execute(db, workload);
+        let elapsed = start.elapsed();
+        let mut report = ExecutionReport::new(result, elapsed);
+        if let Some(detail) = block_durations_detail(&report.result.block_durations) {
+            report = report.with_detail(detail);
+        }
+        if let Some(scheduler) = report.result.scheduler {
+            report = report.with_detail(scheduler.to_string());
+        }
+        report
+    }
+}
+
+/// Summarizes per-block durations, if the executor reported any.
+fn block_durations_detail(durations: &[Duration]) -> Option<String> {
+    let min = durations.iter().min()?;
+    let max = durations.iter().max()?;
+    let total: Duration = durations.iter().sum();
+    Some(format!(
+        "per-block: {} blocks, min {:.2} ms, avg {:.2} ms, max {:.2} ms",
+        durations.len(),
+        min.as_secs_f64() * 1000.0,
+        total.as_secs_f64() * 1000.0 / durations.len() as f64,
+        max.as_secs_f64() * 1000.0
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::{OrderingMode, SequentialExecutor};
+    use crate::WorkloadConfig;
+
+    /// Rejects every transaction without touching a database.
+    struct RejectAll;
+
+    impl AnyExecutor for RejectAll {
+        fn name(&self) -> String {
+            "reject_all".to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            false
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            ExecutionReport::new(ExecutionResult::new(0, workload.transactions.len()), Duration::ZERO)
+                .with_detail("nothing executed")
+        }
+    }
+
+    #[test]
+    fn test_heterogeneous_executors() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            ..Default::default()
+        });
+
+        let executors: Vec<Box<dyn AnyExecutor>> = vec![
+            Box::new(SequentialExecutor::new(true, OrderingMode::Strict)),
+            Box::new(RejectAll),
+        ];
+        let reports: Vec<_> = executors
+            .iter()
+            .map(|executor| executor.execute_workload(&workload))
+            .collect();
+
+        assert_eq!(executors[0].name(), "sequential_in_memory");
+        assert!(executors[0].preserves_order());
+        assert_eq!(reports[0].result.successful, 20);
+        assert!(reports[0].details.is_empty());
+
+        assert!(!executors[1].preserves_order());
+        assert_eq!(reports[1].result.failed, 20);
+        assert_eq!(reports[1].details, vec!["nothing executed".to_string()]);
+    }
+}
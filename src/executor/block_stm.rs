@@ -3,12 +3,23 @@
 //! This module provides a wrapper around the block-stm-executor crate,
 //! implementing the Executor trait for use in benchmarks.
 
-use crate::executor::{ExecutionResult, Executor};
-use crate::Workload;
-use alloy_primitives::U256;
-use block_stm_executor::{AccountState, ExecutorConfig, ParallelExecutor, Transaction};
-use revm::database::{CacheDB, EmptyDB};
-use std::collections::HashMap;
+use crate::executor::pipeline::{pipeline, BlockSink, PipelineResult};
+use crate::executor::{
+    CheckpointRecorder, ConflictHints, ExecutionResult, Executor, FailureReason, SchedulerCounters, TransactionIndex,
+    TxStatus,
+};
+use crate::analysis;
+use crate::live::{self, LiveProgress};
+use crate::{GenesisState, SignedTransaction, Workload};
+use alloy_primitives::{Address, B256, U256};
+use block_stm_executor::{
+    simulate, AccountState, BlockExecutionResult, ExecutionObserver, ExecutorConfig, ParallelExecutor,
+    PoisonedTransaction, Schedule, SchedulingPolicy, SimulationConfig, SimulationReport, Transaction, TxnIndex,
+};
+use revm::database::CacheDB;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Block-STM parallel executor.
 ///
@@ -18,6 +29,40 @@ use std::collections::HashMap;
 pub struct BlockStmExecutor {
     pub num_threads: usize,
     pub verify_signatures: bool,
+    /// Scheduling window past the commit point, if bounded.
+    pub lookahead: Option<usize>,
+    /// Byte budget for speculative state, if bounded.
+    pub memory_budget: Option<usize>,
+    /// Conflicts observed in an earlier run, used as scheduling dependencies.
+    pub hints: Option<Arc<ConflictHints>>,
+    /// Whether transactions declare the dependencies the workload analyzer
+    /// derives from their accounts.
+    pub declare_dependencies: bool,
+    /// Whether each workload block runs as its own Block-STM block.
+    pub per_block: bool,
+    /// Whether a block with any failed transaction is discarded whole.
+    pub atomic_blocks: bool,
+    /// Dispatch orders recorded in an earlier run, one per Block-STM block.
+    pub schedules: Option<Arc<Vec<Schedule>>>,
+}
+
+/// One engine run over a workload.
+struct Run {
+    /// Engine result, merged across blocks when running block by block.
+    result: BlockExecutionResult,
+    /// Per-block durations; empty unless running block by block.
+    block_durations: Vec<Duration>,
+    /// The workload replay mask.
+    replay_mask: Vec<bool>,
+    /// Hashes of the transactions passed to the engine, in engine order.
+    hashes: Vec<B256>,
+    /// Recorded dispatch order of each Block-STM block, if recording.
+    schedules: Vec<Schedule>,
+    /// Where each Block-STM block diverged from its replayed schedule.
+    divergences: Vec<Option<usize>>,
+    /// Whether the block of each engine transaction was discarded; empty
+    /// unless running block by block.
+    discarded: Vec<bool>,
 }
 
 impl BlockStmExecutor {
@@ -26,82 +71,578 @@ impl BlockStmExecutor {
         Self {
             num_threads,
             verify_signatures,
+            lookahead: None,
+            memory_budget: None,
+            hints: None,
+            declare_dependencies: false,
+            per_block: false,
+            atomic_blocks: false,
+            schedules: None,
         }
     }
-}
 
-impl Executor for BlockStmExecutor {
-    type Database = CacheDB<EmptyDB>;
+    /// Limits scheduling to `lookahead` transactions past the commit point.
+    pub fn with_lookahead(mut self, lookahead: usize) -> Self {
+        self.lookahead = Some(lookahead);
+        self
+    }
 
-    fn execute(
-        &self,
-        _db: Self::Database,
-        workload: &Workload,
-    ) -> (Self::Database, ExecutionResult) {
-        // Extract initial account states from the workload
-        // All accounts start with the same initial balance (1000 ETH)
-        let mut initial_states = HashMap::new();
-        let initial_balance = U256::from(1_000_000_000_000_000_000_000u128); // 1000 ETH
-        
-        for account in &workload.accounts {
-            initial_states.insert(
-                account.address,
-                AccountState::new(0, initial_balance),
-            );
+    /// Bounds speculative state to roughly `bytes`, throttling scheduling when exceeded.
+    ///
+    /// Pruning drops the versions per-block checkpoints are read from, so a
+    /// budgeted run emits no checkpoints unless it runs block by block.
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// Delays each hinted reader until its hinted writer has executed once.
+    pub fn with_hints(mut self, hints: Arc<ConflictHints>) -> Self {
+        self.hints = Some(hints);
+        self
+    }
+
+    /// Has each transaction declare its
+    /// [`declared_dependencies`](crate::analysis::declared_dependencies), so
+    /// it is not scheduled until the earlier transactions touching its
+    /// accounts have executed once, instead of discovering them by aborting.
+    ///
+    /// Block by block, dependencies on earlier blocks are dropped.
+    pub fn with_declared_dependencies(mut self) -> Self {
+        self.declare_dependencies = true;
+        self
+    }
+
+    /// Runs each workload block as its own Block-STM block, carrying state
+    /// from one block to the next, instead of one block for the whole workload.
+    ///
+    /// Throughput, parallelism and memory then reflect blocks of the
+    /// workload's size, and per-block durations are reported.
+    pub fn with_block_boundaries(mut self) -> Self {
+        self.per_block = true;
+        self
+    }
+
+    /// Makes every workload block all-or-nothing, as a rollup treats a batch:
+    /// if any transaction fails, the engine discards the block's writes and
+    /// every transaction in it fails, those valid on their own with
+    /// [`FailureReason::BlockDiscarded`].
+    ///
+    /// Implies [`with_block_boundaries`](Self::with_block_boundaries), since
+    /// a block is only discarded as a whole Block-STM block.
+    pub fn with_atomic_blocks(mut self) -> Self {
+        self.atomic_blocks = true;
+        self.per_block = true;
+        self
+    }
+
+    /// Forces the dispatch order of `schedules`, as recorded by
+    /// [`record_schedules`](Self::record_schedules) with the same options.
+    ///
+    /// Needs at least as many threads as the recording run.
+    pub fn with_schedule_replay(mut self, schedules: Arc<Vec<Schedule>>) -> Self {
+        self.schedules = Some(schedules);
+        self
+    }
+
+    /// Executes `workload` and returns the conflicts the scheduler observed.
+    pub fn profile_conflicts(&self, workload: &Workload) -> ConflictHints {
+        let Run { result, hashes, .. } = self.run(workload, false);
+        ConflictHints {
+            pairs: result
+                .conflicts
+                .iter()
+                .map(|&(writer, reader)| (hashes[writer as usize], hashes[reader as usize]))
+                .collect(),
         }
-        
-        // Convert all transactions across all blocks to Block-STM format
-        // Note: We pass signature data so verification happens in parallel workers
-        let mut block_stm_txs = Vec::new();
-        for block in &workload.blocks {
-            for tx in block {
-                block_stm_txs.push(Transaction {
-                    from: tx.from,
-                    to: tx.to,
-                    value: tx.value,
-                    nonce: tx.nonce,
-                    signature: tx.signature,
-                    tx_hash: tx.tx_hash,
-                });
-            }
+    }
+
+    /// Executes `workload` and returns the dispatch order of each Block-STM
+    /// block: one per workload block when running block by block, and a
+    /// single one otherwise.
+    pub fn record_schedules(&self, workload: &Workload) -> Vec<Schedule> {
+        self.run(workload, true).schedules
+    }
+
+    /// Executes `workload` like [`Executor::execute`] and also returns, for
+    /// each Block-STM block, the index of the first replayed event it could
+    /// not follow, if any.
+    pub fn execute_replay(&self, workload: &Workload) -> (ExecutionResult, Vec<Option<usize>>) {
+        let mut run = self.run(workload, false);
+        let divergences = std::mem::take(&mut run.divergences);
+        let (_, result) = finish(workload, run);
+        (result, divergences)
+    }
+
+    /// Simulates this executor's workers on `workload` in virtual time (see
+    /// [`block_stm_executor::simulation`]), transaction `i` of the workload
+    /// taking `costs[i]` per execution.
+    ///
+    /// Block by block, the blocks run one after another and the report sums
+    /// them. Lookahead, memory budget and hints are not modelled.
+    pub fn simulate(&self, workload: &Workload, policy: SchedulingPolicy, costs: &[u64]) -> SimulationReport {
+        let EngineBlocks {
+            blocks, replay_mask, ..
+        } = engine_blocks(workload, self.per_block, false);
+        let costs: Vec<u64> = costs
+            .iter()
+            .zip(&replay_mask)
+            .filter(|&(_, &replay)| !replay)
+            .map(|(&cost, _)| cost)
+            .collect();
+
+        let config = SimulationConfig {
+            num_workers: self.num_threads,
+            policy,
+        };
+        let mut start = 0;
+        let mut total: Option<SimulationReport> = None;
+        for block in blocks {
+            let report = simulate(&block, &costs[start..], config);
+            start += block.len();
+            total = Some(match total {
+                Some(total) => SimulationReport {
+                    num_txns: total.num_txns + report.num_txns,
+                    makespan: total.makespan + report.makespan,
+                    sequential_time: total.sequential_time + report.sequential_time,
+                    executions: total.executions + report.executions,
+                    retries: total.retries + report.retries,
+                    busy_time: total.busy_time + report.busy_time,
+                    ..total
+                },
+                None => report,
+            });
         }
-        
+        total.unwrap_or_else(|| simulate(&[], &[], config))
+    }
+
+    /// Executes `workload` block by block, handing each block's writes to
+    /// `sink` on a separate thread while the next block executes.
+    ///
+    /// Execution runs at most one block ahead of the sink, and the time it
+    /// waits for the sink is reported. Fails if the sink fails to commit a
+    /// block; blocks after it still execute but are not persisted.
+    pub fn execute_pipelined<S: BlockSink>(
+        &self,
+        workload: &Workload,
+        sink: &mut S,
+    ) -> Result<(CacheDB<GenesisState>, PipelineResult), String> {
+        let start = Instant::now();
+        let (run, stats) = pipeline(sink, |handoff| {
+            self.run_with(workload, false, true, |result| handoff(result.final_states.clone()))
+        })?;
+        let elapsed = start.elapsed();
+        let (db, result) = finish(workload, run);
+        Ok((db, PipelineResult { result, elapsed, stats }))
+    }
+
+    /// Runs the engine on the non-replayed transactions of `workload`,
+    /// recording each block's dispatch order if `record_schedule` is set.
+    fn run(&self, workload: &Workload, record_schedule: bool) -> Run {
+        self.run_with(workload, record_schedule, self.per_block, |_| {})
+    }
+
+    /// Like [`run`](Self::run), running block by block if `per_block` is set
+    /// and then calling `on_block` as each block commits.
+    fn run_with(
+        &self,
+        workload: &Workload,
+        record_schedule: bool,
+        per_block: bool,
+        on_block: impl FnMut(&BlockExecutionResult),
+    ) -> Run {
+        let EngineBlocks {
+            mut blocks,
+            block_lens,
+            replay_mask,
+        } = engine_blocks(workload, per_block, self.declare_dependencies);
+        let hashes: Vec<B256> = blocks.iter().flat_map(|block| block.iter()).map(|tx| tx.tx_hash).collect();
+
         // Execute with Block-STM
-        let config = ExecutorConfig {
+        let mut config = ExecutorConfig {
             num_threads: self.num_threads,
             verify_signatures: self.verify_signatures,
-            initial_states,
+            initial_states: initial_states(workload),
+            lookahead: self.lookahead,
+            memory_budget: self.memory_budget,
+            dependency_hints: match &self.hints {
+                Some(hints) => hints
+                    .to_positions(&hashes)
+                    .into_iter()
+                    .map(|(earlier, later)| (earlier as TxnIndex, later as TxnIndex))
+                    .collect(),
+                None => Vec::new(),
+            },
+            checkpoints: Vec::new(),
         };
         
-        let executor = ParallelExecutor::new(config);
-        let result = executor.execute_block(block_stm_txs);
-        
-        // Create a fresh database with final states
-        let mut final_db = CacheDB::new(EmptyDB::default());
-        for (address, state) in result.final_states {
-            use revm::state::AccountInfo;
-            use revm::primitives::KECCAK_EMPTY;
-            let info = AccountInfo {
-                balance: state.balance,
-                nonce: state.nonce,
-                code_hash: KECCAK_EMPTY,
-                code: None,
+        // Running as one block for the whole workload, checkpoint at the end of
+        // each workload block
+        if !per_block {
+            let mut end = 0;
+            for len in &block_lens {
+                end += len;
+                config.checkpoints.push(end as TxnIndex);
+            }
+        }
+        let mut executor = engine(config, workload);
+        if self.atomic_blocks {
+            // Replays never reach the engine, but still fail their block
+            let rejected = workload.blocks.iter().zip(&block_lens).map(|(block, &len)| block.len() - len).collect();
+            executor = executor.with_atomic_blocks().with_rejected(rejected);
+        }
+        if record_schedule {
+            executor = executor.with_schedule_recording();
+        }
+        if let Some(schedules) = &self.schedules {
+            executor = executor.with_schedule_replay(schedules.to_vec());
+        }
+        if per_block {
+            let results = executor.execute_blocks_with(blocks, on_block);
+            return Run {
+                block_durations: results.iter().map(|result| result.duration).collect(),
+                schedules: results.iter().filter_map(|result| result.schedule.clone()).collect(),
+                divergences: results.iter().map(|result| result.replay_divergence).collect(),
+                discarded: results
+                    .iter()
+                    .flat_map(|result| std::iter::repeat(result.discarded).take(result.tx_success.len()))
+                    .collect(),
+                result: merge_blocks(results),
+                replay_mask,
+                hashes,
             };
-            final_db.insert_account_info(address, info);
         }
-        
-        let exec_result = ExecutionResult {
-            successful: result.successful,
-            failed: result.failed,
+
+        let transactions = blocks.pop().unwrap_or_else(|| Arc::from(Vec::new()));
+        let result = executor.execute_block(transactions);
+        Run {
+            block_durations: Vec::new(),
+            schedules: result.schedule.clone().into_iter().collect(),
+            divergences: vec![result.replay_divergence],
+            discarded: Vec::new(),
+            result,
+            replay_mask,
+            hashes,
+        }
+    }
+}
+
+/// Non-replayed transactions of a workload in Block-STM format.
+struct EngineBlocks {
+    /// One block per workload block, or a single block for the whole workload.
+    blocks: Vec<Arc<[Transaction]>>,
+    /// Engine transactions in each workload block.
+    block_lens: Vec<usize>,
+    replay_mask: Vec<bool>,
+}
+
+/// Converts the transactions of `workload` to Block-STM format, one engine
+/// block per workload block if `per_block` is set and a single one otherwise,
+/// with their declared dependencies if `declare_dependencies` is set.
+///
+/// Each transaction is converted once, straight into the shared slice the
+/// engine's workers read from.
+fn engine_blocks(workload: &Workload, per_block: bool, declare_dependencies: bool) -> EngineBlocks {
+    // Block-STM has no notion of duplicate transactions, so replays are rejected
+    // up front. Only the first occurrence of a hash or (sender, nonce) pair, or
+    // of an id in nonce-less workloads, is passed on, which keeps rejection
+    // deterministic across thread counts.
+    let replay_mask = workload.replay_mask();
+
+    let mut replays = replay_mask.iter();
+    let block_lens: Vec<usize> = workload
+        .blocks
+        .iter()
+        .map(|block| replays.by_ref().take(block.len()).filter(|&&replay| !replay).count())
+        .collect();
+
+    let mut dependencies = if declare_dependencies {
+        engine_dependencies(workload, &replay_mask, per_block)
+    } else {
+        vec![Vec::new(); replay_mask.len()]
+    }
+    .into_iter();
+
+    let mut replays = replay_mask.iter();
+    let blocks = if per_block {
+        workload
+            .blocks
+            .iter()
+            .map(|block| to_engine(block, &mut replays, &mut dependencies))
+            .collect()
+    } else {
+        vec![to_engine(&workload.transactions, &mut replays, &mut dependencies)]
+    };
+
+    EngineBlocks {
+        blocks,
+        block_lens,
+        replay_mask,
+    }
+}
+
+/// The analyzer's declared dependencies of every workload transaction, as
+/// indices into the engine block it runs in.
+fn engine_dependencies(workload: &Workload, replay_mask: &[bool], per_block: bool) -> Vec<Vec<TxnIndex>> {
+    // Engine block and position of each workload transaction; replays take
+    // no position
+    let mut positions = Vec::with_capacity(replay_mask.len());
+    let mut replays = replay_mask.iter();
+    let mut position = 0;
+    for (block, transactions) in workload.blocks.iter().enumerate() {
+        if per_block {
+            position = 0;
+        }
+        for &replay in replays.by_ref().take(transactions.len()) {
+            positions.push((if per_block { block } else { 0 }, position as TxnIndex));
+            position += usize::from(!replay);
+        }
+    }
+
+    analysis::declared_dependencies(workload)
+        .into_iter()
+        .enumerate()
+        .map(|(index, dependencies)| {
+            let (block, _) = positions[index];
+            dependencies
+                .into_iter()
+                .filter(|&earlier| positions[earlier].0 == block)
+                .map(|earlier| positions[earlier].1)
+                .collect()
+        })
+        .collect()
+}
+
+/// Converts the transactions of `txs` that `replays` does not mark as
+/// replays, each with its entry of `dependencies`.
+///
+/// Note: We pass signature data so verification happens in parallel workers
+fn to_engine(
+    txs: &[SignedTransaction],
+    replays: &mut std::slice::Iter<'_, bool>,
+    dependencies: &mut std::vec::IntoIter<Vec<TxnIndex>>,
+) -> Arc<[Transaction]> {
+    txs.iter()
+        .zip(replays.by_ref())
+        .zip(dependencies.by_ref())
+        .filter(|&((_, &replay), _)| !replay)
+        .map(|((tx, _), dependencies)| Transaction {
+            from: tx.from,
+            to: tx.to,
+            value: tx.value,
+            token: tx.token,
+            nonce: tx.nonce,
+            signature: tx.signature,
+            tx_hash: tx.tx_hash,
+            compute_cost: tx.compute_cost,
+            dependencies,
+        })
+        .collect()
+}
+
+/// Creates the engine for `workload`, reporting to the installed live
+/// progress sink, if any.
+fn engine(config: ExecutorConfig, workload: &Workload) -> ParallelExecutor {
+    let mut executor = ParallelExecutor::new(config);
+    if workload.config.nonceless {
+        executor = executor.with_nonceless(HashSet::new());
+    }
+    match live::installed() {
+        Some(progress) => executor.with_observer(progress.clone()),
+        None => executor,
+    }
+}
+
+impl ExecutionObserver for LiveProgress {
+    fn executed(&self, worker: usize, busy: Duration) {
+        self.add_busy(worker, busy);
+    }
+
+    fn committed(&self, count: usize) {
+        self.add_committed(count);
+    }
+}
+
+/// Merges consecutive per-block results into one result over the whole
+/// workload, with each block's writes as its checkpoint.
+fn merge_blocks(results: Vec<BlockExecutionResult>) -> BlockExecutionResult {
+    let duration: Duration = results.iter().map(|result| result.duration).sum();
+    let num_txs: usize = results.iter().map(|result| result.tx_success.len()).sum();
+    let mut merged = BlockExecutionResult {
+        successful: 0,
+        failed: 0,
+        total_executions: 0,
+        revalidated: 0,
+        suppressed_duplicates: 0,
+        tx_success: Vec::with_capacity(num_txs),
+        final_states: Vec::new(),
+        avg_concurrency: 0.0,
+        max_commit_lag: 0,
+        avg_commit_lag: 0.0,
+        conflicts: Vec::new(),
+        peak_memory_usage: 0,
+        skipped_invalidations: 0,
+        cancelled: false,
+        discarded: false,
+        checkpoint_states: Vec::with_capacity(results.len()),
+        poisoned: Vec::new(),
+        schedule: None,
+        replay_divergence: None,
+        duration,
+    };
+    let mut final_states = HashMap::new();
+    for result in results {
+        let offset = merged.tx_success.len() as TxnIndex;
+        let block_txs = result.tx_success.len();
+
+        merged.successful += result.successful;
+        merged.failed += result.failed;
+        merged.total_executions += result.total_executions;
+        merged.revalidated += result.revalidated;
+        merged.suppressed_duplicates += result.suppressed_duplicates;
+        merged.tx_success.extend(result.tx_success);
+        final_states.extend(result.final_states.iter().cloned());
+        // Averages are weighted by the time or transactions they were taken over
+        if !duration.is_zero() {
+            merged.avg_concurrency += result.avg_concurrency * result.duration.as_secs_f64() / duration.as_secs_f64();
+        }
+        if num_txs > 0 {
+            merged.avg_commit_lag += result.avg_commit_lag * block_txs as f64 / num_txs as f64;
+        }
+        merged.max_commit_lag = merged.max_commit_lag.max(result.max_commit_lag);
+        merged
+            .conflicts
+            .extend(result.conflicts.iter().map(|&(writer, reader)| (writer + offset, reader + offset)));
+        merged.peak_memory_usage = merged.peak_memory_usage.max(result.peak_memory_usage);
+        merged.skipped_invalidations += result.skipped_invalidations;
+        merged.cancelled |= result.cancelled;
+        merged.discarded |= result.discarded;
+        merged.checkpoint_states.push(result.final_states);
+        merged.poisoned.extend(result.poisoned.into_iter().map(|poisoned| PoisonedTransaction {
+            txn_idx: poisoned.txn_idx + offset,
+            ..poisoned
+        }));
+    }
+    merged.final_states = final_states.into_iter().collect();
+    merged
+}
+
+/// Converts an engine run over `workload` into the final database and
+/// per-workload result.
+fn finish(workload: &Workload, run: Run) -> (CacheDB<GenesisState>, ExecutionResult) {
+    let Run {
+        result,
+        block_durations,
+        replay_mask,
+        discarded,
+        ..
+    } = run;
+    let num_replays = replay_mask.iter().filter(|&&replay| replay).count();
+    
+    // Replay the per-block writes over the initial states to checkpoint each block
+    let mut checkpoints = CheckpointRecorder::new(workload, 0);
+    let mut states = initial_states(workload);
+    for writes in &result.checkpoint_states {
+        states.extend(writes.iter().cloned());
+        checkpoints.record_block(|address| {
+            states.get(&address).map(|account| (account.nonce, account.balance))
+        });
+    }
+    
+    // Create a fresh database with final states; revm accounts have no
+    // token balances, so those are dropped
+    let mut final_db = CacheDB::new(workload.genesis().clone());
+    for (address, state) in result.final_states {
+        use revm::state::AccountInfo;
+        let info = AccountInfo {
+            balance: state.balance,
+            nonce: state.nonce,
+            code_hash: state.code_hash,
+            code: None,
         };
-        
-        (final_db, exec_result)
+        final_db.insert_account_info(address, info);
+    }
+    
+    // Map engine outcomes back onto workload positions; replays never reached
+    // the engine and are always failures, and a discarded block fails even
+    // the transactions that succeeded on their own. The engine reports only
+    // whether a transaction succeeded, so only those two are attributed.
+    let mut engine_outcomes = result
+        .tx_success
+        .iter()
+        .zip(discarded.iter().chain(std::iter::repeat(&false)))
+        .map(|(&success, &discarded)| (success, discarded));
+    let mut discarded_successes = 0;
+    let (statuses, reasons): (Vec<TxStatus>, Vec<Option<FailureReason>>) = replay_mask
+        .iter()
+        .map(|&replay| {
+            if replay {
+                return (TxStatus::Failed, Some(FailureReason::Replayed));
+            }
+            match engine_outcomes.next() {
+                Some((true, true)) => {
+                    discarded_successes += 1;
+                    (TxStatus::Failed, Some(FailureReason::BlockDiscarded))
+                }
+                Some((success, _)) => (TxStatus::from(success), None),
+                None => (TxStatus::Failed, None),
+            }
+        })
+        .unzip();
+    let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+    let successful = result.successful - discarded_successes;
+    let failed = result.failed + num_replays + discarded_successes;
+
+    let exec_result = ExecutionResult::new(successful, failed)
+        .with_tx_index(tx_index)
+        .with_parallelism(result.avg_concurrency)
+        .with_scheduler_counters(SchedulerCounters {
+            executions: result.total_executions,
+            revalidated: result.revalidated,
+            suppressed_duplicates: result.suppressed_duplicates,
+            skipped_invalidations: result.skipped_invalidations,
+        })
+        .with_checkpoints(checkpoints.finish())
+        .with_block_durations(block_durations)
+        .with_failure_reasons(reasons);
+    
+    (final_db, exec_result)
+}
+
+/// All accounts start with the workload's initial native and token balances.
+fn initial_states(workload: &Workload) -> HashMap<Address, AccountState> {
+    let initial_balance = workload.initial_balance();
+    let initial_tokens = workload.initial_token_balances();
+    workload
+        .accounts
+        .iter()
+        .map(|account| (account.address, AccountState::new(0, initial_balance).with_tokens(initial_tokens.clone())))
+        .collect()
+}
+
+impl Executor for BlockStmExecutor {
+    type Database = CacheDB<GenesisState>;
+
+    fn execute(
+        &self,
+        _db: Self::Database,
+        workload: &Workload,
+    ) -> (Self::Database, ExecutionResult) {
+        finish(workload, self.run(workload, false))
     }
 
     fn preserves_order(&self) -> bool {
         true // Block-STM maintains strict transaction ordering
     }
 
+    fn supports_tokens(&self) -> bool {
+        true
+    }
+
+    fn supports_nonceless(&self) -> bool {
+        true
+    }
+
     fn name(&self) -> &'static str {
         "block_stm_parallel"
     }
@@ -110,7 +651,8 @@ impl Executor for BlockStmExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Account, WorkloadConfig};
+    use crate::executor::{AnyExecutor, MemorySink, SequentialExecutor};
+    use crate::{Account, ComputeCost, WorkloadConfig};
 
     #[test]
     fn test_block_stm_executor() {
@@ -123,6 +665,7 @@ mod tests {
             hot_accounts: 100,
             seed: 42,
             chain_id: 1,
+            ..Default::default()
         };
         
         let workload = Workload::generate(config);
@@ -134,5 +677,311 @@ mod tests {
         assert_eq!(result.successful, 20);
         assert_eq!(result.failed, 0);
     }
-}
 
+    #[test]
+    fn test_block_stm_reports_scheduler_counters() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 40,
+            transactions_per_block: 20,
+            hot_accounts: 2,
+            fake_signatures: true,
+            ..Default::default()
+        });
+        
+        let report = AnyExecutor::execute_workload(&BlockStmExecutor::new(4, false), &workload);
+        
+        let scheduler = report.result.scheduler.expect("Block-STM reports its scheduler counters");
+        assert!(scheduler.executions + scheduler.revalidated >= 40);
+        assert!(report.details.contains(&scheduler.to_string()));
+    }
+
+    #[test]
+    fn test_block_stm_rejects_replays() {
+        let executor = BlockStmExecutor::new(4, true);
+
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 30,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            duplicate_transactions: 3,
+            double_spends: 3,
+            fake_signatures: false,
+            tokens: 0,
+            nonceless: false,
+            compute_cost: ComputeCost::default(),
+            near_max_balance: None,
+            hot_probability: 1.0,
+        };
+
+        let workload = Workload::generate(config);
+        let db = workload.create_db();
+
+        let (_, result) = executor.execute(db, &workload);
+
+        assert_eq!(result.successful, 30);
+        assert_eq!(result.failed, 6);
+        assert_eq!(result.failure_breakdown(), vec![(FailureReason::Replayed, 6)]);
+
+        // Fee-normalized checkpoints match the revm-based sequential executor,
+        // which sees the replays as reused nonces
+        let (_, expected) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+        assert_eq!(expected.failure_breakdown(), vec![(FailureReason::NonceTooLow, 6)]);
+        assert_eq!(expected.unattributed_failures(), 0);
+        assert_eq!(result.checkpoints.len(), workload.blocks.len());
+        assert_eq!(result.checkpoints, expected.checkpoints);
+    }
+
+    #[test]
+    fn test_block_stm_block_boundaries() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 30,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            seed: 7,
+            chain_id: 1,
+            duplicate_transactions: 2,
+            double_spends: 2,
+            fake_signatures: false,
+            tokens: 0,
+            nonceless: false,
+            compute_cost: ComputeCost::default(),
+            near_max_balance: None,
+            hot_probability: 1.0,
+        };
+        let workload = Workload::generate(config);
+
+        let (_, flat) = BlockStmExecutor::new(4, true).execute(workload.create_db(), &workload);
+        let (_, per_block) = BlockStmExecutor::new(4, true)
+            .with_block_boundaries()
+            .execute(workload.create_db(), &workload);
+
+        assert_eq!(per_block.successful, flat.successful);
+        assert_eq!(per_block.failed, flat.failed);
+        assert_eq!(per_block.checkpoints, flat.checkpoints);
+        assert_eq!(per_block.block_durations.len(), workload.blocks.len());
+        assert!(flat.block_durations.is_empty());
+    }
+
+    #[test]
+    fn test_block_stm_atomic_blocks_discard_replays() {
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 40,
+            hot_accounts: 20,
+            transactions_per_block: 10,
+            double_spends: 2,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+        let (_, result) = BlockStmExecutor::new(4, true)
+            .with_atomic_blocks()
+            .execute(workload.create_db(), &workload);
+
+        // The first block holding a replay is rolled back whole, as MDBX batched does
+        let replays = workload.replay_mask();
+        let mut start = 0;
+        let first_failed = workload
+            .blocks
+            .iter()
+            .position(|block| {
+                start += block.len();
+                replays[start - block.len()..start].contains(&true)
+            })
+            .unwrap();
+        let first_start = start - workload.blocks[first_failed].len();
+        assert!(result.failure_reasons[..first_start].iter().all(Option::is_none));
+        let reasons = &result.failure_reasons[first_start..start];
+        assert!(reasons.iter().all(Option::is_some));
+        assert!(reasons.contains(&Some(FailureReason::BlockDiscarded)));
+        assert_eq!(result.successful + result.failed, workload.transactions.len());
+    }
+
+    #[test]
+    fn test_block_stm_pipelined() {
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 40,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            seed: 7,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+        let executor = BlockStmExecutor::new(4, true).with_block_boundaries();
+
+        let latency = Duration::from_millis(5);
+        let mut sink = MemorySink::new().with_commit_latency(latency);
+        let (db, pipelined) = executor.execute_pipelined(&workload, &mut sink).unwrap();
+        let (_, expected) = executor.execute(workload.create_db(), &workload);
+
+        assert_eq!(pipelined.result.successful, expected.successful);
+        assert_eq!(pipelined.result.checkpoints, expected.checkpoints);
+        assert_eq!(pipelined.stats.blocks, workload.blocks.len());
+        assert!(pipelined.stats.commit_time >= latency * workload.blocks.len() as u32);
+
+        // The sink holds the final state of every account the workload wrote
+        assert_eq!(sink.blocks, workload.blocks.len());
+        assert!(!sink.accounts.is_empty());
+        for (address, state) in &sink.accounts {
+            let info = &db.cache.accounts[address].info;
+            assert_eq!((info.nonce, info.balance), (state.nonce, state.balance));
+        }
+    }
+
+    #[test]
+    fn test_block_stm_declared_dependencies() {
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 40,
+            transactions_per_block: 10,
+            hot_accounts: 3,
+            double_spends: 3,
+            seed: 7,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+        let (_, expected) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+
+        for executor in [BlockStmExecutor::new(4, true), BlockStmExecutor::new(4, true).with_block_boundaries()] {
+            let executor = executor.with_declared_dependencies();
+            let (_, result) = executor.execute(workload.create_db(), &workload);
+            assert_eq!(result.successful, expected.successful);
+            assert_eq!(result.checkpoints, expected.checkpoints);
+
+            // Three hot accounts chain every applied transaction, so no read
+            // goes stale
+            assert!(executor.profile_conflicts(&workload).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_block_stm_nonceless() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 30,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            duplicate_transactions: 2,
+            double_spends: 2,
+            nonceless: true,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let (db, result) = BlockStmExecutor::new(4, true).execute(workload.create_db(), &workload);
+
+        assert_eq!(result.successful, 30);
+        assert_eq!(result.failed, 4);
+        assert!(db.cache.accounts.values().all(|account| account.info.nonce == 0));
+    }
+
+    #[test]
+    fn test_block_stm_nonce_gaps() {
+        let mut workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 60,
+            transactions_per_block: 20,
+            hot_accounts: 3,
+            ..Default::default()
+        });
+        workload.mutate(&[&crate::mutation::Mutation::DropTransactions(0.2)], 7);
+
+        // Transactions past a gap commit as failed instead of retrying forever
+        let (_, result) = BlockStmExecutor::new(4, true).execute(workload.create_db(), &workload);
+        let (_, expected) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+        assert!(expected.failed > 0);
+        assert_eq!((result.successful, result.failed), (expected.successful, expected.failed));
+    }
+
+    #[test]
+    fn test_block_stm_near_max_balances() {
+        let config = WorkloadConfig {
+            num_accounts: 2,
+            num_transactions: 30,
+            transactions_per_block: 10,
+            hot_accounts: 2,
+            nonceless: true,
+            near_max_balance: Some(2),
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        // In order, a transfer fails exactly when it would push the receiver past U256::MAX
+        let mut balances: HashMap<Address, U256> = workload
+            .accounts
+            .iter()
+            .map(|account| (account.address, workload.initial_balance()))
+            .collect();
+        let mut expected_failed = 0;
+        for tx in &workload.transactions {
+            match balances[&tx.to].checked_add(tx.value) {
+                Some(balance) => {
+                    balances.insert(tx.to, balance);
+                    *balances.get_mut(&tx.from).unwrap() -= tx.value;
+                }
+                None => expected_failed += 1,
+            }
+        }
+        assert!(expected_failed > 0);
+
+        let (db, result) = BlockStmExecutor::new(4, true).execute(workload.create_db(), &workload);
+        assert_eq!(result.failed, expected_failed);
+        for (address, balance) in balances {
+            assert_eq!(db.cache.accounts[&address].info.balance, balance);
+        }
+    }
+
+    #[test]
+    fn test_block_stm_schedule_replay() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 30,
+            transactions_per_block: 10,
+            hot_accounts: 3,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let schedules = BlockStmExecutor::new(4, false)
+            .with_block_boundaries()
+            .record_schedules(&workload);
+        assert_eq!(schedules.len(), workload.blocks.len());
+
+        let (result, divergences) = BlockStmExecutor::new(4, false)
+            .with_block_boundaries()
+            .with_schedule_replay(Arc::new(schedules))
+            .execute_replay(&workload);
+        let (_, expected) = BlockStmExecutor::new(4, false).execute(workload.create_db(), &workload);
+        assert_eq!(divergences.len(), workload.blocks.len());
+        assert_eq!(result.checkpoints, expected.checkpoints);
+    }
+
+    #[test]
+    fn test_block_stm_simulate() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 30,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            duplicate_transactions: 2,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+        let costs = vec![100; workload.transactions.len()];
+
+        let report = BlockStmExecutor::new(4, false)
+            .with_block_boundaries()
+            .simulate(&workload, SchedulingPolicy::DependencyAware, &costs);
+
+        // Replays never reach the engine
+        assert_eq!(report.num_txns, 30);
+        assert_eq!(report.sequential_time, 3000);
+        assert_eq!(report.executions, 30);
+        assert!(report.makespan >= 3000 / 4);
+    }
+}
@@ -0,0 +1,136 @@
+//! Per-block state checkpoints.
+//!
+//! A checkpoint is a keccak hash over the state of every account a block
+//! touched, taken after the block executes. Executors that emit one per
+//! block let two runs be compared block by block, so a divergence can be
+//! located without diffing full state.
+//!
+//! Executors disagree on fees: the revm-based ones charge 21,000 gas at a
+//! gas price of 1 per transaction while the others charge nothing. Balances
+//! are therefore hashed as if no fees were charged, adding back
+//! `fee_per_tx` for every transaction the account has sent (its nonce).
+
+use crate::Workload;
+use alloy_primitives::{keccak256, Address, B256, U256};
+use std::convert::Infallible;
+
+/// Fee charged per transaction by executors that run transfers through revm.
+pub const REVM_TRANSFER_FEE: u64 = 21_000;
+
+/// Builds the checkpoint hashes of one run, block by block.
+#[derive(Debug, Clone)]
+pub struct CheckpointRecorder {
+    fee_per_tx: U256,
+    /// Sorted, deduplicated senders and receivers of each block.
+    touched: Vec<Vec<Address>>,
+    checkpoints: Vec<B256>,
+}
+
+impl CheckpointRecorder {
+    /// Creates a recorder for `workload`, normalizing balances by `fee_per_tx`.
+    pub fn new(workload: &Workload, fee_per_tx: u64) -> Self {
+        let touched = workload
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut accounts: Vec<Address> = block.iter().flat_map(|tx| [tx.from, tx.to]).collect();
+                accounts.sort_unstable();
+                accounts.dedup();
+                accounts
+            })
+            .collect();
+        Self {
+            fee_per_tx: U256::from(fee_per_tx),
+            touched,
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// Accounts touched by the next block to be recorded.
+    pub fn next_touched(&self) -> &[Address] {
+        self.touched.get(self.checkpoints.len()).map_or(&[], Vec::as_slice)
+    }
+
+    /// Records the next block, reading each touched account's `(nonce, balance)`
+    /// through `state`; missing accounts hash as empty. Returns the checkpoint.
+    pub fn record_block(&mut self, mut state: impl FnMut(Address) -> Option<(u64, U256)>) -> B256 {
+        match self.try_record_block(|address| Ok::<_, Infallible>(state(address))) {
+            Ok(checkpoint) => checkpoint,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Like [`record_block`](Self::record_block), for backends whose reads can fail.
+    pub fn try_record_block<E>(
+        &mut self,
+        mut state: impl FnMut(Address) -> Result<Option<(u64, U256)>, E>,
+    ) -> Result<B256, E> {
+        let mut data = Vec::new();
+        for &address in self.next_touched() {
+            let (nonce, balance) = state(address)?.unwrap_or((0, U256::ZERO));
+            let balance = balance + self.fee_per_tx * U256::from(nonce);
+            data.extend_from_slice(address.as_slice());
+            data.extend_from_slice(&nonce.to_be_bytes());
+            data.extend_from_slice(&balance.to_be_bytes::<32>());
+        }
+        let checkpoint = keccak256(&data);
+        self.checkpoints.push(checkpoint);
+        Ok(checkpoint)
+    }
+
+    /// Checkpoint hashes recorded so far, one per block.
+    pub fn finish(self) -> Vec<B256> {
+        self.checkpoints
+    }
+}
+
+/// Index of the first block whose checkpoints differ, if any.
+///
+/// Only blocks both runs recorded are compared.
+pub fn first_divergent_block(expected: &[B256], actual: &[B256]) -> Option<usize> {
+    expected.iter().zip(actual).position(|(expected, actual)| expected != actual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkloadConfig;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_fees_are_normalized() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            fake_signatures: true,
+            ..Default::default()
+        });
+        let initial = U256::from(1_000_000u64);
+        let run = |fee: u64| {
+            let mut state: HashMap<Address, (u64, U256)> =
+                workload.accounts.iter().map(|account| (account.address, (0, initial))).collect();
+            let mut recorder = CheckpointRecorder::new(&workload, fee);
+            for block in &workload.blocks {
+                for tx in block {
+                    let sender = state[&tx.from];
+                    state.insert(tx.from, (sender.0 + 1, sender.1 - tx.value - U256::from(fee)));
+                    let receiver = state.get(&tx.to).copied().unwrap_or_default();
+                    state.insert(tx.to, (receiver.0, receiver.1 + tx.value));
+                }
+                recorder.record_block(|address| state.get(&address).copied());
+            }
+            recorder.finish()
+        };
+
+        let free = run(0);
+        assert_eq!(free.len(), 2);
+        assert_eq!(run(REVM_TRANSFER_FEE), free);
+
+        let mut diverged = free.clone();
+        diverged[1] = B256::ZERO;
+        assert_eq!(first_divergent_block(&free, &free), None);
+        assert_eq!(first_divergent_block(&free, &diverged), Some(1));
+    }
+}
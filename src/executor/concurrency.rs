@@ -0,0 +1,181 @@
+//! Per-run concurrency statistics.
+//!
+//! [`ConcurrencyStats`] relates what an executor achieved to what the
+//! workload allowed. The workload bound comes from the transactions that
+//! actually committed: each transfer writes its sender and receiver, so the
+//! longest chain of transactions linked through shared accounts has to run
+//! serially, and no schedule can average more than `committed / chain`
+//! transactions in flight. Achieved parallelism is sampled by executors that
+//! support it (see [`ExecutionResult::parallelism`]).
+//!
+//! When achieved parallelism is close to the workload bound the workload
+//! limited the speedup; when it is close to the thread count, the hardware
+//! did; anything well below both points at the executor.
+
+use super::{AnyExecutor, ExecutionReport, ExecutionResult};
+use crate::Workload;
+use alloy_primitives::Address;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Concurrency metrics for one executor run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConcurrencyStats {
+    /// Committed transactions.
+    pub committed: usize,
+    /// Distinct senders among committed transactions.
+    pub distinct_senders: usize,
+    /// Longest chain of committed transactions that touch a common account.
+    pub max_chain_length: usize,
+    /// Sampled average of concurrently executing transactions, if measured.
+    pub parallelism: Option<f64>,
+}
+
+impl ConcurrencyStats {
+    /// Computes statistics for `result`, an execution of `workload`.
+    ///
+    /// Executors that do not index their outcomes are assumed to have
+    /// committed every transaction except injected replays.
+    pub fn measure(workload: &Workload, result: &ExecutionResult) -> Self {
+        let mut senders = HashSet::new();
+        let mut depths: HashMap<Address, usize> = HashMap::new();
+        let mut committed = 0;
+        let mut max_chain_length = 0;
+
+        for (tx, success) in workload.transactions.iter().zip(committed_mask(workload, result)) {
+            if !success {
+                continue;
+            }
+
+            committed += 1;
+            senders.insert(tx.from);
+            let depth = 1 + depths.get(&tx.from).max(depths.get(&tx.to)).copied().unwrap_or(0);
+            depths.insert(tx.from, depth);
+            depths.insert(tx.to, depth);
+            max_chain_length = max_chain_length.max(depth);
+        }
+
+        Self {
+            committed,
+            distinct_senders: senders.len(),
+            max_chain_length,
+            parallelism: result.parallelism,
+        }
+    }
+
+    /// Highest average parallelism the committed transactions allow.
+    pub fn workload_bound(&self) -> f64 {
+        if self.max_chain_length == 0 {
+            0.0
+        } else {
+            self.committed as f64 / self.max_chain_length as f64
+        }
+    }
+}
+
+/// Which workload transactions `result` committed, by position.
+pub(crate) fn committed_mask(workload: &Workload, result: &ExecutionResult) -> Vec<bool> {
+    if result.tx_index.is_empty() {
+        return workload.replay_mask().into_iter().map(|replay| !replay).collect();
+    }
+    let per_block = workload.transactions_per_block().max(1);
+    workload
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(position, tx)| {
+            let (block, index) = (position / per_block, position % per_block);
+            result
+                .tx_index
+                .occurrences(&tx.tx_hash)
+                .iter()
+                .any(|location| location.block == block && location.index == index && location.status.is_success())
+        })
+        .collect()
+}
+
+impl fmt::Display for ConcurrencyStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "senders: {}, max chain: {} (workload bound {:.1}x), parallelism: ",
+            self.distinct_senders,
+            self.max_chain_length,
+            self.workload_bound()
+        )?;
+        match self.parallelism {
+            Some(parallelism) => write!(f, "{:.2}", parallelism),
+            None => f.write_str("not sampled"),
+        }
+    }
+}
+
+/// Adds a [`ConcurrencyStats`] detail line to every run of the wrapped executor.
+pub struct WithConcurrencyStats {
+    inner: Box<dyn AnyExecutor>,
+}
+
+impl WithConcurrencyStats {
+    /// Wraps `inner`.
+    pub fn new(inner: Box<dyn AnyExecutor>) -> Self {
+        Self { inner }
+    }
+}
+
+impl AnyExecutor for WithConcurrencyStats {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn preserves_order(&self) -> bool {
+        self.inner.preserves_order()
+    }
+
+    fn supports_tokens(&self) -> bool {
+        self.inner.supports_tokens()
+    }
+
+    fn supports_nonceless(&self) -> bool {
+        self.inner.supports_nonceless()
+    }
+
+    fn supports_large_balances(&self) -> bool {
+        self.inner.supports_large_balances()
+    }
+
+    fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+        let report = self.inner.execute_workload(workload);
+        let stats = ConcurrencyStats::measure(workload, &report.result);
+        report.with_detail(stats.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::SequentialExecutor;
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_chain_length_bounds_parallelism() {
+        // Two hot accounts: every transfer touches both, so the chain is the whole workload
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 2,
+            ..Default::default()
+        });
+        let report = SequentialExecutor::with_verification(false).execute_workload(&workload);
+        let stats = ConcurrencyStats::measure(&workload, &report.result);
+
+        assert_eq!(stats.committed, 20);
+        assert_eq!(stats.distinct_senders, 2);
+        assert_eq!(stats.max_chain_length, 20);
+        assert_eq!(stats.workload_bound(), 1.0);
+        assert_eq!(stats.parallelism, None);
+
+        let executor = WithConcurrencyStats::new(Box::new(SequentialExecutor::with_verification(false)));
+        assert_eq!(executor.execute_workload(&workload).details.len(), 1);
+    }
+}
@@ -0,0 +1,530 @@
+//! Conditional-write executor modelling DynamoDB-style key-value stores.
+//!
+//! Cloud KV stores such as DynamoDB offer single-item conditional puts
+//! (compare-and-swap on a version attribute) but no cheap multi-key
+//! transactions. A transfer therefore has to be choreographed as a sequence of
+//! independent conditional writes:
+//!
+//! 1. **Debit**: bump the sender's nonce, subtract the value, and mark the
+//!    transfer as pending on the sender item
+//! 2. **Credit**: add the value to the receiver
+//! 3. **Release**: clear the pending marker so the sender can transfer again
+//!
+//! Each step retries on condition failure. The pending marker serializes a
+//! sender's transfers the same way a real application would guard against
+//! partially-applied choreographies.
+//!
+//! The [`ConditionalStore`] trait is the seam for plugging in a DynamoDB Local
+//! client; [`InMemoryConditionalStore`] emulates the semantics locally, with
+//! optional per-operation latency to approximate a network hop.
+
+use alloy_primitives::{Address, B256, U256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use super::{ExecutionResult, TransactionIndex, TxStatus};
+use crate::recovery::RecoveryBackend;
+use crate::sharding;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::{SignedTransaction, Workload};
+
+/// Number of lock shards in the in-memory emulation.
+const NUM_SHARDS: usize = 64;
+
+/// Attributes stored for each account item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountItem {
+    /// Account nonce.
+    pub nonce: u64,
+    /// Account balance in wei.
+    pub balance: U256,
+    /// Hash of a debited transfer whose credit has not been released yet.
+    pub pending: Option<B256>,
+}
+
+/// An item together with its version attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionedItem {
+    /// The stored attributes.
+    pub item: AccountItem,
+    /// Version attribute, incremented on every successful write.
+    pub version: u64,
+}
+
+/// Error returned when a conditional write's expected version does not match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConditionFailed;
+
+/// Key-value store offering single-item conditional writes.
+pub trait ConditionalStore: Sync {
+    /// Reads an item and its version.
+    fn get(&self, address: &Address) -> Option<VersionedItem>;
+
+    /// Writes an item if its current version equals `expected_version`.
+    ///
+    /// `None` means the item must not exist yet (`attribute_not_exists`).
+    /// Returns the new version on success.
+    fn put_if_version(
+        &self,
+        address: Address,
+        item: AccountItem,
+        expected_version: Option<u64>,
+    ) -> Result<u64, ConditionFailed>;
+}
+
+/// In-memory emulation of a conditional-write KV store.
+#[derive(Debug)]
+pub struct InMemoryConditionalStore {
+    shards: Vec<Mutex<HashMap<Address, VersionedItem>>>,
+    /// Artificial delay added to every operation.
+    latency: Duration,
+}
+
+impl InMemoryConditionalStore {
+    /// Creates an empty store with no artificial latency.
+    pub fn new() -> Self {
+        Self {
+            shards: (0..NUM_SHARDS).map(|_| Mutex::new(HashMap::new())).collect(),
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// Adds an artificial delay to every read and write.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Initializes the store with pre-funded accounts.
+    pub fn init_accounts(&self, accounts: &[(Address, U256)]) {
+        for &(address, balance) in accounts {
+            let item = AccountItem {
+                nonce: 0,
+                balance,
+                pending: None,
+            };
+            self.shard(&address)
+                .lock()
+                .expect("shard lock poisoned")
+                .insert(address, VersionedItem { item, version: 0 });
+        }
+    }
+
+    fn shard(&self, address: &Address) -> &Mutex<HashMap<Address, VersionedItem>> {
+        &self.shards[sharding::range_shard(*address, NUM_SHARDS)]
+    }
+
+    fn simulate_latency(&self) {
+        if !self.latency.is_zero() {
+            thread::sleep(self.latency);
+        }
+    }
+}
+
+impl Default for InMemoryConditionalStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConditionalStore for InMemoryConditionalStore {
+    fn get(&self, address: &Address) -> Option<VersionedItem> {
+        self.simulate_latency();
+        self.shard(address)
+            .lock()
+            .expect("shard lock poisoned")
+            .get(address)
+            .copied()
+    }
+
+    fn put_if_version(
+        &self,
+        address: Address,
+        item: AccountItem,
+        expected_version: Option<u64>,
+    ) -> Result<u64, ConditionFailed> {
+        self.simulate_latency();
+        let mut shard = self.shard(&address).lock().expect("shard lock poisoned");
+
+        let current_version = shard.get(&address).map(|existing| existing.version);
+        if current_version != expected_version {
+            return Err(ConditionFailed);
+        }
+
+        let version = current_version.map_or(0, |v| v + 1);
+        shard.insert(address, VersionedItem { item, version });
+        Ok(version)
+    }
+}
+
+/// Result of executing a workload with conditional writes.
+#[derive(Debug, Clone)]
+pub struct ConditionalWriteResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// Number of successful conditional writes (three per successful transfer).
+    pub writes: usize,
+    /// Number of conditional writes rejected because the version had moved on.
+    pub condition_failures: usize,
+    /// Number of times a transfer waited for an earlier nonce, balance or pending transfer.
+    pub waits: usize,
+}
+
+/// Counters shared by worker threads.
+#[derive(Debug, Default)]
+struct Counters {
+    writes: AtomicUsize,
+    condition_failures: AtomicUsize,
+    waits: AtomicUsize,
+}
+
+/// Parallel executor built only on single-item conditional writes.
+///
+/// Like the FoundationDB executor, transactions are split across threads and do
+/// not preserve strict ordering; a transfer whose nonce is ahead of the sender,
+/// or whose sender cannot afford it yet, waits until the earlier transfers
+/// touching the sender have resolved, and fails if it is still invalid then.
+/// Replays are rejected before dispatch.
+///
+/// # Example
+///
+/// ```
+/// use db_test::executor::{ConditionalWriteExecutor, InMemoryConditionalStore};
+/// use db_test::{Workload, WorkloadConfig};
+///
+/// let workload = Workload::generate(WorkloadConfig {
+///     num_accounts: 20,
+///     num_transactions: 20,
+///     ..Default::default()
+/// });
+/// let store = InMemoryConditionalStore::new();
+/// let executor = ConditionalWriteExecutor::new(4, true);
+/// let result = executor.execute_workload(&store, &workload);
+/// assert_eq!(result.result.successful, 20);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConditionalWriteExecutor {
+    num_threads: usize,
+    verify_signatures: bool,
+    recovery: RecoveryBackend,
+}
+
+impl ConditionalWriteExecutor {
+    /// Creates a new conditional-write executor.
+    ///
+    /// # Arguments
+    /// * `num_threads` - Number of threads issuing conditional writes
+    /// * `verify_signatures` - Whether to verify transaction signatures
+    pub fn new(num_threads: usize, verify_signatures: bool) -> Self {
+        Self {
+            num_threads: num_threads.max(1),
+            verify_signatures,
+            recovery: RecoveryBackend::default(),
+        }
+    }
+
+    /// Uses `recovery` to recover signers when verifying signatures.
+    pub fn with_recovery(mut self, recovery: RecoveryBackend) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Executes a workload, first seeding the store with any missing workload accounts.
+    pub fn execute_workload<S: ConditionalStore>(
+        &self,
+        store: &S,
+        workload: &Workload,
+    ) -> ConditionalWriteResult {
+        let initial_balance = workload.initial_balance();
+        for account in &workload.accounts {
+            let item = AccountItem {
+                nonce: 0,
+                balance: initial_balance,
+                pending: None,
+            };
+            // Fresh stores only: an existing item is left untouched.
+            let _ = store.put_if_version(account.address, item, None);
+        }
+
+        let replay_mask = workload.replay_mask();
+        let positions: Vec<usize> = (0..workload.transactions.len())
+            .filter(|&position| !replay_mask[position])
+            .collect();
+        // No per-block checkpoints: chunks span blocks and threads race, so
+        // the store never holds exactly the state after a given block
+        let chunk_size = positions.len().div_ceil(self.num_threads).max(1);
+        let counters = Counters::default();
+
+        // Positions touching each account, in workload order, and whether each
+        // position has resolved; transfers only wait on earlier positions, so
+        // every wait ends
+        let mut touching: HashMap<Address, Vec<usize>> = HashMap::new();
+        for &position in &positions {
+            let tx = &workload.transactions[position];
+            touching.entry(tx.from).or_default().push(position);
+            if tx.to != tx.from {
+                touching.entry(tx.to).or_default().push(position);
+            }
+        }
+        let resolved: Vec<AtomicBool> = (0..workload.transactions.len()).map(|_| AtomicBool::new(false)).collect();
+
+        let outcomes: Vec<(usize, bool)> = thread::scope(|scope| {
+            let handles: Vec<_> = positions
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let counters = &counters;
+                    let touching = &touching;
+                    let resolved = &resolved;
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|&position| {
+                                let tx = &workload.transactions[position];
+                                let sender = &touching[&tx.from];
+                                let earlier = &sender[..sender.partition_point(|&other| other < position)];
+                                let success = self.execute_transfer(store, tx, counters, earlier, resolved);
+                                resolved[position].store(true, Ordering::Release);
+                                (position, success)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("worker thread panicked"))
+                .collect()
+        });
+
+        let mut statuses = vec![TxStatus::Failed; workload.transactions.len()];
+        for (position, success) in outcomes {
+            statuses[position] = TxStatus::from(success);
+        }
+        let successful = statuses.iter().filter(|status| status.is_success()).count();
+        let failed = statuses.len() - successful;
+
+        ConditionalWriteResult {
+            result: ExecutionResult::new(successful, failed)
+                .with_tx_index(TransactionIndex::from_statuses(workload, &statuses)),
+            writes: counters.writes.into_inner(),
+            condition_failures: counters.condition_failures.into_inner(),
+            waits: counters.waits.into_inner(),
+        }
+    }
+
+    /// Runs the debit / credit / release choreography for one transfer.
+    ///
+    /// `earlier` are the positions before this transfer's that touch its
+    /// sender, and `resolved` tells which positions have finished.
+    fn execute_transfer<S: ConditionalStore>(
+        &self,
+        store: &S,
+        tx: &SignedTransaction,
+        counters: &Counters,
+        earlier: &[usize],
+        resolved: &[AtomicBool],
+    ) -> bool {
+        if self.verify_signatures && validate_stateless(tx, self.recovery).is_err() {
+            return false;
+        }
+        tx.burn_compute();
+
+        // Step 1: debit the sender and mark the transfer as pending.
+        loop {
+            // Checked before the read: once every earlier transfer touching the
+            // sender has resolved, nothing left can fix a nonce gap or a short
+            // balance
+            let settled = earlier.iter().all(|&position| resolved[position].load(Ordering::Acquire));
+            let Some(sender) = store.get(&tx.from) else {
+                return false;
+            };
+            match validate_stateful(tx, Some(SenderState::new(sender.item.nonce, sender.item.balance))) {
+                Ok(()) if sender.item.pending.is_none() => {}
+                // Another transfer from this sender has not been released yet.
+                Ok(()) => {
+                    counters.waits.fetch_add(1, Ordering::Relaxed);
+                    thread::sleep(Duration::from_micros(100));
+                    continue;
+                }
+                // An earlier transfer touching the sender may still fix this one.
+                Err(err) if err.may_become_valid() && !settled => {
+                    counters.waits.fetch_add(1, Ordering::Relaxed);
+                    thread::sleep(Duration::from_micros(100));
+                    continue;
+                }
+                Err(_) => return false,
+            }
+
+            let (Some(nonce), Some(balance)) =
+                (sender.item.nonce.checked_add(1), sender.item.balance.checked_sub(tx.value))
+            else {
+                return false;
+            };
+            let debited = AccountItem {
+                nonce,
+                balance,
+                pending: Some(tx.tx_hash),
+            };
+            if Self::record(store.put_if_version(tx.from, debited, Some(sender.version)), counters) {
+                break;
+            }
+        }
+
+        // Step 2: credit the receiver, unless its balance would overflow.
+        let credited = loop {
+            let receiver = store.get(&tx.to);
+            let credited = match receiver {
+                Some(existing) => match existing.item.balance.checked_add(tx.value) {
+                    Some(balance) => AccountItem {
+                        balance,
+                        ..existing.item
+                    },
+                    None => break false,
+                },
+                None => AccountItem {
+                    nonce: 0,
+                    balance: tx.value,
+                    pending: None,
+                },
+            };
+            let expected = receiver.map(|existing| existing.version);
+            if Self::record(store.put_if_version(tx.to, credited, expected), counters) {
+                break true;
+            }
+        };
+
+        // Step 3: release the sender's pending marker, undoing the debit if
+        // the credit overflowed. The marker kept other transfers from this
+        // sender out, so only credits can have landed since the debit.
+        loop {
+            let sender = store.get(&tx.from).expect("sender was debited");
+            let released = if credited {
+                AccountItem {
+                    pending: None,
+                    ..sender.item
+                }
+            } else {
+                AccountItem {
+                    nonce: sender.item.nonce.saturating_sub(1),
+                    balance: sender.item.balance.saturating_add(tx.value),
+                    pending: None,
+                }
+            };
+            if Self::record(store.put_if_version(tx.from, released, Some(sender.version)), counters) {
+                break;
+            }
+        }
+
+        credited
+    }
+
+    /// Updates counters for a conditional write and returns whether it succeeded.
+    fn record(result: Result<u64, ConditionFailed>, counters: &Counters) -> bool {
+        match result {
+            Ok(_) => {
+                counters.writes.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(ConditionFailed) => {
+                counters.condition_failures.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    /// Returns whether this executor preserves transaction ordering.
+    pub fn preserves_order(&self) -> bool {
+        false // Threads race on conditional writes
+    }
+
+    /// Returns the name of this executor.
+    pub fn name(&self) -> &'static str {
+        "conditional_write"
+    }
+
+    /// Returns the number of threads.
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor, WorkloadConfig};
+
+    #[test]
+    fn test_conditional_put_semantics() {
+        let store = InMemoryConditionalStore::new();
+        let addr = Address::with_last_byte(1);
+        let item = AccountItem {
+            nonce: 0,
+            balance: U256::from(10),
+            pending: None,
+        };
+
+        assert_eq!(store.put_if_version(addr, item, None), Ok(0));
+        assert_eq!(store.put_if_version(addr, item, None), Err(ConditionFailed));
+        assert_eq!(store.put_if_version(addr, item, Some(0)), Ok(1));
+        assert_eq!(store.put_if_version(addr, item, Some(0)), Err(ConditionFailed));
+        assert_eq!(store.get(&addr).unwrap().version, 1);
+    }
+
+    #[test]
+    fn test_conditional_write_executor_with_contention() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 60,
+            hot_accounts: 3,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 20,
+            double_spends: 2,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let store = InMemoryConditionalStore::new();
+        let executor = ConditionalWriteExecutor::new(4, true);
+        let result = executor.execute_workload(&store, &workload);
+
+        assert_eq!(result.result.successful, 60);
+        assert_eq!(result.result.failed, 2);
+        assert_eq!(result.writes, 3 * 60);
+
+        // Balances are conserved across the choreography.
+        let total: U256 = workload
+            .accounts
+            .iter()
+            .map(|acc| store.get(&acc.address).unwrap().item.balance)
+            .fold(U256::ZERO, |acc, balance| acc + balance);
+        assert_eq!(
+            total,
+            U256::from(1_000_000_000_000_000_000_000u128) * U256::from(10)
+        );
+    }
+
+    #[test]
+    fn test_conditional_write_executor_with_nonce_gaps() {
+        let mut workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 60,
+            hot_accounts: 3,
+            transactions_per_block: 20,
+            ..Default::default()
+        });
+        workload.mutate(&[&crate::mutation::Mutation::DropTransactions(0.2)], 7);
+
+        // Transfers past a gap fail once everything before them resolved,
+        // as they do in order, instead of waiting forever
+        let store = InMemoryConditionalStore::new();
+        let result = ConditionalWriteExecutor::new(4, true).execute_workload(&store, &workload).result;
+        let (_, expected) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+        assert!(expected.failed > 0);
+        assert_eq!((result.successful, result.failed), (expected.successful, expected.failed));
+    }
+}
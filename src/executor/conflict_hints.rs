@@ -0,0 +1,109 @@
+//! Conflict hints recorded from one run and replayed in the next.
+//!
+//! A profiling run of Block-STM records every write that invalidated a
+//! speculative read. [`ConflictHints`] stores those pairs by transaction hash
+//! so they survive re-generation of the same workload, and a later run feeds
+//! them back to the scheduler as predicted dependencies: the reader is not
+//! scheduled until the writer has executed, avoiding the wasted incarnation.
+//!
+//! The file format is one `writer_hash reader_hash` pair per line, with `#`
+//! starting a comment.
+
+use alloy_primitives::B256;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Observed `(writer, reader)` conflicts, keyed by transaction hash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictHints {
+    pub pairs: Vec<(B256, B256)>,
+}
+
+impl ConflictHints {
+    /// Number of recorded pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns true if no conflicts were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Appends the pairs of `other`, skipping ones already present.
+    pub fn merge(&mut self, other: ConflictHints) {
+        self.pairs.extend(other.pairs);
+        self.pairs.sort_unstable();
+        self.pairs.dedup();
+    }
+
+    /// Resolves the hints to `(earlier, later)` positions in `hashes`, ignoring
+    /// hashes that do not occur and pairs whose order is reversed.
+    pub fn to_positions(&self, hashes: &[B256]) -> Vec<(usize, usize)> {
+        let mut positions = HashMap::with_capacity(hashes.len());
+        for (position, hash) in hashes.iter().enumerate() {
+            positions.entry(*hash).or_insert(position);
+        }
+
+        self.pairs
+            .iter()
+            .filter_map(|(writer, reader)| Some((*positions.get(writer)?, *positions.get(reader)?)))
+            .filter(|(earlier, later)| earlier < later)
+            .collect()
+    }
+
+    /// Reads hints written by [`ConflictHints::save`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let invalid = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("invalid hint line '{}'", line));
+
+        let mut pairs = Vec::new();
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut hashes = line.split_whitespace().map(str::parse::<B256>);
+            match (hashes.next(), hashes.next(), hashes.next()) {
+                (Some(Ok(writer)), Some(Ok(reader)), None) => pairs.push((writer, reader)),
+                _ => return Err(invalid(line)),
+            }
+        }
+        Ok(Self { pairs })
+    }
+
+    /// Writes the hints, one pair per line.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut contents = String::from("# db-test conflict hints: writer_hash reader_hash\n");
+        for (writer, reader) in &self.pairs {
+            contents.push_str(&format!("{} {}\n", writer, reader));
+        }
+        fs::write(path, contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_resolve() {
+        let hash = |byte| B256::with_last_byte(byte);
+        let hints = ConflictHints {
+            pairs: vec![(hash(1), hash(3)), (hash(3), hash(2)), (hash(1), hash(9))],
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hints.txt");
+        hints.save(&path).unwrap();
+        assert_eq!(ConflictHints::load(&path).unwrap(), hints);
+
+        // Unknown hashes and reversed pairs are dropped
+        assert_eq!(hints.to_positions(&[hash(1), hash(2), hash(3)]), vec![(0, 2)]);
+
+        fs::write(&path, "0x01 not-a-hash\n").unwrap();
+        assert!(ConflictHints::load(&path).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Critical path through the realized conflict graph.
+//!
+//! [`ConcurrencyStats`](super::ConcurrencyStats) counts the longest chain of
+//! committed transactions, which is the floor only if every transaction costs
+//! the same. With [`compute_cost`](crate::SignedTransaction::compute_cost)
+//! workloads one expensive transaction can outweigh a long chain of cheap
+//! ones, so [`CriticalPath`] weighs each transaction by its execution time
+//! and sums the heaviest dependent chain instead. No schedule finishes faster
+//! than that, however many threads it has, and achieved time divided by the
+//! critical path shows how close an executor gets to the floor.
+//!
+//! Execution times come from [`transaction_costs`]: each transaction's
+//! synthetic work is timed on its own, and the rest of a sequential run is
+//! shared evenly as the per-transfer base cost.
+
+use super::concurrency::committed_mask;
+use super::{AnyExecutor, ExecutionReport, ExecutionResult, SequentialExecutor};
+use crate::Workload;
+use alloy_primitives::Address;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Heaviest chain of committed transactions that touch a common account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CriticalPath {
+    /// Committed transactions.
+    pub committed: usize,
+    /// Transactions on the critical path.
+    pub length: usize,
+    /// Summed execution time of the critical path.
+    pub duration: Duration,
+    /// Summed execution time of every committed transaction.
+    pub total_work: Duration,
+}
+
+impl CriticalPath {
+    /// Computes the critical path of `result`, an execution of `workload`,
+    /// with `costs[i]` the execution time of transaction `i`.
+    pub fn measure(workload: &Workload, result: &ExecutionResult, costs: &[Duration]) -> Self {
+        // Finish time and chain length of the last committed transaction per account
+        let mut finishes: HashMap<Address, (Duration, usize)> = HashMap::new();
+        let mut path = Self {
+            committed: 0,
+            length: 0,
+            duration: Duration::ZERO,
+            total_work: Duration::ZERO,
+        };
+
+        let committed = committed_mask(workload, result);
+        for ((tx, success), &cost) in workload.transactions.iter().zip(committed).zip(costs) {
+            if !success {
+                continue;
+            }
+
+            path.committed += 1;
+            path.total_work += cost;
+            let before = finishes.get(&tx.from).max(finishes.get(&tx.to)).copied().unwrap_or_default();
+            let finish = (before.0 + cost, before.1 + 1);
+            finishes.insert(tx.from, finish);
+            finishes.insert(tx.to, finish);
+            if finish > (path.duration, path.length) {
+                (path.duration, path.length) = finish;
+            }
+        }
+
+        path
+    }
+
+    /// Highest speedup over serial execution the conflicts allow.
+    pub fn bound(&self) -> f64 {
+        if self.duration.is_zero() {
+            0.0
+        } else {
+            self.total_work.as_secs_f64() / self.duration.as_secs_f64()
+        }
+    }
+
+    /// How many times the critical path `achieved` took; 1.0 is the floor.
+    pub fn ratio(&self, achieved: Duration) -> f64 {
+        if self.duration.is_zero() {
+            0.0
+        } else {
+            achieved.as_secs_f64() / self.duration.as_secs_f64()
+        }
+    }
+}
+
+/// Estimates each transaction's execution time, by position.
+///
+/// Synthetic work is timed per transaction; whatever else `reference` spends
+/// on the workload is split evenly across transactions.
+pub fn transaction_costs(workload: &Workload, reference: &SequentialExecutor) -> Vec<Duration> {
+    let report = AnyExecutor::execute_workload(reference, workload);
+    let work: Vec<Duration> = workload
+        .transactions
+        .iter()
+        .map(|tx| {
+            let start = Instant::now();
+            tx.burn_compute();
+            start.elapsed()
+        })
+        .collect();
+
+    let num_transactions = u32::try_from(work.len().max(1)).unwrap_or(u32::MAX);
+    let base = report.elapsed.saturating_sub(work.iter().sum()) / num_transactions;
+    work.into_iter().map(|work| work + base).collect()
+}
+
+impl fmt::Display for CriticalPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "critical path: {:.2?} over {} of {} committed txs (bound {:.1}x)",
+            self.duration,
+            self.length,
+            self.committed,
+            self.bound()
+        )
+    }
+}
+
+/// Adds a [`CriticalPath`] detail line to every run of the wrapped executor.
+pub struct WithCriticalPath {
+    inner: Box<dyn AnyExecutor>,
+    reference: SequentialExecutor,
+}
+
+impl WithCriticalPath {
+    /// Wraps `inner`, timing transactions with `reference`.
+    pub fn new(inner: Box<dyn AnyExecutor>, reference: SequentialExecutor) -> Self {
+        Self { inner, reference }
+    }
+}
+
+impl AnyExecutor for WithCriticalPath {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn preserves_order(&self) -> bool {
+        self.inner.preserves_order()
+    }
+
+    fn supports_tokens(&self) -> bool {
+        self.inner.supports_tokens()
+    }
+
+    fn supports_nonceless(&self) -> bool {
+        self.inner.supports_nonceless()
+    }
+
+    fn supports_large_balances(&self) -> bool {
+        self.inner.supports_large_balances()
+    }
+
+    fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+        let report = self.inner.execute_workload(workload);
+        let costs = transaction_costs(workload, &self.reference);
+        let path = CriticalPath::measure(workload, &report.result, &costs);
+        let detail = format!("{}, achieved {:.2}x the floor", path, path.ratio(report.elapsed));
+        report.with_detail(detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_heaviest_chain() {
+        // Two hot accounts: every transfer touches both, so the path is the whole workload
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 2,
+            ..Default::default()
+        });
+        let reference = SequentialExecutor::with_verification(false);
+        let report = AnyExecutor::execute_workload(&reference, &workload);
+        let costs: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        let path = CriticalPath::measure(&workload, &report.result, &costs);
+
+        assert_eq!(path.committed, 20);
+        assert_eq!(path.length, 20);
+        assert_eq!(path.duration, Duration::from_millis(210));
+        assert_eq!(path.total_work, path.duration);
+        assert_eq!(path.bound(), 1.0);
+        assert_eq!(path.ratio(Duration::from_millis(420)), 2.0);
+
+        // Independent transfers: the single most expensive one is the path
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 1000,
+            num_transactions: 2,
+            transactions_per_block: 2,
+            ..Default::default()
+        });
+        let report = AnyExecutor::execute_workload(&reference, &workload);
+        let first = &workload.transactions[0];
+        let second = &workload.transactions[1];
+        if ![second.from, second.to].contains(&first.from) && ![second.from, second.to].contains(&first.to) {
+            let costs = [Duration::from_millis(5), Duration::from_millis(3)];
+            let path = CriticalPath::measure(&workload, &report.result, &costs);
+            assert_eq!((path.length, path.duration), (1, Duration::from_millis(5)));
+        }
+
+        assert_eq!(transaction_costs(&workload, &reference).len(), 2);
+        let executor = WithCriticalPath::new(Box::new(reference.clone()), reference);
+        assert_eq!(executor.execute_workload(&workload).details.len(), 1);
+    }
+}
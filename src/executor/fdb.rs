@@ -9,30 +9,170 @@
 //! - Automatic conflict detection and retry
 //! - Atomic transactions
 //! - Does NOT preserve strict ordering due to parallel execution and retries
+//!
+//! # Dispatch
+//!
+//! By default ([`FdbDispatch::Threads`]) each worker is an OS thread with its
+//! own tokio runtime that blocks on one transfer at a time. With
+//! [`FdbDispatch::Async`] every transfer is instead a future spawned on the
+//! caller's runtime, with a semaphore keeping as many in flight as there are
+//! workers, so no runtime is built per thread and no thread blocks while FDB
+//! round-trips are outstanding.
+//!
+//! # Commitment Mode
+//!
+//! With [`FdbParallelExecutor::with_commitment`], every transfer also updates a
+//! per-shard state commitment stored under `commitment/<shard>`. Each shard's
+//! commitment is the XOR of `keccak256(key || value)` over the shard's
+//! accounts, so a write XORs out the old entry hash and XORs in the new one
+//! with an atomic `BitXor` mutation. Atomic mutations add no read conflicts,
+//! and the final commitment depends only on the final state, not on the order
+//! in which parallel transfers happened to commit.
+//!
+//! # Contract Code
+//!
+//! Account values carry a code hash (see [`crate::codec`]), and each distinct
+//! bytecode is stored once under `code/<code_hash>`. Code is immutable once
+//! written, so it is left out of the commitment: the code hash in the account
+//! value already commits to it.
+//!
+//! # Tokens
+//!
+//! Workloads with [`tokens`](crate::WorkloadConfig::tokens) store every
+//! account's token balances in its value, in the codec's version 3 format.
+//! A token transfer rewrites the same two keys a native transfer does, so
+//! transfers of different assets between the same accounts still conflict.
+//!
+//! # Nonce-less Transactions
+//!
+//! In [`nonceless`](crate::WorkloadConfig::nonceless) workloads the sender's
+//! nonce is neither checked nor bumped. Instead every transfer reads and
+//! writes `txid/<id>` in the same FDB transaction, so the seen-set commits
+//! atomically with the transfer and a repeated id is rejected. Ids are not
+//! account state and are left out of the commitment.
+//!
+//! # Transfer Log
+//!
+//! With [`FdbParallelExecutor::with_transfer_log`], every committed transfer
+//! also appends `txlog/<versionstamp>` with a `SetVersionstampedKey` mutation.
+//! FDB fills in the commit version when the transaction commits, so the log
+//! keys sort in the order transfers actually committed, which
+//! [`FdbParallelExecutor::transfer_log`] reads back. The log is written
+//! without reading anything, so it adds no conflicts between transfers.
 
-use alloy_primitives::{keccak256, Address, U256};
-use foundationdb::{Database, FdbBindingError};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use foundationdb::options::MutationType;
+use foundationdb::{Database, FdbBindingError, RangeOption, Transaction};
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 
-use super::ExecutionResult;
+use super::{ExecutionResult, TransactionIndex, TxStatus};
+use crate::background::BackgroundAccounts;
+use crate::codec::{AccountCodec, AccountValue, RawCodec};
+use crate::key_scheme::KeyScheme;
+use crate::options::{FdbDispatch, FdbOptions, RetryPolicy};
+use crate::recovery::RecoveryBackend;
+use crate::sharding;
+use crate::validation::{validate_stateful, validate_stateless, SenderState, ValidationError};
 use crate::Workload;
 
+/// Key prefix for account entries.
+const ACCOUNT_PREFIX: &[u8] = b"account/";
+
+/// Key prefix for contract bytecode, keyed by code hash.
+const CODE_PREFIX: &[u8] = b"code/";
+
+/// Key prefix for per-shard state commitments.
+const COMMITMENT_PREFIX: &[u8] = b"commitment/";
+
+/// Key prefix for the ids of committed nonce-less transactions.
+const TX_ID_PREFIX: &[u8] = b"txid/";
+
+/// Key prefix for the versionstamped transfer log.
+const LOG_PREFIX: &[u8] = b"txlog/";
+
+/// Bytes of an FDB versionstamp: the commit version and the batch order.
+const VERSIONSTAMP_LEN: usize = 10;
+
+/// Encoded length of a log entry's value: transaction hash, sender and nonce.
+const LOG_VALUE_LEN: usize = 32 + 20 + 8;
+
+/// A committed transfer read back from the transfer log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoggedTransfer {
+    /// Versionstamp the transfer committed at; entries sort by it.
+    pub versionstamp: [u8; VERSIONSTAMP_LEN],
+    pub tx_hash: B256,
+    pub from: Address,
+    /// The transaction's nonce, or its id in nonce-less workloads.
+    pub nonce: u64,
+}
+
+impl LoggedTransfer {
+    fn encode_value(tx: &crate::SignedTransaction) -> Vec<u8> {
+        let mut value = Vec::with_capacity(LOG_VALUE_LEN);
+        value.extend_from_slice(tx.tx_hash.as_slice());
+        value.extend_from_slice(tx.from.as_slice());
+        value.extend_from_slice(&tx.nonce.to_be_bytes());
+        value
+    }
+
+    /// Decodes a log entry, or `None` if the key or value is malformed.
+    fn decode(key: &[u8], value: &[u8]) -> Option<Self> {
+        let versionstamp = key.strip_prefix(LOG_PREFIX)?.get(..VERSIONSTAMP_LEN)?.try_into().ok()?;
+        if value.len() != LOG_VALUE_LEN {
+            return None;
+        }
+        Some(Self {
+            versionstamp,
+            tx_hash: B256::from_slice(&value[..32]),
+            from: Address::from_slice(&value[32..52]),
+            nonce: u64::from_be_bytes(value[52..].try_into().ok()?),
+        })
+    }
+}
+
+/// Index into `log` of the first transfer whose nonce does not follow the
+/// previous logged transfer from the same sender, or `None` if every
+/// sender's transfers committed in nonce order.
+///
+/// This is the ordering the executor does guarantee: transfers from
+/// different senders may commit in any order, but a sender's nonce check
+/// serializes its own. Not meaningful for nonce-less workloads.
+pub fn first_sender_order_violation(log: &[LoggedTransfer]) -> Option<usize> {
+    let mut next_nonce = std::collections::HashMap::new();
+    log.iter().position(|transfer| {
+        let expected = next_nonce.insert(transfer.from, transfer.nonce + 1);
+        expected.is_some_and(|expected| expected != transfer.nonce)
+    })
+}
+
 /// Result of multi-threaded execution with per-thread statistics.
 #[derive(Debug, Clone)]
 pub struct ParallelExecutionResult {
-    /// Results from each thread.
+    /// Results from each thread; empty with [`FdbDispatch::Async`].
     pub thread_results: Vec<ThreadResult>,
     /// Total successful transactions across all threads.
     pub total_successful: usize,
     /// Total failed transactions across all threads.
     pub total_failed: usize,
+    /// Validation retries across all threads.
+    pub total_retries: usize,
+    /// Transfers that failed after exhausting their retry attempts.
+    pub total_gave_up: usize,
+    /// Time threads spent sleeping between retries, summed over threads.
+    pub total_backoff: Duration,
+    /// Per-transaction outcomes indexed by transaction hash.
+    pub tx_index: TransactionIndex,
 }
 
 impl ParallelExecutionResult {
     /// Converts to a simple ExecutionResult for compatibility.
     pub fn to_execution_result(&self) -> ExecutionResult {
         ExecutionResult::new(self.total_successful, self.total_failed)
+            .with_tx_index(self.tx_index.clone())
     }
 }
 
@@ -43,9 +183,77 @@ pub struct ThreadResult {
     pub thread_id: usize,
     /// Number of successful transactions.
     pub successful: usize,
-    /// Number of permanently failed transactions (e.g., invalid signatures).
-    /// Validation failures (nonce mismatch, insufficient balance) are retried until success.
+    /// Number of permanently failed transactions (e.g., invalid signatures),
+    /// including transfers that ran out of retry attempts.
     pub failed: usize,
+    /// Validation failures (nonce mismatch, insufficient balance) retried.
+    pub retries: usize,
+    /// Transfers that failed after exhausting their retry attempts.
+    pub gave_up: usize,
+    /// Time spent sleeping between retries.
+    pub backoff: Duration,
+    /// Outcome of each transaction assigned to this thread, in assignment order.
+    pub statuses: Vec<TxStatus>,
+}
+
+impl ThreadResult {
+    fn new(thread_id: usize, capacity: usize) -> Self {
+        Self {
+            thread_id,
+            successful: 0,
+            failed: 0,
+            retries: 0,
+            gave_up: 0,
+            backoff: Duration::ZERO,
+            statuses: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Records the outcome of the next transaction.
+    fn record(&mut self, status: TxStatus) {
+        if status.is_success() {
+            self.successful += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.statuses.push(status);
+    }
+
+    /// Adds `other`'s counts and outcomes after this result's.
+    fn merge(&mut self, other: ThreadResult) {
+        self.successful += other.successful;
+        self.failed += other.failed;
+        self.retries += other.retries;
+        self.gave_up += other.gave_up;
+        self.backoff += other.backoff;
+        self.statuses.extend(other.statuses);
+    }
+}
+
+/// What follows one attempt at a transfer.
+enum Step {
+    /// The transfer finished with this outcome.
+    Done(TxStatus),
+    /// Validation attempts ran out; the transfer fails.
+    GiveUp,
+    /// Try again after the delay; whether the retry counts against the
+    /// retry policy.
+    Retry(Duration, bool),
+}
+
+/// Outcome of one attempt at a transfer's FDB transaction.
+enum Attempt {
+    /// The transfer committed.
+    Committed,
+    /// A check failed that another transfer may still fix; try again.
+    Retry,
+    /// The nonce-less transaction id was already used.
+    Replayed,
+    /// The sender's nonce or the receiver's balance would overflow.
+    Overflow,
+    /// A check failed that no other transfer can fix, e.g. a nonce below
+    /// the sender's.
+    Invalid,
 }
 
 /// FoundationDB parallel executor with automatic retry and conflict resolution.
@@ -56,9 +264,16 @@ pub struct ThreadResult {
 ///
 /// # Retry Behavior
 /// - **FDB conflicts**: Automatic infinite retry (handled by FDB)
-/// - **Nonce mismatches**: Manual infinite retry with 100μs delay
-/// - **Insufficient balance**: Manual infinite retry with 100μs delay (rare with 1 wei transfers)
+/// - **Nonces above the sender's**: Manual retry under the [`RetryPolicy`], by
+///   default forever with a 100μs delay
+/// - **Nonces below the sender's**: Permanent failure, unless FDB re-runs a
+///   transfer whose commit result was unknown: then it was this transfer's
+///   own commit, which counts as success
+/// - **Insufficient balance**: Manual retry under the [`RetryPolicy`] (rare with 1 wei transfers)
 /// - **Invalid signatures**: Permanent failure (no retry)
+/// - **Replays** (duplicate hash or reused sender nonce): Rejected before dispatch
+/// - **Reused nonce-less ids**: Permanent failure once the id is in the seen-set
+/// - **Nonce or balance overflow**: Permanent failure
 ///
 /// With 1 wei transfers and large initial balances, retries are primarily due to nonce
 /// ordering in parallel execution. This means transactions will eventually succeed 
@@ -78,6 +293,18 @@ pub struct FdbParallelExecutor {
     db: Arc<Database>,
     verify_signatures: bool,
     num_threads: usize,
+    codec: &'static dyn AccountCodec,
+    /// How account keys are derived from addresses.
+    key_scheme: KeyScheme,
+    commitment_shards: Option<usize>,
+    /// Accounts written per FDB transaction by `init_accounts`.
+    batch_size: usize,
+    /// How transfers that fail validation are retried.
+    retry: RetryPolicy,
+    /// How transfers are driven.
+    dispatch: FdbDispatch,
+    /// Whether committed transfers are appended to the transfer log.
+    transfer_log: bool,
 }
 
 impl FdbParallelExecutor {
@@ -93,9 +320,69 @@ impl FdbParallelExecutor {
             db: Arc::new(db),
             verify_signatures,
             num_threads: num_threads.max(1),
+            codec: &RawCodec,
+            key_scheme: KeyScheme::default(),
+            commitment_shards: None,
+            batch_size: FdbOptions::default().batch_size,
+            retry: RetryPolicy::default(),
+            dispatch: FdbDispatch::default(),
+            transfer_log: false,
         })
     }
 
+    /// Maintains a per-shard state commitment alongside every account write.
+    pub fn with_commitment(mut self, num_shards: usize) -> Self {
+        self.commitment_shards = Some(num_shards.clamp(1, u16::MAX as usize));
+        self
+    }
+
+    /// Appends every committed transfer to the versionstamped transfer log.
+    pub fn with_transfer_log(mut self) -> Self {
+        self.transfer_log = true;
+        self
+    }
+
+    /// Returns the number of commitment shards, if commitment mode is enabled.
+    pub fn commitment_shards(&self) -> Option<usize> {
+        self.commitment_shards
+    }
+
+    /// Stores account values with `codec` instead of the default fixed-width encoding.
+    pub fn with_codec(mut self, codec: &'static dyn AccountCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Returns the codec used for account values.
+    pub fn codec(&self) -> &'static dyn AccountCodec {
+        self.codec
+    }
+
+    /// Applies executor options from the command line.
+    pub fn with_options(mut self, options: &FdbOptions) -> Self {
+        self.batch_size = options.batch_size.max(1);
+        self.retry = options.retry;
+        self.dispatch = options.dispatch.unwrap_or_default();
+        self.key_scheme = options.key_scheme;
+        self
+    }
+
+    /// Returns how account keys are derived from addresses.
+    pub fn key_scheme(&self) -> KeyScheme {
+        self.key_scheme
+    }
+
+    /// Drives transfers in `dispatch` mode.
+    pub fn with_dispatch(mut self, dispatch: FdbDispatch) -> Self {
+        self.dispatch = dispatch;
+        self
+    }
+
+    /// Returns how transfers are driven.
+    pub fn dispatch(&self) -> FdbDispatch {
+        self.dispatch
+    }
+
     /// Clears all keys from the database.
     /// This is useful for starting with a clean slate.
     pub async fn clear_database(&self) -> Result<(), FdbBindingError> {
@@ -104,8 +391,12 @@ impl FdbParallelExecutor {
         // Use a transaction to clear our account key space
         // Using a narrow range is better practice than clearing everything
         db.run(|trx, _maybe_committed| async move {
-            // Clear only our account keyspace
+            // Clear only our account, code, commitment, transaction id and log keyspaces
             trx.clear_range(b"account/", b"account/\xff");
+            trx.clear_range(b"code/", b"code/\xff");
+            trx.clear_range(b"commitment/", b"commitment/\xff");
+            trx.clear_range(b"txid/", b"txid/\xff");
+            trx.clear_range(b"txlog/", b"txlog/\xff");
             Ok(())
         })
         .await?;
@@ -116,22 +407,36 @@ impl FdbParallelExecutor {
     /// Initializes accounts in the database.
     /// Batches the writes to avoid transaction_too_old errors.
     pub async fn init_accounts(&self, accounts: &[(Address, U256)]) -> Result<(), FdbBindingError> {
+        self.init_accounts_with_tokens(accounts, &[]).await
+    }
+
+    /// Initializes accounts like [`init_accounts`](Self::init_accounts), each
+    /// holding `tokens` as its token balances.
+    pub async fn init_accounts_with_tokens(
+        &self,
+        accounts: &[(Address, U256)],
+        tokens: &[U256],
+    ) -> Result<(), FdbBindingError> {
         let db = self.db.clone();
+        let codec = self.codec;
+        let key_scheme = self.key_scheme;
+        let commitment_shards = self.commitment_shards;
         
-        // Batch size - keep transactions small to avoid hitting time limits
-        const BATCH_SIZE: usize = 1000;
-        
-        // Process accounts in batches
-        for chunk in accounts.chunks(BATCH_SIZE) {
+        // Process accounts in batches - keep transactions small to avoid hitting time limits
+        for chunk in accounts.chunks(self.batch_size) {
             let accounts_batch = chunk.to_vec();
             
             db.run(|trx, _maybe_committed| {
                 let accounts_batch = accounts_batch.clone();
+                let tokens = tokens.to_vec();
                 async move {
                     for (address, balance) in accounts_batch {
-                        let key = Self::account_key(address);
-                        let value = Self::encode_account(0, balance);
+                        let key = Self::account_key(key_scheme, address);
+                        let value = codec.encode(&AccountValue::new(0, balance).with_tokens(tokens.clone()));
                         trx.set(&key, &value);
+                        if let Some(num_shards) = commitment_shards {
+                            Self::update_commitment(&trx, num_shards, &key, None, &value);
+                        }
                     }
                     Ok(())
                 }
@@ -142,6 +447,98 @@ impl FdbParallelExecutor {
         Ok(())
     }
 
+    /// Deploys `code` at `address`, creating the account if it does not exist.
+    ///
+    /// The bytecode is stored under its hash, which is returned and recorded
+    /// in the account value.
+    pub async fn set_code(&self, address: Address, code: &[u8]) -> Result<B256, FdbBindingError> {
+        let codec = self.codec;
+        let key_scheme = self.key_scheme;
+        let commitment_shards = self.commitment_shards;
+        let code_hash = keccak256(code);
+
+        self.db
+            .run(|trx, _maybe_committed| async move {
+                let key = Self::account_key(key_scheme, address);
+                let old = trx.get(&key, false).await?;
+                let account = match &old {
+                    Some(data) => codec.decode(data).expect("malformed account value"),
+                    None => AccountValue::new(0, U256::ZERO),
+                };
+                let value = codec.encode(&account.with_code_hash(code_hash));
+
+                trx.set(&Self::code_key(code_hash), code);
+                trx.set(&key, &value);
+                if let Some(num_shards) = commitment_shards {
+                    Self::update_commitment(&trx, num_shards, &key, old.as_deref(), &value);
+                }
+                Ok(())
+            })
+            .await?;
+
+        Ok(code_hash)
+    }
+
+    /// Reads the bytecode stored under `code_hash`, if any.
+    pub async fn get_code(&self, code_hash: B256) -> Result<Option<Vec<u8>>, FdbBindingError> {
+        self.db
+            .run(|trx, _maybe_committed| async move {
+                Ok(trx.get(&Self::code_key(code_hash), false).await?.map(|code| code.to_vec()))
+            })
+            .await
+    }
+
+    /// Writes `background` next to the existing accounts.
+    ///
+    /// `execute_workload` clears the account keyspace, so follow this with
+    /// `execute_workload_shared`.
+    pub async fn load_background(&self, background: &BackgroundAccounts) -> Result<(), FdbBindingError> {
+        Self::write_background(
+            &self.db,
+            self.codec,
+            self.key_scheme,
+            self.commitment_shards,
+            self.batch_size,
+            background,
+        )
+        .await
+    }
+
+    /// Writes `background` in contiguous key ranges of `batch_size` accounts, one
+    /// range per FDB transaction.
+    ///
+    /// Background keys ascend, so each transaction's writes land on the same
+    /// few storage shards instead of being scattered across the cluster.
+    pub(crate) async fn write_background(
+        db: &Database,
+        codec: &'static dyn AccountCodec,
+        key_scheme: KeyScheme,
+        commitment_shards: Option<usize>,
+        batch_size: usize,
+        background: &BackgroundAccounts,
+    ) -> Result<(), FdbBindingError> {
+        let value = codec.encode(&AccountValue::new(0, background.balance()));
+        for range in background.chunks(batch_size as u64) {
+            let value = &value;
+            db.run(|trx, _maybe_committed| {
+                let range = range.clone();
+                async move {
+                    for hashed_key in background.hashed_keys(range) {
+                        let key = Self::prefixed_account_key(key_scheme, key_scheme.background_key(hashed_key));
+                        trx.set(&key, value);
+                        if let Some(num_shards) = commitment_shards {
+                            Self::update_commitment(&trx, num_shards, &key, None, value);
+                        }
+                    }
+                    Ok(())
+                }
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
     /// Executes a workload across multiple threads with parallel execution.
     /// 
     /// Transaction boundaries: Each ETH transfer = one FDB transaction
@@ -155,35 +552,99 @@ impl FdbParallelExecutor {
     ) -> Result<ParallelExecutionResult, FdbBindingError> {
         // Clear the database first
         self.clear_database().await?;
-        
+
+        self.execute_workload_shared(workload).await
+    }
+
+    /// Executes a workload without clearing the database first.
+    ///
+    /// Only the workload's own accounts are (re)initialized, so several
+    /// workloads with disjoint accounts can run against the same cluster at
+    /// once. With commitments enabled the shard values then cover every
+    /// tenant's accounts.
+    pub async fn execute_workload_shared(
+        &self,
+        workload: &Workload,
+    ) -> Result<ParallelExecutionResult, FdbBindingError> {
         // Initialize accounts in batches to avoid transaction_too_old
         let accounts: Vec<_> = workload
             .accounts
             .iter()
-            .map(|acc| (acc.address, U256::from(1_000_000_000_000_000_000_000u128)))
+            .map(|acc| (acc.address, workload.initial_balance()))
             .collect();
         
-        self.init_accounts(&accounts).await?;
+        self.init_accounts_with_tokens(&accounts, &workload.initial_token_balances()).await?;
+
+        // Reject replays before dispatch. With parallel retries the second occurrence
+        // of a (sender, nonce) pair would otherwise either win the race or spin forever
+        // on a nonce that can never match again. Nonce-less replays are dispatched:
+        // the seen-set rejects whichever occurrence commits second.
+        let nonceless = workload.config.nonceless;
+        let (positions, transactions): (Vec<usize>, Vec<_>) = workload
+            .transactions
+            .iter()
+            .zip(workload.replay_mask())
+            .enumerate()
+            .filter(|(_, (_, replay))| nonceless || !replay)
+            .map(|(position, (tx, _))| (position, tx.clone()))
+            .unzip();
+        let num_replays = workload.transactions.len() - transactions.len();
+
+        // No per-block checkpoints: blocks are ignored and transfers from any
+        // block commit whenever their worker gets to them, so the cluster never
+        // holds exactly the state after a given block
+        if self.dispatch == FdbDispatch::Async {
+            let result = self.execute_async(&transactions, nonceless).await;
+            let mut statuses = vec![TxStatus::Failed; workload.transactions.len()];
+            for (&status, &position) in result.statuses.iter().zip(&positions) {
+                statuses[position] = status;
+            }
+            return Ok(ParallelExecutionResult {
+                thread_results: Vec::new(),
+                total_successful: result.successful,
+                total_failed: num_replays + result.failed,
+                total_retries: result.retries,
+                total_gave_up: result.gave_up,
+                total_backoff: result.backoff,
+                tx_index: TransactionIndex::from_statuses(workload, &statuses),
+            });
+        }
 
         // Divide transactions among threads (each thread gets a slice of the flat transaction list)
-        let txs_per_thread = (workload.transactions.len() + self.num_threads - 1) / self.num_threads;
+        let txs_per_thread = (transactions.len() + self.num_threads - 1) / self.num_threads;
         
         let mut handles = Vec::new();
         
         for thread_id in 0..self.num_threads {
             let start_idx = thread_id * txs_per_thread;
-            let end_idx = (start_idx + txs_per_thread).min(workload.transactions.len());
+            let end_idx = (start_idx + txs_per_thread).min(transactions.len());
             
-            if start_idx >= workload.transactions.len() {
+            if start_idx >= transactions.len() {
                 break;
             }
             
-            let thread_txs = workload.transactions[start_idx..end_idx].to_vec();
+            let thread_txs = transactions[start_idx..end_idx].to_vec();
             let db = self.db.clone();
             let verify_signatures = self.verify_signatures;
+            let codec = self.codec;
+            let key_scheme = self.key_scheme;
+            let commitment_shards = self.commitment_shards;
+            let transfer_log = self.transfer_log;
+            let retry = self.retry;
             
             let handle = thread::spawn(move || {
-                Self::execute_thread(thread_id, db, &thread_txs, verify_signatures)
+                Self::execute_thread(
+                    thread_id,
+                    db,
+                    codec,
+                    key_scheme,
+                    commitment_shards,
+                    &thread_txs,
+                    verify_signatures,
+                    nonceless,
+                    transfer_log,
+                    retry,
+                )
             });
             
             handles.push(handle);
@@ -192,12 +653,25 @@ impl FdbParallelExecutor {
         // Collect results from all threads
         let mut thread_results = Vec::new();
         let mut total_successful = 0;
-        let mut total_failed = 0;
+        let mut total_failed = num_replays;
+        let mut total_retries = 0;
+        let mut total_gave_up = 0;
+        let mut total_backoff = Duration::ZERO;
+        
+        // Replays stay marked as failed; threads report outcomes for their slice in order
+        let mut statuses = vec![TxStatus::Failed; workload.transactions.len()];
+        let mut next_position = positions.iter();
         
         for handle in handles {
             let result = handle.join().expect("Thread panicked");
             total_successful += result.successful;
             total_failed += result.failed;
+            total_retries += result.retries;
+            total_gave_up += result.gave_up;
+            total_backoff += result.backoff;
+            for (&status, &position) in result.statuses.iter().zip(next_position.by_ref()) {
+                statuses[position] = status;
+            }
             thread_results.push(result);
         }
         
@@ -205,10 +679,14 @@ impl FdbParallelExecutor {
             thread_results,
             total_successful,
             total_failed,
+            total_retries,
+            total_gave_up,
+            total_backoff,
+            tx_index: TransactionIndex::from_statuses(workload, &statuses),
         })
     }
 
-    /// Executes transactions on a single thread with infinite retry.
+    /// Executes transactions on a single thread, retrying under `retry`.
     /// 
     /// IMPORTANT: Each ETH transfer is executed as its own independent FDB transaction.
     /// This allows maximum concurrency - multiple threads can execute transfers in parallel,
@@ -216,122 +694,293 @@ impl FdbParallelExecutor {
     /// 
     /// Retry behavior:
     /// - Invalid signatures: Fail immediately (permanent error)
-    /// - Nonce mismatches: Retry with 100μs delay (the main retry case with parallel execution)
-    /// - Insufficient balance: Retry with 100μs delay (rare with 1 wei transfers)
+    /// - Nonce mismatches: Retry after the policy's backoff (the main retry case
+    ///   with parallel execution), failing once its attempts run out
+    /// - Insufficient balance (native or token): Same as nonce mismatches (rare with 1 wei transfers)
+    /// - Reused nonce-less id: Fail immediately (permanent error)
     /// - FDB conflicts: Automatic retry (handled by db.run())
     /// 
-    /// With 1 wei transfers, nonce ordering is the primary challenge.
+    /// With 1 wei transfers, nonce ordering is the primary challenge, which
+    /// `nonceless` removes.
     /// 
     /// The `workload.blocks` structure is ignored - we process all transactions in a flat list.
+    #[allow(clippy::too_many_arguments)]
     fn execute_thread(
         thread_id: usize,
         db: Arc<Database>,
+        codec: &'static dyn AccountCodec,
+        key_scheme: KeyScheme,
+        commitment_shards: Option<usize>,
         transactions: &[crate::SignedTransaction],
         verify_signatures: bool,
+        nonceless: bool,
+        transfer_log: bool,
+        retry: RetryPolicy,
     ) -> ThreadResult {
         let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+        let mut rng = rand::thread_rng();
         
-        let mut successful = 0;
-        let mut failed = 0;
+        let mut result = ThreadResult::new(thread_id, transactions.len());
         
         for tx in transactions {
-            let tx = tx.clone();
-            
-            // ═══════════════════════════════════════════════════════════════════════════
-            // Each iteration of this loop = ONE FDB transaction (one ETH transfer)
-            // We retry until success - validation failures will retry after a delay
-            // db.run() provides automatic conflict detection and retry
-            // ═══════════════════════════════════════════════════════════════════════════
-            
             // Verify signature once upfront (permanent failure if invalid)
-            if verify_signatures {
-                let recovered = tx.recover_signer();
-                if recovered.is_none() || recovered.unwrap() != tx.from {
-                    failed += 1;
-                    continue; // Skip this transaction - signature is permanently invalid
-                }
+            if verify_signatures && validate_stateless(&tx, RecoveryBackend::K256).is_err() {
+                result.record(TxStatus::Failed);
+                continue; // Skip this transaction - signature is permanently invalid
             }
             
             // Retry loop for validation failures
             // With 1 wei transfers: primarily nonce mismatches from out-of-order execution
+            let mut attempts = 0;
             loop {
-                let result = rt.block_on(async {
-                    db.run(|trx, _maybe_committed| {
-                        let tx = tx.clone();
-                        async move {
-                            // Get sender account
-                            let sender_key = Self::account_key(tx.from);
-                            let sender_data = trx.get(&sender_key, false).await?;
-                            
-                            let sender_data = match sender_data {
-                                Some(data) => data,
-                                None => return Ok(false), // Account not found
-                            };
-                            
-                            let (sender_nonce, sender_balance) = Self::decode_account(&sender_data);
-                            
-                            // Check nonce - might be wrong due to out-of-order parallel execution
-                            if sender_nonce != tx.nonce {
-                                return Ok(false); // Nonce mismatch - will retry
-                            }
-                            
-                            // Check balance
-                            if sender_balance < tx.value {
-                                return Ok(false); // Insufficient balance - will retry
-                            }
-                            
-                            // Get receiver account
-                            let receiver_key = Self::account_key(tx.to);
-                            let receiver_data = trx.get(&receiver_key, false).await?;
-                            
-                            let (receiver_nonce, receiver_balance) = if let Some(data) = receiver_data {
-                                Self::decode_account(&data)
-                            } else {
-                                (0, U256::ZERO)
-                            };
-                            
-                            // Execute transfer
-                            let new_sender_balance = sender_balance - tx.value;
-                            let new_sender_nonce = sender_nonce + 1;
-                            let new_receiver_balance = receiver_balance + tx.value;
-                            
-                            // Write updates
-                            trx.set(&sender_key, &Self::encode_account(new_sender_nonce, new_sender_balance));
-                            trx.set(&receiver_key, &Self::encode_account(receiver_nonce, new_receiver_balance));
-                            
-                            Ok(true) // Success!
-                        }
-                    })
-                    .await
-                });
-                
-                match result {
-                    Ok(true) => {
-                        // Transaction succeeded
-                        successful += 1;
+                // Every retry pays the synthetic work again
+                tx.burn_compute();
+                let attempt = rt.block_on(Self::execute_transfer(
+                    &db,
+                    codec,
+                    key_scheme,
+                    commitment_shards,
+                    tx,
+                    nonceless,
+                    transfer_log,
+                ));
+                match Self::next_step(attempt, &retry, &mut attempts, &mut rng) {
+                    Step::Done(status) => {
+                        result.record(status);
                         break;
                     }
-                    Ok(false) => {
-                        // Validation failed (nonce mismatch or insufficient balance)
-                        // Wait a tiny bit and retry - another transaction might complete
-                        std::thread::sleep(std::time::Duration::from_micros(100));
-                        continue; // Retry the transaction
+                    Step::GiveUp => {
+                        result.gave_up += 1;
+                        result.record(TxStatus::Failed);
+                        break;
                     }
-                    Err(_) => {
-                        // FDB error (should be rare due to automatic retry)
-                        // Wait and retry
-                        std::thread::sleep(std::time::Duration::from_millis(1));
-                        continue;
+                    Step::Retry(delay, counted) => {
+                        // Back off and retry - another transaction might complete
+                        std::thread::sleep(delay);
+                        if counted {
+                            result.retries += 1;
+                            result.backoff += delay;
+                        }
                     }
                 }
             }
         }
         
-        ThreadResult {
-            thread_id,
-            successful,
-            failed,
+        result
+    }
+
+    /// Executes transactions as concurrent futures on the current runtime,
+    /// at most `num_threads` in flight at once.
+    ///
+    /// Transfers are started in workload order, so the earliest unfinished
+    /// transfer always holds a permit and a sender's nonce chain cannot
+    /// starve. Outcomes are returned in input order.
+    async fn execute_async(&self, transactions: &[crate::SignedTransaction], nonceless: bool) -> ThreadResult {
+        let permits = Arc::new(Semaphore::new(self.num_threads));
+        let mut tasks = Vec::with_capacity(transactions.len());
+        
+        for tx in transactions {
+            let permit = permits.clone().acquire_owned().await.expect("semaphore closed");
+            let tx = tx.clone();
+            let db = self.db.clone();
+            let codec = self.codec;
+            let key_scheme = self.key_scheme;
+            let commitment_shards = self.commitment_shards;
+            let transfer_log = self.transfer_log;
+            let verify_signatures = self.verify_signatures;
+            let retry = self.retry;
+            
+            tasks.push(tokio::spawn(async move {
+                let _permit = permit;
+                let mut result = ThreadResult::new(0, 1);
+                if verify_signatures && validate_stateless(&tx, RecoveryBackend::K256).is_err() {
+                    result.record(TxStatus::Failed);
+                    return result;
+                }
+                
+                let mut attempts = 0;
+                loop {
+                    tx.burn_compute();
+                    let attempt = Self::execute_transfer(
+                        &db,
+                        codec,
+                        key_scheme,
+                        commitment_shards,
+                        &tx,
+                        nonceless,
+                        transfer_log,
+                    )
+                    .await;
+                    let step = Self::next_step(attempt, &retry, &mut attempts, &mut rand::thread_rng());
+                    match step {
+                        Step::Done(status) => {
+                            result.record(status);
+                            return result;
+                        }
+                        Step::GiveUp => {
+                            result.gave_up += 1;
+                            result.record(TxStatus::Failed);
+                            return result;
+                        }
+                        Step::Retry(delay, counted) => {
+                            tokio::time::sleep(delay).await;
+                            if counted {
+                                result.retries += 1;
+                                result.backoff += delay;
+                            }
+                        }
+                    }
+                }
+            }));
         }
+        
+        let mut merged = ThreadResult::new(0, transactions.len());
+        for task in tasks {
+            merged.merge(task.await.expect("Transfer task panicked"));
+        }
+        merged
+    }
+
+    /// Decides what follows one attempt at a transfer: its outcome, giving up,
+    /// or a retry after a delay. Only validation retries count against
+    /// `retry`'s attempts and are reported; FDB errors wait 1ms and retry.
+    fn next_step(
+        attempt: Result<Attempt, FdbBindingError>,
+        retry: &RetryPolicy,
+        attempts: &mut usize,
+        rng: &mut impl rand::Rng,
+    ) -> Step {
+        match attempt {
+            Ok(Attempt::Committed) => Step::Done(TxStatus::Success),
+            // The id was already used - permanently invalid
+            Ok(Attempt::Replayed) => Step::Done(TxStatus::Failed),
+            // Overflow cannot be fixed by other transfers - permanently invalid
+            Ok(Attempt::Overflow) => Step::Done(TxStatus::Failed),
+            // Neither can a nonce the sender is already past
+            Ok(Attempt::Invalid) => Step::Done(TxStatus::Failed),
+            // Validation failed (nonce too high or insufficient balance)
+            Ok(Attempt::Retry) => {
+                *attempts += 1;
+                if retry.allows_retry(*attempts) {
+                    Step::Retry(retry.backoff(*attempts, rng), true)
+                } else {
+                    Step::GiveUp
+                }
+            }
+            // FDB error (should be rare due to automatic retry)
+            Err(_) => Step::Retry(Duration::from_millis(1), false),
+        }
+    }
+
+    /// Runs one transfer as one FDB transaction, appending it to the
+    /// transfer log if `transfer_log` is set.
+    async fn execute_transfer(
+        db: &Database,
+        codec: &'static dyn AccountCodec,
+        key_scheme: KeyScheme,
+        commitment_shards: Option<usize>,
+        tx: &crate::SignedTransaction,
+        nonceless: bool,
+        transfer_log: bool,
+    ) -> Result<Attempt, FdbBindingError> {
+        // db.run() provides automatic conflict detection and retry
+        db.run(|trx, maybe_committed| {
+            let tx = tx.clone();
+            let maybe_committed = bool::from(maybe_committed);
+            async move {
+                // Get sender account
+                let sender_key = Self::account_key(key_scheme, tx.from);
+                let sender_data = trx.get(&sender_key, false).await?;
+                
+                let sender_data = match sender_data {
+                    Some(data) => data,
+                    None => return Ok(Attempt::Retry), // Account not found
+                };
+                
+                let sender = codec
+                    .decode(&sender_data)
+                    .expect("malformed account value");
+                
+                // Nonce-less transactions check the seen-set instead of a nonce.
+                let id_key = Self::tx_id_key(tx.nonce);
+                if nonceless && trx.get(&id_key, false).await?.is_some() {
+                    return Ok(Attempt::Replayed);
+                }
+                
+                // Check the nonce and the balance of the transferred asset. Out-of-order
+                // parallel execution can leave the nonce too high or the balance short
+                // for now, so those retry. A nonce below the sender's is permanent,
+                // unless FDB re-runs this transfer after an unknown commit result:
+                // replays never reach here, so the nonce moved past it by its own commit.
+                let sender_balance = sender.balance_of(tx.token);
+                let sender_state = if nonceless {
+                    SenderState::nonceless(sender_balance)
+                } else {
+                    SenderState::new(sender.nonce, sender_balance)
+                };
+                match validate_stateful(&tx, Some(sender_state)) {
+                    Ok(()) => {}
+                    Err(err) if err.may_become_valid() => return Ok(Attempt::Retry),
+                    Err(ValidationError::NonceTooLow { .. }) if maybe_committed => return Ok(Attempt::Committed),
+                    Err(_) => return Ok(Attempt::Invalid),
+                }
+                
+                // Get receiver account
+                let receiver_key = Self::account_key(key_scheme, tx.to);
+                let receiver_data = trx.get(&receiver_key, false).await?;
+                
+                let receiver = if let Some(data) = &receiver_data {
+                    codec.decode(&data).expect("malformed account value")
+                } else {
+                    AccountValue::new(0, U256::ZERO)
+                };
+                
+                // Execute transfer, keeping each account's code hash and other assets.
+                // The balance was checked above, so a failed debit means nonce overflow.
+                let Some(new_sender) = sender.checked_debit(tx.token, tx.value, !nonceless) else {
+                    return Ok(Attempt::Overflow);
+                };
+                let Some(new_receiver) = receiver.checked_credit(tx.token, tx.value) else {
+                    return Ok(Attempt::Overflow);
+                };
+                
+                // Write updates
+                let new_sender_data = codec.encode(&new_sender);
+                let new_receiver_data = codec.encode(&new_receiver);
+                trx.set(&sender_key, &new_sender_data);
+                trx.set(&receiver_key, &new_receiver_data);
+                if nonceless {
+                    trx.set(&id_key, &[]);
+                }
+                if transfer_log {
+                    trx.atomic_op(
+                        &Self::log_key_template(),
+                        &LoggedTransfer::encode_value(&tx),
+                        MutationType::SetVersionstampedKey,
+                    );
+                }
+
+                if let Some(num_shards) = commitment_shards {
+                    Self::update_commitment(
+                        &trx,
+                        num_shards,
+                        &sender_key,
+                        Some(&sender_data[..]),
+                        &new_sender_data,
+                    );
+                    Self::update_commitment(
+                        &trx,
+                        num_shards,
+                        &receiver_key,
+                        receiver_data.as_deref(),
+                        &new_receiver_data,
+                    );
+                }
+                
+                Ok(Attempt::Committed) // Success!
+            }
+        })
+        .await
     }
 
     /// Returns whether this executor preserves transaction ordering.
@@ -341,7 +990,20 @@ impl FdbParallelExecutor {
 
     /// Returns the name of this executor.
     pub fn name(&self) -> &'static str {
-        "fdb_parallel"
+        match self.dispatch {
+            FdbDispatch::Threads => "fdb_parallel",
+            FdbDispatch::Async => "fdb_async",
+        }
+    }
+
+    /// Returns whether this executor applies token transfers.
+    pub fn supports_tokens(&self) -> bool {
+        true
+    }
+
+    /// Returns whether this executor runs nonce-less workloads.
+    pub fn supports_nonceless(&self) -> bool {
+        true
     }
 
     /// Returns the number of threads.
@@ -351,23 +1013,161 @@ impl FdbParallelExecutor {
 
     // Helper methods for key encoding
     
-    fn account_key(address: Address) -> Vec<u8> {
-        let mut key = b"account/".to_vec();
-        key.extend_from_slice(keccak256(address.as_slice()).as_slice());
+    pub(crate) fn account_key(key_scheme: KeyScheme, address: Address) -> Vec<u8> {
+        Self::prefixed_account_key(key_scheme, key_scheme.key(address))
+    }
+
+    /// Prefixes the significant bytes of a padded `key`.
+    fn prefixed_account_key(key_scheme: KeyScheme, key: B256) -> Vec<u8> {
+        let mut account_key = ACCOUNT_PREFIX.to_vec();
+        account_key.extend_from_slice(&key[..key_scheme.key_len()]);
+        account_key
+    }
+
+    pub(crate) fn code_key(code_hash: B256) -> Vec<u8> {
+        let mut key = CODE_PREFIX.to_vec();
+        key.extend_from_slice(code_hash.as_slice());
         key
     }
-    
-    fn encode_account(nonce: u64, balance: U256) -> Vec<u8> {
-        let mut data = Vec::new();
-        data.extend_from_slice(&nonce.to_be_bytes());
-        data.extend_from_slice(&balance.to_be_bytes::<32>());
-        data
+
+    fn tx_id_key(id: u64) -> Vec<u8> {
+        let mut key = TX_ID_PREFIX.to_vec();
+        key.extend_from_slice(&id.to_be_bytes());
+        key
     }
-    
-    fn decode_account(data: &[u8]) -> (u64, U256) {
-        let nonce = u64::from_be_bytes(data[0..8].try_into().unwrap());
-        let balance = U256::from_be_bytes::<32>(data[8..40].try_into().unwrap());
-        (nonce, balance)
+
+    /// Key of a log entry with the versionstamp left for FDB to fill in,
+    /// followed by the placeholder's offset as FDB expects.
+    fn log_key_template() -> Vec<u8> {
+        let mut key = LOG_PREFIX.to_vec();
+        key.extend_from_slice(&[0; VERSIONSTAMP_LEN]);
+        key.extend_from_slice(&(LOG_PREFIX.len() as u32).to_le_bytes());
+        key
+    }
+
+    /// Reads the transfer log in commit order.
+    ///
+    /// Empty unless the executor was built with
+    /// [`with_transfer_log`](Self::with_transfer_log). Reads the whole log in
+    /// a single transaction, so like [`verify_commitments`](Self::verify_commitments)
+    /// it suits workloads small enough to read within FDB's transaction limit.
+    pub async fn transfer_log(&self) -> Result<Vec<LoggedTransfer>, FdbBindingError> {
+        self.db
+            .run(|trx, _maybe_committed| async move {
+                let mut log = Vec::new();
+                let mut range = Some(RangeOption::from((LOG_PREFIX.to_vec(), b"txlog/\xff".to_vec())));
+                let mut iteration = 1;
+
+                while let Some(option) = range.take() {
+                    let values = trx.get_range(&option, iteration, true).await?;
+                    log.extend(
+                        values
+                            .iter()
+                            .map(|kv| LoggedTransfer::decode(kv.key(), kv.value()).expect("malformed transfer log entry")),
+                    );
+                    range = option.next_range(&values);
+                    iteration += 1;
+                }
+
+                Ok(log)
+            })
+            .await
+    }
+
+    // Helper methods for commitment mode
+
+    fn commitment_key(shard: usize) -> Vec<u8> {
+        let mut key = COMMITMENT_PREFIX.to_vec();
+        key.extend_from_slice(&(shard as u16).to_be_bytes());
+        key
+    }
+
+    /// Shard of an account key, by range of the first byte after the prefix:
+    /// the hashed address's, or the address's under a plain key scheme.
+    fn commitment_shard(account_key: &[u8], num_shards: usize) -> usize {
+        sharding::byte_range_shard(account_key[ACCOUNT_PREFIX.len()], num_shards)
+    }
+
+    fn entry_hash(key: &[u8], value: &[u8]) -> B256 {
+        keccak256([key, value].concat())
+    }
+
+    /// XORs the change from `old` to `new` into the account's shard commitment.
+    fn update_commitment(
+        trx: &Transaction,
+        num_shards: usize,
+        key: &[u8],
+        old: Option<&[u8]>,
+        new: &[u8],
+    ) {
+        let mut delta = Self::entry_hash(key, new);
+        if let Some(old) = old {
+            delta ^= Self::entry_hash(key, old);
+        }
+        let shard = Self::commitment_shard(key, num_shards);
+        trx.atomic_op(&Self::commitment_key(shard), delta.as_slice(), MutationType::BitXor);
+    }
+
+    /// Reads the stored per-shard commitments.
+    ///
+    /// Returns `None` if commitment mode is disabled.
+    pub async fn commitments(&self) -> Result<Option<Vec<B256>>, FdbBindingError> {
+        let Some(num_shards) = self.commitment_shards else {
+            return Ok(None);
+        };
+
+        let commitments = self
+            .db
+            .run(|trx, _maybe_committed| async move {
+                let mut commitments = Vec::with_capacity(num_shards);
+                for shard in 0..num_shards {
+                    let value = trx.get(&Self::commitment_key(shard), false).await?;
+                    commitments.push(value.map_or(B256::ZERO, |value| B256::from_slice(&value)));
+                }
+                Ok(commitments)
+            })
+            .await?;
+
+        Ok(Some(commitments))
+    }
+
+    /// Recomputes every shard commitment from the stored accounts and compares
+    /// it against the incrementally maintained one.
+    ///
+    /// Scans the whole account keyspace in a single read transaction, so it is
+    /// meant for workloads small enough to read within FDB's transaction limit.
+    /// Returns `None` if commitment mode is disabled.
+    pub async fn verify_commitments(&self) -> Result<Option<bool>, FdbBindingError> {
+        let Some(stored) = self.commitments().await? else {
+            return Ok(None);
+        };
+        let num_shards = stored.len();
+
+        let recomputed = self
+            .db
+            .run(|trx, _maybe_committed| async move {
+                let mut commitments = vec![B256::ZERO; num_shards];
+                let mut range = Some(RangeOption::from((
+                    ACCOUNT_PREFIX.to_vec(),
+                    b"account/\xff".to_vec(),
+                )));
+                let mut iteration = 1;
+
+                while let Some(option) = range.take() {
+                    let values = trx.get_range(&option, iteration, true).await?;
+                    for kv in values.iter() {
+                        let shard = Self::commitment_shard(kv.key(), num_shards);
+                        commitments[shard] ^= Self::entry_hash(kv.key(), kv.value());
+                    }
+                    range = option.next_range(&values);
+                    iteration += 1;
+                }
+
+                Ok(commitments)
+            })
+            .await?;
+
+        Ok(Some(recomputed == stored))
     }
     
 }
@@ -388,6 +1188,7 @@ mod tests {
             seed: 42,
             chain_id: 1,
             transactions_per_block: 10,
+            ..Default::default()
         };
 
         let workload = Workload::generate(config);
@@ -402,6 +1203,127 @@ mod tests {
         assert_eq!(executor.num_threads(), 4);
     }
 
+    #[tokio::test]
+    #[ignore] // Requires FoundationDB running
+    async fn test_fdb_nonceless() {
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 50,
+            hot_accounts: 10,
+            transactions_per_block: 10,
+            duplicate_transactions: 3,
+            double_spends: 2,
+            nonceless: true,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let executor = FdbParallelExecutor::new(4, true).await.unwrap();
+
+        // Replays reach the threads and are rejected by the seen-set
+        let result = executor.execute_workload(&workload).await.unwrap();
+        assert_eq!(result.total_successful, 50);
+        assert_eq!(result.total_failed, 5);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires FoundationDB running
+    async fn test_fdb_commitment_mode() {
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 50,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let executor = FdbParallelExecutor::new(4, true).await.unwrap().with_commitment(4);
+
+        let result = executor.execute_workload(&workload).await.unwrap();
+        assert_eq!(result.total_successful, 50);
+
+        let commitments = executor.commitments().await.unwrap().unwrap();
+        assert_eq!(commitments.len(), 4);
+        assert_eq!(executor.verify_commitments().await.unwrap(), Some(true));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires FoundationDB running
+    async fn test_fdb_contract_code() {
+        let executor = FdbParallelExecutor::new(1, true).await.unwrap().with_commitment(4);
+        executor.clear_database().await.unwrap();
+
+        let contract = Address::with_last_byte(1);
+        executor.init_accounts(&[(contract, U256::from(1000))]).await.unwrap();
+        let code = [0x60, 0x00, 0x60, 0x00, 0xf3]; // PUSH1 0 PUSH1 0 RETURN
+        let code_hash = executor.set_code(contract, &code).await.unwrap();
+
+        assert_eq!(code_hash, keccak256(code));
+        assert_eq!(executor.get_code(code_hash).await.unwrap(), Some(code.to_vec()));
+        assert_eq!(executor.verify_commitments().await.unwrap(), Some(true));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires FoundationDB running
+    async fn test_fdb_transfer_log() {
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 50,
+            hot_accounts: 10,
+            seed: 42,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        for dispatch in FdbDispatch::ALL {
+            let executor = FdbParallelExecutor::new(4, true)
+                .await
+                .unwrap()
+                .with_dispatch(dispatch)
+                .with_transfer_log();
+            executor.execute_workload(&workload).await.unwrap();
+
+            // Every transfer is logged once, each sender's in nonce order
+            let log = executor.transfer_log().await.unwrap();
+            assert_eq!(log.len(), 50);
+            assert!(log.windows(2).all(|pair| pair[0].versionstamp < pair[1].versionstamp));
+            assert_eq!(first_sender_order_violation(&log), None);
+        }
+    }
+
+    #[test]
+    fn test_sender_order_violation() {
+        let (alice, bob) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let transfer = |from, nonce: u64| LoggedTransfer {
+            versionstamp: [0; VERSIONSTAMP_LEN],
+            tx_hash: B256::ZERO,
+            from,
+            nonce,
+        };
+
+        let interleaved = [transfer(alice, 0), transfer(bob, 4), transfer(alice, 1), transfer(bob, 5)];
+        assert_eq!(first_sender_order_violation(&interleaved), None);
+        let swapped = [transfer(alice, 0), transfer(alice, 2), transfer(alice, 1)];
+        assert_eq!(first_sender_order_violation(&swapped), Some(1));
+
+        // Entries round-trip through the log encoding
+        let mut key = LOG_PREFIX.to_vec();
+        key.extend_from_slice(&[7; VERSIONSTAMP_LEN]);
+        let logged = LoggedTransfer {
+            versionstamp: [7; VERSIONSTAMP_LEN],
+            ..transfer(alice, 3)
+        };
+        let mut value = logged.tx_hash.to_vec();
+        value.extend_from_slice(alice.as_slice());
+        value.extend_from_slice(&3u64.to_be_bytes());
+        assert_eq!(LoggedTransfer::decode(&key, &value), Some(logged));
+        assert_eq!(LoggedTransfer::decode(&key, &value[1..]), None);
+    }
+
     #[tokio::test]
     #[ignore] // Requires FoundationDB running
     async fn test_clear_database() {
@@ -0,0 +1,565 @@
+//! revm `Database` bridge over FoundationDB state.
+//!
+//! [`FdbStateProvider`] serves revm's synchronous `Database` calls by blocking
+//! on FoundationDB's async reads. Concurrent reads of the same key from
+//! different threads are coalesced: the first caller issues the FDB read and
+//! later callers wait on its result instead of issuing their own.
+//!
+//! Accounts use the same `account/<key>` layout, with the same
+//! [`KeyScheme`] choices, as
+//! [`FdbParallelExecutor`](super::FdbParallelExecutor), so both executors can
+//! be compared against the same cluster. Contract code is stored under
+//! `code/<code_hash>` as well; storage is not persisted, since the benchmark
+//! workload only contains plain transfers.
+//!
+//! [`FdbRevmExecutor::with_prefetch`] adds a [`Prefetcher`] thread that reads
+//! upcoming accounts through a second handle on the same connection, so its
+//! reads are coalesced with the executor's.
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use foundationdb::{Database as FdbDatabase, FdbBindingError};
+use revm::{
+    context::TxEnv,
+    database_interface::{DBErrorMarker, Database, DatabaseCommit, DatabaseRef},
+    primitives::{HashMap, TxKind, KECCAK_EMPTY},
+    state::{Account, AccountInfo, Bytecode},
+    Context, ExecuteCommitEvm, MainBuilder, MainContext,
+};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use super::fdb::FdbParallelExecutor;
+use crate::background::BackgroundAccounts;
+use crate::codec::{AccountCodec, AccountValue, RawCodec};
+use crate::key_scheme::KeyScheme;
+use super::prefetch::{PrefetchCache, PrefetchStats, Prefetcher};
+use super::{CheckpointRecorder, ExecutionResult, Executor, TransactionIndex, TxStatus, REVM_TRANSFER_FEE};
+use crate::recovery::RecoveryBackend;
+use crate::validation::validate_stateless;
+use crate::Workload;
+
+/// Error returned by [`FdbStateProvider`] reads.
+#[derive(Debug, Clone)]
+pub struct FdbStateError(String);
+
+impl fmt::Display for FdbStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FoundationDB error: {}", self.0)
+    }
+}
+
+impl std::error::Error for FdbStateError {}
+
+impl DBErrorMarker for FdbStateError {}
+
+impl From<FdbBindingError> for FdbStateError {
+    fn from(err: FdbBindingError) -> Self {
+        Self(err.to_string())
+    }
+}
+
+/// A read that is currently in flight, shared by every caller waiting on it.
+#[derive(Default)]
+struct InFlight {
+    result: Mutex<Option<Result<Option<Vec<u8>>, FdbStateError>>>,
+    ready: Condvar,
+}
+
+/// Read statistics for an [`FdbStateProvider`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FdbStateStats {
+    /// Reads issued to FoundationDB.
+    pub fdb_reads: usize,
+    /// Reads served by waiting on another caller's in-flight read.
+    pub coalesced_reads: usize,
+    /// Transactions committed to FoundationDB.
+    pub commits: usize,
+    /// Prefetch cache counters, if the provider was used with a prefetcher.
+    pub prefetch: Option<PrefetchStats>,
+}
+
+/// Connection state shared by every handle on a provider.
+struct Connection {
+    db: FdbDatabase,
+    rt: tokio::runtime::Runtime,
+    in_flight: Mutex<HashMap<Vec<u8>, Arc<InFlight>>>,
+    fdb_reads: AtomicUsize,
+    coalesced_reads: AtomicUsize,
+    commits: AtomicUsize,
+}
+
+/// FoundationDB-backed state provider implementing revm's `Database`.
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::{FdbRevmExecutor, FdbStateProvider};
+/// use db_test::{Executor, Workload, WorkloadConfig};
+///
+/// let _network = unsafe { foundationdb::boot() };
+/// let workload = Workload::generate(WorkloadConfig::default());
+/// let provider = FdbStateProvider::new()?;
+/// provider.init_workload(&workload)?;
+/// let (provider, result) = FdbRevmExecutor::new(true).execute(provider, &workload);
+/// println!("{:?}", provider.stats());
+/// ```
+pub struct FdbStateProvider {
+    conn: Arc<Connection>,
+    codec: &'static dyn AccountCodec,
+    key_scheme: KeyScheme,
+    prefetched: Option<Arc<PrefetchCache<AccountInfo>>>,
+}
+
+impl FdbStateProvider {
+    /// Connects to the default FoundationDB cluster.
+    ///
+    /// The FDB network must already be booted.
+    pub fn new() -> Result<Self, FdbStateError> {
+        let db = FdbDatabase::default().map_err(|err| FdbStateError(err.to_string()))?;
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .map_err(|err| FdbStateError(err.to_string()))?;
+
+        let conn = Connection {
+            db,
+            rt,
+            in_flight: Mutex::new(Default::default()),
+            fdb_reads: AtomicUsize::new(0),
+            coalesced_reads: AtomicUsize::new(0),
+            commits: AtomicUsize::new(0),
+        };
+
+        Ok(Self {
+            conn: Arc::new(conn),
+            codec: &RawCodec,
+            key_scheme: KeyScheme::default(),
+            prefetched: None,
+        })
+    }
+
+    /// Stores account values with `codec` instead of the default fixed-width encoding.
+    pub fn with_codec(mut self, codec: &'static dyn AccountCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Derives account keys with `key_scheme` instead of hashing addresses.
+    pub fn with_key_scheme(mut self, key_scheme: KeyScheme) -> Self {
+        self.key_scheme = key_scheme;
+        self
+    }
+
+    /// Returns another handle on the same connection, without a prefetch cache.
+    ///
+    /// Reads through either handle are coalesced and counted together.
+    pub fn handle(&self) -> Self {
+        Self {
+            conn: self.conn.clone(),
+            codec: self.codec,
+            key_scheme: self.key_scheme,
+            prefetched: None,
+        }
+    }
+
+    /// Clears the account and code keyspaces and writes the workload's genesis accounts.
+    pub fn init_workload(&self, workload: &Workload) -> Result<(), FdbStateError> {
+        // Batch size - keep transactions small to avoid hitting time limits
+        const BATCH_SIZE: usize = 1000;
+
+        let initial_balance = workload.initial_balance();
+        let addresses: Vec<Address> = workload.accounts.iter().map(|acc| acc.address).collect();
+
+        let conn = &self.conn;
+        let codec = self.codec;
+        let key_scheme = self.key_scheme;
+        conn.rt.block_on(async {
+            conn.db
+                .run(|trx, _maybe_committed| async move {
+                    trx.clear_range(b"account/", b"account/\xff");
+                    trx.clear_range(b"code/", b"code/\xff");
+                    Ok(())
+                })
+                .await?;
+
+            for chunk in addresses.chunks(BATCH_SIZE) {
+                conn.db
+                    .run(|trx, _maybe_committed| async move {
+                        for &address in chunk {
+                            let key = FdbParallelExecutor::account_key(key_scheme, address);
+                            let value = codec.encode(&AccountValue::new(0, initial_balance));
+                            trx.set(&key, &value);
+                        }
+                        Ok(())
+                    })
+                    .await?;
+            }
+
+            Ok::<_, FdbBindingError>(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Writes `background` next to the workload's accounts; call after `init_workload`.
+    pub fn load_background(&self, background: &BackgroundAccounts) -> Result<(), FdbStateError> {
+        const BATCH_SIZE: usize = 1000;
+
+        let conn = &self.conn;
+        conn.rt.block_on(FdbParallelExecutor::write_background(
+            &conn.db,
+            self.codec,
+            self.key_scheme,
+            None,
+            BATCH_SIZE,
+            background,
+        ))?;
+        Ok(())
+    }
+
+    /// Returns read and commit statistics accumulated so far.
+    pub fn stats(&self) -> FdbStateStats {
+        FdbStateStats {
+            fdb_reads: self.conn.fdb_reads.load(Ordering::Relaxed),
+            coalesced_reads: self.conn.coalesced_reads.load(Ordering::Relaxed),
+            commits: self.conn.commits.load(Ordering::Relaxed),
+            prefetch: self.prefetched.as_ref().map(|cache| cache.stats()),
+        }
+    }
+
+    /// Reads a key, joining an identical in-flight read if there is one.
+    fn get(&self, key: Vec<u8>) -> Result<Option<Vec<u8>>, FdbStateError> {
+        let conn = &self.conn;
+        let (entry, leader) = {
+            let mut in_flight = conn.in_flight.lock().unwrap();
+            match in_flight.get(&key) {
+                Some(entry) => (entry.clone(), false),
+                None => {
+                    let entry = Arc::new(InFlight::default());
+                    in_flight.insert(key.clone(), entry.clone());
+                    (entry, true)
+                }
+            }
+        };
+
+        if !leader {
+            conn.coalesced_reads.fetch_add(1, Ordering::Relaxed);
+            let mut result = entry.result.lock().unwrap();
+            while result.is_none() {
+                result = entry.ready.wait(result).unwrap();
+            }
+            return result.clone().unwrap();
+        }
+
+        conn.fdb_reads.fetch_add(1, Ordering::Relaxed);
+        let result = conn
+            .rt
+            .block_on(conn.db.run(|trx, _maybe_committed| {
+                let key = key.clone();
+                async move { Ok(trx.get(&key, false).await?.map(|value| value.to_vec())) }
+            }))
+            .map_err(FdbStateError::from);
+
+        // Unregister before publishing so late arrivals issue a fresh read.
+        conn.in_flight.lock().unwrap().remove(&key);
+        *entry.result.lock().unwrap() = Some(result.clone());
+        entry.ready.notify_all();
+
+        result
+    }
+}
+
+impl DatabaseRef for FdbStateProvider {
+    type Error = FdbStateError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(info) = self.prefetched.as_ref().and_then(|cache| cache.get(&address)) {
+            return Ok(Some(info));
+        }
+
+        let data = self.get(FdbParallelExecutor::account_key(self.key_scheme, address))?;
+
+        let Some(data) = data else {
+            return Ok(None);
+        };
+        let account = self
+            .codec
+            .decode(&data)
+            .ok_or_else(|| FdbStateError(format!("malformed account value for {}", address)))?;
+
+        Ok(Some(AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            code: None,
+        }))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::default());
+        }
+        match self.get(FdbParallelExecutor::code_key(code_hash))? {
+            Some(code) => Ok(Bytecode::new_raw(code.into())),
+            None => Err(FdbStateError(format!("missing code for hash {}", code_hash))),
+        }
+    }
+
+    fn storage_ref(&self, _address: Address, _index: U256) -> Result<U256, Self::Error> {
+        Ok(U256::ZERO)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        Ok(keccak256(number.to_string().as_bytes()))
+    }
+}
+
+impl Database for FdbStateProvider {
+    type Error = FdbStateError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+impl DatabaseCommit for FdbStateProvider {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        let touched: Vec<(Address, AccountInfo)> = changes
+            .into_iter()
+            .filter(|(_, account)| account.is_touched())
+            .map(|(address, account)| (address, account.info))
+            .collect();
+        let mut writes: Vec<(Vec<u8>, Vec<u8>)> = Vec::with_capacity(touched.len());
+        for (address, info) in &touched {
+            let account = AccountValue::new(info.nonce, info.balance).with_code_hash(info.code_hash);
+            writes.push((FdbParallelExecutor::account_key(self.key_scheme, *address), self.codec.encode(&account)));
+            // Newly deployed code arrives with the account; rewriting known code is harmless
+            if let Some(code) = info.code.as_ref().filter(|_| account.has_code()) {
+                writes.push((FdbParallelExecutor::code_key(info.code_hash), code.original_bytes().to_vec()));
+            }
+        }
+
+        self.conn
+            .rt
+            .block_on(self.conn.db.run(|trx, _maybe_committed| {
+                let writes = &writes;
+                async move {
+                    for (key, value) in writes {
+                        trx.set(key, value);
+                    }
+                    Ok(())
+                }
+            }))
+            .expect("FoundationDB commit failed");
+        self.conn.commits.fetch_add(1, Ordering::Relaxed);
+
+        // Write through so the prefetcher can never resurrect a stale value.
+        if let Some(cache) = &self.prefetched {
+            for (address, info) in touched {
+                cache.insert(address, info);
+            }
+        }
+    }
+}
+
+/// Sequential revm executor running against FoundationDB state.
+///
+/// Identical to [`SequentialExecutor`](super::SequentialExecutor) except that
+/// every account read and every commit goes through [`FdbStateProvider`].
+#[derive(Debug, Clone)]
+pub struct FdbRevmExecutor {
+    /// Whether to verify signatures during execution.
+    pub verify_signatures: bool,
+    /// Prefetch lookahead in transactions, if prefetching is enabled.
+    pub prefetch: Option<usize>,
+}
+
+impl FdbRevmExecutor {
+    /// Creates a new FDB-backed revm executor.
+    pub fn new(verify_signatures: bool) -> Self {
+        Self {
+            verify_signatures,
+            prefetch: None,
+        }
+    }
+
+    /// Prefetches the accounts of the next `lookahead` transactions in the background.
+    pub fn with_prefetch(mut self, lookahead: usize) -> Self {
+        self.prefetch = Some(lookahead);
+        self
+    }
+
+    fn execute_transactions(
+        &self,
+        db: FdbStateProvider,
+        workload: &Workload,
+        cache: Option<&PrefetchCache<AccountInfo>>,
+    ) -> (FdbStateProvider, ExecutionResult) {
+        let mut statuses = Vec::with_capacity(workload.transactions.len());
+        let mut evm = Context::mainnet().with_db(db).build_mainnet();
+
+        // Checkpoints read through a handle without the prefetch cache, so
+        // they don't count as prefetch hits or misses
+        let reader = evm.ctx.journaled_state.database.handle();
+        let mut checkpoints = CheckpointRecorder::new(workload, REVM_TRANSFER_FEE);
+
+        for block in &workload.blocks {
+            for tx in block {
+                let position = statuses.len();
+                if let Some(cache) = cache {
+                    cache.advance(position);
+                }
+
+                if self.verify_signatures && validate_stateless(tx, RecoveryBackend::K256).is_err() {
+                    statuses.push(TxStatus::Failed);
+                    continue;
+                }
+                tx.burn_compute();
+
+                let tx_env = TxEnv {
+                    caller: tx.from,
+                    kind: TxKind::Call(tx.to),
+                    value: tx.value,
+                    gas_limit: 21_000,
+                    gas_price: 1,
+                    nonce: tx.nonce,
+                    chain_id: Some(workload.config.chain_id),
+                    ..Default::default()
+                };
+
+                let success = match evm.transact_commit(tx_env) {
+                    Ok(result) => result.is_success(),
+                    Err(_) => false,
+                };
+                statuses.push(TxStatus::from(success));
+            }
+
+            // Every transaction commits on its own, so FDB holds the block's state
+            checkpoints.record_block(|address| {
+                let info = reader.basic_ref(address).expect("FoundationDB read failed")?;
+                Some((info.nonce, info.balance))
+            });
+        }
+
+        let successful = statuses.iter().filter(|status| status.is_success()).count();
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+
+        (
+            evm.ctx.journaled_state.database,
+            ExecutionResult::new(successful, statuses.len() - successful)
+                .with_tx_index(tx_index)
+                .with_checkpoints(checkpoints.finish()),
+        )
+    }
+}
+
+impl Executor for FdbRevmExecutor {
+    type Database = FdbStateProvider;
+
+    fn execute(
+        &self,
+        db: Self::Database,
+        workload: &Workload,
+    ) -> (Self::Database, ExecutionResult) {
+        let Some(lookahead) = self.prefetch else {
+            return self.execute_transactions(db, workload, None);
+        };
+
+        let reader = db.handle();
+        let (output, _) = Prefetcher::new(lookahead).run(
+            &workload.transactions,
+            |address| reader.basic_ref(address).ok().flatten(),
+            |cache| {
+                let db = FdbStateProvider {
+                    prefetched: Some(cache.clone()),
+                    ..db
+                };
+                self.execute_transactions(db, workload, Some(cache.as_ref()))
+            },
+        );
+
+        output
+    }
+
+    fn preserves_order(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "fdb_revm_sequential"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SequentialExecutor, WorkloadConfig};
+
+    #[test]
+    #[ignore] // Requires FoundationDB running
+    fn test_fdb_revm_executor() {
+        let _network = unsafe { foundationdb::boot() };
+
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            double_spends: 1,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let provider = FdbStateProvider::new().unwrap();
+        provider.init_workload(&workload).unwrap();
+
+        let (provider, result) = FdbRevmExecutor::new(true).execute(provider, &workload);
+
+        assert_eq!(result.successful, 20);
+        assert_eq!(result.failed, 1);
+        assert_eq!(provider.stats().commits, 20);
+
+        let (_, sequential) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+        assert_eq!(result.checkpoints, sequential.checkpoints);
+    }
+
+    #[test]
+    #[ignore] // Requires FoundationDB running
+    fn test_fdb_revm_executor_with_prefetch() {
+        let _network = unsafe { foundationdb::boot() };
+
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 40,
+            hot_accounts: 5,
+            seed: 7,
+            chain_id: 1,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let provider = FdbStateProvider::new().unwrap();
+        provider.init_workload(&workload).unwrap();
+
+        let executor = FdbRevmExecutor::new(true).with_prefetch(8);
+        let (provider, result) = executor.execute(provider, &workload);
+
+        assert_eq!(result.successful, 40);
+        assert!(provider.stats().prefetch.unwrap().hits > 0);
+    }
+}
@@ -2,26 +2,41 @@
 //!
 //! This module provides an executor that uses MDBX for persistent storage,
 //! with hashed accounts and hashed storage tables similar to Reth's design.
+//! Contract bytecode lives in a separate table keyed by code hash, which the
+//! account's `bytecode_hash` points into, as in reth. Account keys follow the
+//! configured [`KeyScheme`]; the table keeps reth's name whichever is used.
 
-use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
 use eyre::Result;
 use reth_db::{mdbx::DatabaseArguments, ClientVersion, DatabaseEnv, DatabaseEnvKind};
 use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW},
     database::Database,
     table::{DupSort, Table},
     transaction::{DbTx, DbTxMut},
 };
-use reth_primitives_traits::{Account, StorageEntry};
+use reth_libmdbx::SyncMode;
+use reth_primitives_traits::{Account, Bytecode, StorageEntry};
 use std::path::Path;
-
-use super::ExecutionResult;
+use std::time::{Duration, Instant};
+
+use super::mdbx_schema::{self, SchemaMigration};
+use super::prefetch::{PrefetchCache, PrefetchStats, Prefetcher};
+use crate::background::BackgroundAccounts;
+use crate::key_scheme::KeyScheme;
+use super::{CheckpointRecorder, ExecutionResult, FailureReason, TransactionIndex, TxStatus};
+use crate::options::{MdbxOptions, MdbxSyncMode};
+use crate::recovery::RecoveryBackend;
+use crate::state_root::{self, AccountProof};
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
 use crate::Workload;
 
 // ============================================================================
 // Table Definitions
 // ============================================================================
 
-/// Hashed accounts table - stores account state indexed by keccak256(address).
+/// Hashed accounts table - stores account state indexed by keccak256(address),
+/// or by another [`KeyScheme`]'s key.
 #[derive(Debug)]
 pub struct HashedAccountsTable;
 
@@ -47,6 +62,29 @@ impl DupSort for HashedStoragesTable {
     type SubKey = B256;
 }
 
+/// Bytecodes table - stores contract bytecode indexed by its keccak256 hash.
+#[derive(Debug)]
+pub struct BytecodesTable;
+
+impl Table for BytecodesTable {
+    const NAME: &'static str = "Bytecodes";
+    const DUPSORT: bool = false;
+    type Key = B256;
+    type Value = Bytecode;
+}
+
+/// Prepared transfers table - stores the before-image of an account for each
+/// transfer that has been prepared but not yet committed by a 2PC coordinator.
+#[derive(Debug)]
+pub struct PreparedTransfersTable;
+
+impl Table for PreparedTransfersTable {
+    const NAME: &'static str = "PreparedTransfers";
+    const DUPSORT: bool = false;
+    type Key = B256;
+    type Value = Account;
+}
+
 // ============================================================================
 // MDBX Database Wrapper
 // ============================================================================
@@ -55,61 +93,373 @@ impl DupSort for HashedStoragesTable {
 pub struct MdbxDatabase {
     /// The MDBX database environment.
     pub(crate) env: DatabaseEnv,
+    /// Schema migrations applied when the environment was opened.
+    migrations: Vec<SchemaMigration>,
+    /// How account keys are derived from addresses.
+    key_scheme: KeyScheme,
 }
 
 impl MdbxDatabase {
     /// Creates a new MDBX database at the specified path.
     pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::create_with_options(path, &MdbxOptions::default())
+    }
+
+    /// Creates a new MDBX database at the specified path with custom options.
+    ///
+    /// An existing database at `path` is opened instead and migrated to the
+    /// current schema version; see [`mdbx_schema`](super::mdbx_schema).
+    pub fn create_with_options<P: AsRef<Path>>(path: P, options: &MdbxOptions) -> Result<Self> {
         let path = path.as_ref();
         std::fs::create_dir_all(path)?;
 
-        let args = DatabaseArguments::new(ClientVersion::default());
+        let sync_mode = match options.sync_mode {
+            MdbxSyncMode::Durable => SyncMode::Durable,
+            MdbxSyncMode::NoMetaSync => SyncMode::NoMetaSync,
+            MdbxSyncMode::SafeNoSync => SyncMode::SafeNoSync,
+            MdbxSyncMode::UtterlyNoSync => SyncMode::UtterlyNoSync,
+        };
+        let args = DatabaseArguments::new(ClientVersion::default()).with_sync_mode(Some(sync_mode));
         let env = DatabaseEnv::open(path, DatabaseEnvKind::RW, args)?;
 
-        // Create tables using low-level API
-        {
-            let tx = env.begin_rw_txn()?;
-            tx.create_db(Some(HashedAccountsTable::NAME), Default::default())?;
-            tx.create_db(Some(HashedStoragesTable::NAME), reth_libmdbx::DatabaseFlags::DUP_SORT)?;
-            tx.commit()?;
-        }
+        // Create or upgrade the tables
+        let migrations = mdbx_schema::migrate(&env)?;
+
+        Ok(Self {
+            env,
+            migrations,
+            key_scheme: options.key_scheme,
+        })
+    }
+
+    /// Returns how account keys are derived from addresses.
+    pub fn key_scheme(&self) -> KeyScheme {
+        self.key_scheme
+    }
+
+    /// Returns the schema migrations applied when the database was opened,
+    /// empty if it was already current.
+    pub fn migrations(&self) -> &[SchemaMigration] {
+        &self.migrations
+    }
 
-        Ok(Self { env })
+    /// Reads the schema version stored in the database.
+    pub fn schema_version(&self) -> Result<u64> {
+        mdbx_schema::schema_version(&self.env)
     }
 
     /// Gets an account by its address.
     pub fn get_account(&self, address: Address) -> Result<Option<Account>> {
         let tx = self.env.tx()?;
-        let hashed_address = keccak256(address.as_slice());
-        Ok(tx.get::<HashedAccountsTable>(hashed_address)?)
+        Ok(tx.get::<HashedAccountsTable>(self.key_scheme.key(address))?)
+    }
+
+    /// Gets several accounts within a single read transaction, in `addresses` order.
+    pub fn get_accounts(&self, addresses: &[Address]) -> Result<Vec<Option<Account>>> {
+        let tx = self.env.tx()?;
+        let accounts = addresses
+            .iter()
+            .map(|&address| tx.get::<HashedAccountsTable>(self.key_scheme.key(address)))
+            .collect::<Result<_, _>>()?;
+        Ok(accounts)
     }
 
     /// Sets an account state.
     pub fn set_account(&self, address: Address, account: Account) -> Result<()> {
         let tx = self.env.tx_mut()?;
-        let hashed_address = keccak256(address.as_slice());
-        tx.put::<HashedAccountsTable>(hashed_address, account)?;
+        tx.put::<HashedAccountsTable>(self.key_scheme.key(address), account)?;
         tx.commit()?;
         Ok(())
     }
 
+    /// Deploys `code` at `address`, creating the account if it does not exist.
+    ///
+    /// The bytecode is stored under its hash, which is returned and recorded
+    /// as the account's `bytecode_hash`.
+    pub fn set_code(&self, address: Address, code: Bytes) -> Result<B256> {
+        let tx = self.env.tx_mut()?;
+        let key = self.key_scheme.key(address);
+        let code_hash = keccak256(&code);
+
+        let mut account = tx.get::<HashedAccountsTable>(key)?.unwrap_or_default();
+        account.bytecode_hash = Some(code_hash);
+        tx.put::<BytecodesTable>(code_hash, Bytecode::new_raw(code))?;
+        tx.put::<HashedAccountsTable>(key, account)?;
+        tx.commit()?;
+        Ok(code_hash)
+    }
+
+    /// Reads the bytecode stored under `code_hash`, if any.
+    pub fn get_code(&self, code_hash: B256) -> Result<Option<Bytes>> {
+        let tx = self.env.tx()?;
+        Ok(tx.get::<BytecodesTable>(code_hash)?.map(|bytecode| bytecode.original_bytes()))
+    }
+
     /// Initializes the database with pre-funded accounts.
     pub fn init_accounts(&self, accounts: &[(Address, U256)]) -> Result<()> {
         let tx = self.env.tx_mut()?;
         
         for &(address, balance) in accounts {
-            let hashed_address = keccak256(address.as_slice());
             let account = Account {
                 nonce: 0,
                 balance,
                 bytecode_hash: None,
             };
-            tx.put::<HashedAccountsTable>(hashed_address, account)?;
+            tx.put::<HashedAccountsTable>(self.key_scheme.key(address), account)?;
         }
         
         tx.commit()?;
         Ok(())
     }
+
+    /// Writes a batch of account states in a single transaction.
+    pub fn set_accounts(&self, accounts: &[(Address, u64, U256)]) -> Result<()> {
+        let tx = self.env.tx_mut()?;
+
+        for &(address, nonce, balance) in accounts {
+            let account = Account {
+                nonce,
+                balance,
+                bytecode_hash: None,
+            };
+            tx.put::<HashedAccountsTable>(self.key_scheme.key(address), account)?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Replaces all accounts with `background`, appended in key order.
+    ///
+    /// Keys are appended with a write cursor and committed every million
+    /// accounts, so loading is bounded by disk bandwidth rather than B-tree
+    /// searches. Write the workload's own accounts afterwards.
+    pub fn load_background(&self, background: &BackgroundAccounts) -> Result<()> {
+        const BACKGROUND_CHUNK: u64 = 1_000_000;
+
+        self.clear_accounts()?;
+        let account = Account {
+            nonce: 0,
+            balance: background.balance(),
+            bytecode_hash: None,
+        };
+        for range in background.chunks(BACKGROUND_CHUNK) {
+            let tx = self.env.tx_mut()?;
+            let mut cursor = tx.cursor_write::<HashedAccountsTable>()?;
+            for hashed_key in background.hashed_keys(range) {
+                cursor.append(self.key_scheme.background_key(hashed_key), &account)?;
+            }
+            drop(cursor);
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Removes all accounts from the database.
+    pub fn clear_accounts(&self) -> Result<()> {
+        let tx = self.env.tx_mut()?;
+        tx.clear::<HashedAccountsTable>()?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Reads all accounts in hashed-address order.
+    ///
+    /// MDBX keeps keys sorted, so with hashed keys a full cursor walk over the
+    /// accounts table yields accounts in exactly the order the state root
+    /// expects. Other key schemes rehash each key's address and sort.
+    fn hashed_accounts(&self) -> Result<Vec<(B256, Account)>> {
+        let mut accounts = self.scan_accounts()?;
+        if self.key_scheme != KeyScheme::Hashed {
+            for (key, _) in &mut accounts {
+                *key = self.key_scheme.hashed_address(key.as_slice());
+            }
+            accounts.sort_unstable_by_key(|(hashed_address, _)| *hashed_address);
+        }
+        Ok(accounts)
+    }
+
+    /// Reads all accounts in key order with a single cursor walk.
+    pub fn scan_accounts(&self) -> Result<Vec<(B256, Account)>> {
+        let tx = self.env.tx()?;
+        let mut cursor = tx.cursor_read::<HashedAccountsTable>()?;
+
+        let mut accounts = Vec::new();
+        for entry in cursor.walk(None)? {
+            accounts.push(entry?);
+        }
+
+        Ok(accounts)
+    }
+
+    /// Computes state root leaves from hashed accounts.
+    fn leaves(accounts: &[(B256, Account)]) -> Vec<B256> {
+        accounts
+            .iter()
+            .map(|(hashed_address, account)| {
+                state_root::leaf_hash(*hashed_address, account.nonce, account.balance)
+            })
+            .collect()
+    }
+
+    /// Computes the state root over all accounts in the database.
+    pub fn state_root(&self) -> Result<B256> {
+        let leaves = Self::leaves(&self.hashed_accounts()?);
+        Ok(state_root::compute_root(&leaves))
+    }
+
+    /// Computes the state root with subtree hashing spread across `num_threads` threads.
+    pub fn parallel_state_root(&self, num_threads: usize) -> Result<B256> {
+        let leaves = Self::leaves(&self.hashed_accounts()?);
+        Ok(state_root::parallel::compute_root(&leaves, num_threads))
+    }
+
+    /// Generates an inclusion proof for an account against the current state root.
+    ///
+    /// Returns `None` if the account does not exist.
+    pub fn get_proof(&self, address: Address) -> Result<Option<AccountProof>> {
+        Ok(self.get_proofs(&[address])?.pop().flatten())
+    }
+
+    /// Generates inclusion proofs for several accounts, reading the table once.
+    ///
+    /// The returned vector is aligned with `addresses`; missing accounts yield `None`.
+    pub fn get_proofs(&self, addresses: &[Address]) -> Result<Vec<Option<AccountProof>>> {
+        let accounts = self.hashed_accounts()?;
+        let leaves = Self::leaves(&accounts);
+        let root = state_root::compute_root(&leaves);
+
+        let proofs = addresses
+            .iter()
+            .map(|&address| {
+                let hashed_address = keccak256(address.as_slice());
+                let leaf_index = accounts
+                    .binary_search_by_key(&hashed_address, |(key, _)| *key)
+                    .ok()?;
+                let account = &accounts[leaf_index].1;
+
+                Some(AccountProof {
+                    address,
+                    nonce: account.nonce,
+                    balance: account.balance,
+                    leaf_index,
+                    num_leaves: leaves.len(),
+                    siblings: state_root::sibling_path(&leaves, leaf_index),
+                    root,
+                })
+            })
+            .collect();
+
+        Ok(proofs)
+    }
+}
+
+/// Moves `value` from `sender` to `receiver` and bumps the sender's nonce.
+///
+/// Returns `None` if the sender's balance is insufficient, or its nonce or the
+/// receiver's balance would overflow.
+pub(super) fn apply_transfer(mut sender: Account, mut receiver: Account, value: U256) -> Option<(Account, Account)> {
+    sender.balance = sender.balance.checked_sub(value)?;
+    sender.nonce = sender.nonce.checked_add(1)?;
+    receiver.balance = receiver.balance.checked_add(value)?;
+    Some((sender, receiver))
+}
+
+/// How a key scheme performs on MDBX for one workload.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeySchemeReport {
+    pub scheme: KeyScheme,
+    /// Accounts in the table, background included.
+    pub accounts: usize,
+    /// Workload accounts rewritten by the timed commit.
+    pub written: usize,
+    /// Time to rewrite every workload account in one transaction and commit it.
+    pub commit_time: Duration,
+    /// Time to walk the whole accounts table with one cursor.
+    pub scan_time: Duration,
+    /// Point reads issued by each read pass: two per transaction.
+    pub reads: usize,
+    /// Time to read every sender and receiver in address order.
+    pub ordered_read_time: Duration,
+    /// Time to read each transaction's sender and receiver in workload order.
+    pub workload_read_time: Duration,
+}
+
+impl KeySchemeReport {
+    /// Loads `workload`'s accounts (after `background`, if any) into a fresh
+    /// database at `path` keyed by `scheme`, then times a commit, a full scan
+    /// and two read passes.
+    ///
+    /// Both read passes look up every transaction's sender and receiver.
+    /// Address-ordered reads favor schemes that keep neighbouring addresses
+    /// on the same pages; the workload-order pass shows how much of that
+    /// locality the workload's own access pattern can use.
+    pub fn measure<P: AsRef<Path>>(
+        path: P,
+        scheme: KeyScheme,
+        workload: &Workload,
+        background: Option<&BackgroundAccounts>,
+    ) -> Result<Self> {
+        let options = MdbxOptions {
+            key_scheme: scheme,
+            ..MdbxOptions::default()
+        };
+        let db = MdbxDatabase::create_with_options(path, &options)?;
+        if let Some(background) = background {
+            db.load_background(background)?;
+        }
+        let balance = U256::from(1_000_000_000_000_000_000_000u128);
+        let accounts: Vec<_> = workload.accounts.iter().map(|acc| (acc.address, balance)).collect();
+        db.init_accounts(&accounts)?;
+
+        let updates: Vec<_> = accounts.iter().map(|&(address, balance)| (address, 1, balance)).collect();
+        let start = Instant::now();
+        db.set_accounts(&updates)?;
+        let commit_time = start.elapsed();
+
+        let start = Instant::now();
+        let total = db.scan_accounts()?.len();
+        let scan_time = start.elapsed();
+
+        let workload_order: Vec<Address> = workload.transactions.iter().flat_map(|tx| [tx.from, tx.to]).collect();
+        let mut ordered = workload_order.clone();
+        ordered.sort_unstable();
+
+        let start = Instant::now();
+        db.get_accounts(&ordered)?;
+        let ordered_read_time = start.elapsed();
+
+        let start = Instant::now();
+        db.get_accounts(&workload_order)?;
+        let workload_read_time = start.elapsed();
+
+        Ok(Self {
+            scheme,
+            accounts: total,
+            written: updates.len(),
+            commit_time,
+            scan_time,
+            reads: workload_order.len(),
+            ordered_read_time,
+            workload_read_time,
+        })
+    }
+
+    /// Accounts scanned per second.
+    pub fn scan_throughput(&self) -> f64 {
+        self.accounts as f64 / self.scan_time.as_secs_f64()
+    }
+
+    /// Address-ordered point reads per second.
+    pub fn ordered_read_throughput(&self) -> f64 {
+        self.reads as f64 / self.ordered_read_time.as_secs_f64()
+    }
+
+    /// Workload-order point reads per second.
+    pub fn workload_read_throughput(&self) -> f64 {
+        self.reads as f64 / self.workload_read_time.as_secs_f64()
+    }
 }
 
 // ============================================================================
@@ -133,6 +483,7 @@ impl MdbxDatabase {
 pub struct MdbxSequentialExecutor {
     db: MdbxDatabase,
     verify_signatures: bool,
+    recovery: RecoveryBackend,
 }
 
 impl MdbxSequentialExecutor {
@@ -145,86 +496,164 @@ impl MdbxSequentialExecutor {
         path: P,
         verify_signatures: bool,
     ) -> Result<Self> {
-        let db = MdbxDatabase::create(path)?;
+        Self::new_with_options(path, verify_signatures, &MdbxOptions::default())
+    }
+
+    /// Creates a new MDBX sequential executor with custom environment options.
+    pub fn new_with_options<P: AsRef<Path>>(
+        path: P,
+        verify_signatures: bool,
+        options: &MdbxOptions,
+    ) -> Result<Self> {
+        let db = MdbxDatabase::create_with_options(path, options)?;
         Ok(Self {
             db,
             verify_signatures,
+            recovery: RecoveryBackend::default(),
         })
     }
 
+    /// Uses `recovery` to recover signers when verifying signatures.
+    pub fn with_recovery(mut self, recovery: RecoveryBackend) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Pre-populates the database with untouched accounts; call before executing.
+    pub fn load_background(&self, background: &BackgroundAccounts) -> Result<()> {
+        self.db.load_background(background)
+    }
+
     /// Executes a workload on the MDBX database.
     pub fn execute_workload(&self, workload: &Workload) -> Result<(ExecutionResult, ())> {
-        // Initialize accounts
+        self.init_workload(workload)?;
+        Ok((self.execute_transactions(workload, None)?, ()))
+    }
+
+    /// Executes a workload while a background thread prefetches the accounts of
+    /// the next `lookahead` transactions.
+    pub fn execute_workload_with_prefetch(
+        &self,
+        workload: &Workload,
+        lookahead: usize,
+    ) -> Result<(ExecutionResult, PrefetchStats)> {
+        self.init_workload(workload)?;
+
+        let (result, stats) = Prefetcher::new(lookahead).run(
+            &workload.transactions,
+            |address| self.db.get_account(address).ok().flatten(),
+            |cache| self.execute_transactions(workload, Some(cache.as_ref())),
+        );
+
+        Ok((result?, stats))
+    }
+
+    /// Writes the workload's pre-funded accounts.
+    fn init_workload(&self, workload: &Workload) -> Result<()> {
         let accounts: Vec<_> = workload
             .accounts
             .iter()
-            .map(|acc| (acc.address, U256::from(1_000_000_000_000_000_000_000u128)))
+            .map(|acc| (acc.address, workload.initial_balance()))
             .collect();
-        
-        self.db.init_accounts(&accounts)?;
 
-        // Execute transactions
-        let mut successful = 0;
-        let mut failed = 0;
+        self.db.init_accounts(&accounts)
+    }
+
+    /// Executes the workload's transactions, reading through `cache` if given.
+    fn execute_transactions(
+        &self,
+        workload: &Workload,
+        cache: Option<&PrefetchCache<Account>>,
+    ) -> Result<ExecutionResult> {
+        let get_account = |address: Address| -> Result<Option<Account>> {
+            match cache.and_then(|cache| cache.get(&address)) {
+                Some(account) => Ok(Some(account)),
+                None => self.db.get_account(address),
+            }
+        };
+        let set_account = |address: Address, account: Account| -> Result<()> {
+            self.db.set_account(address, account)?;
+            if let Some(cache) = cache {
+                cache.insert(address, account);
+            }
+            Ok(())
+        };
+
+        // Execute transactions, recording why each one failed (`None` on success)
+        let mut reasons: Vec<Option<FailureReason>> = Vec::with_capacity(workload.transactions.len());
+
+        // Checkpoint each block once the first transaction past its end is reached
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+        let mut block_ends = workload
+            .blocks
+            .iter()
+            .scan(0, |end, block| {
+                *end += block.len();
+                Some(*end)
+            })
+            .peekable();
+        let state = |address: Address| -> Result<_> {
+            Ok(get_account(address)?.map(|account| (account.nonce, account.balance)))
+        };
+
+        for (position, tx) in workload.transactions.iter().enumerate() {
+            if let Some(cache) = cache {
+                cache.advance(position);
+            }
+            while block_ends.next_if_eq(&position).is_some() {
+                checkpoints.try_record_block(state)?;
+            }
 
-        for tx in &workload.transactions {
             // Verify signature if enabled
             if self.verify_signatures {
-                let recovered = match tx.recover_signer() {
-                    Some(addr) => addr,
-                    None => {
-                        failed += 1;
-                        continue;
-                    }
-                };
-
-                if recovered != tx.from {
-                    failed += 1;
+                if let Err(err) = validate_stateless(tx, self.recovery) {
+                    reasons.push(Some(err.into()));
                     continue;
                 }
             }
-
-            // Get sender account
-            let mut sender = match self.db.get_account(tx.from)? {
-                Some(acc) => acc,
-                None => {
-                    failed += 1;
+            tx.burn_compute();
+
+            // Get sender account and check its nonce and balance
+            let sender = get_account(tx.from)?;
+            let sender = match validate_stateful(tx, sender.as_ref().map(|sender| SenderState::new(sender.nonce, sender.balance))) {
+                Ok(()) => sender.expect("validated sender has an account"),
+                Err(err) => {
+                    reasons.push(Some(err.into()));
                     continue;
                 }
             };
 
-            // Check nonce
-            if sender.nonce != tx.nonce {
-                failed += 1;
-                continue;
-            }
-
-            // Check balance
-            if sender.balance < tx.value {
-                failed += 1;
-                continue;
-            }
-
             // Get receiver account or create new one
-            let mut receiver = self.db.get_account(tx.to)?.unwrap_or(Account {
+            let receiver = get_account(tx.to)?.unwrap_or(Account {
                 nonce: 0,
                 balance: U256::ZERO,
                 bytecode_hash: None,
             });
 
-            // Execute transfer
-            sender.balance -= tx.value;
-            sender.nonce += 1;
-            receiver.balance += tx.value;
+            // Execute transfer; overflow fails the transaction
+            let Some((sender, receiver)) = apply_transfer(sender, receiver, tx.value) else {
+                reasons.push(Some(FailureReason::Overflow));
+                continue;
+            };
 
             // Write back to database
-            self.db.set_account(tx.from, sender)?;
-            self.db.set_account(tx.to, receiver)?;
+            set_account(tx.from, sender)?;
+            set_account(tx.to, receiver)?;
 
-            successful += 1;
+            reasons.push(None);
+        }
+        for _ in block_ends {
+            checkpoints.try_record_block(state)?;
         }
 
-        Ok((ExecutionResult::new(successful, failed), ()))
+        let statuses: Vec<TxStatus> = reasons.iter().map(|reason| TxStatus::from(reason.is_none())).collect();
+        let successful = statuses.iter().filter(|status| status.is_success()).count();
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+
+        Ok(ExecutionResult::new(successful, statuses.len() - successful)
+            .with_tx_index(tx_index)
+            .with_checkpoints(checkpoints.finish())
+            .with_failure_reasons(reasons))
     }
 }
 
@@ -244,6 +673,17 @@ impl MdbxSequentialExecutor {
     pub fn name(&self) -> &'static str {
         "mdbx_sequential"
     }
+
+    /// Returns the underlying database, e.g. for state root or proof queries.
+    pub fn database(&self) -> &MdbxDatabase {
+        &self.db
+    }
+
+    /// Consumes the executor, keeping its database, e.g. to serve the state
+    /// a run produced.
+    pub fn into_database(self) -> MdbxDatabase {
+        self.db
+    }
 }
 
 #[cfg(test)]
@@ -252,6 +692,23 @@ mod tests {
     use crate::WorkloadConfig;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_apply_transfer_rejects_overflow() {
+        let account = |nonce, balance| Account {
+            nonce,
+            balance,
+            bytecode_hash: None,
+        };
+        let one = U256::from(1);
+
+        let (sender, receiver) = apply_transfer(account(0, one), account(0, U256::MAX - one), one).unwrap();
+        assert_eq!((sender.nonce, sender.balance, receiver.balance), (1, U256::ZERO, U256::MAX));
+
+        assert!(apply_transfer(account(0, one), account(0, U256::MAX), one).is_none());
+        assert!(apply_transfer(account(u64::MAX, one), account(0, U256::ZERO), one).is_none());
+        assert!(apply_transfer(account(0, U256::ZERO), account(0, U256::ZERO), one).is_none());
+    }
+
     #[test]
     fn test_mdbx_database_creation() {
         let dir = tempdir().unwrap();
@@ -271,6 +728,23 @@ mod tests {
         assert_eq!(retrieved.unwrap().nonce, 1);
     }
 
+    #[test]
+    fn test_mdbx_contract_code() {
+        let dir = tempdir().unwrap();
+        let db = MdbxDatabase::create(dir.path()).unwrap();
+
+        let contract = Address::with_last_byte(7);
+        db.init_accounts(&[(contract, U256::from(1000))]).unwrap();
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]); // PUSH1 0 PUSH1 0 RETURN
+        let code_hash = db.set_code(contract, code.clone()).unwrap();
+
+        let account = db.get_account(contract).unwrap().unwrap();
+        assert_eq!(account.bytecode_hash, Some(keccak256(&code)));
+        assert_eq!(account.balance, U256::from(1000));
+        assert_eq!(db.get_code(code_hash).unwrap(), Some(code));
+        assert!(db.get_code(B256::ZERO).unwrap().is_none());
+    }
+
     #[test]
     fn test_mdbx_sequential_executor() {
         let dir = tempdir().unwrap();
@@ -281,6 +755,7 @@ mod tests {
             seed: 42,
             chain_id: 1,
             transactions_per_block: 5,
+            ..Default::default()
         };
 
         let workload = Workload::generate(config);
@@ -292,4 +767,101 @@ mod tests {
         assert!(executor.preserves_order());
         assert_eq!(executor.name(), "mdbx_sequential");
     }
+
+    #[test]
+    fn test_mdbx_sequential_executor_with_background() {
+        let dir = tempdir().unwrap();
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            hot_accounts: 10,
+            transactions_per_block: 10,
+            fake_signatures: true,
+            ..Default::default()
+        });
+        let executor = MdbxSequentialExecutor::new(dir.path(), false).unwrap();
+        executor.load_background(&BackgroundAccounts::new(2500, 1)).unwrap();
+
+        let (result, _) = executor.execute_workload(&workload).unwrap();
+        assert_eq!(result.successful, 20);
+        assert_eq!(executor.db.hashed_accounts().unwrap().len(), 2500 + 10);
+    }
+
+    #[test]
+    fn test_mdbx_sequential_executor_with_prefetch() {
+        let dir = tempdir().unwrap();
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 60,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 20,
+            double_spends: 2,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let executor = MdbxSequentialExecutor::new(dir.path(), true).unwrap();
+        let (result, stats) = executor.execute_workload_with_prefetch(&workload, 8).unwrap();
+
+        assert_eq!(result.successful, 60);
+        assert_eq!(result.failed, 2);
+        assert!(stats.hits + stats.misses > 0);
+    }
+
+    #[test]
+    fn test_mdbx_account_proofs() {
+        let dir = tempdir().unwrap();
+        let db = MdbxDatabase::create(dir.path()).unwrap();
+
+        let accounts: Vec<_> = (1..=5u8)
+            .map(|i| (Address::with_last_byte(i), U256::from(i as u64 * 100)))
+            .collect();
+        db.init_accounts(&accounts).unwrap();
+
+        let root = db.state_root().unwrap();
+        let expected = state_root::state_root(
+            accounts.iter().map(|&(address, balance)| (address, 0, balance)),
+        );
+        assert_eq!(root, expected);
+
+        for &(address, balance) in &accounts {
+            let proof = db.get_proof(address).unwrap().unwrap();
+            assert_eq!(proof.balance, balance);
+            assert_eq!(proof.root, root);
+            assert!(proof.verify());
+        }
+
+        assert!(db.get_proof(Address::with_last_byte(99)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_mdbx_key_schemes_agree_on_state_root() {
+        let accounts: Vec<_> = (1..=5u8)
+            .map(|i| (Address::with_last_byte(i), U256::from(i as u64 * 100)))
+            .collect();
+        let expected = state_root::state_root(
+            accounts.iter().map(|&(address, balance)| (address, 0, balance)),
+        );
+
+        for key_scheme in KeyScheme::ALL {
+            let dir = tempdir().unwrap();
+            let options = MdbxOptions {
+                key_scheme,
+                ..MdbxOptions::default()
+            };
+            let db = MdbxDatabase::create_with_options(dir.path(), &options).unwrap();
+            db.init_accounts(&accounts).unwrap();
+
+            assert_eq!(db.state_root().unwrap(), expected, "{} root", key_scheme);
+            let proof = db.get_proof(Address::with_last_byte(3)).unwrap().unwrap();
+            assert_eq!(proof.balance, U256::from(300));
+            assert!(proof.verify());
+
+            db.load_background(&BackgroundAccounts::new(100, 1)).unwrap();
+            db.init_accounts(&accounts).unwrap();
+            assert_eq!(db.scan_accounts().unwrap().len(), 105);
+        }
+    }
 }
@@ -5,14 +5,29 @@
 //! - Caching all state changes in memory during block execution
 //! - Committing once at the end of each block
 //! - Running multiple blocks sequentially
-
-use alloy_primitives::{keccak256, Address, U256};
+//! - Optionally recording each block's account changesets, so state as of
+//!   any committed block can be read back through a [`CommittedStateReader`]
+//! - Optionally treating each block as all-or-nothing, rolling back every
+//!   block in which a transaction fails
+//! - Optionally deferring commits to every N blocks or T milliseconds, with
+//!   later blocks reading the pending writes of earlier ones; each block
+//!   reports its durability lag, the time from the end of its execution
+//!   until the commit that persisted it
+
+use alloy_primitives::{Address, B256, U256};
 use eyre::Result;
+use reth_db_api::models::AccountBeforeTx;
 use reth_primitives_traits::Account;
 use std::collections::HashMap;
 use std::path::Path;
-
-use super::{ExecutionResult, mdbx::MdbxDatabase};
+use std::time::{Duration, Instant};
+
+use super::mdbx_history::{AccountChangeSetsTable, CommittedStateReader};
+use super::{CheckpointRecorder, ExecutionResult, FailureReason, TransactionIndex, TxStatus, mdbx::MdbxDatabase};
+use crate::background::BackgroundAccounts;
+use crate::options::MdbxOptions;
+use crate::recovery::RecoveryBackend;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
 use crate::Workload;
 
 /// Block execution result with per-block statistics.
@@ -24,6 +39,19 @@ pub struct BlockResult {
     pub successful: usize,
     /// Number of failed transactions in this block.
     pub failed: usize,
+    /// State root after the commit that ended with this block (only if state
+    /// root computation is enabled).
+    pub state_root: Option<B256>,
+    /// Time spent computing the state root (only if state root computation is enabled).
+    pub state_root_time: Option<Duration>,
+    /// State checkpoint hash over the accounts the block touched.
+    pub checkpoint: B256,
+    /// Whether the block was rolled back because a transaction failed, with
+    /// atomic blocks; every transaction in it then counts as failed.
+    pub discarded: bool,
+    /// Time from the end of the block's execution until the commit that
+    /// persisted it finished; zero for discarded blocks.
+    pub durability_lag: Duration,
 }
 
 /// Multi-block execution result.
@@ -35,12 +63,45 @@ pub struct MultiBlockResult {
     pub total_successful: usize,
     /// Total failed transactions across all blocks.
     pub total_failed: usize,
+    /// Per-transaction outcomes indexed by transaction hash.
+    pub tx_index: TransactionIndex,
+    /// Why each transaction failed, in workload order; `None` for successes.
+    pub failure_reasons: Vec<Option<FailureReason>>,
+    /// Write transactions committed to the database.
+    pub commits: usize,
 }
 
 impl MultiBlockResult {
+    /// Number of blocks rolled back with atomic blocks.
+    pub fn discarded_blocks(&self) -> usize {
+        self.blocks.iter().filter(|block| block.discarded).count()
+    }
+
+    /// Mean durability lag over committed blocks.
+    pub fn mean_durability_lag(&self) -> Duration {
+        let committed: Vec<_> = self.blocks.iter().filter(|block| !block.discarded).collect();
+        if committed.is_empty() {
+            return Duration::ZERO;
+        }
+        committed.iter().map(|block| block.durability_lag).sum::<Duration>() / committed.len() as u32
+    }
+
+    /// Longest durability lag of any block.
+    pub fn max_durability_lag(&self) -> Duration {
+        self.blocks.iter().map(|block| block.durability_lag).max().unwrap_or_default()
+    }
+
+    /// Total time spent computing state roots across all blocks.
+    pub fn total_state_root_time(&self) -> Duration {
+        self.blocks.iter().filter_map(|block| block.state_root_time).sum()
+    }
+
     /// Converts to a simple ExecutionResult for compatibility.
     pub fn to_execution_result(&self) -> ExecutionResult {
         ExecutionResult::new(self.total_successful, self.total_failed)
+            .with_tx_index(self.tx_index.clone())
+            .with_failure_reasons(self.failure_reasons.clone())
+            .with_checkpoints(self.blocks.iter().map(|block| block.checkpoint).collect())
     }
 }
 
@@ -71,6 +132,18 @@ impl BlockCache {
     }
 }
 
+/// Writes of executed blocks not yet committed.
+#[derive(Debug, Default)]
+struct PendingCommit {
+    /// Latest state of every account the pending blocks wrote.
+    cache: BlockCache,
+    /// Before-images of the accounts each pending block wrote, by block
+    /// number, if changesets are recorded.
+    changesets: Vec<(u64, Vec<AccountBeforeTx>)>,
+    /// Result index of each pending block and when it finished executing.
+    blocks: Vec<(usize, Instant)>,
+}
+
 /// MDBX batched executor with block-level caching and commit.
 ///
 /// This executor processes transactions in blocks from the workload, caching all state changes
@@ -92,6 +165,17 @@ impl BlockCache {
 pub struct MdbxBatchedExecutor {
     db: MdbxDatabase,
     verify_signatures: bool,
+    recovery: RecoveryBackend,
+    /// Threads used to compute the state root after each block, if enabled.
+    state_root_threads: Option<usize>,
+    /// Whether each block's account changesets are recorded.
+    changesets: bool,
+    /// Whether a block with any failed transaction is rolled back whole.
+    atomic_blocks: bool,
+    /// Commit once this many blocks are pending, if set.
+    commit_every: Option<usize>,
+    /// Commit once this long has passed since the last commit, if set.
+    commit_interval: Option<Duration>,
 }
 
 impl MdbxBatchedExecutor {
@@ -104,13 +188,95 @@ impl MdbxBatchedExecutor {
         path: P,
         verify_signatures: bool,
     ) -> Result<Self> {
-        let db = MdbxDatabase::create(path)?;
+        Self::new_with_options(path, verify_signatures, &MdbxOptions::default())
+    }
+
+    /// Creates a new MDBX batched executor with custom environment options.
+    pub fn new_with_options<P: AsRef<Path>>(
+        path: P,
+        verify_signatures: bool,
+        options: &MdbxOptions,
+    ) -> Result<Self> {
+        let db = MdbxDatabase::create_with_options(path, options)?;
         Ok(Self {
             db,
             verify_signatures,
+            recovery: RecoveryBackend::default(),
+            state_root_threads: None,
+            changesets: options.changesets,
+            atomic_blocks: options.atomic_blocks,
+            commit_every: options.commit_every,
+            commit_interval: options.commit_interval,
         })
     }
 
+    /// Uses `recovery` to recover signers when verifying signatures.
+    pub fn with_recovery(mut self, recovery: RecoveryBackend) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Enables state root computation after every commit.
+    ///
+    /// The root is computed with [`crate::state_root::parallel`] using `num_threads`
+    /// threads and its duration is reported separately from execution time.
+    /// With commits deferred, only the last block of each commit gets a root.
+    pub fn with_state_root(mut self, num_threads: usize) -> Self {
+        self.state_root_threads = Some(num_threads.max(1));
+        self
+    }
+
+    /// Records the state each account had before every block that wrote it,
+    /// in the block's commit, for reads through [`committed_state`](Self::committed_state).
+    ///
+    /// Each run replaces the history of the previous one.
+    pub fn with_changesets(mut self) -> Self {
+        self.changesets = true;
+        self
+    }
+
+    /// Makes every block all-or-nothing, as a rollup treats a batch: if any
+    /// transaction fails, the block's writes are rolled back and every
+    /// transaction in it fails, those valid on their own with
+    /// [`FailureReason::BlockDiscarded`].
+    ///
+    /// A discarded block commits nothing, changesets included, so the next
+    /// block executes against the state before it.
+    pub fn with_atomic_blocks(mut self) -> Self {
+        self.atomic_blocks = true;
+        self
+    }
+
+    /// Commits once `blocks` executed blocks are pending instead of after
+    /// every block.
+    ///
+    /// Combined with [`with_commit_interval`](Self::with_commit_interval),
+    /// whichever is reached first commits. Blocks still pending after the
+    /// last one are committed together.
+    pub fn with_commit_every(mut self, blocks: usize) -> Self {
+        self.commit_every = Some(blocks.max(1));
+        self
+    }
+
+    /// Commits after the first block that ends at least `interval` after
+    /// the previous commit instead of after every block.
+    pub fn with_commit_interval(mut self, interval: Duration) -> Self {
+        self.commit_interval = Some(interval);
+        self
+    }
+
+    /// Opens a snapshot reader over the committed state and, with
+    /// [`with_changesets`](Self::with_changesets), its history; block numbers
+    /// are those of [`BlockResult::block_number`].
+    pub fn committed_state(&self) -> Result<CommittedStateReader> {
+        CommittedStateReader::new(&self.db)
+    }
+
+    /// Pre-populates the database with untouched accounts; call before executing.
+    pub fn load_background(&self, background: &BackgroundAccounts) -> Result<()> {
+        self.db.load_background(background)
+    }
+
     /// Executes a workload across multiple blocks with batched commits.
     /// 
     /// The workload must have transactions organized into blocks (via transactions_per_block config).
@@ -119,42 +285,133 @@ impl MdbxBatchedExecutor {
         let accounts: Vec<_> = workload
             .accounts
             .iter()
-            .map(|acc| (acc.address, U256::from(1_000_000_000_000_000_000_000u128)))
+            .map(|acc| (acc.address, workload.initial_balance()))
             .collect();
         
         self.db.init_accounts(&accounts)?;
+        if self.changesets {
+            self.clear_changesets()?;
+        }
+        self.execute_blocks(workload, 0)
+    }
 
-        let mut block_results = Vec::new();
+    /// Executes the workload's blocks on the state already in the database,
+    /// numbering them from `first_block`.
+    ///
+    /// Unlike [`execute_workload`](Self::execute_workload) nothing is reset,
+    /// so a workload from [`Workload::next_round`] continues the previous
+    /// one's state and history.
+    pub fn execute_blocks(&self, workload: &Workload, first_block: u64) -> Result<(MultiBlockResult, ())> {
+        let mut block_results: Vec<BlockResult> = Vec::new();
         let mut total_successful = 0;
         let mut total_failed = 0;
+        let mut reasons = Vec::with_capacity(workload.transactions.len());
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+        let mut pending = PendingCommit::default();
+        let mut commits = 0;
+        let mut last_commit = Instant::now();
 
         // Process each block from the workload
         for (block_num, block_txs) in workload.blocks.iter().enumerate() {
+            let block_number = first_block + block_num as u64;
             // Execute block with caching
-            let (successful, failed) = self.execute_block(block_txs)?;
-            
+            let (successful, failed, discarded) =
+                self.execute_block(block_number, block_txs, &mut pending, &mut reasons)?;
+            if !discarded {
+                pending.blocks.push((block_results.len(), Instant::now()));
+            }
+            let checkpoint = checkpoints.try_record_block(|address| {
+                Ok::<_, eyre::Report>(
+                    self.read_account(&pending.cache, address)?.map(|account| (account.nonce, account.balance)),
+                )
+            })?;
+
+            let last_block = block_num + 1 == workload.blocks.len();
+            let commit = !pending.blocks.is_empty() && (last_block || self.commit_due(&pending, last_commit));
             block_results.push(BlockResult {
-                block_number: block_num as u64,
+                block_number,
                 successful,
                 failed,
+                state_root: None,
+                state_root_time: None,
+                checkpoint,
+                discarded,
+                durability_lag: Duration::ZERO,
             });
 
+            if commit {
+                self.commit_pending(&pending)?;
+                last_commit = Instant::now();
+                commits += 1;
+                for (index, executed) in pending.blocks.drain(..) {
+                    block_results[index].durability_lag = last_commit - executed;
+                }
+                pending.cache.clear();
+                pending.changesets.clear();
+
+                // Compute the post-commit root, timed separately from execution
+                if let Some(num_threads) = self.state_root_threads {
+                    let start = Instant::now();
+                    let root = self.db.parallel_state_root(num_threads)?;
+                    let block = block_results.last_mut().expect("block was just pushed");
+                    block.state_root = Some(root);
+                    block.state_root_time = Some(start.elapsed());
+                }
+            }
+
             total_successful += successful;
             total_failed += failed;
         }
 
+        let statuses: Vec<TxStatus> = reasons.iter().map(|reason| TxStatus::from(reason.is_none())).collect();
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+
         Ok((
             MultiBlockResult {
                 blocks: block_results,
                 total_successful,
                 total_failed,
+                tx_index,
+                failure_reasons: reasons,
+                commits,
             },
             (),
         ))
     }
 
-    /// Executes a single block of transactions with in-memory caching and a single commit.
-    fn execute_block(&self, transactions: &[crate::SignedTransaction]) -> Result<(usize, usize)> {
+    /// Whether the pending blocks are due to be committed.
+    fn commit_due(&self, pending: &PendingCommit, last_commit: Instant) -> bool {
+        match (self.commit_every, self.commit_interval) {
+            (None, None) => true,
+            (blocks, interval) => {
+                blocks.is_some_and(|blocks| pending.blocks.len() >= blocks)
+                    || interval.is_some_and(|interval| last_commit.elapsed() >= interval)
+            }
+        }
+    }
+
+    /// Reads an account through the pending writes, then the database.
+    fn read_account(&self, pending: &BlockCache, address: Address) -> Result<Option<Account>> {
+        match pending.get_account(&address) {
+            Some(account) => Ok(Some(account.clone())),
+            None => self.db.get_account(address),
+        }
+    }
+
+    /// Executes a single block of transactions with in-memory caching and
+    /// adds its writes to `pending`.
+    ///
+    /// Why each transaction failed, or `None` if it succeeded, is appended to
+    /// `reasons` in block order. Returns the successful and failed counts and
+    /// whether the block was discarded.
+    fn execute_block(
+        &self,
+        block_number: u64,
+        transactions: &[crate::SignedTransaction],
+        pending: &mut PendingCommit,
+        reasons: &mut Vec<Option<FailureReason>>,
+    ) -> Result<(usize, usize, bool)> {
+        let first_reason = reasons.len();
         let mut cache = BlockCache::new();
         let mut successful = 0;
         let mut failed = 0;
@@ -163,89 +420,111 @@ impl MdbxBatchedExecutor {
         for tx in transactions {
             // Verify signature if enabled
             if self.verify_signatures {
-                let recovered = match tx.recover_signer() {
-                    Some(addr) => addr,
-                    None => {
-                        failed += 1;
-                        continue;
-                    }
-                };
-
-                if recovered != tx.from {
+                if let Err(err) = validate_stateless(tx, self.recovery) {
                     failed += 1;
+                    reasons.push(Some(err.into()));
                     continue;
                 }
             }
+            tx.burn_compute();
 
-            // Get sender account (from cache or database)
-            let mut sender = if let Some(cached) = cache.get_account(&tx.from) {
-                cached.clone()
-            } else {
-                match self.db.get_account(tx.from)? {
-                    Some(acc) => acc,
-                    None => {
-                        failed += 1;
-                        continue;
-                    }
+            // Get sender account (from cache, pending blocks or database) and check its nonce and balance
+            let sender = match cache.get_account(&tx.from) {
+                Some(cached) => Some(cached.clone()),
+                None => self.read_account(&pending.cache, tx.from)?,
+            };
+            let sender = match validate_stateful(tx, sender.as_ref().map(|sender| SenderState::new(sender.nonce, sender.balance))) {
+                Ok(()) => sender.expect("validated sender has an account"),
+                Err(err) => {
+                    failed += 1;
+                    reasons.push(Some(err.into()));
+                    continue;
                 }
             };
 
-            // Check nonce
-            if sender.nonce != tx.nonce {
-                failed += 1;
-                continue;
-            }
-
-            // Check balance
-            if sender.balance < tx.value {
-                failed += 1;
-                continue;
-            }
-
-            // Get receiver account (from cache or database)
-            let mut receiver = if let Some(cached) = cache.get_account(&tx.to) {
+            // Get receiver account (from cache, pending blocks or database)
+            let receiver = if let Some(cached) = cache.get_account(&tx.to) {
                 cached.clone()
             } else {
-                self.db.get_account(tx.to)?.unwrap_or(Account {
+                self.read_account(&pending.cache, tx.to)?.unwrap_or(Account {
                     nonce: 0,
                     balance: U256::ZERO,
                     bytecode_hash: None,
                 })
             };
 
-            // Execute transfer in cache
-            sender.balance -= tx.value;
-            sender.nonce += 1;
-            receiver.balance += tx.value;
+            // Execute transfer in cache; overflow fails the transaction
+            let Some((sender, receiver)) = super::mdbx::apply_transfer(sender, receiver, tx.value) else {
+                failed += 1;
+                reasons.push(Some(FailureReason::Overflow));
+                continue;
+            };
 
             // Update cache
             cache.set_account(tx.from, sender);
             cache.set_account(tx.to, receiver);
 
             successful += 1;
+            reasons.push(None);
+        }
+
+        // Nothing has been written yet, so dropping the cache rolls the block back
+        if self.atomic_blocks && failed > 0 {
+            for reason in &mut reasons[first_reason..] {
+                reason.get_or_insert(FailureReason::BlockDiscarded);
+            }
+            return Ok((0, transactions.len(), true));
         }
 
-        // Commit all cached changes to database in a single transaction
-        self.commit_cache(&cache)?;
+        // Record the state before the block, then queue its writes for the next commit
+        if self.changesets {
+            let before = cache
+                .accounts
+                .keys()
+                .map(|address| {
+                    Ok(AccountBeforeTx {
+                        address: *address,
+                        info: self.read_account(&pending.cache, *address)?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            pending.changesets.push((block_number, before));
+        }
+        pending.cache.accounts.extend(cache.accounts);
 
-        Ok((successful, failed))
+        Ok((successful, failed, false))
     }
 
-    /// Commits all cached account changes to the database in a single transaction.
-    fn commit_cache(&self, cache: &BlockCache) -> Result<()> {
-        use reth_db_api::{database::Database, transaction::{DbTx, DbTxMut}};
+    /// Commits the pending blocks' account changes to the database in a single
+    /// transaction, along with their changesets if recorded.
+    fn commit_pending(&self, pending: &PendingCommit) -> Result<()> {
+        use reth_db_api::{database::Database, transaction::DbTxMut};
         
         let tx = self.db.env.tx_mut()?;
         
-        for (address, account) in &cache.accounts {
-            let hashed_address = keccak256(address.as_slice());
-            tx.put::<super::mdbx::HashedAccountsTable>(hashed_address, account.clone())?;
+        for (block_number, before) in &pending.changesets {
+            for change in before {
+                tx.put::<AccountChangeSetsTable>(*block_number, change.clone())?;
+            }
+        }
+        for (address, account) in &pending.cache.accounts {
+            tx.put::<super::mdbx::HashedAccountsTable>(self.db.key_scheme().key(*address), account.clone())?;
         }
         
         tx.commit()?;
         Ok(())
     }
 
+    /// Drops the changesets of earlier runs.
+    fn clear_changesets(&self) -> Result<()> {
+        use reth_db_api::{database::Database, transaction::{DbTx, DbTxMut}};
+
+        let tx = self.db.env.tx_mut()?;
+        tx.clear::<AccountChangeSetsTable>()?;
+        tx.commit()?;
+        Ok(())
+    }
+
     /// Returns whether this executor preserves transaction ordering.
     pub fn preserves_order(&self) -> bool {
         true
@@ -273,6 +552,7 @@ mod tests {
             seed: 42,
             chain_id: 1,
             transactions_per_block: 10,
+            ..Default::default()
         };
 
         let workload = Workload::generate(config);
@@ -291,6 +571,191 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mdbx_batched_state_root() {
+        let dir = tempdir().unwrap();
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 30,
+            hot_accounts: 20,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let executor = MdbxBatchedExecutor::new(dir.path(), true)
+            .unwrap()
+            .with_state_root(4);
+
+        let (result, _) = executor.execute_workload(&workload).unwrap();
+
+        assert!(result.blocks.iter().all(|block| block.state_root_time.is_some()));
+        let last_root = result.blocks.last().unwrap().state_root.unwrap();
+        assert_eq!(last_root, executor.db.state_root().unwrap());
+    }
+
+    #[test]
+    fn test_mdbx_batched_history() {
+        let dir = tempdir().unwrap();
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 40,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let executor = MdbxBatchedExecutor::new(dir.path(), true).unwrap().with_changesets();
+        let (result, _) = executor.execute_workload(&workload).unwrap();
+
+        // Checkpoints rebuilt from history match those taken after each commit
+        let reader = executor.committed_state().unwrap();
+        let mut checkpoints = CheckpointRecorder::new(&workload, 0);
+        for block in &result.blocks {
+            let checkpoint = checkpoints
+                .try_record_block(|address| {
+                    Ok::<_, eyre::Report>(
+                        reader.account_at(address, block.block_number)?.map(|account| (account.nonce, account.balance)),
+                    )
+                })
+                .unwrap();
+            assert_eq!(checkpoint, block.checkpoint, "block {}", block.block_number);
+        }
+
+        // The reader keeps its snapshot while another thread commits
+        let sender = workload.transactions[0].from;
+        let balance = reader.balance_at(sender, u64::MAX).unwrap();
+        std::thread::scope(|scope| {
+            scope.spawn(|| executor.db.set_accounts(&[(sender, 0, U256::ZERO)]).unwrap());
+        });
+        assert_eq!(reader.balance_at(sender, u64::MAX).unwrap(), balance);
+        assert_eq!(executor.committed_state().unwrap().balance_at(sender, u64::MAX).unwrap(), U256::ZERO);
+        assert_eq!(reader.balance_at(Address::with_last_byte(0xee), 0).unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn test_mdbx_batched_atomic_blocks() {
+        let dir = tempdir().unwrap();
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 40,
+            hot_accounts: 20,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            double_spends: 2,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let executor = MdbxBatchedExecutor::new(dir.path(), true).unwrap().with_atomic_blocks();
+        let (result, _) = executor.execute_workload(&workload).unwrap();
+
+        // The first block holding a double-spend is rolled back whole
+        let replays = workload.replay_mask();
+        let mut start = 0;
+        let first_failed = workload
+            .blocks
+            .iter()
+            .position(|block| {
+                start += block.len();
+                replays[start - block.len()..start].contains(&true)
+            })
+            .unwrap();
+        assert!(result.blocks[..first_failed].iter().all(|block| !block.discarded && block.failed == 0));
+        let discarded = &result.blocks[first_failed];
+        assert!(discarded.discarded);
+        assert_eq!(discarded.successful, 0);
+        assert_eq!(discarded.failed, workload.blocks[first_failed].len());
+        let reasons = &result.failure_reasons[start - discarded.failed..start];
+        assert!(reasons.iter().all(Option::is_some));
+        assert!(reasons.contains(&Some(FailureReason::BlockDiscarded)));
+        assert_eq!(result.total_successful + result.total_failed, workload.transactions.len());
+    }
+
+    #[test]
+    fn test_mdbx_batched_commit_every() {
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 70,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let dir = tempdir().unwrap();
+        let per_block = MdbxBatchedExecutor::new(dir.path(), true).unwrap();
+        let (expected, _) = per_block.execute_workload(&workload).unwrap();
+        assert_eq!(expected.commits, 7);
+
+        // Later blocks read the pending writes, so outcomes are unchanged
+        let dir = tempdir().unwrap();
+        let executor = MdbxBatchedExecutor::new(dir.path(), true)
+            .unwrap()
+            .with_changesets()
+            .with_commit_every(3);
+        let (result, _) = executor.execute_workload(&workload).unwrap();
+        assert_eq!(result.commits, 3);
+        assert_eq!(result.failure_reasons, expected.failure_reasons);
+        let checkpoints: Vec<_> = result.blocks.iter().map(|block| block.checkpoint).collect();
+        assert_eq!(checkpoints, expected.blocks.iter().map(|block| block.checkpoint).collect::<Vec<_>>());
+        assert_eq!(executor.db.state_root().unwrap(), per_block.db.state_root().unwrap());
+
+        // The first block of a commit waits for the ones after it
+        assert!(result.blocks[0].durability_lag >= result.blocks[2].durability_lag);
+        assert_eq!(result.max_durability_lag(), result.blocks.iter().map(|block| block.durability_lag).max().unwrap());
+
+        // Every block's history is recorded, not only the last of each commit
+        let reader = executor.committed_state().unwrap();
+        let mut history = CheckpointRecorder::new(&workload, 0);
+        for block in &result.blocks {
+            let checkpoint = history
+                .try_record_block(|address| {
+                    Ok::<_, eyre::Report>(
+                        reader.account_at(address, block.block_number)?.map(|account| (account.nonce, account.balance)),
+                    )
+                })
+                .unwrap();
+            assert_eq!(checkpoint, block.checkpoint, "block {}", block.block_number);
+        }
+    }
+
+    #[test]
+    fn test_mdbx_batched_next_round() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 50,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            double_spends: 2,
+            ..Default::default()
+        });
+        let dir = tempdir().unwrap();
+        let executor = MdbxBatchedExecutor::new(dir.path(), true).unwrap().with_changesets();
+        let (first, _) = executor.execute_workload(&workload).unwrap();
+        assert_eq!(first.total_failed, 2);
+
+        // The next round's nonces continue on the committed state
+        let next = workload.next_round();
+        let (second, _) = executor.execute_blocks(&next, first.blocks.len() as u64).unwrap();
+        assert_eq!(second.total_failed, next.num_replays());
+        assert_eq!(second.blocks[0].block_number, first.blocks.len() as u64);
+        let sender = next.transactions.last().unwrap().from;
+        let nonce = executor.db.get_account(sender).unwrap().unwrap().nonce;
+        let sent = workload.transactions.iter().chain(&next.transactions).filter(|tx| tx.from == sender);
+        assert_eq!(nonce, sent.map(|tx| tx.nonce + 1).max().unwrap());
+    }
+
     #[test]
     fn test_block_cache() {
         let mut cache = BlockCache::new();
@@ -322,6 +787,7 @@ mod tests {
             seed: 42,
             chain_id: 1,
             transactions_per_block: 10,
+            ..Default::default()
         };
 
         let workload = Workload::generate(config);
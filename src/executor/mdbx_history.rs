@@ -0,0 +1,92 @@
+//! Account history over committed MDBX blocks.
+//!
+//! With changesets enabled, the batched executor records, in the same write
+//! transaction as each block's commit, the state every account the block
+//! wrote had before it (`None` for accounts the block created), as reth's
+//! `AccountChangeSets` table does. Changesets are keyed by block number and
+//! hold plain addresses whatever [`KeyScheme`](crate::key_scheme::KeyScheme)
+//! the accounts table uses.
+//!
+//! A [`CommittedStateReader`] answers reads as of the end of any committed
+//! block: the state of an account after block `n` is the before-image in the
+//! first changeset past `n` that touches it, or its current state if no later
+//! block changed it. The reader holds one MDBX read transaction, so every read
+//! sees the same snapshot, however many blocks commit meanwhile.
+
+use alloy_primitives::{Address, U256};
+use eyre::Result;
+use reth_db::DatabaseEnv;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    models::AccountBeforeTx,
+    table::{DupSort, Table},
+    transaction::DbTx,
+};
+use reth_primitives_traits::Account;
+
+use super::mdbx::{HashedAccountsTable, MdbxDatabase};
+use crate::key_scheme::KeyScheme;
+
+/// Account changesets table - stores, per block number, the state each
+/// account the block wrote had before it.
+#[derive(Debug)]
+pub struct AccountChangeSetsTable;
+
+impl Table for AccountChangeSetsTable {
+    const NAME: &'static str = "AccountChangeSets";
+    const DUPSORT: bool = true;
+    type Key = u64;
+    type Value = AccountBeforeTx;
+}
+
+impl DupSort for AccountChangeSetsTable {
+    type SubKey = Address;
+}
+
+/// Reads account state as of the end of committed blocks, over one snapshot.
+pub struct CommittedStateReader {
+    tx: <DatabaseEnv as Database>::TX,
+    key_scheme: KeyScheme,
+}
+
+impl CommittedStateReader {
+    /// Opens a snapshot of `db` and its recorded history.
+    pub fn new(db: &MdbxDatabase) -> Result<Self> {
+        Ok(Self {
+            tx: db.env.tx()?,
+            key_scheme: db.key_scheme(),
+        })
+    }
+
+    /// State of `address` after block `block` committed, or `None` if the
+    /// account did not exist then.
+    ///
+    /// Blocks past the last committed one read the snapshot's current state.
+    /// Costs one lookup per later block that changed any account.
+    pub fn account_at(&self, address: Address, block: u64) -> Result<Option<Account>> {
+        let mut cursor = self.tx.cursor_dup_read::<AccountChangeSetsTable>()?;
+        let mut entry = match block.checked_add(1) {
+            Some(next) => cursor.seek(next)?,
+            None => None,
+        };
+        while let Some((number, _)) = entry {
+            if let Some(change) = cursor.seek_by_key_subkey(number, address)? {
+                if change.address == address {
+                    return Ok(change.info);
+                }
+            }
+            entry = match number.checked_add(1) {
+                Some(next) => cursor.seek(next)?,
+                None => None,
+            };
+        }
+        Ok(self.tx.get::<HashedAccountsTable>(self.key_scheme.key(address))?)
+    }
+
+    /// Balance of `address` after block `block` committed; zero if the
+    /// account did not exist then.
+    pub fn balance_at(&self, address: Address, block: u64) -> Result<U256> {
+        Ok(self.account_at(address, block)?.map_or(U256::ZERO, |account| account.balance))
+    }
+}
@@ -0,0 +1,188 @@
+//! On-disk schema versions and migrations for the MDBX backend.
+//!
+//! The schema version is stored under [`SCHEMA_VERSION_KEY`] in the
+//! `Metadata` table. Opening a database runs every migration from its version
+//! up to [`SCHEMA_VERSION`] in order, so existing benchmark databases, large
+//! pre-populated ones included, are upgraded in place instead of regenerated.
+//! A database written before versioning has no `Metadata` table and is read
+//! as version 1; an empty environment is version 0.
+//!
+//! | Version | Change                                                             |
+//! |---------|--------------------------------------------------------------------|
+//! | 1       | `HashedAccounts`, `HashedStorages` and `PreparedTransfers` tables  |
+//! | 2       | `Bytecodes` table for contract code                                |
+//! | 3       | `AccountChangeSets` table for account history                      |
+//!
+//! Each migration records its version once it has run, so one interrupted
+//! before that reruns on the next open: migrations must be idempotent, and
+//! one that rewrites a large table should commit in chunks, as
+//! [`MdbxDatabase::load_background`](super::MdbxDatabase::load_background) does.
+
+use eyre::{bail, Result};
+use reth_db::DatabaseEnv;
+use reth_db_api::{
+    database::Database,
+    table::Table,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_libmdbx::DatabaseFlags;
+
+use super::mdbx::{BytecodesTable, HashedAccountsTable, HashedStoragesTable, PreparedTransfersTable};
+use super::mdbx_history::AccountChangeSetsTable;
+
+/// Schema version written by this build.
+pub const SCHEMA_VERSION: u64 = 3;
+
+/// `Metadata` key holding the schema version.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Metadata table - stores environment-wide settings such as the schema version.
+#[derive(Debug)]
+pub struct MetadataTable;
+
+impl Table for MetadataTable {
+    const NAME: &'static str = "Metadata";
+    const DUPSORT: bool = false;
+    type Key = String;
+    type Value = u64;
+}
+
+/// One step from a schema version to the next.
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaMigration {
+    /// Version the migration upgrades to.
+    pub to: u64,
+    /// What the migration changes.
+    pub description: &'static str,
+    apply: fn(&DatabaseEnv) -> Result<()>,
+}
+
+/// Every migration in order: `MIGRATIONS[v]` upgrades version `v` to `v + 1`.
+const MIGRATIONS: [SchemaMigration; SCHEMA_VERSION as usize] = [
+    SchemaMigration {
+        to: 1,
+        description: "create account, storage and prepared-transfer tables",
+        apply: |env| {
+            create_tables(
+                env,
+                &[
+                    (HashedAccountsTable::NAME, DatabaseFlags::default()),
+                    (HashedStoragesTable::NAME, DatabaseFlags::DUP_SORT),
+                    (PreparedTransfersTable::NAME, DatabaseFlags::default()),
+                ],
+            )
+        },
+    },
+    SchemaMigration {
+        to: 2,
+        description: "create bytecode table",
+        apply: |env| create_tables(env, &[(BytecodesTable::NAME, DatabaseFlags::default())]),
+    },
+    SchemaMigration {
+        to: 3,
+        description: "create account changeset table",
+        apply: |env| create_tables(env, &[(AccountChangeSetsTable::NAME, DatabaseFlags::DUP_SORT)]),
+    },
+];
+
+/// Reads the schema version of `env`.
+pub(crate) fn schema_version(env: &DatabaseEnv) -> Result<u64> {
+    let (has_metadata, has_accounts) = {
+        let tx = env.begin_ro_txn()?;
+        (
+            tx.open_db(Some(MetadataTable::NAME)).is_ok(),
+            tx.open_db(Some(HashedAccountsTable::NAME)).is_ok(),
+        )
+    };
+
+    if has_metadata {
+        if let Some(version) = env.tx()?.get::<MetadataTable>(SCHEMA_VERSION_KEY.to_string())? {
+            return Ok(version);
+        }
+    }
+    Ok(if has_accounts { 1 } else { 0 })
+}
+
+/// Upgrades `env` to [`SCHEMA_VERSION`], returning the migrations applied.
+///
+/// Fails without touching the database if it was written by a newer schema.
+pub(crate) fn migrate(env: &DatabaseEnv) -> Result<Vec<SchemaMigration>> {
+    let version = schema_version(env)?;
+    if version > SCHEMA_VERSION {
+        bail!(
+            "MDBX schema version {} is newer than the supported version {}",
+            version,
+            SCHEMA_VERSION
+        );
+    }
+
+    let pending = &MIGRATIONS[version as usize..];
+    for migration in pending {
+        (migration.apply)(env)?;
+        set_schema_version(env, migration.to)?;
+    }
+    Ok(pending.to_vec())
+}
+
+fn set_schema_version(env: &DatabaseEnv, version: u64) -> Result<()> {
+    create_tables(env, &[(MetadataTable::NAME, DatabaseFlags::default())])?;
+    let tx = env.tx_mut()?;
+    tx.put::<MetadataTable>(SCHEMA_VERSION_KEY.to_string(), version)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Creates any of `tables` that do not exist yet.
+fn create_tables(env: &DatabaseEnv, tables: &[(&str, DatabaseFlags)]) -> Result<()> {
+    let tx = env.begin_rw_txn()?;
+    for &(name, flags) in tables {
+        tx.create_db(Some(name), flags)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::MdbxDatabase;
+    use alloy_primitives::{Address, U256};
+    use reth_db::{mdbx::DatabaseArguments, ClientVersion, DatabaseEnvKind};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_migrates_unversioned_database() {
+        let dir = tempdir().unwrap();
+
+        // Lay out a database the way builds before versioning did
+        {
+            let args = DatabaseArguments::new(ClientVersion::default());
+            let env = DatabaseEnv::open(dir.path(), DatabaseEnvKind::RW, args).unwrap();
+            (MIGRATIONS[0].apply)(&env).unwrap();
+            assert_eq!(schema_version(&env).unwrap(), 1);
+        }
+
+        let account = Address::with_last_byte(1);
+        {
+            let db = MdbxDatabase::create(dir.path()).unwrap();
+            assert_eq!(db.migrations().len(), 2);
+            assert_eq!(db.migrations()[0].to, 2);
+            assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+            db.init_accounts(&[(account, U256::from(1000))]).unwrap();
+        }
+
+        // Reopening a current database migrates nothing and keeps its data
+        {
+            let db = MdbxDatabase::create(dir.path()).unwrap();
+            assert!(db.migrations().is_empty());
+            assert_eq!(db.get_account(account).unwrap().unwrap().balance, U256::from(1000));
+            set_schema_version(&db.env, SCHEMA_VERSION + 1).unwrap();
+        }
+
+        // A database from a newer build is refused rather than misread
+        assert!(MdbxDatabase::create(dir.path()).is_err());
+
+        let fresh = tempdir().unwrap();
+        assert_eq!(MdbxDatabase::create(fresh.path()).unwrap().migrations().len(), 3);
+    }
+}
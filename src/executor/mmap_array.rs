@@ -0,0 +1,308 @@
+//! Memory-mapped flat array of accounts.
+//!
+//! Workload accounts are dense and known before execution starts, so a
+//! backend needs no search structure at all: [`MmapArray`] gives every
+//! account an id up front and stores its nonce and balance in the id's
+//! 40-byte slot of one memory-mapped file. A lookup is a hash of the address
+//! into the id map and a load from the mapping; a write is a store into it.
+//! Nothing is encoded beyond byte order, nothing is logged and, unless a
+//! flush per block is asked for, the kernel writes pages back when it sees
+//! fit.
+//!
+//! That is as little as storage can cost, so [`MmapArrayExecutor`] is the
+//! upper bound for every other backend: what a backend loses against it is
+//! the price of its indexing, encoding and durability.
+//!
+//! An all-zero slot is an account that does not exist, as in Ethereum, where
+//! an account without nonce, balance or code is empty. Like the other
+//! executors that bypass revm it charges no fees.
+
+use alloy_primitives::{Address, U256};
+use memmap2::MmapMut;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::{CheckpointRecorder, ExecutionResult, FailureReason, TransactionIndex, TxStatus};
+use crate::recovery::RecoveryBackend;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::{SignedTransaction, Workload};
+
+/// Bytes of a slot: 8-byte nonce and 32-byte balance, little-endian.
+const SLOT_LEN: usize = 40;
+
+/// Accounts in a memory-mapped array, indexed by id.
+pub struct MmapArray {
+    map: MmapMut,
+    /// Slot id of every account.
+    ids: HashMap<Address, u32>,
+}
+
+impl MmapArray {
+    /// Creates a zeroed array at `path` with one slot per distinct address,
+    /// in the order given.
+    pub fn create(path: impl AsRef<Path>, addresses: impl IntoIterator<Item = Address>) -> io::Result<Self> {
+        let mut ids = HashMap::new();
+        for address in addresses {
+            let next = ids.len() as u32;
+            ids.entry(address).or_insert(next);
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        // A zero-length mapping is an error, so an empty array keeps one unused slot
+        file.set_len((ids.len().max(1) * SLOT_LEN) as u64)?;
+        // SAFETY: the file was just created for this array and nothing else maps or writes it
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        Ok(Self { map, ids })
+    }
+
+    /// Number of slots.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the array has no slots.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Slot id of `address`, if it has one.
+    pub fn id(&self, address: Address) -> Option<u32> {
+        self.ids.get(&address).copied()
+    }
+
+    /// Nonce and balance in slot `id`, or `None` if the account is empty.
+    pub fn get(&self, id: u32) -> Option<(u64, U256)> {
+        let slot = &self.map[id as usize * SLOT_LEN..][..SLOT_LEN];
+        let nonce = u64::from_le_bytes(slot[..8].try_into().expect("8-byte nonce"));
+        let balance = U256::from_le_slice(&slot[8..]);
+        (nonce != 0 || !balance.is_zero()).then_some((nonce, balance))
+    }
+
+    /// Stores a nonce and balance in slot `id`.
+    pub fn set(&mut self, id: u32, nonce: u64, balance: U256) {
+        let slot = &mut self.map[id as usize * SLOT_LEN..][..SLOT_LEN];
+        slot[..8].copy_from_slice(&nonce.to_le_bytes());
+        slot[8..].copy_from_slice(&balance.to_le_bytes::<32>());
+    }
+
+    /// Writes dirty pages back to the file and waits for them.
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+}
+
+/// Result of a memory-mapped array run.
+#[derive(Debug, Clone, Default)]
+pub struct MmapArrayResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// Bytes of the array file.
+    pub file_bytes: u64,
+    /// Time spent flushing the mapping, if flushes were requested.
+    pub flush_time: Duration,
+}
+
+/// Sequential executor over a [`MmapArray`].
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::MmapArrayExecutor;
+/// use db_test::{Workload, WorkloadConfig};
+/// use tempfile::tempdir;
+///
+/// let dir = tempdir()?;
+/// let executor = MmapArrayExecutor::new(dir.path().join("accounts"), true);
+/// let workload = Workload::generate(WorkloadConfig::default());
+/// let result = executor.execute_workload(&workload)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct MmapArrayExecutor {
+    path: PathBuf,
+    verify_signatures: bool,
+    recovery: RecoveryBackend,
+    /// Whether the mapping is flushed to disk after every block.
+    flush_per_block: bool,
+}
+
+impl MmapArrayExecutor {
+    /// Creates an executor keeping its array in the file at `path`.
+    pub fn new(path: impl AsRef<Path>, verify_signatures: bool) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            verify_signatures,
+            recovery: RecoveryBackend::default(),
+            flush_per_block: false,
+        }
+    }
+
+    /// Uses `recovery` to recover signers when verifying signatures.
+    pub fn with_recovery(mut self, recovery: RecoveryBackend) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Flushes the mapping to disk after every block, making each block
+    /// durable before the next one starts.
+    pub fn with_flush_per_block(mut self) -> Self {
+        self.flush_per_block = true;
+        self
+    }
+
+    /// Executes the workload on a fresh array.
+    ///
+    /// Every account and every receiver in the workload gets a slot before
+    /// the first transaction runs.
+    pub fn execute_workload(&self, workload: &Workload) -> io::Result<MmapArrayResult> {
+        let addresses = workload
+            .accounts
+            .iter()
+            .map(|account| account.address)
+            .chain(workload.transactions.iter().map(|tx| tx.to));
+        let mut array = MmapArray::create(&self.path, addresses)?;
+        for account in &workload.accounts {
+            let id = array.id(account.address).expect("every account has a slot");
+            array.set(id, 0, workload.initial_balance());
+        }
+
+        let mut successful = 0;
+        let mut failed = 0;
+        let mut statuses = Vec::with_capacity(workload.transactions.len());
+        let mut reasons = Vec::with_capacity(workload.transactions.len());
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+        let mut flush_time = Duration::ZERO;
+
+        for block in &workload.blocks {
+            for tx in block {
+                let verified = if self.verify_signatures {
+                    validate_stateless(tx, self.recovery)
+                } else {
+                    Ok(())
+                };
+                let outcome = verified.map_err(FailureReason::from).and_then(|()| {
+                    tx.burn_compute();
+                    apply_transfer(&mut array, tx)
+                });
+
+                if outcome.is_ok() {
+                    successful += 1;
+                } else {
+                    failed += 1;
+                }
+                statuses.push(TxStatus::from(outcome.is_ok()));
+                reasons.push(outcome.err());
+            }
+
+            if self.flush_per_block {
+                let start = Instant::now();
+                array.flush()?;
+                flush_time += start.elapsed();
+            }
+            checkpoints.record_block(|address| array.get(array.id(address)?));
+        }
+
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+        Ok(MmapArrayResult {
+            result: ExecutionResult::new(successful, failed)
+                .with_tx_index(tx_index)
+                .with_checkpoints(checkpoints.finish())
+                .with_failure_reasons(reasons),
+            file_bytes: (array.len() * SLOT_LEN) as u64,
+            flush_time,
+        })
+    }
+
+    /// Returns whether this executor preserves transaction ordering.
+    pub fn preserves_order(&self) -> bool {
+        true
+    }
+
+    /// Returns the name of this executor.
+    pub fn name(&self) -> &'static str {
+        "mmap_array"
+    }
+}
+
+/// Applies one transfer, returning why it failed if it did; a failed
+/// transfer changes nothing.
+fn apply_transfer(array: &mut MmapArray, tx: &SignedTransaction) -> Result<(), FailureReason> {
+    let from = array.id(tx.from);
+    let sender = from.and_then(|id| array.get(id));
+    validate_stateful(tx, sender.map(|(nonce, balance)| SenderState::new(nonce, balance)))?;
+    let (nonce, balance) = sender.expect("validated sender has an account");
+    let from = from.expect("validated sender has a slot");
+    let to = array.id(tx.to).expect("every receiver has a slot");
+
+    // A self-transfer credits the debited sender
+    let sender = (
+        nonce.checked_add(1).ok_or(FailureReason::Overflow)?,
+        balance.checked_sub(tx.value).ok_or(FailureReason::InsufficientBalance)?,
+    );
+    let receiver = if to == from {
+        sender
+    } else {
+        array.get(to).unwrap_or_default()
+    };
+    let receiver_balance = receiver.1.checked_add(tx.value).ok_or(FailureReason::Overflow)?;
+    array.set(from, sender.0, sender.1);
+    array.set(to, receiver.0, receiver_balance);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor, WorkloadConfig};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_array_slots() {
+        let dir = tempdir().unwrap();
+        let addresses = [Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(1)];
+        let mut array = MmapArray::create(dir.path().join("accounts"), addresses).unwrap();
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.id(addresses[1]), Some(1));
+        assert_eq!(array.id(Address::ZERO), None);
+
+        // Empty slots read as missing accounts
+        assert_eq!(array.get(1), None);
+        array.set(1, 3, U256::from(7));
+        assert_eq!(array.get(1), Some((3, U256::from(7))));
+        array.flush().unwrap();
+        let file = std::fs::read(dir.path().join("accounts")).unwrap();
+        assert_eq!(file.len(), 2 * SLOT_LEN);
+        assert_eq!(file[SLOT_LEN], 3);
+    }
+
+    #[test]
+    fn test_mmap_array_matches_sequential_executor() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 60,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            double_spends: 2,
+            ..Default::default()
+        });
+        let dir = tempdir().unwrap();
+        let mmap = MmapArrayExecutor::new(dir.path().join("accounts"), true)
+            .with_flush_per_block()
+            .execute_workload(&workload)
+            .unwrap();
+        let (_, revm) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+
+        assert_eq!((mmap.result.successful, mmap.result.failed), (revm.successful, revm.failed));
+        assert_eq!(mmap.result.tx_index.statuses(&workload), revm.tx_index.statuses(&workload));
+        assert_eq!(mmap.result.checkpoints, revm.checkpoints);
+    }
+}
@@ -3,7 +3,20 @@
 //! This module provides the [`Executor`] trait and implementations for
 //! different transaction execution strategies.
 
+mod any;
+mod checkpoint;
+mod concurrency;
+mod conditional;
+mod conflict_hints;
+mod critical_path;
+mod multi_tenant;
+mod native_transfer;
+mod pre_verify;
+mod prefetch;
 mod sequential;
+mod speculative;
+mod tx_index;
+mod wal;
 
 #[cfg(feature = "mdbx")]
 mod mdbx;
@@ -11,27 +24,117 @@ mod mdbx;
 #[cfg(feature = "mdbx")]
 mod mdbx_batched;
 
+#[cfg(feature = "mdbx")]
+mod mdbx_history;
+
+#[cfg(feature = "mdbx")]
+mod mdbx_schema;
+
+#[cfg(feature = "mdbx")]
+mod two_phase;
+
 #[cfg(feature = "fdb")]
 mod fdb;
 
+#[cfg(feature = "fdb")]
+mod fdb_state;
+
 #[cfg(feature = "block-stm")]
 mod block_stm;
 
+#[cfg(feature = "block-stm")]
+mod pipeline;
+
+#[cfg(feature = "block-stm")]
+mod schedule_log;
+
+#[cfg(feature = "block-stm")]
+mod sharded_block_stm;
+
+#[cfg(feature = "verkle")]
+mod verkle;
+
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+mod uring;
+
+#[cfg(feature = "mmap")]
+mod mmap_array;
+
+#[cfg(feature = "remote")]
+pub mod remote;
+
+pub use any::{AnyExecutor, ExecutionReport};
+pub use checkpoint::{first_divergent_block, CheckpointRecorder, REVM_TRANSFER_FEE};
+pub use concurrency::{ConcurrencyStats, WithConcurrencyStats};
+pub use conditional::{
+    AccountItem, ConditionFailed, ConditionalStore, ConditionalWriteExecutor,
+    ConditionalWriteResult, InMemoryConditionalStore, VersionedItem,
+};
+pub use conflict_hints::ConflictHints;
+pub use critical_path::{transaction_costs, CriticalPath, WithCriticalPath};
+pub use multi_tenant::{run_concurrent, run_isolated, MultiTenantResult, TenantResult};
+pub use native_transfer::NativeTransferExecutor;
+pub use pre_verify::{BatchVerifier, CpuBatchVerifier, PreVerifiedExecutor};
+pub use prefetch::{PrefetchCache, PrefetchStats, Prefetcher};
 pub use sequential::SequentialExecutor;
+pub use speculative::{Checkpoint, SpeculativeDb};
+pub use tx_index::{failure_breakdown, format_failures, FailureReason, TransactionIndex, TxLocation, TxStatus};
+pub use wal::{WalExecutor, WalResult, WalState};
 
 #[cfg(feature = "mdbx")]
-pub use mdbx::{MdbxDatabase, MdbxSequentialExecutor};
+pub use mdbx::{KeySchemeReport, MdbxDatabase, MdbxSequentialExecutor};
 
 #[cfg(feature = "mdbx")]
 pub use mdbx_batched::{BlockResult, MdbxBatchedExecutor, MultiBlockResult};
 
+#[cfg(feature = "mdbx")]
+pub use mdbx_history::CommittedStateReader;
+
+#[cfg(feature = "mdbx")]
+pub use mdbx_schema::{MetadataTable, SchemaMigration, SCHEMA_VERSION};
+
+#[cfg(feature = "mdbx")]
+pub use two_phase::{TwoPhaseCommitExecutor, TwoPhaseResult};
+
+#[cfg(feature = "fdb")]
+pub use fdb::{
+    first_sender_order_violation, FdbParallelExecutor, LoggedTransfer, ParallelExecutionResult, ThreadResult,
+};
+
 #[cfg(feature = "fdb")]
-pub use fdb::{FdbParallelExecutor, ParallelExecutionResult, ThreadResult};
+pub use fdb_state::{FdbRevmExecutor, FdbStateError, FdbStateProvider, FdbStateStats};
 
 #[cfg(feature = "block-stm")]
 pub use block_stm::BlockStmExecutor;
 
+#[cfg(feature = "block-stm")]
+pub use block_stm_executor::{SchedulingPolicy, SimulationReport};
+
+#[cfg(feature = "block-stm")]
+pub use pipeline::{BlockSink, MemorySink, PipelineResult, PipelineStats};
+
+#[cfg(feature = "block-stm")]
+pub use schedule_log::{RecordedSchedule, ScheduleLog};
+
+#[cfg(feature = "block-stm")]
+pub use sharded_block_stm::{ShardedBlockStmExecutor, ShardedBlockStmResult};
+
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub use uring::{UringExecutor, UringResult, UringStats, UringStore};
+
+#[cfg(feature = "mmap")]
+pub use mmap_array::{MmapArray, MmapArrayExecutor, MmapArrayResult};
+
+#[cfg(feature = "remote")]
+pub use remote::{MemoryBackend, RemoteDbExecutor, RemoteDbServer, RemoteExecutionResult, StateBackend};
+
+#[cfg(feature = "verkle")]
+pub use verkle::{
+    BalanceTooLarge, VerkleBlockResult, VerkleDatabase, VerkleExecutionResult, VerkleExecutor, VerkleTree,
+};
+
 use crate::Workload;
+use std::fmt;
 
 /// Transaction ordering requirements.
 ///
@@ -77,17 +180,99 @@ impl OrderingMode {
 
 /// Result of executing a workload.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExecutionResult {
     /// Number of successfully executed transactions.
     pub successful: usize,
     /// Number of failed transactions (reverted or validation error).
     pub failed: usize,
+    /// Per-transaction outcomes indexed by transaction hash.
+    pub tx_index: TransactionIndex,
+    /// Average number of transactions executing at once, if the executor samples it.
+    pub parallelism: Option<f64>,
+    /// Per-block state checkpoint hashes, empty if the executor does not emit them.
+    pub checkpoints: Vec<alloy_primitives::B256>,
+    /// Wall-clock time of each block, empty if the executor does not run block by block.
+    pub block_durations: Vec<std::time::Duration>,
+    /// Why each transaction failed, in workload order; `None` for successes
+    /// and failures of unknown cause. Empty if the executor does not record reasons.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub failure_reasons: Vec<Option<FailureReason>>,
+    /// Scheduler work beyond one execution per transaction, if the executor
+    /// schedules optimistically.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scheduler: Option<SchedulerCounters>,
 }
 
 impl ExecutionResult {
     /// Creates a new execution result.
     pub fn new(successful: usize, failed: usize) -> Self {
-        Self { successful, failed }
+        Self {
+            successful,
+            failed,
+            tx_index: TransactionIndex::new(),
+            parallelism: None,
+            checkpoints: Vec::new(),
+            block_durations: Vec::new(),
+            failure_reasons: Vec::new(),
+            scheduler: None,
+        }
+    }
+
+    /// Attaches a transaction hash index to this result.
+    pub fn with_tx_index(mut self, tx_index: TransactionIndex) -> Self {
+        self.tx_index = tx_index;
+        self
+    }
+
+    /// Records the sampled average number of concurrently executing transactions.
+    pub fn with_parallelism(mut self, parallelism: f64) -> Self {
+        self.parallelism = Some(parallelism);
+        self
+    }
+
+    /// Attaches per-block state checkpoint hashes.
+    pub fn with_checkpoints(mut self, checkpoints: Vec<alloy_primitives::B256>) -> Self {
+        self.checkpoints = checkpoints;
+        self
+    }
+
+    /// Attaches the wall-clock time of each block.
+    pub fn with_block_durations(mut self, block_durations: Vec<std::time::Duration>) -> Self {
+        self.block_durations = block_durations;
+        self
+    }
+
+    /// Attaches the optimistic scheduler's counters.
+    pub fn with_scheduler_counters(mut self, scheduler: SchedulerCounters) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    /// Attaches the reason each transaction failed, in workload order.
+    pub fn with_failure_reasons(mut self, failure_reasons: Vec<Option<FailureReason>>) -> Self {
+        self.failure_reasons = failure_reasons;
+        self
+    }
+
+    /// Failures counted by reason; see [`failure_breakdown`].
+    pub fn failure_breakdown(&self) -> Vec<(FailureReason, usize)> {
+        failure_breakdown(&self.failure_reasons)
+    }
+
+    /// Failures without a recorded reason.
+    pub fn unattributed_failures(&self) -> usize {
+        self.failed.saturating_sub(self.failure_reasons.iter().filter(|reason| reason.is_some()).count())
+    }
+
+    /// Failures by reason as one line; see [`format_failures`].
+    pub fn failure_summary(&self) -> String {
+        format_failures(&self.failure_breakdown(), self.failed)
+    }
+
+    /// Looks up a transaction's location and outcome by hash.
+    pub fn lookup(&self, tx_hash: &alloy_primitives::B256) -> Option<&TxLocation> {
+        self.tx_index.get(tx_hash)
     }
 
     /// Total number of transactions processed.
@@ -96,6 +281,32 @@ impl ExecutionResult {
     }
 }
 
+/// What an optimistic scheduler did beyond executing each transaction once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchedulerCounters {
+    /// Executions, re-executions included.
+    pub executions: usize,
+    /// Re-executions skipped because the previous incarnation's reads still held.
+    pub revalidated: usize,
+    /// Queued re-executions dropped because a later incarnation superseded them.
+    pub suppressed_duplicates: usize,
+    /// Readers kept rather than invalidated because a re-execution rewrote
+    /// the value they read unchanged.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub skipped_invalidations: usize,
+}
+
+impl fmt::Display for SchedulerCounters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "scheduler: {} executions, {} revalidated, {} suppressed duplicates, {} skipped invalidations",
+            self.executions, self.revalidated, self.suppressed_duplicates, self.skipped_invalidations
+        )
+    }
+}
+
 /// Trait for different transaction execution strategies.
 ///
 /// This allows benchmarking different approaches to executing transactions,
@@ -105,8 +316,8 @@ impl ExecutionResult {
 ///
 /// ```ignore
 /// use db_test::executor::{Executor, ExecutionResult, OrderingMode};
-/// use db_test::Workload;
-/// use revm::database::{CacheDB, EmptyDB};
+/// use db_test::{GenesisState, Workload};
+/// use revm::database::CacheDB;
 ///
 /// pub struct ParallelExecutor {
 ///     pub verify_signatures: bool,
@@ -114,7 +325,7 @@ impl ExecutionResult {
 /// }
 ///
 /// impl Executor for ParallelExecutor {
-///     type Database = CacheDB<EmptyDB>;
+///     type Database = CacheDB<GenesisState>;
 ///
 ///     fn execute(
 ///         &self,
@@ -157,6 +368,23 @@ pub trait Executor {
         true // Default to strict ordering for safety
     }
 
+    /// Returns whether this executor can run workloads with token transfers.
+    ///
+    /// revm accounts have no token balances, so only executors that keep
+    /// account state themselves can.
+    fn supports_tokens(&self) -> bool {
+        false
+    }
+
+    /// Returns whether this executor can run
+    /// [`nonceless`](crate::WorkloadConfig::nonceless) workloads.
+    ///
+    /// revm checks transactions against sequential sender nonces, so only
+    /// executors that keep account state themselves can.
+    fn supports_nonceless(&self) -> bool {
+        false
+    }
+
     /// Returns a human-readable name for this executor.
     fn name(&self) -> &'static str {
         "unknown"
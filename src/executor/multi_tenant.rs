@@ -0,0 +1,213 @@
+//! Multi-tenant benchmark runner.
+//!
+//! Runs several workloads at once against one shared backend instance to
+//! measure how they interfere. Each tenant gets its own thread and all
+//! tenants are released together, so per-tenant throughput reflects
+//! contention for the backend (write locks, commit queues, conflict
+//! detection) rather than staggered start times.
+//!
+//! Tenants must use disjoint accounts (e.g. workloads generated from
+//! different seeds), otherwise one tenant's transfers would invalidate the
+//! other's nonces.
+
+use crate::executor::ExecutionResult;
+use crate::Workload;
+use std::sync::Barrier;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Outcome of one tenant's workload.
+#[derive(Debug, Clone, Default)]
+pub struct TenantResult {
+    /// Transaction outcomes for this tenant.
+    pub result: ExecutionResult,
+    /// Number of transactions in the tenant's workload.
+    pub num_transactions: usize,
+    /// Wall-clock time from release until this tenant finished.
+    pub elapsed: Duration,
+}
+
+impl TenantResult {
+    /// Transactions per second achieved by this tenant.
+    pub fn throughput_tps(&self) -> f64 {
+        self.num_transactions as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Per-tenant results of a multi-tenant run.
+#[derive(Debug, Clone, Default)]
+pub struct MultiTenantResult {
+    /// One entry per tenant, in workload order.
+    pub tenants: Vec<TenantResult>,
+    /// Wall-clock time until the last tenant finished.
+    pub elapsed: Duration,
+}
+
+impl MultiTenantResult {
+    /// Combined throughput of all tenants over the whole run.
+    pub fn aggregate_tps(&self) -> f64 {
+        let total: usize = self.tenants.iter().map(|t| t.num_transactions).sum();
+        total as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Jain's fairness index over raw per-tenant throughput.
+    ///
+    /// 1.0 means every tenant got the same TPS; `1 / n` means one tenant got
+    /// everything.
+    pub fn fairness(&self) -> f64 {
+        jain_index(self.tenants.iter().map(TenantResult::throughput_tps))
+    }
+
+    /// Jain's fairness index over throughput relative to each tenant's
+    /// isolated baseline.
+    ///
+    /// Tenants with different workloads run at different speeds even alone,
+    /// so this measures whether they were slowed down equally.
+    pub fn relative_fairness(&self, isolated: &MultiTenantResult) -> f64 {
+        jain_index(self.slowdowns(isolated).into_iter().map(|slowdown| 1.0 / slowdown))
+    }
+
+    /// Per-tenant slowdown versus the isolated baseline (`isolated TPS / shared TPS`).
+    pub fn slowdowns(&self, isolated: &MultiTenantResult) -> Vec<f64> {
+        self.tenants
+            .iter()
+            .zip(&isolated.tenants)
+            .map(|(shared, alone)| alone.throughput_tps() / shared.throughput_tps())
+            .collect()
+    }
+}
+
+/// Executes every workload concurrently, one thread per tenant.
+///
+/// `execute` receives the tenant index and its workload and must run it
+/// against the shared backend.
+pub fn run_concurrent<F>(workloads: &[Workload], execute: F) -> MultiTenantResult
+where
+    F: Fn(usize, &Workload) -> ExecutionResult + Sync,
+{
+    let barrier = Barrier::new(workloads.len());
+    let start = Instant::now();
+
+    let tenants = thread::scope(|scope| {
+        let handles: Vec<_> = workloads
+            .iter()
+            .enumerate()
+            .map(|(tenant, workload)| {
+                let barrier = &barrier;
+                let execute = &execute;
+                scope.spawn(move || {
+                    barrier.wait();
+                    let tenant_start = Instant::now();
+                    let result = execute(tenant, workload);
+                    TenantResult {
+                        result,
+                        num_transactions: workload.transactions.len(),
+                        elapsed: tenant_start.elapsed(),
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("tenant thread panicked"))
+            .collect()
+    });
+
+    MultiTenantResult {
+        tenants,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Executes every workload one after another, giving the isolated baseline
+/// for [`MultiTenantResult::relative_fairness`].
+///
+/// `execute` should use a fresh backend per call.
+pub fn run_isolated<F>(workloads: &[Workload], execute: F) -> MultiTenantResult
+where
+    F: Fn(usize, &Workload) -> ExecutionResult,
+{
+    let start = Instant::now();
+    let tenants = workloads
+        .iter()
+        .enumerate()
+        .map(|(tenant, workload)| {
+            let tenant_start = Instant::now();
+            let result = execute(tenant, workload);
+            TenantResult {
+                result,
+                num_transactions: workload.transactions.len(),
+                elapsed: tenant_start.elapsed(),
+            }
+        })
+        .collect();
+
+    MultiTenantResult {
+        tenants,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Jain's fairness index, `(Σx)² / (n·Σx²)`.
+fn jain_index(values: impl Iterator<Item = f64>) -> f64 {
+    let (count, sum, sum_sq) = values.fold((0usize, 0.0, 0.0), |(count, sum, sum_sq), x| {
+        (count + 1, sum + x, sum_sq + x * x)
+    });
+    if count == 0 || sum_sq == 0.0 {
+        1.0
+    } else {
+        sum * sum / (count as f64 * sum_sq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::{ConditionalWriteExecutor, InMemoryConditionalStore};
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_tenants_share_one_store() {
+        let workloads: Vec<_> = [(2, 1), (20, 2)]
+            .into_iter()
+            .map(|(hot_accounts, seed)| {
+                Workload::generate(WorkloadConfig {
+                    num_accounts: 20,
+                    num_transactions: 40,
+                    transactions_per_block: 10,
+                    hot_accounts,
+                    seed,
+                    chain_id: 1,
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let store = InMemoryConditionalStore::new();
+        let executor = ConditionalWriteExecutor::new(2, true);
+        let shared = run_concurrent(&workloads, |_, workload| {
+            executor.execute_workload(&store, workload).result
+        });
+
+        assert_eq!(shared.tenants.len(), 2);
+        for tenant in &shared.tenants {
+            assert_eq!(tenant.result.successful, 40);
+            assert_eq!(tenant.result.failed, 0);
+        }
+        assert!(shared.fairness() > 0.5 && shared.fairness() <= 1.0);
+
+        let isolated = run_isolated(&workloads, |_, workload| {
+            executor
+                .execute_workload(&InMemoryConditionalStore::new(), workload)
+                .result
+        });
+        assert_eq!(shared.slowdowns(&isolated).len(), 2);
+    }
+
+    #[test]
+    fn test_jain_index_bounds() {
+        assert_eq!(jain_index([5.0, 5.0, 5.0].into_iter()), 1.0);
+        assert_eq!(jain_index([10.0, 0.0].into_iter()), 0.5);
+    }
+}
@@ -0,0 +1,170 @@
+//! Sequential executor that applies transfers without revm.
+//!
+//! Workload transactions are plain value transfers, which the EVM turns into
+//! a nonce bump and two balance updates after building an environment,
+//! validating it and running the interpreter's call frame. This module
+//! applies exactly those updates to the `CacheDB` account infos, the way the
+//! MDBX and Block-STM executors do against their own state, so comparing it
+//! with [`SequentialExecutor`](super::SequentialExecutor) on the same
+//! in-memory database isolates revm's overhead from the database's.
+//!
+//! Like the other executors that bypass revm it charges no fees.
+
+use revm::database::CacheDB;
+use revm::database_interface::{Database, DatabaseRef};
+
+use super::{CheckpointRecorder, ExecutionResult, Executor, FailureReason, TransactionIndex, TxStatus};
+use crate::recovery::RecoveryBackend;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::{GenesisState, SignedTransaction, Workload};
+
+/// In-memory sequential executor that applies transfers directly to `CacheDB`.
+#[derive(Debug, Clone)]
+pub struct NativeTransferExecutor {
+    /// Whether to verify signatures during execution.
+    pub verify_signatures: bool,
+    /// Backend used to recover signers when verifying signatures.
+    pub recovery: RecoveryBackend,
+}
+
+impl NativeTransferExecutor {
+    /// Creates a new native transfer executor.
+    pub fn new(verify_signatures: bool) -> Self {
+        Self {
+            verify_signatures,
+            recovery: RecoveryBackend::default(),
+        }
+    }
+
+    /// Uses `recovery` to recover signers when verifying signatures.
+    pub fn with_recovery(mut self, recovery: RecoveryBackend) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Applies one transfer, returning why it failed if it did.
+    fn apply_transfer(db: &mut CacheDB<GenesisState>, tx: &SignedTransaction) -> Result<(), FailureReason> {
+        let sender = db.basic(tx.from).ok().flatten();
+        validate_stateful(tx, sender.as_ref().map(|sender| SenderState::new(sender.nonce, sender.balance)))?;
+        let Some(mut sender) = sender else {
+            return Err(FailureReason::UnknownSender);
+        };
+        sender.nonce = sender.nonce.checked_add(1).ok_or(FailureReason::Overflow)?;
+        sender.balance = sender.balance.checked_sub(tx.value).ok_or(FailureReason::InsufficientBalance)?;
+
+        // A self-transfer credits the debited sender; overflow fails the
+        // transfer before anything is written
+        let mut receiver = if tx.to == tx.from {
+            sender.clone()
+        } else {
+            db.basic(tx.to).ok().flatten().unwrap_or_default()
+        };
+        receiver.balance = receiver.balance.checked_add(tx.value).ok_or(FailureReason::Overflow)?;
+        db.insert_account_info(tx.from, sender);
+        db.insert_account_info(tx.to, receiver);
+        Ok(())
+    }
+}
+
+impl Executor for NativeTransferExecutor {
+    type Database = CacheDB<GenesisState>;
+
+    fn execute(
+        &self,
+        mut db: Self::Database,
+        workload: &Workload,
+    ) -> (Self::Database, ExecutionResult) {
+        let mut successful = 0;
+        let mut failed = 0;
+        let mut statuses = Vec::with_capacity(workload.transactions.len());
+        let mut reasons = Vec::with_capacity(workload.transactions.len());
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+
+        for block in &workload.blocks {
+            for tx in block {
+                let verified = if self.verify_signatures {
+                    validate_stateless(tx, self.recovery)
+                } else {
+                    Ok(())
+                };
+                let outcome = verified.map_err(FailureReason::from).and_then(|()| {
+                    tx.burn_compute();
+                    Self::apply_transfer(&mut db, tx)
+                });
+
+                if outcome.is_ok() {
+                    successful += 1;
+                } else {
+                    failed += 1;
+                }
+                statuses.push(TxStatus::from(outcome.is_ok()));
+                reasons.push(outcome.err());
+            }
+
+            checkpoints.record_block(|address| {
+                let info = db.basic_ref(address).ok().flatten()?;
+                Some((info.nonce, info.balance))
+            });
+        }
+
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+
+        (
+            db,
+            ExecutionResult::new(successful, failed)
+                .with_tx_index(tx_index)
+                .with_checkpoints(checkpoints.finish())
+                .with_failure_reasons(reasons),
+        )
+    }
+
+    fn preserves_order(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "native_transfer_in_memory"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComputeCost, SequentialExecutor, WorkloadConfig};
+    use alloy_primitives::U256;
+
+    #[test]
+    fn test_matches_sequential_executor() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 5,
+            duplicate_transactions: 2,
+            double_spends: 2,
+            fake_signatures: false,
+            tokens: 0,
+            nonceless: false,
+            compute_cost: ComputeCost::default(),
+            near_max_balance: None,
+            hot_probability: 1.0,
+        });
+
+        let (db, native) = NativeTransferExecutor::new(true).execute(workload.create_db(), &workload);
+        let (_, revm) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+
+        assert_eq!((native.successful, native.failed), (revm.successful, revm.failed));
+        assert_eq!(native.tx_index.statuses(&workload), revm.tx_index.statuses(&workload));
+        assert_eq!(native.checkpoints, revm.checkpoints);
+
+        // No fees: the workload's total balance is conserved
+        let total = |db: &CacheDB<GenesisState>| {
+            workload.accounts.iter().fold(U256::ZERO, |total, account| {
+                total + db.basic_ref(account.address).unwrap().unwrap().balance
+            })
+        };
+        assert_eq!(total(&db), total(&workload.create_db()));
+    }
+}
@@ -0,0 +1,227 @@
+//! Pipelined block persistence.
+//!
+//! On a node, a block's writes are persisted before the next block builds on
+//! them. Running block by block, Block-STM keeps every committed block's
+//! writes in memory for the next one, so persisting them need not hold the
+//! next block back: while block `N` is written to a [`BlockSink`], block
+//! `N + 1` executes against the in-memory view.
+//!
+//! The pipeline is double-buffered. The sink thread owns one block's write
+//! set while the engine fills the next, and handing that one over waits for
+//! the sink to take it. Execution therefore never runs more than one block
+//! ahead of persistence, and any time it spends waiting is reported as a
+//! stall.
+
+use crate::executor::ExecutionResult;
+use alloy_primitives::Address;
+use block_stm_executor::AccountState;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Destination of each block's committed writes.
+pub trait BlockSink: Send {
+    /// Persists the accounts one block wrote, in block order.
+    fn commit_block(&mut self, writes: &[(Address, AccountState)]) -> Result<(), String>;
+}
+
+/// Sink holding the latest state of every written account in memory.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    /// Latest committed state of each written account.
+    pub accounts: HashMap<Address, AccountState>,
+    /// Blocks committed so far.
+    pub blocks: usize,
+    /// Artificial latency of each commit, modelling a slow store.
+    pub commit_latency: Duration,
+}
+
+impl MemorySink {
+    /// Creates an empty sink with instant commits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sleeps for `latency` on every commit.
+    pub fn with_commit_latency(mut self, latency: Duration) -> Self {
+        self.commit_latency = latency;
+        self
+    }
+}
+
+impl BlockSink for MemorySink {
+    fn commit_block(&mut self, writes: &[(Address, AccountState)]) -> Result<(), String> {
+        if !self.commit_latency.is_zero() {
+            thread::sleep(self.commit_latency);
+        }
+        self.accounts.extend(writes.iter().cloned());
+        self.blocks += 1;
+        Ok(())
+    }
+}
+
+/// Writes each block's native balances and nonces in one MDBX transaction.
+///
+/// MDBX accounts hold no token balances, so those are dropped.
+#[cfg(feature = "mdbx")]
+impl BlockSink for crate::executor::MdbxDatabase {
+    fn commit_block(&mut self, writes: &[(Address, AccountState)]) -> Result<(), String> {
+        let accounts: Vec<_> = writes
+            .iter()
+            .map(|(address, state)| (*address, state.nonce, state.balance))
+            .collect();
+        self.set_accounts(&accounts).map_err(|err| err.to_string())
+    }
+}
+
+/// Timing of a pipelined run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PipelineStats {
+    /// Blocks the sink committed.
+    pub blocks: usize,
+    /// Time the sink spent committing, overlapped with execution.
+    pub commit_time: Duration,
+    /// Time execution waited for the sink to take a block's writes.
+    pub stall_time: Duration,
+    /// Time spent after the last block waiting for the sink to finish.
+    pub drain_time: Duration,
+}
+
+/// Result of a pipelined Block-STM run.
+#[derive(Debug, Clone, Default)]
+pub struct PipelineResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// Wall-clock time from the first block to the last commit.
+    pub elapsed: Duration,
+    /// Commit and stall times.
+    pub stats: PipelineStats,
+}
+
+impl PipelineResult {
+    /// Fraction of the run spent waiting on the sink, drain included.
+    pub fn stall_fraction(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            0.0
+        } else {
+            (self.stats.stall_time + self.stats.drain_time).as_secs_f64() / self.elapsed.as_secs_f64()
+        }
+    }
+}
+
+/// Runs `execute` while a second thread commits every write set it hands off
+/// to `sink`, then waits for the sink to finish.
+///
+/// Once the sink fails, later hand-offs are dropped and the error is returned
+/// after `execute` completes.
+pub(super) fn pipeline<S, R>(
+    sink: &mut S,
+    execute: impl FnOnce(&mut dyn FnMut(Vec<(Address, AccountState)>)) -> R,
+) -> Result<(R, PipelineStats), String>
+where
+    S: BlockSink,
+{
+    thread::scope(|scope| {
+        // A rendezvous channel: a send completes only once the sink is done
+        // with the previous block and takes the next one
+        let (sender, receiver) = mpsc::sync_channel::<Vec<(Address, AccountState)>>(0);
+        let committer = scope.spawn(move || {
+            let mut blocks = 0;
+            let mut commit_time = Duration::ZERO;
+            for writes in receiver {
+                let start = Instant::now();
+                sink.commit_block(&writes)?;
+                commit_time += start.elapsed();
+                blocks += 1;
+            }
+            Ok::<_, String>((blocks, commit_time))
+        });
+
+        let mut stall_time = Duration::ZERO;
+        let mut sink_failed = false;
+        let output = execute(&mut |writes| {
+            if sink_failed {
+                return;
+            }
+            let start = Instant::now();
+            sink_failed = sender.send(writes).is_err();
+            stall_time += start.elapsed();
+        });
+        drop(sender);
+
+        let start = Instant::now();
+        let committed = committer.join().expect("sink thread panicked");
+        let drain_time = start.elapsed();
+        let (blocks, commit_time) = committed?;
+        Ok((
+            output,
+            PipelineStats {
+                blocks,
+                commit_time,
+                stall_time,
+                drain_time,
+            },
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    /// Fails every commit after the first `limit`.
+    struct FailingSink {
+        limit: usize,
+    }
+
+    impl BlockSink for FailingSink {
+        fn commit_block(&mut self, _writes: &[(Address, AccountState)]) -> Result<(), String> {
+            match self.limit.checked_sub(1) {
+                Some(limit) => {
+                    self.limit = limit;
+                    Ok(())
+                }
+                None => Err("disk full".to_string()),
+            }
+        }
+    }
+
+    fn write_set(balance: u64) -> Vec<(Address, AccountState)> {
+        vec![(Address::with_last_byte(1), AccountState::new(0, U256::from(balance)))]
+    }
+
+    #[test]
+    fn test_pipeline_commits_in_order() {
+        let mut sink = MemorySink::new();
+        let (blocks, stats) = pipeline(&mut sink, |handoff| {
+            for balance in 1..=3 {
+                handoff(write_set(balance));
+            }
+            3
+        })
+        .unwrap();
+
+        assert_eq!(blocks, 3);
+        assert_eq!(stats.blocks, 3);
+        assert_eq!(sink.blocks, 3);
+        assert_eq!(sink.accounts[&Address::with_last_byte(1)].balance, U256::from(3));
+    }
+
+    #[test]
+    fn test_pipeline_reports_sink_failure() {
+        let mut sink = FailingSink { limit: 1 };
+        let mut executed = 0;
+        let result = pipeline(&mut sink, |handoff| {
+            for balance in 1..=3 {
+                handoff(write_set(balance));
+                executed += 1;
+            }
+        });
+
+        // Execution still finishes; the failure surfaces once it has
+        assert_eq!(result.unwrap_err(), "disk full");
+        assert_eq!(executed, 3);
+    }
+}
@@ -0,0 +1,265 @@
+//! Batch signature pre-verification stage.
+//!
+//! Instead of recovering each signer inline, [`PreVerifiedExecutor`] hands the
+//! whole workload to a [`BatchVerifier`] first, drops transactions whose
+//! signature does not recover to `tx.from`, and runs the wrapped executor on
+//! the rest with its own verification disabled. Verification time is part of
+//! the reported execution time and also shown on its own detail line.
+//!
+//! [`CpuBatchVerifier`] splits the batch across threads. Hardware-accelerated
+//! verifiers (e.g. GPU batch recovery) plug in by implementing
+//! [`BatchVerifier`].
+//!
+//! Repeated benchmarks of the same workload can skip recovery altogether:
+//! [`Workload::preverify`] recovers every signer once and caches it, and
+//! [`PreVerifiedExecutor::trusting`] checks transactions against the cache
+//! outside the timed region, so the reported time is execution alone.
+
+use super::{AnyExecutor, ExecutionReport, ExecutionResult};
+use crate::recovery::RecoveryBackend;
+use crate::{SignedTransaction, Workload};
+use alloy_primitives::Address;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Verifies the signatures of many transactions at once.
+pub trait BatchVerifier: Send + Sync {
+    /// Name shown in result tables.
+    fn name(&self) -> &'static str;
+
+    /// Returns, for each transaction, whether its signature recovers to `tx.from`.
+    fn verify_batch(&self, transactions: &[SignedTransaction]) -> Vec<bool>;
+}
+
+/// Verifies a batch on CPU threads.
+#[derive(Debug, Clone, Copy)]
+pub struct CpuBatchVerifier {
+    threads: usize,
+    recovery: RecoveryBackend,
+}
+
+impl CpuBatchVerifier {
+    /// Creates a verifier using `threads` threads and the default recovery backend.
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            recovery: RecoveryBackend::default(),
+        }
+    }
+
+    /// Uses `recovery` to recover signers.
+    pub fn with_recovery(mut self, recovery: RecoveryBackend) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Recovers the signer of each transaction, `None` where the signature is invalid.
+    pub fn recover_batch(&self, transactions: &[SignedTransaction]) -> Vec<Option<Address>> {
+        if transactions.is_empty() {
+            return Vec::new();
+        }
+        let chunk_size = transactions.len().div_ceil(self.threads);
+        let recovery = self.recovery;
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = transactions
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || chunk.iter().map(|tx| recovery.recover_signer(tx)).collect::<Vec<_>>())
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("verifier thread panicked"))
+                .collect()
+        })
+    }
+}
+
+impl BatchVerifier for CpuBatchVerifier {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn verify_batch(&self, transactions: &[SignedTransaction]) -> Vec<bool> {
+        self.recover_batch(transactions)
+            .into_iter()
+            .zip(transactions)
+            .map(|(signer, tx)| signer == Some(tx.from))
+            .collect()
+    }
+}
+
+/// Runs a batch verification stage before an executor.
+///
+/// The wrapped executor should be built with signature verification disabled;
+/// it only sees transactions that passed the batch stage.
+pub struct PreVerifiedExecutor {
+    inner: Box<dyn AnyExecutor>,
+    /// `None` trusts the workload's cached signers instead.
+    verifier: Option<Box<dyn BatchVerifier>>,
+}
+
+impl PreVerifiedExecutor {
+    /// Wraps `inner` with a pre-verification stage using `verifier`.
+    pub fn new(inner: Box<dyn AnyExecutor>, verifier: Box<dyn BatchVerifier>) -> Self {
+        Self {
+            inner,
+            verifier: Some(verifier),
+        }
+    }
+
+    /// Wraps `inner` to check signers against [`Workload::preverify`]'s cache,
+    /// recovering them on the first run only and never timing it.
+    pub fn trusting(inner: Box<dyn AnyExecutor>) -> Self {
+        Self { inner, verifier: None }
+    }
+}
+
+impl AnyExecutor for PreVerifiedExecutor {
+    fn name(&self) -> String {
+        match &self.verifier {
+            Some(verifier) => format!("{}+{}_verify", self.inner.name(), verifier.name()),
+            None => format!("{}+preverified", self.inner.name()),
+        }
+    }
+
+    fn preserves_order(&self) -> bool {
+        self.inner.preserves_order()
+    }
+
+    fn supports_tokens(&self) -> bool {
+        self.inner.supports_tokens()
+    }
+
+    fn supports_nonceless(&self) -> bool {
+        self.inner.supports_nonceless()
+    }
+
+    fn supports_large_balances(&self) -> bool {
+        self.inner.supports_large_balances()
+    }
+
+    fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+        let (valid, verify_time) = match &self.verifier {
+            Some(verifier) => {
+                let start = Instant::now();
+                let valid = verifier.verify_batch(&workload.transactions);
+                (valid, start.elapsed())
+            }
+            None => {
+                let signers = workload.preverify();
+                let valid = signers.iter().zip(&workload.transactions).map(|(&signer, tx)| signer == Some(tx.from));
+                (valid.collect(), Duration::ZERO)
+            }
+        };
+
+        let invalid = valid.iter().filter(|&&valid| !valid).count();
+        let report = if invalid == 0 {
+            self.inner.execute_workload(workload)
+        } else {
+            self.inner.execute_workload(&workload.retain_transactions(&valid))
+        };
+
+        // Retained transactions are re-split into blocks, so checkpoints only line
+        // up with the original blocks if nothing was rejected
+        let checkpoints = if invalid == 0 { report.result.checkpoints } else { Vec::new() };
+        ExecutionReport::new(
+            ExecutionResult::new(report.result.successful, report.result.failed + invalid)
+                .with_checkpoints(checkpoints),
+            verify_time + report.elapsed,
+        )
+        .with_detail(match self.verifier {
+            Some(_) => format!("pre-verification: {:.2} ms, {} rejected", verify_time.as_secs_f64() * 1000.0, invalid),
+            None => format!("pre-verified signers trusted, {} rejected", invalid),
+        })
+    }
+}
+
+impl Workload {
+    /// Recovers the signer of every transaction on all cores and caches them,
+    /// `None` where the signature is invalid. Later calls return the cache.
+    pub fn preverify(&self) -> &[Option<Address>] {
+        self.signers.get_or_init(|| {
+            let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+            CpuBatchVerifier::new(threads).recover_batch(&self.transactions)
+        })
+    }
+
+    /// Signers cached by [`Workload::preverify`], if it ran.
+    pub fn preverified_signers(&self) -> Option<&[Option<Address>]> {
+        self.signers.get().map(Vec::as_slice)
+    }
+
+    /// Returns a copy keeping only the transactions where `keep` is true,
+    /// re-split into blocks of the configured size.
+    pub fn retain_transactions(&self, keep: &[bool]) -> Workload {
+        let transactions: Vec<SignedTransaction> = self
+            .transactions
+            .iter()
+            .zip(keep)
+            .filter(|(_, &keep)| keep)
+            .map(|(tx, _)| tx.clone())
+            .collect();
+        let blocks = transactions
+            .chunks(self.config.transactions_per_block)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+
+        Workload {
+            accounts: self.accounts.clone(),
+            transactions,
+            blocks,
+            config: self.config.clone(),
+            genesis: self.genesis.clone(),
+            signers: OnceLock::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::SequentialExecutor;
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_pre_verification_rejects_forged_transactions() {
+        let mut workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            ..Default::default()
+        });
+        // Forge the last transaction so no later nonce depends on it
+        workload.transactions[19].from = Address::with_last_byte(1);
+
+        let valid = CpuBatchVerifier::new(3).verify_batch(&workload.transactions);
+        assert_eq!(valid.len(), 20);
+        assert_eq!(valid.iter().filter(|&&valid| valid).count(), 19);
+
+        let executor = PreVerifiedExecutor::new(
+            Box::new(SequentialExecutor::with_verification(false)),
+            Box::new(CpuBatchVerifier::new(2)),
+        );
+        let report = executor.execute_workload(&workload);
+        assert_eq!(executor.name(), "sequential_in_memory+cpu_verify");
+        assert_eq!(report.result.successful, 19);
+        assert_eq!(report.result.failed, 1);
+        assert_eq!(report.details.len(), 1);
+
+        assert!(workload.preverified_signers().is_none());
+        let executor = PreVerifiedExecutor::trusting(Box::new(SequentialExecutor::with_verification(false)));
+        let report = executor.execute_workload(&workload);
+        assert_eq!(executor.name(), "sequential_in_memory+preverified");
+        assert_eq!((report.result.successful, report.result.failed), (19, 1));
+        let signers = workload.preverified_signers().unwrap();
+        assert_ne!(signers[19], Some(workload.transactions[19].from));
+        assert!(signers[..19].iter().zip(&workload.transactions).all(|(&signer, tx)| signer == Some(tx.from)));
+    }
+}
@@ -0,0 +1,243 @@
+//! Lookahead prefetching of account state.
+//!
+//! The whole workload is known before execution starts, so the accounts a
+//! transaction will touch can be loaded before execution reaches it. A
+//! [`Prefetcher`] runs a background thread that stays up to `lookahead`
+//! transactions ahead of the executor, loading sender and receiver accounts
+//! from the backend into a shared [`PrefetchCache`].
+//!
+//! The cache is write-through: the executor must [`insert`](PrefetchCache::insert)
+//! every account it writes, after writing it to the backend. The prefetcher
+//! only fills empty slots, so a value it loaded before an executor write can
+//! never replace the newer one.
+
+use alloy_primitives::Address;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::SignedTransaction;
+
+/// Prefetch effectiveness counters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrefetchStats {
+    /// Accounts loaded by the prefetch thread.
+    pub prefetched: usize,
+    /// Executor reads served from the cache.
+    pub hits: usize,
+    /// Executor reads that fell through to the backend.
+    pub misses: usize,
+}
+
+impl PrefetchStats {
+    /// Fraction of executor reads served from the cache.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Account cache shared between the executor and the prefetch thread.
+#[derive(Debug)]
+pub struct PrefetchCache<V> {
+    entries: Mutex<HashMap<Address, V>>,
+    cursor: AtomicUsize,
+    finished: AtomicBool,
+    prefetched: AtomicUsize,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl<V: Clone> PrefetchCache<V> {
+    fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            cursor: AtomicUsize::new(0),
+            finished: AtomicBool::new(false),
+            prefetched: AtomicUsize::new(0),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Looks up an account, counting the read as a hit or a miss.
+    pub fn get(&self, address: &Address) -> Option<V> {
+        let value = self.entries.lock().unwrap().get(address).cloned();
+        let counter = if value.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    /// Records an account written by the executor, replacing any prefetched value.
+    pub fn insert(&self, address: Address, value: V) {
+        self.entries.lock().unwrap().insert(address, value);
+    }
+
+    /// Tells the prefetch thread the executor has reached `position`.
+    pub fn advance(&self, position: usize) {
+        self.cursor.store(position, Ordering::Release);
+    }
+
+    /// Returns the counters accumulated so far.
+    pub fn stats(&self) -> PrefetchStats {
+        PrefetchStats {
+            prefetched: self.prefetched.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn contains(&self, address: &Address) -> bool {
+        self.entries.lock().unwrap().contains_key(address)
+    }
+
+    fn insert_if_absent(&self, address: Address, value: V) {
+        if let Entry::Vacant(entry) = self.entries.lock().unwrap().entry(address) {
+            entry.insert(value);
+            self.prefetched.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Stops the prefetch thread even if the executor panics.
+struct FinishOnDrop<'a, V>(&'a PrefetchCache<V>);
+
+impl<V> Drop for FinishOnDrop<'_, V> {
+    fn drop(&mut self) {
+        self.0.finished.store(true, Ordering::Release);
+    }
+}
+
+/// Background loader that warms a [`PrefetchCache`] ahead of execution.
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::Prefetcher;
+///
+/// let (result, stats) = Prefetcher::new(64).run(
+///     &workload.transactions,
+///     |address| backend.get_account(address).ok().flatten(),
+///     |cache| execute_with_cache(cache),
+/// );
+/// println!("hit rate: {:.1}%", stats.hit_rate() * 100.0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Prefetcher {
+    lookahead: usize,
+}
+
+impl Prefetcher {
+    /// Creates a prefetcher that stays up to `lookahead` transactions ahead.
+    pub fn new(lookahead: usize) -> Self {
+        Self {
+            lookahead: lookahead.max(1),
+        }
+    }
+
+    /// Runs `execute` while a background thread prefetches accounts for
+    /// `transactions` using `load`.
+    ///
+    /// `execute` must call [`PrefetchCache::advance`] as it moves through
+    /// `transactions`, or the prefetcher stalls after the first window.
+    pub fn run<V, L, F, R>(&self, transactions: &[SignedTransaction], load: L, execute: F) -> (R, PrefetchStats)
+    where
+        V: Clone + Send,
+        L: Fn(Address) -> Option<V> + Sync,
+        F: FnOnce(&Arc<PrefetchCache<V>>) -> R,
+    {
+        let cache = Arc::new(PrefetchCache::new());
+
+        let result = thread::scope(|scope| {
+            scope.spawn(|| self.prefetch(transactions, &load, &cache));
+
+            let _finish = FinishOnDrop(&*cache);
+            execute(&cache)
+        });
+
+        (result, cache.stats())
+    }
+
+    fn prefetch<V, L>(&self, transactions: &[SignedTransaction], load: &L, cache: &PrefetchCache<V>)
+    where
+        V: Clone,
+        L: Fn(Address) -> Option<V>,
+    {
+        for (position, tx) in transactions.iter().enumerate() {
+            // Wait until this transaction enters the lookahead window.
+            while position >= cache.cursor.load(Ordering::Acquire) + self.lookahead {
+                if cache.finished.load(Ordering::Acquire) {
+                    return;
+                }
+                thread::yield_now();
+            }
+            if cache.finished.load(Ordering::Acquire) {
+                return;
+            }
+
+            for address in [tx.from, tx.to] {
+                if cache.contains(&address) {
+                    continue;
+                }
+                if let Some(value) = load(address) {
+                    cache.insert_if_absent(address, value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Workload, WorkloadConfig};
+
+    #[test]
+    fn test_executor_writes_win_over_prefetched_values() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 40,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        // Backend state: nonce per account, shared with the loader.
+        let backend = Mutex::new(HashMap::<Address, u64>::new());
+        for account in &workload.accounts {
+            backend.lock().unwrap().insert(account.address, 0);
+        }
+
+        let (applied, stats) = Prefetcher::new(4).run(
+            &workload.transactions,
+            |address| backend.lock().unwrap().get(&address).copied(),
+            |cache| {
+                let mut applied = 0;
+                for (position, tx) in workload.transactions.iter().enumerate() {
+                    cache.advance(position);
+                    let nonce = cache
+                        .get(&tx.from)
+                        .unwrap_or_else(|| backend.lock().unwrap()[&tx.from]);
+                    if nonce == tx.nonce {
+                        backend.lock().unwrap().insert(tx.from, nonce + 1);
+                        cache.insert(tx.from, nonce + 1);
+                        applied += 1;
+                    }
+                }
+                applied
+            },
+        );
+
+        assert_eq!(applied, 40);
+        assert_eq!(stats.hits + stats.misses, 40);
+    }
+}
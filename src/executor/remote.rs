@@ -0,0 +1,470 @@
+//! Remote database executor over a thin TCP protocol.
+//!
+//! This module models network-attached storage without requiring FoundationDB:
+//! a [`RemoteDbServer`] wraps any [`StateBackend`] and serves batched reads and
+//! commits over TCP, while [`RemoteDbExecutor`] executes transfers locally and
+//! talks to the server once per block for reads and once per block for writes.
+//!
+//! # Wire Format
+//!
+//! Every message is a 4-byte big-endian length followed by a bincode-encoded
+//! [`Request`] or [`Response`]. Each request receives exactly one response.
+
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use super::{CheckpointRecorder, ExecutionResult, TransactionIndex, TxStatus};
+use crate::recovery::RecoveryBackend;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::Workload;
+
+/// Upper bound on a single frame, guarding against corrupt length prefixes.
+const MAX_FRAME_SIZE: usize = 256 * 1024 * 1024;
+
+/// Account state as transferred over the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteAccount {
+    /// Account nonce.
+    pub nonce: u64,
+    /// Account balance in wei.
+    pub balance: U256,
+}
+
+/// Client-to-server message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Request {
+    /// Clears all state and installs the given pre-funded accounts.
+    Reset(Vec<(Address, U256)>),
+    /// Reads a batch of accounts.
+    Get(Vec<Address>),
+    /// Atomically writes a batch of account states.
+    Commit(Vec<(Address, RemoteAccount)>),
+}
+
+/// Server-to-client message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Response {
+    /// Accounts for a [`Request::Get`], aligned with the requested addresses.
+    Accounts(Vec<Option<RemoteAccount>>),
+    /// Acknowledges a [`Request::Reset`] or [`Request::Commit`].
+    Ok,
+    /// The backend failed to serve the request.
+    Error(String),
+}
+
+/// Writes a length-prefixed bincode frame and returns the number of bytes written.
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, message: &T) -> io::Result<usize> {
+    let payload =
+        bincode::serialize(message).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()?;
+    Ok(4 + payload.len())
+}
+
+/// Reads a length-prefixed bincode frame, returning the message and its size in bytes.
+pub fn read_frame<R: Read, T: for<'de> Deserialize<'de>>(reader: &mut R) -> io::Result<(T, usize)> {
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds limit", len),
+        ));
+    }
+
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    let message =
+        bincode::deserialize(&payload).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok((message, 4 + len))
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// Storage backend that can be served by a [`RemoteDbServer`].
+pub trait StateBackend: Send {
+    /// Clears all state and installs the given pre-funded accounts.
+    fn reset(&mut self, accounts: &[(Address, U256)]) -> Result<(), String>;
+
+    /// Reads a batch of accounts, aligned with `addresses`.
+    fn get(&mut self, addresses: &[Address]) -> Result<Vec<Option<RemoteAccount>>, String>;
+
+    /// Atomically writes a batch of account states.
+    fn commit(&mut self, updates: &[(Address, RemoteAccount)]) -> Result<(), String>;
+}
+
+/// Plain in-memory backend.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    accounts: HashMap<Address, RemoteAccount>,
+}
+
+impl StateBackend for MemoryBackend {
+    fn reset(&mut self, accounts: &[(Address, U256)]) -> Result<(), String> {
+        self.accounts = accounts
+            .iter()
+            .map(|&(address, balance)| (address, RemoteAccount { nonce: 0, balance }))
+            .collect();
+        Ok(())
+    }
+
+    fn get(&mut self, addresses: &[Address]) -> Result<Vec<Option<RemoteAccount>>, String> {
+        Ok(addresses.iter().map(|address| self.accounts.get(address).copied()).collect())
+    }
+
+    fn commit(&mut self, updates: &[(Address, RemoteAccount)]) -> Result<(), String> {
+        self.accounts.extend(updates.iter().copied());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mdbx")]
+impl StateBackend for super::MdbxDatabase {
+    fn reset(&mut self, accounts: &[(Address, U256)]) -> Result<(), String> {
+        self.clear_accounts().map_err(|e| e.to_string())?;
+        self.init_accounts(accounts).map_err(|e| e.to_string())
+    }
+
+    fn get(&mut self, addresses: &[Address]) -> Result<Vec<Option<RemoteAccount>>, String> {
+        addresses
+            .iter()
+            .map(|&address| {
+                let account = self.get_account(address).map_err(|e| e.to_string())?;
+                Ok(account.map(|acc| RemoteAccount {
+                    nonce: acc.nonce,
+                    balance: acc.balance,
+                }))
+            })
+            .collect()
+    }
+
+    fn commit(&mut self, updates: &[(Address, RemoteAccount)]) -> Result<(), String> {
+        let accounts: Vec<_> = updates
+            .iter()
+            .map(|&(address, acc)| (address, acc.nonce, acc.balance))
+            .collect();
+        self.set_accounts(&accounts).map_err(|e| e.to_string())
+    }
+}
+
+/// TCP server exposing a [`StateBackend`] to remote executors.
+///
+/// Connections are handled on their own threads; requests are serialized
+/// through a single lock around the backend.
+pub struct RemoteDbServer<B: StateBackend> {
+    backend: Arc<Mutex<B>>,
+}
+
+impl<B: StateBackend + 'static> RemoteDbServer<B> {
+    /// Creates a server around the given backend.
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend: Arc::new(Mutex::new(backend)),
+        }
+    }
+
+    /// Accepts connections forever, serving each on its own thread.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let backend = self.backend.clone();
+            thread::spawn(move || {
+                // A disconnecting client ends its session; nothing else to clean up.
+                let _ = Self::handle_connection(stream, backend);
+            });
+        }
+        Ok(())
+    }
+
+    /// Serves requests on a single connection until the client disconnects.
+    fn handle_connection(stream: TcpStream, backend: Arc<Mutex<B>>) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+
+        loop {
+            let request: Request = match read_frame(&mut reader) {
+                Ok((request, _)) => request,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            let response = {
+                let mut backend = backend.lock().expect("backend lock poisoned");
+                let result = match request {
+                    Request::Reset(accounts) => backend.reset(&accounts).map(|_| Response::Ok),
+                    Request::Get(addresses) => backend.get(&addresses).map(Response::Accounts),
+                    Request::Commit(updates) => backend.commit(&updates).map(|_| Response::Ok),
+                };
+                result.unwrap_or_else(Response::Error)
+            };
+
+            write_frame(&mut writer, &response)?;
+        }
+    }
+}
+
+// ============================================================================
+// Client / Executor
+// ============================================================================
+
+/// Network statistics collected by a [`RemoteDbExecutor`] run.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkStats {
+    /// Number of request/response round trips.
+    pub round_trips: usize,
+    /// Bytes sent to the server, including framing.
+    pub bytes_sent: usize,
+    /// Bytes received from the server, including framing.
+    pub bytes_received: usize,
+}
+
+/// Result of executing a workload against a remote backend.
+#[derive(Debug, Clone)]
+pub struct RemoteExecutionResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// Network statistics for the run.
+    pub network: NetworkStats,
+}
+
+/// Executor that keeps state on a [`RemoteDbServer`].
+///
+/// For each block the executor fetches every account the block touches in a
+/// single batch, applies transfers to a local cache in strict order, and sends
+/// all dirty accounts back in a single commit.
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::RemoteDbExecutor;
+/// use db_test::{Workload, WorkloadConfig};
+///
+/// let executor = RemoteDbExecutor::connect("127.0.0.1:9000", true)?;
+/// let workload = Workload::generate(WorkloadConfig::default());
+/// let result = executor.execute_workload(&workload)?;
+/// println!("round trips: {}", result.network.round_trips);
+/// ```
+pub struct RemoteDbExecutor {
+    connection: Mutex<Connection>,
+    verify_signatures: bool,
+}
+
+/// A buffered client connection.
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+    stats: NetworkStats,
+}
+
+impl Connection {
+    fn request(&mut self, request: &Request) -> io::Result<Response> {
+        self.stats.bytes_sent += write_frame(&mut self.writer, request)?;
+        let (response, received) = read_frame(&mut self.reader)?;
+        self.stats.bytes_received += received;
+        self.stats.round_trips += 1;
+
+        match response {
+            Response::Error(message) => Err(io::Error::other(message)),
+            response => Ok(response),
+        }
+    }
+}
+
+impl RemoteDbExecutor {
+    /// Connects to a remote database server.
+    ///
+    /// # Arguments
+    /// * `addr` - Address of a running [`RemoteDbServer`]
+    /// * `verify_signatures` - Whether to verify transaction signatures
+    pub fn connect<A: ToSocketAddrs>(addr: A, verify_signatures: bool) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self {
+            connection: Mutex::new(Connection {
+                reader: BufReader::new(stream.try_clone()?),
+                writer: BufWriter::new(stream),
+                stats: NetworkStats::default(),
+            }),
+            verify_signatures,
+        })
+    }
+
+    /// Executes a workload block by block against the remote backend.
+    pub fn execute_workload(&self, workload: &Workload) -> io::Result<RemoteExecutionResult> {
+        let mut conn = self.connection.lock().expect("connection lock poisoned");
+        conn.stats = NetworkStats::default();
+
+        let accounts: Vec<_> = workload
+            .accounts
+            .iter()
+            .map(|acc| (acc.address, workload.initial_balance()))
+            .collect();
+        conn.request(&Request::Reset(accounts))?;
+
+        let mut successful = 0;
+        let mut failed = 0;
+        let mut statuses = Vec::with_capacity(workload.transactions.len());
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+
+        for block_txs in &workload.blocks {
+            // Fetch every account the block touches in one round trip
+            let touched: Vec<Address> = block_txs
+                .iter()
+                .flat_map(|tx| [tx.from, tx.to])
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            let Response::Accounts(fetched) = conn.request(&Request::Get(touched.clone()))? else {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "expected accounts"));
+            };
+            let mut cache: HashMap<Address, Option<RemoteAccount>> =
+                touched.into_iter().zip(fetched).collect();
+            let mut dirty = BTreeSet::new();
+
+            for tx in block_txs {
+                let success = self.apply_transfer(&mut cache, tx);
+                if success {
+                    successful += 1;
+                    dirty.insert(tx.from);
+                    dirty.insert(tx.to);
+                } else {
+                    failed += 1;
+                }
+                statuses.push(TxStatus::from(success));
+            }
+
+            // Send all dirty accounts back in one round trip
+            let updates: Vec<_> = dirty
+                .into_iter()
+                .filter_map(|address| cache[&address].map(|acc| (address, acc)))
+                .collect();
+            conn.request(&Request::Commit(updates))?;
+
+            // The cache holds every account the block touched, as committed
+            checkpoints.record_block(|address| cache[&address].map(|account| (account.nonce, account.balance)));
+        }
+
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+
+        Ok(RemoteExecutionResult {
+            result: ExecutionResult::new(successful, failed)
+                .with_tx_index(tx_index)
+                .with_checkpoints(checkpoints.finish()),
+            network: conn.stats.clone(),
+        })
+    }
+
+    /// Applies a single transfer to the block cache, returning whether it succeeded.
+    fn apply_transfer(
+        &self,
+        cache: &mut HashMap<Address, Option<RemoteAccount>>,
+        tx: &crate::SignedTransaction,
+    ) -> bool {
+        if self.verify_signatures && validate_stateless(tx, RecoveryBackend::K256).is_err() {
+            return false;
+        }
+        tx.burn_compute();
+
+        let sender = cache[&tx.from];
+        let validated = validate_stateful(tx, sender.map(|sender| SenderState::new(sender.nonce, sender.balance)));
+        let (Ok(()), Some(sender)) = (validated, sender) else {
+            return false;
+        };
+
+        // Overflow fails the transfer before anything is written
+        let (Some(nonce), Some(balance)) = (sender.nonce.checked_add(1), sender.balance.checked_sub(tx.value)) else {
+            return false;
+        };
+        let sender = RemoteAccount { nonce, balance };
+        // A self-transfer credits the debited sender
+        let receiver = if tx.to == tx.from {
+            sender
+        } else {
+            cache[&tx.to].unwrap_or(RemoteAccount {
+                nonce: 0,
+                balance: U256::ZERO,
+            })
+        };
+        let Some(balance) = receiver.balance.checked_add(tx.value) else {
+            return false;
+        };
+        cache.insert(tx.from, Some(sender));
+        cache.insert(
+            tx.to,
+            Some(RemoteAccount {
+                nonce: receiver.nonce,
+                balance,
+            }),
+        );
+
+        true
+    }
+
+    /// Returns whether this executor preserves transaction ordering.
+    pub fn preserves_order(&self) -> bool {
+        true
+    }
+
+    /// Returns the name of this executor.
+    pub fn name(&self) -> &'static str {
+        "remote_batched"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor, WorkloadConfig};
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let request = Request::Get(vec![Address::with_last_byte(1), Address::with_last_byte(2)]);
+        let mut buf = Vec::new();
+        let written = write_frame(&mut buf, &request).unwrap();
+        assert_eq!(written, buf.len());
+
+        let (decoded, read): (Request, usize) = read_frame(&mut buf.as_slice()).unwrap();
+        assert_eq!(read, written);
+        assert!(matches!(decoded, Request::Get(addrs) if addrs.len() == 2));
+    }
+
+    #[test]
+    fn test_remote_executor_against_memory_backend() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || RemoteDbServer::new(MemoryBackend::default()).serve(listener));
+
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 40,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let executor = RemoteDbExecutor::connect(addr, true).unwrap();
+        let result = executor.execute_workload(&workload).unwrap();
+
+        assert_eq!(result.result.successful, 40);
+        assert_eq!(result.result.failed, 0);
+        // One reset plus a get and a commit per block.
+        assert_eq!(result.network.round_trips, 1 + 2 * 4);
+
+        let (_, sequential) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+        assert_eq!(result.result.checkpoints, sequential.checkpoints);
+    }
+}
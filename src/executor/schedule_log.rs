@@ -0,0 +1,172 @@
+//! Block-STM dispatch orders recorded in one run and replayed in the next.
+//!
+//! A recording run logs, for every block of every workload, the order in
+//! which Block-STM handed transactions to its workers. [`ScheduleLog`] keys
+//! those schedules by workload hash so a later run on the re-generated
+//! workload can force the same interleaving, e.g. to reproduce a rare abort
+//! pattern under a debugger.
+//!
+//! The file format is a `schedule <workload_hash> <block> <threads>` header
+//! per schedule, followed by one `dispatch <txn> <incarnation> <worker>` or
+//! `finish <txn> <incarnation>` line per event, with `#` starting a comment.
+
+use alloy_primitives::B256;
+use block_stm_executor::{Schedule, ScheduleEvent};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// One recorded block schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedSchedule {
+    /// Hash of the workload it was recorded on.
+    pub workload: B256,
+    /// Index of the Block-STM block within the run.
+    pub block: usize,
+    /// Worker threads of the recording run.
+    pub num_threads: usize,
+    pub schedule: Schedule,
+}
+
+/// Recorded schedules, for any number of workloads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleLog {
+    pub entries: Vec<RecordedSchedule>,
+}
+
+impl ScheduleLog {
+    /// Number of recorded schedules.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nothing was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Appends the schedules of one run on the workload hashing to `workload`.
+    pub fn record(&mut self, workload: B256, num_threads: usize, schedules: Vec<Schedule>) {
+        self.entries
+            .extend(schedules.into_iter().enumerate().map(|(block, schedule)| RecordedSchedule {
+                workload,
+                block,
+                num_threads,
+                schedule,
+            }));
+    }
+
+    /// The schedules recorded on `workload`, in block order.
+    pub fn schedules_for(&self, workload: B256) -> Vec<Schedule> {
+        let mut entries: Vec<_> = self.entries.iter().filter(|entry| entry.workload == workload).collect();
+        entries.sort_by_key(|entry| entry.block);
+        entries.into_iter().map(|entry| entry.schedule.clone()).collect()
+    }
+
+    /// Worker threads of the run recorded on `workload`, if any.
+    pub fn num_threads_for(&self, workload: B256) -> Option<usize> {
+        self.entries
+            .iter()
+            .find(|entry| entry.workload == workload)
+            .map(|entry| entry.num_threads)
+    }
+
+    /// Reads schedules written by [`ScheduleLog::save`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let invalid =
+            |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("invalid schedule line '{}'", line));
+
+        let mut entries: Vec<RecordedSchedule> = Vec::new();
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let number = |field: &str| field.parse::<usize>().map_err(|_| invalid(line));
+            match fields.as_slice() {
+                ["schedule", workload, block, threads] => entries.push(RecordedSchedule {
+                    workload: workload.parse().map_err(|_| invalid(line))?,
+                    block: number(block)?,
+                    num_threads: number(threads)?,
+                    schedule: Schedule::default(),
+                }),
+                ["dispatch", txn, incarnation, worker] => {
+                    let event = ScheduleEvent::Dispatch {
+                        txn_idx: txn.parse().map_err(|_| invalid(line))?,
+                        incarnation: incarnation.parse().map_err(|_| invalid(line))?,
+                        worker: number(worker)?,
+                    };
+                    entries.last_mut().ok_or_else(|| invalid(line))?.schedule.events.push(event);
+                }
+                ["finish", txn, incarnation] => {
+                    let event = ScheduleEvent::Finish {
+                        txn_idx: txn.parse().map_err(|_| invalid(line))?,
+                        incarnation: incarnation.parse().map_err(|_| invalid(line))?,
+                    };
+                    entries.last_mut().ok_or_else(|| invalid(line))?.schedule.events.push(event);
+                }
+                _ => return Err(invalid(line)),
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Writes the schedules, one event per line under each header.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut contents = String::from("# db-test Block-STM schedules: schedule workload_hash block threads\n");
+        for entry in &self.entries {
+            contents.push_str(&format!("schedule {} {} {}\n", entry.workload, entry.block, entry.num_threads));
+            for event in &entry.schedule.events {
+                match *event {
+                    ScheduleEvent::Dispatch {
+                        txn_idx,
+                        incarnation,
+                        worker,
+                    } => contents.push_str(&format!("dispatch {} {} {}\n", txn_idx, incarnation, worker)),
+                    ScheduleEvent::Finish { txn_idx, incarnation } => {
+                        contents.push_str(&format!("finish {} {}\n", txn_idx, incarnation))
+                    }
+                }
+            }
+        }
+        fs::write(path, contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_lookup() {
+        let schedule = |txn_idx| Schedule {
+            events: vec![
+                ScheduleEvent::Dispatch {
+                    txn_idx,
+                    incarnation: 0,
+                    worker: 1,
+                },
+                ScheduleEvent::Finish { txn_idx, incarnation: 0 },
+            ],
+        };
+        let mut log = ScheduleLog::default();
+        log.record(B256::with_last_byte(1), 4, vec![schedule(0), schedule(3)]);
+        log.record(B256::with_last_byte(2), 2, vec![schedule(5)]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schedules.txt");
+        log.save(&path).unwrap();
+        let loaded = ScheduleLog::load(&path).unwrap();
+        assert_eq!(loaded, log);
+
+        assert_eq!(loaded.schedules_for(B256::with_last_byte(1)), vec![schedule(0), schedule(3)]);
+        assert_eq!(loaded.num_threads_for(B256::with_last_byte(2)), Some(2));
+        assert!(loaded.schedules_for(B256::with_last_byte(3)).is_empty());
+
+        // Events need a header
+        fs::write(&path, "finish 0 0\n").unwrap();
+        assert!(ScheduleLog::load(&path).is_err());
+    }
+}
@@ -0,0 +1,422 @@
+//! Sharded Block-STM executor.
+//!
+//! Models execution sharding: accounts are split into `K` contiguous address
+//! ranges, and for each block every shard runs its own independent Block-STM
+//! instance over the block's transactions that stay within it, after which
+//! everything that crosses a shard boundary is applied serially.
+//!
+//! A sender's transactions must still apply in nonce order, so once one of a
+//! sender's transactions is deferred, every later transaction from that sender
+//! in the block is deferred too. Credits from deferred transactions land after
+//! the shard phase, so the deferred set is reported separately from the
+//! strictly cross-shard set. [`nonceless`](crate::WorkloadConfig::nonceless)
+//! workloads have no such order, so only cross-shard transactions are
+//! deferred.
+
+use crate::executor::{CheckpointRecorder, ExecutionResult, Executor, TransactionIndex, TxStatus};
+use crate::recovery::RecoveryBackend;
+use crate::sharding;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::{GenesisState, SignedTransaction, Workload};
+use alloy_primitives::{Address, U256};
+use block_stm_executor::{AccountState, ExecutorConfig, ParallelExecutor, Transaction};
+use revm::database::CacheDB;
+use revm::state::AccountInfo;
+use std::collections::{HashMap, HashSet};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Result of a sharded Block-STM run.
+#[derive(Debug, Clone, Default)]
+pub struct ShardedBlockStmResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// Number of transactions executed inside each shard.
+    pub shard_sizes: Vec<usize>,
+    /// Transactions whose sender and receiver live on different shards.
+    pub cross_shard: usize,
+    /// Transactions applied serially: cross-shard ones plus, unless the
+    /// workload is nonce-less, later transactions from the same senders.
+    pub deferred: usize,
+    /// Wall-clock time of the parallel per-shard phases, over all blocks.
+    pub shard_time: Duration,
+    /// Wall-clock time of the serial cross-shard phases, over all blocks.
+    pub serial_time: Duration,
+}
+
+impl ShardedBlockStmResult {
+    /// Fraction of non-replayed transactions that were applied serially.
+    pub fn deferred_fraction(&self) -> f64 {
+        let total = self.deferred + self.shard_sizes.iter().sum::<usize>();
+        if total == 0 {
+            0.0
+        } else {
+            self.deferred as f64 / total as f64
+        }
+    }
+}
+
+/// Sharded Block-STM executor.
+///
+/// Each shard gets `num_threads / num_shards` worker threads (at least one)
+/// and all shards run concurrently.
+#[derive(Debug)]
+pub struct ShardedBlockStmExecutor {
+    pub num_shards: usize,
+    pub num_threads: usize,
+    pub verify_signatures: bool,
+}
+
+impl ShardedBlockStmExecutor {
+    /// Creates a new sharded executor.
+    pub fn new(num_shards: usize, num_threads: usize, verify_signatures: bool) -> Self {
+        Self {
+            num_shards: num_shards.max(1),
+            num_threads,
+            verify_signatures,
+        }
+    }
+
+    /// Returns the shard owning an address, by leading-byte address range.
+    pub fn shard_of(&self, address: Address) -> usize {
+        sharding::range_shard(address, self.num_shards)
+    }
+
+    /// Executes a workload, returning the final state and sharding statistics.
+    pub fn execute_workload(&self, workload: &Workload) -> (CacheDB<GenesisState>, ShardedBlockStmResult) {
+        let initial_balance = workload.initial_balance();
+        let initial_tokens = workload.initial_token_balances();
+        let mut state: HashMap<Address, AccountState> = workload
+            .accounts
+            .iter()
+            .map(|account| (account.address, AccountState::new(0, initial_balance).with_tokens(initial_tokens.clone())))
+            .collect();
+
+        // Replays are rejected up front, exactly as in the unsharded executor.
+        let replay_mask = workload.replay_mask();
+        let mut statuses = vec![TxStatus::Failed; workload.transactions.len()];
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+        let mut shard_sizes = vec![0; self.num_shards];
+        let mut cross_shard = 0;
+        let mut deferred = 0;
+        let mut shard_time = Duration::ZERO;
+        let mut serial_time = Duration::ZERO;
+
+        // Blocks run one after another, so the state after each block's
+        // serial phase is its checkpoint
+        let mut block_start = 0;
+        for block in &workload.blocks {
+            let block_positions = block_start..block_start + block.len();
+            block_start = block_positions.end;
+
+            // Partition the block, keeping workload order within each shard.
+            let mut shard_positions = vec![Vec::new(); self.num_shards];
+            let mut deferred_positions = Vec::new();
+            let mut deferred_senders = HashSet::new();
+            for position in block_positions {
+                if replay_mask[position] {
+                    continue;
+                }
+                let tx = &workload.transactions[position];
+                let shard = self.shard_of(tx.from);
+                let crosses = shard != self.shard_of(tx.to);
+                if crosses {
+                    cross_shard += 1;
+                }
+                let nonce_ordered = !workload.config.nonceless && deferred_senders.contains(&tx.from);
+                if crosses || nonce_ordered {
+                    deferred_senders.insert(tx.from);
+                    deferred_positions.push(position);
+                } else {
+                    shard_positions[shard].push(position);
+                }
+            }
+
+            // Phase 1: independent Block-STM instance per shard, seeded with
+            // the accounts its transactions touch.
+            let shard_start = Instant::now();
+            let threads_per_shard = (self.num_threads / self.num_shards).max(1);
+            let shard_results: Vec<_> = thread::scope(|scope| {
+                let handles: Vec<_> = shard_positions
+                    .iter()
+                    .map(|positions| {
+                        let initial_states: HashMap<Address, AccountState> = positions
+                            .iter()
+                            .map(|&position| &workload.transactions[position])
+                            .flat_map(|tx| [tx.from, tx.to])
+                            .filter_map(|address| Some((address, state.get(&address)?.clone())))
+                            .collect();
+                        scope.spawn(move || {
+                            let txs: Vec<Transaction> = positions
+                                .iter()
+                                .map(|&position| to_block_stm(&workload.transactions[position]))
+                                .collect();
+                            let config = ExecutorConfig {
+                                num_threads: threads_per_shard,
+                                verify_signatures: self.verify_signatures,
+                                initial_states,
+                                lookahead: None,
+                                memory_budget: None,
+                                dependency_hints: Vec::new(),
+                                checkpoints: Vec::new(),
+                            };
+                            let executor = ParallelExecutor::new(config);
+                            if workload.config.nonceless {
+                                executor.with_nonceless(HashSet::new()).execute_block(txs)
+                            } else {
+                                executor.execute_block(txs)
+                            }
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("shard worker panicked"))
+                    .collect()
+            });
+            shard_time += shard_start.elapsed();
+
+            // The engine only reports accounts it wrote, so merge over the
+            // state so far.
+            for (shard, (positions, shard_result)) in shard_positions.iter().zip(shard_results).enumerate() {
+                for (&position, &success) in positions.iter().zip(&shard_result.tx_success) {
+                    statuses[position] = TxStatus::from(success);
+                }
+                state.extend(shard_result.final_states);
+                shard_sizes[shard] += positions.len();
+            }
+
+            // Phase 2: serially apply the block's deferred transactions in
+            // workload order.
+            let serial_start = Instant::now();
+            for &position in &deferred_positions {
+                let tx = &workload.transactions[position];
+                if self.verify_signatures && validate_stateless(tx, RecoveryBackend::K256).is_err() {
+                    continue;
+                }
+                tx.burn_compute();
+
+                let Some(sender) = state.get(&tx.from) else {
+                    continue;
+                };
+                let balance = sender.balance_of(tx.token);
+                let sender_state = if workload.config.nonceless {
+                    SenderState::nonceless(balance)
+                } else {
+                    SenderState::new(sender.nonce, balance)
+                };
+                if validate_stateful(tx, Some(sender_state)).is_err() {
+                    continue;
+                }
+                // Nonce and balance were validated, so this only fails on overflow
+                let Some(sender) = sender.clone().checked_debit(tx.token, tx.value, !workload.config.nonceless) else {
+                    continue;
+                };
+                let receiver = state.get(&tx.to).cloned().unwrap_or_else(|| AccountState::new(0, U256::ZERO));
+                let Some(receiver) = receiver.checked_credit(tx.token, tx.value) else {
+                    continue;
+                };
+                state.insert(tx.from, sender);
+                state.insert(tx.to, receiver);
+
+                statuses[position] = TxStatus::Success;
+            }
+            serial_time += serial_start.elapsed();
+            deferred += deferred_positions.len();
+
+            checkpoints.record_block(|address| state.get(&address).map(|account| (account.nonce, account.balance)));
+        }
+
+        let mut final_db = CacheDB::new(workload.genesis().clone());
+        for (address, account) in state {
+            let info = AccountInfo {
+                balance: account.balance,
+                nonce: account.nonce,
+                code_hash: account.code_hash,
+                code: None,
+            };
+            final_db.insert_account_info(address, info);
+        }
+
+        let successful = statuses.iter().filter(|status| status.is_success()).count();
+        let result = ExecutionResult::new(successful, statuses.len() - successful)
+            .with_tx_index(TransactionIndex::from_statuses(workload, &statuses))
+            .with_checkpoints(checkpoints.finish());
+
+        let sharded = ShardedBlockStmResult {
+            result,
+            shard_sizes,
+            cross_shard,
+            deferred,
+            shard_time,
+            serial_time,
+        };
+
+        (final_db, sharded)
+    }
+}
+
+/// Converts a workload transaction into the Block-STM engine's format.
+fn to_block_stm(tx: &SignedTransaction) -> Transaction {
+    Transaction {
+        from: tx.from,
+        to: tx.to,
+        value: tx.value,
+        token: tx.token,
+        nonce: tx.nonce,
+        signature: tx.signature,
+        tx_hash: tx.tx_hash,
+        compute_cost: tx.compute_cost,
+        dependencies: Vec::new(),
+    }
+}
+
+impl Executor for ShardedBlockStmExecutor {
+    type Database = CacheDB<GenesisState>;
+
+    fn execute(
+        &self,
+        _db: Self::Database,
+        workload: &Workload,
+    ) -> (Self::Database, ExecutionResult) {
+        let (db, sharded) = self.execute_workload(workload);
+        (db, sharded.result)
+    }
+
+    fn preserves_order(&self) -> bool {
+        false // Cross-shard transactions are moved to the end of their block
+    }
+
+    fn supports_tokens(&self) -> bool {
+        true
+    }
+
+    fn supports_nonceless(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "block_stm_sharded"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SequentialExecutor, WorkloadConfig};
+
+    #[test]
+    fn test_sharded_block_stm_executor() {
+        let config = WorkloadConfig {
+            num_accounts: 40,
+            num_transactions: 60,
+            transactions_per_block: 20,
+            hot_accounts: 40,
+            seed: 42,
+            chain_id: 1,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let executor = ShardedBlockStmExecutor::new(4, 4, true);
+        let (db, result) = executor.execute_workload(&workload);
+
+        assert_eq!(result.result.successful, 60);
+        assert_eq!(result.result.failed, 0);
+        assert_eq!(result.shard_sizes.len(), 4);
+        assert_eq!(result.shard_sizes.iter().sum::<usize>() + result.deferred, 60);
+        assert!(result.cross_shard > 0);
+        assert!(result.deferred >= result.cross_shard);
+
+        // Total balance is conserved across the shard and serial phases.
+        let total = db
+            .cache
+            .accounts
+            .values()
+            .fold(U256::ZERO, |sum, acc| sum + acc.info.balance);
+        let expected = U256::from(1_000_000_000_000_000_000_000u128) * U256::from(40);
+        assert_eq!(total, expected);
+
+        // Deferred transactions land by the end of their block, so every
+        // block's state matches running it in order
+        let (_, sequential) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+        assert_eq!(result.result.checkpoints.len(), 3);
+        assert_eq!(result.result.checkpoints, sequential.checkpoints);
+    }
+
+    #[test]
+    fn test_single_shard_matches_block_stm() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 30,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            seed: 7,
+            chain_id: 1,
+            double_spends: 2,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let (_, result) = ShardedBlockStmExecutor::new(1, 2, true).execute_workload(&workload);
+
+        assert_eq!(result.cross_shard, 0);
+        assert_eq!(result.deferred, 0);
+        assert_eq!(result.result.successful, 30);
+        assert_eq!(result.result.failed, 2);
+    }
+
+    #[test]
+    fn test_cross_shard_token_transfers() {
+        let config = WorkloadConfig {
+            num_accounts: 40,
+            num_transactions: 60,
+            transactions_per_block: 20,
+            hot_accounts: 40,
+            fake_signatures: true,
+            tokens: 2,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+        assert!(workload.transactions.iter().any(|tx| tx.token.is_some()));
+
+        let (db, result) = ShardedBlockStmExecutor::new(4, 4, false).execute_workload(&workload);
+
+        assert_eq!(result.result.successful, 60);
+        assert!(result.deferred > 0);
+
+        // Token transfers leave native balances alone
+        let initial_balance = workload.initial_balance();
+        let mut expected: HashMap<Address, U256> =
+            workload.accounts.iter().map(|account| (account.address, initial_balance)).collect();
+        for tx in workload.transactions.iter().filter(|tx| tx.token.is_none()) {
+            *expected.get_mut(&tx.from).unwrap() -= tx.value;
+            *expected.get_mut(&tx.to).unwrap() += tx.value;
+        }
+        for (address, account) in &db.cache.accounts {
+            assert_eq!(account.info.balance, expected[address]);
+        }
+    }
+
+    #[test]
+    fn test_nonceless_defers_only_cross_shard() {
+        let config = WorkloadConfig {
+            num_accounts: 40,
+            num_transactions: 60,
+            transactions_per_block: 20,
+            hot_accounts: 40,
+            fake_signatures: true,
+            nonceless: true,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let (db, result) = ShardedBlockStmExecutor::new(4, 4, false).execute_workload(&workload);
+
+        assert_eq!(result.result.successful, 60);
+        assert!(result.cross_shard > 0);
+        assert_eq!(result.deferred, result.cross_shard);
+        assert!(db.cache.accounts.values().all(|account| account.info.nonce == 0));
+    }
+}
@@ -0,0 +1,304 @@
+//! Copy-on-write speculative overlay over a revm database.
+//!
+//! [`SpeculativeDb`] sits in front of a read-only base (typically a
+//! `CacheDB`) and buffers every committed change in an overlay. Before the
+//! first write to an account within a speculative transaction, the account's
+//! previous overlay entry is pushed onto a journal, so a transaction that
+//! turns out to have read stale state can be discarded by rolling the journal
+//! back to a [`Checkpoint`] without touching the base database.
+//!
+//! This is the building block for a revm-integrated parallel executor: each
+//! speculative transaction runs against the overlay, commits into it, and is
+//! either finalized or reverted once validation completes.
+
+use alloy_primitives::{Address, B256, U256};
+use revm::database::{AccountState as DbAccountState, CacheDB, DbAccount};
+use revm::database_interface::{Database, DatabaseCommit, DatabaseRef};
+use revm::primitives::{HashMap, KECCAK_EMPTY};
+use revm::state::{Account, AccountInfo, Bytecode};
+
+/// Position in the journal a [`SpeculativeDb`] can be reverted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Checkpoint(usize);
+
+/// Overlay state for a single account.
+#[derive(Debug, Clone, Default)]
+struct OverlayAccount {
+    /// Current account info; `None` if the account was destroyed.
+    info: Option<AccountInfo>,
+    /// Storage slots written in the overlay.
+    storage: HashMap<U256, U256>,
+    /// Whether slots not in `storage` read as zero instead of from the base.
+    storage_cleared: bool,
+}
+
+/// Copy-on-write journal layer over a base database.
+///
+/// Reads fall through to the base unless the overlay has a newer value.
+/// Writes arrive through [`DatabaseCommit`] and are journaled per account so
+/// they can be discarded with [`SpeculativeDb::revert`].
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::SpeculativeDb;
+///
+/// let mut spec = SpeculativeDb::new(&base_db);
+/// let checkpoint = spec.checkpoint();
+/// // ... execute a speculative transaction against `spec` and commit it ...
+/// if validation_failed {
+///     spec.revert(checkpoint);
+/// } else {
+///     spec.finalize();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SpeculativeDb<D> {
+    base: D,
+    overlay: HashMap<Address, OverlayAccount>,
+    contracts: HashMap<B256, Bytecode>,
+    journal: Vec<(Address, Option<OverlayAccount>)>,
+}
+
+impl<D> SpeculativeDb<D> {
+    /// Creates an empty overlay over `base`.
+    pub fn new(base: D) -> Self {
+        Self {
+            base,
+            overlay: Default::default(),
+            contracts: Default::default(),
+            journal: Vec::new(),
+        }
+    }
+
+    /// Returns a checkpoint marking the start of a speculative transaction.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.journal.len())
+    }
+
+    /// Discards every change committed since `checkpoint`.
+    pub fn revert(&mut self, checkpoint: Checkpoint) {
+        while self.journal.len() > checkpoint.0 {
+            let (address, previous) = self.journal.pop().expect("journal is non-empty");
+            match previous {
+                Some(account) => {
+                    self.overlay.insert(address, account);
+                }
+                None => {
+                    self.overlay.remove(&address);
+                }
+            }
+        }
+    }
+
+    /// Makes all journaled changes permanent, so they can no longer be reverted.
+    pub fn finalize(&mut self) {
+        self.journal.clear();
+    }
+
+    /// Number of journal entries that a full revert would undo.
+    pub fn journal_len(&self) -> usize {
+        self.journal.len()
+    }
+
+    /// Addresses with pending changes in the overlay.
+    pub fn touched(&self) -> impl Iterator<Item = &Address> {
+        self.overlay.keys()
+    }
+
+    /// Returns the underlying base database.
+    pub fn base(&self) -> &D {
+        &self.base
+    }
+}
+
+impl<ExtDB> SpeculativeDb<CacheDB<ExtDB>> {
+    /// Writes the overlay into the base `CacheDB` and returns it.
+    ///
+    /// Any unfinalized journal entries are applied as well; revert first to
+    /// drop them.
+    pub fn flush(self) -> CacheDB<ExtDB> {
+        let mut db = self.base;
+        db.cache.contracts.extend(self.contracts);
+
+        for (address, account) in self.overlay {
+            let Some(info) = account.info else {
+                db.cache.accounts.insert(address, DbAccount::new_not_existing());
+                continue;
+            };
+
+            let entry = db.cache.accounts.entry(address).or_default();
+            entry.info = info;
+            if account.storage_cleared {
+                entry.storage.clear();
+                entry.account_state = DbAccountState::StorageCleared;
+            } else if entry.account_state == DbAccountState::NotExisting {
+                entry.account_state = DbAccountState::Touched;
+            }
+            entry.storage.extend(account.storage);
+        }
+
+        db
+    }
+}
+
+impl<D: DatabaseRef> DatabaseRef for SpeculativeDb<D> {
+    type Error = D::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        match self.overlay.get(&address) {
+            Some(account) => Ok(account.info.clone()),
+            None => self.base.basic_ref(address),
+        }
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        match self.contracts.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.base.code_by_hash_ref(code_hash),
+        }
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let Some(account) = self.overlay.get(&address) else {
+            return self.base.storage_ref(address, index);
+        };
+
+        match account.storage.get(&index) {
+            Some(value) => Ok(*value),
+            None if account.storage_cleared || account.info.is_none() => Ok(U256::ZERO),
+            None => self.base.storage_ref(address, index),
+        }
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.base.block_hash_ref(number)
+    }
+}
+
+impl<D: DatabaseRef> Database for SpeculativeDb<D> {
+    type Error = D::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.basic_ref(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.storage_ref(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+impl<D> DatabaseCommit for SpeculativeDb<D> {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        for (address, account) in changes {
+            if !account.is_touched() {
+                continue;
+            }
+
+            // Copy-on-write: save the previous overlay entry before mutating it.
+            self.journal.push((address, self.overlay.get(&address).cloned()));
+            let entry = self.overlay.entry(address).or_default();
+
+            if account.is_selfdestructed() {
+                *entry = OverlayAccount {
+                    info: None,
+                    storage: Default::default(),
+                    storage_cleared: true,
+                };
+                continue;
+            }
+
+            if account.is_created() {
+                entry.storage.clear();
+                entry.storage_cleared = true;
+            }
+
+            if let Some(code) = &account.info.code {
+                if account.info.code_hash != KECCAK_EMPTY {
+                    self.contracts.insert(account.info.code_hash, code.clone());
+                }
+            }
+
+            entry.info = Some(account.info);
+            entry.storage.extend(
+                account
+                    .storage
+                    .into_iter()
+                    .map(|(slot, value)| (slot, value.present_value)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::database::EmptyDB;
+
+    fn changed(address: Address, balance: u64, nonce: u64) -> HashMap<Address, Account> {
+        let info = AccountInfo {
+            balance: U256::from(balance),
+            nonce,
+            code_hash: KECCAK_EMPTY,
+            code: None,
+        };
+        let mut account = Account::from(info);
+        account.mark_touch();
+
+        let mut changes = HashMap::default();
+        changes.insert(address, account);
+        changes
+    }
+
+    #[test]
+    fn test_revert_discards_speculative_writes() {
+        let alice = Address::repeat_byte(0xaa);
+        let bob = Address::repeat_byte(0xbb);
+
+        let mut base = CacheDB::new(EmptyDB::default());
+        base.insert_account_info(
+            alice,
+            AccountInfo {
+                balance: U256::from(100),
+                ..Default::default()
+            },
+        );
+
+        let mut spec = SpeculativeDb::new(base);
+
+        // First transaction is accepted.
+        spec.commit(changed(alice, 90, 1));
+        spec.finalize();
+
+        // Second transaction touches two accounts and is discarded.
+        let checkpoint = spec.checkpoint();
+        spec.commit(changed(alice, 50, 2));
+        spec.commit(changed(bob, 40, 0));
+        assert_eq!(spec.journal_len(), 2);
+        assert_eq!(spec.basic(alice).unwrap().unwrap().balance, U256::from(50));
+
+        spec.revert(checkpoint);
+        assert_eq!(spec.journal_len(), 0);
+        assert_eq!(spec.basic(alice).unwrap().unwrap().nonce, 1);
+        assert!(spec.basic(bob).unwrap().is_none());
+
+        // The base is untouched until the overlay is flushed.
+        assert_eq!(
+            spec.base().basic_ref(alice).unwrap().unwrap().balance,
+            U256::from(100)
+        );
+
+        let flushed = spec.flush();
+        let alice_info = flushed.basic_ref(alice).unwrap().unwrap();
+        assert_eq!(alice_info.balance, U256::from(90));
+        assert_eq!(alice_info.nonce, 1);
+    }
+}
@@ -0,0 +1,367 @@
+//! Two-phase commit executor over independently sharded MDBX environments.
+//!
+//! Accounts are assigned to one of several MDBX environments by hashed address.
+//! A transfer whose sender and receiver live on the same shard commits with a
+//! single MDBX transaction. A cross-shard transfer is coordinated with 2PC:
+//!
+//! 1. **Prepare**: each participant validates its half of the transfer, applies
+//!    it, and records the account's before-image in the prepared transfers
+//!    table, all in one durable commit. A failed validation is a "no" vote.
+//! 2. **Decide**: if every participant voted "yes" the coordinator commits by
+//!    deleting the prepare records; otherwise it aborts by restoring the
+//!    before-images on every participant that had prepared.
+//!
+//! Cross-shard transfers therefore cost two commits per participant instead of
+//! one, which is exactly the overhead this executor is meant to quantify.
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use eyre::Result;
+use reth_db_api::{
+    database::Database,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_primitives_traits::Account;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::mdbx::{HashedAccountsTable, MdbxDatabase, PreparedTransfersTable};
+use super::{CheckpointRecorder, ExecutionResult, TransactionIndex, TxStatus};
+use crate::recovery::RecoveryBackend;
+use crate::sharding;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::{SignedTransaction, Workload};
+
+/// One side of a transfer as seen by a participant shard.
+#[derive(Debug, Clone, Copy)]
+enum Leg<'a> {
+    /// Debit the sender of a transaction, validating it against the sender.
+    Debit(&'a SignedTransaction),
+    /// Credit the receiver.
+    Credit { value: U256 },
+}
+
+/// Result of executing a workload with the 2PC executor.
+#[derive(Debug, Clone)]
+pub struct TwoPhaseResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// Number of transfers whose accounts live on a single shard.
+    pub single_shard: usize,
+    /// Number of transfers that spanned two shards.
+    pub cross_shard: usize,
+    /// Number of cross-shard transfers aborted after a "no" vote.
+    pub aborts: usize,
+    /// Time spent on single-shard transfers.
+    pub single_shard_time: Duration,
+    /// Time spent on cross-shard transfers, including both 2PC phases.
+    pub cross_shard_time: Duration,
+}
+
+impl TwoPhaseResult {
+    /// Fraction of transfers that required cross-shard coordination.
+    pub fn cross_shard_fraction(&self) -> f64 {
+        let total = self.single_shard + self.cross_shard;
+        if total == 0 {
+            0.0
+        } else {
+            self.cross_shard as f64 / total as f64
+        }
+    }
+}
+
+/// Experimental executor that shards accounts across MDBX environments and
+/// coordinates cross-shard transfers with two-phase commit.
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::TwoPhaseCommitExecutor;
+/// use db_test::{Workload, WorkloadConfig};
+/// use tempfile::tempdir;
+///
+/// let dir = tempdir()?;
+/// let executor = TwoPhaseCommitExecutor::new(dir.path(), 2, true)?;
+/// let workload = Workload::generate(WorkloadConfig::default());
+/// let result = executor.execute_workload(&workload)?;
+/// println!("cross-shard: {:.1}%", result.cross_shard_fraction() * 100.0);
+/// ```
+pub struct TwoPhaseCommitExecutor {
+    shards: Vec<MdbxDatabase>,
+    verify_signatures: bool,
+}
+
+impl TwoPhaseCommitExecutor {
+    /// Creates a new 2PC executor with one MDBX environment per shard.
+    ///
+    /// # Arguments
+    /// * `path` - Directory under which each shard gets its own environment
+    /// * `num_shards` - Number of independent environments (at least 1)
+    /// * `verify_signatures` - Whether to verify transaction signatures
+    pub fn new<P: AsRef<Path>>(path: P, num_shards: usize, verify_signatures: bool) -> Result<Self> {
+        let shards = (0..num_shards.max(1))
+            .map(|shard| MdbxDatabase::create(path.as_ref().join(format!("shard-{}", shard))))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            shards,
+            verify_signatures,
+        })
+    }
+
+    /// Returns the shard an address is stored on, by jump consistent hash.
+    pub fn shard_of(&self, address: Address) -> usize {
+        sharding::jump_shard(address, self.shards.len())
+    }
+
+    /// Executes a workload in strict order, using 2PC for cross-shard transfers.
+    pub fn execute_workload(&self, workload: &Workload) -> Result<TwoPhaseResult> {
+        let initial_balance = workload.initial_balance();
+        let mut per_shard = vec![Vec::new(); self.shards.len()];
+        for account in &workload.accounts {
+            per_shard[self.shard_of(account.address)].push((account.address, initial_balance));
+        }
+        for (shard, accounts) in self.shards.iter().zip(&per_shard) {
+            shard.init_accounts(accounts)?;
+        }
+
+        let mut result = TwoPhaseResult {
+            result: ExecutionResult::default(),
+            single_shard: 0,
+            cross_shard: 0,
+            aborts: 0,
+            single_shard_time: Duration::ZERO,
+            cross_shard_time: Duration::ZERO,
+        };
+        let mut statuses = Vec::with_capacity(workload.transactions.len());
+
+        // Every transfer commits before the next starts, so the shards hold
+        // each block's state once its last transfer has
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+        for block in &workload.blocks {
+            for tx in block {
+                if self.verify_signatures && validate_stateless(tx, RecoveryBackend::K256).is_err() {
+                    statuses.push(TxStatus::Failed);
+                    continue;
+                }
+                tx.burn_compute();
+
+                let start = Instant::now();
+                let sender_shard = self.shard_of(tx.from);
+                let receiver_shard = self.shard_of(tx.to);
+
+                let success = if sender_shard == receiver_shard {
+                    let success = self.execute_single_shard(sender_shard, tx)?;
+                    result.single_shard += 1;
+                    result.single_shard_time += start.elapsed();
+                    success
+                } else {
+                    let success = self.execute_cross_shard(sender_shard, receiver_shard, tx)?;
+                    result.cross_shard += 1;
+                    if !success {
+                        result.aborts += 1;
+                    }
+                    result.cross_shard_time += start.elapsed();
+                    success
+                };
+
+                statuses.push(TxStatus::from(success));
+            }
+            checkpoints.try_record_block(|address| {
+                let account = self.shards[self.shard_of(address)].get_account(address)?;
+                Ok::<_, eyre::Report>(account.map(|account| (account.nonce, account.balance)))
+            })?;
+        }
+
+        let successful = statuses.iter().filter(|status| status.is_success()).count();
+        result.result = ExecutionResult::new(successful, statuses.len() - successful)
+            .with_tx_index(TransactionIndex::from_statuses(workload, &statuses))
+            .with_checkpoints(checkpoints.finish());
+
+        Ok(result)
+    }
+
+    /// Applies both legs of a transfer in a single MDBX transaction.
+    fn execute_single_shard(&self, shard: usize, tx: &SignedTransaction) -> Result<bool> {
+        let db_tx = self.shards[shard].env.tx_mut()?;
+
+        let debited = Self::apply_leg(&db_tx, tx.from, Leg::Debit(tx))?;
+        let Some((_, debited)) = debited else {
+            db_tx.abort();
+            return Ok(false);
+        };
+        db_tx.put::<HashedAccountsTable>(keccak256(tx.from.as_slice()), debited)?;
+
+        let Some((_, credited)) = Self::apply_leg(&db_tx, tx.to, Leg::Credit { value: tx.value })? else {
+            db_tx.abort();
+            return Ok(false);
+        };
+        db_tx.put::<HashedAccountsTable>(keccak256(tx.to.as_slice()), credited)?;
+
+        db_tx.commit()?;
+        Ok(true)
+    }
+
+    /// Runs two-phase commit for a transfer spanning two shards.
+    fn execute_cross_shard(
+        &self,
+        sender_shard: usize,
+        receiver_shard: usize,
+        tx: &SignedTransaction,
+    ) -> Result<bool> {
+        let participants = [
+            (sender_shard, tx.from, Leg::Debit(tx)),
+            (receiver_shard, tx.to, Leg::Credit { value: tx.value }),
+        ];
+
+        // Phase 1: prepare. Stop at the first "no" vote.
+        let mut prepared = Vec::with_capacity(participants.len());
+        for &(shard, address, leg) in &participants {
+            if !self.prepare(shard, tx.tx_hash, address, leg)? {
+                break;
+            }
+            prepared.push((shard, address));
+        }
+
+        // Phase 2: commit if everyone voted yes, otherwise roll back.
+        let commit = prepared.len() == participants.len();
+        for (shard, address) in prepared {
+            self.finish(shard, tx.tx_hash, address, commit)?;
+        }
+
+        Ok(commit)
+    }
+
+    /// Prepares one participant: applies its leg and durably records the before-image.
+    ///
+    /// Returns false (a "no" vote) if the leg fails validation.
+    fn prepare(&self, shard: usize, tx_hash: B256, address: Address, leg: Leg<'_>) -> Result<bool> {
+        let db_tx = self.shards[shard].env.tx_mut()?;
+
+        let Some((before, after)) = Self::apply_leg(&db_tx, address, leg)? else {
+            db_tx.abort();
+            return Ok(false);
+        };
+
+        db_tx.put::<PreparedTransfersTable>(tx_hash, before)?;
+        db_tx.put::<HashedAccountsTable>(keccak256(address.as_slice()), after)?;
+        db_tx.commit()?;
+        Ok(true)
+    }
+
+    /// Completes one participant: drops the prepare record, restoring the
+    /// before-image first if the coordinator decided to abort.
+    fn finish(&self, shard: usize, tx_hash: B256, address: Address, commit: bool) -> Result<()> {
+        let db_tx = self.shards[shard].env.tx_mut()?;
+
+        if !commit {
+            let before = db_tx
+                .get::<PreparedTransfersTable>(tx_hash)?
+                .expect("prepared participant has a before-image");
+            db_tx.put::<HashedAccountsTable>(keccak256(address.as_slice()), before)?;
+        }
+        db_tx.delete::<PreparedTransfersTable>(tx_hash, None)?;
+
+        db_tx.commit()?;
+        Ok(())
+    }
+
+    /// Computes a participant's before and after account state for a leg.
+    ///
+    /// Returns `None` if a debit fails nonce or balance validation, or if
+    /// either leg would overflow.
+    fn apply_leg<TX: DbTx>(db_tx: &TX, address: Address, leg: Leg<'_>) -> Result<Option<(Account, Account)>> {
+        let existing = db_tx.get::<HashedAccountsTable>(keccak256(address.as_slice()))?;
+
+        match leg {
+            Leg::Debit(tx) => {
+                let validated =
+                    validate_stateful(tx, existing.as_ref().map(|before| SenderState::new(before.nonce, before.balance)));
+                let (Ok(()), Some(before)) = (validated, existing) else {
+                    return Ok(None);
+                };
+                let Some(nonce) = before.nonce.checked_add(1) else {
+                    return Ok(None);
+                };
+                let after = Account {
+                    nonce,
+                    balance: before.balance - tx.value,
+                    bytecode_hash: before.bytecode_hash,
+                };
+                Ok(Some((before, after)))
+            }
+            Leg::Credit { value } => {
+                let before = existing.unwrap_or(Account {
+                    nonce: 0,
+                    balance: U256::ZERO,
+                    bytecode_hash: None,
+                });
+                let Some(balance) = before.balance.checked_add(value) else {
+                    return Ok(None);
+                };
+                let after = Account { balance, ..before };
+                Ok(Some((before, after)))
+            }
+        }
+    }
+
+    /// Returns the shard databases, e.g. for inspecting final state.
+    pub fn shards(&self) -> &[MdbxDatabase] {
+        &self.shards
+    }
+
+    /// Returns whether this executor preserves transaction ordering.
+    pub fn preserves_order(&self) -> bool {
+        true
+    }
+
+    /// Returns the name of this executor.
+    pub fn name(&self) -> &'static str {
+        "mdbx_two_phase"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor, WorkloadConfig};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_two_phase_executor() {
+        let dir = tempdir().unwrap();
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 50,
+            hot_accounts: 20,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            double_spends: 2,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let executor = TwoPhaseCommitExecutor::new(dir.path(), 2, true).unwrap();
+        let result = executor.execute_workload(&workload).unwrap();
+
+        assert_eq!(result.result.successful, 50);
+        assert_eq!(result.result.failed, 2);
+        assert_eq!(result.single_shard + result.cross_shard, 52);
+        assert!(result.cross_shard > 0);
+        let (_, sequential) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+        assert_eq!(result.result.checkpoints, sequential.checkpoints);
+
+        // Every account lives on exactly one shard and no prepare records remain.
+        for account in &workload.accounts {
+            let home = executor.shard_of(account.address);
+            for (shard, db) in executor.shards().iter().enumerate() {
+                let stored = db.get_account(account.address).unwrap();
+                assert_eq!(stored.is_some(), shard == home);
+            }
+        }
+        for db in executor.shards() {
+            let tx = db.env.tx().unwrap();
+            assert_eq!(tx.entries::<PreparedTransfersTable>().unwrap(), 0);
+        }
+    }
+}
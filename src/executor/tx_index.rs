@@ -0,0 +1,347 @@
+//! Transaction hash index over execution results.
+//!
+//! Executors record the outcome of every transaction they process so that
+//! verification tooling can reference transactions by hash rather than by
+//! positional index when reporting divergences. Executors that know why a
+//! transaction failed also record a [`FailureReason`] for it.
+
+use alloy_primitives::B256;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::validation::ValidationError;
+use crate::Workload;
+
+/// Final outcome of a single transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TxStatus {
+    /// The transaction executed and its effects were committed.
+    Success,
+    /// The transaction was rejected (invalid signature, nonce, replay, etc.).
+    Failed,
+}
+
+impl TxStatus {
+    /// Returns true if the transaction succeeded.
+    pub fn is_success(&self) -> bool {
+        matches!(self, TxStatus::Success)
+    }
+}
+
+impl From<bool> for TxStatus {
+    fn from(success: bool) -> Self {
+        if success {
+            TxStatus::Success
+        } else {
+            TxStatus::Failed
+        }
+    }
+}
+
+/// Why a transaction failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FailureReason {
+    /// The signature does not recover to the sender.
+    InvalidSignature,
+    /// The sender has no account.
+    UnknownSender,
+    /// The sender's nonce is already past the transaction's.
+    NonceTooLow,
+    /// The sender's nonce has not reached the transaction's.
+    NonceTooHigh,
+    /// The sender cannot afford the transfer.
+    InsufficientBalance,
+    /// A duplicate or double-spend rejected before execution.
+    Replayed,
+    /// The sender's nonce or the receiver's balance would overflow.
+    Overflow,
+    /// Execution failed after validation, e.g. a revert.
+    Reverted,
+    /// Valid on its own, but discarded with an atomic block in which another
+    /// transaction failed.
+    BlockDiscarded,
+}
+
+impl FailureReason {
+    /// Every reason, in reporting order.
+    pub const ALL: [Self; 9] = [
+        Self::InvalidSignature,
+        Self::UnknownSender,
+        Self::NonceTooLow,
+        Self::NonceTooHigh,
+        Self::InsufficientBalance,
+        Self::Replayed,
+        Self::Overflow,
+        Self::Reverted,
+        Self::BlockDiscarded,
+    ];
+
+    /// Short name used in reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::InvalidSignature => "bad signature",
+            Self::UnknownSender => "unknown sender",
+            Self::NonceTooLow => "nonce too low",
+            Self::NonceTooHigh => "nonce too high",
+            Self::InsufficientBalance => "insufficient balance",
+            Self::Replayed => "replayed",
+            Self::Overflow => "overflow",
+            Self::Reverted => "reverted",
+            Self::BlockDiscarded => "block discarded",
+        }
+    }
+}
+
+impl fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<ValidationError> for FailureReason {
+    fn from(err: ValidationError) -> Self {
+        match err {
+            ValidationError::InvalidSignature => Self::InvalidSignature,
+            ValidationError::UnknownSender => Self::UnknownSender,
+            ValidationError::NonceTooLow { .. } => Self::NonceTooLow,
+            ValidationError::NonceTooHigh { .. } => Self::NonceTooHigh,
+            ValidationError::InsufficientBalance { .. } => Self::InsufficientBalance,
+        }
+    }
+}
+
+/// Counts failures by reason, in [`FailureReason::ALL`] order, skipping
+/// reasons that did not occur.
+///
+/// `reasons` holds one entry per transaction; `None` entries (successes and
+/// failures of unknown cause) are not counted.
+pub fn failure_breakdown(reasons: &[Option<FailureReason>]) -> Vec<(FailureReason, usize)> {
+    FailureReason::ALL
+        .into_iter()
+        .map(|reason| (reason, reasons.iter().filter(|&&r| r == Some(reason)).count()))
+        .filter(|&(_, count)| count > 0)
+        .collect()
+}
+
+/// Formats a [`failure_breakdown`] of `failed` failures, e.g.
+/// `nonce too low 3, replayed 2, 1 unattributed`.
+pub fn format_failures(breakdown: &[(FailureReason, usize)], failed: usize) -> String {
+    let attributed: usize = breakdown.iter().map(|(_, count)| count).sum();
+    let mut parts: Vec<String> = breakdown.iter().map(|(reason, count)| format!("{} {}", reason, count)).collect();
+    if failed > attributed {
+        parts.push(format!("{} unattributed", failed - attributed));
+    }
+    if parts.is_empty() {
+        "none".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Position and outcome of a transaction within a workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxLocation {
+    /// Block number (0-based) the transaction belongs to.
+    pub block: usize,
+    /// Index of the transaction within its block.
+    pub index: usize,
+    /// Outcome of the transaction.
+    pub status: TxStatus,
+}
+
+/// Index from transaction hash to its location and outcome.
+///
+/// A hash may occur more than once when a workload contains duplicate
+/// transactions; all occurrences are kept in block order.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransactionIndex {
+    entries: HashMap<B256, Vec<TxLocation>>,
+}
+
+impl TransactionIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds an index from per-transaction statuses in workload order.
+    ///
+    /// `statuses` must contain one entry per transaction in `workload.transactions`.
+    pub fn from_statuses(workload: &Workload, statuses: &[TxStatus]) -> Self {
+        assert_eq!(
+            statuses.len(),
+            workload.transactions.len(),
+            "one status per transaction is required"
+        );
+
+        let per_block = workload.transactions_per_block().max(1);
+        let mut index = Self::new();
+
+        for (position, (tx, &status)) in workload.transactions.iter().zip(statuses).enumerate() {
+            index.insert(
+                tx.tx_hash,
+                TxLocation {
+                    block: position / per_block,
+                    index: position % per_block,
+                    status,
+                },
+            );
+        }
+
+        index
+    }
+
+    /// Looks up the outcome of every transaction in `workload`, in workload order.
+    ///
+    /// Entries are `None` for transactions the index has no record of at their
+    /// position, e.g. when the executor does not index its outcomes.
+    pub fn statuses(&self, workload: &Workload) -> Vec<Option<TxStatus>> {
+        let per_block = workload.transactions_per_block().max(1);
+        workload
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(position, tx)| {
+                let (block, index) = (position / per_block, position % per_block);
+                self.occurrences(&tx.tx_hash)
+                    .iter()
+                    .find(|location| location.block == block && location.index == index)
+                    .map(|location| location.status)
+            })
+            .collect()
+    }
+
+    /// Records a transaction occurrence.
+    pub fn insert(&mut self, tx_hash: B256, location: TxLocation) {
+        self.entries.entry(tx_hash).or_default().push(location);
+    }
+
+    /// Looks up the first occurrence of a transaction by hash.
+    pub fn get(&self, tx_hash: &B256) -> Option<&TxLocation> {
+        self.entries.get(tx_hash).and_then(|locations| locations.first())
+    }
+
+    /// Returns every occurrence of a transaction hash, in block order.
+    pub fn occurrences(&self, tx_hash: &B256) -> &[TxLocation] {
+        self.entries
+            .get(tx_hash)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the hash has been recorded.
+    pub fn contains(&self, tx_hash: &B256) -> bool {
+        self.entries.contains_key(tx_hash)
+    }
+
+    /// Number of distinct transaction hashes in the index.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over all hashes and their occurrences.
+    pub fn iter(&self) -> impl Iterator<Item = (&B256, &[TxLocation])> {
+        self.entries
+            .iter()
+            .map(|(hash, locations)| (hash, locations.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_index_locations() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 12,
+            hot_accounts: 10,
+            seed: 1,
+            chain_id: 1,
+            transactions_per_block: 5,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let statuses: Vec<TxStatus> = (0..12).map(|i| TxStatus::from(i != 7)).collect();
+        let index = TransactionIndex::from_statuses(&workload, &statuses);
+
+        assert_eq!(index.len(), 12);
+
+        let location = index.get(&workload.transactions[7].tx_hash).unwrap();
+        assert_eq!(location.block, 1);
+        assert_eq!(location.index, 2);
+        assert_eq!(location.status, TxStatus::Failed);
+
+        let location = index.get(&workload.transactions[11].tx_hash).unwrap();
+        assert_eq!(location.block, 2);
+        assert_eq!(location.index, 1);
+        assert!(location.status.is_success());
+
+        assert!(index.get(&B256::ZERO).is_none());
+        assert!(index.occurrences(&B256::ZERO).is_empty());
+
+        let round_trip: Vec<Option<TxStatus>> = statuses.iter().copied().map(Some).collect();
+        assert_eq!(index.statuses(&workload), round_trip);
+        assert_eq!(TransactionIndex::new().statuses(&workload), vec![None; 12]);
+    }
+
+    #[test]
+    fn test_failure_breakdown() {
+        let reasons = [
+            None,
+            Some(FailureReason::Replayed),
+            Some(FailureReason::NonceTooLow),
+            None,
+            Some(FailureReason::Replayed),
+        ];
+        let breakdown = failure_breakdown(&reasons);
+        assert_eq!(breakdown, vec![(FailureReason::NonceTooLow, 1), (FailureReason::Replayed, 2)]);
+        assert_eq!(format_failures(&breakdown, 4), "nonce too low 1, replayed 2, 1 unattributed");
+        assert_eq!(format_failures(&[], 0), "none");
+
+        let err = ValidationError::InsufficientBalance {
+            balance: alloy_primitives::U256::ZERO,
+            value: alloy_primitives::U256::from(1),
+        };
+        assert_eq!(FailureReason::from(err), FailureReason::InsufficientBalance);
+    }
+
+    #[test]
+    fn test_index_keeps_duplicate_occurrences() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 10,
+            hot_accounts: 10,
+            seed: 3,
+            chain_id: 1,
+            transactions_per_block: 4,
+            duplicate_transactions: 1,
+            ..Default::default()
+        };
+        let workload = Workload::generate(config);
+
+        let statuses: Vec<TxStatus> = workload
+            .replay_mask()
+            .into_iter()
+            .map(|replay| TxStatus::from(!replay))
+            .collect();
+        let index = TransactionIndex::from_statuses(&workload, &statuses);
+
+        assert_eq!(index.len(), 10);
+        let duplicated = index.iter().find(|(_, locations)| locations.len() == 2).unwrap();
+        assert_eq!(duplicated.1[0].status, TxStatus::Success);
+        assert_eq!(duplicated.1[1].status, TxStatus::Failed);
+    }
+}
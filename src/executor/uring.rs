@@ -0,0 +1,429 @@
+//! Flat-file account store driven by io_uring.
+//!
+//! Account records here are fixed-size and keyed by nothing more than an
+//! address, which a general-purpose KV engine spends B-tree pages, locking
+//! and a write-ahead log on. [`UringStore`] skips all of it: every account
+//! owns a 40-byte slot in one flat file, encoded by
+//! [`RawCodec`](crate::codec::RawCodec), and an in-memory index maps
+//! addresses to slots. Reads and writes are batched into io_uring
+//! submissions, so one block's accounts are fetched, and written back, with a
+//! single system call per queue's worth of records.
+//!
+//! [`UringExecutor`] runs a workload block by block on the store: it reads
+//! every account the block touches in one batch, executes the block against
+//! those records in memory, then writes the changed ones back in one batch,
+//! followed by an fsync the kernel orders after them, unless disabled.
+//!
+//! Linux only, behind the `io-uring` feature. Like the other executors that
+//! bypass revm it charges no fees.
+
+use alloy_primitives::{Address, U256};
+use io_uring::{opcode, squeue, types, IoUring};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::{CheckpointRecorder, ExecutionResult, FailureReason, TransactionIndex, TxStatus};
+use crate::codec::{AccountCodec, AccountValue, RawCodec};
+use crate::options::UringOptions;
+use crate::recovery::RecoveryBackend;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::Workload;
+
+/// Bytes of an account record: 8-byte nonce and 32-byte balance.
+const RECORD_LEN: usize = 40;
+
+/// I/O performed by a [`UringStore`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UringStats {
+    /// Records read.
+    pub reads: usize,
+    /// Records written.
+    pub writes: usize,
+    /// Submissions to the ring, each covering up to a queue of operations.
+    pub submissions: usize,
+    /// Fsyncs of the file.
+    pub fsyncs: usize,
+    /// Time spent waiting for read batches.
+    pub read_time: Duration,
+    /// Time spent waiting for write batches, fsyncs included.
+    pub write_time: Duration,
+}
+
+/// Fixed-size account records in one flat file, accessed through io_uring.
+pub struct UringStore {
+    file: File,
+    ring: IoUring,
+    /// Record slot of every stored account.
+    slots: HashMap<Address, u64>,
+    stats: UringStats,
+}
+
+impl UringStore {
+    /// Creates an empty store at `path` with a ring of `queue_depth` entries.
+    pub fn create(path: impl AsRef<Path>, queue_depth: u32) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            file,
+            ring: IoUring::new(queue_depth.max(1))?,
+            slots: HashMap::new(),
+            stats: UringStats::default(),
+        })
+    }
+
+    /// I/O performed so far.
+    pub fn stats(&self) -> UringStats {
+        self.stats
+    }
+
+    /// Number of stored accounts.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Whether the store holds no accounts.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Replaces the store's contents with fresh accounts holding `balance`,
+    /// in one sequential write outside the ring and its statistics.
+    pub fn init_accounts(&mut self, accounts: &[(Address, U256)]) -> io::Result<()> {
+        self.slots.clear();
+        self.file.set_len(0)?;
+        let mut data = Vec::with_capacity(accounts.len() * RECORD_LEN);
+        for &(address, balance) in accounts {
+            if self.slots.contains_key(&address) {
+                continue;
+            }
+            self.slots.insert(address, self.slots.len() as u64);
+            data.extend_from_slice(&RawCodec.encode(&AccountValue::new(0, balance)));
+        }
+        self.file.write_all_at(&data, 0)?;
+        self.file.sync_all()
+    }
+
+    /// Reads `addresses` in one batch; `None` for accounts never written.
+    pub fn read_accounts(&mut self, addresses: &[Address]) -> io::Result<Vec<Option<AccountValue>>> {
+        let slots: Vec<Option<u64>> = addresses.iter().map(|address| self.slots.get(address).copied()).collect();
+        let mut buffers = vec![[0u8; RECORD_LEN]; addresses.len()];
+        let fd = types::Fd(self.file.as_raw_fd());
+        let ops: Vec<(squeue::Entry, usize)> = buffers
+            .iter_mut()
+            .zip(&slots)
+            .filter_map(|(buffer, slot)| {
+                let slot = (*slot)?;
+                let read = opcode::Read::new(fd, buffer.as_mut_ptr(), RECORD_LEN as u32)
+                    .offset(slot * RECORD_LEN as u64)
+                    .build();
+                Some((read, RECORD_LEN))
+            })
+            .collect();
+
+        let start = Instant::now();
+        // SAFETY: `buffers` is neither moved nor dropped until `submit` has
+        // waited for every read into it to complete
+        unsafe { self.submit(&ops)? };
+        self.stats.read_time += start.elapsed();
+        self.stats.reads += ops.len();
+
+        buffers
+            .iter()
+            .zip(&slots)
+            .map(|(buffer, slot)| match slot {
+                Some(_) => RawCodec
+                    .decode(buffer)
+                    .map(Some)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed account record")),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    /// Writes `accounts` in one batch, appending a slot for each new one,
+    /// followed by an fsync ordered after the writes if `fsync` is set.
+    pub fn write_accounts(&mut self, accounts: &[(Address, AccountValue)], fsync: bool) -> io::Result<()> {
+        let buffers: Vec<Vec<u8>> = accounts
+            .iter()
+            .map(|(_, account)| {
+                let data = RawCodec.encode(account);
+                debug_assert_eq!(data.len(), RECORD_LEN, "only native balances fit a record");
+                data
+            })
+            .collect();
+        let fd = types::Fd(self.file.as_raw_fd());
+        let mut ops = Vec::with_capacity(accounts.len() + 1);
+        for ((address, _), data) in accounts.iter().zip(&buffers) {
+            let next = self.slots.len() as u64;
+            let slot = *self.slots.entry(*address).or_insert(next);
+            let write = opcode::Write::new(fd, data.as_ptr(), data.len() as u32)
+                .offset(slot * RECORD_LEN as u64)
+                .build();
+            ops.push((write, data.len()));
+        }
+        if fsync {
+            ops.push((opcode::Fsync::new(fd).build().flags(squeue::Flags::IO_DRAIN), 0));
+        }
+
+        let start = Instant::now();
+        // SAFETY: `buffers` is neither moved nor dropped until `submit` has
+        // waited for every write from it to complete
+        unsafe { self.submit(&ops)? };
+        self.stats.write_time += start.elapsed();
+        self.stats.writes += accounts.len();
+        self.stats.fsyncs += usize::from(fsync);
+        Ok(())
+    }
+
+    /// Submits `ops` a queue at a time, waiting for each queue to complete,
+    /// and checks every operation transferred its expected number of bytes.
+    ///
+    /// # Safety
+    ///
+    /// Every buffer an operation points to must stay valid, and unmoved,
+    /// until this returns.
+    unsafe fn submit(&mut self, ops: &[(squeue::Entry, usize)]) -> io::Result<()> {
+        let capacity = self.ring.submission().capacity();
+        for (chunk_index, chunk) in ops.chunks(capacity).enumerate() {
+            let base = chunk_index * capacity;
+            {
+                let mut submission = self.ring.submission();
+                for (i, (op, _)) in chunk.iter().enumerate() {
+                    let op = op.clone().user_data((base + i) as u64);
+                    // SAFETY: upheld by the caller
+                    unsafe { submission.push(&op) }.expect("chunk fits the submission queue");
+                }
+            }
+            self.ring.submit_and_wait(chunk.len())?;
+            self.stats.submissions += 1;
+
+            for completion in self.ring.completion() {
+                let expected = ops[completion.user_data() as usize].1;
+                match completion.result() {
+                    result if result < 0 => return Err(io::Error::from_raw_os_error(-result)),
+                    result if result as usize != expected => {
+                        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "short account record transfer"));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of an io_uring run.
+#[derive(Debug, Clone, Default)]
+pub struct UringResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// I/O the store performed while executing.
+    pub stats: UringStats,
+}
+
+/// Sequential executor over a [`UringStore`], one read and one write batch
+/// per block.
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::UringExecutor;
+/// use db_test::{Workload, WorkloadConfig};
+/// use tempfile::tempdir;
+///
+/// let dir = tempdir()?;
+/// let executor = UringExecutor::new(dir.path().join("accounts"), true);
+/// let workload = Workload::generate(WorkloadConfig::default());
+/// let result = executor.execute_workload(&workload)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct UringExecutor {
+    path: PathBuf,
+    verify_signatures: bool,
+    recovery: RecoveryBackend,
+    options: UringOptions,
+}
+
+impl UringExecutor {
+    /// Creates an executor storing accounts in the file at `path`, with the
+    /// default options.
+    pub fn new(path: impl AsRef<Path>, verify_signatures: bool) -> Self {
+        Self::new_with_options(path, verify_signatures, &UringOptions::default())
+    }
+
+    /// Creates an executor storing accounts in the file at `path`.
+    pub fn new_with_options(path: impl AsRef<Path>, verify_signatures: bool, options: &UringOptions) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            verify_signatures,
+            recovery: RecoveryBackend::default(),
+            options: *options,
+        }
+    }
+
+    /// Uses `recovery` to recover signers when verifying signatures.
+    pub fn with_recovery(mut self, recovery: RecoveryBackend) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Executes the workload block by block on a fresh store.
+    pub fn execute_workload(&self, workload: &Workload) -> io::Result<UringResult> {
+        let mut store = UringStore::create(&self.path, self.options.queue_depth)?;
+        let accounts: Vec<_> = workload
+            .accounts
+            .iter()
+            .map(|account| (account.address, workload.initial_balance()))
+            .collect();
+        store.init_accounts(&accounts)?;
+
+        let mut successful = 0;
+        let mut failed = 0;
+        let mut statuses = Vec::with_capacity(workload.transactions.len());
+        let mut reasons = Vec::with_capacity(workload.transactions.len());
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+
+        for block in &workload.blocks {
+            // Fetch every account the block touches in one batch
+            let touched = checkpoints.next_touched().to_vec();
+            let mut cache: HashMap<Address, Option<AccountValue>> =
+                touched.iter().copied().zip(store.read_accounts(&touched)?).collect();
+            let mut dirty = Vec::new();
+
+            for tx in block {
+                let verified = if self.verify_signatures {
+                    validate_stateless(tx, self.recovery)
+                } else {
+                    Ok(())
+                };
+                let outcome = verified.map_err(FailureReason::from).and_then(|()| {
+                    tx.burn_compute();
+                    let sender = cache[&tx.from].clone();
+                    validate_stateful(tx, sender.as_ref().map(|sender| SenderState::new(sender.nonce, sender.balance)))?;
+                    let sender = sender
+                        .expect("validated sender has an account")
+                        .checked_debit(None, tx.value, true)
+                        .ok_or(FailureReason::Overflow)?;
+                    // A self-transfer credits the debited sender
+                    let receiver = if tx.to == tx.from {
+                        Some(sender.clone())
+                    } else {
+                        cache[&tx.to].clone()
+                    };
+                    let receiver = receiver
+                        .unwrap_or_else(|| AccountValue::new(0, U256::ZERO))
+                        .checked_credit(None, tx.value)
+                        .ok_or(FailureReason::Overflow)?;
+                    cache.insert(tx.from, Some(sender));
+                    cache.insert(tx.to, Some(receiver));
+                    Ok(())
+                });
+
+                if outcome.is_ok() {
+                    dirty.extend([tx.from, tx.to]);
+                    successful += 1;
+                } else {
+                    failed += 1;
+                }
+                statuses.push(TxStatus::from(outcome.is_ok()));
+                reasons.push(outcome.err());
+            }
+
+            // Write the block's changes back in one batch
+            dirty.sort_unstable();
+            dirty.dedup();
+            let writes: Vec<_> = dirty
+                .into_iter()
+                .filter_map(|address| Some((address, cache[&address].clone()?)))
+                .collect();
+            store.write_accounts(&writes, self.options.fsync)?;
+            checkpoints.record_block(|address| {
+                cache.get(&address).cloned().flatten().map(|account| (account.nonce, account.balance))
+            });
+        }
+
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+        Ok(UringResult {
+            result: ExecutionResult::new(successful, failed)
+                .with_tx_index(tx_index)
+                .with_checkpoints(checkpoints.finish())
+                .with_failure_reasons(reasons),
+            stats: store.stats(),
+        })
+    }
+
+    /// Returns whether this executor preserves transaction ordering.
+    pub fn preserves_order(&self) -> bool {
+        true
+    }
+
+    /// Returns the name of this executor.
+    pub fn name(&self) -> &'static str {
+        "io_uring_flat_file"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor, WorkloadConfig};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_store_roundtrip() {
+        let dir = tempdir().unwrap();
+        // A two-entry ring splits larger batches over several submissions
+        let mut store = UringStore::create(dir.path().join("accounts"), 2).unwrap();
+        let addresses: Vec<Address> = (1..=5).map(Address::with_last_byte).collect();
+        let accounts: Vec<_> = addresses.iter().map(|&address| (address, U256::from(100))).collect();
+        store.init_accounts(&accounts).unwrap();
+
+        let updated = AccountValue::new(3, U256::from(7));
+        let new = Address::with_last_byte(9);
+        store
+            .write_accounts(&[(addresses[4], updated.clone()), (new, updated.clone())], true)
+            .unwrap();
+        assert_eq!(store.len(), 6);
+
+        let read = store.read_accounts(&[addresses[0], addresses[4], new, Address::ZERO]).unwrap();
+        assert_eq!(read[0], Some(AccountValue::new(0, U256::from(100))));
+        assert_eq!(read[1].as_ref(), Some(&updated));
+        assert_eq!(read[2].as_ref(), Some(&updated));
+        assert_eq!(read[3], None);
+        assert_eq!(store.stats().fsyncs, 1);
+        assert!(store.stats().submissions >= 3);
+    }
+
+    #[test]
+    fn test_uring_matches_sequential_executor() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 60,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            double_spends: 2,
+            ..Default::default()
+        });
+        let dir = tempdir().unwrap();
+        let uring = UringExecutor::new(dir.path().join("accounts"), true)
+            .execute_workload(&workload)
+            .unwrap();
+        let (_, revm) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+
+        assert_eq!((uring.result.successful, uring.result.failed), (revm.successful, revm.failed));
+        assert_eq!(uring.result.tx_index.statuses(&workload), revm.tx_index.statuses(&workload));
+        assert_eq!(uring.result.checkpoints, revm.checkpoints);
+        assert_eq!(uring.stats.fsyncs, workload.blocks.len());
+    }
+}
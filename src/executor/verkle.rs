@@ -0,0 +1,538 @@
+//! Experimental in-memory Verkle-tree state backend.
+//!
+//! This module stores account state in a width-256 trie laid out like the
+//! Verkle tree proposed in EIP-6800:
+//!
+//! - Keys are 32 bytes: a 31-byte *stem* followed by a 1-byte *suffix*
+//! - Internal nodes branch on one stem byte per level (256 children)
+//! - Leaf nodes hold a full stem and up to 256 values indexed by suffix
+//! - An account's nonce and balance are packed into the basic data leaf (suffix 0)
+//!
+//! Node commitments are computed incrementally: writes only invalidate the
+//! commitments on the path from the root to the modified leaf, and the root is
+//! recomputed once per block.
+//!
+//! # Limitations
+//!
+//! Real Verkle trees commit to children with Pedersen vector commitments over
+//! the Bandersnatch curve. Here each commitment is a keccak256 hash over the
+//! node's populated slots, so the tree shape, key layout, and update pattern
+//! match Verkle while the per-node cryptographic cost does not. Compare against
+//! [`crate::state_root`] to measure structural overhead, not curve arithmetic.
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use std::time::{Duration, Instant};
+
+use super::{CheckpointRecorder, ExecutionResult, TransactionIndex, TxStatus};
+use crate::recovery::RecoveryBackend;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::Workload;
+
+/// Width of every node in the tree.
+const NODE_WIDTH: usize = 256;
+
+/// Suffix of the leaf holding an account's version, nonce, and balance.
+pub const BASIC_DATA_LEAF_KEY: u8 = 0;
+
+/// A 31-byte tree stem.
+pub type Stem = [u8; 31];
+
+/// A 32-byte leaf value.
+pub type LeafValue = [u8; 32];
+
+/// A node in the tree.
+#[derive(Debug, Clone)]
+enum Node {
+    Internal(Box<InternalNode>),
+    Leaf(Box<LeafNode>),
+}
+
+impl Node {
+    fn commitment(&mut self) -> B256 {
+        match self {
+            Node::Internal(internal) => internal.commitment(),
+            Node::Leaf(leaf) => leaf.commitment(),
+        }
+    }
+}
+
+/// Internal node branching on a single stem byte.
+#[derive(Debug, Clone)]
+struct InternalNode {
+    children: Vec<Option<Node>>,
+    /// Cached commitment, cleared whenever a descendant changes.
+    commitment: Option<B256>,
+}
+
+impl InternalNode {
+    fn new() -> Self {
+        Self {
+            children: vec![None; NODE_WIDTH],
+            commitment: None,
+        }
+    }
+
+    fn get(&self, depth: usize, stem: &Stem, suffix: u8) -> Option<LeafValue> {
+        match &self.children[stem[depth] as usize] {
+            None => None,
+            Some(Node::Internal(child)) => child.get(depth + 1, stem, suffix),
+            Some(Node::Leaf(leaf)) if leaf.stem == *stem => leaf.values[suffix as usize],
+            Some(Node::Leaf(_)) => None,
+        }
+    }
+
+    fn insert(&mut self, depth: usize, stem: &Stem, suffix: u8, value: LeafValue) {
+        self.commitment = None;
+        let idx = stem[depth] as usize;
+
+        // A different stem occupies this slot: push it one level down so both
+        // stems can live under a new internal node.
+        let needs_split =
+            matches!(&self.children[idx], Some(Node::Leaf(leaf)) if leaf.stem != *stem);
+        if needs_split {
+            let Some(Node::Leaf(existing)) = self.children[idx].take() else {
+                unreachable!("slot was checked to hold a leaf");
+            };
+            let mut internal = InternalNode::new();
+            internal.children[existing.stem[depth + 1] as usize] = Some(Node::Leaf(existing));
+            self.children[idx] = Some(Node::Internal(Box::new(internal)));
+        }
+
+        match &mut self.children[idx] {
+            None => {
+                let mut leaf = LeafNode::new(*stem);
+                leaf.set(suffix, value);
+                self.children[idx] = Some(Node::Leaf(Box::new(leaf)));
+            }
+            Some(Node::Internal(child)) => child.insert(depth + 1, stem, suffix, value),
+            Some(Node::Leaf(leaf)) => leaf.set(suffix, value),
+        }
+    }
+
+    fn commitment(&mut self) -> B256 {
+        if let Some(commitment) = self.commitment {
+            return commitment;
+        }
+
+        let mut data = Vec::new();
+        for (idx, child) in self.children.iter_mut().enumerate() {
+            if let Some(child) = child {
+                data.push(idx as u8);
+                data.extend_from_slice(child.commitment().as_slice());
+            }
+        }
+
+        let commitment = keccak256(&data);
+        self.commitment = Some(commitment);
+        commitment
+    }
+}
+
+/// Leaf node holding all values that share a stem.
+#[derive(Debug, Clone)]
+struct LeafNode {
+    stem: Stem,
+    values: Vec<Option<LeafValue>>,
+    /// Cached commitment, cleared whenever a value changes.
+    commitment: Option<B256>,
+}
+
+impl LeafNode {
+    fn new(stem: Stem) -> Self {
+        Self {
+            stem,
+            values: vec![None; NODE_WIDTH],
+            commitment: None,
+        }
+    }
+
+    fn set(&mut self, suffix: u8, value: LeafValue) {
+        self.values[suffix as usize] = Some(value);
+        self.commitment = None;
+    }
+
+    fn commitment(&mut self) -> B256 {
+        if let Some(commitment) = self.commitment {
+            return commitment;
+        }
+
+        // Marker byte distinguishes leaf commitments from internal ones,
+        // mirroring the extension-node marker in EIP-6800.
+        let mut data = vec![1u8];
+        data.extend_from_slice(&self.stem);
+        for (suffix, value) in self.values.iter().enumerate() {
+            if let Some(value) = value {
+                data.push(suffix as u8);
+                data.extend_from_slice(value);
+            }
+        }
+
+        let commitment = keccak256(&data);
+        self.commitment = Some(commitment);
+        commitment
+    }
+}
+
+/// In-memory Verkle-style trie.
+#[derive(Debug, Clone)]
+pub struct VerkleTree {
+    root: InternalNode,
+}
+
+impl VerkleTree {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        Self {
+            root: InternalNode::new(),
+        }
+    }
+
+    /// Reads the value stored under `stem` and `suffix`.
+    pub fn get(&self, stem: &Stem, suffix: u8) -> Option<LeafValue> {
+        self.root.get(0, stem, suffix)
+    }
+
+    /// Writes a value under `stem` and `suffix`, invalidating commitments on its path.
+    pub fn insert(&mut self, stem: &Stem, suffix: u8, value: LeafValue) {
+        self.root.insert(0, stem, suffix, value);
+    }
+
+    /// Returns the root commitment, recomputing only invalidated nodes.
+    pub fn root_commitment(&mut self) -> B256 {
+        self.root.commitment()
+    }
+}
+
+impl Default for VerkleTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Derives the tree stem for an account.
+///
+/// EIP-6800 derives stems with a Pedersen hash; keccak256 is used here for
+/// consistency with the commitment placeholder.
+pub fn account_stem(address: Address) -> Stem {
+    let mut padded = [0u8; 32];
+    padded[12..].copy_from_slice(address.as_slice());
+    let hash = keccak256(padded);
+
+    let mut stem = [0u8; 31];
+    stem.copy_from_slice(&hash[..31]);
+    stem
+}
+
+/// Error returned when a balance does not fit the basic data leaf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceTooLarge;
+
+/// Packs nonce and balance into the EIP-6800 basic data layout.
+///
+/// Layout: version (1) | reserved (4) | code size (3) | nonce (8) | balance (16).
+/// Fails if the balance is 2^128 or more.
+pub fn encode_basic_data(nonce: u64, balance: U256) -> Result<LeafValue, BalanceTooLarge> {
+    let balance: u128 = balance.try_into().map_err(|_| BalanceTooLarge)?;
+
+    let mut value = [0u8; 32];
+    value[8..16].copy_from_slice(&nonce.to_be_bytes());
+    value[16..32].copy_from_slice(&balance.to_be_bytes());
+    Ok(value)
+}
+
+/// Unpacks nonce and balance from a basic data leaf.
+pub fn decode_basic_data(value: &LeafValue) -> (u64, U256) {
+    let nonce = u64::from_be_bytes(value[8..16].try_into().unwrap());
+    let balance = u128::from_be_bytes(value[16..32].try_into().unwrap());
+    (nonce, U256::from(balance))
+}
+
+/// Account-level view over a [`VerkleTree`].
+#[derive(Debug, Clone, Default)]
+pub struct VerkleDatabase {
+    tree: VerkleTree,
+}
+
+impl VerkleDatabase {
+    /// Creates an empty database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets an account's (nonce, balance), if it exists.
+    pub fn get_account(&self, address: Address) -> Option<(u64, U256)> {
+        self.tree
+            .get(&account_stem(address), BASIC_DATA_LEAF_KEY)
+            .map(|value| decode_basic_data(&value))
+    }
+
+    /// Sets an account's nonce and balance, leaving it unchanged if the
+    /// balance does not fit the basic data leaf.
+    pub fn set_account(&mut self, address: Address, nonce: u64, balance: U256) -> Result<(), BalanceTooLarge> {
+        self.tree.insert(
+            &account_stem(address),
+            BASIC_DATA_LEAF_KEY,
+            encode_basic_data(nonce, balance)?,
+        );
+        Ok(())
+    }
+
+    /// Initializes the database with pre-funded accounts.
+    pub fn init_accounts(&mut self, accounts: &[(Address, U256)]) -> Result<(), BalanceTooLarge> {
+        for &(address, balance) in accounts {
+            self.set_account(address, 0, balance)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the current root commitment.
+    pub fn root_commitment(&mut self) -> B256 {
+        self.tree.root_commitment()
+    }
+}
+
+/// Per-block commitment statistics.
+#[derive(Debug, Clone)]
+pub struct VerkleBlockResult {
+    /// Block number.
+    pub block_number: u64,
+    /// Root commitment after the block.
+    pub root: B256,
+    /// Time spent recomputing commitments at the end of the block.
+    pub commitment_time: Duration,
+}
+
+/// Result of executing a workload against the Verkle backend.
+#[derive(Debug, Clone)]
+pub struct VerkleExecutionResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// Commitment statistics for each block.
+    pub blocks: Vec<VerkleBlockResult>,
+}
+
+impl VerkleExecutionResult {
+    /// Total time spent on commitments across all blocks.
+    pub fn total_commitment_time(&self) -> Duration {
+        self.blocks.iter().map(|block| block.commitment_time).sum()
+    }
+}
+
+/// Sequential executor that stores state in a Verkle-style trie.
+///
+/// Transfers are applied directly to the tree and the root commitment is
+/// recomputed at the end of every block, so per-block commitment cost can be
+/// compared with other state commitment schemes on identical workloads.
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::VerkleExecutor;
+/// use db_test::{Workload, WorkloadConfig};
+///
+/// let executor = VerkleExecutor::new(true);
+/// let workload = Workload::generate(WorkloadConfig::default());
+/// let (result, _db) = executor.execute_workload(&workload).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct VerkleExecutor {
+    verify_signatures: bool,
+}
+
+impl VerkleExecutor {
+    /// Creates a new Verkle executor.
+    ///
+    /// # Arguments
+    /// * `verify_signatures` - Whether to verify transaction signatures
+    pub fn new(verify_signatures: bool) -> Self {
+        Self { verify_signatures }
+    }
+
+    /// Executes a workload block by block, committing after each block.
+    ///
+    /// Fails if the initial balance does not fit the basic data leaf, as
+    /// with near-max balance workloads.
+    pub fn execute_workload(
+        &self,
+        workload: &Workload,
+    ) -> Result<(VerkleExecutionResult, VerkleDatabase), BalanceTooLarge> {
+        let mut db = VerkleDatabase::new();
+        let accounts: Vec<_> = workload
+            .accounts
+            .iter()
+            .map(|acc| (acc.address, workload.initial_balance()))
+            .collect();
+        db.init_accounts(&accounts)?;
+        db.root_commitment();
+
+        let mut successful = 0;
+        let mut failed = 0;
+        let mut statuses = Vec::with_capacity(workload.transactions.len());
+        let mut blocks = Vec::with_capacity(workload.blocks.len());
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+
+        for (block_num, block_txs) in workload.blocks.iter().enumerate() {
+            for tx in block_txs {
+                let success = self.apply_transfer(&mut db, tx);
+                if success {
+                    successful += 1;
+                } else {
+                    failed += 1;
+                }
+                statuses.push(TxStatus::from(success));
+            }
+            checkpoints.record_block(|address| db.get_account(address));
+
+            let start = Instant::now();
+            let root = db.root_commitment();
+            blocks.push(VerkleBlockResult {
+                block_number: block_num as u64,
+                root,
+                commitment_time: start.elapsed(),
+            });
+        }
+
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+
+        Ok((
+            VerkleExecutionResult {
+                result: ExecutionResult::new(successful, failed)
+                    .with_tx_index(tx_index)
+                    .with_checkpoints(checkpoints.finish()),
+                blocks,
+            },
+            db,
+        ))
+    }
+
+    /// Applies a single transfer, returning whether it succeeded.
+    fn apply_transfer(&self, db: &mut VerkleDatabase, tx: &crate::SignedTransaction) -> bool {
+        if self.verify_signatures && validate_stateless(tx, RecoveryBackend::K256).is_err() {
+            return false;
+        }
+        tx.burn_compute();
+
+        let sender = db.get_account(tx.from);
+        let validated = validate_stateful(tx, sender.map(|(nonce, balance)| SenderState::new(nonce, balance)));
+        let (Ok(()), Some((sender_nonce, sender_balance))) = (validated, sender) else {
+            return false;
+        };
+
+        // Overflow, or a credited balance the leaf cannot hold, fails the
+        // transfer before anything is written
+        let (Some(nonce), Some(balance)) = (sender_nonce.checked_add(1), sender_balance.checked_sub(tx.value)) else {
+            return false;
+        };
+        // A self-transfer credits the debited sender
+        let (receiver_nonce, receiver_balance) = if tx.to == tx.from {
+            (nonce, balance)
+        } else {
+            db.get_account(tx.to).unwrap_or((0, U256::ZERO))
+        };
+        let Some(receiver_balance) = receiver_balance.checked_add(tx.value) else {
+            return false;
+        };
+        if encode_basic_data(receiver_nonce, receiver_balance).is_err() {
+            return false;
+        }
+
+        // Debiting shrinks a balance that already fit, so neither write fails
+        let debited = db.set_account(tx.from, nonce, balance);
+        debited.is_ok() && db.set_account(tx.to, receiver_nonce, receiver_balance).is_ok()
+    }
+
+    /// Returns whether this executor preserves transaction ordering.
+    pub fn preserves_order(&self) -> bool {
+        true
+    }
+
+    /// Returns the name of this executor.
+    pub fn name(&self) -> &'static str {
+        "verkle_sequential"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_tree_insert_and_split() {
+        let mut tree = VerkleTree::new();
+
+        // Two stems sharing the first two bytes force a split two levels deep.
+        let mut stem_a = [0u8; 31];
+        let mut stem_b = [0u8; 31];
+        stem_a[2] = 1;
+        stem_b[2] = 2;
+
+        tree.insert(&stem_a, 0, [1u8; 32]);
+        tree.insert(&stem_b, 0, [2u8; 32]);
+        tree.insert(&stem_a, 5, [3u8; 32]);
+
+        assert_eq!(tree.get(&stem_a, 0), Some([1u8; 32]));
+        assert_eq!(tree.get(&stem_b, 0), Some([2u8; 32]));
+        assert_eq!(tree.get(&stem_a, 5), Some([3u8; 32]));
+        assert_eq!(tree.get(&stem_b, 5), None);
+    }
+
+    #[test]
+    fn test_commitment_tracks_updates() {
+        let mut db = VerkleDatabase::new();
+        let addr = Address::with_last_byte(7);
+
+        db.set_account(addr, 0, U256::from(100)).unwrap();
+        let root_before = db.root_commitment();
+
+        db.set_account(addr, 1, U256::from(99)).unwrap();
+        let root_after = db.root_commitment();
+        assert_ne!(root_before, root_after);
+
+        db.set_account(addr, 0, U256::from(100)).unwrap();
+        assert_eq!(db.root_commitment(), root_before);
+        assert_eq!(db.get_account(addr), Some((0, U256::from(100))));
+    }
+
+    #[test]
+    fn test_verkle_executor() {
+        let config = WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 50,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let executor = VerkleExecutor::new(true);
+        let (result, _) = executor.execute_workload(&workload).unwrap();
+
+        assert_eq!(result.result.successful, 50);
+        assert_eq!(result.result.failed, 0);
+        assert_eq!(result.blocks.len(), 5);
+        assert_eq!(executor.name(), "verkle_sequential");
+    }
+
+    #[test]
+    fn test_balances_past_the_leaf_fail() {
+        let addr = Address::with_last_byte(7);
+        let too_large = U256::from(u128::MAX) + U256::from(1);
+        assert_eq!(encode_basic_data(0, too_large), Err(BalanceTooLarge));
+
+        let mut db = VerkleDatabase::new();
+        db.set_account(addr, 0, U256::from(100)).unwrap();
+        assert_eq!(db.set_account(addr, 1, too_large), Err(BalanceTooLarge));
+        assert_eq!(db.get_account(addr), Some((0, U256::from(100))));
+
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 4,
+            num_transactions: 4,
+            near_max_balance: Some(1000),
+            ..Default::default()
+        });
+        assert!(VerkleExecutor::new(false).execute_workload(&workload).is_err());
+    }
+}
@@ -0,0 +1,425 @@
+//! Write-ahead log executor over in-memory state.
+//!
+//! Many high-throughput chains keep their whole state in memory and make it
+//! durable with a redo log and periodic snapshots instead of committing every
+//! block to a B-tree. [`WalExecutor`] models that design: every successful
+//! transfer appends the new state of the accounts it wrote to a log file,
+//! every block ends with a marker, and the log is flushed to disk as its
+//! [`WalSyncMode`] says. Every `checkpoint_blocks` blocks the whole state is
+//! written to a snapshot, after which the log is truncated.
+//!
+//! [`WalExecutor::recover`] rebuilds the state a restart would see: the
+//! snapshot, then every block of the log whose end marker was written.
+//! Records hold post-states rather than deltas, so replaying a block the
+//! snapshot already covers is harmless.
+//!
+//! Log records, with integers big-endian:
+//!
+//! ```text
+//! write:     0x01 ++ address (20) ++ nonce (8) ++ balance (32)
+//! block end: 0x02 ++ block number (8)
+//! ```
+//!
+//! A snapshot is the number of blocks it covers (8 bytes) followed by every
+//! account as in a write record, without the tag. Like the other executors
+//! that bypass revm it charges no fees.
+
+use alloy_primitives::{Address, U256};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::{CheckpointRecorder, ExecutionResult, FailureReason, TransactionIndex, TxStatus};
+use crate::options::{WalOptions, WalSyncMode};
+use crate::recovery::RecoveryBackend;
+use crate::validation::{validate_stateful, validate_stateless, SenderState};
+use crate::{SignedTransaction, Workload};
+
+/// Log file name within the executor's directory.
+const LOG_FILE: &str = "wal.log";
+/// Snapshot file name within the executor's directory.
+const SNAPSHOT_FILE: &str = "snapshot";
+/// Tag of a record holding one account's new state.
+const WRITE_RECORD: u8 = 1;
+/// Tag of a record closing a block.
+const BLOCK_END_RECORD: u8 = 2;
+/// Bytes of an encoded account: address, nonce and balance.
+const ACCOUNT_LEN: usize = 20 + 8 + 32;
+
+/// In-memory state: each account's next nonce and balance.
+pub type WalState = HashMap<Address, (u64, U256)>;
+
+/// Result of a write-ahead log run.
+#[derive(Debug, Clone, Default)]
+pub struct WalResult {
+    /// Aggregate transaction outcomes.
+    pub result: ExecutionResult,
+    /// Bytes appended to the log, across truncations.
+    pub log_bytes: u64,
+    /// Fsyncs of the log.
+    pub syncs: usize,
+    /// Time spent in log fsyncs.
+    pub sync_time: Duration,
+    /// Snapshots written, the initial one included.
+    pub checkpoints: usize,
+    /// Time spent writing snapshots and truncating the log.
+    pub checkpoint_time: Duration,
+}
+
+/// Append-only log file with fsync accounting.
+struct Log {
+    writer: BufWriter<File>,
+    bytes: u64,
+    syncs: usize,
+    sync_time: Duration,
+}
+
+impl Log {
+    /// Opens `path`, dropping any earlier contents.
+    fn create(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.set_len(0)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            bytes: 0,
+            syncs: 0,
+            sync_time: Duration::ZERO,
+        })
+    }
+
+    fn append_write(&mut self, address: Address, nonce: u64, balance: U256) -> io::Result<()> {
+        self.writer.write_all(&[WRITE_RECORD])?;
+        write_account(&mut self.writer, address, nonce, balance)?;
+        self.bytes += 1 + ACCOUNT_LEN as u64;
+        Ok(())
+    }
+
+    fn append_block_end(&mut self, block_number: u64) -> io::Result<()> {
+        self.writer.write_all(&[BLOCK_END_RECORD])?;
+        self.writer.write_all(&block_number.to_be_bytes())?;
+        self.bytes += 1 + 8;
+        Ok(())
+    }
+
+    /// Hands buffered records to the OS without waiting for the disk.
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flushes buffered records and waits until they reach the disk.
+    fn sync(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        let start = Instant::now();
+        self.writer.get_ref().sync_data()?;
+        self.sync_time += start.elapsed();
+        self.syncs += 1;
+        Ok(())
+    }
+
+    /// Drops every record; appends continue from the start of the file.
+    fn truncate(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        self.writer.get_ref().set_len(0)
+    }
+}
+
+/// Sequential executor that keeps state in memory behind a redo log.
+///
+/// # Example
+///
+/// ```ignore
+/// use db_test::executor::WalExecutor;
+/// use db_test::{Workload, WorkloadConfig};
+/// use tempfile::tempdir;
+///
+/// let dir = tempdir()?;
+/// let executor = WalExecutor::new(dir.path(), true);
+/// let workload = Workload::generate(WorkloadConfig::default());
+/// let result = executor.execute_workload(&workload)?;
+/// let (blocks, state) = executor.recover()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct WalExecutor {
+    dir: PathBuf,
+    verify_signatures: bool,
+    recovery: RecoveryBackend,
+    options: WalOptions,
+}
+
+impl WalExecutor {
+    /// Creates an executor keeping its log and snapshots in `dir`, with the
+    /// default options.
+    pub fn new(dir: impl AsRef<Path>, verify_signatures: bool) -> Self {
+        Self::new_with_options(dir, verify_signatures, &WalOptions::default())
+    }
+
+    /// Creates an executor keeping its log and snapshots in `dir`.
+    pub fn new_with_options(dir: impl AsRef<Path>, verify_signatures: bool, options: &WalOptions) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            verify_signatures,
+            recovery: RecoveryBackend::default(),
+            options: *options,
+        }
+    }
+
+    /// Uses `recovery` to recover signers when verifying signatures.
+    pub fn with_recovery(mut self, recovery: RecoveryBackend) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// Executes the workload block by block, logging every write.
+    ///
+    /// Replaces the log and snapshot of any earlier run. The initial state is
+    /// snapshotted first, so the log never needs the workload to be replayed.
+    pub fn execute_workload(&self, workload: &Workload) -> io::Result<WalResult> {
+        fs::create_dir_all(&self.dir)?;
+        let mut state: WalState = workload
+            .accounts
+            .iter()
+            .map(|account| (account.address, (0, workload.initial_balance())))
+            .collect();
+        let mut log = Log::create(&self.dir.join(LOG_FILE))?;
+
+        let start = Instant::now();
+        self.write_snapshot(0, &state)?;
+        let mut checkpoint_time = start.elapsed();
+        let mut snapshots = 1;
+
+        let mut successful = 0;
+        let mut failed = 0;
+        let mut statuses = Vec::with_capacity(workload.transactions.len());
+        let mut reasons = Vec::with_capacity(workload.transactions.len());
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+
+        for (block_number, block) in workload.blocks.iter().enumerate() {
+            for tx in block {
+                let verified = if self.verify_signatures {
+                    validate_stateless(tx, self.recovery)
+                } else {
+                    Ok(())
+                };
+                let outcome = verified.map_err(FailureReason::from).and_then(|()| {
+                    tx.burn_compute();
+                    apply_transfer(&mut state, tx)
+                });
+
+                match outcome {
+                    Ok(writes) => {
+                        for (address, (nonce, balance)) in writes {
+                            log.append_write(address, nonce, balance)?;
+                        }
+                        if self.options.sync_mode == WalSyncMode::Transaction {
+                            log.sync()?;
+                        }
+                        successful += 1;
+                    }
+                    Err(_) => failed += 1,
+                }
+                statuses.push(TxStatus::from(outcome.is_ok()));
+                reasons.push(outcome.err());
+            }
+
+            // The block is acknowledged once its end marker is as durable as the mode promises
+            log.append_block_end(block_number as u64)?;
+            match self.options.sync_mode {
+                WalSyncMode::None => log.flush()?,
+                WalSyncMode::Transaction | WalSyncMode::Block => log.sync()?,
+            }
+            checkpoints.record_block(|address| state.get(&address).copied());
+
+            let blocks = block_number + 1;
+            if blocks % self.options.checkpoint_blocks.max(1) == 0 {
+                let start = Instant::now();
+                self.write_snapshot(blocks as u64, &state)?;
+                log.truncate()?;
+                checkpoint_time += start.elapsed();
+                snapshots += 1;
+            }
+        }
+        log.flush()?;
+
+        let tx_index = TransactionIndex::from_statuses(workload, &statuses);
+        Ok(WalResult {
+            result: ExecutionResult::new(successful, failed)
+                .with_tx_index(tx_index)
+                .with_checkpoints(checkpoints.finish())
+                .with_failure_reasons(reasons),
+            log_bytes: log.bytes,
+            syncs: log.syncs,
+            sync_time: log.sync_time,
+            checkpoints: snapshots,
+            checkpoint_time,
+        })
+    }
+
+    /// Rebuilds the state a restart would see from the snapshot and log on
+    /// disk, with the number of blocks it covers.
+    ///
+    /// A block whose end marker is missing or torn was never acknowledged,
+    /// so its writes, and everything after them, are ignored.
+    pub fn recover(&self) -> io::Result<(u64, WalState)> {
+        let snapshot = fs::read(self.dir.join(SNAPSHOT_FILE))?;
+        if snapshot.len() < 8 || (snapshot.len() - 8) % ACCOUNT_LEN != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated snapshot"));
+        }
+        let (header, accounts) = snapshot.split_at(8);
+        let mut blocks = u64::from_be_bytes(header.try_into().expect("8-byte header"));
+        let mut state: WalState = accounts.chunks_exact(ACCOUNT_LEN).map(read_account).collect();
+
+        let log = match fs::read(self.dir.join(LOG_FILE)) {
+            Ok(log) => log,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        let mut pending = Vec::new();
+        let mut rest = log.as_slice();
+        loop {
+            match rest.split_first() {
+                Some((&WRITE_RECORD, body)) if body.len() >= ACCOUNT_LEN => {
+                    pending.push(read_account(&body[..ACCOUNT_LEN]));
+                    rest = &body[ACCOUNT_LEN..];
+                }
+                Some((&BLOCK_END_RECORD, body)) if body.len() >= 8 => {
+                    let block_number = u64::from_be_bytes(body[..8].try_into().expect("8-byte block number"));
+                    state.extend(pending.drain(..));
+                    blocks = blocks.max(block_number + 1);
+                    rest = &body[8..];
+                }
+                _ => break,
+            }
+        }
+        Ok((blocks, state))
+    }
+
+    /// Writes `state` after `blocks` blocks to a new snapshot, durably, and
+    /// swaps it in for the previous one.
+    fn write_snapshot(&self, blocks: u64, state: &WalState) -> io::Result<()> {
+        let staging = self.dir.join(format!("{}.tmp", SNAPSHOT_FILE));
+        let mut writer = BufWriter::new(File::create(&staging)?);
+        writer.write_all(&blocks.to_be_bytes())?;
+        for (&address, &(nonce, balance)) in state {
+            write_account(&mut writer, address, nonce, balance)?;
+        }
+        writer.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+        fs::rename(&staging, self.dir.join(SNAPSHOT_FILE))
+    }
+
+    /// Returns whether this executor preserves transaction ordering.
+    pub fn preserves_order(&self) -> bool {
+        true
+    }
+
+    /// Returns the name of this executor.
+    pub fn name(&self) -> &'static str {
+        "wal_in_memory"
+    }
+}
+
+/// Applies one transfer, returning the new state of the accounts it wrote or
+/// why it failed; a failed transfer changes nothing.
+fn apply_transfer(state: &mut WalState, tx: &SignedTransaction) -> Result<[(Address, (u64, U256)); 2], FailureReason> {
+    let sender = state.get(&tx.from).copied();
+    validate_stateful(tx, sender.map(|(nonce, balance)| SenderState::new(nonce, balance)))?;
+    let (nonce, balance) = sender.expect("validated sender has an account");
+    let sender = (
+        nonce.checked_add(1).ok_or(FailureReason::Overflow)?,
+        balance.checked_sub(tx.value).ok_or(FailureReason::InsufficientBalance)?,
+    );
+
+    // Read the receiver after the sender's update, so a self-transfer sees it
+    let receiver = if tx.to == tx.from {
+        sender
+    } else {
+        state.get(&tx.to).copied().unwrap_or_default()
+    };
+    let receiver = (receiver.0, receiver.1.checked_add(tx.value).ok_or(FailureReason::Overflow)?);
+    state.insert(tx.from, sender);
+    state.insert(tx.to, receiver);
+    Ok([(tx.from, state[&tx.from]), (tx.to, receiver)])
+}
+
+fn write_account(writer: &mut impl Write, address: Address, nonce: u64, balance: U256) -> io::Result<()> {
+    writer.write_all(address.as_slice())?;
+    writer.write_all(&nonce.to_be_bytes())?;
+    writer.write_all(&balance.to_be_bytes::<32>())
+}
+
+fn read_account(bytes: &[u8]) -> (Address, (u64, U256)) {
+    let address = Address::from_slice(&bytes[..20]);
+    let nonce = u64::from_be_bytes(bytes[20..28].try_into().expect("8-byte nonce"));
+    let balance = U256::from_be_slice(&bytes[28..ACCOUNT_LEN]);
+    (address, (nonce, balance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor, WorkloadConfig};
+    use tempfile::tempdir;
+
+    fn workload() -> Workload {
+        Workload::generate(WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 60,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            transactions_per_block: 10,
+            double_spends: 2,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_wal_matches_sequential_executor() {
+        let workload = workload();
+        let dir = tempdir().unwrap();
+        let executor = WalExecutor::new(dir.path(), true);
+        let wal = executor.execute_workload(&workload).unwrap();
+        let (_, revm) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+
+        assert_eq!((wal.result.successful, wal.result.failed), (revm.successful, revm.failed));
+        assert_eq!(wal.result.tx_index.statuses(&workload), revm.tx_index.statuses(&workload));
+        assert_eq!(wal.result.checkpoints, revm.checkpoints);
+        assert_eq!(wal.syncs, workload.blocks.len());
+        assert!(wal.log_bytes > 0);
+    }
+
+    #[test]
+    fn test_wal_recovers_snapshot_and_log() {
+        let workload = workload();
+        let options = WalOptions {
+            sync_mode: WalSyncMode::Transaction,
+            checkpoint_blocks: 4,
+        };
+
+        // Seven blocks, with the double-spends: a snapshot after the fourth, three more in the log
+        let dir = tempdir().unwrap();
+        let executor = WalExecutor::new_with_options(dir.path(), true, &options);
+        let wal = executor.execute_workload(&workload).unwrap();
+        assert_eq!(workload.blocks.len(), 7);
+        assert_eq!(wal.checkpoints, 2);
+        assert_eq!(wal.syncs, wal.result.successful + workload.blocks.len());
+
+        let (blocks, state) = executor.recover().unwrap();
+        assert_eq!(blocks, 7);
+        let mut checkpoints = CheckpointRecorder::new(&workload, 0);
+        let last = workload
+            .blocks
+            .iter()
+            .map(|_| checkpoints.record_block(|address| state.get(&address).copied()))
+            .last();
+        assert_eq!(last.as_ref(), wal.result.checkpoints.last());
+
+        // A torn final block is dropped, with the writes it had logged
+        let log_path = dir.path().join(LOG_FILE);
+        let log = fs::read(&log_path).unwrap();
+        fs::write(&log_path, &log[..log.len() - 4]).unwrap();
+        let (blocks, _) = executor.recover().unwrap();
+        assert_eq!(blocks, 6);
+    }
+}
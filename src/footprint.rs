@@ -0,0 +1,143 @@
+//! On-disk footprint of persistent backends.
+//!
+//! Throughput is half of a backend comparison; the other half is what the
+//! state costs to keep. A [`Footprint`] measures the space a backend's files
+//! take after a run and relates it to the accounts they hold: bytes per
+//! account, and space amplification against [`RAW_ACCOUNT_BYTES`], the
+//! 20-byte address plus the fixed 40-byte [`RawCodec`](crate::codec::RawCodec)
+//! value that any store has to hold for an account.
+//!
+//! Sizes are the blocks a file system allocated where the platform reports
+//! them, not file lengths, so sparse files and preallocated but unused
+//! database geometry count only for what is actually on disk. Everything
+//! under the backend's directory counts: indexes, free pages, logs,
+//! snapshots and history alike.
+
+use crate::Workload;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Bytes of an account in its rawest form: address, 8-byte nonce and
+/// 32-byte balance.
+pub const RAW_ACCOUNT_BYTES: u64 = 20 + 40;
+
+/// Space a backend takes for the accounts it holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Footprint {
+    /// Bytes the backend's files take on disk.
+    pub disk_bytes: u64,
+    /// Accounts the backend holds.
+    pub accounts: u64,
+}
+
+impl Footprint {
+    /// Measures the file or directory at `path`, holding `accounts` accounts.
+    pub fn measure(path: impl AsRef<Path>, accounts: u64) -> Self {
+        Self {
+            disk_bytes: disk_bytes(path.as_ref()),
+            accounts,
+        }
+    }
+
+    /// Measures a backend at `path` after running `workload` on top of
+    /// `background_accounts` untouched accounts.
+    pub fn of_workload(path: impl AsRef<Path>, workload: &Workload, background_accounts: u64) -> Self {
+        Self::measure(path, workload.accounts.len() as u64 + background_accounts)
+    }
+
+    /// Bytes the accounts take in raw form.
+    pub fn raw_bytes(&self) -> u64 {
+        self.accounts * RAW_ACCOUNT_BYTES
+    }
+
+    /// Bytes on disk per account held.
+    pub fn bytes_per_account(&self) -> f64 {
+        self.disk_bytes as f64 / self.accounts.max(1) as f64
+    }
+
+    /// Bytes on disk per byte of raw account data.
+    pub fn space_amplification(&self) -> f64 {
+        self.disk_bytes as f64 / self.raw_bytes().max(1) as f64
+    }
+}
+
+impl fmt::Display for Footprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2} MiB on disk for {} accounts, {:.0} bytes/account, {:.1}x space amplification",
+            self.disk_bytes as f64 / (1024.0 * 1024.0),
+            self.accounts,
+            self.bytes_per_account(),
+            self.space_amplification()
+        )
+    }
+}
+
+/// Bytes the file at `path` takes on disk, or every file below it if it is
+/// a directory; unreadable entries count as empty.
+pub fn disk_bytes(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if metadata.is_dir() {
+        return fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| disk_bytes(&entry.path())).sum())
+            .unwrap_or(0);
+    }
+    allocated_bytes(&metadata)
+}
+
+#[cfg(unix)]
+fn allocated_bytes(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    // st_blocks is in 512-byte units whatever the file system's block size
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_bytes(metadata: &fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_footprint() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("data"), vec![1u8; 64 * 1024]).unwrap();
+        fs::create_dir(dir.path().join("log")).unwrap();
+        fs::write(dir.path().join("log").join("0"), vec![1u8; 16 * 1024]).unwrap();
+
+        // Allocation rounds up to whole blocks
+        let footprint = Footprint::measure(dir.path(), 1000);
+        assert!(footprint.disk_bytes >= 80 * 1024, "{} bytes", footprint.disk_bytes);
+        assert_eq!(Footprint::measure(dir.path().join("missing"), 1000).disk_bytes, 0);
+
+        let footprint = Footprint {
+            disk_bytes: 120_000,
+            accounts: 1000,
+        };
+        assert_eq!(footprint.raw_bytes(), 60_000);
+        assert_eq!(footprint.bytes_per_account(), 120.0);
+        assert_eq!(footprint.space_amplification(), 2.0);
+        assert_eq!(
+            footprint.to_string(),
+            "0.11 MiB on disk for 1000 accounts, 120 bytes/account, 2.0x space amplification"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sparse_files_count_allocated_blocks() {
+        let dir = tempdir().unwrap();
+        let file = fs::File::create(dir.path().join("sparse")).unwrap();
+        file.set_len(64 * 1024 * 1024).unwrap();
+        assert!(disk_bytes(dir.path()) < 1024 * 1024);
+    }
+}
@@ -0,0 +1,221 @@
+//! CPU frequency sampling for benchmark stability checks.
+//!
+//! Parallel executor comparisons are very sensitive to turbo and thermal
+//! throttling: a run that starts at boost clocks and ends throttled looks like
+//! a scaling problem. [`FrequencyMonitor`] samples the current frequency of
+//! every core on a background thread while a benchmark cell runs, so cells
+//! whose clock moved too much can be flagged. [`preflight`] reports the
+//! frequency governor and boost setting before any cell runs.
+//!
+//! Frequencies are read from Linux cpufreq sysfs. On other platforms, or when
+//! cpufreq is unavailable, sampling yields no statistics.
+
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+const CPU_SYSFS: &str = "/sys/devices/system/cpu";
+
+/// Summary of frequency samples, each averaged across all cores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrequencyStats {
+    /// Number of samples taken.
+    pub samples: usize,
+    /// Mean frequency in MHz.
+    pub mean_mhz: f64,
+    /// Lowest sampled frequency in MHz.
+    pub min_mhz: f64,
+    /// Highest sampled frequency in MHz.
+    pub max_mhz: f64,
+    /// Standard deviation in MHz.
+    pub stddev_mhz: f64,
+}
+
+impl FrequencyStats {
+    /// Computes statistics over per-sample frequencies, or `None` if empty.
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count;
+        let variance = samples.iter().map(|mhz| (mhz - mean).powi(2)).sum::<f64>() / count;
+
+        Some(Self {
+            samples: samples.len(),
+            mean_mhz: mean,
+            min_mhz: samples.iter().copied().fold(f64::INFINITY, f64::min),
+            max_mhz: samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            stddev_mhz: variance.sqrt(),
+        })
+    }
+
+    /// Coefficient of variation in percent (`stddev / mean * 100`).
+    pub fn variation_percent(&self) -> f64 {
+        if self.mean_mhz == 0.0 {
+            0.0
+        } else {
+            self.stddev_mhz / self.mean_mhz * 100.0
+        }
+    }
+
+    /// Whether the variation exceeded `threshold_percent`.
+    pub fn is_unstable(&self, threshold_percent: f64) -> bool {
+        self.variation_percent() > threshold_percent
+    }
+}
+
+/// Reads the current frequency of every core in MHz.
+///
+/// Returns `None` if cpufreq is unavailable.
+pub fn read_core_frequencies() -> Option<Vec<f64>> {
+    let mut frequencies = Vec::new();
+    for entry in fs::read_dir(CPU_SYSFS).ok()?.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let is_core = name
+            .strip_prefix("cpu")
+            .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()));
+        if !is_core {
+            continue;
+        }
+        let khz = fs::read_to_string(entry.path().join("cpufreq/scaling_cur_freq")).ok();
+        if let Some(khz) = khz.and_then(|khz| khz.trim().parse::<f64>().ok()) {
+            frequencies.push(khz / 1000.0);
+        }
+    }
+    (!frequencies.is_empty()).then_some(frequencies)
+}
+
+/// Mean frequency across all cores in MHz.
+fn read_mean_frequency() -> Option<f64> {
+    let frequencies = read_core_frequencies()?;
+    Some(frequencies.iter().sum::<f64>() / frequencies.len() as f64)
+}
+
+/// Samples CPU frequency in the background while a closure runs.
+#[derive(Debug, Clone, Copy)]
+pub struct FrequencyMonitor {
+    interval: Duration,
+}
+
+impl FrequencyMonitor {
+    /// Creates a monitor sampling every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval: interval.max(Duration::from_millis(1)),
+        }
+    }
+
+    /// Runs `f`, returning its result and the frequency observed meanwhile.
+    ///
+    /// One sample is always taken at the start, so even very short runs get
+    /// statistics when cpufreq is available.
+    pub fn sample_during<R>(&self, f: impl FnOnce() -> R) -> (R, Option<FrequencyStats>) {
+        let done = AtomicBool::new(false);
+
+        let (result, samples) = thread::scope(|scope| {
+            let sampler = scope.spawn(|| {
+                let mut samples = Vec::new();
+                loop {
+                    samples.extend(read_mean_frequency());
+                    if done.load(Ordering::Acquire) {
+                        return samples;
+                    }
+                    thread::sleep(self.interval);
+                }
+            });
+
+            let result = f();
+            done.store(true, Ordering::Release);
+            (result, sampler.join().expect("frequency sampler panicked"))
+        });
+
+        (result, FrequencyStats::from_samples(&samples))
+    }
+}
+
+/// Frequency scaling configuration observed before benchmarking.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreflightReport {
+    /// cpufreq governor of the first core, e.g. `performance` or `powersave`.
+    pub governor: Option<String>,
+    /// Whether turbo/boost is enabled.
+    pub boost_enabled: Option<bool>,
+    /// Frequency while idle for the sampling window.
+    pub idle: Option<FrequencyStats>,
+}
+
+impl PreflightReport {
+    /// Human-readable warnings about settings that make results less stable.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        match self.governor.as_deref() {
+            Some("performance") => {}
+            Some(governor) => warnings.push(format!(
+                "CPU governor is '{}'; 'performance' gives more stable clocks",
+                governor
+            )),
+            None => warnings.push("CPU frequency information unavailable (no cpufreq)".to_string()),
+        }
+        if self.boost_enabled == Some(true) {
+            warnings.push("Turbo/boost is enabled; clocks may drop as the CPU heats up".to_string());
+        }
+        warnings
+    }
+}
+
+/// Inspects frequency scaling settings and samples idle frequency for `window`.
+pub fn preflight(window: Duration) -> PreflightReport {
+    let governor = fs::read_to_string(format!("{}/cpu0/cpufreq/scaling_governor", CPU_SYSFS))
+        .ok()
+        .map(|governor| governor.trim().to_string());
+
+    // intel_pstate exposes an inverted flag; acpi-cpufreq and amd-pstate use `boost`
+    let boost_enabled = fs::read_to_string(format!("{}/intel_pstate/no_turbo", CPU_SYSFS))
+        .ok()
+        .map(|no_turbo| no_turbo.trim() == "0")
+        .or_else(|| {
+            fs::read_to_string(format!("{}/cpufreq/boost", CPU_SYSFS))
+                .ok()
+                .map(|boost| boost.trim() == "1")
+        });
+
+    let monitor = FrequencyMonitor::new(Duration::from_millis(50));
+    let ((), idle) = monitor.sample_during(|| thread::sleep(window));
+
+    PreflightReport {
+        governor,
+        boost_enabled,
+        idle,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequency_stats() {
+        let stable = FrequencyStats::from_samples(&[3000.0, 3000.0, 3000.0]).unwrap();
+        assert_eq!(stable.variation_percent(), 0.0);
+        assert!(!stable.is_unstable(1.0));
+
+        let throttled = FrequencyStats::from_samples(&[4000.0, 4000.0, 2000.0, 2000.0]).unwrap();
+        assert_eq!(throttled.mean_mhz, 3000.0);
+        assert_eq!(throttled.min_mhz, 2000.0);
+        assert_eq!(throttled.max_mhz, 4000.0);
+        assert!(throttled.is_unstable(5.0));
+
+        assert!(FrequencyStats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_monitor_returns_closure_result() {
+        let (value, _) = FrequencyMonitor::new(Duration::from_millis(5)).sample_during(|| 7);
+        assert_eq!(value, 7);
+    }
+}
@@ -0,0 +1,114 @@
+//! Shared, read-only genesis state for in-memory executors.
+//!
+//! Inserting tens of thousands of accounts into a fresh `CacheDB` before
+//! every run made database setup dominate short benchmark iterations.
+//! [`GenesisState`] holds a workload's funded accounts behind an `Arc` and
+//! serves them as the `CacheDB`'s backing database, so
+//! [`Workload::create_db`](crate::Workload::create_db) builds it once per
+//! workload and every later database is a pointer copy. Executors read
+//! accounts through the cache, which loads them from the snapshot on first
+//! touch and keeps every write to itself; the snapshot never changes.
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use revm::database_interface::DatabaseRef;
+use revm::primitives::KECCAK_EMPTY;
+use revm::state::{AccountInfo, Bytecode};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::Arc;
+
+/// Accounts every run of a workload starts from.
+#[derive(Clone, Default)]
+pub struct GenesisState {
+    accounts: Arc<HashMap<Address, AccountInfo>>,
+}
+
+impl GenesisState {
+    /// Funds every address in `addresses` with `balance`.
+    pub fn funded(addresses: impl IntoIterator<Item = Address>, balance: U256) -> Self {
+        let accounts = addresses
+            .into_iter()
+            .map(|address| {
+                let info = AccountInfo {
+                    balance,
+                    nonce: 0,
+                    code_hash: KECCAK_EMPTY,
+                    code: None,
+                };
+                (address, info)
+            })
+            .collect();
+        Self {
+            accounts: Arc::new(accounts),
+        }
+    }
+
+    /// Number of accounts in the snapshot.
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Returns true if the snapshot holds no accounts.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Returns the genesis account at `address`, if funded.
+    pub fn get(&self, address: &Address) -> Option<&AccountInfo> {
+        self.accounts.get(address)
+    }
+}
+
+impl fmt::Debug for GenesisState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenesisState").field("accounts", &self.accounts.len()).finish()
+    }
+}
+
+impl DatabaseRef for GenesisState {
+    type Error = Infallible;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.accounts.get(&address).cloned())
+    }
+
+    fn code_by_hash_ref(&self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Ok(Bytecode::default())
+    }
+
+    fn storage_ref(&self, _address: Address, _index: U256) -> Result<U256, Self::Error> {
+        Ok(U256::ZERO)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        Ok(keccak256(number.to_string().as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::database::CacheDB;
+    use revm::database_interface::Database;
+
+    #[test]
+    fn test_databases_share_snapshot() {
+        let (alice, bob) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let genesis = GenesisState::funded([alice, bob], U256::from(100));
+        assert_eq!(genesis.len(), 2);
+
+        let mut first = CacheDB::new(genesis.clone());
+        let mut info = first.basic(alice).unwrap().unwrap();
+        info.balance = U256::from(40);
+        first.insert_account_info(alice, info);
+
+        // Writes stay in their own cache; the snapshot and other databases are untouched
+        let mut second = CacheDB::new(genesis.clone());
+        assert_eq!(first.basic(alice).unwrap().unwrap().balance, U256::from(40));
+        assert_eq!(second.basic(alice).unwrap().unwrap().balance, U256::from(100));
+        assert_eq!(genesis.get(&alice).unwrap().balance, U256::from(100));
+        assert!(second.basic(Address::with_last_byte(3)).unwrap().is_none());
+        assert!(Arc::ptr_eq(&first.db.accounts, &second.db.accounts));
+    }
+}
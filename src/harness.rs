@@ -0,0 +1,869 @@
+//! Benchmark matrix harness.
+//!
+//! A [`BenchPlan`] is a list of cells, each one executor configuration at one
+//! hot-account count, with a closure that runs the cell and returns a
+//! [`CellOutput`]. A [`BenchRunner`] executes the plan with repetitions,
+//! optional seeded interleaving, cooldowns, and CPU frequency sampling, and
+//! returns a [`BenchReport`] holding every result in plan order.
+//!
+//! The `db-test` binary builds its plan from command-line flags. Downstream
+//! crates can build their own, mixing built-in and custom executors.
+//!
+//! # Example
+//!
+//! ```
+//! use db_test::harness::{run_in_memory, BenchPlan, BenchRunner, CellOutput};
+//! use db_test::{SequentialExecutor, Workload, WorkloadConfig};
+//!
+//! let workload = Workload::generate(WorkloadConfig {
+//!     num_accounts: 10,
+//!     num_transactions: 20,
+//!     transactions_per_block: 10,
+//!     hot_accounts: 10,
+//!     ..Default::default()
+//! });
+//!
+//! let mut plan = BenchPlan::new();
+//! plan.add("Sequential", None, || {
+//!     let executor = SequentialExecutor::with_verification(true);
+//!     CellOutput::new(run_in_memory(&executor, &workload, "10 accounts", 20))
+//! });
+//!
+//! let report = BenchRunner::new().with_repetitions(2).run(&plan);
+//! assert_eq!(report.cells.len(), 2);
+//! assert_eq!(report.summary()[0].runs, 2);
+//! ```
+
+use crate::executor::Executor;
+use crate::frequency::{FrequencyMonitor, FrequencyStats};
+use crate::{GenesisState, Workload};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use revm::database::CacheDB;
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Results from a single benchmark run.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BenchResult {
+    pub hot_accounts_label: String,
+    pub executor_name: String,
+    pub preserves_order: bool,
+    pub successful: usize,
+    pub failed: usize,
+    pub duration_ms: f64,
+    pub throughput_tps: f64,
+}
+
+impl BenchResult {
+    /// Prints the column header for [`print`](Self::print) rows.
+    pub fn print_header() {
+        println!(
+            "{:<20} | {:<25} | {:<8} | {:<10} | {:<10} | {:<12} | {:<12}",
+            "Hot Accounts", "Executor", "Ordering", "Successful", "Failed", "Time (ms)", "TPS"
+        );
+        println!("{}", "-".repeat(120));
+    }
+
+    /// Prints this result as a table row.
+    pub fn print(&self) {
+        println!(
+            "{:<20} | {:<25} | {:<8} | {:<10} | {:<10} | {:<12.2} | {:<12.0}",
+            self.hot_accounts_label,
+            self.executor_name,
+            if self.preserves_order { "strict" } else { "loose" },
+            self.successful,
+            self.failed,
+            self.duration_ms,
+            self.throughput_tps,
+        );
+    }
+}
+
+/// Runs an executor over a fresh in-memory database and times it.
+pub fn run_in_memory<E>(
+    executor: &E,
+    workload: &Workload,
+    hot_accounts_label: &str,
+    num_transactions: usize,
+) -> BenchResult
+where
+    E: Executor<Database = CacheDB<GenesisState>>,
+{
+    let db = workload.create_db();
+
+    let start = Instant::now();
+    let (_, result) = executor.execute(db, workload);
+    let elapsed = start.elapsed();
+
+    BenchResult {
+        hot_accounts_label: hot_accounts_label.to_string(),
+        executor_name: executor.name().to_string(),
+        preserves_order: executor.preserves_order(),
+        successful: result.successful,
+        failed: result.failed,
+        duration_ms: elapsed.as_secs_f64() * 1000.0,
+        throughput_tps: num_transactions as f64 / elapsed.as_secs_f64(),
+    }
+}
+
+/// Runs an object-safe executor and collects its result row and detail lines.
+pub fn run_any(
+    executor: &dyn crate::executor::AnyExecutor,
+    workload: &Workload,
+    hot_accounts_label: &str,
+    num_transactions: usize,
+) -> CellOutput {
+    let mut report = executor.execute_workload(workload);
+    let mut details = std::mem::take(&mut report.details);
+    if report.result.failed > 0 && !report.result.failure_reasons.is_empty() {
+        details.push(format!("failures: {}", report.result.failure_summary()));
+    }
+
+    CellOutput {
+        result: BenchResult {
+            hot_accounts_label: hot_accounts_label.to_string(),
+            executor_name: executor.name(),
+            preserves_order: executor.preserves_order(),
+            successful: report.result.successful,
+            failed: report.result.failed,
+            duration_ms: report.elapsed.as_secs_f64() * 1000.0,
+            throughput_tps: report.throughput_tps(num_transactions),
+        },
+        details,
+    }
+}
+
+/// Output of one benchmark cell: its result row plus detail lines printed under it.
+#[derive(Debug, Clone)]
+pub struct CellOutput {
+    pub result: BenchResult,
+    pub details: Vec<String>,
+}
+
+impl CellOutput {
+    /// Creates an output without detail lines.
+    pub fn new(result: BenchResult) -> Self {
+        Self {
+            result,
+            details: Vec::new(),
+        }
+    }
+
+    /// Appends a detail line.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.details.push(detail.into());
+        self
+    }
+}
+
+/// One executor configuration at one hot-account count.
+struct Cell<'a> {
+    section: String,
+    group: Option<String>,
+    run: Box<dyn Fn() -> CellOutput + 'a>,
+}
+
+/// Every benchmark cell, in the order their results are grouped.
+#[derive(Default)]
+pub struct BenchPlan<'a> {
+    cells: Vec<Cell<'a>>,
+}
+
+impl<'a> BenchPlan<'a> {
+    /// Creates an empty plan.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a cell to `section`, optionally within a sub-group such as a thread count.
+    ///
+    /// `run` may be called several times (once per repetition) and should set
+    /// up fresh backend state on every call.
+    pub fn add(&mut self, section: &str, group: Option<String>, run: impl Fn() -> CellOutput + 'a) {
+        self.cells.push(Cell {
+            section: section.to_string(),
+            group,
+            run: Box::new(run),
+        });
+    }
+
+    /// Number of cells in the plan.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether the plan has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+/// Result of one run of one cell.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellReport {
+    /// Position of the cell in its [`BenchPlan`].
+    pub index: usize,
+    pub section: String,
+    pub group: Option<String>,
+    /// Zero-based repetition this run belongs to.
+    pub repetition: usize,
+    pub result: BenchResult,
+    pub details: Vec<String>,
+    /// CPU frequency while the cell ran, if sampling was enabled and available.
+    pub frequency: Option<FrequencyStats>,
+    /// Whether the frequency varied more than the runner's threshold.
+    pub unstable: bool,
+}
+
+impl CellReport {
+    fn print(&self) {
+        self.result.print();
+        for line in &self.details {
+            println!("{:<20} | {}", "", line);
+        }
+        if let Some(stats) = self.frequency {
+            println!(
+                "{:<20} | cpu freq: {:.0} MHz avg ({:.0}-{:.0}), variation: {:.1}%{}",
+                "",
+                stats.mean_mhz,
+                stats.min_mhz,
+                stats.max_mhz,
+                stats.variation_percent(),
+                if self.unstable { " ⚠️  UNSTABLE" } else { "" }
+            );
+        }
+    }
+}
+
+/// Throughput statistics for one executor across every cell it ran in.
+#[derive(Debug, Clone)]
+pub struct ExecutorSummary {
+    pub executor_name: String,
+    pub runs: usize,
+    pub avg_tps: f64,
+    pub min_tps: f64,
+    pub max_tps: f64,
+}
+
+/// Executor the ranking's speedups are relative to.
+pub const BASELINE_EXECUTOR: &str = "sequential_in_memory";
+
+/// One executor configuration's mean throughput at one conflict level.
+#[derive(Debug, Clone)]
+pub struct RankedExecutor {
+    /// Executor name, followed by its group (e.g. thread count) if it has one.
+    pub label: String,
+    /// Runs at this conflict level: repetitions, times workload seeds.
+    pub runs: usize,
+    pub avg_tps: f64,
+    pub min_tps: f64,
+    pub max_tps: f64,
+    /// Mean TPS relative to the baseline at the same conflict level, if it ran.
+    pub speedup: Option<f64>,
+}
+
+/// Executors ranked by throughput at one conflict level.
+#[derive(Debug, Clone)]
+pub struct ConflictRanking {
+    pub hot_accounts_label: String,
+    /// Fastest first.
+    pub executors: Vec<RankedExecutor>,
+}
+
+/// Mean durations of two executors at one conflict level, where one of them
+/// does the work of the other plus some layer the other skips.
+#[derive(Debug, Clone)]
+pub struct PairedOverhead {
+    pub hot_accounts_label: String,
+    /// Mean duration of the executor with the layer.
+    pub with_ms: f64,
+    /// Mean duration of the executor without it.
+    pub without_ms: f64,
+}
+
+impl PairedOverhead {
+    /// Share of the slower executor's time spent in the layer, in percent.
+    pub fn overhead_percent(&self) -> f64 {
+        if self.with_ms == 0.0 {
+            0.0
+        } else {
+            (self.with_ms - self.without_ms) / self.with_ms * 100.0
+        }
+    }
+}
+
+/// Structured results of a [`BenchRunner`] run.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BenchReport {
+    /// Every cell run, grouped in plan order with repetitions adjacent.
+    pub cells: Vec<CellReport>,
+    /// Frequency variation threshold in percent, if sampling was enabled.
+    pub frequency_threshold: Option<f64>,
+}
+
+impl BenchReport {
+    /// Iterates over every result row.
+    pub fn results(&self) -> impl Iterator<Item = &BenchResult> {
+        self.cells.iter().map(|cell| &cell.result)
+    }
+
+    /// Number of cell runs flagged for unstable CPU frequency.
+    pub fn unstable_cells(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.unstable).count()
+    }
+
+    /// Per-executor throughput statistics, in order of first appearance.
+    pub fn summary(&self) -> Vec<ExecutorSummary> {
+        let mut summaries: Vec<ExecutorSummary> = Vec::new();
+        for result in self.results() {
+            let tps = result.throughput_tps;
+            match summaries
+                .iter_mut()
+                .find(|summary| summary.executor_name == result.executor_name)
+            {
+                Some(summary) => {
+                    summary.avg_tps += tps;
+                    summary.min_tps = summary.min_tps.min(tps);
+                    summary.max_tps = summary.max_tps.max(tps);
+                    summary.runs += 1;
+                }
+                None => summaries.push(ExecutorSummary {
+                    executor_name: result.executor_name.clone(),
+                    runs: 1,
+                    avg_tps: tps,
+                    min_tps: tps,
+                    max_tps: tps,
+                }),
+            }
+        }
+        for summary in &mut summaries {
+            summary.avg_tps /= summary.runs as f64;
+        }
+        summaries
+    }
+
+    /// Ranks every executor configuration by mean TPS at each conflict level,
+    /// in order of first appearance, with speedups relative to `baseline`.
+    pub fn ranking(&self, baseline: &str) -> Vec<ConflictRanking> {
+        let mut rankings: Vec<ConflictRanking> = Vec::new();
+        // (conflict level, executor with its total TPS in `avg_tps`)
+        let mut totals: Vec<(String, RankedExecutor)> = Vec::new();
+        for cell in &self.cells {
+            let result = &cell.result;
+            let tps = result.throughput_tps;
+            let label = match &cell.group {
+                Some(group) => format!("{} ({})", result.executor_name, group),
+                None => result.executor_name.clone(),
+            };
+            match totals
+                .iter_mut()
+                .find(|(level, existing)| *level == result.hot_accounts_label && existing.label == label)
+            {
+                Some((_, existing)) => {
+                    existing.avg_tps += tps;
+                    existing.min_tps = existing.min_tps.min(tps);
+                    existing.max_tps = existing.max_tps.max(tps);
+                    existing.runs += 1;
+                }
+                None => totals.push((
+                    result.hot_accounts_label.clone(),
+                    RankedExecutor {
+                        label,
+                        runs: 1,
+                        avg_tps: tps,
+                        min_tps: tps,
+                        max_tps: tps,
+                        speedup: None,
+                    },
+                )),
+            }
+        }
+
+        for (level, mut executor) in totals {
+            executor.avg_tps /= executor.runs as f64;
+            match rankings.iter_mut().find(|ranking| ranking.hot_accounts_label == level) {
+                Some(ranking) => ranking.executors.push(executor),
+                None => rankings.push(ConflictRanking {
+                    hot_accounts_label: level,
+                    executors: vec![executor],
+                }),
+            }
+        }
+
+        for ranking in &mut rankings {
+            let baseline_tps: Vec<f64> = self
+                .results()
+                .filter(|result| {
+                    result.executor_name == baseline && result.hot_accounts_label == ranking.hot_accounts_label
+                })
+                .map(|result| result.throughput_tps)
+                .collect();
+            let baseline_tps =
+                (!baseline_tps.is_empty()).then(|| baseline_tps.iter().sum::<f64>() / baseline_tps.len() as f64);
+            for executor in &mut ranking.executors {
+                executor.speedup = baseline_tps.map(|baseline_tps| executor.avg_tps / baseline_tps);
+            }
+            ranking.executors.sort_by(|a, b| b.avg_tps.total_cmp(&a.avg_tps));
+        }
+        rankings
+    }
+
+    /// Pairs executors `with` and `without` at every conflict level both ran
+    /// at, in order of first appearance.
+    pub fn paired_overhead(&self, with: &str, without: &str) -> Vec<PairedOverhead> {
+        let mean_ms = |executor: &str, label: &str| {
+            let durations: Vec<f64> = self
+                .results()
+                .filter(|result| result.executor_name == executor && result.hot_accounts_label == label)
+                .map(|result| result.duration_ms)
+                .collect();
+            (!durations.is_empty()).then(|| durations.iter().sum::<f64>() / durations.len() as f64)
+        };
+
+        let mut labels: Vec<&str> = Vec::new();
+        for result in self.results() {
+            if !labels.contains(&result.hot_accounts_label.as_str()) {
+                labels.push(&result.hot_accounts_label);
+            }
+        }
+        labels
+            .into_iter()
+            .filter_map(|label| {
+                Some(PairedOverhead {
+                    hot_accounts_label: label.to_string(),
+                    with_ms: mean_ms(with, label)?,
+                    without_ms: mean_ms(without, label)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Prints [`paired_overhead`](Self::paired_overhead) as a table under `title`.
+    pub fn print_paired_overhead(&self, title: &str, with: &str, without: &str) {
+        let rows = self.paired_overhead(with, without);
+        if rows.is_empty() {
+            return;
+        }
+
+        print_section_header(title);
+        println!("{:<20} | {:<28} | {:<28} | {:<10}", "Conflict Level", with, without, "Overhead");
+        println!("{}", "-".repeat(96));
+        for row in rows {
+            println!(
+                "{:<20} | {:<28} | {:<28} | {:.1}%",
+                row.hot_accounts_label,
+                format!("{:.2} ms", row.with_ms),
+                format!("{:.2} ms", row.without_ms),
+                row.overhead_percent()
+            );
+        }
+        println!();
+    }
+
+    /// Prints every cell grouped by section, followed by any stability warning.
+    pub fn print(&self) {
+        let mut printer = ReportPrinter::default();
+        for cell in &self.cells {
+            printer.print(cell);
+        }
+        printer.finish();
+        self.print_stability_warning();
+    }
+
+    /// Prints the per-executor summary table.
+    pub fn print_summary(&self) {
+        if self.cells.is_empty() {
+            return;
+        }
+
+        println!();
+        print_section_header("Summary Statistics");
+
+        println!("{:<30} | {:<15} | {:<15} | {:<15}", "Executor", "Avg TPS", "Min TPS", "Max TPS");
+        println!("{}", "-".repeat(80));
+
+        for summary in self.summary() {
+            println!(
+                "{:<30} | {:<15.0} | {:<15.0} | {:<15.0}",
+                summary.executor_name, summary.avg_tps, summary.min_tps, summary.max_tps
+            );
+        }
+
+        println!();
+        self.print_ranking();
+    }
+
+    /// Prints executors ranked by TPS at each conflict level.
+    fn print_ranking(&self) {
+        print_section_header("Ranking by Conflict Level");
+
+        println!(
+            "{:<20} | {:<4} | {:<45} | {:<12} | {:<17} | {:<14}",
+            "Hot Accounts", "Rank", "Executor", "Avg TPS", "TPS Range", "vs Sequential"
+        );
+        println!("{}", "-".repeat(127));
+
+        for ranking in self.ranking(BASELINE_EXECUTOR) {
+            for (rank, executor) in ranking.executors.iter().enumerate() {
+                let speedup = executor
+                    .speedup
+                    .map_or_else(|| "-".to_string(), |speedup| format!("{:.2}x", speedup));
+                println!(
+                    "{:<20} | {:<4} | {:<45} | {:<12.0} | {:<17} | {:<14}",
+                    if rank == 0 { ranking.hot_accounts_label.as_str() } else { "" },
+                    rank + 1,
+                    executor.label,
+                    executor.avg_tps,
+                    format!("{:.0}-{:.0}", executor.min_tps, executor.max_tps),
+                    speedup
+                );
+            }
+        }
+
+        println!();
+    }
+
+    fn print_stability_warning(&self) {
+        let unstable = self.unstable_cells();
+        if let (Some(threshold), true) = (self.frequency_threshold, unstable > 0) {
+            println!(
+                "⚠️  {} of {} cells exceeded {:.1}% CPU frequency variation; treat them with caution",
+                unstable,
+                self.cells.len(),
+                threshold
+            );
+            println!();
+        }
+    }
+}
+
+/// Receives progress from a [`BenchRunner`] as it runs, e.g. to drive a live display.
+pub trait RunObserver: fmt::Debug {
+    /// The runner is about to run `cells`, given as `(index, section, group)`
+    /// in plan order, `repetitions` times each.
+    fn run_started(&self, cells: &[(usize, &str, Option<&str>)], repetitions: usize);
+
+    /// A run of the cell at `index` is starting.
+    fn cell_started(&self, index: usize, repetition: usize);
+
+    /// A run of a cell finished.
+    fn cell_finished(&self, report: &CellReport);
+}
+
+/// Executes a [`BenchPlan`].
+///
+/// Without interleaving, each cell's repetitions run back-to-back in plan
+/// order. With an interleave seed, every repetition runs all cells once in a
+/// freshly shuffled order, so cache and thermal effects are spread across
+/// executors instead of favouring whichever ran first. Either way the report
+/// lists cells in plan order.
+#[derive(Debug, Clone)]
+pub struct BenchRunner {
+    repetitions: usize,
+    interleave_seed: Option<u64>,
+    cooldown: Duration,
+    frequency: Option<(FrequencyMonitor, f64)>,
+    print_results: bool,
+    only_cell: Option<usize>,
+    observer: Option<Arc<dyn RunObserver>>,
+}
+
+impl Default for BenchRunner {
+    fn default() -> Self {
+        Self {
+            repetitions: 1,
+            interleave_seed: None,
+            cooldown: Duration::ZERO,
+            frequency: None,
+            print_results: false,
+            only_cell: None,
+            observer: None,
+        }
+    }
+}
+
+impl BenchRunner {
+    /// Creates a runner that runs every cell once, in plan order, silently.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs every cell `repetitions` times.
+    pub fn with_repetitions(mut self, repetitions: usize) -> Self {
+        self.repetitions = repetitions.max(1);
+        self
+    }
+
+    /// Shuffles cell order with `seed`, reshuffling every repetition.
+    pub fn with_interleave(mut self, seed: u64) -> Self {
+        self.interleave_seed = Some(seed);
+        self
+    }
+
+    /// Idles for `cooldown` between consecutive cells.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Samples CPU frequency every `interval` while each cell runs, flagging
+    /// cells whose variation exceeds `threshold_percent`.
+    pub fn with_frequency_sampling(mut self, interval: Duration, threshold_percent: f64) -> Self {
+        self.frequency = Some((FrequencyMonitor::new(interval), threshold_percent));
+        self
+    }
+
+    /// Prints results to stdout as they become available.
+    ///
+    /// Without interleaving, rows stream as each cell finishes; with it, rows
+    /// are printed in plan order once every cell has run.
+    pub fn with_printing(mut self, print_results: bool) -> Self {
+        self.print_results = print_results;
+        self
+    }
+
+    /// Runs only the plan cell at `index`, e.g. to reproduce one result.
+    pub fn with_only_cell(mut self, index: usize) -> Self {
+        self.only_cell = Some(index);
+        self
+    }
+
+    /// Reports every cell run to `observer` as it starts and finishes.
+    pub fn with_observer(mut self, observer: Arc<dyn RunObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Runs every cell in `plan` and collects the results.
+    pub fn run(&self, plan: &BenchPlan<'_>) -> BenchReport {
+        let mut cells_run = 0;
+        let mut report = BenchReport {
+            cells: Vec::new(),
+            frequency_threshold: self.frequency.map(|(_, threshold)| threshold),
+        };
+        if let Some(observer) = &self.observer {
+            let cells: Vec<(usize, &str, Option<&str>)> = self
+                .selected(plan)
+                .into_iter()
+                .map(|index| {
+                    let cell = &plan.cells[index];
+                    (index, cell.section.as_str(), cell.group.as_deref())
+                })
+                .collect();
+            observer.run_started(&cells, self.repetitions);
+        }
+
+        let Some(seed) = self.interleave_seed else {
+            let mut printer = ReportPrinter::default();
+            for index in self.selected(plan) {
+                for repetition in 0..self.repetitions {
+                    let cell_report = self.run_cell(plan, index, repetition, &mut cells_run);
+                    if self.print_results {
+                        printer.print(&cell_report);
+                    }
+                    report.cells.push(cell_report);
+                }
+            }
+            if self.print_results {
+                printer.finish();
+                report.print_stability_warning();
+            }
+            return report;
+        };
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut order: Vec<usize> = self.selected(plan);
+        let mut runs: Vec<Vec<CellReport>> = plan.cells.iter().map(|_| Vec::new()).collect();
+
+        for repetition in 0..self.repetitions {
+            order.shuffle(&mut rng);
+            if self.print_results {
+                println!(
+                    "Repetition {}/{}: running {} cells in shuffled order (seed {})",
+                    repetition + 1,
+                    self.repetitions,
+                    order.len(),
+                    seed
+                );
+            }
+            for &index in &order {
+                runs[index].push(self.run_cell(plan, index, repetition, &mut cells_run));
+            }
+        }
+
+        report.cells = runs.into_iter().flatten().collect();
+        if self.print_results {
+            println!();
+            report.print();
+        }
+        report
+    }
+
+    /// Indices of the plan cells this runner executes.
+    fn selected(&self, plan: &BenchPlan<'_>) -> Vec<usize> {
+        (0..plan.cells.len())
+            .filter(|&index| self.only_cell.map_or(true, |only| only == index))
+            .collect()
+    }
+
+    fn run_cell(&self, plan: &BenchPlan<'_>, index: usize, repetition: usize, cells_run: &mut usize) -> CellReport {
+        let cell = &plan.cells[index];
+        if *cells_run > 0 && !self.cooldown.is_zero() {
+            thread::sleep(self.cooldown);
+        }
+        *cells_run += 1;
+        if let Some(observer) = &self.observer {
+            observer.cell_started(index, repetition);
+        }
+
+        let (output, frequency) = match self.frequency {
+            Some((monitor, _)) => monitor.sample_during(|| (cell.run)()),
+            None => ((cell.run)(), None),
+        };
+        let unstable = match (frequency, self.frequency) {
+            (Some(stats), Some((_, threshold))) => stats.is_unstable(threshold),
+            _ => false,
+        };
+
+        let report = CellReport {
+            index,
+            section: cell.section.clone(),
+            group: cell.group.clone(),
+            repetition,
+            result: output.result,
+            details: output.details,
+            frequency,
+            unstable,
+        };
+        if let Some(observer) = &self.observer {
+            observer.cell_finished(&report);
+        }
+        report
+    }
+}
+
+/// Prints cell reports, emitting section and group headers whenever they change.
+#[derive(Default)]
+struct ReportPrinter {
+    current: Option<(String, Option<String>)>,
+}
+
+impl ReportPrinter {
+    fn print(&mut self, cell: &CellReport) {
+        let key = (cell.section.clone(), cell.group.clone());
+        if self.current.as_ref() != Some(&key) {
+            if self.current.is_some() {
+                println!();
+            }
+            if self.current.as_ref().map(|(section, _)| section) != Some(&key.0) {
+                print_section_header(&key.0);
+            }
+            if let Some(group) = &key.1 {
+                println!("--- {} ---", group);
+            }
+            BenchResult::print_header();
+            self.current = Some(key);
+        }
+
+        cell.print();
+    }
+
+    fn finish(&self) {
+        if self.current.is_some() {
+            println!();
+        }
+    }
+}
+
+/// Prints a boxed section title.
+pub fn print_section_header(title: &str) {
+    println!("═══════════════════════════════════════════════════════════════════════════════════════════════════════");
+    println!("  {}", title);
+    println!("═══════════════════════════════════════════════════════════════════════════════════════════════════════");
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SequentialExecutor, WorkloadConfig};
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_interleaved_runs_are_reported_in_plan_order() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            ..Default::default()
+        });
+
+        let executed = RefCell::new(Vec::new());
+        let mut plan = BenchPlan::new();
+        for name in ["first", "second", "third"] {
+            let executed = &executed;
+            let workload = &workload;
+            plan.add(name, None, move || {
+                executed.borrow_mut().push(name);
+                let executor = SequentialExecutor::with_verification(false);
+                let mut result = run_in_memory(&executor, workload, "10 accounts", 20);
+                result.executor_name = name.to_string();
+                CellOutput::new(result).with_detail(name)
+            });
+        }
+
+        let report = BenchRunner::new()
+            .with_repetitions(4)
+            .with_interleave(7)
+            .run(&plan);
+
+        // Every cell ran once per repetition
+        assert_eq!(executed.borrow().len(), 12);
+        for name in ["first", "second", "third"] {
+            assert_eq!(executed.borrow().iter().filter(|&&ran| ran == name).count(), 4);
+        }
+
+        // The report is grouped by cell in plan order regardless of run order
+        let sections: Vec<&str> = report.cells.iter().map(|cell| cell.section.as_str()).collect();
+        assert_eq!(sections[..4], ["first"; 4]);
+        assert_eq!(sections[8..], ["third"; 4]);
+        assert_eq!(report.cells[1].repetition, 1);
+        assert_eq!(report.cells[0].details, vec!["first".to_string()]);
+
+        let summary = report.summary();
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].executor_name, "first");
+        assert_eq!(summary[0].runs, 4);
+        assert!(summary.iter().all(|s| s.min_tps <= s.avg_tps && s.avg_tps <= s.max_tps));
+        assert!(report.results().all(|result| result.successful == 20));
+
+        let ranking = report.ranking("first");
+        assert_eq!(ranking.len(), 1);
+        assert_eq!(ranking[0].executors.len(), 3);
+        assert!(ranking[0].executors.windows(2).all(|pair| pair[0].avg_tps >= pair[1].avg_tps));
+        let first = ranking[0].executors.iter().find(|executor| executor.label == "first").unwrap();
+        assert_eq!(first.speedup, Some(1.0));
+        assert_eq!(first.runs, 4);
+        assert!(first.min_tps <= first.avg_tps && first.avg_tps <= first.max_tps);
+        assert!(report.ranking("missing")[0].executors.iter().all(|executor| executor.speedup.is_none()));
+
+        let overhead = report.paired_overhead("first", "second");
+        assert_eq!(overhead.len(), 1);
+        assert_eq!(overhead[0].hot_accounts_label, "10 accounts");
+        assert!(report.paired_overhead("first", "missing").is_empty());
+        let halved = PairedOverhead {
+            hot_accounts_label: String::new(),
+            with_ms: 4.0,
+            without_ms: 1.0,
+        };
+        assert_eq!(halved.overhead_percent(), 75.0);
+    }
+}
@@ -0,0 +1,181 @@
+//! Account key layouts for persistent backends.
+//!
+//! Reth keys accounts by `keccak256(address)`, which spreads writes evenly
+//! but scatters accounts that are touched together across the whole key
+//! space. A [`KeyScheme`] picks the layout per backend:
+//!
+//! - [`KeyScheme::Hashed`]: the 32-byte hashed address, as in reth
+//! - [`KeyScheme::Plain`]: the raw 20-byte address, so keys sort by address
+//! - [`KeyScheme::Prefixed`]: a 2-byte hash prefix followed by the raw
+//!   address; the prefix still spreads writes over 65536 buckets while
+//!   addresses within a bucket keep their order, in 22 bytes
+//!
+//! Backends with fixed-width keys (MDBX's `B256`) store the scheme's bytes
+//! zero-padded to 32, which preserves their order. The state root always
+//! commits to hashed addresses, so non-hashed schemes rehash decoded keys
+//! and sort before building the tree.
+
+use alloy_primitives::{keccak256, Address, B256};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::options::OptionError;
+
+/// Bytes of the bucket prefix in [`KeyScheme::Prefixed`] keys.
+const PREFIX_LEN: usize = 2;
+
+/// How a backend derives an account's key from its address.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum KeyScheme {
+    /// `keccak256(address)`.
+    #[default]
+    Hashed,
+    /// The address itself.
+    Plain,
+    /// `keccak256(address)[..2] ++ address`.
+    Prefixed,
+}
+
+impl KeyScheme {
+    /// Every scheme, in reporting order.
+    pub const ALL: [Self; 3] = [Self::Hashed, Self::Plain, Self::Prefixed];
+
+    /// Name used on the command line and in reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hashed => "hashed",
+            Self::Plain => "plain",
+            Self::Prefixed => "prefixed",
+        }
+    }
+
+    /// Significant bytes of a key; the rest of a [`key`](Self::key) is padding.
+    pub fn key_len(&self) -> usize {
+        match self {
+            Self::Hashed => 32,
+            Self::Plain => 20,
+            Self::Prefixed => PREFIX_LEN + 20,
+        }
+    }
+
+    /// Key of `address`, zero-padded to 32 bytes.
+    pub fn key(&self, address: Address) -> B256 {
+        match self {
+            Self::Hashed => keccak256(address.as_slice()),
+            Self::Plain => Self::pad(&[address.as_slice()]),
+            Self::Prefixed => Self::pad(&[&keccak256(address.as_slice())[..PREFIX_LEN], address.as_slice()]),
+        }
+    }
+
+    /// Key of `address` without padding, for byte-oriented backends.
+    pub fn key_bytes(&self, address: Address) -> Vec<u8> {
+        self.key(address)[..self.key_len()].to_vec()
+    }
+
+    /// Address a key was derived from, or `None` for hashed keys.
+    ///
+    /// Accepts both padded and unpadded keys.
+    pub fn address(&self, key: &[u8]) -> Option<Address> {
+        let start = match self {
+            Self::Hashed => return None,
+            Self::Plain => 0,
+            Self::Prefixed => PREFIX_LEN,
+        };
+        key.get(start..start + 20).map(Address::from_slice)
+    }
+
+    /// Hashed address a key commits to in the state root.
+    pub fn hashed_address(&self, key: &[u8]) -> B256 {
+        match self.address(key) {
+            Some(address) => keccak256(address.as_slice()),
+            None => B256::from_slice(&key[..32]),
+        }
+    }
+
+    /// Key of a background account synthesized as `hashed_key`.
+    ///
+    /// Background accounts have no address, so non-hashed schemes use the
+    /// first 20 bytes of the hashed key as one, and [`Prefixed`](Self::Prefixed)
+    /// takes its bucket from the same bytes rather than their hash. Both keep
+    /// keys ascending with `hashed_key`, so bulk loads can still append.
+    pub fn background_key(&self, hashed_key: B256) -> B256 {
+        match self {
+            Self::Hashed => hashed_key,
+            Self::Plain => Self::pad(&[&hashed_key[..20]]),
+            Self::Prefixed => Self::pad(&[&hashed_key[..PREFIX_LEN], &hashed_key[..20]]),
+        }
+    }
+
+    /// Concatenates `parts` and zero-pads them to 32 bytes.
+    fn pad(parts: &[&[u8]]) -> B256 {
+        let mut key = B256::ZERO;
+        let mut offset = 0;
+        for part in parts {
+            key[offset..offset + part.len()].copy_from_slice(part);
+            offset += part.len();
+        }
+        key
+    }
+}
+
+impl fmt::Display for KeyScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for KeyScheme {
+    type Err = OptionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|scheme| scheme.name() == s).ok_or_else(|| {
+            OptionError(format!("unknown key scheme '{}' (expected hashed, plain or prefixed)", s))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    #[test]
+    fn test_keys_roundtrip_to_hashed_address() {
+        let address = Address::repeat_byte(0xab);
+        for scheme in KeyScheme::ALL {
+            let key = scheme.key(address);
+            assert_eq!(scheme.hashed_address(key.as_slice()), keccak256(address.as_slice()));
+            assert_eq!(scheme.hashed_address(&scheme.key_bytes(address)), keccak256(address.as_slice()));
+            assert!(key[scheme.key_len()..].iter().all(|&b| b == 0));
+        }
+        assert_eq!(KeyScheme::Plain.address(KeyScheme::Plain.key(address).as_slice()), Some(address));
+        assert_eq!(KeyScheme::Prefixed.address(&KeyScheme::Prefixed.key_bytes(address)), Some(address));
+        assert_eq!(KeyScheme::Hashed.address(KeyScheme::Hashed.key(address).as_slice()), None);
+    }
+
+    #[test]
+    fn test_plain_keys_sort_by_address() {
+        let low = Address::with_last_byte(1);
+        let high = Address::with_last_byte(2);
+        assert!(KeyScheme::Plain.key(low) < KeyScheme::Plain.key(high));
+    }
+
+    #[test]
+    fn test_background_keys_stay_ascending() {
+        let keys: Vec<B256> = (1u64..100).map(|i| B256::from(U256::MAX / U256::from(100) * U256::from(i))).collect();
+        for scheme in KeyScheme::ALL {
+            let mapped: Vec<B256> = keys.iter().map(|&key| scheme.background_key(key)).collect();
+            assert!(mapped.windows(2).all(|pair| pair[0] < pair[1]), "{} keys out of order", scheme);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for scheme in KeyScheme::ALL {
+            assert_eq!(scheme.name().parse::<KeyScheme>(), Ok(scheme));
+        }
+        assert!("sorted".parse::<KeyScheme>().is_err());
+    }
+}
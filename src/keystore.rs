@@ -0,0 +1,311 @@
+//! Ethereum keystore (Web3 Secret Storage v3) import and export.
+//!
+//! Lets benchmarks use externally provisioned keys, such as devnet accounts,
+//! instead of seed-derived ones. Both `scrypt` and `pbkdf2` key derivation are
+//! read; exports use `scrypt` with geth's "light" parameters so that writing
+//! thousands of keystores stays fast.
+//!
+//! ```
+//! use db_test::Account;
+//!
+//! let account = Account::from_seed(1);
+//! let json = account.to_keystore("hunter2");
+//! let imported = Account::from_keystore(&json, "hunter2").unwrap();
+//! assert_eq!(imported.address, account.address);
+//! ```
+
+use crate::Account;
+use aes::Aes128;
+use alloy_primitives::{hex, keccak256};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use k256::ecdsa::SigningKey;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// scrypt cost used for exported keystores (geth's light `N = 2^12`).
+const EXPORT_SCRYPT_LOG_N: u8 = 12;
+const EXPORT_SCRYPT_R: u32 = 8;
+const EXPORT_SCRYPT_P: u32 = 1;
+
+/// Error produced when reading or writing a keystore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeystoreError(pub String);
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "keystore error: {}", self.0)
+    }
+}
+
+impl std::error::Error for KeystoreError {}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    id: String,
+    address: String,
+    #[serde(alias = "Crypto")]
+    crypto: CryptoSection,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CryptoSection {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: KdfParams,
+    mac: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfParams {
+    dklen: usize,
+    salt: String,
+    // scrypt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    r: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    p: Option<u32>,
+    // pbkdf2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    c: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prf: Option<String>,
+}
+
+impl Account {
+    /// Decrypts an account from keystore JSON.
+    pub fn from_keystore(json: &str, password: &str) -> Result<Self, KeystoreError> {
+        let file: KeystoreFile =
+            serde_json::from_str(json).map_err(|err| KeystoreError(format!("invalid JSON: {}", err)))?;
+        let crypto = &file.crypto;
+        if crypto.cipher != "aes-128-ctr" {
+            return Err(KeystoreError(format!("unsupported cipher '{}'", crypto.cipher)));
+        }
+
+        let salt = decode_hex("salt", &crypto.kdfparams.salt)?;
+        let iv = decode_hex("iv", &crypto.cipherparams.iv)?;
+        let mut key_bytes = decode_hex("ciphertext", &crypto.ciphertext)?;
+        let mac = decode_hex("mac", &crypto.mac)?;
+
+        let derived = derive_key(&crypto.kdf, &crypto.kdfparams, password.as_bytes(), &salt)?;
+        if compute_mac(&derived, &key_bytes).as_slice() != mac.as_slice() {
+            return Err(KeystoreError("MAC mismatch (wrong password?)".to_string()));
+        }
+
+        apply_cipher(&derived, &iv, &mut key_bytes)?;
+        let signing_key = SigningKey::from_slice(&key_bytes)
+            .map_err(|err| KeystoreError(format!("invalid private key: {}", err)))?;
+        Ok(Self::from_signing_key(signing_key))
+    }
+
+    /// Reads and decrypts an account from a keystore file.
+    pub fn from_keystore_file(path: impl AsRef<Path>, password: &str) -> Result<Self, KeystoreError> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .map_err(|err| KeystoreError(format!("{}: {}", path.display(), err)))?;
+        Self::from_keystore(&json, password)
+            .map_err(|err| KeystoreError(format!("{}: {}", path.display(), err.0)))
+    }
+
+    /// Encrypts this account as keystore JSON.
+    pub fn to_keystore(&self, password: &str) -> String {
+        let mut rng = rand::thread_rng();
+        let salt: [u8; 32] = rng.gen();
+        let iv: [u8; 16] = rng.gen();
+        let id: [u8; 16] = rng.gen();
+
+        let kdfparams = KdfParams {
+            dklen: 32,
+            salt: hex::encode(salt),
+            n: Some(1 << EXPORT_SCRYPT_LOG_N),
+            r: Some(EXPORT_SCRYPT_R),
+            p: Some(EXPORT_SCRYPT_P),
+            c: None,
+            prf: None,
+        };
+        let derived = derive_key("scrypt", &kdfparams, password.as_bytes(), &salt)
+            .expect("export scrypt parameters are valid");
+
+        let mut ciphertext = self.signing_key.to_bytes().to_vec();
+        apply_cipher(&derived, &iv, &mut ciphertext).expect("iv has the cipher's length");
+        let mac = compute_mac(&derived, &ciphertext);
+
+        let file = KeystoreFile {
+            version: 3,
+            id: format_uuid(id),
+            address: hex::encode(self.address),
+            crypto: CryptoSection {
+                cipher: "aes-128-ctr".to_string(),
+                cipherparams: CipherParams { iv: hex::encode(iv) },
+                ciphertext: hex::encode(ciphertext),
+                kdf: "scrypt".to_string(),
+                kdfparams,
+                mac: hex::encode(mac),
+            },
+        };
+        serde_json::to_string(&file).expect("keystore serializes")
+    }
+}
+
+/// Loads every `*.json` keystore in `dir`, sorted by file name so the account
+/// order (and therefore the generated workload) is reproducible.
+pub fn load_dir(dir: impl AsRef<Path>, password: &str) -> Result<Vec<Account>, KeystoreError> {
+    let dir = dir.as_ref();
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map_err(|err| KeystoreError(format!("{}: {}", dir.display(), err)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| Account::from_keystore_file(path, password))
+        .collect()
+}
+
+/// Writes one keystore per account to `dir`, named by index and address.
+pub fn export_dir(accounts: &[Account], dir: impl AsRef<Path>, password: &str) -> Result<(), KeystoreError> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir).map_err(|err| KeystoreError(format!("{}: {}", dir.display(), err)))?;
+
+    for (index, account) in accounts.iter().enumerate() {
+        let path = dir.join(format!("{:06}-{}.json", index, hex::encode(account.address)));
+        fs::write(&path, account.to_keystore(password))
+            .map_err(|err| KeystoreError(format!("{}: {}", path.display(), err)))?;
+    }
+    Ok(())
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, KeystoreError> {
+    hex::decode(value).map_err(|err| KeystoreError(format!("invalid {}: {}", field, err)))
+}
+
+fn derive_key(kdf: &str, params: &KdfParams, password: &[u8], salt: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+    if params.dklen < 32 {
+        return Err(KeystoreError(format!("dklen {} is too short", params.dklen)));
+    }
+    let mut derived = vec![0u8; params.dklen];
+
+    match kdf {
+        "scrypt" => {
+            let (Some(n), Some(r), Some(p)) = (params.n, params.r, params.p) else {
+                return Err(KeystoreError("scrypt requires n, r and p".to_string()));
+            };
+            if !n.is_power_of_two() {
+                return Err(KeystoreError(format!("scrypt n {} is not a power of two", n)));
+            }
+            let scrypt_params = scrypt::Params::new(n.trailing_zeros() as u8, r, p, params.dklen)
+                .map_err(|err| KeystoreError(format!("invalid scrypt parameters: {}", err)))?;
+            scrypt::scrypt(password, salt, &scrypt_params, &mut derived)
+                .map_err(|err| KeystoreError(format!("scrypt failed: {}", err)))?;
+        }
+        "pbkdf2" => {
+            match params.prf.as_deref() {
+                Some("hmac-sha256") => {}
+                prf => return Err(KeystoreError(format!("unsupported pbkdf2 prf {:?}", prf))),
+            }
+            let rounds = params
+                .c
+                .ok_or_else(|| KeystoreError("pbkdf2 requires c".to_string()))?;
+            pbkdf2::pbkdf2_hmac::<sha2::Sha256>(password, salt, rounds, &mut derived);
+        }
+        other => return Err(KeystoreError(format!("unsupported kdf '{}'", other))),
+    }
+    Ok(derived)
+}
+
+/// `keccak256(derived[16..32] ++ ciphertext)`.
+fn compute_mac(derived: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(16 + ciphertext.len());
+    data.extend_from_slice(&derived[16..32]);
+    data.extend_from_slice(ciphertext);
+    keccak256(&data).0
+}
+
+/// AES-128-CTR keyed with `derived[..16]`; encrypts and decrypts in place.
+fn apply_cipher(derived: &[u8], iv: &[u8], data: &mut [u8]) -> Result<(), KeystoreError> {
+    let mut cipher = ctr::Ctr128BE::<Aes128>::new_from_slices(&derived[..16], iv)
+        .map_err(|_| KeystoreError(format!("iv must be 16 bytes, got {}", iv.len())))?;
+    cipher.apply_keystream(data);
+    Ok(())
+}
+
+/// Formats 16 random bytes as a version 4 UUID.
+fn format_uuid(mut bytes: [u8; 16]) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let account = Account::from_seed(7);
+        let json = account.to_keystore("correct horse");
+
+        let imported = Account::from_keystore(&json, "correct horse").unwrap();
+        assert_eq!(imported.address, account.address);
+        assert_eq!(imported.signing_key.to_bytes(), account.signing_key.to_bytes());
+
+        assert!(Account::from_keystore(&json, "battery staple").is_err());
+        assert!(Account::from_keystore("{}", "correct horse").is_err());
+    }
+
+    #[test]
+    fn test_pbkdf2_keystore() {
+        // Test vector from the Web3 Secret Storage definition
+        let json = r#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3,
+            "address": "008aeeda4d805471df9b2a5b0f38a0c3bcba786b"
+        }"#;
+
+        let account = Account::from_keystore(json, "testpassword").unwrap();
+        assert_eq!(
+            hex::encode(account.signing_key.to_bytes()),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+    }
+
+    #[test]
+    fn test_directory_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let accounts: Vec<_> = (0..3).map(Account::from_seed).collect();
+        export_dir(&accounts, dir.path(), "pw").unwrap();
+
+        let loaded = load_dir(dir.path(), "pw").unwrap();
+        let addresses: Vec<_> = loaded.iter().map(|account| account.address).collect();
+        let expected: Vec<_> = accounts.iter().map(|account| account.address).collect();
+        assert_eq!(addresses, expected);
+    }
+}
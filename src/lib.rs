@@ -25,6 +25,7 @@
 //!     seed: 42,
 //!     chain_id: 1,
 //!     transactions_per_block: 10,
+//!     ..Default::default()
 //! };
 //!
 //! // Generate workload (signs all transactions upfront)
@@ -38,24 +39,88 @@
 //! println!("Successful: {}", result.successful);
 //! ```
 
+pub mod analysis;
+pub mod background;
+pub mod codec;
+pub mod compute;
+pub mod diff;
+pub mod environment;
+pub mod equivalence;
+pub mod footprint;
 pub mod executor;
+pub mod frequency;
+pub mod genesis;
+pub mod harness;
+pub mod key_scheme;
+pub mod live;
+pub mod manifest;
+pub mod markdown;
+pub mod multi_chain;
+pub mod mutation;
+pub mod options;
+pub mod recovery;
+pub mod registry;
+pub mod rlp_blocks;
+pub mod sharding;
+pub mod significance;
+pub mod soak;
+pub mod state_root;
+pub mod storage;
+pub mod validation;
+pub mod workload_file;
 
-pub use executor::{ExecutionResult, Executor, OrderingMode, SequentialExecutor};
+#[cfg(feature = "charts")]
+pub mod charts;
+
+#[cfg(feature = "keystore")]
+pub mod keystore;
+
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic;
+
+#[cfg(feature = "oracle")]
+pub mod oracle;
+
+#[cfg(feature = "reth-compare")]
+pub mod reth_compare;
+
+#[cfg(feature = "results-db")]
+pub mod results_db;
+
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+#[cfg(feature = "tui")]
+pub mod tui;
+
+#[cfg(feature = "serde")]
+pub mod schema;
+
+pub use executor::{
+    ExecutionResult, Executor, OrderingMode, SequentialExecutor, TransactionIndex, TxLocation,
+    TxStatus,
+};
 #[cfg(feature = "mdbx")]
 pub use executor::{MdbxBatchedExecutor, MdbxDatabase, MdbxSequentialExecutor};
 #[cfg(feature = "fdb")]
 pub use executor::FdbParallelExecutor;
 #[cfg(feature = "block-stm")]
 pub use executor::BlockStmExecutor;
+#[cfg(feature = "verkle")]
+pub use executor::VerkleExecutor;
+#[cfg(feature = "remote")]
+pub use executor::RemoteDbExecutor;
+
+pub use compute::ComputeCost;
+pub use genesis::GenesisState;
 
-use alloy_primitives::{keccak256, Address, Signature, B256, U256};
+use alloy_consensus::{SignableTransaction, Signed, TxEip1559};
+use alloy_primitives::{keccak256, Address, Bytes, Signature, TxKind, B256, U256};
 use k256::ecdsa::{SigningKey, VerifyingKey};
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use revm::{
-    database::{CacheDB, EmptyDB},
-    state::AccountInfo,
-};
-use std::collections::HashMap;
+use revm::database::CacheDB;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
 
 // ============================================================================
 // Account & Key Management
@@ -87,6 +152,22 @@ impl Account {
             .expect("valid key bytes");
         Self::from_signing_key(signing_key)
     }
+
+    /// Generates a deterministic account from a seed without deriving its public key.
+    ///
+    /// The address is a hash of the key bytes rather than of the public key, so
+    /// the account can only sign with [`SignedTransaction::new_fake`]. Used by
+    /// workloads with [`WorkloadConfig::fake_signatures`], where public key
+    /// derivation would otherwise dominate setup time.
+    pub fn from_seed_fake(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut key_bytes = [0u8; 32];
+        rng.fill(&mut key_bytes);
+        let signing_key = SigningKey::from_bytes(&key_bytes.into())
+            .expect("valid key bytes");
+        let address = Address::from_slice(&keccak256(key_bytes)[12..]);
+        Self { signing_key, address }
+    }
 }
 
 impl std::fmt::Debug for Account {
@@ -109,21 +190,31 @@ fn public_key_to_address(verifying_key: &VerifyingKey) -> Address {
 // Signed Transaction
 // ============================================================================
 
-/// A signed ETH transfer transaction ready for execution.
+/// Identifies one of a workload's tokens, held next to each account's native
+/// balance; see [`WorkloadConfig::tokens`].
+pub type TokenId = u32;
+
+/// A signed ETH or token transfer transaction ready for execution.
 #[derive(Debug, Clone)]
 pub struct SignedTransaction {
     /// The sender's address (derived from signature during verification).
     pub from: Address,
     /// The recipient's address.
     pub to: Address,
-    /// The value to transfer in wei.
+    /// The value to transfer in wei, or in the token's smallest unit.
     pub value: U256,
-    /// The transaction nonce.
+    /// The token transferred; `None` transfers the native balance.
+    pub token: Option<TokenId>,
+    /// The transaction nonce, or the transaction id in
+    /// [`nonceless`](WorkloadConfig::nonceless) workloads.
     pub nonce: u64,
     /// The ECDSA signature.
     pub signature: Signature,
     /// The hash of the transaction data that was signed.
     pub tx_hash: B256,
+    /// Synthetic work: keccak iterations executors run before applying the
+    /// transfer; see [`compute`]. Not signed.
+    pub compute_cost: u64,
 }
 
 impl SignedTransaction {
@@ -136,27 +227,101 @@ impl SignedTransaction {
         nonce: u64,
         chain_id: u64,
     ) -> Self {
-        let tx_hash = Self::compute_tx_hash(account.address, to, value, nonce, chain_id);
+        Self::new_transfer(account, to, value, None, nonce, chain_id)
+    }
+
+    /// Creates a signed transfer of the native balance or of `token`.
+    ///
+    /// The token id is signed along with the other fields, so a token
+    /// transfer hashes differently from a native one of the same value.
+    pub fn new_transfer(
+        account: &Account,
+        to: Address,
+        value: U256,
+        token: Option<TokenId>,
+        nonce: u64,
+        chain_id: u64,
+    ) -> Self {
+        let tx_hash = Self::compute_tx_hash(account.address, to, value, token, nonce, chain_id);
         let signature = Self::sign(&account.signing_key, tx_hash);
         
         Self {
             from: account.address,
             to,
             value,
+            token,
             nonce,
             signature,
             tx_hash,
+            compute_cost: 0,
+        }
+    }
+
+    /// Creates a transaction with a deterministic placeholder signature.
+    ///
+    /// The signature is derived from the transaction hash and does not recover
+    /// to `from`, so executors must run with signature verification disabled
+    /// and trust `tx.from`.
+    pub fn new_fake(
+        account: &Account,
+        to: Address,
+        value: U256,
+        nonce: u64,
+        chain_id: u64,
+    ) -> Self {
+        Self::new_fake_transfer(account, to, value, None, nonce, chain_id)
+    }
+
+    /// Like [`new_transfer`](Self::new_transfer), with a placeholder signature
+    /// as [`new_fake`](Self::new_fake) makes.
+    pub fn new_fake_transfer(
+        account: &Account,
+        to: Address,
+        value: U256,
+        token: Option<TokenId>,
+        nonce: u64,
+        chain_id: u64,
+    ) -> Self {
+        let tx_hash = Self::compute_tx_hash(account.address, to, value, token, nonce, chain_id);
+        let signature = Signature::new(
+            U256::from_be_bytes(tx_hash.0),
+            U256::from_be_bytes(keccak256(tx_hash).0),
+            false,
+        );
+
+        Self {
+            from: account.address,
+            to,
+            value,
+            token,
+            nonce,
+            signature,
+            tx_hash,
+            compute_cost: 0,
         }
     }
 
     /// Computes the transaction hash for signing.
-    fn compute_tx_hash(from: Address, to: Address, value: U256, nonce: u64, chain_id: u64) -> B256 {
-        let mut data = Vec::with_capacity(20 + 20 + 32 + 8 + 8);
+    ///
+    /// Token transfers append the token id, so native transfers hash as they
+    /// did before tokens existed.
+    pub(crate) fn compute_tx_hash(
+        from: Address,
+        to: Address,
+        value: U256,
+        token: Option<TokenId>,
+        nonce: u64,
+        chain_id: u64,
+    ) -> B256 {
+        let mut data = Vec::with_capacity(20 + 20 + 32 + 8 + 8 + 4);
         data.extend_from_slice(from.as_slice());
         data.extend_from_slice(to.as_slice());
         data.extend_from_slice(&value.to_be_bytes::<32>());
         data.extend_from_slice(&nonce.to_be_bytes());
         data.extend_from_slice(&chain_id.to_be_bytes());
+        if let Some(token) = token {
+            data.extend_from_slice(&token.to_be_bytes());
+        }
         keccak256(&data)
     }
 
@@ -183,6 +348,40 @@ impl SignedTransaction {
             .map(|addr| addr == self.from)
             .unwrap_or(false)
     }
+
+    /// Runs this transaction's [`compute_cost`](Self::compute_cost) work.
+    pub fn burn_compute(&self) -> B256 {
+        compute::burn(self.tx_hash, self.compute_cost)
+    }
+
+    /// Re-signs this transfer as a real EIP-1559 transaction with `signing_key`,
+    /// which must belong to `from`.
+    ///
+    /// The result is what a production client would accept for the same
+    /// transfer; gas limit is the 21000 the executors charge. Token transfers
+    /// have no equivalent here and are re-signed as native transfers.
+    pub fn to_eip1559(
+        &self,
+        signing_key: &SigningKey,
+        nonce: u64,
+        chain_id: u64,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    ) -> Signed<TxEip1559> {
+        let unsigned = TxEip1559 {
+            chain_id,
+            nonce,
+            gas_limit: 21_000,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to: TxKind::Call(self.to),
+            value: self.value,
+            access_list: Default::default(),
+            input: Bytes::new(),
+        };
+        let signature = Self::sign(signing_key, unsigned.signature_hash());
+        unsigned.into_signed(signature)
+    }
 }
 
 // ============================================================================
@@ -190,7 +389,9 @@ impl SignedTransaction {
 // ============================================================================
 
 /// Configuration for workload generation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct WorkloadConfig {
     /// Total number of accounts in the system (for pre-funding).
     pub num_accounts: usize,
@@ -200,6 +401,9 @@ pub struct WorkloadConfig {
     /// - 2 = All transactions touch same 2 accounts (no parallelism possible)
     /// - 10 = Transactions pick from 10 accounts (limited parallelism)
     /// - num_accounts = Full account pool (maximum parallelism)
+    ///
+    /// With a [`hot_probability`](Self::hot_probability) below 1, only that
+    /// share of transactions stays within the hot set.
     pub hot_accounts: usize,
     /// Random seed for reproducibility.
     pub seed: u64,
@@ -207,6 +411,56 @@ pub struct WorkloadConfig {
     pub chain_id: u64,
     /// Number of transactions per block. Transactions will be divided into blocks of this size.
     pub transactions_per_block: usize,
+    /// Number of exact duplicate transactions (same hash) to inject after generation.
+    /// Each duplicate is placed somewhere after its original and must be rejected.
+    pub duplicate_transactions: usize,
+    /// Number of same-nonce double-spends to inject after generation.
+    /// Each double-spend reuses an earlier transaction's sender and nonce (its id,
+    /// in nonce-less workloads) with a different recipient, and must be rejected.
+    pub double_spends: usize,
+    /// Use deterministic placeholder signatures instead of ECDSA.
+    ///
+    /// Skips key derivation and signing, which dominate setup time for large
+    /// workloads. Signatures do not verify, so executors must run with
+    /// signature verification disabled.
+    pub fake_signatures: bool,
+    /// Number of tokens every account holds next to its native balance.
+    ///
+    /// Each transaction transfers one asset picked uniformly from the native
+    /// balance and the tokens, so wider account records and conflicts on
+    /// different assets of the same account can be studied.
+    /// - 0 = native transfers only
+    /// - K = token ids 0..K, each account starting with 1000 units of each
+    pub tokens: usize,
+    /// Identify transactions by unique random ids instead of per-sender nonces.
+    ///
+    /// The id is carried in [`SignedTransaction::nonce`]. Executors then reject
+    /// a transaction only if its id was already seen, so a sender's transfers
+    /// no longer have to apply in order and only the accounts they touch
+    /// conflict. Comparing against the same workload with nonces shows how
+    /// much nonce serialization alone limits parallelism.
+    pub nonceless: bool,
+    /// Synthetic execution work drawn for each transaction; see [`compute`].
+    ///
+    /// Costs come from their own random stream, so a workload with costs has
+    /// the same transactions as the one without.
+    pub compute_cost: ComputeCost,
+    /// Fund accounts with `U256::MAX` minus this many wei, of the native
+    /// asset and of each token, instead of 1000 ETH.
+    ///
+    /// An account that nets more than the headroom in incoming transfers
+    /// would overflow, so executors must fail those transfers permanently
+    /// instead of wrapping.
+    pub near_max_balance: Option<u64>,
+    /// Probability that a transaction transfers between two
+    /// [`hot_accounts`](Self::hot_accounts); otherwise both ends are drawn
+    /// from the remaining, cold accounts.
+    ///
+    /// The default of 1 keeps every transaction in the hot set, so
+    /// `hot_accounts` alone sets the conflict level. Lower values decouple
+    /// the two: 2 hot accounts at 0.3 gives 2 accounts 30% of the traffic.
+    /// With fewer than two cold accounts every transaction stays hot.
+    pub hot_probability: f64,
 }
 
 impl Default for WorkloadConfig {
@@ -218,10 +472,21 @@ impl Default for WorkloadConfig {
             seed: 42,
             chain_id: 1,
             transactions_per_block: 625, // Mid-range of 2k-20k (scaled down for benchmarking)
+            duplicate_transactions: 0,
+            double_spends: 0,
+            fake_signatures: false,
+            tokens: 0,
+            nonceless: false,
+            compute_cost: ComputeCost::default(),
+            near_max_balance: None,
+            hot_probability: 1.0,
         }
     }
 }
 
+/// Mixed into the workload seed to seed the compute cost stream.
+const COMPUTE_COST_SEED: u64 = 0xc0_57;
+
 /// A complete benchmark workload with pre-generated accounts and signed transactions.
 #[derive(Debug, Clone)]
 pub struct Workload {
@@ -234,49 +499,147 @@ pub struct Workload {
     pub blocks: Vec<Vec<SignedTransaction>>,
     /// The configuration used to generate this workload.
     pub config: WorkloadConfig,
+    /// Funded accounts, built by the first [`Workload::create_db`] call; later
+    /// changes to `accounts` are not reflected.
+    genesis: OnceLock<GenesisState>,
+    /// Signers recovered by [`Workload::preverify`], by position; cleared by
+    /// [`Workload::mutate`].
+    signers: OnceLock<Vec<Option<Address>>>,
 }
 
 impl Workload {
     /// Generates a new workload from the given configuration.
     /// All transactions are pre-signed during generation.
     pub fn generate(config: WorkloadConfig) -> Self {
-        let mut rng = StdRng::seed_from_u64(config.seed);
-        
         // Generate accounts with deterministic keys.
         let accounts: Vec<Account> = (0..config.num_accounts)
-            .map(|i| Account::from_seed(config.seed.wrapping_add(i as u64)))
+            .map(|i| {
+                let seed = config.seed.wrapping_add(i as u64);
+                if config.fake_signatures {
+                    Account::from_seed_fake(seed)
+                } else {
+                    Account::from_seed(seed)
+                }
+            })
             .collect();
 
-        // Track nonces per account for proper transaction sequencing.
+        Self::generate_with_accounts(config, accounts)
+    }
+
+    /// Generates a workload whose accounts are `accounts` instead of seed-derived ones,
+    /// e.g. keys loaded from keystores.
+    ///
+    /// `config.num_accounts` is replaced by the number of accounts given, which
+    /// must be at least two.
+    pub fn generate_with_accounts(config: WorkloadConfig, accounts: Vec<Account>) -> Self {
+        Self::generate_from(config, accounts, HashMap::new())
+    }
+
+    /// Generates the workload that follows this one on the same accounts,
+    /// for running workloads back to back against one persistent state.
+    ///
+    /// The next round has the same configuration with the seed advanced by
+    /// one, and each sender's nonces continue after the last one it used
+    /// here, so its transactions are valid on the state this workload leaves
+    /// behind. Balances are not tracked, so rounds of 1 wei transfers stay
+    /// valid however many run.
+    pub fn next_round(&self) -> Self {
+        let positions: HashMap<Address, usize> =
+            self.accounts.iter().enumerate().map(|(index, account)| (account.address, index)).collect();
         let mut nonces: HashMap<usize, u64> = HashMap::new();
+        if !self.config.nonceless {
+            for (tx, replay) in self.transactions.iter().zip(self.replay_mask()) {
+                if let (false, Some(&index)) = (replay, positions.get(&tx.from)) {
+                    let next = nonces.entry(index).or_insert(0);
+                    *next = (*next).max(tx.nonce + 1);
+                }
+            }
+        }
+
+        let config = WorkloadConfig {
+            seed: self.config.seed.wrapping_add(1),
+            ..self.config.clone()
+        };
+        Self::generate_from(config, self.accounts.clone(), nonces)
+    }
+
+    /// Generates a workload on `accounts` whose senders start at `nonces`,
+    /// by account position; senders not in `nonces` start at zero.
+    fn generate_from(mut config: WorkloadConfig, accounts: Vec<Account>, mut nonces: HashMap<usize, u64>) -> Self {
+        config.num_accounts = accounts.len();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+
+        // Track nonces per account for proper transaction sequencing, or the
+        // ids already drawn for nonce-less workloads.
+        let mut ids: HashSet<u64> = HashSet::new();
 
         // Clamp hot_accounts to valid range [2, num_accounts]
         let hot_account_count = config.hot_accounts.clamp(2, config.num_accounts);
+        // Cold transactions need two distinct cold accounts
+        let cold_traffic = config.hot_probability < 1.0 && config.num_accounts - hot_account_count >= 2;
 
         // Generate and sign transactions.
-        // All transactions pick from the first `hot_account_count` accounts.
+        // Hot transactions pick from the first `hot_account_count` accounts,
+        // cold ones from the rest.
         let transactions: Vec<SignedTransaction> = (0..config.num_transactions)
             .map(|_| {
-                // Pick random sender and receiver from hot accounts
-                let from_idx = rng.gen_range(0..hot_account_count);
-                let mut to_idx = rng.gen_range(0..hot_account_count);
+                // Only workloads with cold traffic draw the coin, so others
+                // keep their random stream
+                let accounts_range = if cold_traffic && !rng.gen_bool(config.hot_probability.max(0.0)) {
+                    hot_account_count..config.num_accounts
+                } else {
+                    0..hot_account_count
+                };
+
+                // Pick random sender and receiver from the chosen accounts
+                let from_idx = rng.gen_range(accounts_range.clone());
+                let mut to_idx = rng.gen_range(accounts_range.clone());
                 while to_idx == from_idx {
-                    to_idx = rng.gen_range(0..hot_account_count);
+                    to_idx = rng.gen_range(accounts_range.clone());
                 }
 
-                let nonce = nonces.entry(from_idx).or_insert(0);
-                let tx = SignedTransaction::new(
+                // Asset 0 is the native balance; only token workloads draw
+                // one, so native-only workloads keep their random stream
+                let token = match config.tokens {
+                    0 => None,
+                    tokens => (rng.gen_range(0..=tokens) as TokenId).checked_sub(1),
+                };
+
+                let nonce = if config.nonceless {
+                    loop {
+                        let id = rng.gen::<u64>();
+                        if ids.insert(id) {
+                            break id;
+                        }
+                    }
+                } else {
+                    let next = nonces.entry(from_idx).or_insert(0);
+                    let nonce = *next;
+                    *next += 1;
+                    nonce
+                };
+                Self::sign(
+                    &config,
                     &accounts[from_idx],
                     accounts[to_idx].address,
                     U256::from(1), // 1 wei - balance never an issue, focus on nonce ordering
-                    *nonce,
-                    config.chain_id,
-                );
-                *nonce += 1;
-                tx
+                    token,
+                    nonce,
+                )
             })
             .collect();
 
+        // Draw costs from a separate stream so they leave the transactions as they are
+        let mut transactions = transactions;
+        let mut cost_rng = StdRng::seed_from_u64(config.seed ^ COMPUTE_COST_SEED);
+        for tx in &mut transactions {
+            tx.compute_cost = config.compute_cost.sample(&mut cost_rng);
+        }
+
+        // Inject replays after the honest transactions so that workloads without
+        // replays consume exactly the same random stream as before.
+        Self::inject_replays(&mut rng, &mut transactions, &accounts, &config);
+
         // Divide transactions into blocks.
         let blocks: Vec<Vec<SignedTransaction>> = transactions
             .chunks(config.transactions_per_block)
@@ -288,25 +651,129 @@ impl Workload {
             transactions,
             blocks,
             config,
+            genesis: OnceLock::new(),
+            signers: OnceLock::new(),
         }
     }
 
-    /// Creates a CacheDB pre-funded with all accounts in this workload.
-    pub fn create_db(&self) -> CacheDB<EmptyDB> {
-        let mut db = CacheDB::new(EmptyDB::default());
-        let initial_balance = U256::from(1_000_000_000_000_000_000_000u128); // 1000 ETH
-
-        for account in &self.accounts {
-            let info = AccountInfo {
-                balance: initial_balance,
-                nonce: 0,
-                code_hash: revm::primitives::KECCAK_EMPTY,
-                code: None,
+    /// Signs a transfer, with a placeholder signature if the config asks for one.
+    fn sign(
+        config: &WorkloadConfig,
+        account: &Account,
+        to: Address,
+        value: U256,
+        token: Option<TokenId>,
+        nonce: u64,
+    ) -> SignedTransaction {
+        if config.fake_signatures {
+            SignedTransaction::new_fake_transfer(account, to, value, token, nonce, config.chain_id)
+        } else {
+            SignedTransaction::new_transfer(account, to, value, token, nonce, config.chain_id)
+        }
+    }
+
+    /// Inserts duplicate transactions and same-nonce double-spends into `transactions`.
+    ///
+    /// Every injected transaction is placed strictly after the transaction it replays,
+    /// so the replay is always the second occurrence in block order.
+    fn inject_replays(
+        rng: &mut StdRng,
+        transactions: &mut Vec<SignedTransaction>,
+        accounts: &[Account],
+        config: &WorkloadConfig,
+    ) {
+        if transactions.is_empty() {
+            return;
+        }
+
+        let signers: HashMap<Address, &Account> =
+            accounts.iter().map(|acc| (acc.address, acc)).collect();
+
+        for i in 0..config.duplicate_transactions + config.double_spends {
+            let source_idx = rng.gen_range(0..transactions.len());
+            let source = &transactions[source_idx];
+
+            let replay = if i < config.duplicate_transactions {
+                source.clone()
+            } else {
+                // Same sender and nonce, but a different recipient so the hash differs.
+                let mut to = accounts[rng.gen_range(0..accounts.len())].address;
+                if to == source.to || to == source.from {
+                    to = Address::with_last_byte(0xde);
+                }
+                SignedTransaction {
+                    compute_cost: source.compute_cost,
+                    ..Self::sign(config, signers[&source.from], to, source.value, source.token, source.nonce)
+                }
             };
-            db.insert_account_info(account.address, info);
+
+            let insert_idx = rng.gen_range(source_idx + 1..=transactions.len());
+            transactions.insert(insert_idx, replay);
         }
+    }
+
+    /// Returns, for each transaction in block order, whether it replays an earlier one.
+    ///
+    /// A transaction is a replay if an earlier transaction has the same hash, or the
+    /// same sender and nonce. In [`nonceless`](WorkloadConfig::nonceless) workloads
+    /// the id alone decides, whoever the sender. Only the first occurrence is
+    /// considered valid, which gives executors that do not process transactions
+    /// in strict order a deterministic way to reject replays.
+    pub fn replay_mask(&self) -> Vec<bool> {
+        let mut seen_hashes = HashSet::new();
+        let mut seen_nonces = HashSet::new();
+        let mut seen_ids = HashSet::new();
+
+        self.transactions
+            .iter()
+            .map(|tx| {
+                let new_hash = seen_hashes.insert(tx.tx_hash);
+                let new_nonce = if self.config.nonceless {
+                    seen_ids.insert(tx.nonce)
+                } else {
+                    seen_nonces.insert((tx.from, tx.nonce))
+                };
+                !(new_hash && new_nonce)
+            })
+            .collect()
+    }
+
+    /// Returns the number of replayed transactions in this workload.
+    pub fn num_replays(&self) -> usize {
+        self.replay_mask().into_iter().filter(|&replay| replay).count()
+    }
+
+    /// Creates a CacheDB pre-funded with all accounts in this workload.
+    ///
+    /// The accounts live in a [`GenesisState`] shared by every database of
+    /// this workload, so only the first call pays for building it.
+    ///
+    /// revm accounts have no token balances, so token workloads need an
+    /// executor that holds them itself.
+    pub fn create_db(&self) -> CacheDB<GenesisState> {
+        CacheDB::new(self.genesis().clone())
+    }
 
-        db
+    /// Returns the accounts every run starts from, building them on first use.
+    pub fn genesis(&self) -> &GenesisState {
+        self.genesis.get_or_init(|| {
+            GenesisState::funded(self.accounts.iter().map(|account| account.address), self.initial_balance())
+        })
+    }
+
+    /// Returns the native balance every account starts with: 1000 ETH, or
+    /// just below `U256::MAX` with [`WorkloadConfig::near_max_balance`].
+    pub fn initial_balance(&self) -> U256 {
+        match self.config.near_max_balance {
+            Some(headroom) => U256::MAX - U256::from(headroom),
+            None => U256::from(1_000_000_000_000_000_000_000u128), // 1000 ETH
+        }
+    }
+
+    /// Returns the token balances every account starts with: the
+    /// [initial balance](Self::initial_balance) of each of the workload's tokens.
+    pub fn initial_token_balances(&self) -> Vec<U256> {
+        vec![self.initial_balance(); self.config.tokens]
     }
 
     /// Returns the number of blocks in this workload.
@@ -365,6 +832,7 @@ mod tests {
             seed: 123,
             chain_id: 1,
             transactions_per_block: 5,
+            ..Default::default()
         };
 
         let workload = Workload::generate(config);
@@ -384,4 +852,193 @@ mod tests {
             assert!(tx.verify(), "Transaction signature should be valid");
         }
     }
+
+    #[test]
+    fn test_replay_injection() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            hot_accounts: 10,
+            seed: 7,
+            chain_id: 1,
+            transactions_per_block: 5,
+            duplicate_transactions: 3,
+            double_spends: 2,
+            fake_signatures: false,
+            tokens: 0,
+            nonceless: false,
+            compute_cost: ComputeCost::default(),
+            near_max_balance: None,
+            hot_probability: 1.0,
+        };
+
+        let workload = Workload::generate(config);
+
+        assert_eq!(workload.transactions.len(), 25);
+        assert_eq!(workload.num_replays(), 5);
+
+        // The first occurrence is never marked as a replay.
+        let mask = workload.replay_mask();
+        let mut seen = std::collections::HashSet::new();
+        for (tx, replay) in workload.transactions.iter().zip(mask) {
+            assert_eq!(replay, !seen.insert((tx.from, tx.nonce)));
+        }
+    }
+
+    #[test]
+    fn test_fake_signatures() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            hot_accounts: 10,
+            transactions_per_block: 5,
+            fake_signatures: true,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config.clone());
+        let again = Workload::generate(config);
+        for (tx, same) in workload.transactions.iter().zip(&again.transactions) {
+            assert_eq!(tx.signature, same.signature);
+            assert!(!tx.verify());
+        }
+
+        // Trusting tx.from, every transfer still executes.
+        let executor = SequentialExecutor::with_verification(false);
+        let (_, result) = executor.execute(workload.create_db(), &workload);
+        assert_eq!(result.successful, 20);
+    }
+
+    #[test]
+    fn test_token_transfers() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 200,
+            hot_accounts: 10,
+            transactions_per_block: 50,
+            tokens: 3,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        assert!(workload.transactions.iter().all(|tx| tx.token.map_or(true, |token| token < 3)));
+        assert!(workload.transactions.iter().any(|tx| tx.token.is_some()));
+        assert!(workload.transactions.iter().any(|tx| tx.token.is_none()));
+        assert_eq!(workload.initial_token_balances().len(), 3);
+
+        // The token id is part of the signed hash.
+        for tx in &workload.transactions {
+            assert!(tx.verify());
+        }
+    }
+
+    #[test]
+    fn test_nonceless_ids() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 100,
+            hot_accounts: 2,
+            transactions_per_block: 25,
+            double_spends: 5,
+            fake_signatures: true,
+            nonceless: true,
+            ..Default::default()
+        };
+
+        let workload = Workload::generate(config);
+        let ids: HashSet<u64> = workload.transactions.iter().map(|tx| tx.nonce).collect();
+        assert_eq!(ids.len(), 100);
+
+        // A double-spend reuses its source's id, which is all the seen-set checks.
+        assert_eq!(workload.num_replays(), 5);
+    }
+
+    #[test]
+    fn test_compute_costs() {
+        let config = WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 100,
+            hot_accounts: 5,
+            transactions_per_block: 25,
+            double_spends: 5,
+            fake_signatures: true,
+            ..Default::default()
+        };
+        let plain = Workload::generate(config.clone());
+        let costly = Workload::generate(WorkloadConfig {
+            compute_cost: ComputeCost::Uniform { min: 1, max: 1000 },
+            ..config
+        });
+
+        // Same transactions, each with its own cost; replays cost what their source does
+        let hashes = |workload: &Workload| workload.transactions.iter().map(|tx| tx.tx_hash).collect::<Vec<_>>();
+        assert_eq!(hashes(&costly), hashes(&plain));
+        assert!(plain.transactions.iter().all(|tx| tx.compute_cost == 0));
+        assert!(costly.transactions.iter().all(|tx| (1..=1000).contains(&tx.compute_cost)));
+        let costs: HashSet<u64> = costly.transactions.iter().map(|tx| tx.compute_cost).collect();
+        assert!(costs.len() > 50);
+        let mut source_costs = HashMap::new();
+        for tx in &costly.transactions {
+            assert_eq!(*source_costs.entry((tx.from, tx.nonce)).or_insert(tx.compute_cost), tx.compute_cost);
+        }
+    }
+
+    #[test]
+    fn test_hot_probability() {
+        let config = WorkloadConfig {
+            num_accounts: 100,
+            num_transactions: 2000,
+            hot_accounts: 2,
+            transactions_per_block: 100,
+            fake_signatures: true,
+            ..Default::default()
+        };
+        let all_hot = Workload::generate(config.clone());
+        let mixed = Workload::generate(WorkloadConfig {
+            hot_probability: 0.3,
+            ..config
+        });
+
+        // Every transaction stays on one side of the hot set
+        let hot: HashSet<Address> = mixed.accounts[..2].iter().map(|account| account.address).collect();
+        let hot_txs = mixed
+            .transactions
+            .iter()
+            .filter(|tx| {
+                assert_eq!(hot.contains(&tx.from), hot.contains(&tx.to));
+                hot.contains(&tx.from)
+            })
+            .count();
+        assert!((500..700).contains(&hot_txs), "{} of 2000 transactions hot", hot_txs);
+        assert!(all_hot.transactions.iter().all(|tx| hot.contains(&tx.from)));
+    }
+
+    #[test]
+    fn test_next_round_continues_nonces() {
+        let first = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 100,
+            hot_accounts: 10,
+            transactions_per_block: 10,
+            double_spends: 3,
+            fake_signatures: true,
+            ..Default::default()
+        });
+        let second = first.next_round();
+        assert_eq!(second.config.seed, first.config.seed + 1);
+        assert_eq!(second.accounts.len(), first.accounts.len());
+        assert_ne!(second.transactions[0].tx_hash, first.transactions[0].tx_hash);
+
+        // Each sender's nonces run on without gaps or reuse across both rounds
+        let mut next: HashMap<Address, u64> = HashMap::new();
+        for workload in [&first, &second] {
+            for (tx, replay) in workload.transactions.iter().zip(workload.replay_mask()) {
+                if !replay {
+                    let expected = next.entry(tx.from).or_insert(0);
+                    assert_eq!(tx.nonce, *expected);
+                    *expected += 1;
+                }
+            }
+        }
+    }
 }
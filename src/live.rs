@@ -0,0 +1,169 @@
+//! Live progress of the running benchmark cell.
+//!
+//! Executors that can report while they run (currently Block-STM) add
+//! committed transactions and per-worker busy time to the process-wide
+//! [`LiveProgress`] once one is [installed](install), e.g. by the `--tui`
+//! dashboard. Nothing is recorded otherwise, so benchmark runs without a
+//! dashboard pay nothing for it.
+//!
+//! The dashboard [resets](LiveProgress::reset) the counters when a cell
+//! starts and samples [snapshots](LiveProgress::snapshot) while it runs: TPS
+//! is the committed count over elapsed time, and a worker's utilization is
+//! the busy time it gained between two snapshots over the time between them.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Workers tracked per cell; activity of higher worker ids is dropped.
+pub const MAX_WORKERS: usize = 256;
+
+static INSTALLED: OnceLock<Arc<LiveProgress>> = OnceLock::new();
+
+/// Installs `progress` as the process-wide sink for live progress.
+///
+/// Returns `false`, leaving the existing sink in place, if one was installed already.
+pub fn install(progress: Arc<LiveProgress>) -> bool {
+    INSTALLED.set(progress).is_ok()
+}
+
+/// The installed progress sink, if any.
+pub fn installed() -> Option<&'static Arc<LiveProgress>> {
+    INSTALLED.get()
+}
+
+/// Counters for the cell currently running.
+#[derive(Debug)]
+pub struct LiveProgress {
+    started: Mutex<Instant>,
+    committed: AtomicUsize,
+    busy_nanos: Vec<AtomicU64>,
+}
+
+impl Default for LiveProgress {
+    fn default() -> Self {
+        Self {
+            started: Mutex::new(Instant::now()),
+            committed: AtomicUsize::new(0),
+            busy_nanos: (0..MAX_WORKERS).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+}
+
+impl LiveProgress {
+    /// Creates zeroed counters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Zeroes the counters and restarts the clock, e.g. when a cell starts.
+    pub fn reset(&self) {
+        *self.started.lock().unwrap() = Instant::now();
+        self.committed.store(0, Ordering::Relaxed);
+        for busy in &self.busy_nanos {
+            busy.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Records `count` more committed transactions.
+    pub fn add_committed(&self, count: usize) {
+        self.committed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Records that `worker` spent `busy` executing.
+    pub fn add_busy(&self, worker: usize, busy: Duration) {
+        if let Some(counter) = self.busy_nanos.get(worker) {
+            counter.fetch_add(busy.as_nanos() as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Reads the counters.
+    pub fn snapshot(&self) -> LiveSnapshot {
+        let elapsed = self.started.lock().unwrap().elapsed();
+        let mut worker_busy: Vec<Duration> = self
+            .busy_nanos
+            .iter()
+            .map(|busy| Duration::from_nanos(busy.load(Ordering::Relaxed)))
+            .collect();
+        let active = worker_busy.iter().rposition(|busy| !busy.is_zero()).map_or(0, |last| last + 1);
+        worker_busy.truncate(active);
+
+        LiveSnapshot {
+            elapsed,
+            committed: self.committed.load(Ordering::Relaxed),
+            worker_busy,
+        }
+    }
+}
+
+/// Counters of the running cell at one point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LiveSnapshot {
+    /// Time since the cell started.
+    pub elapsed: Duration,
+    pub committed: usize,
+    /// Busy time per worker, up to the highest worker that reported any.
+    pub worker_busy: Vec<Duration>,
+}
+
+impl LiveSnapshot {
+    /// Committed transactions per second since the cell started.
+    pub fn throughput_tps(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            0.0
+        } else {
+            self.committed as f64 / self.elapsed.as_secs_f64()
+        }
+    }
+
+    /// Fraction of the time since `earlier` each worker spent executing, in
+    /// `0.0..=1.0`.
+    pub fn utilization_since(&self, earlier: &LiveSnapshot) -> Vec<f64> {
+        let window = self.elapsed.saturating_sub(earlier.elapsed).as_secs_f64();
+        self.worker_busy
+            .iter()
+            .enumerate()
+            .map(|(worker, busy)| {
+                let before = earlier.worker_busy.get(worker).copied().unwrap_or_default();
+                if window > 0.0 {
+                    (busy.saturating_sub(before).as_secs_f64() / window).min(1.0)
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshots_track_throughput_and_utilization() {
+        let progress = LiveProgress::new();
+        progress.add_committed(50);
+        progress.add_busy(0, Duration::from_millis(5));
+        progress.add_busy(MAX_WORKERS, Duration::from_millis(5));
+
+        let earlier = LiveSnapshot {
+            elapsed: Duration::from_millis(10),
+            ..progress.snapshot()
+        };
+        assert_eq!(earlier.committed, 50);
+        assert_eq!(earlier.worker_busy, vec![Duration::from_millis(5)]);
+
+        progress.add_busy(0, Duration::from_millis(5));
+        progress.add_busy(2, Duration::from_millis(20));
+        let later = LiveSnapshot {
+            elapsed: Duration::from_millis(20),
+            ..progress.snapshot()
+        };
+        assert_eq!(later.throughput_tps(), 2500.0);
+        assert_eq!(later.utilization_since(&earlier), vec![0.5, 0.0, 1.0]);
+
+        progress.reset();
+        assert_eq!(progress.snapshot().committed, 0);
+        assert!(progress.snapshot().worker_busy.is_empty());
+    }
+}
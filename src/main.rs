@@ -1,24 +1,403 @@
 //! Comprehensive benchmark runner for all executor backends.
 
-use clap::Parser;
-use db_test::{Executor, SequentialExecutor, Workload, WorkloadConfig};
-use std::time::Instant;
+use clap::{Parser, Subcommand};
+use db_test::executor::{
+    ConditionalWriteExecutor, CpuBatchVerifier, InMemoryConditionalStore, MultiTenantResult,
+    PreVerifiedExecutor, WithConcurrencyStats, WithCriticalPath,
+};
+use db_test::codec::{codec_by_name, AccountValue, CodecReport, ALL_CODECS};
+use db_test::diff::RunDiff;
+use db_test::environment::EnvironmentInfo;
+use db_test::markdown::{self, OutputFormat};
+use db_test::frequency::preflight;
+use db_test::harness::{
+    print_section_header, run_any, BenchPlan, BenchReport, BenchResult, BenchRunner, CellOutput,
+};
+use db_test::analysis::WorkloadStats;
+use db_test::equivalence::check_equivalence;
+use db_test::manifest::RunManifest;
+use db_test::multi_chain::{ChainLayout, MultiChainWorkload, WithChainBreakdown};
+use db_test::mutation::{Mutation, WorkloadMutator};
+use db_test::options::{ExecutorOpt, ExecutorOptions, FdbDispatch, FdbOptions};
+use db_test::recovery::RecoveryBackend;
+use db_test::registry::ExecutorRegistry;
+use db_test::rlp_blocks::{export_blocks, import_blocks};
+use db_test::significance::SignificanceTest;
+use db_test::storage::{StorageConfig, StorageLayout};
+use db_test::workload_file::{load_workloads, save_workloads, WorkloadFileError};
+use db_test::{Account, ComputeCost, Executor, SequentialExecutor, Workload, WorkloadConfig};
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "mdbx")]
 use db_test::executor::{MdbxBatchedExecutor, MdbxSequentialExecutor};
 #[cfg(feature = "mdbx")]
+use db_test::footprint::Footprint;
+#[cfg(feature = "mdbx")]
+use db_test::soak::Soak;
+#[cfg(feature = "mdbx")]
 use tempfile::tempdir;
 
+#[cfg(any(feature = "mdbx", feature = "fdb"))]
+use db_test::executor::{run_concurrent, run_isolated};
+
 #[cfg(feature = "fdb")]
 use db_test::executor::FdbParallelExecutor;
 
 #[cfg(feature = "block-stm")]
-use db_test::BlockStmExecutor;
+use db_test::analysis::declared_dependencies;
+#[cfg(feature = "block-stm")]
+use db_test::executor::{BlockStmExecutor, ConflictHints, ScheduleLog, ShardedBlockStmExecutor};
+#[cfg(feature = "block-stm")]
+use db_test::manifest::workload_hash;
+#[cfg(feature = "block-stm")]
+use db_test::options::BlockStmOptions;
+#[cfg(feature = "block-stm")]
+use std::sync::Arc;
+
+#[cfg(all(feature = "block-stm", feature = "mdbx"))]
+use db_test::executor::MdbxDatabase;
+
+#[cfg(feature = "remote")]
+use db_test::RemoteDbExecutor;
+
+#[cfg(feature = "results-db")]
+use db_test::results_db::ResultsDb;
+
+#[cfg(feature = "rpc")]
+use db_test::rpc::RpcServer;
+#[cfg(feature = "rpc")]
+use std::net::TcpListener;
+
+#[cfg(feature = "charts")]
+use db_test::charts::{write_charts, ChartFormat};
+
+#[cfg(feature = "tui")]
+use db_test::live::{self, LiveProgress};
+#[cfg(feature = "tui")]
+use db_test::tui::Dashboard;
 
 /// Benchmark runner for REVM database implementations
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
-struct Args {
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Options for `bench`, which also runs when no subcommand is given
+    #[command(flatten)]
+    bench: BenchArgs,
+}
+
+/// Benchmark options: which executors to run and how
+#[derive(clap::Args, Debug)]
+struct BenchArgs {
+    #[command(flatten)]
+    workload: WorkloadArgs,
+
+    /// Thread counts to test for parallel executors (comma-separated)
+    #[arg(long, value_delimiter = ',', default_values_t = vec![1, 2, 4, 8])]
+    threads: Vec<usize>,
+
+    /// Enable sequential in-memory executor
+    #[arg(long, default_value_t = false)]
+    sequential: bool,
+
+    /// Enable the in-memory executor that applies transfers to CacheDB without revm
+    #[arg(long, default_value_t = false)]
+    native_transfer: bool,
+
+    /// Enable the executor that keeps state in memory behind a write-ahead log and snapshots
+    #[arg(long, default_value_t = false)]
+    wal: bool,
+
+    /// Enable the io_uring flat-file executor (requires --features io-uring, Linux only)
+    #[arg(long, default_value_t = false)]
+    uring: bool,
+
+    /// Enable the memory-mapped flat array executor (requires --features mmap)
+    #[arg(long, default_value_t = false)]
+    mmap_array: bool,
+
+    /// Run the sequential (revm) and native transfer executors on every workload
+    /// and report the share of sequential time spent in revm per conflict level
+    #[arg(long, default_value_t = false)]
+    evm_overhead: bool,
+
+    /// Enable MDBX sequential executor (requires --features mdbx)
+    #[arg(long, default_value_t = false)]
+    mdbx_sequential: bool,
+
+    /// Enable MDBX batched executor (requires --features mdbx)
+    #[arg(long, default_value_t = false)]
+    mdbx_batched: bool,
+
+    /// Enable MDBX two-phase commit executor across sharded stores (requires --features mdbx)
+    #[arg(long, default_value_t = false)]
+    two_phase: bool,
+
+    /// Number of shards for the two-phase commit and sharded Block-STM executors
+    #[arg(long, default_value_t = 2)]
+    shards: usize,
+
+    /// Enable FoundationDB parallel executor (requires --features fdb)
+    #[arg(long, default_value_t = false)]
+    fdb: bool,
+
+    /// Enable sequential revm executor reading and committing state through FoundationDB
+    /// (requires --features fdb)
+    #[arg(long, default_value_t = false)]
+    fdb_revm: bool,
+
+    /// Enable Block-STM parallel executor (requires --features block-stm)
+    #[arg(long, default_value_t = false)]
+    block_stm: bool,
+
+    /// Enable sharded Block-STM executor with cross-shard transactions applied serially
+    /// (requires --features block-stm)
+    #[arg(long, default_value_t = false)]
+    sharded_block_stm: bool,
+
+    /// Enable pipelined Block-STM: each block executes while the previous block's
+    /// writes are committed to MDBX (requires --features block-stm,mdbx)
+    #[arg(long, default_value_t = false)]
+    pipelined_block_stm: bool,
+
+    /// Enable Block-STM with declared dependencies: each transaction declares the earlier
+    /// transactions touching its accounts, as the workload analyzer derives them, and waits
+    /// for them to execute instead of discovering them by aborting (requires --features block-stm)
+    #[arg(long, default_value_t = false)]
+    declared_dependencies: bool,
+
+    /// Enable conditional-write (DynamoDB-style) executor with in-memory emulation
+    #[arg(long, default_value_t = false)]
+    conditional_write: bool,
+
+    /// Artificial per-operation latency for the conditional-write store, in microseconds
+    #[arg(long, default_value_t = 0)]
+    kv_latency_us: u64,
+
+    /// Enable experimental Verkle-tree executor (requires --features verkle)
+    #[arg(long, default_value_t = false)]
+    verkle: bool,
+
+    /// Run the remote executor against a remote-db-server at this address
+    /// (requires --features remote)
+    #[arg(long)]
+    remote: Option<String>,
+
+    /// Run a registered executor by name (repeatable); see --list-executors
+    #[arg(long = "executor", value_name = "NAME")]
+    executors: Vec<String>,
+
+    /// List registered executor names and exit
+    #[arg(long, default_value_t = false)]
+    list_executors: bool,
+
+    /// Enable all available executors
+    #[arg(long, default_value_t = false)]
+    all: bool,
+
+    /// Disable signature verification (faster but less realistic)
+    #[arg(long, default_value_t = false)]
+    no_verify: bool,
+
+    /// Verify all signatures in a batch stage on this many threads before execution,
+    /// instead of inline (registry executors, by --executor or their flag; see --list-executors)
+    #[arg(long, value_name = "THREADS")]
+    pre_verify: Option<usize>,
+
+    /// Recover every signer once per workload, untimed, and check registry executors'
+    /// transactions against the cache, so results show execution throughput alone
+    #[arg(long, default_value_t = false, conflicts_with = "pre_verify")]
+    trust_preverified: bool,
+
+    /// Report distinct senders, the longest dependency chain among committed
+    /// transactions and sampled parallelism under each registry executor's result
+    #[arg(long, default_value_t = false)]
+    concurrency_stats: bool,
+
+    /// Report the critical path through the committed transactions' conflicts,
+    /// weighted by execution time, and how close each registry executor got to it
+    #[arg(long, default_value_t = false)]
+    critical_path: bool,
+
+    /// Signer recovery backend for the sequential and MDBX executors
+    /// (k256, or secp256k1 with --features secp256k1)
+    #[arg(long, default_value = "k256")]
+    recovery: RecoveryBackend,
+
+    /// Pre-populate persistent backends (MDBX and FDB executors) with this many
+    /// untouched accounts before each run, outside the timed region
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    background_accounts: u64,
+
+    /// Executor-specific option as name=key=value (repeatable), e.g.
+    /// block-stm=lookahead=64, mdbx=sync-mode=safe-no-sync, fdb=batch-size=500
+    #[arg(long = "executor-opt", value_name = "NAME=KEY=VALUE")]
+    executor_opts: Vec<ExecutorOpt>,
+
+    /// Account value encoding for FDB executors (raw, rlp, compact, varint)
+    #[arg(long, default_value = "raw")]
+    codec: String,
+
+    /// Report encoded value size and encode/decode throughput for every account codec
+    #[arg(long, default_value_t = false)]
+    codec_report: bool,
+
+    /// Report MDBX commit time, range scan and point read throughput for every
+    /// account key scheme (requires --features mdbx)
+    #[arg(long, default_value_t = false)]
+    key_scheme_report: bool,
+
+    /// Maintain per-shard state commitments in the FDB parallel executor, with
+    /// this many shards (requires --features fdb)
+    #[arg(long)]
+    fdb_commitment_shards: Option<usize>,
+
+    /// Log every transfer the FDB parallel executor commits under a
+    /// versionstamped key, and check each sender's commit order after the run
+    /// (requires --features fdb)
+    #[arg(long, default_value_t = false)]
+    fdb_transfer_log: bool,
+
+    /// Prefetch accounts for the next N transactions in a background thread
+    /// (MDBX sequential and FDB revm executors)
+    #[arg(long)]
+    prefetch: Option<usize>,
+
+    /// Compute the state root after every block with this many threads
+    /// (MDBX batched executor only; reported separately from execution time)
+    #[arg(long)]
+    state_root_threads: Option<usize>,
+
+    /// Run selected MDBX/FDB backends in multi-tenant mode: one concurrent tenant
+    /// per listed hot-account count, all sharing a single backend instance
+    #[arg(long, value_delimiter = ',')]
+    multi_tenant: Vec<usize>,
+
+    /// Also run registry executors on one workload per hot-account count that
+    /// interleaves a workload for each of these chain ids, on disjoint accounts,
+    /// and break their results down per chain
+    #[arg(long, value_name = "IDS", value_delimiter = ',')]
+    chains: Vec<u64>,
+
+    /// After the benchmark matrix, run rounds of the first workload back to back
+    /// against one MDBX batched database for this many minutes, sampling TPS,
+    /// database size and memory over time
+    #[arg(long, value_name = "N")]
+    soak_minutes: Option<u64>,
+
+    /// Seconds between --soak-minutes samples
+    #[arg(long, value_name = "SECS", default_value_t = 60)]
+    soak_sample_secs: u64,
+
+    /// Run every benchmark cell this many times
+    #[arg(long, default_value_t = 1)]
+    repetitions: usize,
+
+    /// Shuffle the order benchmark cells run in (reshuffled every repetition) to
+    /// spread cache and thermal effects across executors; output stays grouped
+    #[arg(long, default_value_t = false)]
+    interleave: bool,
+
+    /// Seed for the --interleave shuffle
+    #[arg(long, default_value_t = 42)]
+    interleave_seed: u64,
+
+    /// Check CPU governor and turbo settings and sample idle frequency before benchmarking
+    #[arg(long, default_value_t = false)]
+    preflight: bool,
+
+    /// Idle time between consecutive benchmark cells, in seconds
+    #[arg(long, default_value_t = 0)]
+    cooldown_secs: u64,
+
+    /// Sample CPU frequency while each cell runs and report it under the result
+    #[arg(long, default_value_t = false)]
+    sample_frequency: bool,
+
+    /// Flag cells whose CPU frequency varied by more than this percentage
+    /// (coefficient of variation; used with --sample-frequency)
+    #[arg(long, default_value_t = 5.0)]
+    freq_variance_threshold: f64,
+
+    /// Submit every workload to the node at this HTTP JSON-RPC endpoint as EIP-1559
+    /// transactions and cross-check the sequential executor against its receipts and
+    /// balances; the node must fund the accounts, e.g. via --mnemonic (requires --features oracle)
+    #[arg(long, value_name = "URL")]
+    oracle_rpc: Option<String>,
+
+    /// Run every workload through reth's block executor and Block-STM (at the largest
+    /// --threads count), diff their receipts and final state, and exit if they disagree
+    /// (requires --features reth-compare)
+    #[arg(long, default_value_t = false)]
+    compare_reth: bool,
+
+    /// Run every workload through Block-STM (at the largest --threads count) and write
+    /// the observed conflicts to this file, for use with --conflict-hints
+    /// (requires --features block-stm)
+    #[arg(long, value_name = "PATH")]
+    dump_conflict_hints: Option<String>,
+
+    /// Also run Block-STM with the conflicts in this file (from --dump-conflict-hints)
+    /// as scheduling hints: a hinted reader waits until its writer has executed once
+    /// (requires --features block-stm)
+    #[arg(long, value_name = "PATH")]
+    conflict_hints: Option<String>,
+
+    /// Run every workload through Block-STM (at the largest --threads count) and write
+    /// the order it dispatched transactions to its workers to this file, for use with
+    /// --replay-schedule (requires --features block-stm)
+    #[arg(long, value_name = "PATH")]
+    record_schedule: Option<String>,
+
+    /// Also run Block-STM forcing the dispatch order recorded in this file (from
+    /// --record-schedule) at the recorded thread count, and report where each replay
+    /// diverged (requires --features block-stm)
+    #[arg(long, value_name = "PATH")]
+    replay_schedule: Option<String>,
+
+    /// Validate every generated workload (nonces, signatures, balances, block layout)
+    /// before benchmarking, and exit if any check fails
+    #[arg(long, default_value_t = false)]
+    validate_workload: bool,
+
+    /// Write a machine-readable manifest of this run (command line, workload configs
+    /// and hashes, features, per-cell result hashes) for use with `repro`
+    #[arg(long, value_name = "PATH")]
+    manifest: Option<String>,
+
+    /// Append every cell run of this run, with build and command-line metadata, to a
+    /// SQLite results database for `history` (requires --features results-db)
+    #[arg(long, value_name = "PATH")]
+    results_db: Option<String>,
+
+    /// Write every cell run of this run as a versioned JSON document
+    /// (requires --features serde)
+    #[arg(long, value_name = "PATH")]
+    results_json: Option<String>,
+
+    /// Show a live dashboard (cell grid, live TPS, worker utilization, log) while
+    /// benchmarking instead of streaming rows; results print once it finishes
+    /// (requires --features tui)
+    #[arg(long, default_value_t = false)]
+    tui: bool,
+
+    /// Result format: text, or markdown to also print GitHub-flavored tables
+    /// with the environment after the run
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    output: OutputFormat,
+
+    /// Draw TPS-vs-conflict and speedup-vs-threads line charts in FORMAT (svg or png)
+    /// next to the --manifest file, or in the current directory without one
+    /// (requires --features charts)
+    #[arg(long, value_name = "FORMAT")]
+    charts: Option<String>,
+}
+
+/// Workload generation options shared by every subcommand that needs workloads
+#[derive(clap::Args, Debug)]
+struct WorkloadArgs {
     /// Number of accounts in the system
     #[arg(short = 'a', long, default_value_t = 50_000)]
     num_accounts: usize,
@@ -38,409 +417,2488 @@ struct Args {
     #[arg(short = 'H', long, value_delimiter = ',', default_values_t = vec![2, 10, 100, 1000])]
     hot_accounts: Vec<usize>,
 
-    /// Thread counts to test for parallel executors (comma-separated)
-    #[arg(long, value_delimiter = ',', default_values_t = vec![1, 2, 4, 8])]
-    threads: Vec<usize>,
+    /// Probability that a transaction stays within the hot accounts; the rest transfer
+    /// between the other accounts (1 = all traffic is hot, so -H alone sets contention)
+    #[arg(long, value_name = "P", default_value_t = 1.0, value_parser = parse_probability)]
+    hot_probability: f64,
+
+    /// Number of independently seeded workloads per hot-account count (seeds 42, 43, ...);
+    /// every cell runs on each, and the ranking aggregates them like repetitions
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    seeds: u64,
+
+    /// Generate workloads with deterministic placeholder signatures instead of ECDSA
+    /// (much faster setup); implies --no-verify, so executors trust tx.from
+    #[arg(long, default_value_t = false)]
+    fake_signatures: bool,
+
+    /// Use the accounts in this directory of keystore JSON files instead of
+    /// seed-derived ones (requires --features keystore)
+    #[arg(long, value_name = "DIR")]
+    keystore_dir: Option<String>,
+
+    /// Derive the accounts from this BIP-39 mnemonic along m/44'/60'/0'/0/i instead of
+    /// from seeds, matching devnet-funded accounts (requires --features mnemonic)
+    #[arg(long, value_name = "PHRASE", conflicts_with = "keystore_dir")]
+    mnemonic: Option<String>,
+
+    /// File holding the password for --keystore-dir (empty password if omitted)
+    #[arg(long, value_name = "PATH")]
+    keystore_password_file: Option<String>,
+
+    /// Number of exact duplicate transactions to inject into each workload
+    #[arg(long, default_value_t = 0)]
+    duplicates: usize,
+
+    /// Number of same-nonce double-spends to inject into each workload
+    #[arg(long, default_value_t = 0)]
+    double_spends: usize,
+
+    /// Number of tokens every account holds next to its native balance; each transaction
+    /// moves one asset picked uniformly from native and the tokens (0 = native only)
+    #[arg(long, default_value_t = 0)]
+    tokens: usize,
+
+    /// Identify transactions by unique random ids checked against a seen-set instead of
+    /// sequential sender nonces
+    #[arg(long, default_value_t = false)]
+    nonceless: bool,
+
+    /// Synthetic keccak iterations every executor runs per transaction execution, drawn
+    /// per transaction: fixed:N, uniform:MIN:MAX or bimodal:CHEAP:EXPENSIVE:PERCENT
+    #[arg(long, value_name = "DIST", default_value = "fixed:0")]
+    compute_cost: ComputeCost,
+
+    /// Fund accounts with U256::MAX minus this many wei instead of 1000 ETH, so
+    /// accounts that receive more than that overflow and their transfers fail
+    #[arg(long, value_name = "HEADROOM")]
+    near_max_balance: Option<u64>,
+
+    /// Mutate every workload before execution (repeatable, applied in order):
+    /// shuffle, drop=FRACTION, duplicate-blocks=N or truncate=N
+    #[arg(long = "mutate", value_name = "SPEC")]
+    mutations: Vec<Mutation>,
+
+    /// Load the workloads from this file (written by `generate`) instead of generating
+    /// them; the generation flags above are ignored
+    #[arg(long, value_name = "PATH")]
+    workload_file: Option<String>,
+
+    /// Load the workload from this RLP block file (written by `export-blocks`, `geth export`
+    /// or `reth export`) instead of generating it; only value transfers are kept
+    #[arg(long, value_name = "PATH", conflicts_with = "workload_file")]
+    block_file: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Benchmark the selected executors (the default)
+    Bench(BenchArgs),
+
+    /// Generate workloads and write them to a file for --workload-file
+    Generate {
+        #[command(flatten)]
+        workload: WorkloadArgs,
+
+        /// File to write
+        #[arg(short, long, value_name = "PATH")]
+        output: String,
+
+        /// Write the compact binary form instead of tab-separated text (requires --features serde)
+        #[arg(long, default_value_t = false)]
+        binary: bool,
+    },
+
+    /// Export a generated workload's blocks as an RLP block file for other EVM tooling
+    ExportBlocks {
+        #[command(flatten)]
+        workload: WorkloadArgs,
+
+        /// File to write
+        #[arg(short, long, value_name = "PATH")]
+        output: String,
+    },
+
+    /// Print shape and contention statistics for each workload without executing it
+    Analyze {
+        #[command(flatten)]
+        workload: WorkloadArgs,
+
+        /// Also give this many contracts storage and every transaction slot
+        /// accesses, concentrated on a hot slot set of the first contract
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        storage_contracts: usize,
+
+        /// Storage slots per contract
+        #[arg(long, value_name = "N", default_value_t = 4096)]
+        storage_slots: usize,
+
+        /// Storage slot accesses per transaction
+        #[arg(long, value_name = "N", default_value_t = 2)]
+        storage_accesses: usize,
+
+        /// Slots of the first contract that most accesses hit
+        #[arg(long, value_name = "N", default_value_t = 4)]
+        storage_hot_slots: usize,
+    },
+
+    /// Simulate Block-STM in virtual time on each workload under each scheduling policy
+    /// and worker count (requires --features block-stm)
+    Simulate {
+        #[command(flatten)]
+        workload: WorkloadArgs,
+
+        /// Virtual worker counts to simulate (comma-separated)
+        #[arg(long, value_delimiter = ',', default_values_t = vec![1, 2, 4, 8, 16])]
+        workers: Vec<usize>,
+
+        /// Scheduling policies to compare: fifo, priority, dependency-aware (comma-separated)
+        #[arg(long = "policy", value_delimiter = ',', default_values_t = vec![
+            "fifo".to_string(),
+            "priority".to_string(),
+            "dependency-aware".to_string(),
+        ])]
+        policies: Vec<String>,
+
+        /// Virtual cost of executing one transaction, in nanoseconds
+        #[arg(long, value_name = "NS", default_value_t = 100_000)]
+        exec_cost_ns: u64,
+
+        /// Spread of per-transaction costs: each is drawn uniformly within this
+        /// fraction of --exec-cost-ns, seeded by the workload seed
+        #[arg(long, value_name = "FRACTION", default_value_t = 0.0)]
+        cost_jitter: f64,
+
+        /// Simulate each workload block as its own Block-STM block
+        #[arg(long, default_value_t = false)]
+        per_block: bool,
+    },
+
+    /// Run registry executors on each workload and check they commit exactly the
+    /// transactions the sequential executor does
+    Verify {
+        #[command(flatten)]
+        workload: WorkloadArgs,
+
+        /// Registered executor to check (repeatable); defaults to every registered executor
+        #[arg(long = "executor", value_name = "NAME")]
+        executors: Vec<String>,
+
+        /// Thread counts to check threaded executors at (comma-separated)
+        #[arg(long, value_delimiter = ',', default_values_t = vec![4])]
+        threads: Vec<usize>,
+
+        /// Disable signature verification
+        #[arg(long, default_value_t = false)]
+        no_verify: bool,
+
+        /// Executor-specific option as name=key=value (repeatable)
+        #[arg(long = "executor-opt", value_name = "NAME=KEY=VALUE")]
+        executor_opts: Vec<ExecutorOpt>,
+    },
+
+    /// Execute the first workload, then serve the resulting state over JSON-RPC
+    /// (eth_getBalance, eth_getTransactionCount, eth_chainId) until interrupted
+    /// (requires --features rpc)
+    Serve {
+        #[command(flatten)]
+        workload: WorkloadArgs,
+
+        /// Address to listen on
+        #[arg(short = 'l', long, default_value = "127.0.0.1:8545")]
+        listen: String,
+
+        /// Backend to populate and serve: memory or mdbx (requires --features mdbx)
+        #[arg(long, default_value = "memory")]
+        backend: String,
+
+        /// Disable signature verification
+        #[arg(long, default_value_t = false)]
+        no_verify: bool,
+    },
+
+    /// Print the result tables and summary recorded in a --manifest file
+    Report {
+        /// Manifest written by --manifest
+        manifest: String,
+    },
+
+    /// Re-run exactly one cell recorded in a --manifest file and compare its result hash
+    Repro {
+        /// Manifest written by --manifest
+        manifest: String,
+
+        /// Plan index of the cell to re-run (second field of its `cell` record)
+        #[arg(long)]
+        cell: usize,
+    },
+
+    /// List the runs recorded in a --results-db database and each executor's trend across them
+    History {
+        /// Results database written by --results-db
+        db: String,
+
+        /// Only show the trend of this executor
+        #[arg(long)]
+        executor: Option<String>,
+
+        /// Number of most recent runs to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Test whether two executors' TPS differ significantly across the repetitions in a --manifest file
+    Compare {
+        /// Manifest written by --manifest, ideally with --repetitions and --interleave
+        manifest: String,
+
+        /// Executor to compare against
+        #[arg(long)]
+        baseline: String,
+
+        /// Executor whose change from the baseline is tested
+        #[arg(long)]
+        candidate: String,
+
+        /// Significance level; the bootstrap interval has confidence 1 - alpha
+        #[arg(long, default_value_t = 0.05)]
+        alpha: f64,
+
+        /// Number of bootstrap resamples
+        #[arg(long, default_value_t = 10_000)]
+        resamples: usize,
+
+        /// Seed for bootstrap resampling
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+    },
+
+    /// Show per-cell TPS and failure changes between two --manifest files as Markdown for PR review
+    Diff {
+        /// Manifest of the earlier run, e.g. before a change
+        before: String,
+
+        /// Manifest of the later run, e.g. after a change
+        after: String,
+
+        /// TPS change in percent beyond which a cell is highlighted
+        #[arg(long, value_name = "PERCENT", default_value_t = 5.0)]
+        threshold: f64,
+    },
+}
+
+/// Parses a probability between 0 and 1.
+fn parse_probability(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(p) if (0.0..=1.0).contains(&p) => Ok(p),
+        _ => Err(format!("expected a probability between 0 and 1, got '{}'", s)),
+    }
+}
+
+impl WorkloadArgs {
+    /// Builds the workload configuration for a single hot-account setting.
+    fn workload_config(&self, hot_accounts: usize) -> WorkloadConfig {
+        WorkloadConfig {
+            num_accounts: self.num_accounts,
+            num_transactions: self.num_transactions,
+            transactions_per_block: self.transactions_per_block,
+            hot_accounts,
+            seed: 42,
+            chain_id: 1,
+            duplicate_transactions: self.duplicates,
+            double_spends: self.double_spends,
+            fake_signatures: self.fake_signatures,
+            tokens: self.tokens,
+            nonceless: self.nonceless,
+            compute_cost: self.compute_cost,
+            near_max_balance: self.near_max_balance,
+            hot_probability: self.hot_probability,
+        }
+    }
+
+    /// Loads the --workload-file workloads, or generates one per hot-account count
+    /// and seed, exiting if they cannot be read.
+    fn workloads(&self) -> Vec<Workload> {
+        if let Some(path) = &self.workload_file {
+            let workloads = load_workloads(path).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            });
+            if workloads.iter().any(|workload| workload.config.fake_signatures) && !self.fake_signatures {
+                eprintln!("{} holds placeholder signatures; pass --fake-signatures", path);
+                std::process::exit(1);
+            }
+            println!("Loaded {} workloads from {}", workloads.len(), path);
+            println!();
+            return workloads;
+        }
+
+        if let Some(path) = &self.block_file {
+            let imported = import_blocks(path).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            });
+            let workload = imported.workload;
+            println!(
+                "Imported {} transfers in {} blocks from {} ({} contract transactions skipped)",
+                workload.transactions.len(),
+                workload.num_blocks(),
+                path,
+                imported.skipped
+            );
+            println!();
+            return vec![workload];
+        }
+
+        let external_accounts = load_keystore_accounts(self).or_else(|| derive_mnemonic_accounts(self));
+        self.hot_accounts
+            .iter()
+            .flat_map(|&hot_accounts| {
+                (0..self.seeds).map(move |offset| {
+                    let config = self.workload_config(hot_accounts);
+                    WorkloadConfig {
+                        seed: config.seed + offset,
+                        ..config
+                    }
+                })
+            })
+            .map(|config| match &external_accounts {
+                Some(accounts) => Workload::generate_with_accounts(config, accounts.clone()),
+                None => Workload::generate(config),
+            })
+            .map(|mut workload| {
+                if !self.mutations.is_empty() {
+                    let mutators: Vec<&dyn WorkloadMutator> =
+                        self.mutations.iter().map(|mutation| mutation as &dyn WorkloadMutator).collect();
+                    workload.mutate(&mutators, workload.config.seed);
+                }
+                workload
+            })
+            .collect()
+    }
+}
+
+impl BenchArgs {
+    /// Generates one workload per tenant for each --seeds seed.
+    ///
+    /// Tenant `t` advances the seed by `t * num_accounts`, as chains do in
+    /// multi-chain workloads, so each tenant's accounts come from their own
+    /// range of seeds and no two tenants share an account.
+    #[cfg_attr(not(any(feature = "mdbx", feature = "fdb")), allow(dead_code))]
+    fn tenant_workloads(&self) -> Vec<Vec<Workload>> {
+        (0..self.workload.seeds)
+            .map(|offset| {
+                self.multi_tenant
+                    .iter()
+                    .enumerate()
+                    .map(|(tenant, &hot_accounts)| {
+                        let config = self.workload.workload_config(hot_accounts);
+                        Workload::generate(WorkloadConfig {
+                            seed: (config.seed + offset).wrapping_add((tenant * config.num_accounts) as u64),
+                            ..config
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Generates one multi-chain workload per hot-account count, exiting if
+    /// --chains repeats a chain id.
+    fn multi_chain_workloads(&self) -> Vec<MultiChainWorkload> {
+        if self.chains.is_empty() {
+            return Vec::new();
+        }
+        let mut distinct = self.chains.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() != self.chains.len() {
+            eprintln!("--chains lists a chain id more than once");
+            std::process::exit(1);
+        }
+        self.workload
+            .hot_accounts
+            .iter()
+            .map(|&hot_accounts| {
+                MultiChainWorkload::generate(self.workload.workload_config(hot_accounts), &self.chains)
+            })
+            .collect()
+    }
+
+    /// Registry executors the per-executor flags select, each with the build
+    /// features it needs if it has any. `--fdb` selects both FDB dispatch
+    /// modes unless `fdb_dispatch` picks one.
+    fn legacy_executors(&self, fdb_dispatch: Option<FdbDispatch>) -> Vec<(&'static str, Option<&'static str>)> {
+        let enabled = |flag: bool| self.all || flag;
+        let fdb = |dispatch| enabled(self.fdb) && fdb_dispatch.map_or(true, |chosen| chosen == dispatch);
+        [
+            (enabled(self.sequential || self.evm_overhead), "sequential", None),
+            (enabled(self.native_transfer || self.evm_overhead), "native_transfer", None),
+            (enabled(self.wal), "wal", None),
+            (enabled(self.uring), "uring", Some("io-uring on Linux")),
+            (enabled(self.mmap_array), "mmap_array", Some("mmap")),
+            (enabled(self.block_stm), "block_stm", Some("block-stm")),
+            (enabled(self.verkle), "verkle", Some("verkle")),
+            (enabled(self.mdbx_sequential), "mdbx_sequential", Some("mdbx")),
+            (enabled(self.mdbx_batched), "mdbx_batched", Some("mdbx")),
+            (enabled(self.two_phase), "two_phase", Some("mdbx")),
+            (fdb(FdbDispatch::Threads), "fdb_parallel", Some("fdb")),
+            (fdb(FdbDispatch::Async), "fdb_async", Some("fdb")),
+            (enabled(self.fdb_revm), "fdb_revm", Some("fdb")),
+        ]
+        .into_iter()
+        .filter(|&(selected, _, _)| selected)
+        .map(|(_, name, features)| (name, features))
+        .collect()
+    }
+}
+
+/// Measures every account codec over post-execution account states
+fn run_codec_report(args: &BenchArgs) {
+    use revm::database_interface::DatabaseRef;
+
+    print_section_header("Account Codec Report (post-execution account values)");
+    println!(
+        "{:<20} | {:<10} | {:<14} | {:<16} | {:<16}",
+        "Hot Accounts", "Codec", "Avg Size (B)", "Encode (ops/s)", "Decode (ops/s)"
+    );
+    println!("{}", "-".repeat(90));
+
+    for &hot_accounts in &args.workload.hot_accounts {
+        let workload = Workload::generate(args.workload.workload_config(hot_accounts));
+        let (db, _) = SequentialExecutor::with_verification(false).execute(workload.create_db(), &workload);
+        let accounts: Vec<_> = workload
+            .accounts
+            .iter()
+            .filter_map(|account| db.basic_ref(account.address).ok().flatten())
+            .map(|info| AccountValue::new(info.nonce, info.balance).with_code_hash(info.code_hash))
+            .collect();
+
+        for codec in ALL_CODECS {
+            let report = CodecReport::measure(codec, &accounts);
+            println!(
+                "{:<20} | {:<10} | {:<14.1} | {:<16.0} | {:<16.0}",
+                format!("{} accounts", hot_accounts),
+                report.name,
+                report.avg_value_size(),
+                report.encode_throughput(),
+                report.decode_throughput(),
+            );
+        }
+    }
+
+    println!();
+}
+
+/// Measures every account key scheme on MDBX, with the run's background accounts
+#[cfg(feature = "mdbx")]
+fn run_key_scheme_report(args: &BenchArgs) {
+    use db_test::executor::KeySchemeReport;
+    use db_test::key_scheme::KeyScheme;
+
+    print_section_header("Account Key Scheme Report (MDBX)");
+    println!(
+        "{:<20} | {:<10} | {:<12} | {:<14} | {:<18} | {:<18}",
+        "Hot Accounts", "Scheme", "Commit (ms)", "Scan (keys/s)", "Sorted Reads/s", "Workload Reads/s"
+    );
+    println!("{}", "-".repeat(106));
+
+    for &hot_accounts in &args.workload.hot_accounts {
+        let workload = Workload::generate(args.workload.workload_config(hot_accounts));
+        let background = (args.background_accounts > 0)
+            .then(|| db_test::background::BackgroundAccounts::new(args.background_accounts, workload.config.seed));
+
+        for scheme in KeyScheme::ALL {
+            let dir = tempdir().expect("Failed to create temp directory");
+            let report = KeySchemeReport::measure(dir.path(), scheme, &workload, background.as_ref())
+                .expect("Key scheme measurement failed");
+            println!(
+                "{:<20} | {:<10} | {:<12.2} | {:<14.0} | {:<18.0} | {:<18.0}",
+                format!("{} accounts", hot_accounts),
+                report.scheme,
+                report.commit_time.as_secs_f64() * 1000.0,
+                report.scan_throughput(),
+                report.ordered_read_throughput(),
+                report.workload_read_throughput(),
+            );
+        }
+    }
+
+    println!();
+}
+
+/// Loads the --keystore-dir accounts, exiting if they cannot be read
+#[cfg(feature = "keystore")]
+fn load_keystore_accounts(args: &WorkloadArgs) -> Option<Vec<Account>> {
+    let dir = args.keystore_dir.as_ref()?;
+    let password = match &args.keystore_password_file {
+        Some(path) => std::fs::read_to_string(path)
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to read keystore password file {}: {}", path, err);
+                std::process::exit(1);
+            })
+            .trim_end_matches(['\r', '\n'])
+            .to_string(),
+        None => String::new(),
+    };
+
+    let accounts = db_test::keystore::load_dir(dir, &password).unwrap_or_else(|err| {
+        eprintln!("Failed to load keystores: {}", err);
+        std::process::exit(1);
+    });
+    if accounts.len() < 2 {
+        eprintln!("--keystore-dir needs at least 2 keystores, found {}", accounts.len());
+        std::process::exit(1);
+    }
+    println!("Loaded {} accounts from {}", accounts.len(), dir);
+    println!();
+    Some(accounts)
+}
+
+#[cfg(not(feature = "keystore"))]
+fn load_keystore_accounts(args: &WorkloadArgs) -> Option<Vec<Account>> {
+    if args.keystore_dir.is_some() {
+        eprintln!("--keystore-dir requires rebuilding with --features keystore");
+        std::process::exit(1);
+    }
+    None
+}
+
+/// Derives --num-accounts accounts from the --mnemonic phrase, exiting if it is invalid
+#[cfg(feature = "mnemonic")]
+fn derive_mnemonic_accounts(args: &WorkloadArgs) -> Option<Vec<Account>> {
+    let phrase = args.mnemonic.as_ref()?;
+    let accounts = db_test::mnemonic::derive_accounts(phrase, "", args.num_accounts).unwrap_or_else(|err| {
+        eprintln!("Failed to derive accounts: {}", err);
+        std::process::exit(1);
+    });
+    println!("Derived {} accounts from mnemonic", accounts.len());
+    println!();
+    Some(accounts)
+}
+
+#[cfg(not(feature = "mnemonic"))]
+fn derive_mnemonic_accounts(args: &WorkloadArgs) -> Option<Vec<Account>> {
+    if args.mnemonic.is_some() {
+        eprintln!("--mnemonic requires rebuilding with --features mnemonic");
+        std::process::exit(1);
+    }
+    None
+}
+
+/// Validates every workload, printing one line per workload, and exits on any failure
+fn validate_workloads(hot_accounts: &[usize], workloads: &[Workload]) {
+    println!("Workload Validation:");
+    let mut valid = true;
+    for (&hot_accounts, workload) in hot_accounts.iter().zip(workloads) {
+        let report = workload.validate();
+        println!(
+            "  • {} accounts: {} txs, {} senders, {} injected replays — {}",
+            hot_accounts,
+            report.num_transactions,
+            report.num_senders,
+            report.num_replays,
+            if report.is_valid() { "ok".to_string() } else { format!("{} issues", report.issues.len()) }
+        );
+        for issue in report.issues.iter().take(10) {
+            println!("      {}", issue);
+        }
+        if report.issues.len() > 10 {
+            println!("      ... and {} more", report.issues.len() - 10);
+        }
+        valid &= report.is_valid();
+    }
+    println!();
+
+    if !valid {
+        eprintln!("Workload validation failed; not running benchmarks");
+        std::process::exit(1);
+    }
+}
+
+/// Submits every workload to a live node and compares it with the sequential executor,
+/// exiting if they disagree
+#[cfg(feature = "oracle")]
+fn run_oracle(url: &str, hot_accounts: &[usize], workloads: &[Workload]) {
+    use db_test::oracle::{compare, submit_workload, OracleOptions, RpcClient};
+
+    let client = RpcClient::new(url).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    println!("Execution Oracle ({}):", url);
+    println!(
+        "{:<20} | {:<12} | {:<12} | {:<15} | {:<10}",
+        "Hot Accounts", "Node OK/Fail", "Local OK/Fail", "Node Time (ms)", "Mismatches"
+    );
+    println!("{}", "-".repeat(80));
+
+    let mut agrees = true;
+    for (&hot_accounts, workload) in hot_accounts.iter().zip(workloads) {
+        let node = submit_workload(&client, workload, &OracleOptions::default()).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+        // The node checks its own re-signed copies, so only real local signatures need verifying
+        let verify = !workload.config.fake_signatures;
+        let (db, result) = SequentialExecutor::with_verification(verify).execute(workload.create_db(), workload);
+        let comparison = compare(&node, result.successful, &db);
+
+        println!(
+            "{:<20} | {:<12} | {:<12} | {:<15.2} | {:<10}",
+            format!("{} accounts", hot_accounts),
+            format!("{}/{}", node.successful(), node.failed()),
+            format!("{}/{}", result.successful, result.failed),
+            node.elapsed.as_secs_f64() * 1000.0,
+            comparison.mismatches.len()
+        );
+        for mismatch in comparison.mismatches.iter().take(10) {
+            println!(
+                "{:<20} | {}: node sent {} txs, value {}; local sent {} txs, value {}",
+                "",
+                mismatch.address,
+                mismatch.nonces.0,
+                mismatch.value_deltas.0,
+                mismatch.nonces.1,
+                mismatch.value_deltas.1
+            );
+        }
+        agrees &= comparison.agrees();
+    }
+    println!();
+
+    if !agrees {
+        eprintln!("Local execution disagrees with the node; not running benchmarks");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "oracle"))]
+fn run_oracle(_url: &str, _hot_accounts: &[usize], _workloads: &[Workload]) {
+    eprintln!("--oracle-rpc requires rebuilding with --features oracle");
+    std::process::exit(1);
+}
+
+/// Diffs Block-STM against reth's block executor on every workload, exiting if they disagree
+#[cfg(feature = "reth-compare")]
+fn compare_reth(args: &BenchArgs, workloads: &[Workload]) {
+    use db_test::reth_compare::compare_with_reth;
+    use db_test::BlockStmExecutor;
+
+    let num_threads = args.threads.iter().copied().max().unwrap_or(1);
+    let executor = BlockStmExecutor::new(num_threads, !args.workload.fake_signatures);
+
+    println!("Reth Comparison (Block-STM, {} threads):", num_threads);
+    println!(
+        "{:<20} | {:<12} | {:<12} | {:<18} | {:<16}",
+        "Hot Accounts", "Reth OK", "Local OK", "Receipt Mismatches", "State Mismatches"
+    );
+    println!("{}", "-".repeat(90));
+
+    let mut agrees = true;
+    for workload in workloads {
+        let hot_accounts = workload.config.hot_accounts;
+        let comparison = compare_with_reth(&executor, workload).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+
+        println!(
+            "{:<20} | {:<12} | {:<12} | {:<18} | {:<16}",
+            format!("{} accounts", hot_accounts),
+            comparison.reth_successful,
+            comparison.local_successful,
+            comparison.receipt_mismatches.len(),
+            comparison.state_mismatches.len()
+        );
+        for mismatch in comparison.receipt_mismatches.iter().take(10) {
+            println!(
+                "{:<20} | block {} tx {} ({}): reth {:?} ({} gas), local {:?}",
+                "",
+                mismatch.block,
+                mismatch.index,
+                mismatch.tx_hash,
+                mismatch.reth,
+                mismatch.reth_gas_used,
+                mismatch.local
+            );
+        }
+        for mismatch in comparison.state_mismatches.iter().take(10) {
+            println!(
+                "{:<20} | {}: reth nonce {} balance {}, local nonce {} balance {}",
+                "",
+                mismatch.address,
+                mismatch.reth.0,
+                mismatch.reth.1,
+                mismatch.local.0,
+                mismatch.local.1
+            );
+        }
+        agrees &= comparison.agrees();
+    }
+    println!();
+
+    if !agrees {
+        eprintln!("Block-STM disagrees with reth; not running benchmarks");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "reth-compare"))]
+fn compare_reth(_args: &BenchArgs, _workloads: &[Workload]) {
+    eprintln!("--compare-reth requires rebuilding with --features reth-compare");
+    std::process::exit(1);
+}
+
+/// Profiles every workload with Block-STM and writes the observed conflicts to `path`
+#[cfg(feature = "block-stm")]
+fn dump_conflict_hints(args: &BenchArgs, path: &str, workloads: &[Workload]) {
+    let num_threads = args.threads.iter().copied().max().unwrap_or(1);
+    let executor = BlockStmExecutor::new(num_threads, !args.workload.fake_signatures && !args.no_verify);
+
+    let mut hints = ConflictHints::default();
+    for workload in workloads {
+        hints.merge(executor.profile_conflicts(workload));
+    }
+    if let Err(err) = hints.save(path) {
+        eprintln!("Failed to write conflict hints to {}: {}", path, err);
+        std::process::exit(1);
+    }
+    println!(
+        "Wrote {} conflict hints to {} (Block-STM, {} threads)",
+        hints.len(),
+        path,
+        num_threads
+    );
+    println!();
+}
+
+#[cfg(not(feature = "block-stm"))]
+fn dump_conflict_hints(_args: &BenchArgs, _path: &str, _workloads: &[Workload]) {
+    eprintln!("--dump-conflict-hints requires rebuilding with --features block-stm");
+    std::process::exit(1);
+}
+
+/// Runs every workload with Block-STM and writes each block's dispatch order to `path`
+#[cfg(feature = "block-stm")]
+fn record_schedules(args: &BenchArgs, options: &ExecutorOptions, path: &str, workloads: &[Workload]) {
+    let num_threads = args.threads.iter().copied().max().unwrap_or(1);
+    let executor = block_stm_with_options(
+        BlockStmExecutor::new(num_threads, !args.workload.fake_signatures && !args.no_verify),
+        &options.block_stm,
+    );
+
+    let mut log = ScheduleLog::default();
+    for workload in workloads {
+        log.record(workload_hash(workload), num_threads, executor.record_schedules(workload));
+    }
+    if let Err(err) = log.save(path) {
+        eprintln!("Failed to write schedules to {}: {}", path, err);
+        std::process::exit(1);
+    }
+    println!("Wrote {} schedules to {} (Block-STM, {} threads)", log.len(), path, num_threads);
+    println!();
+}
+
+#[cfg(not(feature = "block-stm"))]
+fn record_schedules(_args: &BenchArgs, _options: &ExecutorOptions, _path: &str, _workloads: &[Workload]) {
+    eprintln!("--record-schedule requires rebuilding with --features block-stm");
+    std::process::exit(1);
+}
+
+/// Runs MDBX batched rounds continuing `workload` against one database for
+/// `minutes`, printing a row per sample and the drift between the first and last
+#[cfg(feature = "mdbx")]
+fn run_soak(args: &BenchArgs, options: &ExecutorOptions, workload: &Workload, minutes: u64, verify_signatures: bool) {
+    print_section_header(&format!(
+        "MDBX Batched Soak ({} minutes, {} accounts, {} hot, {} txs per round)",
+        minutes,
+        workload.accounts.len(),
+        workload.config.hot_accounts,
+        workload.transactions.len()
+    ));
+
+    let dir = tempdir().expect("Failed to create temp directory");
+    let mut executor = MdbxBatchedExecutor::new_with_options(dir.path(), verify_signatures, &options.mdbx)
+        .expect("Failed to create MDBX batched executor")
+        .with_recovery(options.recovery);
+    if let Some(num_threads) = options.state_root_threads {
+        executor = executor.with_state_root(num_threads);
+    }
+    if let Some(background) = options.background(workload.config.seed) {
+        executor.load_background(&background).expect("Failed to load background accounts");
+    }
+
+    let mib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{:<10} | {:<8} | {:<12} | {:<8} | {:<12} | {:<14} | {:<10}",
+        "Elapsed", "Rounds", "Txs", "Failed", "TPS", "DB size (MiB)", "RSS (MiB)"
+    );
+    println!("{}", "-".repeat(92));
+    let report = Soak::new(Duration::from_secs(minutes * 60))
+        .with_sample_interval(Duration::from_secs(args.soak_sample_secs))
+        .run(
+            workload,
+            dir.path(),
+            |round, first_block| {
+                let (result, _) = if first_block == 0 {
+                    executor.execute_workload(round)?
+                } else {
+                    executor.execute_blocks(round, first_block)?
+                };
+                Ok::<_, eyre::Report>(result.to_execution_result())
+            },
+            |sample| {
+                println!(
+                    "{:<10} | {:<8} | {:<12} | {:<8} | {:<12.0} | {:<14.1} | {:<10}",
+                    format!("{:.0} s", sample.elapsed.as_secs_f64()),
+                    sample.rounds,
+                    sample.transactions,
+                    sample.failed,
+                    sample.throughput_tps,
+                    mib(sample.db_bytes),
+                    sample.rss_bytes.map_or("-".to_string(), |bytes| format!("{:.1}", mib(bytes)))
+                );
+            },
+        )
+        .expect("Soak failed");
+    println!();
+
+    let growth = |bytes: Option<i64>| {
+        bytes.map_or("-".to_string(), |bytes| format!("{:+.1} MiB", bytes as f64 / (1024.0 * 1024.0)))
+    };
+    println!(
+        "Soak drift (first to last sample): TPS {}, database {}, memory {}",
+        report
+            .tps_drift_pct()
+            .map_or("-".to_string(), |drift| format!("{:+.1}%", drift)),
+        growth(report.db_growth_bytes()),
+        growth(report.rss_growth_bytes())
+    );
+    println!();
+}
+
+/// Applies the `block-stm=...` executor options to `executor`
+#[cfg(feature = "block-stm")]
+fn block_stm_with_options(mut executor: BlockStmExecutor, options: &BlockStmOptions) -> BlockStmExecutor {
+    if let Some(lookahead) = options.lookahead {
+        executor = executor.with_lookahead(lookahead);
+    }
+    if let Some(bytes) = options.memory_budget {
+        executor = executor.with_memory_budget(bytes);
+    }
+    if options.per_block {
+        executor = executor.with_block_boundaries();
+    }
+    if options.atomic_blocks {
+        executor = executor.with_atomic_blocks();
+    }
+    executor
+}
+
+/// Runs `plan` under the live dashboard, then prints its results
+#[cfg(feature = "tui")]
+fn run_with_dashboard(runner: BenchRunner, plan: &BenchPlan<'_>) -> BenchReport {
+    let progress = std::sync::Arc::new(LiveProgress::new());
+    live::install(progress.clone());
+    let dashboard = std::sync::Arc::new(Dashboard::start(progress));
+
+    let report = runner.with_observer(dashboard.clone()).run(plan);
+    if let Err(err) = dashboard.finish() {
+        eprintln!("Dashboard failed: {}", err);
+    }
+    report.print();
+    report
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_with_dashboard(_runner: BenchRunner, _plan: &BenchPlan<'_>) -> BenchReport {
+    eprintln!("--tui requires rebuilding with --features tui");
+    std::process::exit(1);
+}
+
+/// Prints per-tenant throughput of a shared run next to each tenant's isolated baseline
+#[cfg_attr(not(any(feature = "mdbx", feature = "fdb")), allow(dead_code))]
+fn print_tenant_results(hot_accounts: &[usize], shared: &MultiTenantResult, isolated: &MultiTenantResult) {
+    println!(
+        "{:<20} | {:<15} | {:<15} | {:<10} | {:<12}",
+        "Tenant", "Isolated TPS", "Shared TPS", "Slowdown", "Success/Fail"
+    );
+    println!("{}", "-".repeat(85));
+
+    let slowdowns = shared.slowdowns(isolated);
+    for (tenant, ((result, alone), slowdown)) in shared
+        .tenants
+        .iter()
+        .zip(&isolated.tenants)
+        .zip(slowdowns)
+        .enumerate()
+    {
+        println!(
+            "{:<20} | {:<15.0} | {:<15.0} | {:<10} | {}/{}",
+            format!("#{} ({} accounts)", tenant, hot_accounts[tenant]),
+            alone.throughput_tps(),
+            result.throughput_tps(),
+            format!("{:.2}x", slowdown),
+            result.result.successful,
+            result.result.failed
+        );
+    }
+
+    println!(
+        "{:<20} | aggregate: {:.0} TPS, fairness: {:.3} (raw), {:.3} (relative to isolated)",
+        "",
+        shared.aggregate_tps(),
+        shared.fairness(),
+        shared.relative_fairness(isolated)
+    );
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match &cli.command {
+        None => bench(&cli.bench),
+        Some(Command::Bench(args)) => bench(args),
+        Some(Command::Generate {
+            workload,
+            output,
+            binary,
+        }) => generate(workload, output, *binary),
+        Some(Command::ExportBlocks { workload, output }) => export(workload, output),
+        Some(Command::Analyze {
+            workload,
+            storage_contracts,
+            storage_slots,
+            storage_accesses,
+            storage_hot_slots,
+        }) => {
+            let storage = (*storage_contracts > 0).then(|| StorageConfig {
+                contracts: *storage_contracts,
+                slots_per_contract: *storage_slots,
+                accesses_per_tx: *storage_accesses,
+                hot_slots: *storage_hot_slots,
+                ..Default::default()
+            });
+            analyze(workload, storage)
+        }
+        Some(Command::Simulate {
+            workload,
+            workers,
+            policies,
+            exec_cost_ns,
+            cost_jitter,
+            per_block,
+        }) => simulate(workload, workers, policies, *exec_cost_ns, *cost_jitter, *per_block),
+        Some(Command::Verify {
+            workload,
+            executors,
+            threads,
+            no_verify,
+            executor_opts,
+        }) => verify(workload, executors, threads, !no_verify, executor_opts),
+        Some(Command::Serve {
+            workload,
+            listen,
+            backend,
+            no_verify,
+        }) => serve(workload, listen, backend, !no_verify),
+        Some(Command::Report { manifest }) => report(manifest),
+        Some(Command::Repro { manifest, cell }) => repro(manifest, *cell),
+        Some(Command::History { db, executor, limit }) => history(db, executor.as_deref(), *limit),
+        Some(Command::Compare {
+            manifest,
+            baseline,
+            candidate,
+            alpha,
+            resamples,
+            seed,
+        }) => compare(
+            manifest,
+            baseline,
+            candidate,
+            SignificanceTest::new()
+                .with_alpha(*alpha)
+                .with_resamples(*resamples)
+                .with_seed(*seed),
+        ),
+        Some(Command::Diff {
+            before,
+            after,
+            threshold,
+        }) => diff(before, after, *threshold),
+    }
+}
+
+/// Runs the benchmark matrix and writes its manifest if asked to
+fn bench(args: &BenchArgs) {
+    let raw_args = std::env::args().skip(1).collect();
+    if let Some(manifest) = run_benchmarks(args, raw_args, None) {
+        if let Some(path) = &args.manifest {
+            manifest.save(path).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            });
+            println!("Wrote run manifest to {} ({} cell runs)", path, manifest.cells.len());
+        }
+        if let Some(path) = &args.results_db {
+            record_results(path, &manifest);
+        }
+    }
+}
+
+/// Writes the workloads to `output`
+fn generate(args: &WorkloadArgs, output: &str, binary: bool) {
+    let workloads = args.workloads();
+    let saved = if binary {
+        save_binary(output, &workloads)
+    } else {
+        save_workloads(output, &workloads)
+    };
+    saved.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let transactions: usize = workloads.iter().map(|workload| workload.transactions.len()).sum();
+    println!("Wrote {} workloads ({} transactions) to {}", workloads.len(), transactions, output);
+}
+
+/// Writes `workloads` to `output` in the binary form
+#[cfg(feature = "serde")]
+fn save_binary(output: &str, workloads: &[Workload]) -> Result<(), WorkloadFileError> {
+    db_test::workload_file::save_workloads_binary(output, workloads)
+}
+
+#[cfg(not(feature = "serde"))]
+fn save_binary(_output: &str, _workloads: &[Workload]) -> Result<(), WorkloadFileError> {
+    eprintln!("--binary requires rebuilding with --features serde");
+    std::process::exit(1);
+}
+
+/// Writes the only workload's blocks to `output` in RLP
+fn export(args: &WorkloadArgs, output: &str) {
+    let workloads = args.workloads();
+    let [workload] = &workloads[..] else {
+        eprintln!("export-blocks writes a single workload; pass one --hot-accounts value and --seeds 1");
+        std::process::exit(1);
+    };
+    export_blocks(output, workload).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    println!(
+        "Wrote {} blocks ({} transactions) to {}",
+        workload.num_blocks(),
+        workload.transactions.len(),
+        output
+    );
+}
+
+/// Prints statistics for every workload, and its storage contention if `storage` is given
+fn analyze(args: &WorkloadArgs, storage: Option<StorageConfig>) {
+    println!("Workload Analysis:");
+    for workload in args.workloads() {
+        let stats = WorkloadStats::compute(&workload);
+        println!("  • {} accounts (seed {}): {}", workload.config.hot_accounts, workload.config.seed, stats);
+        if let Some(config) = storage {
+            let layout = StorageLayout::generate(&workload, config);
+            let chain = layout.max_chain_length(&workload);
+            println!(
+                "      storage: {} contracts × {} slots, {} accesses/tx, hottest slot: {:.1}% of accesses, \
+                 max chain with storage: {} (bound {:.1}x)",
+                config.contracts,
+                config.slots_per_contract,
+                config.accesses_per_tx,
+                layout.hottest_slot_share() * 100.0,
+                chain,
+                workload.transactions.len() as f64 / chain.max(1) as f64
+            );
+        }
+    }
+}
+
+/// Prints the simulated makespan of every workload under each policy and worker count
+#[cfg(feature = "block-stm")]
+fn simulate(
+    args: &WorkloadArgs,
+    workers: &[usize],
+    policies: &[String],
+    exec_cost_ns: u64,
+    cost_jitter: f64,
+    per_block: bool,
+) {
+    use db_test::executor::SchedulingPolicy;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let policies: Vec<SchedulingPolicy> = policies
+        .iter()
+        .map(|policy| policy.parse())
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|err| {
+            eprintln!("Invalid --policy: {}", err);
+            std::process::exit(1);
+        });
+    if workers.contains(&0) || !(0.0..=1.0).contains(&cost_jitter) {
+        eprintln!("--workers must be positive and --cost-jitter within 0..=1");
+        std::process::exit(1);
+    }
+
+    for workload in args.workloads() {
+        let mut rng = StdRng::seed_from_u64(workload.config.seed);
+        let costs: Vec<u64> = (0..workload.blocks.iter().map(Vec::len).sum::<usize>())
+            .map(|_| {
+                let factor = 1.0 + cost_jitter * rng.gen_range(-1.0..=1.0);
+                (exec_cost_ns as f64 * factor).round() as u64
+            })
+            .collect();
+
+        println!(
+            "Simulated Block-STM ({} accounts, {} ns per execution{}):",
+            workload.config.hot_accounts,
+            exec_cost_ns,
+            if per_block { ", block by block" } else { "" }
+        );
+        println!(
+            "  {:<18} | {:<7} | {:<12} | {:<8} | {:<10} | {:<8} | {}",
+            "Policy", "Workers", "Makespan ms", "Speedup", "Executions", "Retries", "Utilization"
+        );
+        for &policy in &policies {
+            for &num_workers in workers {
+                let mut executor = BlockStmExecutor::new(num_workers, false);
+                if per_block {
+                    executor = executor.with_block_boundaries();
+                }
+                let report = executor.simulate(&workload, policy, &costs);
+                println!(
+                    "  {:<18} | {:<7} | {:<12.3} | {:<8} | {:<10} | {:<8} | {:.1}%",
+                    policy.name(),
+                    num_workers,
+                    report.makespan as f64 / 1e6,
+                    format!("{:.2}x", report.speedup()),
+                    report.executions,
+                    report.retries,
+                    report.utilization() * 100.0
+                );
+            }
+        }
+        println!();
+    }
+}
+
+#[cfg(not(feature = "block-stm"))]
+fn simulate(
+    _args: &WorkloadArgs,
+    _workers: &[usize],
+    _policies: &[String],
+    _exec_cost_ns: u64,
+    _cost_jitter: f64,
+    _per_block: bool,
+) {
+    eprintln!("simulate requires rebuilding with --features block-stm");
+    std::process::exit(1);
+}
+
+/// Registry executors that run against a FoundationDB cluster
+#[cfg(feature = "fdb")]
+const FDB_EXECUTORS: [&str; 3] = ["fdb_parallel", "fdb_async", "fdb_revm"];
+
+/// Boots the FoundationDB network if any of `names` is an FDB executor; the
+/// network stops when the returned guard drops
+#[cfg(feature = "fdb")]
+fn boot_fdb(names: &[&str]) -> Option<impl Sized> {
+    names.iter().any(|name| FDB_EXECUTORS.contains(name)).then(|| {
+        let network = unsafe { foundationdb::boot() };
+        std::thread::sleep(Duration::from_millis(100));
+        network
+    })
+}
+
+/// Checks registry executors against the sequential executor, exiting on any divergence
+fn verify(
+    args: &WorkloadArgs,
+    executors: &[String],
+    threads: &[usize],
+    verify_signatures: bool,
+    executor_opts: &[ExecutorOpt],
+) {
+    let registry = ExecutorRegistry::builtin();
+    if let Some(unknown) = executors.iter().find(|name| registry.get(name).is_none()) {
+        eprintln!("Unknown executor '{}' (see --list-executors)", unknown);
+        std::process::exit(1);
+    }
+    let options = ExecutorOptions::from_opts(executor_opts).unwrap_or_else(|err| {
+        eprintln!("Invalid --executor-opt: {}", err);
+        std::process::exit(1);
+    });
+    let options = ExecutorOptions {
+        verify_signatures: verify_signatures && !args.fake_signatures,
+        ..options
+    };
+    #[cfg(feature = "fdb")]
+    let _fdb_network = {
+        let names: Vec<&str> = registry
+            .entries()
+            .iter()
+            .map(|entry| entry.name)
+            .filter(|name| executors.is_empty() || executors.iter().any(|executor| executor == name))
+            .collect();
+        boot_fdb(&names)
+    };
+
+    let reference = SequentialExecutor::with_verification(options.verify_signatures);
+    let mut agrees = true;
+    for workload in args.workloads() {
+        if workload.config.tokens > 0 {
+            eprintln!("verify needs native-only workloads: the sequential executor holds no token balances");
+            std::process::exit(1);
+        }
+        if workload.config.nonceless {
+            eprintln!("verify needs workloads with nonces: the sequential executor checks sender nonces");
+            std::process::exit(1);
+        }
+        println!("Equivalence with the sequential executor ({} accounts):", workload.config.hot_accounts);
+        let entries = registry
+            .entries()
+            .iter()
+            .filter(|entry| executors.is_empty() || executors.iter().any(|name| name == entry.name));
+        for entry in entries {
+            let thread_counts = if entry.threaded { threads.to_vec() } else { vec![1] };
+            for num_threads in thread_counts {
+                let executor = entry.create(&ExecutorOptions { num_threads, ..options });
+                let equivalence = check_equivalence(&reference, executor.as_ref(), &workload);
+                println!(
+                    "  {} {}{}",
+                    if equivalence.agrees() { "✓" } else { "✗" },
+                    equivalence,
+                    if entry.threaded { format!(" ({} threads)", num_threads) } else { String::new() }
+                );
+                if equivalence.failure_reasons.0 != equivalence.failure_reasons.1 {
+                    let (reference, candidate) = equivalence.failure_summaries();
+                    println!("      failures: sequential {}; candidate {}", reference, candidate);
+                }
+                for mismatch in equivalence.mismatches.iter().take(10) {
+                    println!(
+                        "      tx {} ({}): sequential {}, {}",
+                        mismatch.position,
+                        mismatch.tx_hash,
+                        mismatch.reference_outcome(),
+                        mismatch.candidate_outcome()
+                    );
+                }
+                if let Some(block) = equivalence.divergent_block {
+                    let start: usize = workload.blocks[..block].iter().map(Vec::len).sum();
+                    println!(
+                        "      state first diverges at block {} (txs {}..{})",
+                        block,
+                        start,
+                        start + workload.blocks[block].len()
+                    );
+                }
+                agrees &= equivalence.agrees();
+            }
+        }
+        println!();
+    }
 
-    /// Enable sequential in-memory executor
-    #[arg(long, default_value_t = false)]
-    sequential: bool,
+    if !agrees {
+        eprintln!("Executors disagree with the sequential executor");
+        std::process::exit(1);
+    }
+}
 
-    /// Enable MDBX sequential executor (requires --features mdbx)
-    #[arg(long, default_value_t = false)]
-    mdbx_sequential: bool,
+/// Prints the results recorded in the manifest at `path`
+fn report(path: &str) {
+    let manifest = RunManifest::load(path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    println!(
+        "Run manifest {}: build {}, features [{}]",
+        path,
+        manifest.git_hash,
+        manifest.features.join(", ")
+    );
+    println!("  • Command line: {}", manifest.args.join(" "));
+    for workload in &manifest.workloads {
+        println!(
+            "  • Workload: {} accounts, {} hot, {} txs, seed {} ({})",
+            workload.config.num_accounts,
+            workload.config.hot_accounts,
+            workload.config.num_transactions,
+            workload.config.seed,
+            workload.hash
+        );
+    }
+    println!();
 
-    /// Enable MDBX batched executor (requires --features mdbx)
-    #[arg(long, default_value_t = false)]
-    mdbx_batched: bool,
+    let report = manifest.to_report();
+    report.print();
+    report.print_summary();
+}
 
-    /// Enable FoundationDB parallel executor (requires --features fdb)
-    #[arg(long, default_value_t = false)]
-    fdb: bool,
+/// Re-runs one cell of the manifest at `path` and exits non-zero if its result differs
+fn repro(path: &str, cell: usize) {
+    let manifest = RunManifest::load(path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let Some(recorded) = manifest.cell(cell) else {
+        eprintln!("{} has no cell {}", path, cell);
+        std::process::exit(1);
+    };
+    let cli = Cli::try_parse_from(std::iter::once("db-test".to_string()).chain(manifest.args.clone()))
+        .unwrap_or_else(|err| {
+            eprintln!("Recorded command line no longer parses: {}", err);
+            std::process::exit(1);
+        });
+    let args = match cli.command {
+        None => cli.bench,
+        Some(Command::Bench(args)) => args,
+        Some(_) => {
+            eprintln!("{} was not recorded by a benchmark run", path);
+            std::process::exit(1);
+        }
+    };
 
-    /// Enable Block-STM parallel executor (requires --features block-stm)
-    #[arg(long, default_value_t = false)]
-    block_stm: bool,
+    let environment = EnvironmentInfo::capture();
+    if environment.git_hash != manifest.git_hash {
+        println!("⚠️  Manifest was recorded at {}, this build is {}", manifest.git_hash, environment.git_hash);
+    }
+    if environment.features != manifest.features {
+        println!(
+            "⚠️  Manifest was recorded with features [{}], this build has [{}]",
+            manifest.features.join(", "),
+            environment.features.join(", ")
+        );
+    }
+    println!(
+        "Reproducing cell {}: {} / {} / {}",
+        cell,
+        recorded.section,
+        recorded.executor_name,
+        recorded.hot_accounts_label
+    );
+    println!();
 
-    /// Enable all available executors
-    #[arg(long, default_value_t = false)]
-    all: bool,
+    let Some(rerun) = run_benchmarks(&args, manifest.args.clone(), Some(cell)) else {
+        std::process::exit(1);
+    };
+    if rerun.workloads != manifest.workloads {
+        eprintln!("Regenerated workloads differ from the manifest; the cell cannot be reproduced");
+        std::process::exit(1);
+    }
+    let Some(result) = rerun.cell(cell) else {
+        eprintln!("Cell {} did not run in this build", cell);
+        std::process::exit(1);
+    };
 
-    /// Disable signature verification (faster but less realistic)
-    #[arg(long, default_value_t = false)]
-    no_verify: bool,
+    if result.result_hash == recorded.result_hash {
+        println!(
+            "✓ Result hash matches ({}); TPS {:.0} vs recorded {:.0}",
+            result.result_hash, result.throughput_tps, recorded.throughput_tps
+        );
+    } else {
+        println!(
+            "✗ Result hash differs: recorded {} ({}/{} successful/failed), got {} ({}/{})",
+            recorded.result_hash,
+            recorded.successful,
+            recorded.failed,
+            result.result_hash,
+            result.successful,
+            result.failed
+        );
+        std::process::exit(1);
+    }
 }
 
-/// Results from a single benchmark run.
-#[derive(Debug, Clone)]
-struct BenchmarkResult {
-    hot_accounts_label: String,
-    executor_name: String,
-    preserves_order: bool,
-    successful: usize,
-    failed: usize,
-    duration_ms: f64,
-    throughput_tps: f64,
+/// Prints the cell-by-cell differences between the manifests at `before` and `after`
+fn diff(before: &str, after: &str, threshold_pct: f64) {
+    let load = |path: &str| {
+        RunManifest::load(path).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        })
+    };
+    let diff = RunDiff::new(&load(before), &load(after));
+    print!("{}", diff.render(threshold_pct));
 }
 
-impl BenchmarkResult {
-    fn print_header() {
+/// Tests whether `candidate` differs significantly from `baseline` on each cell of the manifest at `path`
+fn compare(path: &str, baseline: &str, candidate: &str, test: SignificanceTest) {
+    let manifest = RunManifest::load(path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let comparisons = test.compare(&manifest, baseline, candidate).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    print_section_header(&format!("{} vs {} (alpha {})", candidate, baseline, test.alpha()));
+    println!(
+        "{:<20} | {:<12} | {:<7} | {:<12} | {:<12} | {:<9} | {:<20} | {:<9} | {}",
+        "Hot Accounts", "Group", "Samples", "Base TPS", "Cand TPS", "Change", "Interval", "p-value", "Verdict"
+    );
+    println!("{}", "-".repeat(130));
+    for comparison in &comparisons {
         println!(
-            "{:<20} | {:<25} | {:<8} | {:<10} | {:<10} | {:<12} | {:<12}",
-            "Hot Accounts", "Executor", "Ordering", "Successful", "Failed", "Time (ms)", "TPS"
+            "{:<20} | {:<12} | {:<7} | {:<12.0} | {:<12.0} | {:<9} | {:<20} | {:<9.4} | {}",
+            comparison.hot_accounts_label,
+            comparison.group.as_deref().unwrap_or("-"),
+            format!("{}/{}", comparison.baseline_samples, comparison.candidate_samples),
+            comparison.baseline_mean_tps,
+            comparison.candidate_mean_tps,
+            format!("{:+.1}%", comparison.change_pct),
+            format!("[{:+.1}%, {:+.1}%]", comparison.interval_pct.0, comparison.interval_pct.1),
+            comparison.mann_whitney.p_value,
+            if !comparison.significant {
+                "no significant difference"
+            } else if comparison.change_pct > 0.0 {
+                "significantly faster"
+            } else {
+                "significantly slower"
+            }
         );
-        println!("{}", "-".repeat(120));
     }
+    println!();
 
-    fn print(&self) {
+    let significant = comparisons.iter().filter(|comparison| comparison.significant).count();
+    println!("{} of {} cells differ significantly", significant, comparisons.len());
+    if comparisons
+        .iter()
+        .any(|comparison| comparison.baseline_samples.min(comparison.candidate_samples) < 4)
+    {
         println!(
-            "{:<20} | {:<25} | {:<8} | {:<10} | {:<10} | {:<12.2} | {:<12.0}",
-            self.hot_accounts_label,
-            self.executor_name,
-            if self.preserves_order { "strict" } else { "loose" },
-            self.successful,
-            self.failed,
-            self.duration_ms,
-            self.throughput_tps,
+            "⚠️  Cells with fewer than 4 samples per executor cannot reach significance at alpha 0.05; \
+             record with more --repetitions"
         );
     }
 }
 
-/// Generic benchmark runner for in-memory executors
-fn run_in_memory_benchmark<E>(
-    executor: &E,
-    workload: &Workload,
-    hot_accounts_label: &str,
-    num_transactions: usize,
-) -> BenchmarkResult
-where
-    E: Executor<Database = revm::database::CacheDB<revm::database::EmptyDB>>,
-{
-    let db = workload.create_db();
-    
-    let start = Instant::now();
-    let (_, result) = executor.execute(db, workload);
-    let elapsed = start.elapsed();
-
-    BenchmarkResult {
-        hot_accounts_label: hot_accounts_label.to_string(),
-        executor_name: executor.name().to_string(),
-        preserves_order: executor.preserves_order(),
-        successful: result.successful,
-        failed: result.failed,
-        duration_ms: elapsed.as_secs_f64() * 1000.0,
-        throughput_tps: num_transactions as f64 / elapsed.as_secs_f64(),
-    }
-}
-
-fn print_section_header(title: &str) {
-    println!("═══════════════════════════════════════════════════════════════════════════════════════════════════════");
-    println!("  {}", title);
-    println!("═══════════════════════════════════════════════════════════════════════════════════════════════════════");
-    println!();
+/// Appends the cells of `manifest` to the results database at `path`
+#[cfg(feature = "results-db")]
+fn record_results(path: &str, manifest: &RunManifest) {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let run_id = ResultsDb::open(path)
+        .and_then(|mut db| db.record_run(manifest, timestamp))
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+    println!("Recorded run #{} in {} ({} cell runs)", run_id, path, manifest.cells.len());
 }
 
-fn print_summary(results: &[BenchmarkResult]) {
-    if results.is_empty() {
-        return;
-    }
+#[cfg(not(feature = "results-db"))]
+fn record_results(_path: &str, _manifest: &RunManifest) {
+    eprintln!("--results-db requires rebuilding with --features results-db");
+    std::process::exit(1);
+}
 
-    println!();
-    println!("═══════════════════════════════════════════════════════════════════════════════════════════════════════");
-    println!("  Summary Statistics");
-    println!("═══════════════════════════════════════════════════════════════════════════════════════════════════════");
-    println!();
+/// Writes `report` to `path` as a versioned JSON document
+#[cfg(feature = "serde")]
+fn write_results_json(path: &str, report: &BenchReport) {
+    db_test::schema::save_json(path, report).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    println!("Wrote {} cell runs to {}", report.cells.len(), path);
+}
 
-    // Group by executor
-    let mut executor_groups: std::collections::HashMap<String, Vec<&BenchmarkResult>> =
-        std::collections::HashMap::new();
+#[cfg(not(feature = "serde"))]
+fn write_results_json(_path: &str, _report: &BenchReport) {
+    eprintln!("--results-json requires rebuilding with --features serde");
+    std::process::exit(1);
+}
 
-    for result in results {
-        executor_groups
-            .entry(result.executor_name.clone())
-            .or_default()
-            .push(result);
+/// Writes the result charts of `report` next to `manifest`, or as `db-test-*` in the current directory
+#[cfg(feature = "charts")]
+fn draw_charts(report: &BenchReport, manifest: Option<&str>, format: &str) {
+    let format: ChartFormat = format.parse().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let prefix = manifest.map_or_else(
+        || std::path::PathBuf::from("db-test"),
+        |path| std::path::Path::new(path).with_extension(""),
+    );
+    let written = write_charts(report, &prefix, format).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    for path in written {
+        println!("Wrote chart {}", path.display());
     }
+}
+
+#[cfg(not(feature = "charts"))]
+fn draw_charts(_report: &BenchReport, _manifest: Option<&str>, _format: &str) {
+    eprintln!("--charts requires rebuilding with --features charts");
+    std::process::exit(1);
+}
 
-    println!("{:<30} | {:<15} | {:<15} | {:<15}", "Executor", "Avg TPS", "Min TPS", "Max TPS");
+/// Lists the runs recorded in the results database at `path` and the throughput trend per executor
+#[cfg(feature = "results-db")]
+fn history(path: &str, executor: Option<&str>, limit: usize) {
+    let (runs, trend) = ResultsDb::open(path)
+        .and_then(|db| Ok((db.runs(limit)?, db.trend(executor)?)))
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+
+    println!("Runs in {} (most recent {})", path, runs.len());
+    println!(
+        "{:<6} | {:<19} | {:<12} | {:<6} | {}",
+        "Run", "Recorded (UTC)", "Build", "Cells", "Command line"
+    );
     println!("{}", "-".repeat(80));
+    for run in &runs {
+        println!(
+            "{:<6} | {:<19} | {:<12} | {:<6} | {}",
+            format!("#{}", run.id),
+            run.recorded_at,
+            run.git_hash,
+            run.cells,
+            run.args.join(" ")
+        );
+    }
+    println!();
+
+    if trend.is_empty() {
+        match executor {
+            Some(name) => println!("No cells recorded for executor {}", name),
+            None => println!("No cells recorded"),
+        }
+        return;
+    }
+    println!(
+        "{:<30} | {:<12} | {:<15} | {:<6} | {:<19} | {:<12} | {}",
+        "Executor", "Group", "Hot accounts", "Run", "Recorded (UTC)", "Mean TPS", "Change"
+    );
+    println!("{}", "-".repeat(118));
+    for point in &trend {
+        println!(
+            "{:<30} | {:<12} | {:<15} | {:<6} | {:<19} | {:<12.0} | {}",
+            point.executor_name,
+            point.group.as_deref().unwrap_or("-"),
+            point.hot_accounts_label,
+            format!("#{}", point.run_id),
+            point.recorded_at,
+            point.mean_throughput_tps,
+            point.change_pct.map_or("-".to_string(), |change| format!("{:+.1}%", change))
+        );
+    }
+}
 
-    for (executor_name, results) in executor_groups.iter() {
-        let avg_tps: f64 = results.iter().map(|r| r.throughput_tps).sum::<f64>() / results.len() as f64;
-        let min_tps = results.iter().map(|r| r.throughput_tps).fold(f64::INFINITY, f64::min);
-        let max_tps = results.iter().map(|r| r.throughput_tps).fold(f64::NEG_INFINITY, f64::max);
+#[cfg(not(feature = "results-db"))]
+fn history(_path: &str, _executor: Option<&str>, _limit: usize) {
+    eprintln!("history requires rebuilding with --features results-db");
+    std::process::exit(1);
+}
 
-        println!("{:<30} | {:<15.0} | {:<15.0} | {:<15.0}", executor_name, avg_tps, min_tps, max_tps);
+/// Executes the first workload on `backend` and serves the state it leaves over JSON-RPC at `listen`
+#[cfg(feature = "rpc")]
+fn serve(args: &WorkloadArgs, listen: &str, backend: &str, verify_signatures: bool) {
+    let Some(workload) = args.workloads().into_iter().next() else {
+        eprintln!("No workload to serve");
+        std::process::exit(1);
+    };
+    if workload.config.tokens > 0 || workload.config.nonceless {
+        eprintln!("serve needs native-only workloads with nonces: the RPC methods read native balances and nonces");
+        std::process::exit(1);
     }
+    let verify_signatures = verify_signatures && !workload.config.fake_signatures;
+    let listener = TcpListener::bind(listen).unwrap_or_else(|err| {
+        eprintln!("Failed to listen on {}: {}", listen, err);
+        std::process::exit(1);
+    });
+    let chain_id = workload.config.chain_id;
+    let announce = |successful: usize, failed: usize| {
+        println!(
+            "Executed {} transactions on the {} backend ({} successful, {} failed)",
+            workload.transactions.len(),
+            backend,
+            successful,
+            failed
+        );
+        println!(
+            "Serving eth_getBalance, eth_getTransactionCount and eth_chainId for {} accounts on http://{}",
+            workload.accounts.len(),
+            listen
+        );
+        if let Some(account) = workload.accounts.first() {
+            println!("  • Example account: {}", account.address);
+        }
+    };
 
-    println!();
+    let served = match backend {
+        "memory" => {
+            let executor = SequentialExecutor::with_verification(verify_signatures);
+            let (db, result) = executor.execute(workload.create_db(), &workload);
+            announce(result.successful, result.failed);
+            RpcServer::new(db, chain_id).serve(listener)
+        }
+        #[cfg(feature = "mdbx")]
+        "mdbx" => {
+            let dir = tempdir().expect("Failed to create temp directory");
+            let executor = MdbxSequentialExecutor::new(dir.path(), verify_signatures)
+                .expect("Failed to create MDBX sequential executor");
+            let (result, _) = executor.execute_workload(&workload).expect("Execution failed");
+            announce(result.successful, result.failed);
+            RpcServer::new(executor.into_database(), chain_id).serve(listener)
+        }
+        #[cfg(not(feature = "mdbx"))]
+        "mdbx" => {
+            eprintln!("The mdbx backend requires rebuilding with --features mdbx");
+            std::process::exit(1);
+        }
+        other => {
+            eprintln!("Unknown backend '{}' (expected memory or mdbx)", other);
+            std::process::exit(1);
+        }
+    };
+    if let Err(err) = served {
+        eprintln!("RPC server failed: {}", err);
+        std::process::exit(1);
+    }
 }
 
-fn main() {
-    let args = Args::parse();
+#[cfg(not(feature = "rpc"))]
+fn serve(_args: &WorkloadArgs, _listen: &str, _backend: &str, _verify_signatures: bool) {
+    eprintln!("serve requires rebuilding with --features rpc");
+    std::process::exit(1);
+}
+
+/// Runs the benchmark described by `args` and returns its manifest
+///
+/// With `only_cell`, runs just that plan cell and skips the stages outside the
+/// benchmark matrix (codec report, validation, oracle, comparisons, multi-tenant).
+/// Returns `None` if the run exited early.
+fn run_benchmarks(args: &BenchArgs, raw_args: Vec<String>, only_cell: Option<usize>) -> Option<RunManifest> {
+    let reproducing = only_cell.is_some();
 
     println!("╔══════════════════════════════════════════════════════════════════════════════════════════════════════╗");
     println!("║                              REVM Database Benchmark Suite                                           ║");
     println!("╚══════════════════════════════════════════════════════════════════════════════════════════════════════╝");
     println!();
 
-    let num_blocks = args.num_transactions / args.transactions_per_block;
-    let verify_signatures = !args.no_verify;
+    let num_blocks = args.workload.num_transactions / args.workload.transactions_per_block;
+    // Placeholder signatures never verify, so executors must trust tx.from
+    let verify_signatures = !args.no_verify && !args.workload.fake_signatures;
+    let codec = codec_by_name(&args.codec).unwrap_or_else(|| {
+        let names: Vec<_> = ALL_CODECS.iter().map(|codec| codec.name()).collect();
+        eprintln!("Unknown codec '{}' (expected one of: {})", args.codec, names.join(", "));
+        std::process::exit(1);
+    });
+
+    let executor_options = ExecutorOptions::from_opts(&args.executor_opts).unwrap_or_else(|err| {
+        eprintln!("Invalid --executor-opt: {}", err);
+        std::process::exit(1);
+    });
+    let executor_options = ExecutorOptions {
+        recovery: args.recovery,
+        trust_preverified: args.trust_preverified,
+        background_accounts: args.background_accounts,
+        prefetch: args.prefetch,
+        state_root_threads: args.state_root_threads,
+        shards: args.shards,
+        fdb: FdbOptions {
+            codec: codec.name(),
+            commitment_shards: args.fdb_commitment_shards,
+            transfer_log: args.fdb_transfer_log,
+            ..executor_options.fdb
+        },
+        ..executor_options
+    };
+
+    // Declared before any plan: benchmark cells borrow registry entries
+    let registry = ExecutorRegistry::builtin();
+    if args.list_executors {
+        for entry in registry.entries() {
+            println!("{:<20} {}", entry.name, entry.title);
+        }
+        return None;
+    }
+    if let Some(unknown) = args.executors.iter().find(|name| registry.get(name).is_none()) {
+        eprintln!("Unknown executor '{}' (see --list-executors)", unknown);
+        std::process::exit(1);
+    }
+
+    let environment = EnvironmentInfo::capture();
+    println!("Environment:");
+    println!("  • CPU: {} ({} cores)", environment.cpu_model, environment.cores);
+    match environment.memory_gib() {
+        Some(gib) => println!("  • Memory: {:.1} GiB", gib),
+        None => println!("  • Memory: unknown"),
+    }
+    println!("  • Kernel: {}", environment.kernel);
+    println!("  • Compiler: {}", environment.rustc_version);
+    println!("  • Build: {}", environment.git_hash);
+    if environment.features.is_empty() {
+        println!("  • Features: none");
+    } else {
+        println!("  • Features: {}", environment.features.join(", "));
+    }
+    println!();
+
+    if args.preflight {
+        let report = preflight(Duration::from_secs(1));
+        println!("Pre-flight Check:");
+        println!("  • Governor: {}", report.governor.as_deref().unwrap_or("unknown"));
+        match report.boost_enabled {
+            Some(enabled) => println!("  • Turbo/boost: {}", if enabled { "enabled" } else { "disabled" }),
+            None => println!("  • Turbo/boost: unknown"),
+        }
+        if let Some(idle) = report.idle {
+            println!(
+                "  • Idle frequency: {:.0} MHz avg ({:.0}-{:.0})",
+                idle.mean_mhz, idle.min_mhz, idle.max_mhz
+            );
+        }
+        for warning in report.warnings() {
+            println!("  ⚠️  {}", warning);
+        }
+        println!();
+    }
 
     println!("Benchmark Configuration:");
-    println!("  • Accounts: {}", args.num_accounts);
-    println!("  • Transactions per run: {}", args.num_transactions);
-    println!("  • Transactions per block: {}", args.transactions_per_block);
+    println!("  • Accounts: {}", args.workload.num_accounts);
+    println!("  • Transactions per run: {}", args.workload.num_transactions);
+    println!("  • Transactions per block: {}", args.workload.transactions_per_block);
     println!("  • Number of blocks: {}", num_blocks);
-    println!("  • Signature verification: {}", if verify_signatures { "enabled" } else { "disabled" });
-    println!("  • Hot accounts: {:?}", args.hot_accounts);
+    println!(
+        "  • Signature verification: {}",
+        if args.workload.fake_signatures {
+            "disabled (placeholder signatures)".to_string()
+        } else if verify_signatures {
+            format!("enabled ({} recovery)", args.recovery)
+        } else {
+            "disabled".to_string()
+        }
+    );
+    println!("  • Hot accounts: {:?}", args.workload.hot_accounts);
+    if args.workload.hot_probability < 1.0 {
+        println!("  • Hot traffic: {:.0}% of transactions", args.workload.hot_probability * 100.0);
+    }
+    if let Some(path) = &args.workload.workload_file {
+        println!("  • Workload file: {} (overrides the settings above)", path);
+    }
+    if let Some(path) = &args.workload.block_file {
+        println!("  • Block file: {} (overrides the settings above)", path);
+    }
     println!("  • Thread counts (parallel): {:?}", args.threads);
+    if args.repetitions > 1 || args.interleave {
+        println!(
+            "  • Repetitions: {}{}",
+            args.repetitions,
+            if args.interleave { format!(" (interleaved, seed {})", args.interleave_seed) } else { String::new() }
+        );
+    }
+    if !args.executor_opts.is_empty() {
+        println!("  • Executor options: {:?}", executor_options);
+    }
+    if args.background_accounts > 0 {
+        println!("  • Background accounts: {} (MDBX and FDB executors)", args.background_accounts);
+    }
+    if args.workload.duplicates > 0 || args.workload.double_spends > 0 {
+        println!("  • Injected replays: {} duplicates, {} double-spends", args.workload.duplicates, args.workload.double_spends);
+    }
+    if !args.workload.mutations.is_empty() {
+        let mutations: Vec<String> = args.workload.mutations.iter().map(Mutation::to_string).collect();
+        println!("  • Mutations: {}", mutations.join(", "));
+    }
+    if args.workload.tokens > 0 {
+        println!("  • Tokens: {} per account", args.workload.tokens);
+    }
+    if args.workload.nonceless {
+        println!("  • Nonce-less: random transaction ids instead of sender nonces");
+    }
+    if !args.workload.compute_cost.is_none() {
+        println!(
+            "  • Compute cost: {} keccak iterations per execution (mean {:.0})",
+            args.workload.compute_cost,
+            args.workload.compute_cost.mean()
+        );
+    }
     println!();
 
-    let mut all_results: Vec<BenchmarkResult> = Vec::new();
-
-    // Determine which executors to run
-    let run_sequential = args.all || args.sequential;
-    let run_mdbx_sequential = args.all || args.mdbx_sequential;
-    let run_mdbx_batched = args.all || args.mdbx_batched;
-    let run_fdb = args.all || args.fdb;
-    let run_block_stm = args.all || args.block_stm;
-
-    // Run sequential in-memory executor
-    if run_sequential {
-        print_section_header("Sequential In-Memory Executor (CacheDB)");
-        BenchmarkResult::print_header();
-
-        for &hot_accounts in &args.hot_accounts {
-            let hot_accounts_label = format!("{} accounts", hot_accounts);
-            
-            let workload_config = WorkloadConfig {
-                num_accounts: args.num_accounts,
-                num_transactions: args.num_transactions,
-                transactions_per_block: args.transactions_per_block,
-                hot_accounts,
-                seed: 42,
-                chain_id: 1,
-            };
+    if args.codec_report && !reproducing {
+        run_codec_report(args);
+    }
+    #[cfg(feature = "mdbx")]
+    if args.key_scheme_report && !reproducing {
+        run_key_scheme_report(args);
+    }
 
-            let workload = Workload::generate(workload_config);
-            let executor = SequentialExecutor::with_verification(verify_signatures);
+    // Determine which executors outside the registry to run
+    let run_sharded_block_stm = args.all || args.sharded_block_stm;
+    let run_pipelined_block_stm = args.all || args.pipelined_block_stm;
+    let run_declared_dependencies = args.all || args.declared_dependencies;
+    let run_conditional_write = args.all || args.conditional_write;
+
+    // Every executor runs the same workload for a given hot-account count
+    let workloads = args.workload.workloads();
+    let hot_accounts: Vec<usize> = workloads.iter().map(|workload| workload.config.hot_accounts).collect();
+    let multi_chains = args.multi_chain_workloads();
+
+    // Token and nonce-less workloads only run on executors that keep account state themselves
+    let token_workloads = workloads.iter().any(|workload| workload.config.tokens > 0);
+    let nonceless_workloads = workloads.iter().any(|workload| workload.config.nonceless);
+    let near_max_workloads = workloads.iter().any(|workload| workload.config.near_max_balance.is_some());
+    if token_workloads {
+        println!("Token workloads skip MDBX, two-phase, conditional-write, remote, FDB revm and verkle executors");
+        println!();
+    }
+    if nonceless_workloads {
+        println!(
+            "Nonce-less workloads skip MDBX, two-phase, conditional-write, remote, FDB revm and verkle executors, \
+             the oracle and the reth comparison"
+        );
+        println!();
+    }
+    let own_state_only = token_workloads || nonceless_workloads;
+    let run_conditional_write = run_conditional_write && !own_state_only;
+
+    // Registry executors: --executor names plus the ones legacy flags select
+    let mut selected: Vec<&str> = args.executors.iter().map(String::as_str).collect();
+    let mut unavailable = Vec::new();
+    for (name, features) in args.legacy_executors(executor_options.fdb.dispatch) {
+        match (registry.get(name), features) {
+            (None, Some(features)) => unavailable.push((name, features)),
+            _ if !selected.contains(&name) => selected.push(name),
+            _ => {}
+        }
+    }
+    let run_fdb_parallel = selected.iter().any(|name| matches!(*name, "fdb_parallel" | "fdb_async"));
+
+    // Dropped transactions leave nonce gaps, which FDB retries until it gives up
+    let drops_transactions = args
+        .workload
+        .mutations
+        .iter()
+        .any(|mutation| matches!(mutation, Mutation::DropTransactions(fraction) if *fraction > 0.0));
+    if run_fdb_parallel && drops_transactions && executor_options.fdb.retry.max_attempts.is_none() {
+        eprintln!(
+            "--mutate drop=FRACTION leaves nonce gaps the FDB executor retries forever; \
+             bound its retries with --executor-opt fdb=max-attempts=N"
+        );
+        std::process::exit(1);
+    }
 
-            let result = run_in_memory_benchmark(&executor, &workload, &hot_accounts_label, args.num_transactions);
-            result.print();
-            all_results.push(result);
+    if args.validate_workload && !reproducing {
+        validate_workloads(&hot_accounts, &workloads);
+        // Merged workloads carry one chain id, so each chain is validated on its own
+        for multi_chain in &multi_chains {
+            let hot_accounts: Vec<usize> =
+                multi_chain.chains.iter().map(|chain| chain.config.hot_accounts).collect();
+            validate_workloads(&hot_accounts, &multi_chain.chains);
         }
+    }
 
-        println!();
+    if let Some(url) = args.oracle_rpc.as_ref().filter(|_| !reproducing && !nonceless_workloads) {
+        run_oracle(url, &hot_accounts, &workloads);
     }
 
-    // Run MDBX sequential executor
-    #[cfg(feature = "mdbx")]
-    if run_mdbx_sequential {
-        print_section_header("MDBX Sequential Executor (Persistent storage)");
-        BenchmarkResult::print_header();
-
-        for &hot_accounts in &args.hot_accounts {
-            let hot_accounts_label = format!("{} accounts", hot_accounts);
-            
-            let workload_config = WorkloadConfig {
-                num_accounts: args.num_accounts,
-                num_transactions: args.num_transactions,
-                transactions_per_block: args.transactions_per_block,
-                hot_accounts,
-                seed: 42,
-                chain_id: 1,
-            };
+    if args.compare_reth && !reproducing && !nonceless_workloads {
+        compare_reth(args, &workloads);
+    }
 
-            let workload = Workload::generate(workload_config);
+    if let Some(path) = args.dump_conflict_hints.as_ref().filter(|_| !reproducing) {
+        dump_conflict_hints(args, path, &workloads);
+    }
 
-            let dir = tempdir().expect("Failed to create temp directory");
-            let executor = MdbxSequentialExecutor::new(dir.path(), verify_signatures)
-                .expect("Failed to create MDBX sequential executor");
+    if let Some(path) = args.record_schedule.as_ref().filter(|_| !reproducing) {
+        record_schedules(args, &executor_options, path, &workloads);
+    }
 
-            let start = Instant::now();
-            let (result, _) = executor
-                .execute_workload(&workload)
-                .expect("Execution failed");
-            let elapsed = start.elapsed();
-
-            let bench_result = BenchmarkResult {
-                hot_accounts_label,
-                executor_name: executor.name().to_string(),
-                preserves_order: executor.preserves_order(),
-                successful: result.successful,
-                failed: result.failed,
-                duration_ms: elapsed.as_secs_f64() * 1000.0,
-                throughput_tps: args.num_transactions as f64 / elapsed.as_secs_f64(),
-            };
+    // Recover signers before any cell runs, so none of them pays for it
+    if executor_options.trust_preverified && verify_signatures {
+        for workload in workloads.iter().chain(multi_chains.iter().map(|multi_chain| &multi_chain.workload)) {
+            workload.preverify();
+        }
+    }
 
-            bench_result.print();
-            all_results.push(bench_result);
+    let mut plan = BenchPlan::new();
+
+    // Registry executors, in registration order
+    for entry in registry.entries().iter().filter(|entry| selected.contains(&entry.name)) {
+        let executor = entry.create(&executor_options);
+        let unsupported = if token_workloads && !executor.supports_tokens() {
+            Some("no token balances")
+        } else if nonceless_workloads && !executor.supports_nonceless() {
+            Some("checks sender nonces")
+        } else if near_max_workloads && !executor.supports_large_balances() {
+            Some("balances must stay below 2^128")
+        } else {
+            None
+        };
+        if let Some(reason) = unsupported {
+            println!("Skipping {}: {}", entry.name, reason);
+            continue;
         }
 
-        println!();
+        let thread_counts: Vec<Option<usize>> = if entry.threaded {
+            args.threads.iter().copied().map(Some).collect()
+        } else {
+            vec![None]
+        };
+
+        // Plain workloads, then --chains workloads in a section of their own
+        let plain = workloads.iter().map(|workload| (workload, workload.config.hot_accounts, None)).collect();
+        let mut variants: Vec<(String, Vec<(&Workload, usize, Option<&ChainLayout>)>)> =
+            vec![(entry.title.clone(), plain)];
+        if !multi_chains.is_empty() {
+            let chained = multi_chains
+                .iter()
+                .map(|multi_chain| {
+                    (&multi_chain.workload, multi_chain.chains[0].config.hot_accounts, Some(&multi_chain.layout))
+                })
+                .collect();
+            variants.push((format!("{} ({} chains)", entry.title, args.chains.len()), chained));
+        }
+
+        for (section, runs) in &variants {
+            for &num_threads in &thread_counts {
+                // The batch stage or the signer cache replaces inline verification
+                let options = ExecutorOptions {
+                    num_threads: num_threads.unwrap_or(1),
+                    verify_signatures: verify_signatures && args.pre_verify.is_none() && !args.trust_preverified,
+                    ..executor_options
+                };
+                let group = num_threads.map(|num_threads| format!("{} threads", num_threads));
+
+                for &(workload, hot_accounts, layout) in runs {
+                    plan.add(section, group.clone(), move || {
+                        let hot_accounts_label = format!("{} accounts", hot_accounts);
+                        let mut executor = entry.create(&options);
+                        if let Some(verify_threads) = args.pre_verify.filter(|_| verify_signatures) {
+                            let verifier = CpuBatchVerifier::new(verify_threads).with_recovery(options.recovery);
+                            executor = Box::new(PreVerifiedExecutor::new(executor, Box::new(verifier)));
+                        }
+                        if options.trust_preverified && verify_signatures {
+                            executor = Box::new(PreVerifiedExecutor::trusting(executor));
+                        }
+                        if args.concurrency_stats {
+                            executor = Box::new(WithConcurrencyStats::new(executor));
+                        }
+                        if args.critical_path {
+                            let reference = SequentialExecutor::with_verification(verify_signatures)
+                                .with_recovery(options.recovery);
+                            executor = Box::new(WithCriticalPath::new(executor, reference));
+                        }
+                        if let Some(layout) = layout {
+                            executor = Box::new(WithChainBreakdown::new(executor, layout.clone()));
+                        }
+
+                        run_any(executor.as_ref(), workload, &hot_accounts_label, workload.config.num_transactions)
+                    });
+                }
+            }
+        }
     }
 
-    // Run MDBX batched executor
-    #[cfg(feature = "mdbx")]
-    if run_mdbx_batched {
-        print_section_header("MDBX Batched Executor (Block-level caching and commit)");
-        BenchmarkResult::print_header();
-
-        for &hot_accounts in &args.hot_accounts {
-            let hot_accounts_label = format!("{} accounts", hot_accounts);
-            
-            let workload_config = WorkloadConfig {
-                num_accounts: args.num_accounts,
-                num_transactions: args.num_transactions,
-                transactions_per_block: args.transactions_per_block,
-                hot_accounts,
-                seed: 42,
-                chain_id: 1,
-            };
+    // Conditional-write executor
+    if run_conditional_write {
+        let section = "Conditional-Write Executor (DynamoDB-style CAS, in-memory emulation)";
 
-            let workload = Workload::generate(workload_config);
+        for &num_threads in &args.threads {
+            for workload in &workloads {
+                let hot_accounts = workload.config.hot_accounts;
+                plan.add(section, Some(format!("{} threads", num_threads)), move || {
+                    let hot_accounts_label = format!("{} accounts", hot_accounts);
 
-            let dir = tempdir().expect("Failed to create temp directory");
-            let executor = MdbxBatchedExecutor::new(dir.path(), verify_signatures)
-                .expect("Failed to create MDBX batched executor");
-
-            let start = Instant::now();
-            let (result, _) = executor
-                .execute_workload(&workload)
-                .expect("Execution failed");
-            let elapsed = start.elapsed();
-
-            let bench_result = BenchmarkResult {
-                hot_accounts_label,
-                executor_name: executor.name().to_string(),
-                preserves_order: executor.preserves_order(),
-                successful: result.total_successful,
-                failed: result.total_failed,
-                duration_ms: elapsed.as_secs_f64() * 1000.0,
-                throughput_tps: args.num_transactions as f64 / elapsed.as_secs_f64(),
-            };
+                    let store = InMemoryConditionalStore::new()
+                        .with_latency(Duration::from_micros(args.kv_latency_us));
+                    let executor = ConditionalWriteExecutor::new(num_threads, verify_signatures)
+                        .with_recovery(executor_options.recovery);
 
-            bench_result.print();
-            all_results.push(bench_result);
+                    let start = Instant::now();
+                    let result = executor.execute_workload(&store, workload);
+                    let elapsed = start.elapsed();
+
+                    let bench_result = BenchResult {
+                        hot_accounts_label,
+                        executor_name: format!("{}_{}t", executor.name(), num_threads),
+                        preserves_order: executor.preserves_order(),
+                        successful: result.result.successful,
+                        failed: result.result.failed,
+                        duration_ms: elapsed.as_secs_f64() * 1000.0,
+                        throughput_tps: workload.config.num_transactions as f64 / elapsed.as_secs_f64(),
+                    };
+
+                    CellOutput {
+                        result: bench_result,
+                        details: vec![format!(
+                            "writes: {}, condition failures: {}, waits: {}",
+                            result.writes, result.condition_failures, result.waits
+                        )],
+                    }
+                });
+            }
         }
+    }
 
-        println!();
+    // Remote executor
+    #[cfg(feature = "remote")]
+    if let Some(remote_addr) = args.remote.as_ref().filter(|_| !own_state_only) {
+        let section = format!("Remote Batched Executor (TCP server at {})", remote_addr);
+
+        for workload in &workloads {
+            let hot_accounts = workload.config.hot_accounts;
+            plan.add(&section, None, move || {
+                let hot_accounts_label = format!("{} accounts", hot_accounts);
+                let executor = RemoteDbExecutor::connect(remote_addr, verify_signatures)
+                    .expect("Failed to connect to remote database server");
+
+                let start = Instant::now();
+                let result = executor
+                    .execute_workload(workload)
+                    .expect("Execution failed");
+                let elapsed = start.elapsed();
+
+                let bench_result = BenchResult {
+                    hot_accounts_label,
+                    executor_name: executor.name().to_string(),
+                    preserves_order: executor.preserves_order(),
+                    successful: result.result.successful,
+                    failed: result.result.failed,
+                    duration_ms: elapsed.as_secs_f64() * 1000.0,
+                    throughput_tps: workload.config.num_transactions as f64 / elapsed.as_secs_f64(),
+                };
+
+                CellOutput {
+                    result: bench_result,
+                    details: vec![format!(
+                        "round trips: {}, sent: {} B, received: {} B",
+                        result.network.round_trips,
+                        result.network.bytes_sent,
+                        result.network.bytes_received
+                    )],
+                }
+            });
+        }
     }
 
-    // Run Block-STM parallel executor
+    // Sharded Block-STM executor
     #[cfg(feature = "block-stm")]
-    if run_block_stm {
-        print_section_header("Block-STM Parallel Executor (Optimistic concurrency)");
+    if run_sharded_block_stm {
+        let section = format!(
+            "Sharded Block-STM Executor ({} shards, cross-shard applied serially)",
+            args.shards
+        );
 
         for &num_threads in &args.threads {
-            println!("--- {} threads ---", num_threads);
-            BenchmarkResult::print_header();
+            for workload in &workloads {
+                let hot_accounts = workload.config.hot_accounts;
+                plan.add(&section, Some(format!("{} threads", num_threads)), move || {
+                    let hot_accounts_label = format!("{} accounts", hot_accounts);
+                    let executor = ShardedBlockStmExecutor::new(args.shards, num_threads, verify_signatures);
 
-            for &hot_accounts in &args.hot_accounts {
-                let hot_accounts_label = format!("{} accounts", hot_accounts);
-                
-                let workload_config = WorkloadConfig {
-                    num_accounts: args.num_accounts,
-                    num_transactions: args.num_transactions,
-                    transactions_per_block: args.transactions_per_block,
-                    hot_accounts,
-                    seed: 42,
-                    chain_id: 1,
-                };
+                    let start = Instant::now();
+                    let (_, result) = executor.execute_workload(workload);
+                    let elapsed = start.elapsed();
+
+                    let bench_result = BenchResult {
+                        hot_accounts_label,
+                        executor_name: format!("{}_{}t", executor.name(), num_threads),
+                        preserves_order: executor.preserves_order(),
+                        successful: result.result.successful,
+                        failed: result.result.failed,
+                        duration_ms: elapsed.as_secs_f64() * 1000.0,
+                        throughput_tps: workload.config.num_transactions as f64 / elapsed.as_secs_f64(),
+                    };
+
+                    CellOutput {
+                        result: bench_result,
+                        details: vec![format!(
+                            "cross-shard: {}, deferred: {:.1}%, shard phase: {:.2} ms, serial phase: {:.2} ms",
+                            result.cross_shard,
+                            result.deferred_fraction() * 100.0,
+                            result.shard_time.as_secs_f64() * 1000.0,
+                            result.serial_time.as_secs_f64() * 1000.0
+                        )],
+                    }
+                });
+            }
+        }
+    }
 
-                let workload = Workload::generate(workload_config);
-                let executor = BlockStmExecutor::new(num_threads, verify_signatures);
+    // Pipelined Block-STM executor persisting each block to MDBX
+    #[cfg(all(feature = "block-stm", feature = "mdbx"))]
+    if run_pipelined_block_stm {
+        let section = "Pipelined Block-STM Executor (Block N+1 executes while block N commits to MDBX)";
+
+        for &num_threads in &args.threads {
+            for workload in &workloads {
+                let hot_accounts = workload.config.hot_accounts;
+                plan.add(section, Some(format!("{} threads", num_threads)), move || {
+                    let hot_accounts_label = format!("{} accounts", hot_accounts);
+                    let executor = block_stm_with_options(
+                        BlockStmExecutor::new(num_threads, verify_signatures),
+                        &executor_options.block_stm,
+                    );
+
+                    let dir = tempdir().expect("Failed to create temp directory");
+                    let mut db = MdbxDatabase::create_with_options(dir.path(), &executor_options.mdbx)
+                        .expect("Failed to create MDBX database");
+                    if let Some(background) = executor_options.background(workload.config.seed) {
+                        db.load_background(&background).expect("Failed to load background accounts");
+                    }
+                    let initial_balance = workload.initial_balance();
+                    let accounts: Vec<_> =
+                        workload.accounts.iter().map(|account| (account.address, initial_balance)).collect();
+                    db.init_accounts(&accounts).expect("Failed to initialize accounts");
+
+                    let (_, result) = executor
+                        .execute_pipelined(workload, &mut db)
+                        .expect("Execution failed");
 
-                let result = run_in_memory_benchmark(&executor, &workload, &hot_accounts_label, args.num_transactions);
-                result.print();
-                all_results.push(result);
+                    let bench_result = BenchResult {
+                        hot_accounts_label,
+                        executor_name: format!("block_stm_pipelined_{}t", num_threads),
+                        preserves_order: executor.preserves_order(),
+                        successful: result.result.successful,
+                        failed: result.result.failed,
+                        duration_ms: result.elapsed.as_secs_f64() * 1000.0,
+                        throughput_tps: workload.config.num_transactions as f64 / result.elapsed.as_secs_f64(),
+                    };
+
+                    let footprint =
+                        Footprint::of_workload(dir.path(), workload, executor_options.background_accounts);
+                    CellOutput {
+                        result: bench_result,
+                        details: vec![
+                            format!(
+                                "commit: {:.2} ms over {} blocks, stall: {:.2} ms, drain: {:.2} ms ({:.1}% of run)",
+                                result.stats.commit_time.as_secs_f64() * 1000.0,
+                                result.stats.blocks,
+                                result.stats.stall_time.as_secs_f64() * 1000.0,
+                                result.stats.drain_time.as_secs_f64() * 1000.0,
+                                result.stall_fraction() * 100.0
+                            ),
+                            format!("storage: {}", footprint),
+                        ],
+                    }
+                });
             }
+        }
+    }
 
-            println!();
+    // Block-STM with conflict hints from an earlier run
+    #[cfg(feature = "block-stm")]
+    if let Some(path) = &args.conflict_hints {
+        let hints = Arc::new(ConflictHints::load(path).unwrap_or_else(|err| {
+            eprintln!("Failed to read conflict hints from {}: {}", path, err);
+            std::process::exit(1);
+        }));
+        let section = format!("Block-STM Parallel Executor (Conflict hints, {} pairs)", hints.len());
+
+        for &num_threads in &args.threads {
+            for workload in &workloads {
+                let hot_accounts = workload.config.hot_accounts;
+                let hints = hints.clone();
+                plan.add(&section, Some(format!("{} threads", num_threads)), move || {
+                    let hot_accounts_label = format!("{} accounts", hot_accounts);
+                    let mut executor = BlockStmExecutor::new(num_threads, verify_signatures).with_hints(hints.clone());
+                    if let Some(lookahead) = executor_options.block_stm.lookahead {
+                        executor = executor.with_lookahead(lookahead);
+                    }
+                    if let Some(bytes) = executor_options.block_stm.memory_budget {
+                        executor = executor.with_memory_budget(bytes);
+                    }
+                    if executor_options.block_stm.per_block {
+                        executor = executor.with_block_boundaries();
+                    }
+                    if executor_options.block_stm.atomic_blocks {
+                        executor = executor.with_atomic_blocks();
+                    }
+
+                    run_any(&executor, workload, &hot_accounts_label, workload.config.num_transactions)
+                });
+            }
         }
     }
 
-    // Run FoundationDB parallel executor
-    #[cfg(feature = "fdb")]
-    if run_fdb {
-        print_section_header("FoundationDB Parallel Executor (Distributed transactional)");
+    #[cfg(not(feature = "block-stm"))]
+    if args.conflict_hints.is_some() {
+        eprintln!("--conflict-hints requires rebuilding with --features block-stm");
+        std::process::exit(1);
+    }
 
-        // Initialize FDB network once
-        let _fdb_network = unsafe { foundationdb::boot() };
-        std::thread::sleep(std::time::Duration::from_millis(100));
+    // Block-STM with the analyzer's dependencies declared up front
+    #[cfg(feature = "block-stm")]
+    if run_declared_dependencies {
+        let section = "Block-STM Parallel Executor (Declared dependencies)";
 
         for &num_threads in &args.threads {
-            println!("--- {} threads ---", num_threads);
-            BenchmarkResult::print_header();
+            for workload in &workloads {
+                let hot_accounts = workload.config.hot_accounts;
+                plan.add(section, Some(format!("{} threads", num_threads)), move || {
+                    let hot_accounts_label = format!("{} accounts", hot_accounts);
+                    let executor = block_stm_with_options(
+                        BlockStmExecutor::new(num_threads, verify_signatures),
+                        &executor_options.block_stm,
+                    );
+                    // An untimed run without declarations shows what they replace
+                    let discovered = executor.profile_conflicts(workload).len();
+                    let declared: usize = declared_dependencies(workload).iter().map(Vec::len).sum();
+
+                    let executor = executor.with_declared_dependencies();
+                    run_any(&executor, workload, &hot_accounts_label, workload.config.num_transactions).with_detail(
+                        format!(
+                            "declared: {} dependencies, discovered without them: {} conflicts",
+                            declared, discovered
+                        ),
+                    )
+                });
+            }
+        }
+    }
 
-            for &hot_accounts in &args.hot_accounts {
+    // Block-STM replaying dispatch orders recorded in an earlier run
+    #[cfg(feature = "block-stm")]
+    if let Some(path) = &args.replay_schedule {
+        let log = ScheduleLog::load(path).unwrap_or_else(|err| {
+            eprintln!("Failed to read schedules from {}: {}", path, err);
+            std::process::exit(1);
+        });
+        let section = format!("Block-STM Parallel Executor (Replayed schedule, {} blocks)", log.len());
+
+        for workload in &workloads {
+            let hash = workload_hash(workload);
+            let Some(num_threads) = log.num_threads_for(hash) else {
+                eprintln!(
+                    "No schedule recorded for the {}-account workload in {}; skipping it",
+                    workload.config.hot_accounts, path
+                );
+                continue;
+            };
+            let schedules = Arc::new(log.schedules_for(hash));
+            let hot_accounts = workload.config.hot_accounts;
+            plan.add(&section, Some(format!("{} threads", num_threads)), move || {
                 let hot_accounts_label = format!("{} accounts", hot_accounts);
-                
-                let workload_config = WorkloadConfig {
-                    num_accounts: args.num_accounts,
-                    num_transactions: args.num_transactions,
-                    transactions_per_block: args.transactions_per_block,
-                    hot_accounts,
-                    seed: 42,
-                    chain_id: 1,
+                let executor = block_stm_with_options(
+                    BlockStmExecutor::new(num_threads, verify_signatures).with_schedule_replay(schedules.clone()),
+                    &executor_options.block_stm,
+                );
+
+                let start = Instant::now();
+                let (result, divergences) = executor.execute_replay(workload);
+                let elapsed = start.elapsed();
+
+                let bench_result = BenchResult {
+                    hot_accounts_label,
+                    executor_name: format!("{}_replay_{}t", executor.name(), num_threads),
+                    preserves_order: executor.preserves_order(),
+                    successful: result.successful,
+                    failed: result.failed,
+                    duration_ms: elapsed.as_secs_f64() * 1000.0,
+                    throughput_tps: workload.config.num_transactions as f64 / elapsed.as_secs_f64(),
                 };
+                let diverged: Vec<String> = divergences
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(block, divergence)| {
+                        divergence.map(|event| format!("block {} diverged at event {}", block, event))
+                    })
+                    .collect();
+
+                CellOutput {
+                    result: bench_result,
+                    details: vec![if diverged.is_empty() {
+                        "schedule followed".to_string()
+                    } else {
+                        diverged.join(", ")
+                    }],
+                }
+            });
+        }
+    }
 
-                let workload = Workload::generate(workload_config);
+    #[cfg(not(feature = "block-stm"))]
+    if args.replay_schedule.is_some() {
+        eprintln!("--replay-schedule requires rebuilding with --features block-stm");
+        std::process::exit(1);
+    }
 
-                let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-                let (result, elapsed) = rt.block_on(async {
-                    let executor = FdbParallelExecutor::new(num_threads, verify_signatures)
-                        .await
-                        .expect("Failed to create FDB executor");
+    // Initialize FDB network once for every FDB-backed executor
+    #[cfg(feature = "fdb")]
+    let _fdb_network = boot_fdb(&selected);
+
+    let mut runner = BenchRunner::new()
+        .with_repetitions(args.repetitions)
+        .with_cooldown(Duration::from_secs(args.cooldown_secs))
+        .with_printing(!args.tui);
+    if args.interleave {
+        runner = runner.with_interleave(args.interleave_seed);
+    }
+    if args.sample_frequency {
+        runner = runner.with_frequency_sampling(Duration::from_millis(100), args.freq_variance_threshold);
+    }
+    if let Some(index) = only_cell {
+        runner = runner.with_only_cell(index);
+    }
+    let report = if args.tui { run_with_dashboard(runner, &plan) } else { runner.run(&plan) };
 
-                    let start = Instant::now();
-                    let result = executor
-                        .execute_workload(&workload)
-                        .await
-                        .expect("Execution failed");
-                    let elapsed = start.elapsed();
+    // Run multi-tenant mode: concurrent workloads against one shared backend
+    #[cfg(feature = "mdbx")]
+    if !args.multi_tenant.is_empty() && selected.contains(&"mdbx_sequential") && !own_state_only && !reproducing {
+        print_section_header(&format!(
+            "MDBX Multi-Tenant ({} tenants sharing one environment)",
+            args.multi_tenant.len()
+        ));
+
+        for workloads in args.tenant_workloads() {
+            if args.workload.seeds > 1 {
+                println!("--- seed {} ---", workloads[0].config.seed);
+            }
+            let isolated = run_isolated(&workloads, |_, workload| {
+                let dir = tempdir().expect("Failed to create temp directory");
+                let executor = MdbxSequentialExecutor::new_with_options(
+                    dir.path(),
+                    verify_signatures,
+                    &executor_options.mdbx,
+                )
+                .expect("Failed to create MDBX sequential executor")
+                .with_recovery(executor_options.recovery);
+                executor.execute_workload(workload).expect("Execution failed").0
+            });
+
+            let dir = tempdir().expect("Failed to create temp directory");
+            let executor = MdbxSequentialExecutor::new_with_options(
+                dir.path(),
+                verify_signatures,
+                &executor_options.mdbx,
+            )
+            .expect("Failed to create MDBX sequential executor")
+            .with_recovery(executor_options.recovery);
+            let shared = run_concurrent(&workloads, |_, workload| {
+                executor.execute_workload(workload).expect("Execution failed").0
+            });
+
+            print_tenant_results(&args.multi_tenant, &shared, &isolated);
+            println!();
+        }
+    }
+
+    #[cfg(feature = "fdb")]
+    if !args.multi_tenant.is_empty() && run_fdb_parallel && !reproducing {
+        print_section_header(&format!(
+            "FoundationDB Multi-Tenant ({} tenants sharing one cluster)",
+            args.multi_tenant.len()
+        ));
 
-                    (result, elapsed)
+        let seeded_workloads = args.tenant_workloads();
+        for &num_threads in &args.threads {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            let executor = rt
+                .block_on(FdbParallelExecutor::new(num_threads, verify_signatures))
+                .expect("Failed to create FDB executor")
+                .with_codec(codec)
+                .with_options(&executor_options.fdb);
+
+            for workloads in &seeded_workloads {
+                if args.workload.seeds > 1 {
+                    println!("--- {} threads per tenant, seed {} ---", num_threads, workloads[0].config.seed);
+                } else {
+                    println!("--- {} threads per tenant ---", num_threads);
+                }
+
+                let isolated = run_isolated(workloads, |_, workload| {
+                    rt.block_on(async {
+                        executor.clear_database().await.expect("Failed to clear FDB");
+                        executor.execute_workload_shared(workload).await
+                    })
+                    .expect("Execution failed")
+                    .to_execution_result()
                 });
 
-                let bench_result = BenchmarkResult {
-                    hot_accounts_label,
-                    executor_name: format!("fdb_parallel_{}t", num_threads),
-                    preserves_order: false,
-                    successful: result.total_successful,
-                    failed: result.total_failed,
-                    duration_ms: elapsed.as_secs_f64() * 1000.0,
-                    throughput_tps: args.num_transactions as f64 / elapsed.as_secs_f64(),
-                };
+                rt.block_on(executor.clear_database()).expect("Failed to clear FDB");
+                let shared = run_concurrent(workloads, |_, workload| {
+                    // Each tenant drives its own runtime so neither blocks the other
+                    tokio::runtime::Builder::new_current_thread()
+                        .build()
+                        .expect("Failed to create tokio runtime")
+                        .block_on(executor.execute_workload_shared(workload))
+                        .expect("Execution failed")
+                        .to_execution_result()
+                });
 
-                bench_result.print();
-                all_results.push(bench_result);
+                print_tenant_results(&args.multi_tenant, &shared, &isolated);
+                println!();
             }
+        }
+    }
 
-            println!();
+    // Run the soak: one workload's rounds against one persistent database
+    #[cfg(feature = "mdbx")]
+    if let Some(minutes) = args.soak_minutes.filter(|_| !reproducing) {
+        match workloads.first() {
+            Some(_) if own_state_only => {
+                println!("⚠️  Soak skipped: MDBX holds neither token balances nor nonce-less ids");
+                println!();
+            }
+            Some(workload) => run_soak(args, &executor_options, workload, minutes, verify_signatures),
+            None => {}
         }
     }
 
     // Print warnings for unavailable executors
-    #[cfg(not(feature = "mdbx"))]
-    if run_mdbx_sequential || run_mdbx_batched {
-        println!("⚠️  MDBX executors not available (rebuild with --features mdbx)");
+    for (name, features) in &unavailable {
+        println!("⚠️  {} executor not available (rebuild with --features {})", name, features);
         println!();
     }
 
-    #[cfg(not(feature = "fdb"))]
-    if run_fdb {
-        println!("⚠️  FoundationDB executor not available (rebuild with --features fdb)");
+    #[cfg(not(feature = "mdbx"))]
+    if args.soak_minutes.is_some() {
+        println!("⚠️  Soak mode not available (rebuild with --features mdbx)");
         println!();
     }
 
     #[cfg(not(feature = "block-stm"))]
-    if run_block_stm {
+    if run_sharded_block_stm || run_declared_dependencies {
         println!("⚠️  Block-STM executor not available (rebuild with --features block-stm)");
         println!();
     }
 
+    #[cfg(not(all(feature = "block-stm", feature = "mdbx")))]
+    if run_pipelined_block_stm {
+        println!("⚠️  Pipelined Block-STM executor not available (rebuild with --features block-stm,mdbx)");
+        println!();
+    }
+
+    #[cfg(not(feature = "remote"))]
+    if args.remote.is_some() {
+        println!("⚠️  Remote executor not available (rebuild with --features remote)");
+        println!();
+    }
+
     // Print summary
-    print_summary(&all_results);
+    report.print_summary();
+    if args.evm_overhead {
+        report.print_paired_overhead(
+            "EVM Overhead (revm vs native transfers)",
+            "sequential_in_memory",
+            "native_transfer_in_memory",
+        );
+    }
+    if args.output == OutputFormat::Markdown {
+        print_section_header("Markdown Results");
+        println!("{}", markdown::render(&environment, &report));
+    }
+    if let Some(format) = args.charts.as_ref().filter(|_| !reproducing) {
+        draw_charts(&report, args.manifest.as_deref(), format);
+    }
+    if let Some(path) = args.results_json.as_ref().filter(|_| !reproducing) {
+        write_results_json(path, &report);
+    }
+
+    let executor_opts = args
+        .executor_opts
+        .iter()
+        .map(|opt| format!("{}={}={}", opt.executor, opt.key, opt.value))
+        .collect();
+    Some(RunManifest::capture(&environment, raw_args, executor_opts, &workloads, &report))
 }
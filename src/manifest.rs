@@ -0,0 +1,373 @@
+//! Machine-readable run manifests.
+//!
+//! A [`RunManifest`] records everything needed to re-run a benchmark cell:
+//! the build (git revision and features), the command line, the workload
+//! configurations and seeds with a hash of the generated transactions, the
+//! executor options, and one record per cell run with a hash of its outcome
+//! and the detail lines printed under it.
+//! `db-test repro` re-parses the recorded command line, checks that it
+//! regenerates the same workloads, and runs a single cell.
+//!
+//! The file is tab-separated text, one record per line, with the record kind
+//! in the first field. Lines starting with `#` are comments.
+//!
+//! Result hashes cover the deterministic part of a result (executor, label,
+//! ordering and success/failure counts), never timings, so a faithful
+//! reproduction has the same hash.
+
+use crate::environment::EnvironmentInfo;
+use crate::harness::{BenchReport, BenchResult, CellReport};
+use crate::workload_file::{format_config, parse_config, WORKLOAD_FILE_VERSION};
+use crate::{Workload, WorkloadConfig};
+use alloy_primitives::{keccak256, B256};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Manifest format version written to the `version` record, which must
+/// precede the workloads. Version 1 manifests, whose workload records have
+/// the configuration fields of version 1 workload files, are still read.
+pub const MANIFEST_VERSION: u32 = 2;
+
+/// Error reading or writing a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestError(pub String);
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// A generated workload: its configuration and the hash of its transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkloadRecord {
+    pub config: WorkloadConfig,
+    pub hash: B256,
+}
+
+impl WorkloadRecord {
+    /// Records `workload`.
+    pub fn new(workload: &Workload) -> Self {
+        Self {
+            config: workload.config.clone(),
+            hash: workload_hash(workload),
+        }
+    }
+}
+
+/// One run of one benchmark cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellRecord {
+    /// Position of the cell in the plan; pass to `repro --cell`.
+    pub index: usize,
+    pub repetition: usize,
+    pub section: String,
+    pub group: Option<String>,
+    pub executor_name: String,
+    pub hot_accounts_label: String,
+    pub preserves_order: bool,
+    pub successful: usize,
+    pub failed: usize,
+    pub duration_ms: f64,
+    pub throughput_tps: f64,
+    pub result_hash: B256,
+    /// Detail lines printed under the result, such as its failure breakdown.
+    pub details: Vec<String>,
+}
+
+/// Everything needed to reproduce the cells of one benchmark run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunManifest {
+    pub git_hash: String,
+    pub features: Vec<String>,
+    /// Command-line arguments, excluding the program name.
+    pub args: Vec<String>,
+    /// `--executor-opt` values as `name=key=value`.
+    pub executor_options: Vec<String>,
+    pub workloads: Vec<WorkloadRecord>,
+    pub cells: Vec<CellRecord>,
+}
+
+impl RunManifest {
+    /// Builds a manifest for `report`, produced by running `workloads` with `args`.
+    pub fn capture(
+        environment: &EnvironmentInfo,
+        args: Vec<String>,
+        executor_options: Vec<String>,
+        workloads: &[Workload],
+        report: &BenchReport,
+    ) -> Self {
+        Self {
+            git_hash: environment.git_hash.clone(),
+            features: environment.features.iter().map(|feature| feature.to_string()).collect(),
+            args,
+            executor_options,
+            workloads: workloads.iter().map(WorkloadRecord::new).collect(),
+            cells: report
+                .cells
+                .iter()
+                .map(|cell| CellRecord {
+                    index: cell.index,
+                    repetition: cell.repetition,
+                    section: cell.section.clone(),
+                    group: cell.group.clone(),
+                    executor_name: cell.result.executor_name.clone(),
+                    hot_accounts_label: cell.result.hot_accounts_label.clone(),
+                    preserves_order: cell.result.preserves_order,
+                    successful: cell.result.successful,
+                    failed: cell.result.failed,
+                    duration_ms: cell.result.duration_ms,
+                    throughput_tps: cell.result.throughput_tps,
+                    result_hash: result_hash(&cell.result),
+                    details: cell.details.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Records of the first run of the cell at `index`, if it ran.
+    pub fn cell(&self, index: usize) -> Option<&CellRecord> {
+        self.cells.iter().find(|cell| cell.index == index)
+    }
+
+    /// Rebuilds the recorded results as a report, e.g. to print them again.
+    ///
+    /// Frequency samples are not recorded and come back empty.
+    pub fn to_report(&self) -> BenchReport {
+        BenchReport {
+            cells: self
+                .cells
+                .iter()
+                .map(|cell| CellReport {
+                    index: cell.index,
+                    section: cell.section.clone(),
+                    group: cell.group.clone(),
+                    repetition: cell.repetition,
+                    result: BenchResult {
+                        hot_accounts_label: cell.hot_accounts_label.clone(),
+                        executor_name: cell.executor_name.clone(),
+                        preserves_order: cell.preserves_order,
+                        successful: cell.successful,
+                        failed: cell.failed,
+                        duration_ms: cell.duration_ms,
+                        throughput_tps: cell.throughput_tps,
+                    },
+                    details: cell.details.clone(),
+                    frequency: None,
+                    unstable: false,
+                })
+                .collect(),
+            frequency_threshold: None,
+        }
+    }
+
+    /// Serializes the manifest.
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            "# db-test run manifest".to_string(),
+            format!("version\t{}", MANIFEST_VERSION),
+            format!("git_hash\t{}", self.git_hash),
+        ];
+        lines.extend(self.features.iter().map(|feature| format!("feature\t{}", feature)));
+        lines.extend(self.args.iter().map(|arg| format!("arg\t{}", escape(arg))));
+        lines.extend(self.executor_options.iter().map(|opt| format!("executor_opt\t{}", escape(opt))));
+        for workload in &self.workloads {
+            lines.push(format!("workload\t{}\t{}", format_config(&workload.config), workload.hash));
+        }
+        for cell in &self.cells {
+            lines.push(format!(
+                "cell\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                cell.index,
+                cell.repetition,
+                escape(&cell.section),
+                escape(cell.group.as_deref().unwrap_or("")),
+                escape(&cell.executor_name),
+                escape(&cell.hot_accounts_label),
+                cell.preserves_order,
+                cell.successful,
+                cell.failed,
+                cell.duration_ms,
+                cell.throughput_tps,
+                cell.result_hash
+            ));
+        }
+        for cell in &self.cells {
+            for detail in &cell.details {
+                lines.push(format!("detail\t{}\t{}\t{}", cell.index, cell.repetition, escape(detail)));
+            }
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// Parses a manifest written by [`to_text`](Self::to_text).
+    pub fn from_text(text: &str) -> Result<Self, ManifestError> {
+        let mut manifest = Self::default();
+        let mut workload_file_version = None;
+        for (number, line) in text.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || ManifestError(format!("line {}: invalid record '{}'", number + 1, line));
+            let fields: Vec<&str> = line.split('\t').collect();
+            match (fields[0], fields.len()) {
+                ("version", 2) => {
+                    workload_file_version = match fields[1].parse::<u32>() {
+                        Ok(1) => Some(1),
+                        Ok(MANIFEST_VERSION) => Some(WORKLOAD_FILE_VERSION),
+                        _ => return Err(ManifestError(format!("unsupported manifest version {}", fields[1]))),
+                    };
+                }
+                ("git_hash", 2) => manifest.git_hash = fields[1].to_string(),
+                ("feature", 2) => manifest.features.push(fields[1].to_string()),
+                ("arg", 2) => manifest.args.push(unescape(fields[1])),
+                ("executor_opt", 2) => manifest.executor_options.push(unescape(fields[1])),
+                ("workload", len @ 3..) => manifest.workloads.push(WorkloadRecord {
+                    config: parse_config(&fields[1..len - 1], workload_file_version.ok_or_else(invalid)?)
+                        .ok_or_else(invalid)?,
+                    hash: parse(fields[len - 1]).ok_or_else(invalid)?,
+                }),
+                ("cell", 13) => manifest.cells.push(CellRecord {
+                    index: parse(fields[1]).ok_or_else(invalid)?,
+                    repetition: parse(fields[2]).ok_or_else(invalid)?,
+                    section: unescape(fields[3]),
+                    group: Some(unescape(fields[4])).filter(|group| !group.is_empty()),
+                    executor_name: unescape(fields[5]),
+                    hot_accounts_label: unescape(fields[6]),
+                    preserves_order: parse(fields[7]).ok_or_else(invalid)?,
+                    successful: parse(fields[8]).ok_or_else(invalid)?,
+                    failed: parse(fields[9]).ok_or_else(invalid)?,
+                    duration_ms: parse(fields[10]).ok_or_else(invalid)?,
+                    throughput_tps: parse(fields[11]).ok_or_else(invalid)?,
+                    result_hash: parse(fields[12]).ok_or_else(invalid)?,
+                    details: Vec::new(),
+                }),
+                ("detail", 4) => {
+                    let index: usize = parse(fields[1]).ok_or_else(invalid)?;
+                    let repetition: usize = parse(fields[2]).ok_or_else(invalid)?;
+                    let cell = manifest
+                        .cells
+                        .iter_mut()
+                        .find(|cell| cell.index == index && cell.repetition == repetition)
+                        .ok_or_else(invalid)?;
+                    cell.details.push(unescape(fields[3]));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Reads a manifest from `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|err| ManifestError(format!("failed to read {}: {}", path.display(), err)))?;
+        Self::from_text(&text)
+    }
+
+    /// Writes the manifest to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ManifestError> {
+        let path = path.as_ref();
+        fs::write(path, self.to_text())
+            .map_err(|err| ManifestError(format!("failed to write {}: {}", path.display(), err)))
+    }
+}
+
+/// Hash of every transaction hash in `workload`, in execution order.
+pub fn workload_hash(workload: &Workload) -> B256 {
+    let mut bytes = Vec::with_capacity(workload.transactions.len() * 32);
+    for tx in &workload.transactions {
+        bytes.extend_from_slice(tx.tx_hash.as_slice());
+    }
+    keccak256(bytes)
+}
+
+/// Hash of the deterministic fields of `result`.
+pub fn result_hash(result: &BenchResult) -> B256 {
+    keccak256(format!(
+        "{}\t{}\t{}\t{}\t{}",
+        result.executor_name, result.hot_accounts_label, result.preserves_order, result.successful, result.failed
+    ))
+}
+
+fn parse<T: std::str::FromStr>(field: &str) -> Option<T> {
+    field.parse().ok()
+}
+
+/// Escapes tabs, newlines and backslashes so a value fits in one field.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(field: &str) -> String {
+    let mut value = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => value.push('\t'),
+            Some('n') => value.push('\n'),
+            Some(other) => value.push(other),
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::{run_in_memory, BenchPlan, BenchRunner, CellOutput};
+    use crate::SequentialExecutor;
+
+    #[test]
+    fn test_manifest_round_trip_and_single_cell_repro() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            ..Default::default()
+        });
+
+        let mut plan = BenchPlan::new();
+        for section in ["first", "second\twith tab"] {
+            let workload = &workload;
+            plan.add(section, Some("1 threads".to_string()), move || {
+                let executor = SequentialExecutor::with_verification(false);
+                CellOutput::new(run_in_memory(&executor, workload, "10 accounts", 20)).with_detail("failures: none")
+            });
+        }
+        let report = BenchRunner::new().with_repetitions(2).run(&plan);
+
+        let manifest = RunManifest::capture(
+            &EnvironmentInfo::capture(),
+            vec!["--sequential".to_string(), "-H".to_string(), "10".to_string()],
+            vec!["block-stm=lookahead=64".to_string()],
+            std::slice::from_ref(&workload),
+            &report,
+        );
+        assert_eq!(manifest.cells.len(), 4);
+        assert_eq!(RunManifest::from_text(&manifest.to_text()).unwrap(), manifest);
+        assert_eq!(manifest.to_report().summary()[0].runs, 4);
+        assert_eq!(manifest.to_report().cells[3].details, ["failures: none"]);
+
+        // Re-running one cell reproduces its recorded hash
+        let repro = BenchRunner::new().with_only_cell(1).run(&plan);
+        assert_eq!(repro.cells.len(), 1);
+        assert_eq!(repro.cells[0].index, 1);
+        assert_eq!(result_hash(&repro.cells[0].result), manifest.cell(1).unwrap().result_hash);
+        assert_eq!(manifest.workloads[0].hash, workload_hash(&Workload::generate(workload.config.clone())));
+
+        assert!(RunManifest::from_text("cell\tnot-a-number").is_err());
+        // Details must follow the cell they belong to
+        assert!(RunManifest::from_text("detail\t0\t0\tfailures: none").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! GitHub-flavored Markdown rendering of benchmark results.
+//!
+//! [`render`] turns a [`BenchReport`] into text that can be pasted straight
+//! into an issue or PR description: the environment the run happened in,
+//! one table per section, the per-executor summary and the per-conflict
+//! ranking.
+
+use crate::environment::EnvironmentInfo;
+use crate::harness::{BenchReport, CellReport, BASELINE_EXECUTOR};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// How the benchmark runner presents its results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned text tables, streamed while cells run.
+    #[default]
+    Text,
+    /// Text while running, then every table again as Markdown.
+    Markdown,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(format!("unknown output format '{}' (expected text or markdown)", s)),
+        }
+    }
+}
+
+/// Renders `report`, run on `environment`, as Markdown.
+pub fn render(environment: &EnvironmentInfo, report: &BenchReport) -> String {
+    let mut out = String::new();
+
+    out.push_str("### Environment\n\n");
+    let _ = writeln!(out, "- CPU: {} ({} cores)", escape(&environment.cpu_model), environment.cores);
+    if let Some(gib) = environment.memory_gib() {
+        let _ = writeln!(out, "- Memory: {:.1} GiB", gib);
+    }
+    let _ = writeln!(out, "- Kernel: {}", escape(&environment.kernel));
+    let _ = writeln!(out, "- Compiler: {}", escape(&environment.rustc_version));
+    let _ = writeln!(out, "- Build: `{}`", environment.git_hash);
+    let features = if environment.features.is_empty() {
+        "none".to_string()
+    } else {
+        environment.features.join(", ")
+    };
+    let _ = writeln!(out, "- Features: {}", features);
+
+    let mut sections: Vec<(&str, Vec<&CellReport>)> = Vec::new();
+    for cell in &report.cells {
+        match sections.iter_mut().find(|(section, _)| *section == cell.section) {
+            Some((_, cells)) => cells.push(cell),
+            None => sections.push((&cell.section, vec![cell])),
+        }
+    }
+    for (section, cells) in sections {
+        let _ = write!(out, "\n### {}\n\n", escape(section));
+        out.push_str("| Hot Accounts | Executor | Group | Ordering | Successful | Failed | Time (ms) | TPS |\n");
+        out.push_str("|---|---|---|---|--:|--:|--:|--:|\n");
+        for cell in cells {
+            let result = &cell.result;
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {:.2} | {:.0} |",
+                escape(&result.hot_accounts_label),
+                escape(&result.executor_name),
+                escape(cell.group.as_deref().unwrap_or("")),
+                if result.preserves_order { "strict" } else { "loose" },
+                result.successful,
+                result.failed,
+                result.duration_ms,
+                result.throughput_tps
+            );
+        }
+    }
+
+    if report.cells.is_empty() {
+        return out;
+    }
+
+    out.push_str("\n### Summary\n\n");
+    out.push_str("| Executor | Runs | Avg TPS | Min TPS | Max TPS |\n");
+    out.push_str("|---|--:|--:|--:|--:|\n");
+    for summary in report.summary() {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {:.0} | {:.0} | {:.0} |",
+            escape(&summary.executor_name),
+            summary.runs,
+            summary.avg_tps,
+            summary.min_tps,
+            summary.max_tps
+        );
+    }
+
+    out.push_str("\n### Ranking by Conflict Level\n\n");
+    out.push_str("| Hot Accounts | Rank | Executor | Avg TPS | Min TPS | Max TPS | vs Sequential |\n");
+    out.push_str("|---|--:|---|--:|--:|--:|--:|\n");
+    for ranking in report.ranking(BASELINE_EXECUTOR) {
+        for (rank, executor) in ranking.executors.iter().enumerate() {
+            let speedup = executor
+                .speedup
+                .map_or_else(|| "-".to_string(), |speedup| format!("{:.2}x", speedup));
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {:.0} | {:.0} | {:.0} | {} |",
+                escape(&ranking.hot_accounts_label),
+                rank + 1,
+                escape(&executor.label),
+                executor.avg_tps,
+                executor.min_tps,
+                executor.max_tps,
+                speedup
+            );
+        }
+    }
+
+    out
+}
+
+/// Escapes characters that would break a table cell.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::{run_in_memory, BenchPlan, BenchRunner, CellOutput};
+    use crate::{SequentialExecutor, Workload, WorkloadConfig};
+
+    #[test]
+    fn test_render_tables() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            ..Default::default()
+        });
+        let mut plan = BenchPlan::new();
+        plan.add("Sequential | In-Memory", None, || {
+            let executor = SequentialExecutor::with_verification(false);
+            CellOutput::new(run_in_memory(&executor, &workload, "10 accounts", 20))
+        });
+        let report = BenchRunner::new().with_printing(false).run(&plan);
+
+        let markdown = render(&EnvironmentInfo::capture(), &report);
+        assert!(markdown.contains("### Environment"));
+        assert!(markdown.contains("### Sequential \\| In-Memory"));
+        assert!(markdown.contains("| 10 accounts | sequential_in_memory |  | strict | 20 | 0 |"));
+        assert!(markdown.contains("| 10 accounts | 1 | sequential_in_memory |"));
+        assert!(markdown.contains("| 1.00x |"));
+
+        assert_eq!("md".parse::<OutputFormat>(), Ok(OutputFormat::Markdown));
+        assert!("html".parse::<OutputFormat>().is_err());
+    }
+}
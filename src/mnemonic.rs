@@ -0,0 +1,103 @@
+//! BIP-39 mnemonic / BIP-44 HD-wallet account derivation.
+//!
+//! Devnets such as anvil and reth's dev mode fund accounts derived from a
+//! mnemonic along Ethereum's BIP-44 path `m/44'/60'/0'/0/{index}`. Deriving the
+//! workload's accounts the same way lets a generated workload run against
+//! state those nodes already have.
+//!
+//! ```
+//! use db_test::Account;
+//!
+//! let phrase = "test test test test test test test test test test test junk";
+//! let account = Account::from_mnemonic(phrase, 0).unwrap();
+//! assert_eq!(
+//!     account.address.to_string(),
+//!     "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+//! );
+//! ```
+
+use crate::Account;
+use bip32::{Language, Mnemonic, XPrv};
+use std::fmt;
+
+/// Error produced by an invalid mnemonic or derivation path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MnemonicError(pub String);
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mnemonic error: {}", self.0)
+    }
+}
+
+impl std::error::Error for MnemonicError {}
+
+impl Account {
+    /// Derives the account at `m/44'/60'/0'/0/{index}` from an English BIP-39
+    /// mnemonic with an empty passphrase.
+    pub fn from_mnemonic(phrase: &str, index: u32) -> Result<Self, MnemonicError> {
+        let seed = mnemonic_seed(phrase, "")?;
+        derive(&seed, index)
+    }
+}
+
+/// Derives the first `count` accounts of a mnemonic, in index order.
+///
+/// The BIP-39 seed is computed once, so this is much faster than calling
+/// [`Account::from_mnemonic`] per index.
+pub fn derive_accounts(phrase: &str, passphrase: &str, count: usize) -> Result<Vec<Account>, MnemonicError> {
+    let seed = mnemonic_seed(phrase, passphrase)?;
+    (0..count)
+        .map(|index| {
+            let index = u32::try_from(index).map_err(|_| MnemonicError(format!("index {} out of range", index)))?;
+            derive(&seed, index)
+        })
+        .collect()
+}
+
+fn mnemonic_seed(phrase: &str, passphrase: &str) -> Result<[u8; 64], MnemonicError> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mnemonic = Mnemonic::new(phrase, Language::English)
+        .map_err(|err| MnemonicError(format!("invalid mnemonic: {}", err)))?;
+    Ok(*mnemonic.to_seed(passphrase).as_bytes())
+}
+
+fn derive(seed: &[u8; 64], index: u32) -> Result<Account, MnemonicError> {
+    let path = format!("m/44'/60'/0'/0/{}", index)
+        .parse()
+        .map_err(|err| MnemonicError(format!("invalid derivation path: {}", err)))?;
+    let xprv = XPrv::derive_from_path(seed, &path)
+        .map_err(|err| MnemonicError(format!("derivation failed: {}", err)))?;
+    Ok(Account::from_signing_key(xprv.private_key().clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEV_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn test_derives_dev_accounts() {
+        // anvil / hardhat default accounts 0 and 1
+        let accounts = derive_accounts(DEV_MNEMONIC, "", 2).unwrap();
+        assert_eq!(
+            accounts[0].address.to_string(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert_eq!(
+            accounts[1].address.to_string(),
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+        );
+        assert_eq!(
+            Account::from_mnemonic(DEV_MNEMONIC, 1).unwrap().address,
+            accounts[1].address
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_mnemonic() {
+        assert!(Account::from_mnemonic("test test test", 0).is_err());
+        assert!(Account::from_mnemonic("junk junk junk junk junk junk junk junk junk junk junk junk", 0).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+//! Interleaved workloads for several chains sharing one executor.
+//!
+//! A shared sequencer orders transactions for several rollups at once and
+//! hands them to one execution backend. [`MultiChainWorkload::generate`]
+//! builds that scenario: one workload per chain id, each from its own seed
+//! range so the chains' accounts are disjoint and no chain touches another's
+//! keys in the shared backend, merged block by block with their transactions
+//! interleaved round-robin. Every transaction is signed over its own chain's
+//! id, so a transfer cannot be replayed on another chain.
+//!
+//! Executors run the merged [`workload`](MultiChainWorkload::workload) like
+//! any other; [`ChainLayout::breakdown`] then splits the outcome per chain.
+//! The merged workload carries the first chain's id in its configuration, so
+//! [`Workload::validate`] reports every other chain's transactions as hash
+//! mismatches: validate the [`chains`](MultiChainWorkload::chains) instead.
+
+use crate::executor::{
+    failure_breakdown, format_failures, AnyExecutor, ExecutionReport, ExecutionResult, FailureReason, TxStatus,
+};
+use crate::{Workload, WorkloadConfig};
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Several chains' workloads interleaved into one.
+#[derive(Debug, Clone)]
+pub struct MultiChainWorkload {
+    /// The interleaved transactions of every chain, in block order.
+    pub workload: Workload,
+    /// Each chain's own workload, in chain id order as given.
+    pub chains: Vec<Workload>,
+    /// Which chain each transaction of `workload` belongs to.
+    pub layout: ChainLayout,
+}
+
+impl MultiChainWorkload {
+    /// Generates one workload per entry of `chain_ids` from `config` and
+    /// interleaves them.
+    ///
+    /// Chain `k` uses `config` with its own chain id and a seed advanced by
+    /// `k * num_accounts`, so each chain's accounts come from their own range
+    /// of seeds. Merged block `b` holds block `b` of every chain, one
+    /// transaction from each in turn.
+    pub fn generate(config: WorkloadConfig, chain_ids: &[u64]) -> Self {
+        assert!(!chain_ids.is_empty(), "at least one chain is required");
+        let distinct: HashSet<u64> = chain_ids.iter().copied().collect();
+        assert_eq!(distinct.len(), chain_ids.len(), "chain ids must be distinct");
+
+        let chains: Vec<Workload> = chain_ids
+            .iter()
+            .enumerate()
+            .map(|(chain, &chain_id)| {
+                Workload::generate(WorkloadConfig {
+                    chain_id,
+                    seed: config.seed.wrapping_add((chain * config.num_accounts) as u64),
+                    ..config.clone()
+                })
+            })
+            .collect();
+
+        let num_blocks = chains.iter().map(Workload::num_blocks).max().unwrap_or(0);
+        let mut blocks = Vec::with_capacity(num_blocks);
+        let mut chain_of = Vec::new();
+        for block in 0..num_blocks {
+            let chain_blocks: Vec<&[_]> =
+                chains.iter().map(|chain| chain.blocks.get(block).map_or(&[][..], Vec::as_slice)).collect();
+            let longest = chain_blocks.iter().map(|txs| txs.len()).max().unwrap_or(0);
+            let mut merged = Vec::with_capacity(chain_blocks.iter().map(|txs| txs.len()).sum());
+            for index in 0..longest {
+                for (chain, txs) in chain_blocks.iter().enumerate() {
+                    if let Some(tx) = txs.get(index) {
+                        merged.push(tx.clone());
+                        chain_of.push(chain);
+                    }
+                }
+            }
+            blocks.push(merged);
+        }
+
+        let n = chains.len();
+        let merged_config = WorkloadConfig {
+            num_accounts: config.num_accounts * n,
+            num_transactions: config.num_transactions * n,
+            transactions_per_block: config.transactions_per_block * n,
+            hot_accounts: config.hot_accounts * n,
+            chain_id: chain_ids[0],
+            duplicate_transactions: config.duplicate_transactions * n,
+            double_spends: config.double_spends * n,
+            ..config
+        };
+        let workload = Workload {
+            accounts: chains.iter().flat_map(|chain| chain.accounts.iter().cloned()).collect(),
+            transactions: blocks.iter().flatten().cloned().collect(),
+            blocks,
+            config: merged_config,
+            genesis: OnceLock::new(),
+            signers: OnceLock::new(),
+        };
+
+        Self {
+            workload,
+            chains,
+            layout: ChainLayout {
+                chain_ids: chain_ids.to_vec(),
+                chain_of,
+            },
+        }
+    }
+
+    /// Number of chains.
+    pub fn num_chains(&self) -> usize {
+        self.chains.len()
+    }
+
+    /// Splits the result of executing [`workload`](Self::workload) per chain.
+    pub fn breakdown(&self, result: &ExecutionResult) -> Vec<ChainResult> {
+        self.layout.breakdown(&self.workload, result)
+    }
+}
+
+/// The chain of every transaction in a merged workload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainLayout {
+    /// Chain ids, indexed by chain.
+    pub chain_ids: Vec<u64>,
+    /// Chain index of each transaction, in workload order.
+    pub chain_of: Vec<usize>,
+}
+
+impl ChainLayout {
+    /// Splits `result`, the outcome of executing `workload`, per chain.
+    ///
+    /// Transactions the executor's index does not cover count toward
+    /// neither successes nor failures.
+    pub fn breakdown(&self, workload: &Workload, result: &ExecutionResult) -> Vec<ChainResult> {
+        let statuses = result.tx_index.statuses(workload);
+        let has_reasons = result.failure_reasons.len() == self.chain_of.len();
+
+        self.chain_ids
+            .iter()
+            .enumerate()
+            .map(|(chain, &chain_id)| {
+                let positions: Vec<usize> = (0..self.chain_of.len()).filter(|&i| self.chain_of[i] == chain).collect();
+                let successful = positions.iter().filter(|&&i| statuses[i] == Some(TxStatus::Success)).count();
+                let failed = positions.iter().filter(|&&i| statuses[i] == Some(TxStatus::Failed)).count();
+                let reasons: Vec<Option<FailureReason>> = if has_reasons {
+                    positions.iter().map(|&i| result.failure_reasons[i]).collect()
+                } else {
+                    Vec::new()
+                };
+                ChainResult {
+                    chain_id,
+                    transactions: positions.len(),
+                    successful,
+                    failed,
+                    failures: failure_breakdown(&reasons),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Outcome of one chain's transactions in a multi-chain run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainResult {
+    pub chain_id: u64,
+    /// Transactions of this chain in the merged workload.
+    pub transactions: usize,
+    pub successful: usize,
+    pub failed: usize,
+    /// Failures counted by reason, if the executor records them.
+    pub failures: Vec<(FailureReason, usize)>,
+}
+
+impl fmt::Display for ChainResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "chain {}: {} txs, {}/{} success/fail",
+            self.chain_id, self.transactions, self.successful, self.failed
+        )?;
+        if self.failed > 0 {
+            write!(f, " ({})", format_failures(&self.failures, self.failed))?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds a [`ChainResult`] detail line per chain to every run of the wrapped
+/// executor; it must only run the workload `layout` describes.
+pub struct WithChainBreakdown {
+    inner: Box<dyn AnyExecutor>,
+    layout: ChainLayout,
+}
+
+impl WithChainBreakdown {
+    /// Wraps `inner`.
+    pub fn new(inner: Box<dyn AnyExecutor>, layout: ChainLayout) -> Self {
+        Self { inner, layout }
+    }
+}
+
+impl AnyExecutor for WithChainBreakdown {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn preserves_order(&self) -> bool {
+        self.inner.preserves_order()
+    }
+
+    fn supports_tokens(&self) -> bool {
+        self.inner.supports_tokens()
+    }
+
+    fn supports_nonceless(&self) -> bool {
+        self.inner.supports_nonceless()
+    }
+
+    fn supports_large_balances(&self) -> bool {
+        self.inner.supports_large_balances()
+    }
+
+    fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+        let report = self.inner.execute_workload(workload);
+        let chains = self.layout.breakdown(workload, &report.result);
+        chains.iter().fold(report, |report, chain| report.with_detail(chain.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::SequentialExecutor;
+    use crate::SignedTransaction;
+
+    fn config() -> WorkloadConfig {
+        WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 50,
+            hot_accounts: 4,
+            transactions_per_block: 10,
+            double_spends: 3,
+            fake_signatures: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_chains_are_interleaved_and_separated() {
+        let multi = MultiChainWorkload::generate(config(), &[10, 20, 30]);
+        let workload = &multi.workload;
+        assert_eq!(multi.num_chains(), 3);
+        assert_eq!(workload.transactions.len(), 3 * 53);
+        assert_eq!(workload.accounts.len(), 60);
+        assert_eq!(workload.blocks[0].len(), workload.transactions_per_block());
+
+        // Chains share no accounts
+        let addresses: HashSet<_> = workload.accounts.iter().map(|account| account.address).collect();
+        assert_eq!(addresses.len(), 60);
+
+        // Each transaction is signed for its own chain and keeps its chain's order
+        let mut next = [0usize; 3];
+        for (tx, &chain) in workload.transactions.iter().zip(&multi.layout.chain_of) {
+            let own = &multi.chains[chain];
+            assert_eq!(tx.tx_hash, own.transactions[next[chain]].tx_hash);
+            let expected =
+                SignedTransaction::compute_tx_hash(tx.from, tx.to, tx.value, tx.token, tx.nonce, own.config.chain_id);
+            assert_eq!(tx.tx_hash, expected);
+            next[chain] += 1;
+        }
+        assert!(multi.chains.iter().all(|chain| chain.validate().is_valid()));
+    }
+
+    #[test]
+    fn test_breakdown_matches_chains_run_alone() {
+        let multi = MultiChainWorkload::generate(config(), &[1, 7]);
+        let executor = SequentialExecutor::with_verification(false);
+        let report = executor.execute_workload(&multi.workload);
+        let chains = multi.breakdown(&report.result);
+
+        assert_eq!(chains.iter().map(|chain| chain.successful).sum::<usize>(), report.result.successful);
+        assert_eq!(chains.iter().map(|chain| chain.failed).sum::<usize>(), report.result.failed);
+        for (chain, alone) in chains.iter().zip(&multi.chains) {
+            let alone = executor.execute_workload(alone).result;
+            assert_eq!((chain.successful, chain.failed), (alone.successful, alone.failed));
+            assert_eq!(chain.failures, alone.failure_breakdown());
+        }
+
+        let wrapped = WithChainBreakdown::new(Box::new(executor), multi.layout.clone());
+        let details = wrapped.execute_workload(&multi.workload).details;
+        assert_eq!(details.len(), 2);
+        assert!(details[1].starts_with("chain 7: 53 txs"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Workload mutation for chaos testing executors.
+//!
+//! A [`WorkloadMutator`] rewrites a generated workload before execution so
+//! executors can be checked against malformed block streams: reordered
+//! transactions, nonce gaps, replayed blocks, cut-off workloads. Mutators are
+//! applied in order with [`Workload::mutate`]; the built-in ones are the
+//! [`Mutation`] variants, given on the command line as `--mutate SPEC`:
+//!
+//! | Spec                  | Effect                                                 |
+//! |-----------------------|--------------------------------------------------------|
+//! | `shuffle`             | shuffle transactions within each block                 |
+//! | `drop=FRACTION`       | drop each transaction with probability `FRACTION`      |
+//! | `duplicate-blocks=N`  | re-insert N random blocks right after themselves       |
+//! | `truncate=N`          | keep only the first N transactions                     |
+//!
+//! Mutators edit the workload's blocks as they are, which need not all have
+//! the configured size (e.g. blocks loaded from a block file); afterwards
+//! emptied blocks are removed and `config.num_transactions` is updated to
+//! match, so throughput is reported over the transactions that actually run.
+//!
+//! Dropped transactions leave nonce gaps: every later transaction from the
+//! same sender fails. The FoundationDB executor retries those forever unless
+//! its retries are bounded, so the benchmark refuses `drop` for it then.
+
+use crate::{SignedTransaction, Workload};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Rewrites a workload's transactions before execution.
+pub trait WorkloadMutator {
+    /// Short description shown in the benchmark configuration.
+    fn name(&self) -> String;
+
+    /// Mutates `blocks` in place.
+    fn mutate(&self, blocks: &mut Vec<Vec<SignedTransaction>>, rng: &mut StdRng);
+}
+
+/// Built-in mutations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mutation {
+    /// Shuffles transactions within each block.
+    ShuffleWithinBlocks,
+    /// Drops each transaction with the given probability.
+    DropTransactions(f64),
+    /// Re-inserts this many randomly chosen blocks right after the original.
+    DuplicateBlocks(usize),
+    /// Keeps only the first this many transactions.
+    Truncate(usize),
+}
+
+impl WorkloadMutator for Mutation {
+    fn name(&self) -> String {
+        self.to_string()
+    }
+
+    fn mutate(&self, blocks: &mut Vec<Vec<SignedTransaction>>, rng: &mut StdRng) {
+        match *self {
+            Mutation::ShuffleWithinBlocks => {
+                for block in blocks.iter_mut() {
+                    block.shuffle(rng);
+                }
+            }
+            Mutation::DropTransactions(fraction) => {
+                for block in blocks.iter_mut() {
+                    block.retain(|_| !rng.gen_bool(fraction));
+                }
+            }
+            Mutation::DuplicateBlocks(count) => {
+                for _ in 0..count {
+                    if blocks.is_empty() {
+                        return;
+                    }
+                    let index = rng.gen_range(0..blocks.len());
+                    let block = blocks[index].clone();
+                    blocks.insert(index + 1, block);
+                }
+            }
+            Mutation::Truncate(len) => {
+                let mut remaining = len;
+                for block in blocks.iter_mut() {
+                    block.truncate(remaining);
+                    remaining -= block.len();
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mutation::ShuffleWithinBlocks => f.write_str("shuffle"),
+            Mutation::DropTransactions(fraction) => write!(f, "drop={}", fraction),
+            Mutation::DuplicateBlocks(count) => write!(f, "duplicate-blocks={}", count),
+            Mutation::Truncate(len) => write!(f, "truncate={}", len),
+        }
+    }
+}
+
+impl FromStr for Mutation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, value) = match s.split_once('=') {
+            Some((kind, value)) => (kind, Some(value)),
+            None => (s, None),
+        };
+        let count = |value: Option<&str>| {
+            value
+                .and_then(|value| value.parse::<usize>().ok())
+                .ok_or_else(|| format!("{} expects a count, got '{}'", kind, s))
+        };
+
+        match kind {
+            "shuffle" if value.is_none() => Ok(Mutation::ShuffleWithinBlocks),
+            "drop" => match value.and_then(|value| value.parse::<f64>().ok()) {
+                Some(fraction) if (0.0..=1.0).contains(&fraction) => Ok(Mutation::DropTransactions(fraction)),
+                _ => Err(format!("drop expects a fraction between 0 and 1, got '{}'", s)),
+            },
+            "duplicate-blocks" => count(value).map(Mutation::DuplicateBlocks),
+            "truncate" => count(value).map(Mutation::Truncate),
+            _ => Err(format!(
+                "unknown mutation '{}' (expected shuffle, drop=FRACTION, duplicate-blocks=N or truncate=N)",
+                s
+            )),
+        }
+    }
+}
+
+impl Workload {
+    /// Applies `mutators` in order, seeding their randomness with `seed`.
+    pub fn mutate(&mut self, mutators: &[&dyn WorkloadMutator], seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        for mutator in mutators {
+            mutator.mutate(&mut self.blocks, &mut rng);
+        }
+
+        self.blocks.retain(|block| !block.is_empty());
+        self.transactions = self.blocks.iter().flatten().cloned().collect();
+        self.config.num_transactions = self.transactions.len();
+        // Cached signers are positional
+        self.signers = OnceLock::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor, WorkloadConfig};
+
+    fn workload() -> Workload {
+        Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 40,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_parse_mutations() {
+        for spec in ["shuffle", "drop=0.25", "duplicate-blocks=2", "truncate=100"] {
+            assert_eq!(spec.parse::<Mutation>().unwrap().to_string(), spec);
+        }
+        assert!("drop=1.5".parse::<Mutation>().is_err());
+        assert!("truncate".parse::<Mutation>().is_err());
+        assert!("shuffle=3".parse::<Mutation>().is_err());
+        assert!("reverse".parse::<Mutation>().is_err());
+    }
+
+    #[test]
+    fn test_mutations_keep_blocks_consistent() {
+        let original = workload();
+
+        let mut duplicated = original.clone();
+        duplicated.mutate(&[&Mutation::DuplicateBlocks(1)], 7);
+        assert_eq!(duplicated.transactions.len(), 50);
+        assert_eq!(duplicated.config.num_transactions, 50);
+        assert_eq!(duplicated.blocks.len(), 5);
+        // Replayed block's transactions are rejected, everything else still runs
+        let (_, result) = SequentialExecutor::with_verification(true).execute(duplicated.create_db(), &duplicated);
+        assert_eq!(result.successful, 40);
+
+        let mut truncated = original.clone();
+        truncated.mutate(&[&Mutation::DropTransactions(0.5), &Mutation::Truncate(12)], 7);
+        assert!(truncated.transactions.len() <= 12);
+        assert_eq!(truncated.blocks.iter().map(Vec::len).sum::<usize>(), truncated.transactions.len());
+        assert!(truncated.blocks.iter().all(|block| !block.is_empty()));
+
+        let mut shuffled = original.clone();
+        shuffled.mutate(&[&Mutation::ShuffleWithinBlocks], 7);
+        for (block, shuffled_block) in original.blocks.iter().zip(&shuffled.blocks) {
+            let mut hashes: Vec<_> = block.iter().map(|tx| tx.tx_hash).collect();
+            let mut shuffled_hashes: Vec<_> = shuffled_block.iter().map(|tx| tx.tx_hash).collect();
+            hashes.sort();
+            shuffled_hashes.sort();
+            assert_eq!(hashes, shuffled_hashes);
+        }
+    }
+
+    #[test]
+    fn test_mutations_follow_uneven_blocks() {
+        // Blocks as loaded from a block file need not have the configured size
+        let mut original = workload();
+        let tail = original.blocks[0].split_off(3);
+        original.blocks[1].splice(0..0, tail);
+
+        let hashes = |workload: &Workload| -> Vec<Vec<_>> {
+            workload.blocks.iter().map(|block| block.iter().map(|tx| tx.tx_hash).collect()).collect()
+        };
+
+        let mut shuffled = original.clone();
+        shuffled.mutate(&[&Mutation::ShuffleWithinBlocks], 7);
+        let (mut blocks, mut shuffled_blocks) = (hashes(&original), hashes(&shuffled));
+        for (block, shuffled_block) in blocks.iter_mut().zip(&mut shuffled_blocks) {
+            block.sort();
+            shuffled_block.sort();
+            assert_eq!(block, shuffled_block);
+        }
+
+        // Copies land right after their original block, whatever its size
+        let mut duplicated = original.clone();
+        duplicated.mutate(&[&Mutation::DuplicateBlocks(3)], 7);
+        let mut blocks = hashes(&duplicated);
+        assert_eq!(blocks.len(), 7);
+        blocks.dedup();
+        assert_eq!(blocks, hashes(&original));
+        assert_eq!(duplicated.transactions.len(), duplicated.blocks.iter().map(Vec::len).sum::<usize>());
+    }
+}
@@ -0,0 +1,588 @@
+//! Executor-specific tuning options.
+//!
+//! Options are given on the command line as `--executor-opt name=key=value`
+//! and collected into one typed struct per executor family:
+//!
+//! | Name        | Key                  | Value                                                         |
+//! |-------------|----------------------|---------------------------------------------------------------|
+//! | `sequential` | `parallel-recovery` | recover all signers up front on every core (`true`/`false`) |
+//! | `block-stm` | `lookahead`          | scheduling window past the commit point (positive int)        |
+//! | `block-stm` | `memory-budget`      | bytes of speculative state before throttling (positive int)   |
+//! | `block-stm` | `per-block`          | execute block by block, carrying state (`true` or `false`)    |
+//! | `block-stm` | `atomic-blocks`      | discard blocks with a failed transaction; implies `per-block` |
+//! | `mdbx`      | `sync-mode`          | `durable`, `no-meta-sync`, `safe-no-sync`, `utterly-no-sync`  |
+//! | `mdbx`      | `key-scheme`         | account keys: `hashed`, `plain` or `prefixed`                 |
+//! | `mdbx`      | `changesets`         | record per-block account history (`true` or `false`)          |
+//! | `mdbx`      | `atomic-blocks`      | roll back blocks with a failed transaction (`true`/`false`)   |
+//! | `mdbx`      | `commit-blocks`      | commit once this many blocks are pending (positive int)       |
+//! | `mdbx`      | `commit-interval-ms` | commit once this many ms passed since the last (positive int) |
+//! | `fdb`       | `batch-size`         | accounts written per FDB transaction at init (positive int)   |
+//! | `fdb`       | `max-attempts`       | attempts per transfer before it fails (positive int)          |
+//! | `fdb`       | `backoff-us`         | delay before the first validation retry, in µs (positive int) |
+//! | `fdb`       | `max-backoff-us`     | cap on the doubling retry delay, in µs (positive int)         |
+//! | `fdb`       | `jitter`             | randomize each delay within its upper half (`true`/`false`)   |
+//! | `fdb`       | `dispatch`           | `threads` or `async`; unset runs and compares both            |
+//! | `fdb`       | `key-scheme`         | account keys: `hashed`, `plain` or `prefixed`                 |
+//! | `wal`       | `sync-mode`          | fsync the log per `transaction`, per `block` or `none`        |
+//! | `wal`       | `checkpoint-blocks`  | blocks between state snapshots (positive int)                 |
+//! | `uring`     | `queue-depth`        | io_uring submission queue entries (positive int)              |
+//! | `uring`     | `fsync`              | fsync the file after each block (`true` or `false`)           |
+//! | `mmap`      | `flush`              | flush the mapping after each block (`true` or `false`)        |
+
+use crate::background::BackgroundAccounts;
+use crate::key_scheme::KeyScheme;
+use crate::recovery::RecoveryBackend;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Error produced by a malformed or unknown executor option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionError(pub String);
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for OptionError {}
+
+/// A single raw `name=key=value` option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutorOpt {
+    /// Executor family the option applies to.
+    pub executor: String,
+    /// Option name.
+    pub key: String,
+    /// Unparsed option value.
+    pub value: String,
+}
+
+impl FromStr for ExecutorOpt {
+    type Err = OptionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '=');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(executor), Some(key), Some(value))
+                if !executor.is_empty() && !key.is_empty() && !value.is_empty() =>
+            {
+                Ok(Self {
+                    executor: executor.to_string(),
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+            }
+            _ => Err(OptionError(format!("expected name=key=value, got '{}'", s))),
+        }
+    }
+}
+
+/// Sequential executor options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SequentialOptions {
+    /// Recover every signer on all cores before executing instead of one at
+    /// a time between transactions.
+    pub parallel_recovery: bool,
+}
+
+/// Block-STM scheduler options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockStmOptions {
+    /// Scheduling window past the commit point; `None` schedules the whole block.
+    pub lookahead: Option<usize>,
+    /// Bytes of speculative state before scheduling is throttled; `None` is unbounded.
+    pub memory_budget: Option<usize>,
+    /// Run each workload block separately instead of the workload as one block.
+    pub per_block: bool,
+    /// Discard every block in which a transaction fails; runs block by block.
+    pub atomic_blocks: bool,
+}
+
+/// MDBX durability mode for write transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MdbxSyncMode {
+    /// Flush data and metadata on every commit.
+    #[default]
+    Durable,
+    /// Flush data on commit but defer metadata.
+    NoMetaSync,
+    /// Leave flushing to the OS; a crash may lose recent commits but not corrupt.
+    SafeNoSync,
+    /// Never flush; a crash may corrupt the database.
+    UtterlyNoSync,
+}
+
+impl FromStr for MdbxSyncMode {
+    type Err = OptionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "durable" => Ok(Self::Durable),
+            "no-meta-sync" => Ok(Self::NoMetaSync),
+            "safe-no-sync" => Ok(Self::SafeNoSync),
+            "utterly-no-sync" => Ok(Self::UtterlyNoSync),
+            _ => Err(OptionError(format!(
+                "unknown MDBX sync mode '{}' (expected durable, no-meta-sync, safe-no-sync or utterly-no-sync)",
+                s
+            ))),
+        }
+    }
+}
+
+/// MDBX environment options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MdbxOptions {
+    /// Durability mode for write transactions.
+    pub sync_mode: MdbxSyncMode,
+    /// How account keys are derived from addresses.
+    pub key_scheme: KeyScheme,
+    /// Record each block's account changesets so past state can be read back.
+    pub changesets: bool,
+    /// Roll back every block in which a transaction fails instead of committing it.
+    pub atomic_blocks: bool,
+    /// Blocks executed per commit; `None` commits every block unless an
+    /// interval is set.
+    pub commit_every: Option<usize>,
+    /// Time between commits; `None` commits every block unless a block count
+    /// is set.
+    pub commit_interval: Option<Duration>,
+}
+
+/// When the write-ahead log executor flushes its log to disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WalSyncMode {
+    /// Fsync after every transaction's record.
+    Transaction,
+    /// Fsync once per block, after its end marker (group commit).
+    #[default]
+    Block,
+    /// Never fsync; the OS writes the log back when it sees fit.
+    None,
+}
+
+impl FromStr for WalSyncMode {
+    type Err = OptionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transaction" => Ok(Self::Transaction),
+            "block" => Ok(Self::Block),
+            "none" => Ok(Self::None),
+            _ => Err(OptionError(format!(
+                "unknown WAL sync mode '{}' (expected transaction, block or none)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Write-ahead log executor options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalOptions {
+    /// When the log is flushed to disk.
+    pub sync_mode: WalSyncMode,
+    /// Blocks between snapshots of the in-memory state, after which the log
+    /// is truncated.
+    pub checkpoint_blocks: usize,
+}
+
+impl Default for WalOptions {
+    fn default() -> Self {
+        Self {
+            sync_mode: WalSyncMode::default(),
+            checkpoint_blocks: 16,
+        }
+    }
+}
+
+/// io_uring flat-file executor options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UringOptions {
+    /// Entries in the submission queue; larger batches take several submissions.
+    pub queue_depth: u32,
+    /// Fsync the file after each block's writes.
+    pub fsync: bool,
+}
+
+impl Default for UringOptions {
+    fn default() -> Self {
+        Self {
+            queue_depth: 256,
+            fsync: true,
+        }
+    }
+}
+
+/// Memory-mapped array executor options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MmapOptions {
+    /// Flush the mapping to disk after each block.
+    pub flush: bool,
+}
+
+/// How a transfer whose validation failed (nonce mismatch, insufficient
+/// balance) is retried.
+///
+/// The delay doubles from `initial_backoff` up to `max_backoff`. The default
+/// retries forever every 100µs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per transfer before it fails; `None` retries until it commits.
+    pub max_attempts: Option<usize>,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Largest delay between retries.
+    pub max_backoff: Duration,
+    /// Whether each delay is drawn uniformly from its upper half, so threads
+    /// waiting on the same transfer spread out.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: None,
+            initial_backoff: Duration::from_micros(100),
+            max_backoff: Duration::from_micros(100),
+            jitter: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a transfer that has failed validation `attempts` times may try again.
+    pub fn allows_retry(&self, attempts: usize) -> bool {
+        self.max_attempts.map_or(true, |max_attempts| attempts < max_attempts)
+    }
+
+    /// Delay before retry number `retry`, counting from 1.
+    pub fn backoff(&self, retry: usize, rng: &mut impl Rng) -> Duration {
+        let doublings = retry.saturating_sub(1).min(u32::BITS as usize - 1) as u32;
+        let delay = self
+            .initial_backoff
+            .saturating_mul(1 << doublings)
+            .min(self.max_backoff.max(self.initial_backoff));
+        if self.jitter {
+            rng.gen_range(delay / 2..=delay)
+        } else {
+            delay
+        }
+    }
+}
+
+/// How the FDB parallel executor drives its transfers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FdbDispatch {
+    /// One OS thread per worker, each with its own runtime, blocking on one
+    /// transfer at a time.
+    #[default]
+    Threads,
+    /// Concurrent transfer futures on one shared multi-threaded runtime,
+    /// as many in flight as there are workers.
+    Async,
+}
+
+impl FdbDispatch {
+    /// Every dispatch mode, in the order benchmarks report them.
+    pub const ALL: [Self; 2] = [Self::Threads, Self::Async];
+}
+
+impl FromStr for FdbDispatch {
+    type Err = OptionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "threads" => Ok(Self::Threads),
+            "async" => Ok(Self::Async),
+            _ => Err(OptionError(format!(
+                "unknown FDB dispatch mode '{}' (expected threads or async)",
+                s
+            ))),
+        }
+    }
+}
+
+/// FoundationDB executor options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FdbOptions {
+    /// Accounts written per FDB transaction when initializing a workload.
+    pub batch_size: usize,
+    /// How transfers that fail validation are retried.
+    pub retry: RetryPolicy,
+    /// How transfers are driven; `None` benchmarks every mode.
+    pub dispatch: Option<FdbDispatch>,
+    /// How account keys are derived from addresses.
+    pub key_scheme: KeyScheme,
+    /// Name of the account value codec, as [`codec_by_name`](crate::codec::codec_by_name) takes it.
+    pub codec: &'static str,
+    /// Shards of the parallel executor's state commitment; `None` keeps none.
+    pub commitment_shards: Option<usize>,
+    /// Log every committed transfer and check each sender's commit order after the run.
+    pub transfer_log: bool,
+}
+
+impl Default for FdbOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 1000,
+            retry: RetryPolicy::default(),
+            dispatch: None,
+            key_scheme: KeyScheme::default(),
+            codec: "raw",
+            commitment_shards: None,
+            transfer_log: false,
+        }
+    }
+}
+
+/// Typed options for every executor family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutorOptions {
+    /// Worker threads for parallel executors; set per benchmark cell.
+    pub num_threads: usize,
+    /// Whether executors verify transaction signatures.
+    pub verify_signatures: bool,
+    /// Signer recovery backend for executors that support a choice.
+    pub recovery: RecoveryBackend,
+    /// Check signers against [`Workload::preverify`](crate::Workload::preverify)'s
+    /// cache instead of recovering them while timed.
+    pub trust_preverified: bool,
+    /// Untouched accounts persistent backends are pre-populated with before each run.
+    pub background_accounts: u64,
+    /// Transactions whose accounts the MDBX sequential and FDB revm executors
+    /// prefetch ahead of execution; `None` disables prefetching.
+    pub prefetch: Option<usize>,
+    /// Threads computing the MDBX batched executor's state root after every
+    /// commit; `None` skips it.
+    pub state_root_threads: Option<usize>,
+    /// Sharded stores of the two-phase commit executor.
+    pub shards: usize,
+    /// Options of the sequential executor.
+    pub sequential: SequentialOptions,
+    pub block_stm: BlockStmOptions,
+    pub mdbx: MdbxOptions,
+    pub fdb: FdbOptions,
+    pub wal: WalOptions,
+    pub uring: UringOptions,
+    pub mmap: MmapOptions,
+}
+
+impl Default for ExecutorOptions {
+    fn default() -> Self {
+        Self {
+            num_threads: 1,
+            verify_signatures: true,
+            recovery: RecoveryBackend::default(),
+            trust_preverified: false,
+            background_accounts: 0,
+            prefetch: None,
+            state_root_threads: None,
+            shards: 2,
+            sequential: SequentialOptions::default(),
+            block_stm: BlockStmOptions::default(),
+            mdbx: MdbxOptions::default(),
+            fdb: FdbOptions::default(),
+            wal: WalOptions::default(),
+            uring: UringOptions::default(),
+            mmap: MmapOptions::default(),
+        }
+    }
+}
+
+impl ExecutorOptions {
+    /// Background accounts to pre-populate persistent backends with, keyed by
+    /// the workload `seed`, if any were requested.
+    pub fn background(&self, seed: u64) -> Option<BackgroundAccounts> {
+        (self.background_accounts > 0).then(|| BackgroundAccounts::new(self.background_accounts, seed))
+    }
+
+    /// Applies raw options in order; a later option overrides an earlier one.
+    pub fn from_opts(opts: &[ExecutorOpt]) -> Result<Self, OptionError> {
+        let mut options = Self::default();
+        for opt in opts {
+            match (opt.executor.as_str(), opt.key.as_str()) {
+                ("sequential", "parallel-recovery") => options.sequential.parallel_recovery = parse_bool(opt)?,
+                ("block-stm", "lookahead") => {
+                    options.block_stm.lookahead = Some(parse_positive(opt)?);
+                }
+                ("block-stm", "memory-budget") => {
+                    options.block_stm.memory_budget = Some(parse_positive(opt)?);
+                }
+                ("block-stm", "per-block") => options.block_stm.per_block = parse_bool(opt)?,
+                ("block-stm", "atomic-blocks") => options.block_stm.atomic_blocks = parse_bool(opt)?,
+                ("mdbx", "sync-mode") => options.mdbx.sync_mode = opt.value.parse()?,
+                ("mdbx", "key-scheme") => options.mdbx.key_scheme = opt.value.parse()?,
+                ("mdbx", "changesets") => options.mdbx.changesets = parse_bool(opt)?,
+                ("mdbx", "atomic-blocks") => options.mdbx.atomic_blocks = parse_bool(opt)?,
+                ("mdbx", "commit-blocks") => options.mdbx.commit_every = Some(parse_positive(opt)?),
+                ("mdbx", "commit-interval-ms") => {
+                    options.mdbx.commit_interval = Some(Duration::from_millis(parse_positive(opt)? as u64));
+                }
+                ("fdb", "batch-size") => options.fdb.batch_size = parse_positive(opt)?,
+                ("fdb", "max-attempts") => options.fdb.retry.max_attempts = Some(parse_positive(opt)?),
+                ("fdb", "backoff-us") => {
+                    options.fdb.retry.initial_backoff = Duration::from_micros(parse_positive(opt)? as u64);
+                }
+                ("fdb", "max-backoff-us") => {
+                    options.fdb.retry.max_backoff = Duration::from_micros(parse_positive(opt)? as u64);
+                }
+                ("fdb", "jitter") => options.fdb.retry.jitter = parse_bool(opt)?,
+                ("fdb", "dispatch") => options.fdb.dispatch = Some(opt.value.parse()?),
+                ("fdb", "key-scheme") => options.fdb.key_scheme = opt.value.parse()?,
+                ("wal", "sync-mode") => options.wal.sync_mode = opt.value.parse()?,
+                ("wal", "checkpoint-blocks") => options.wal.checkpoint_blocks = parse_positive(opt)?,
+                ("uring", "queue-depth") => {
+                    options.uring.queue_depth = u32::try_from(parse_positive(opt)?).map_err(|_| {
+                        OptionError(format!("uring=queue-depth={} is too large", opt.value))
+                    })?;
+                }
+                ("uring", "fsync") => options.uring.fsync = parse_bool(opt)?,
+                ("mmap", "flush") => options.mmap.flush = parse_bool(opt)?,
+                ("sequential" | "block-stm" | "mdbx" | "fdb" | "wal" | "uring" | "mmap", key) => {
+                    return Err(OptionError(format!("unknown {} option '{}'", opt.executor, key)));
+                }
+                (executor, _) => {
+                    return Err(OptionError(format!(
+                        "unknown executor '{}' (expected sequential, block-stm, mdbx, fdb, wal, uring or mmap)",
+                        executor
+                    )));
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn parse_positive(opt: &ExecutorOpt) -> Result<usize, OptionError> {
+    match opt.value.parse() {
+        Ok(value) if value > 0 => Ok(value),
+        _ => Err(OptionError(format!(
+            "{}={} expects a positive integer, got '{}'",
+            opt.executor, opt.key, opt.value
+        ))),
+    }
+}
+
+fn parse_bool(opt: &ExecutorOpt) -> Result<bool, OptionError> {
+    opt.value.parse().map_err(|_| {
+        OptionError(format!(
+            "{}={} expects true or false, got '{}'",
+            opt.executor, opt.key, opt.value
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(opts: &[&str]) -> Result<ExecutorOptions, OptionError> {
+        let opts: Vec<ExecutorOpt> = opts.iter().map(|opt| opt.parse().unwrap()).collect();
+        ExecutorOptions::from_opts(&opts)
+    }
+
+    #[test]
+    fn test_parse_executor_options() {
+        let options = parse(&[
+            "sequential=parallel-recovery=true",
+            "block-stm=lookahead=32",
+            "block-stm=memory-budget=1048576",
+            "block-stm=per-block=true",
+            "block-stm=atomic-blocks=true",
+            "mdbx=sync-mode=safe-no-sync",
+            "mdbx=key-scheme=plain",
+            "mdbx=changesets=true",
+            "mdbx=atomic-blocks=true",
+            "mdbx=commit-blocks=4",
+            "mdbx=commit-interval-ms=250",
+            "fdb=batch-size=250",
+            "fdb=batch-size=500",
+            "fdb=max-attempts=8",
+            "fdb=backoff-us=50",
+            "fdb=max-backoff-us=400",
+            "fdb=jitter=true",
+            "fdb=dispatch=async",
+            "fdb=key-scheme=prefixed",
+            "wal=sync-mode=transaction",
+            "wal=checkpoint-blocks=4",
+            "uring=queue-depth=32",
+            "uring=fsync=false",
+            "mmap=flush=true",
+        ])
+        .unwrap();
+
+        assert!(options.sequential.parallel_recovery);
+        assert_eq!(options.block_stm.lookahead, Some(32));
+        assert_eq!(options.block_stm.memory_budget, Some(1 << 20));
+        assert!(options.block_stm.per_block);
+        assert!(options.block_stm.atomic_blocks);
+        assert_eq!(options.mdbx.sync_mode, MdbxSyncMode::SafeNoSync);
+        assert_eq!(options.mdbx.key_scheme, KeyScheme::Plain);
+        assert!(options.mdbx.changesets);
+        assert!(options.mdbx.atomic_blocks);
+        assert_eq!(options.mdbx.commit_every, Some(4));
+        assert_eq!(options.mdbx.commit_interval, Some(Duration::from_millis(250)));
+        assert_eq!(options.fdb.batch_size, 500);
+        assert_eq!(
+            options.fdb.retry,
+            RetryPolicy {
+                max_attempts: Some(8),
+                initial_backoff: Duration::from_micros(50),
+                max_backoff: Duration::from_micros(400),
+                jitter: true,
+            }
+        );
+        assert_eq!(options.fdb.dispatch, Some(FdbDispatch::Async));
+        assert_eq!(options.fdb.key_scheme, KeyScheme::Prefixed);
+        assert_eq!(options.wal.sync_mode, WalSyncMode::Transaction);
+        assert_eq!(options.wal.checkpoint_blocks, 4);
+        assert_eq!(options.uring, UringOptions { queue_depth: 32, fsync: false });
+        assert!(options.mmap.flush);
+        assert_eq!(ExecutorOptions::from_opts(&[]).unwrap(), ExecutorOptions::default());
+    }
+
+    #[test]
+    fn test_reject_malformed_options() {
+        assert!("block-stm=lookahead".parse::<ExecutorOpt>().is_err());
+        assert!(parse(&["block-stm=window=4"]).is_err());
+        assert!(parse(&["rocksdb=batch-size=4"]).is_err());
+        assert!(parse(&["fdb=batch-size=0"]).is_err());
+        assert!(parse(&["block-stm=per-block=yes"]).is_err());
+        assert!(parse(&["mdbx=sync-mode=sometimes"]).is_err());
+        assert!(parse(&["fdb=max-attempts=0"]).is_err());
+        assert!(parse(&["fdb=dispatch=fibers"]).is_err());
+        assert!(parse(&["mdbx=key-scheme=sorted"]).is_err());
+        assert!(parse(&["wal=sync-mode=sometimes"]).is_err());
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let default = RetryPolicy::default();
+        assert!(default.allows_retry(usize::MAX - 1));
+        assert_eq!(default.backoff(1, &mut rng), Duration::from_micros(100));
+        assert_eq!(default.backoff(50, &mut rng), Duration::from_micros(100));
+
+        let policy = RetryPolicy {
+            max_attempts: Some(3),
+            initial_backoff: Duration::from_micros(50),
+            max_backoff: Duration::from_micros(300),
+            jitter: false,
+        };
+        assert!(policy.allows_retry(2));
+        assert!(!policy.allows_retry(3));
+        let delays: Vec<_> = (1..=5).map(|retry| policy.backoff(retry, &mut rng).as_micros()).collect();
+        assert_eq!(delays, [50, 100, 200, 300, 300]);
+
+        let jittered = RetryPolicy { jitter: true, ..policy };
+        for _ in 0..100 {
+            let delay = jittered.backoff(3, &mut rng);
+            assert!((Duration::from_micros(100)..=Duration::from_micros(200)).contains(&delay));
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! Execution oracle: cross-checks local executors against a live node.
+//!
+//! [`submit_workload`] re-signs a workload's transfers as real EIP-1559
+//! transactions, submits them to a devnet (anvil, reth `--dev`, ...) over
+//! JSON-RPC, waits for receipts, and records every touched account's nonce
+//! and balance before and after. [`compare`] then checks the node's outcome
+//! against the final state of a local executor run over the same workload.
+//!
+//! The node must already fund the workload's accounts, e.g. by generating
+//! them with [`crate::mnemonic`] from the node's dev mnemonic. Nonces are
+//! offset by each sender's nonce on the node, so the same accounts can be
+//! reused across runs.
+//!
+//! Gas is accounted for separately: the node charges `gasUsed *
+//! effectiveGasPrice` from receipts, while the local executors charge 21000
+//! gas at a gas price of 1 wei. Balances are compared after adding fees back,
+//! so only the transferred value has to agree.
+
+use crate::{GenesisState, Workload};
+use alloy_consensus::TxEnvelope;
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{hex, Address, B256, I256, U256};
+use revm::database::CacheDB;
+use revm::database_interface::DatabaseRef;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Gas charged per transfer by the local executors.
+const LOCAL_GAS_LIMIT: u64 = 21_000;
+/// Gas price used by the local executors, in wei.
+const LOCAL_GAS_PRICE: u64 = 1;
+/// Balance every account starts with in a local run (1000 ETH).
+const LOCAL_INITIAL_BALANCE: u128 = 1_000_000_000_000_000_000_000;
+
+/// Error talking to the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleError(pub String);
+
+impl fmt::Display for OracleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "oracle error: {}", self.0)
+    }
+}
+
+impl std::error::Error for OracleError {}
+
+/// Minimal JSON-RPC client over plain HTTP.
+#[derive(Debug, Clone)]
+pub struct RpcClient {
+    host: String,
+    path: String,
+}
+
+impl RpcClient {
+    /// Creates a client for an `http://host:port[/path]` endpoint.
+    pub fn new(url: &str) -> Result<Self, OracleError> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| OracleError(format!("only http:// endpoints are supported, got '{}'", url)))?;
+        let (host, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, "/"),
+        };
+        Ok(Self {
+            host: host.to_string(),
+            path: path.to_string(),
+        })
+    }
+
+    /// Calls `method` and returns its `result`.
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, OracleError> {
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}).to_string();
+        let body = self.post(&request)?;
+        let mut response: Value = serde_json::from_str(&body)
+            .map_err(|err| OracleError(format!("{}: invalid response: {}", method, err)))?;
+        if let Some(error) = response.get("error") {
+            return Err(OracleError(format!("{}: {}", method, error)));
+        }
+        Ok(response["result"].take())
+    }
+
+    fn post(&self, body: &str) -> Result<String, OracleError> {
+        let io_err = |err: std::io::Error| OracleError(format!("{}: {}", self.host, err));
+        let mut stream = TcpStream::connect(&self.host).map_err(io_err)?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            body.len(),
+            body
+        )
+        .map_err(io_err)?;
+
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw).map_err(io_err)?;
+        let raw = String::from_utf8_lossy(&raw);
+        let (head, body) = raw
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| OracleError("malformed HTTP response".to_string()))?;
+
+        let status = head.lines().next().unwrap_or_default();
+        if !status.contains(" 200") {
+            return Err(OracleError(format!("HTTP error: {}", status)));
+        }
+        let chunked = head
+            .lines()
+            .any(|line| line.to_ascii_lowercase().starts_with("transfer-encoding:") && line.contains("chunked"));
+        Ok(if chunked { decode_chunked(body)? } else { body.to_string() })
+    }
+}
+
+fn decode_chunked(mut body: &str) -> Result<String, OracleError> {
+    let mut decoded = String::new();
+    loop {
+        let (size, rest) = body
+            .split_once("\r\n")
+            .ok_or_else(|| OracleError("malformed chunked body".to_string()))?;
+        let size = usize::from_str_radix(size.split(';').next().unwrap_or_default().trim(), 16)
+            .map_err(|_| OracleError(format!("invalid chunk size '{}'", size)))?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        let chunk = rest
+            .get(..size)
+            .ok_or_else(|| OracleError("truncated chunked body".to_string()))?;
+        decoded.push_str(chunk);
+        body = rest.get(size + 2..).unwrap_or_default();
+    }
+}
+
+/// Fee settings for submitted transactions.
+#[derive(Debug, Clone, Copy)]
+pub struct OracleOptions {
+    /// `maxFeePerGas` in wei.
+    pub max_fee_per_gas: u128,
+    /// `maxPriorityFeePerGas` in wei.
+    pub max_priority_fee_per_gas: u128,
+    /// How long to wait for each receipt.
+    pub receipt_timeout: Duration,
+}
+
+impl Default for OracleOptions {
+    fn default() -> Self {
+        Self {
+            max_fee_per_gas: 100_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            receipt_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Nonce and balance of one account on the node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountState {
+    pub nonce: u64,
+    pub balance: U256,
+}
+
+/// What the node did with a submitted workload.
+#[derive(Debug, Clone, Default)]
+pub struct NodeOutcome {
+    /// Per transaction: `None` if the node refused it at submission (e.g. a
+    /// replay), otherwise whether its receipt reports success.
+    pub statuses: Vec<Option<bool>>,
+    /// Fees paid per sender.
+    pub fees: HashMap<Address, U256>,
+    /// Touched accounts before submission.
+    pub before: HashMap<Address, AccountState>,
+    /// Touched accounts after every receipt arrived.
+    pub after: HashMap<Address, AccountState>,
+    /// Time from the first submission until the last receipt.
+    pub elapsed: Duration,
+}
+
+impl NodeOutcome {
+    /// Transactions the node included with a successful receipt.
+    pub fn successful(&self) -> usize {
+        self.statuses.iter().filter(|status| **status == Some(true)).count()
+    }
+
+    /// Transactions refused at submission or reverted.
+    pub fn failed(&self) -> usize {
+        self.statuses.len() - self.successful()
+    }
+}
+
+/// Submits `workload` to the node as EIP-1559 transactions and waits for every receipt.
+pub fn submit_workload(
+    client: &RpcClient,
+    workload: &Workload,
+    options: &OracleOptions,
+) -> Result<NodeOutcome, OracleError> {
+    let chain_id = parse_u64(&client.call("eth_chainId", json!([]))?)?;
+    let keys: HashMap<Address, _> = workload
+        .accounts
+        .iter()
+        .map(|account| (account.address, &account.signing_key))
+        .collect();
+
+    let touched: BTreeSet<Address> = workload.transactions.iter().flat_map(|tx| [tx.from, tx.to]).collect();
+    let before = fetch_states(client, &touched)?;
+
+    let start = Instant::now();
+    let mut pending = Vec::with_capacity(workload.transactions.len());
+    for tx in &workload.transactions {
+        let key = keys
+            .get(&tx.from)
+            .ok_or_else(|| OracleError(format!("no key for sender {}", tx.from)))?;
+        let signed = tx.to_eip1559(
+            key,
+            before[&tx.from].nonce + tx.nonce,
+            chain_id,
+            options.max_fee_per_gas,
+            options.max_priority_fee_per_gas,
+        );
+        let raw = TxEnvelope::from(signed).encoded_2718();
+        let hash = client
+            .call("eth_sendRawTransaction", json!([hex::encode_prefixed(raw)]))
+            .ok()
+            .and_then(|hash| hash.as_str().and_then(|hash| hash.parse::<B256>().ok()));
+        pending.push(hash);
+    }
+
+    let mut statuses = Vec::with_capacity(pending.len());
+    let mut fees: HashMap<Address, U256> = HashMap::new();
+    for (tx, hash) in workload.transactions.iter().zip(&pending) {
+        let Some(hash) = hash else {
+            statuses.push(None);
+            continue;
+        };
+        let receipt = wait_for_receipt(client, hash, options.receipt_timeout)?;
+        let gas_used = parse_u256(&receipt["gasUsed"])?;
+        let gas_price = parse_u256(&receipt["effectiveGasPrice"])?;
+        *fees.entry(tx.from).or_default() += gas_used * gas_price;
+        statuses.push(Some(receipt["status"].as_str() == Some("0x1")));
+    }
+    let elapsed = start.elapsed();
+
+    Ok(NodeOutcome {
+        statuses,
+        fees,
+        before,
+        after: fetch_states(client, &touched)?,
+        elapsed,
+    })
+}
+
+/// Difference between the node and a local run for one account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountMismatch {
+    pub address: Address,
+    /// Transactions sent, as seen by the node and locally.
+    pub nonces: (u64, u64),
+    /// Value received minus value sent, excluding fees, as seen by the node and locally.
+    pub value_deltas: (I256, I256),
+}
+
+/// Result of [`compare`].
+#[derive(Debug, Clone, Default)]
+pub struct OracleComparison {
+    /// Successful transactions on the node.
+    pub node_successful: usize,
+    /// Successful transactions locally.
+    pub local_successful: usize,
+    /// Accounts whose nonce or value delta differ.
+    pub mismatches: Vec<AccountMismatch>,
+}
+
+impl OracleComparison {
+    /// Whether the node and the local run agree.
+    pub fn agrees(&self) -> bool {
+        self.node_successful == self.local_successful && self.mismatches.is_empty()
+    }
+}
+
+/// Compares the node's outcome with a local executor's final database.
+pub fn compare(node: &NodeOutcome, local_successful: usize, local_db: &CacheDB<GenesisState>) -> OracleComparison {
+    let local_initial = U256::from(LOCAL_INITIAL_BALANCE);
+    let local_fee = U256::from(LOCAL_GAS_LIMIT * LOCAL_GAS_PRICE);
+
+    let mut mismatches: Vec<_> = node
+        .before
+        .iter()
+        .filter_map(|(&address, before)| {
+            let after = node.after.get(&address).copied().unwrap_or_default();
+            let fees = node.fees.get(&address).copied().unwrap_or_default();
+            let node_nonce = after.nonce - before.nonce;
+            let node_delta = signed_delta(after.balance + fees, before.balance);
+
+            let (local_nonce, local_balance) = local_db
+                .basic_ref(address)
+                .ok()
+                .flatten()
+                .map_or((0, U256::ZERO), |info| (info.nonce, info.balance));
+            let local_delta = signed_delta(local_balance + local_fee * U256::from(local_nonce), local_initial);
+
+            (node_nonce != local_nonce || node_delta != local_delta).then_some(AccountMismatch {
+                address,
+                nonces: (node_nonce, local_nonce),
+                value_deltas: (node_delta, local_delta),
+            })
+        })
+        .collect();
+    mismatches.sort_by_key(|mismatch| mismatch.address);
+
+    OracleComparison {
+        node_successful: node.successful(),
+        local_successful,
+        mismatches,
+    }
+}
+
+fn signed_delta(after: U256, before: U256) -> I256 {
+    I256::from_raw(after.wrapping_sub(before))
+}
+
+fn fetch_states(client: &RpcClient, addresses: &BTreeSet<Address>) -> Result<HashMap<Address, AccountState>, OracleError> {
+    addresses
+        .iter()
+        .map(|&address| {
+            let nonce = parse_u64(&client.call("eth_getTransactionCount", json!([address, "latest"]))?)?;
+            let balance = parse_u256(&client.call("eth_getBalance", json!([address, "latest"]))?)?;
+            Ok((address, AccountState { nonce, balance }))
+        })
+        .collect()
+}
+
+fn wait_for_receipt(client: &RpcClient, hash: &B256, timeout: Duration) -> Result<Value, OracleError> {
+    let start = Instant::now();
+    loop {
+        let receipt = client.call("eth_getTransactionReceipt", json!([hash]))?;
+        if !receipt.is_null() {
+            return Ok(receipt);
+        }
+        if start.elapsed() > timeout {
+            return Err(OracleError(format!("no receipt for {} after {:?}", hash, timeout)));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+fn parse_u256(value: &Value) -> Result<U256, OracleError> {
+    let hex = value
+        .as_str()
+        .and_then(|s| s.strip_prefix("0x"))
+        .ok_or_else(|| OracleError(format!("expected hex quantity, got {}", value)))?;
+    U256::from_str_radix(hex, 16).map_err(|err| OracleError(format!("invalid quantity {}: {}", value, err)))
+}
+
+fn parse_u64(value: &Value) -> Result<u64, OracleError> {
+    u64::try_from(parse_u256(value)?).map_err(|_| OracleError(format!("quantity {} overflows u64", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor, WorkloadConfig};
+
+    #[test]
+    fn test_decode_chunked() {
+        assert_eq!(decode_chunked("4\r\n{\"a\"\r\n3\r\n:1}\r\n0\r\n\r\n").unwrap(), "{\"a\":1}");
+        assert!(decode_chunked("zz\r\n").is_err());
+    }
+
+    #[test]
+    fn test_compare_against_matching_node() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 4,
+            num_transactions: 6,
+            transactions_per_block: 6,
+            hot_accounts: 4,
+            ..Default::default()
+        });
+        let (db, result) = SequentialExecutor::with_verification(true).execute(workload.create_db(), &workload);
+
+        // A node that started every account at 5 ETH and charged 2 wei per transfer
+        let mut node = NodeOutcome {
+            statuses: vec![Some(true); 6],
+            ..Default::default()
+        };
+        let initial = U256::from(5_000_000_000_000_000_000u128);
+        for account in &workload.accounts {
+            let local = db.basic_ref(account.address).unwrap().unwrap();
+            let fee = U256::from(2 * local.nonce);
+            let local_delta = (local.balance + U256::from(LOCAL_GAS_LIMIT * local.nonce))
+                .wrapping_sub(U256::from(LOCAL_INITIAL_BALANCE));
+            node.before.insert(account.address, AccountState { nonce: 3, balance: initial });
+            node.after.insert(
+                account.address,
+                AccountState {
+                    nonce: 3 + local.nonce,
+                    balance: initial.wrapping_add(local_delta) - fee,
+                },
+            );
+            node.fees.insert(account.address, fee);
+        }
+
+        let comparison = compare(&node, result.successful, &db);
+        assert!(comparison.agrees(), "{:?}", comparison.mismatches);
+
+        node.after.values_mut().next().unwrap().nonce += 1;
+        assert_eq!(compare(&node, result.successful, &db).mismatches.len(), 1);
+    }
+}
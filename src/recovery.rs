@@ -0,0 +1,123 @@
+//! Signature recovery backends.
+//!
+//! Recovering the signer is the dominant per-transaction cost in
+//! verification-enabled benchmarks. [`RecoveryBackend::K256`] uses the
+//! pure-Rust `k256` crate that [`SignedTransaction::recover_signer`] is built
+//! on. With the `secp256k1` feature, [`RecoveryBackend::Secp256k1`] uses
+//! bitcoin-core's libsecp256k1 through the `secp256k1` crate instead.
+//!
+//! Executors that support a choice of backend expose a `with_recovery`
+//! builder; the rest always use `k256`.
+
+use crate::SignedTransaction;
+use alloy_primitives::Address;
+use std::fmt;
+use std::str::FromStr;
+
+/// Implementation used to recover transaction signers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecoveryBackend {
+    /// Pure-Rust `k256`.
+    #[default]
+    K256,
+    /// libsecp256k1 via the `secp256k1` crate (requires `--features secp256k1`).
+    #[cfg(feature = "secp256k1")]
+    Secp256k1,
+}
+
+impl RecoveryBackend {
+    /// Every backend compiled into this build.
+    pub fn available() -> Vec<Self> {
+        vec![
+            Self::K256,
+            #[cfg(feature = "secp256k1")]
+            Self::Secp256k1,
+        ]
+    }
+
+    /// Short name, as accepted by [`FromStr`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::K256 => "k256",
+            #[cfg(feature = "secp256k1")]
+            Self::Secp256k1 => "secp256k1",
+        }
+    }
+
+    /// Recovers the address that signed `tx`, or `None` if the signature is invalid.
+    pub fn recover_signer(&self, tx: &SignedTransaction) -> Option<Address> {
+        match self {
+            Self::K256 => tx.recover_signer(),
+            #[cfg(feature = "secp256k1")]
+            Self::Secp256k1 => recover_secp256k1(tx),
+        }
+    }
+
+    /// Whether the signature of `tx` recovers to `tx.from`.
+    pub fn verify(&self, tx: &SignedTransaction) -> bool {
+        self.recover_signer(tx) == Some(tx.from)
+    }
+}
+
+impl fmt::Display for RecoveryBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for RecoveryBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "k256" => Ok(Self::K256),
+            #[cfg(feature = "secp256k1")]
+            "secp256k1" => Ok(Self::Secp256k1),
+            #[cfg(not(feature = "secp256k1"))]
+            "secp256k1" => Err("secp256k1 recovery requires --features secp256k1".to_string()),
+            _ => Err(format!("unknown recovery backend '{}' (expected k256 or secp256k1)", s)),
+        }
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+fn recover_secp256k1(tx: &SignedTransaction) -> Option<Address> {
+    use alloy_primitives::keccak256;
+    use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+    use secp256k1::{Message, SECP256K1};
+
+    let mut compact = [0u8; 64];
+    compact[..32].copy_from_slice(&tx.signature.r().to_be_bytes::<32>());
+    compact[32..].copy_from_slice(&tx.signature.s().to_be_bytes::<32>());
+    let recovery_id = RecoveryId::from_i32(tx.signature.v() as i32).ok()?;
+    let signature = RecoverableSignature::from_compact(&compact, recovery_id).ok()?;
+
+    let message = Message::from_digest(tx.tx_hash.0);
+    let public_key = SECP256K1.recover_ecdsa(&message, &signature).ok()?;
+    // Skip the 0x04 prefix byte, hash the rest.
+    let hash = keccak256(&public_key.serialize_uncompressed()[1..]);
+    Some(Address::from_slice(&hash[12..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Account;
+    use alloy_primitives::U256;
+
+    #[test]
+    fn test_backends_agree() {
+        let account = Account::from_seed(42);
+        let tx = SignedTransaction::new(&account, Address::ZERO, U256::from(1000), 3, 1);
+        let mut forged = tx.clone();
+        forged.from = Address::with_last_byte(1);
+
+        for backend in RecoveryBackend::available() {
+            assert_eq!(backend.recover_signer(&tx), Some(account.address), "{}", backend);
+            assert!(backend.verify(&tx));
+            assert!(!backend.verify(&forged));
+            assert_eq!(backend.name().parse::<RecoveryBackend>(), Ok(backend));
+        }
+        assert!("openssl".parse::<RecoveryBackend>().is_err());
+    }
+}
@@ -0,0 +1,783 @@
+//! Executor registry.
+//!
+//! Maps executor names to factories, so the CLI and the benchmark harness can
+//! construct executors by name. Each factory receives the run's
+//! [`ExecutorOptions`] and returns a type-erased [`AnyExecutor`] that sets up
+//! its own backend on every run. FoundationDB-backed executors expect the
+//! caller to have booted the FDB network.
+//!
+//! Downstream crates can register their own executors next to the built-in
+//! ones:
+//!
+//! ```
+//! use db_test::registry::ExecutorRegistry;
+//! use db_test::SequentialExecutor;
+//!
+//! let mut registry = ExecutorRegistry::builtin();
+//! registry.register("my_sequential", "My Sequential Executor", false, |options| {
+//!     Box::new(SequentialExecutor::with_verification(options.verify_signatures))
+//! });
+//!
+//! assert!(registry.get("my_sequential").is_some());
+//! ```
+
+use crate::executor::AnyExecutor;
+use crate::options::ExecutorOptions;
+
+/// Builds an executor from the run's options.
+pub type ExecutorFactory = Box<dyn Fn(&ExecutorOptions) -> Box<dyn AnyExecutor> + Send + Sync>;
+
+/// A registered executor.
+pub struct ExecutorEntry {
+    /// Name used to select the executor.
+    pub name: &'static str,
+    /// Section title for benchmark output.
+    pub title: String,
+    /// Whether the executor runs once per configured thread count.
+    pub threaded: bool,
+    factory: ExecutorFactory,
+}
+
+impl ExecutorEntry {
+    /// Builds an executor instance.
+    pub fn create(&self, options: &ExecutorOptions) -> Box<dyn AnyExecutor> {
+        (self.factory)(options)
+    }
+}
+
+/// Executor names mapped to factories, in registration order.
+#[derive(Default)]
+pub struct ExecutorRegistry {
+    entries: Vec<ExecutorEntry>,
+}
+
+impl ExecutorRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with every built-in executor enabled in this build.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register("sequential", "Sequential In-Memory Executor (CacheDB)", false, |options| {
+            Box::new(
+                crate::SequentialExecutor::with_verification(options.verify_signatures)
+                    .with_recovery(options.recovery)
+                    .with_parallel_sig_recovery(options.sequential.parallel_recovery),
+            )
+        });
+        registry.register(
+            "native_transfer",
+            "Native Transfer In-Memory Executor (CacheDB, no EVM)",
+            false,
+            |options| {
+                Box::new(
+                    crate::executor::NativeTransferExecutor::new(options.verify_signatures)
+                        .with_recovery(options.recovery),
+                )
+            },
+        );
+        registry.register(
+            "wal",
+            "Write-Ahead Log Executor (In-memory state, redo log and snapshots)",
+            false,
+            |options| Box::new(builtin::Wal(*options)),
+        );
+
+        #[cfg(feature = "block-stm")]
+        registry.register(
+            "block_stm",
+            "Block-STM Parallel Executor (Optimistic concurrency)",
+            true,
+            |options| {
+                let mut executor =
+                    crate::executor::BlockStmExecutor::new(options.num_threads, options.verify_signatures);
+                if let Some(lookahead) = options.block_stm.lookahead {
+                    executor = executor.with_lookahead(lookahead);
+                }
+                if let Some(bytes) = options.block_stm.memory_budget {
+                    executor = executor.with_memory_budget(bytes);
+                }
+                if options.block_stm.per_block {
+                    executor = executor.with_block_boundaries();
+                }
+                if options.block_stm.atomic_blocks {
+                    executor = executor.with_atomic_blocks();
+                }
+                Box::new(executor)
+            },
+        );
+
+        #[cfg(feature = "verkle")]
+        registry.register(
+            "verkle",
+            "Verkle Sequential Executor (In-memory Verkle-style trie)",
+            false,
+            |options| Box::new(builtin::Verkle(crate::executor::VerkleExecutor::new(options.verify_signatures))),
+        );
+
+        #[cfg(feature = "mdbx")]
+        registry.register(
+            "mdbx_sequential",
+            "MDBX Sequential Executor (Persistent storage)",
+            false,
+            |options| Box::new(builtin::MdbxSequential(*options)),
+        );
+
+        #[cfg(feature = "mdbx")]
+        registry.register(
+            "mdbx_batched",
+            "MDBX Batched Executor (Block-level caching and commit)",
+            false,
+            |options| Box::new(builtin::MdbxBatched(*options)),
+        );
+
+        #[cfg(feature = "mdbx")]
+        registry.register(
+            "two_phase",
+            "MDBX Two-Phase Commit Executor (Sharded stores)",
+            false,
+            |options| Box::new(builtin::TwoPhase(*options)),
+        );
+
+        #[cfg(feature = "fdb")]
+        registry.register(
+            "fdb_parallel",
+            "FoundationDB Parallel Executor (Distributed transactional)",
+            true,
+            |options| Box::new(builtin::FdbParallel(*options, crate::options::FdbDispatch::Threads)),
+        );
+
+        #[cfg(feature = "fdb")]
+        registry.register(
+            "fdb_async",
+            "FoundationDB Async Executor (Transfer futures on one runtime)",
+            true,
+            |options| Box::new(builtin::FdbParallel(*options, crate::options::FdbDispatch::Async)),
+        );
+
+        #[cfg(feature = "fdb")]
+        registry.register(
+            "fdb_revm",
+            "FoundationDB revm Executor (Full EVM over FDB state)",
+            false,
+            |options| Box::new(builtin::FdbRevm(*options)),
+        );
+
+        #[cfg(feature = "mmap")]
+        registry.register(
+            "mmap_array",
+            "Memory-Mapped Array Executor (Accounts by dense id, storage upper bound)",
+            false,
+            |options| Box::new(builtin::MmapArray(*options)),
+        );
+
+        #[cfg(all(feature = "io-uring", target_os = "linux"))]
+        registry.register(
+            "uring",
+            "io_uring Flat-File Executor (Fixed-size account records)",
+            false,
+            |options| Box::new(builtin::Uring(*options)),
+        );
+
+        registry
+    }
+
+    /// Registers an executor, replacing any existing entry with the same name.
+    pub fn register<F>(&mut self, name: &'static str, title: &str, threaded: bool, factory: F)
+    where
+        F: Fn(&ExecutorOptions) -> Box<dyn AnyExecutor> + Send + Sync + 'static,
+    {
+        let entry = ExecutorEntry {
+            name,
+            title: title.to_string(),
+            threaded,
+            factory: Box::new(factory),
+        };
+        match self.entries.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Looks up an executor by name.
+    pub fn get(&self, name: &str) -> Option<&ExecutorEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Every registered executor, in registration order.
+    pub fn entries(&self) -> &[ExecutorEntry] {
+        &self.entries
+    }
+}
+
+/// [`AnyExecutor`] adapters for built-in executors that manage their own backend.
+mod builtin {
+    use crate::executor::{AnyExecutor, ExecutionReport};
+    use crate::footprint::Footprint;
+    use crate::options::ExecutorOptions;
+    use crate::Workload;
+    use std::time::Instant;
+
+    pub(super) struct Wal(pub ExecutorOptions);
+
+    impl AnyExecutor for Wal {
+        fn name(&self) -> String {
+            "wal_in_memory".to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            true
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            let dir = tempfile::tempdir().expect("Failed to create temp directory");
+            let executor =
+                crate::executor::WalExecutor::new_with_options(dir.path(), self.0.verify_signatures, &self.0.wal)
+                    .with_recovery(self.0.recovery);
+
+            let start = Instant::now();
+            let result = executor.execute_workload(workload).expect("Execution failed");
+            ExecutionReport::new(result.result, start.elapsed()).with_detail(format!(
+                "log: {:.2} MB, {} fsyncs ({:.2} ms), {} snapshots ({:.2} ms)",
+                result.log_bytes as f64 / (1024.0 * 1024.0),
+                result.syncs,
+                result.sync_time.as_secs_f64() * 1000.0,
+                result.checkpoints,
+                result.checkpoint_time.as_secs_f64() * 1000.0
+            ))
+            .with_detail(format!("storage: {}", Footprint::of_workload(dir.path(), workload, 0)))
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    pub(super) struct MmapArray(pub ExecutorOptions);
+
+    #[cfg(feature = "mmap")]
+    impl AnyExecutor for MmapArray {
+        fn name(&self) -> String {
+            "mmap_array".to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            true
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            let dir = tempfile::tempdir().expect("Failed to create temp directory");
+            let mut executor =
+                crate::executor::MmapArrayExecutor::new(dir.path().join("accounts"), self.0.verify_signatures)
+                    .with_recovery(self.0.recovery);
+            if self.0.mmap.flush {
+                executor = executor.with_flush_per_block();
+            }
+
+            let start = Instant::now();
+            let result = executor.execute_workload(workload).expect("Execution failed");
+            let mut report = ExecutionReport::new(result.result, start.elapsed())
+                .with_detail(format!("array: {:.2} MB", result.file_bytes as f64 / (1024.0 * 1024.0)));
+            if self.0.mmap.flush {
+                report = report.with_detail(format!("flush time: {:.2} ms", result.flush_time.as_secs_f64() * 1000.0));
+            }
+            report.with_detail(format!("storage: {}", Footprint::of_workload(dir.path(), workload, 0)))
+        }
+    }
+
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    pub(super) struct Uring(pub ExecutorOptions);
+
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    impl AnyExecutor for Uring {
+        fn name(&self) -> String {
+            "io_uring_flat_file".to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            true
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            let dir = tempfile::tempdir().expect("Failed to create temp directory");
+            let executor = crate::executor::UringExecutor::new_with_options(
+                dir.path().join("accounts"),
+                self.0.verify_signatures,
+                &self.0.uring,
+            )
+            .with_recovery(self.0.recovery);
+
+            let start = Instant::now();
+            let result = executor.execute_workload(workload).expect("Execution failed");
+            let stats = result.stats;
+            ExecutionReport::new(result.result, start.elapsed()).with_detail(format!(
+                "io: {} reads ({:.2} ms), {} writes and {} fsyncs ({:.2} ms), {} submissions",
+                stats.reads,
+                stats.read_time.as_secs_f64() * 1000.0,
+                stats.writes,
+                stats.fsyncs,
+                stats.write_time.as_secs_f64() * 1000.0,
+                stats.submissions
+            ))
+            .with_detail(format!("storage: {}", Footprint::of_workload(dir.path(), workload, 0)))
+        }
+    }
+
+    #[cfg(feature = "verkle")]
+    pub(super) struct Verkle(pub crate::executor::VerkleExecutor);
+
+    #[cfg(feature = "verkle")]
+    impl AnyExecutor for Verkle {
+        fn name(&self) -> String {
+            self.0.name().to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            self.0.preserves_order()
+        }
+
+        fn supports_large_balances(&self) -> bool {
+            false
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            let start = Instant::now();
+            let (result, _) = self.0.execute_workload(workload).expect("Execution failed");
+            let elapsed = start.elapsed();
+            let commitment_time = result.total_commitment_time();
+            ExecutionReport::new(result.result, elapsed).with_detail(format!(
+                "commitment time: {:.2} ms",
+                commitment_time.as_secs_f64() * 1000.0
+            ))
+        }
+    }
+
+    /// Describes prefetch cache effectiveness
+    #[cfg(any(feature = "mdbx", feature = "fdb"))]
+    fn prefetch_detail(stats: &crate::executor::PrefetchStats) -> String {
+        format!(
+            "prefetched: {}, hit rate: {:.1}% ({} hits, {} misses)",
+            stats.prefetched,
+            stats.hit_rate() * 100.0,
+            stats.hits,
+            stats.misses
+        )
+    }
+
+    #[cfg(feature = "mdbx")]
+    pub(super) struct MdbxSequential(pub ExecutorOptions);
+
+    #[cfg(feature = "mdbx")]
+    impl AnyExecutor for MdbxSequential {
+        fn name(&self) -> String {
+            "mdbx_sequential".to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            true
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            let dir = tempfile::tempdir().expect("Failed to create temp directory");
+            let executor = crate::executor::MdbxSequentialExecutor::new_with_options(
+                dir.path(),
+                self.0.verify_signatures,
+                &self.0.mdbx,
+            )
+            .expect("Failed to create MDBX sequential executor")
+            .with_recovery(self.0.recovery);
+            if let Some(background) = self.0.background(workload.config.seed) {
+                executor.load_background(&background).expect("Failed to load background accounts");
+            }
+
+            let start = Instant::now();
+            let (result, prefetch) = match self.0.prefetch {
+                Some(lookahead) => {
+                    let (result, stats) =
+                        executor.execute_workload_with_prefetch(workload, lookahead).expect("Execution failed");
+                    (result, Some(stats))
+                }
+                None => (executor.execute_workload(workload).expect("Execution failed").0, None),
+            };
+            let elapsed = start.elapsed();
+
+            let mut report = ExecutionReport::new(result, elapsed);
+            if let Some(stats) = prefetch {
+                report = report.with_detail(prefetch_detail(&stats));
+            }
+            let footprint = Footprint::of_workload(dir.path(), workload, self.0.background_accounts);
+            report.with_detail(format!("storage: {}", footprint))
+        }
+    }
+
+    #[cfg(feature = "mdbx")]
+    pub(super) struct MdbxBatched(pub ExecutorOptions);
+
+    #[cfg(feature = "mdbx")]
+    impl AnyExecutor for MdbxBatched {
+        fn name(&self) -> String {
+            "mdbx_batched".to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            true
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            let dir = tempfile::tempdir().expect("Failed to create temp directory");
+            let mut executor = crate::executor::MdbxBatchedExecutor::new_with_options(
+                dir.path(),
+                self.0.verify_signatures,
+                &self.0.mdbx,
+            )
+            .expect("Failed to create MDBX batched executor")
+            .with_recovery(self.0.recovery);
+            if let Some(num_threads) = self.0.state_root_threads {
+                executor = executor.with_state_root(num_threads);
+            }
+            if let Some(background) = self.0.background(workload.config.seed) {
+                executor.load_background(&background).expect("Failed to load background accounts");
+            }
+
+            let start = Instant::now();
+            let (result, _) = executor.execute_workload(workload).expect("Execution failed");
+            // State root time is reported on its own line, not folded into TPS
+            let state_root_time = result.total_state_root_time();
+            let elapsed = start.elapsed() - state_root_time;
+
+            let mut report = ExecutionReport::new(result.to_execution_result(), elapsed).with_detail(format!(
+                "durability: {} commits for {} blocks, lag avg {:.2} ms, max {:.2} ms",
+                result.commits,
+                result.blocks.len(),
+                result.mean_durability_lag().as_secs_f64() * 1000.0,
+                result.max_durability_lag().as_secs_f64() * 1000.0
+            ));
+            if self.0.state_root_threads.is_some() {
+                report = report.with_detail(format!(
+                    "state root time: {:.2} ms ({} roots)",
+                    state_root_time.as_secs_f64() * 1000.0,
+                    result.commits
+                ));
+            }
+            if self.0.mdbx.changesets {
+                report = report.with_detail(check_history(&executor, workload, &result));
+            }
+            if self.0.mdbx.atomic_blocks {
+                report = report.with_detail(format!(
+                    "discarded blocks: {} of {}",
+                    result.discarded_blocks(),
+                    result.blocks.len()
+                ));
+            }
+            let footprint = Footprint::of_workload(dir.path(), workload, self.0.background_accounts);
+            report.with_detail(format!("storage: {}", footprint))
+        }
+    }
+
+    /// Rebuilds every block's checkpoint from the batched executor's recorded
+    /// history and reports the archival read rate and whether they match
+    #[cfg(feature = "mdbx")]
+    fn check_history(
+        executor: &crate::executor::MdbxBatchedExecutor,
+        workload: &Workload,
+        result: &crate::executor::MultiBlockResult,
+    ) -> String {
+        use crate::executor::{first_divergent_block, CheckpointRecorder};
+
+        let reader = executor.committed_state().expect("Failed to open committed state");
+        let mut checkpoints = CheckpointRecorder::new(workload, 0);
+        let mut reads = 0;
+        let start = Instant::now();
+        for block in &result.blocks {
+            reads += checkpoints.next_touched().len();
+            checkpoints
+                .try_record_block(|address| {
+                    Ok::<_, eyre::Report>(
+                        reader.account_at(address, block.block_number)?.map(|account| (account.nonce, account.balance)),
+                    )
+                })
+                .expect("Failed to read history");
+        }
+        let elapsed = start.elapsed();
+
+        let expected: Vec<_> = result.blocks.iter().map(|block| block.checkpoint).collect();
+        let verdict = match first_divergent_block(&expected, &checkpoints.finish()) {
+            Some(block) => format!("checkpoints diverge at block {}", block),
+            None => "checkpoints match".to_string(),
+        };
+        format!(
+            "history: {} archival reads at {:.0} reads/s, {}",
+            reads,
+            reads as f64 / elapsed.as_secs_f64(),
+            verdict
+        )
+    }
+
+    #[cfg(feature = "mdbx")]
+    pub(super) struct TwoPhase(pub ExecutorOptions);
+
+    #[cfg(feature = "mdbx")]
+    impl AnyExecutor for TwoPhase {
+        fn name(&self) -> String {
+            "mdbx_two_phase".to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            true
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            let dir = tempfile::tempdir().expect("Failed to create temp directory");
+            let executor =
+                crate::executor::TwoPhaseCommitExecutor::new(dir.path(), self.0.shards, self.0.verify_signatures)
+                    .expect("Failed to create two-phase commit executor");
+
+            let start = Instant::now();
+            let result = executor.execute_workload(workload).expect("Execution failed");
+            let elapsed = start.elapsed();
+            let detail = format!(
+                "shards: {}, cross-shard: {:.1}% ({} txs, {} aborts), single-shard time: {:.2} ms, \
+                 cross-shard time: {:.2} ms",
+                self.0.shards,
+                result.cross_shard_fraction() * 100.0,
+                result.cross_shard,
+                result.aborts,
+                result.single_shard_time.as_secs_f64() * 1000.0,
+                result.cross_shard_time.as_secs_f64() * 1000.0
+            );
+            ExecutionReport::new(result.result, elapsed)
+                .with_detail(detail)
+                .with_detail(format!("storage: {}", Footprint::of_workload(dir.path(), workload, 0)))
+        }
+    }
+
+    #[cfg(feature = "fdb")]
+    pub(super) struct FdbParallel(pub ExecutorOptions, pub crate::options::FdbDispatch);
+
+    #[cfg(feature = "fdb")]
+    impl AnyExecutor for FdbParallel {
+        fn name(&self) -> String {
+            use crate::options::FdbDispatch;
+
+            let num_threads = self.0.num_threads;
+            match (self.0.fdb.commitment_shards, self.1) {
+                (Some(num_shards), FdbDispatch::Threads) => format!("fdb_merkle{}_{}t", num_shards, num_threads),
+                (Some(num_shards), FdbDispatch::Async) => format!("fdb_async_merkle{}_{}t", num_shards, num_threads),
+                (None, FdbDispatch::Threads) => format!("fdb_parallel_{}t", num_threads),
+                (None, FdbDispatch::Async) => format!("fdb_async_{}t", num_threads),
+            }
+        }
+
+        fn preserves_order(&self) -> bool {
+            false
+        }
+
+        fn supports_tokens(&self) -> bool {
+            true
+        }
+
+        fn supports_nonceless(&self) -> bool {
+            true
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            let options = &self.0;
+            let codec = crate::codec::codec_by_name(options.fdb.codec).expect("Unknown account codec");
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            let (result, elapsed, verified, logged) = rt.block_on(async {
+                let mut executor =
+                    crate::executor::FdbParallelExecutor::new(options.num_threads, options.verify_signatures)
+                        .await
+                        .expect("Failed to create FDB executor")
+                        .with_codec(codec)
+                        .with_options(&options.fdb)
+                        .with_dispatch(self.1);
+                if let Some(num_shards) = options.fdb.commitment_shards {
+                    executor = executor.with_commitment(num_shards);
+                }
+                if options.fdb.transfer_log {
+                    executor = executor.with_transfer_log();
+                }
+
+                // execute_workload clears the keyspace, so with background
+                // accounts clear up front and run the workload alongside them
+                let background = options.background(workload.config.seed);
+                if let Some(background) = &background {
+                    executor.clear_database().await.expect("Failed to clear FDB");
+                    executor.load_background(background).await.expect("Failed to load background accounts");
+                }
+
+                let start = Instant::now();
+                let result = match background {
+                    Some(_) => executor.execute_workload_shared(workload).await,
+                    None => executor.execute_workload(workload).await,
+                }
+                .expect("Execution failed");
+                let elapsed = start.elapsed();
+
+                // Verification is reported separately and excluded from TPS
+                let verify_start = Instant::now();
+                let verified = executor
+                    .verify_commitments()
+                    .await
+                    .expect("Commitment verification failed")
+                    .map(|matches| (matches, verify_start.elapsed()));
+
+                // Nonce-less transfers have no per-sender order to check
+                let logged = if options.fdb.transfer_log {
+                    let log = executor.transfer_log().await.expect("Failed to read transfer log");
+                    let violation = (!workload.config.nonceless)
+                        .then(|| crate::executor::first_sender_order_violation(&log))
+                        .flatten();
+                    Some((log.len(), violation))
+                } else {
+                    None
+                };
+
+                (result, elapsed, verified, logged)
+            });
+
+            // Retry sleeps are summed over threads, so they can exceed wall time
+            let mut report = ExecutionReport::new(result.to_execution_result(), elapsed).with_detail(format!(
+                "validation retries: {} ({:.2} ms backing off), gave up: {}",
+                result.total_retries,
+                result.total_backoff.as_secs_f64() * 1000.0,
+                result.total_gave_up
+            ));
+            if let Some((matches, verify_time)) = verified {
+                report = report.with_detail(format!(
+                    "commitment: {} ({:.2} ms to verify)",
+                    if matches { "verified" } else { "MISMATCH" },
+                    verify_time.as_secs_f64() * 1000.0
+                ));
+            }
+            if let Some((entries, violation)) = logged {
+                report = report.with_detail(match violation {
+                    Some(index) => {
+                        format!("transfer log: {} entries, sender order VIOLATED at entry {}", entries, index)
+                    }
+                    None => format!("transfer log: {} entries, sender order verified", entries),
+                });
+            }
+            report
+        }
+    }
+
+    #[cfg(feature = "fdb")]
+    pub(super) struct FdbRevm(pub ExecutorOptions);
+
+    #[cfg(feature = "fdb")]
+    impl AnyExecutor for FdbRevm {
+        fn name(&self) -> String {
+            "fdb_revm_sequential".to_string()
+        }
+
+        fn preserves_order(&self) -> bool {
+            true
+        }
+
+        fn execute_workload(&self, workload: &Workload) -> ExecutionReport {
+            use crate::Executor;
+
+            let codec = crate::codec::codec_by_name(self.0.fdb.codec).expect("Unknown account codec");
+            let provider = crate::executor::FdbStateProvider::new()
+                .expect("Failed to connect to FoundationDB")
+                .with_codec(codec)
+                .with_key_scheme(self.0.fdb.key_scheme);
+            provider.init_workload(workload).expect("Failed to initialize FDB accounts");
+            if let Some(background) = self.0.background(workload.config.seed) {
+                provider.load_background(&background).expect("Failed to load background accounts");
+            }
+            let mut executor = crate::executor::FdbRevmExecutor::new(self.0.verify_signatures);
+            if let Some(lookahead) = self.0.prefetch {
+                executor = executor.with_prefetch(lookahead);
+            }
+
+            let start = Instant::now();
+            let (provider, result) = executor.execute(provider, workload);
+            let elapsed = start.elapsed();
+
+            let stats = provider.stats();
+            let mut report = ExecutionReport::new(result, elapsed).with_detail(format!(
+                "fdb reads: {}, coalesced reads: {}, commits: {}",
+                stats.fdb_reads, stats.coalesced_reads, stats.commits
+            ));
+            if let Some(prefetch) = stats.prefetch {
+                report = report.with_detail(prefetch_detail(&prefetch));
+            }
+            report
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SequentialExecutor, Workload, WorkloadConfig};
+
+    #[test]
+    fn test_register_and_create_by_name() {
+        let mut registry = ExecutorRegistry::builtin();
+        let builtin_count = registry.entries().len();
+        assert!(registry.get("sequential").is_some());
+        assert!(registry.get("nonexistent").is_none());
+
+        registry.register("custom", "Custom", true, |options| {
+            Box::new(SequentialExecutor::with_verification(options.verify_signatures))
+        });
+        // Re-registering a name replaces the entry instead of duplicating it
+        registry.register("custom", "Custom (replaced)", true, |_| {
+            Box::new(SequentialExecutor::with_verification(false))
+        });
+        assert_eq!(registry.entries().len(), builtin_count + 1);
+
+        let entry = registry.get("custom").unwrap();
+        assert_eq!(entry.title, "Custom (replaced)");
+        assert!(entry.threaded);
+
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            ..Default::default()
+        });
+        let executor = entry.create(&ExecutorOptions::default());
+        let report = executor.execute_workload(&workload);
+        assert_eq!(executor.name(), "sequential_in_memory");
+        assert_eq!(report.result.successful, 20);
+    }
+
+    #[cfg(feature = "mdbx")]
+    #[test]
+    fn test_mdbx_executors_report_their_details() {
+        let registry = ExecutorRegistry::builtin();
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            transactions_per_block: 10,
+            hot_accounts: 10,
+            seed: 42,
+            chain_id: 1,
+            ..Default::default()
+        });
+        let options = ExecutorOptions {
+            prefetch: Some(4),
+            state_root_threads: Some(2),
+            shards: 3,
+            ..Default::default()
+        };
+
+        for (name, detail) in [
+            ("mdbx_sequential", "prefetched: "),
+            ("mdbx_batched", "state root time: "),
+            ("two_phase", "shards: 3, "),
+        ] {
+            let report = registry.get(name).unwrap().create(&options).execute_workload(&workload);
+            assert_eq!(report.result.successful, 20, "{}", name);
+            assert!(report.details.iter().any(|line| line.starts_with(detail)), "{}: {:?}", name, report.details);
+            assert!(report.details.iter().any(|line| line.starts_with("storage: ")), "{}", name);
+        }
+    }
+}
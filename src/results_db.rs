@@ -0,0 +1,308 @@
+//! Persistent results database.
+//!
+//! [`ResultsDb`] appends every cell run of a benchmark, with the build and
+//! command line it ran under, to a SQLite file, so results from many runs
+//! can be compared over time. `db-test bench --results-db <PATH>` records a
+//! run; `db-test history <PATH>` lists the recorded runs and each executor's
+//! throughput trend across them.
+//!
+//! Runs are recorded from their [`RunManifest`], so the database holds the
+//! same fields as a manifest: one `runs` row per benchmark run and one
+//! `cells` row per cell run.
+
+use crate::manifest::RunManifest;
+use rusqlite::{params, Connection};
+use std::fmt;
+use std::path::Path;
+
+/// Tables and indexes, created on first open.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    git_hash TEXT NOT NULL,
+    features TEXT NOT NULL,
+    args TEXT NOT NULL,
+    executor_options TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS cells (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    cell_index INTEGER NOT NULL,
+    repetition INTEGER NOT NULL,
+    section TEXT NOT NULL,
+    group_label TEXT,
+    executor_name TEXT NOT NULL,
+    hot_accounts_label TEXT NOT NULL,
+    preserves_order INTEGER NOT NULL,
+    successful INTEGER NOT NULL,
+    failed INTEGER NOT NULL,
+    duration_ms REAL NOT NULL,
+    throughput_tps REAL NOT NULL,
+    result_hash TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS cells_by_executor ON cells (executor_name, run_id);
+";
+
+/// Error opening, writing or querying a results database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultsDbError(pub String);
+
+impl fmt::Display for ResultsDbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ResultsDbError {}
+
+impl From<rusqlite::Error> for ResultsDbError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self(format!("results database: {}", err))
+    }
+}
+
+/// A recorded benchmark run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub id: i64,
+    /// When the run was recorded, as `YYYY-MM-DD HH:MM:SS` UTC.
+    pub recorded_at: String,
+    pub git_hash: String,
+    pub features: Vec<String>,
+    /// Command-line arguments, excluding the program name.
+    pub args: Vec<String>,
+    /// Cell runs recorded, repetitions included.
+    pub cells: usize,
+}
+
+/// One executor configuration's mean result in one run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendPoint {
+    pub run_id: i64,
+    /// When the run was recorded, as `YYYY-MM-DD HH:MM:SS` UTC.
+    pub recorded_at: String,
+    pub git_hash: String,
+    pub executor_name: String,
+    /// Cell group within the section, e.g. a thread count.
+    pub group: Option<String>,
+    pub hot_accounts_label: String,
+    /// Cell runs averaged, repetitions included.
+    pub samples: usize,
+    pub mean_duration_ms: f64,
+    pub mean_throughput_tps: f64,
+    /// Throughput change from the previous run of the same configuration, in percent.
+    pub change_pct: Option<f64>,
+}
+
+impl TrendPoint {
+    /// Whether `other` measures the same executor configuration.
+    fn same_series(&self, other: &TrendPoint) -> bool {
+        self.executor_name == other.executor_name
+            && self.group == other.group
+            && self.hot_accounts_label == other.hot_accounts_label
+    }
+}
+
+/// SQLite file of benchmark results.
+pub struct ResultsDb {
+    conn: Connection,
+}
+
+impl ResultsDb {
+    /// Opens the database at `path`, creating it and its tables if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ResultsDbError> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Appends the run described by `manifest`, recorded at `timestamp`
+    /// seconds since the Unix epoch, and returns its id.
+    pub fn record_run(&mut self, manifest: &RunManifest, timestamp: u64) -> Result<i64, ResultsDbError> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO runs (timestamp, git_hash, features, args, executor_options)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                timestamp as i64,
+                manifest.git_hash,
+                manifest.features.join(","),
+                manifest.args.join("\n"),
+                manifest.executor_options.join("\n"),
+            ],
+        )?;
+        let run_id = tx.last_insert_rowid();
+
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO cells (run_id, cell_index, repetition, section, group_label, executor_name,
+                     hot_accounts_label, preserves_order, successful, failed, duration_ms, throughput_tps,
+                     result_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            )?;
+            for cell in &manifest.cells {
+                insert.execute(params![
+                    run_id,
+                    cell.index as i64,
+                    cell.repetition as i64,
+                    cell.section,
+                    cell.group,
+                    cell.executor_name,
+                    cell.hot_accounts_label,
+                    cell.preserves_order,
+                    cell.successful as i64,
+                    cell.failed as i64,
+                    cell.duration_ms,
+                    cell.throughput_tps,
+                    cell.result_hash.to_string(),
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(run_id)
+    }
+
+    /// The `limit` most recent runs, newest first.
+    pub fn runs(&self, limit: usize) -> Result<Vec<RunSummary>, ResultsDbError> {
+        let mut query = self.conn.prepare(
+            "SELECT runs.id, datetime(runs.timestamp, 'unixepoch'), runs.git_hash, runs.features, runs.args,
+                 (SELECT COUNT(*) FROM cells WHERE cells.run_id = runs.id)
+             FROM runs ORDER BY runs.id DESC LIMIT ?1",
+        )?;
+        let runs = query
+            .query_map(params![limit as i64], |row| {
+                let features: String = row.get(3)?;
+                let args: String = row.get(4)?;
+                Ok(RunSummary {
+                    id: row.get(0)?,
+                    recorded_at: row.get(1)?,
+                    git_hash: row.get(2)?,
+                    features: split_list(&features, ','),
+                    args: split_list(&args, '\n'),
+                    cells: row.get::<_, i64>(5)? as usize,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(runs)
+    }
+
+    /// Mean result per run of every executor configuration, optionally only
+    /// those of `executor`.
+    ///
+    /// Points are grouped by configuration (executor, group and hot-account
+    /// label) and ordered by run within each.
+    pub fn trend(&self, executor: Option<&str>) -> Result<Vec<TrendPoint>, ResultsDbError> {
+        let mut query = self.conn.prepare(
+            "SELECT runs.id, datetime(runs.timestamp, 'unixepoch'), runs.git_hash, cells.executor_name,
+                 cells.group_label, cells.hot_accounts_label, COUNT(*), AVG(cells.duration_ms),
+                 AVG(cells.throughput_tps)
+             FROM cells JOIN runs ON runs.id = cells.run_id
+             WHERE ?1 IS NULL OR cells.executor_name = ?1
+             GROUP BY runs.id, cells.executor_name, cells.group_label, cells.hot_accounts_label
+             ORDER BY cells.executor_name, cells.group_label, cells.hot_accounts_label, runs.id",
+        )?;
+        let mut points = query
+            .query_map(params![executor], |row| {
+                Ok(TrendPoint {
+                    run_id: row.get(0)?,
+                    recorded_at: row.get(1)?,
+                    git_hash: row.get(2)?,
+                    executor_name: row.get(3)?,
+                    group: row.get(4)?,
+                    hot_accounts_label: row.get(5)?,
+                    samples: row.get::<_, i64>(6)? as usize,
+                    mean_duration_ms: row.get(7)?,
+                    mean_throughput_tps: row.get(8)?,
+                    change_pct: None,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for index in 1..points.len() {
+            let (previous, current) = (&points[index - 1], &points[index]);
+            if current.same_series(previous) && previous.mean_throughput_tps > 0.0 {
+                let change = (current.mean_throughput_tps / previous.mean_throughput_tps - 1.0) * 100.0;
+                points[index].change_pct = Some(change);
+            }
+        }
+        Ok(points)
+    }
+}
+
+fn split_list(joined: &str, separator: char) -> Vec<String> {
+    if joined.is_empty() {
+        Vec::new()
+    } else {
+        joined.split(separator).map(str::to_string).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::CellRecord;
+    use alloy_primitives::B256;
+    use tempfile::tempdir;
+
+    fn manifest(git_hash: &str, throughputs: &[f64]) -> RunManifest {
+        RunManifest {
+            git_hash: git_hash.to_string(),
+            features: vec!["block-stm".to_string()],
+            args: vec!["--block-stm".to_string(), "-t".to_string(), "4".to_string()],
+            cells: throughputs
+                .iter()
+                .enumerate()
+                .map(|(repetition, &throughput_tps)| CellRecord {
+                    index: 0,
+                    repetition,
+                    section: "Block-STM".to_string(),
+                    group: Some("4 threads".to_string()),
+                    executor_name: "block_stm_parallel".to_string(),
+                    hot_accounts_label: "10 accounts".to_string(),
+                    preserves_order: true,
+                    successful: 100,
+                    failed: 0,
+                    duration_ms: 100_000.0 / throughput_tps,
+                    throughput_tps,
+                    result_hash: B256::ZERO,
+                    details: Vec::new(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_runs_and_trend() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bench.sqlite");
+
+        {
+            let mut db = ResultsDb::open(&path).unwrap();
+            assert!(db.runs(10).unwrap().is_empty());
+            assert_eq!(db.record_run(&manifest("aaaa", &[1000.0, 3000.0]), 1_700_000_000).unwrap(), 1);
+        }
+
+        // Reopening appends to the same history
+        let mut db = ResultsDb::open(&path).unwrap();
+        assert_eq!(db.record_run(&manifest("bbbb", &[2200.0]), 1_700_086_400).unwrap(), 2);
+
+        let runs = db.runs(10).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].git_hash, "bbbb");
+        assert_eq!(runs[0].cells, 1);
+        assert_eq!(runs[1].cells, 2);
+        assert_eq!(runs[1].recorded_at, "2023-11-14 22:13:20");
+        assert_eq!(runs[1].args, vec!["--block-stm", "-t", "4"]);
+        assert_eq!(db.runs(1).unwrap().len(), 1);
+
+        let trend = db.trend(Some("block_stm_parallel")).unwrap();
+        assert_eq!(trend.len(), 2);
+        assert_eq!(trend[0].samples, 2);
+        assert_eq!(trend[0].mean_throughput_tps, 2000.0);
+        assert_eq!(trend[0].change_pct, None);
+        assert!((trend[1].change_pct.unwrap() - 10.0).abs() < 1e-9);
+        assert!(db.trend(Some("sequential_in_memory")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,337 @@
+//! Result comparison against reth's block executor.
+//!
+//! [`execute_with_reth`] re-signs a workload as real EIP-1559 transactions and
+//! runs it block by block through reth's Ethereum block executor, the same
+//! code path reth's execution stage uses. [`compare_with_reth`] then runs a
+//! local executor over the same workload and diffs per-transaction receipts
+//! and every account's final nonce and balance.
+//!
+//! Reth treats an invalid transaction as an invalid block, while this crate's
+//! executors skip it. Transactions are therefore fed one at a time the way
+//! reth's payload builder does: a transaction the block executor rejects
+//! (bad nonce, insufficient funds) is recorded as failed and the block
+//! continues.
+//!
+//! Transactions pay a gas price of zero on a zero base fee, so reth's final
+//! balances only reflect transferred value, as Block-STM's do. Receipts are
+//! still checked to charge the 21000 gas the local executors assume.
+
+use crate::executor::{Executor, TxStatus};
+use crate::recovery::RecoveryBackend;
+use crate::{GenesisState, Workload};
+use alloy_consensus::constants::{EMPTY_OMMER_ROOT_HASH, EMPTY_WITHDRAWALS};
+use alloy_consensus::Header;
+use alloy_primitives::{Address, B256, U256};
+use reth_chainspec::{Chain, ChainSpecBuilder};
+use reth_ethereum_primitives::{Block, BlockBody, TransactionSigned};
+use reth_evm::block::{BlockExecutionError, BlockExecutor, BlockValidationError};
+use reth_evm::ConfigureEvm;
+use reth_evm_ethereum::EthEvmConfig;
+use reth_primitives_traits::{Recovered, SealedBlock};
+use revm::database::{CacheDB, State};
+use revm::database_interface::{Database, DatabaseRef};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Gas every transfer is expected to use.
+const TRANSFER_GAS: u64 = 21_000;
+/// Minimum block gas limit, as on mainnet.
+const MIN_BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+/// Error produced when reth fails to execute a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RethCompareError(pub String);
+
+impl fmt::Display for RethCompareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "reth execution error: {}", self.0)
+    }
+}
+
+impl std::error::Error for RethCompareError {}
+
+/// Outcome of running a workload through reth.
+#[derive(Debug, Clone, Default)]
+pub struct RethExecution {
+    /// Status per transaction, in workload order.
+    pub statuses: Vec<TxStatus>,
+    /// Gas used per transaction, in workload order; zero if rejected.
+    pub gas_used: Vec<u64>,
+    /// Final nonce and balance of every workload account.
+    pub accounts: HashMap<Address, (u64, U256)>,
+}
+
+impl RethExecution {
+    /// Number of transactions with a successful receipt.
+    pub fn successful(&self) -> usize {
+        self.statuses.iter().filter(|status| status.is_success()).count()
+    }
+}
+
+/// A transaction whose outcome differs between reth and a local executor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptMismatch {
+    /// Block number (0-based) and index within the block.
+    pub block: usize,
+    pub index: usize,
+    pub tx_hash: B256,
+    pub reth: TxStatus,
+    pub local: TxStatus,
+    /// Gas reth charged; differs from 21000 only if the transfer was not plain.
+    pub reth_gas_used: u64,
+}
+
+/// An account whose final state differs between reth and a local executor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateMismatch {
+    pub address: Address,
+    /// Final (nonce, balance) according to reth.
+    pub reth: (u64, U256),
+    /// Final (nonce, balance) according to the local executor.
+    pub local: (u64, U256),
+}
+
+/// Differences between reth and a local executor over one workload.
+#[derive(Debug, Clone, Default)]
+pub struct RethComparison {
+    pub reth_successful: usize,
+    pub local_successful: usize,
+    pub receipt_mismatches: Vec<ReceiptMismatch>,
+    pub state_mismatches: Vec<StateMismatch>,
+}
+
+impl RethComparison {
+    /// Whether the local executor matches reth on every receipt and account.
+    pub fn agrees(&self) -> bool {
+        self.receipt_mismatches.is_empty() && self.state_mismatches.is_empty()
+    }
+}
+
+/// Executes `workload` through reth's Ethereum block executor.
+///
+/// Signatures are checked against the workload's own signing scheme first
+/// (unless it uses fake signatures); transactions that fail never reach reth,
+/// matching executors that verify signatures.
+pub fn execute_with_reth(workload: &Workload) -> Result<RethExecution, RethCompareError> {
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::mainnet()
+            .chain(Chain::from_id(workload.config.chain_id))
+            .shanghai_activated()
+            .build(),
+    );
+    let evm_config = EthEvmConfig::new(chain_spec);
+    let keys: HashMap<Address, _> = workload
+        .accounts
+        .iter()
+        .map(|account| (account.address, &account.signing_key))
+        .collect();
+    let recovery = RecoveryBackend::default();
+
+    let mut state = State::builder()
+        .with_database(workload.create_db())
+        .with_bundle_update()
+        .build();
+    let mut statuses = Vec::with_capacity(workload.transactions.len());
+    let mut gas_used = Vec::with_capacity(workload.transactions.len());
+
+    for (number, transactions) in workload.blocks.iter().enumerate() {
+        let mut signed = Vec::with_capacity(transactions.len());
+        for tx in transactions {
+            let key = keys
+                .get(&tx.from)
+                .filter(|_| workload.config.fake_signatures || recovery.verify(tx));
+            signed.push(key.map(|key| {
+                let tx_signed: TransactionSigned = tx.to_eip1559(key, tx.nonce, workload.config.chain_id, 0, 0).into();
+                Recovered::new_unchecked(tx_signed, tx.from)
+            }));
+        }
+
+        let block = build_block(number as u64 + 1, signed.iter().flatten().map(|tx| tx.inner().clone()).collect());
+        let mut executor = evm_config
+            .executor_for_block(&mut state, &block)
+            .map_err(|err| RethCompareError(format!("block {}: {}", number, err)))?;
+        executor
+            .apply_pre_execution_changes()
+            .map_err(|err| RethCompareError(format!("block {}: {}", number, err)))?;
+
+        let mut executed = Vec::with_capacity(signed.len());
+        for tx in &signed {
+            let outcome = match tx {
+                Some(tx) => match executor.execute_transaction(tx.as_recovered_ref()) {
+                    Ok(gas) => Some(gas),
+                    Err(BlockExecutionError::Validation(BlockValidationError::InvalidTx { .. })) => None,
+                    Err(err) => return Err(RethCompareError(format!("block {}: {}", number, err))),
+                },
+                None => None,
+            };
+            executed.push(outcome);
+        }
+
+        let (_, result) = executor
+            .finish()
+            .map_err(|err| RethCompareError(format!("block {}: {}", number, err)))?;
+        let mut receipts = result.receipts.iter();
+        for outcome in executed {
+            match outcome {
+                Some(gas) => {
+                    let receipt = receipts.next().expect("one receipt per executed transaction");
+                    statuses.push(TxStatus::from(receipt.success));
+                    gas_used.push(gas);
+                }
+                None => {
+                    statuses.push(TxStatus::Failed);
+                    gas_used.push(0);
+                }
+            }
+        }
+    }
+
+    let mut accounts = HashMap::with_capacity(workload.accounts.len());
+    for account in &workload.accounts {
+        let info = state
+            .basic(account.address)
+            .map_err(|err| RethCompareError(format!("reading {}: {}", account.address, err)))?
+            .unwrap_or_default();
+        accounts.insert(account.address, (info.nonce, info.balance));
+    }
+
+    Ok(RethExecution {
+        statuses,
+        gas_used,
+        accounts,
+    })
+}
+
+/// Runs `executor` and reth over `workload` and diffs their receipts and final state.
+pub fn compare_with_reth<E>(executor: &E, workload: &Workload) -> Result<RethComparison, RethCompareError>
+where
+    E: Executor<Database = CacheDB<GenesisState>>,
+{
+    let reth = execute_with_reth(workload)?;
+    let (db, result) = executor.execute(workload.create_db(), workload);
+
+    let per_block = workload.transactions_per_block().max(1);
+    let receipt_mismatches = workload
+        .transactions
+        .iter()
+        .enumerate()
+        .filter_map(|(position, tx)| {
+            let (block, index) = (position / per_block, position % per_block);
+            let local = result
+                .tx_index
+                .occurrences(&tx.tx_hash)
+                .iter()
+                .find(|location| location.block == block && location.index == index)
+                .map_or(TxStatus::Failed, |location| location.status);
+            let reth_status = reth.statuses[position];
+            let reth_gas_used = reth.gas_used[position];
+            let gas_ok = !reth_status.is_success() || reth_gas_used == TRANSFER_GAS;
+
+            (reth_status != local || !gas_ok).then_some(ReceiptMismatch {
+                block,
+                index,
+                tx_hash: tx.tx_hash,
+                reth: reth_status,
+                local,
+                reth_gas_used,
+            })
+        })
+        .collect();
+
+    let mut state_mismatches: Vec<_> = workload
+        .accounts
+        .iter()
+        .filter_map(|account| {
+            let reth_state = reth.accounts[&account.address];
+            let local_state = db
+                .basic_ref(account.address)
+                .ok()
+                .flatten()
+                .map_or((0, U256::ZERO), |local| (local.nonce, local.balance));
+
+            (reth_state != local_state).then_some(StateMismatch {
+                address: account.address,
+                reth: reth_state,
+                local: local_state,
+            })
+        })
+        .collect();
+    state_mismatches.sort_by_key(|mismatch| mismatch.address);
+
+    Ok(RethComparison {
+        reth_successful: reth.successful(),
+        local_successful: result.successful,
+        receipt_mismatches,
+        state_mismatches,
+    })
+}
+
+fn build_block(number: u64, transactions: Vec<TransactionSigned>) -> SealedBlock<Block> {
+    let header = Header {
+        number,
+        timestamp: number * 12,
+        gas_limit: MIN_BLOCK_GAS_LIMIT.max(transactions.len() as u64 * TRANSFER_GAS),
+        base_fee_per_gas: Some(0),
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        withdrawals_root: Some(EMPTY_WITHDRAWALS),
+        ..Default::default()
+    };
+    let body = BlockBody {
+        transactions,
+        ommers: Vec::new(),
+        withdrawals: Some(Default::default()),
+    };
+    SealedBlock::seal_slow(Block { header, body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockStmExecutor, ComputeCost, SequentialExecutor, WorkloadConfig};
+
+    fn workload() -> Workload {
+        Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 30,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            seed: 42,
+            chain_id: 1,
+            duplicate_transactions: 2,
+            double_spends: 2,
+            fake_signatures: false,
+            tokens: 0,
+            nonceless: false,
+            compute_cost: ComputeCost::default(),
+            near_max_balance: None,
+            hot_probability: 1.0,
+        })
+    }
+
+    #[test]
+    fn test_reth_rejects_replays() {
+        let reth = execute_with_reth(&workload()).unwrap();
+        assert_eq!(reth.statuses.len(), 34);
+        assert_eq!(reth.successful(), 30);
+        assert!(reth.gas_used.iter().all(|&gas| gas == 0 || gas == TRANSFER_GAS));
+    }
+
+    #[test]
+    fn test_block_stm_matches_reth() {
+        let comparison = compare_with_reth(&BlockStmExecutor::new(4, true), &workload()).unwrap();
+        assert!(comparison.agrees(), "{:?}", comparison);
+        assert_eq!(comparison.local_successful, 30);
+    }
+
+    #[test]
+    fn test_sequential_differs_only_in_gas_fees() {
+        // The sequential executor charges gas at a price of 1 wei, reth here at 0
+        let comparison = compare_with_reth(&SequentialExecutor::with_verification(true), &workload()).unwrap();
+        assert!(comparison.receipt_mismatches.is_empty());
+        for mismatch in &comparison.state_mismatches {
+            assert_eq!(mismatch.reth.0, mismatch.local.0);
+            assert_eq!(mismatch.reth.1 - mismatch.local.1, U256::from(TRANSFER_GAS * mismatch.local.0));
+        }
+    }
+}
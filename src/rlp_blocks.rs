@@ -0,0 +1,280 @@
+//! Block files in Ethereum's RLP encoding.
+//!
+//! [`export_blocks`] writes a workload's blocks the way `geth export` and
+//! `reth export` do: RLP-encoded `[header, transactions, ommers]` blocks back
+//! to back. Each transfer is re-signed as an EIP-1559 transaction with its
+//! sender's key (see [`SignedTransaction::to_eip1559`]), so any EVM tooling
+//! can decode and replay the file. Headers are stubs: number, parent hash,
+//! gas, base fee, timestamp and transactions root are filled in, while the
+//! state and receipts roots are left empty, since the executors here do not
+//! compute them.
+//!
+//! [`import_blocks`] reads such a file, including blocks captured from a real
+//! network, back into a workload. Every value transfer becomes a transaction
+//! whose [`tx_hash`](SignedTransaction::tx_hash) is its Ethereum signing
+//! hash, so signature recovery yields its real sender; contract creations
+//! and calls with input data have no equivalent here and are skipped. Each
+//! sender and recipient becomes a funded workload account with a placeholder
+//! key, so imported workloads can be executed and exported as they are but
+//! not extended with new transactions.
+//!
+//! Because imported hashes are not the simplified ones the workload
+//! generator signs, [`Workload::validate`](crate::Workload::validate) reports
+//! them as hash mismatches; their signatures still verify.
+
+use crate::{Account, SignedTransaction, Workload, WorkloadConfig};
+use alloy_consensus::{proofs, Block, BlockBody, Header, Transaction, TxEnvelope};
+use alloy_primitives::{keccak256, Address, TxKind, B256};
+use alloy_rlp::{Decodable, Encodable};
+use k256::ecdsa::SigningKey;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Gas a transfer uses, as the executors charge it.
+const TRANSFER_GAS: u64 = 21_000;
+/// Base fee of exported blocks and fee cap of their transactions, in wei:
+/// the gas price the executors charge.
+const GAS_PRICE: u64 = 1;
+/// Smallest gas limit of an exported block, that of mainnet blocks.
+const MIN_GAS_LIMIT: u64 = 30_000_000;
+/// Seconds between exported blocks.
+const BLOCK_TIME: u64 = 12;
+
+/// Error reading or writing a block file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RlpBlocksError(pub String);
+
+impl fmt::Display for RlpBlocksError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RlpBlocksError {}
+
+/// A workload read from a block file.
+#[derive(Debug, Clone)]
+pub struct ImportedBlocks {
+    /// The transfers, in the file's blocks.
+    pub workload: Workload,
+    /// Transactions skipped because they create or call contracts.
+    pub skipped: usize,
+}
+
+/// Encodes `workload`'s blocks, numbered from 1 on top of an empty genesis.
+///
+/// Every sender must hold the key of its address, so workloads with
+/// placeholder signatures, including imported ones, cannot be exported; nor
+/// can token transfers and nonce-less workloads, which Ethereum
+/// transactions cannot express.
+pub fn encode_blocks(workload: &Workload) -> Result<Vec<u8>, RlpBlocksError> {
+    if workload.config.tokens > 0 {
+        return Err(RlpBlocksError("token transfers have no Ethereum encoding".to_string()));
+    }
+    if workload.config.nonceless {
+        return Err(RlpBlocksError("nonce-less transactions have no Ethereum encoding".to_string()));
+    }
+
+    let mut keys: HashMap<Address, Option<&SigningKey>> = HashMap::new();
+    let mut out = Vec::new();
+    let mut parent_hash = B256::ZERO;
+    for (index, block) in workload.blocks.iter().enumerate() {
+        let mut transactions = Vec::with_capacity(block.len());
+        for tx in block {
+            // Deriving the public key is slow, so each sender's key is checked once
+            let key = *keys.entry(tx.from).or_insert_with(|| {
+                workload
+                    .accounts
+                    .iter()
+                    .find(|account| account.address == tx.from)
+                    .map(|account| &account.signing_key)
+                    .filter(|&key| Account::from_signing_key(key.clone()).address == tx.from)
+            });
+            let key = key.ok_or_else(|| RlpBlocksError(format!("no key for sender {}", tx.from)))?;
+            let signed = tx.to_eip1559(key, tx.nonce, workload.config.chain_id, GAS_PRICE as u128, 0);
+            transactions.push(TxEnvelope::from(signed));
+        }
+
+        let gas_used = TRANSFER_GAS * transactions.len() as u64;
+        let header = Header {
+            parent_hash,
+            number: index as u64 + 1,
+            gas_limit: gas_used.max(MIN_GAS_LIMIT),
+            gas_used,
+            timestamp: (index as u64 + 1) * BLOCK_TIME,
+            base_fee_per_gas: Some(GAS_PRICE),
+            transactions_root: proofs::calculate_transaction_root(&transactions),
+            ..Default::default()
+        };
+        parent_hash = header.hash_slow();
+        Block {
+            header,
+            body: BlockBody {
+                transactions,
+                ommers: Vec::new(),
+                withdrawals: None,
+            },
+        }
+        .encode(&mut out);
+    }
+    Ok(out)
+}
+
+/// Decodes the blocks in `bytes` into a workload.
+///
+/// Blocks keep their transactions as in the file, empty blocks included.
+/// The configuration is `WorkloadConfig::default()` except for the counts,
+/// taken from the blocks, and the chain id, taken from the first
+/// transaction that carries one.
+pub fn decode_blocks(mut bytes: &[u8]) -> Result<ImportedBlocks, RlpBlocksError> {
+    let mut accounts: Vec<Account> = Vec::new();
+    let mut known: HashSet<Address> = HashSet::new();
+    let mut blocks = Vec::new();
+    let mut chain_id = None;
+    let mut skipped = 0;
+
+    while !bytes.is_empty() {
+        let number = blocks.len() + 1;
+        let block = Block::<TxEnvelope>::decode(&mut bytes)
+            .map_err(|err| RlpBlocksError(format!("block {}: {}", number, err)))?;
+
+        let mut transactions = Vec::with_capacity(block.body.transactions.len());
+        for (index, envelope) in block.body.transactions.iter().enumerate() {
+            let TxKind::Call(to) = envelope.kind() else {
+                skipped += 1;
+                continue;
+            };
+            if !envelope.input().is_empty() {
+                skipped += 1;
+                continue;
+            }
+
+            let signature = *envelope.signature();
+            let tx_hash = envelope.signature_hash();
+            let from = signature
+                .recover_address_from_prehash(&tx_hash)
+                .map_err(|_| RlpBlocksError(format!("block {}: tx {}: invalid signature", number, index)))?;
+            chain_id = chain_id.or(envelope.chain_id());
+            for address in [from, to] {
+                if known.insert(address) {
+                    accounts.push(placeholder_account(address));
+                }
+            }
+            transactions.push(SignedTransaction {
+                from,
+                to,
+                value: envelope.value(),
+                token: None,
+                nonce: envelope.nonce(),
+                signature,
+                tx_hash,
+                compute_cost: 0,
+            });
+        }
+        blocks.push(transactions);
+    }
+
+    let transactions: Vec<SignedTransaction> = blocks.iter().flatten().cloned().collect();
+    let defaults = WorkloadConfig::default();
+    let config = WorkloadConfig {
+        num_accounts: accounts.len(),
+        num_transactions: transactions.len(),
+        hot_accounts: accounts.len(),
+        transactions_per_block: blocks.iter().map(Vec::len).max().unwrap_or(0),
+        chain_id: chain_id.unwrap_or(defaults.chain_id),
+        ..defaults
+    };
+    Ok(ImportedBlocks {
+        workload: Workload {
+            accounts,
+            transactions,
+            blocks,
+            config,
+            genesis: OnceLock::new(),
+            signers: OnceLock::new(),
+        },
+        skipped,
+    })
+}
+
+/// An account for `address` whose key is derived from the address, so it
+/// is funded like any other but cannot sign for it.
+fn placeholder_account(address: Address) -> Account {
+    let signing_key = SigningKey::from_bytes(&keccak256(address).0.into()).expect("valid key bytes");
+    Account { signing_key, address }
+}
+
+/// Writes `workload`'s blocks to `path`; see [`encode_blocks`].
+pub fn export_blocks(path: impl AsRef<Path>, workload: &Workload) -> Result<(), RlpBlocksError> {
+    let path = path.as_ref();
+    fs::write(path, encode_blocks(workload)?)
+        .map_err(|err| RlpBlocksError(format!("failed to write {}: {}", path.display(), err)))
+}
+
+/// Reads the blocks in `path`; see [`decode_blocks`].
+pub fn import_blocks(path: impl AsRef<Path>) -> Result<ImportedBlocks, RlpBlocksError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|err| RlpBlocksError(format!("failed to read {}: {}", path.display(), err)))?;
+    decode_blocks(&bytes).map_err(|err| RlpBlocksError(format!("{}: {}", path.display(), err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, SequentialExecutor};
+
+    fn workload() -> Workload {
+        Workload::generate(WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 60,
+            hot_accounts: 5,
+            transactions_per_block: 10,
+            double_spends: 2,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let workload = workload();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocks.rlp");
+        export_blocks(&path, &workload).unwrap();
+        let imported = import_blocks(&path).unwrap();
+        assert_eq!(imported.skipped, 0);
+
+        let imported = imported.workload;
+        assert_eq!(imported.blocks.len(), workload.blocks.len());
+        assert_eq!(imported.config.chain_id, workload.config.chain_id);
+        for (original, imported) in workload.transactions.iter().zip(&imported.transactions) {
+            assert_eq!(
+                (imported.from, imported.to, imported.value, imported.nonce),
+                (original.from, original.to, original.value, original.nonce)
+            );
+            assert!(imported.verify());
+        }
+        assert_eq!(imported.num_replays(), workload.num_replays());
+
+        // The imported transfers execute as the generated ones do
+        let executor = SequentialExecutor::with_verification(true);
+        let (_, expected) = executor.execute(workload.create_db(), &workload);
+        let (_, result) = executor.execute(imported.create_db(), &imported);
+        assert_eq!((result.successful, result.failed), (expected.successful, expected.failed));
+
+        // Placeholder keys cannot re-sign for their addresses
+        assert!(encode_blocks(&imported).is_err());
+    }
+
+    #[test]
+    fn test_unencodable_workloads_are_rejected() {
+        let tokens = Workload::generate(WorkloadConfig {
+            tokens: 2,
+            ..workload().config
+        });
+        assert!(encode_blocks(&tokens).is_err());
+        assert!(decode_blocks(&[0xc0, 0x01]).is_err());
+    }
+}
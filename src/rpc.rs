@@ -0,0 +1,305 @@
+//! Minimal JSON-RPC server over a populated backend.
+//!
+//! [`RpcServer`] answers `eth_getBalance`, `eth_getTransactionCount` and
+//! `eth_chainId` from any [`AccountSource`]: a revm database such as the
+//! `CacheDB` a [`SequentialExecutor`](crate::SequentialExecutor) run leaves
+//! behind, or an MDBX environment. External load generators (k6, flood, ...)
+//! can then benchmark reads against the state a run produced.
+//!
+//! Requests are HTTP/1.1 `POST`s with a JSON-RPC body, single or batched;
+//! connections are kept alive and each is served on its own thread. The block
+//! parameter is accepted but ignored: only the state at the end of the run
+//! exists, so every request reads it.
+
+use alloy_primitives::{Address, U256};
+use revm::database_interface::DatabaseRef;
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+/// Upper bound on a request body, guarding against corrupt length headers.
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// State an [`RpcServer`] reads accounts from.
+pub trait AccountSource: Send + Sync {
+    /// Nonce and balance of `address`, `None` if it has no account.
+    fn account(&self, address: Address) -> Result<Option<(u64, U256)>, String>;
+}
+
+impl<D> AccountSource for D
+where
+    D: DatabaseRef + Send + Sync,
+    D::Error: fmt::Display,
+{
+    fn account(&self, address: Address) -> Result<Option<(u64, U256)>, String> {
+        self.basic_ref(address)
+            .map(|info| info.map(|info| (info.nonce, info.balance)))
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(feature = "mdbx")]
+impl AccountSource for crate::MdbxDatabase {
+    fn account(&self, address: Address) -> Result<Option<(u64, U256)>, String> {
+        self.get_account(address)
+            .map(|account| account.map(|account| (account.nonce, account.balance)))
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// HTTP JSON-RPC server over an [`AccountSource`].
+pub struct RpcServer<S: AccountSource> {
+    source: Arc<S>,
+    chain_id: u64,
+}
+
+impl<S: AccountSource + 'static> RpcServer<S> {
+    /// Creates a server reading from `source` and reporting `chain_id`.
+    pub fn new(source: S, chain_id: u64) -> Self {
+        Self {
+            source: Arc::new(source),
+            chain_id,
+        }
+    }
+
+    /// Accepts connections forever, serving each on its own thread.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = Self {
+                source: self.source.clone(),
+                chain_id: self.chain_id,
+            };
+            thread::spawn(move || {
+                // A disconnecting client ends its session; nothing else to clean up.
+                let _ = server.handle_connection(stream);
+            });
+        }
+        Ok(())
+    }
+
+    /// Serves requests on a single connection until the client disconnects
+    /// or asks to close it.
+    fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+
+        while let Some((body, close)) = read_http_request(&mut reader)? {
+            let response = match serde_json::from_slice::<Value>(&body) {
+                Ok(request) => self.handle(&request),
+                Err(err) => error_response(Value::Null, PARSE_ERROR, err.to_string()),
+            };
+            let payload = response.to_string();
+            write!(
+                writer,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}\r\n{}",
+                payload.len(),
+                if close { "Connection: close\r\n" } else { "" },
+                payload
+            )?;
+            writer.flush()?;
+            if close {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers a JSON-RPC request or batch of requests.
+    pub fn handle(&self, request: &Value) -> Value {
+        match request {
+            Value::Array(batch) if batch.is_empty() => {
+                error_response(Value::Null, INVALID_REQUEST, "empty batch".to_string())
+            }
+            Value::Array(batch) => batch.iter().map(|request| self.handle_single(request)).collect(),
+            request => self.handle_single(request),
+        }
+    }
+
+    fn handle_single(&self, request: &Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return error_response(id, INVALID_REQUEST, "missing method".to_string());
+        };
+        let params = request.get("params").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+
+        let result = match method {
+            "eth_getBalance" => self.read_account(params).map(|(_, balance)| json!(format!("{:#x}", balance))),
+            "eth_getTransactionCount" => self.read_account(params).map(|(nonce, _)| json!(format!("{:#x}", nonce))),
+            "eth_chainId" => Ok(json!(format!("{:#x}", self.chain_id))),
+            method => Err((METHOD_NOT_FOUND, format!("method {} not supported", method))),
+        };
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        }
+    }
+
+    /// Reads the account named by the first parameter; accounts that do not
+    /// exist have nonce and balance zero, as on a node.
+    fn read_account(&self, params: &[Value]) -> Result<(u64, U256), (i64, String)> {
+        let address = params
+            .first()
+            .and_then(Value::as_str)
+            .and_then(|address| address.parse::<Address>().ok())
+            .ok_or_else(|| (INVALID_PARAMS, "expected an address as the first parameter".to_string()))?;
+        let account = self.source.account(address).map_err(|err| (INTERNAL_ERROR, err))?;
+        Ok(account.unwrap_or_default())
+    }
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Reads one HTTP request, returning its body and whether the client asked
+/// to close the connection after it; `None` once the client disconnects.
+fn read_http_request(reader: &mut impl BufRead) -> io::Result<Option<(Vec<u8>, bool)>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let http_1_0 = line.trim_end().ends_with("HTTP/1.0");
+
+    let mut content_length = 0;
+    let mut close = http_1_0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .parse()
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid content length"))?;
+        } else if name.eq_ignore_ascii_case("connection") {
+            close = if http_1_0 {
+                !value.eq_ignore_ascii_case("keep-alive")
+            } else {
+                value.eq_ignore_ascii_case("close")
+            };
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("body of {} bytes exceeds limit", content_length),
+        ));
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some((body, close)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Executor, GenesisState, SequentialExecutor, Workload, WorkloadConfig};
+    use revm::database::CacheDB;
+
+    fn populated() -> (Workload, RpcServer<CacheDB<GenesisState>>) {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 20,
+            num_transactions: 60,
+            hot_accounts: 5,
+            transactions_per_block: 10,
+            fake_signatures: true,
+            ..Default::default()
+        });
+        let executor = SequentialExecutor::with_verification(false);
+        let (db, _) = executor.execute(workload.create_db(), &workload);
+        (workload, RpcServer::new(db, 1))
+    }
+
+    fn call(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params })
+    }
+
+    #[test]
+    fn test_answers_from_executed_state() {
+        let (workload, server) = populated();
+        let sender = workload.transactions[0].from;
+        let sent = workload.transactions.iter().filter(|tx| tx.from == sender).count();
+
+        let response = server.handle(&call("eth_getTransactionCount", json!([sender.to_string(), "latest"])));
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"], format!("{:#x}", sent));
+
+        let response = server.handle(&call("eth_getBalance", json!([sender.to_string(), "latest"])));
+        let balance: U256 = response["result"].as_str().unwrap().parse().unwrap();
+        assert!(balance < workload.initial_balance());
+
+        // Unknown accounts read as empty, as on a node
+        let response = server.handle(&call("eth_getBalance", json!([Address::repeat_byte(0xee).to_string()])));
+        assert_eq!(response["result"], "0x0");
+
+        let batch = server.handle(&json!([call("eth_chainId", json!([])), call("eth_blockNumber", json!([]))]));
+        assert_eq!(batch[0]["result"], "0x1");
+        assert_eq!(batch[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(server.handle(&call("eth_getBalance", json!(["0x12"])))["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_serves_http_with_keep_alive() {
+        let (workload, server) = populated();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || server.serve(listener));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let sender = workload.transactions[0].from.to_string();
+        for _ in 0..2 {
+            let body = call("eth_getTransactionCount", json!([sender, "latest"])).to_string();
+            write!(
+                stream,
+                "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+
+            let mut status = String::new();
+            reader.read_line(&mut status).unwrap();
+            assert!(status.starts_with("HTTP/1.1 200"), "{}", status);
+            let body = read_http_body(&mut reader);
+            let response: Value = serde_json::from_slice(&body).unwrap();
+            assert!(response["result"].as_str().unwrap().starts_with("0x"));
+        }
+    }
+
+    /// Reads the headers and body of a response whose status line was consumed.
+    fn read_http_body(reader: &mut impl BufRead) -> Vec<u8> {
+        let mut length = 0;
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+            if let Some(value) = line.trim_end().strip_prefix("Content-Length: ") {
+                length = value.parse().unwrap();
+            }
+            line.clear();
+        }
+        let mut body = vec![0u8; length];
+        reader.read_exact(&mut body).unwrap();
+        body
+    }
+}
@@ -0,0 +1,211 @@
+//! Versioned JSON documents.
+//!
+//! With the `serde` feature, workload configurations, execution results and
+//! benchmark reports derive `Serialize` and `Deserialize`. [`to_json`] wraps
+//! a value in an envelope naming its [`Schema`] and version:
+//!
+//! ```text
+//! {"schema": "bench-report", "version": 1, "data": { ... }}
+//! ```
+//!
+//! [`from_json`] rejects documents of another schema or a newer version, and
+//! brings older versions up to date one step at a time through
+//! [`Schema::upgrade`] before deserializing. Fields added with a default do
+//! not need a new version: documents written before them still load.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::executor::ExecutionResult;
+use crate::harness::BenchReport;
+use crate::WorkloadConfig;
+
+/// Error reading or writing a versioned document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError(pub String);
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// A type stored as a versioned document.
+pub trait Schema: Serialize + DeserializeOwned {
+    /// Name recorded in, and expected from, the envelope.
+    const NAME: &'static str;
+    /// Current version, written by [`to_json`].
+    const VERSION: u32;
+
+    /// Rewrites `data` from `version` to `version + 1`.
+    ///
+    /// The default knows no older versions and rejects them.
+    fn upgrade(version: u32, data: Value) -> Result<Value, SchemaError> {
+        let _ = data;
+        Err(SchemaError(format!(
+            "{} version {} cannot be upgraded to {}",
+            Self::NAME,
+            version,
+            Self::VERSION
+        )))
+    }
+}
+
+impl Schema for WorkloadConfig {
+    const NAME: &'static str = "workload-config";
+    const VERSION: u32 = 1;
+}
+
+impl Schema for ExecutionResult {
+    const NAME: &'static str = "execution-result";
+    const VERSION: u32 = 1;
+}
+
+impl Schema for BenchReport {
+    const NAME: &'static str = "bench-report";
+    const VERSION: u32 = 1;
+}
+
+/// Envelope around a document's data.
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    schema: String,
+    version: u32,
+    data: T,
+}
+
+/// Serializes `value` as a pretty-printed versioned document.
+pub fn to_json<T: Schema>(value: &T) -> String {
+    let envelope = Envelope {
+        schema: T::NAME.to_string(),
+        version: T::VERSION,
+        data: value,
+    };
+    serde_json::to_string_pretty(&envelope).expect("schema types serialize")
+}
+
+/// Parses a document written by [`to_json`], upgrading older versions.
+pub fn from_json<T: Schema>(json: &str) -> Result<T, SchemaError> {
+    let envelope: Envelope<Value> =
+        serde_json::from_str(json).map_err(|err| SchemaError(format!("invalid document: {}", err)))?;
+    if envelope.schema != T::NAME {
+        return Err(SchemaError(format!(
+            "expected a {} document, found {}",
+            T::NAME,
+            envelope.schema
+        )));
+    }
+    if envelope.version > T::VERSION {
+        return Err(SchemaError(format!(
+            "{} version {} is newer than the supported version {}",
+            T::NAME,
+            envelope.version,
+            T::VERSION
+        )));
+    }
+
+    let mut data = envelope.data;
+    for version in envelope.version..T::VERSION {
+        data = T::upgrade(version, data)?;
+    }
+    serde_json::from_value(data).map_err(|err| SchemaError(format!("invalid {}: {}", T::NAME, err)))
+}
+
+/// Writes `value` to `path` as a versioned document.
+pub fn save_json<T: Schema>(path: impl AsRef<Path>, value: &T) -> Result<(), SchemaError> {
+    let path = path.as_ref();
+    fs::write(path, to_json(value))
+        .map_err(|err| SchemaError(format!("failed to write {}: {}", path.display(), err)))
+}
+
+/// Reads the versioned document in `path`.
+pub fn load_json<T: Schema>(path: impl AsRef<Path>) -> Result<T, SchemaError> {
+    let path = path.as_ref();
+    let json = fs::read_to_string(path)
+        .map_err(|err| SchemaError(format!("failed to read {}: {}", path.display(), err)))?;
+    from_json(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::{TransactionIndex, TxStatus};
+    use crate::{ComputeCost, Workload};
+
+    #[test]
+    fn test_round_trip() {
+        let config = WorkloadConfig {
+            num_transactions: 10,
+            hot_accounts: 4,
+            num_accounts: 8,
+            fake_signatures: true,
+            compute_cost: ComputeCost::Uniform { min: 1, max: 5 },
+            ..Default::default()
+        };
+        assert_eq!(from_json::<WorkloadConfig>(&to_json(&config)), Ok(config.clone()));
+
+        let workload = Workload::generate(config);
+        let statuses = vec![TxStatus::Success; workload.transactions.len()];
+        let result = ExecutionResult::new(10, 0).with_tx_index(TransactionIndex::from_statuses(&workload, &statuses));
+        let loaded: ExecutionResult = from_json(&to_json(&result)).unwrap();
+        assert_eq!(loaded.successful, 10);
+        let tx_hash = workload.transactions[3].tx_hash;
+        assert_eq!(loaded.tx_index.get(&tx_hash), result.tx_index.get(&tx_hash));
+    }
+
+    #[test]
+    fn test_missing_fields_default() {
+        let json = r#"{"schema": "workload-config", "version": 1, "data": {"num_accounts": 20, "hot_accounts": 2}}"#;
+        let config: WorkloadConfig = from_json(json).unwrap();
+        assert_eq!(config.num_accounts, 20);
+        assert_eq!(config.hot_accounts, 2);
+        assert_eq!(config.seed, WorkloadConfig::default().seed);
+    }
+
+    #[test]
+    fn test_reject_mismatched_documents() {
+        let config = to_json(&WorkloadConfig::default());
+        assert!(from_json::<ExecutionResult>(&config).is_err());
+
+        let newer = config.replacen("\"version\": 1", "\"version\": 2", 1);
+        assert!(from_json::<WorkloadConfig>(&newer).unwrap_err().0.contains("newer"));
+        let older = config.replacen("\"version\": 1", "\"version\": 0", 1);
+        assert!(from_json::<WorkloadConfig>(&older).is_err());
+        assert!(from_json::<WorkloadConfig>("{}").is_err());
+    }
+
+    /// A schema at version 2 whose version 1 called `total` `count`.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Counter {
+        total: u64,
+    }
+
+    impl Schema for Counter {
+        const NAME: &'static str = "counter";
+        const VERSION: u32 = 2;
+
+        fn upgrade(version: u32, mut data: Value) -> Result<Value, SchemaError> {
+            match version {
+                1 => {
+                    let count = data["count"].take();
+                    Ok(serde_json::json!({ "total": count }))
+                }
+                _ => Err(SchemaError(format!("unknown counter version {}", version))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_upgrade_older_versions() {
+        let v1 = r#"{"schema": "counter", "version": 1, "data": {"count": 7}}"#;
+        assert_eq!(from_json::<Counter>(v1), Ok(Counter { total: 7 }));
+        assert_eq!(from_json::<Counter>(&to_json(&Counter { total: 3 })), Ok(Counter { total: 3 }));
+        assert!(from_json::<Counter>(r#"{"schema": "counter", "version": 0, "data": {}}"#).is_err());
+    }
+}
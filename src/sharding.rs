@@ -0,0 +1,170 @@
+//! Deterministic address-to-shard assignment.
+//!
+//! Executors that split state or work by address need every run, and every
+//! thread, to agree on where an address lives. This module collects the
+//! schemes they use:
+//!
+//! - [`range_shard`]: contiguous ranges of the address's leading byte, so
+//!   neighbouring addresses share a shard; workload addresses are uniformly
+//!   random, so shards stay balanced without hashing
+//! - [`jump_shard`]: Lamping and Veach's jump consistent hash; growing from
+//!   `n` to `n + 1` shards moves only about `1 / (n + 1)` of the addresses,
+//!   with no table to keep
+//! - [`HashRing`]: consistent hashing over a ring of virtual nodes; shards
+//!   can be removed as well as added, moving only the addresses they owned
+//!
+//! The hash-based schemes take the 64-bit [`address_key`], derived from
+//! `keccak256(address)`, so they spread addresses whatever their bit
+//! patterns.
+
+use alloy_primitives::{keccak256, Address};
+
+/// Shard of a uniformly distributed byte, by contiguous range.
+///
+/// With more than 256 shards some stay empty.
+pub fn byte_range_shard(byte: u8, num_shards: usize) -> usize {
+    byte as usize * num_shards.max(1) / 256
+}
+
+/// Shard of an address, by contiguous range of its leading byte.
+pub fn range_shard(address: Address, num_shards: usize) -> usize {
+    byte_range_shard(address[0], num_shards)
+}
+
+/// 64-bit hash key of an address: the first 8 bytes of its keccak hash.
+pub fn address_key(address: Address) -> u64 {
+    let hash = keccak256(address.as_slice());
+    u64::from_be_bytes(hash[..8].try_into().expect("8-byte prefix"))
+}
+
+/// Jump consistent hash of `key` into `num_buckets` buckets.
+///
+/// From "A Fast, Minimal Memory, Consistent Hash Algorithm" (Lamping and
+/// Veach, 2014). Runs in `O(ln num_buckets)` steps.
+pub fn jump_hash(mut key: u64, num_buckets: usize) -> usize {
+    let num_buckets = num_buckets.max(1) as i64;
+    let mut bucket = -1i64;
+    let mut next = 0i64;
+    while next < num_buckets {
+        bucket = next;
+        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    bucket as usize
+}
+
+/// Shard of an address by jump consistent hash of its [`address_key`].
+pub fn jump_shard(address: Address, num_shards: usize) -> usize {
+    jump_hash(address_key(address), num_shards)
+}
+
+/// Consistent-hash ring with virtual nodes.
+///
+/// Every shard is placed at `replicas` points on a 64-bit ring, and an
+/// address belongs to the shard at the first point at or after its
+/// [`address_key`], wrapping around. More replicas even out shard sizes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashRing {
+    /// Ring points and the shard at each, sorted by point.
+    points: Vec<(u64, usize)>,
+}
+
+impl HashRing {
+    /// Creates a ring over shards `0..num_shards`.
+    pub fn new(num_shards: usize, replicas: usize) -> Self {
+        Self::with_shards(0..num_shards.max(1), replicas)
+    }
+
+    /// Creates a ring over the given shard ids, which need not be contiguous.
+    ///
+    /// Panics if `shards` is empty.
+    pub fn with_shards(shards: impl IntoIterator<Item = usize>, replicas: usize) -> Self {
+        let mut points: Vec<(u64, usize)> = shards
+            .into_iter()
+            .flat_map(|shard| (0..replicas.max(1)).map(move |replica| (Self::point(shard, replica), shard)))
+            .collect();
+        assert!(!points.is_empty(), "a hash ring needs at least one shard");
+        points.sort_unstable();
+        Self { points }
+    }
+
+    /// Shard owning `key`.
+    pub fn shard_for_key(&self, key: u64) -> usize {
+        let index = self.points.partition_point(|&(point, _)| point < key);
+        self.points[index % self.points.len()].1
+    }
+
+    /// Shard owning `address`.
+    pub fn shard_of(&self, address: Address) -> usize {
+        self.shard_for_key(address_key(address))
+    }
+
+    /// Ring position of a shard's `replica`-th virtual node.
+    fn point(shard: usize, replica: usize) -> u64 {
+        let mut data = [0u8; 16];
+        data[..8].copy_from_slice(&(shard as u64).to_be_bytes());
+        data[8..].copy_from_slice(&(replica as u64).to_be_bytes());
+        u64::from_be_bytes(keccak256(data)[..8].try_into().expect("8-byte prefix"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(count: u64) -> Vec<Address> {
+        (0..count).map(|i| Address::from_word(keccak256(i.to_be_bytes()))).collect()
+    }
+
+    #[test]
+    fn test_range_shard() {
+        assert_eq!(byte_range_shard(0, 4), 0);
+        assert_eq!(byte_range_shard(63, 4), 0);
+        assert_eq!(byte_range_shard(64, 4), 1);
+        assert_eq!(byte_range_shard(255, 4), 3);
+        assert_eq!(byte_range_shard(255, 0), 0);
+
+        // Shards are contiguous in the leading byte
+        let shards: Vec<usize> = (0..=255u8).map(|byte| byte_range_shard(byte, 7)).collect();
+        assert!(shards.windows(2).all(|pair| pair[1] == pair[0] || pair[1] == pair[0] + 1));
+        assert_eq!(shards.last(), Some(&6));
+    }
+
+    #[test]
+    fn test_jump_hash_is_consistent() {
+        let keys: Vec<u64> = addresses(10_000).into_iter().map(address_key).collect();
+        for num_shards in [1, 2, 5, 16] {
+            let before: Vec<usize> = keys.iter().map(|&key| jump_hash(key, num_shards)).collect();
+            let after: Vec<usize> = keys.iter().map(|&key| jump_hash(key, num_shards + 1)).collect();
+            assert!(before.iter().all(|&shard| shard < num_shards));
+
+            // Growing only moves keys onto the new shard, about 1 / (n + 1) of them
+            let moved = before.iter().zip(&after).filter(|(old, new)| old != new).count();
+            assert!(before.iter().zip(&after).all(|(&old, &new)| old == new || new == num_shards));
+            let expected = keys.len() / (num_shards + 1);
+            assert!(moved.abs_diff(expected) < expected / 5 + 50, "{} shards moved {}", num_shards, moved);
+        }
+        assert_eq!(jump_hash(42, 0), 0);
+    }
+
+    #[test]
+    fn test_hash_ring_moves_only_removed_shard() {
+        let addresses = addresses(5_000);
+        let full = HashRing::new(4, 64);
+        let without_two = HashRing::with_shards([0, 1, 3], 64);
+
+        let mut sizes = [0usize; 4];
+        for &address in &addresses {
+            let shard = full.shard_of(address);
+            sizes[shard] += 1;
+            if shard != 2 {
+                assert_eq!(without_two.shard_of(address), shard);
+            } else {
+                assert_ne!(without_two.shard_of(address), 2);
+            }
+        }
+        // Virtual nodes keep every shard within a factor of two of its share
+        assert!(sizes.iter().all(|&size| size > addresses.len() / 8 && size < addresses.len() / 2));
+        assert_eq!(full.shard_for_key(u64::MAX), full.shard_for_key(u64::MAX));
+    }
+}
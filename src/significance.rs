@@ -0,0 +1,396 @@
+//! Significance testing between two executors.
+//!
+//! A single throughput number per executor cannot tell a real speedup from
+//! run-to-run noise. Given a [`RunManifest`] recorded with `--repetitions`,
+//! [`SignificanceTest`] collects the TPS of every repetition of two executors
+//! on the same cells and compares them with two tests:
+//!
+//! - a two-sided Mann-Whitney U test, which assumes nothing about the shape of
+//!   the TPS distribution. The p-value is exact for small samples without ties
+//!   and uses the tie-corrected normal approximation otherwise;
+//! - a percentile bootstrap confidence interval for the relative change in
+//!   mean TPS, from seeded resampling so reruns print the same interval.
+//!
+//! A difference is called significant only when both agree: the p-value is
+//! below alpha and the interval excludes zero. With fewer than four
+//! repetitions per executor no difference can reach significance at the
+//! default alpha of 0.05.
+//!
+//! Cells are paired by hot-account label and group. An executor that runs
+//! outside any group, such as the sequential baseline, is paired with every
+//! group (thread count) of the other executor.
+
+use crate::manifest::{CellRecord, RunManifest};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::fmt;
+
+/// Largest combined sample size for which the exact U distribution is used.
+const MAX_EXACT_SAMPLES: usize = 40;
+
+/// Error comparing two executors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignificanceError(pub String);
+
+impl fmt::Display for SignificanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SignificanceError {}
+
+/// Result of a two-sided Mann-Whitney U test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MannWhitney {
+    /// Pairs in which the first sample exceeds the second, ties counting half.
+    pub u: f64,
+    pub p_value: f64,
+    /// Whether `p_value` comes from the exact distribution rather than the
+    /// normal approximation.
+    pub exact: bool,
+}
+
+/// Runs a two-sided Mann-Whitney U test between samples `a` and `b`.
+///
+/// Both samples must be non-empty.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> MannWhitney {
+    let (n1, n2) = (a.len(), b.len());
+    let n = n1 + n2;
+
+    // Rank the pooled samples, averaging the ranks of ties
+    let mut pooled: Vec<(f64, bool)> = a.iter().map(|&x| (x, true)).chain(b.iter().map(|&x| (x, false))).collect();
+    pooled.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let mut rank_sum = 0.0;
+    let mut tie_term = 0.0;
+    let mut start = 0;
+    while start < n {
+        let end = (start..n).find(|&i| pooled[i].0 != pooled[start].0).unwrap_or(n);
+        let ties = (end - start) as f64;
+        let rank = (start + end + 1) as f64 / 2.0;
+        rank_sum += rank * pooled[start..end].iter().filter(|(_, first)| *first).count() as f64;
+        tie_term += ties * ties * ties - ties;
+        start = end;
+    }
+    let u = rank_sum - (n1 * (n1 + 1)) as f64 / 2.0;
+
+    if tie_term == 0.0 && n <= MAX_EXACT_SAMPLES {
+        let counts = exact_u_distribution(n1, n2);
+        let total: f64 = counts.iter().sum();
+        let u_index = u as usize;
+        let lower: f64 = counts[..=u_index].iter().sum::<f64>() / total;
+        let upper: f64 = counts[u_index..].iter().sum::<f64>() / total;
+        return MannWhitney {
+            u,
+            p_value: (2.0 * lower.min(upper)).min(1.0),
+            exact: true,
+        };
+    }
+
+    let (n1, n2, n) = (n1 as f64, n2 as f64, n as f64);
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    let p_value = if variance > 0.0 {
+        // Continuity correction towards the mean
+        let deviation = ((u - mean).abs() - 0.5).max(0.0);
+        erfc(deviation / variance.sqrt() / std::f64::consts::SQRT_2).min(1.0)
+    } else {
+        1.0
+    };
+    MannWhitney {
+        u,
+        p_value,
+        exact: false,
+    }
+}
+
+/// Number of orderings of `n1` and `n2` distinct values yielding each U.
+fn exact_u_distribution(n1: usize, n2: usize) -> Vec<f64> {
+    // previous[j][u]: orderings of i - 1 first-sample and j second-sample values with U = u
+    let mut previous: Vec<Vec<f64>> = vec![vec![1.0]; n2 + 1];
+    for i in 1..=n1 {
+        let mut current: Vec<Vec<f64>> = vec![vec![1.0]];
+        for j in 1..=n2 {
+            let mut counts = vec![0.0; i * j + 1];
+            // The largest value is from the first sample and exceeds all j others
+            for (u, &count) in previous[j].iter().enumerate() {
+                counts[u + j] += count;
+            }
+            // The largest value is from the second sample
+            for (u, &count) in current[j - 1].iter().enumerate() {
+                counts[u] += count;
+            }
+            current.push(counts);
+        }
+        previous = current;
+    }
+    previous.swap_remove(n2)
+}
+
+/// Complementary error function, with fractional error below 1.2e-7.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let result = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+/// Percentile bootstrap interval for the change in mean from `baseline` to
+/// `candidate`, in percent of the baseline mean.
+///
+/// Both samples must be non-empty.
+pub fn bootstrap_change_pct(
+    baseline: &[f64],
+    candidate: &[f64],
+    resamples: usize,
+    confidence: f64,
+    seed: u64,
+) -> (f64, f64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut resample_mean = |samples: &[f64]| -> f64 {
+        (0..samples.len())
+            .map(|_| samples[rng.gen_range(0..samples.len())])
+            .sum::<f64>()
+            / samples.len() as f64
+    };
+
+    let mut changes: Vec<f64> = (0..resamples.max(1))
+        .map(|_| {
+            let base = resample_mean(baseline);
+            let cand = resample_mean(candidate);
+            (cand / base - 1.0) * 100.0
+        })
+        .collect();
+    changes.sort_by(f64::total_cmp);
+
+    let tail = (1.0 - confidence) / 2.0;
+    let at = |quantile: f64| changes[((changes.len() - 1) as f64 * quantile).round() as usize];
+    (at(tail), at(1.0 - tail))
+}
+
+/// Comparison of two executors on one cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub hot_accounts_label: String,
+    /// Cell group both executors ran in, e.g. a thread count.
+    pub group: Option<String>,
+    pub baseline_samples: usize,
+    pub candidate_samples: usize,
+    pub baseline_mean_tps: f64,
+    pub candidate_mean_tps: f64,
+    /// Change in mean TPS from baseline to candidate, in percent.
+    pub change_pct: f64,
+    pub mann_whitney: MannWhitney,
+    /// Bootstrap confidence interval of `change_pct`.
+    pub interval_pct: (f64, f64),
+    /// Whether the Mann-Whitney test and the bootstrap interval both find a difference.
+    pub significant: bool,
+}
+
+/// Compares the TPS of two executors recorded in a manifest.
+#[derive(Debug, Clone)]
+pub struct SignificanceTest {
+    alpha: f64,
+    resamples: usize,
+    seed: u64,
+}
+
+impl Default for SignificanceTest {
+    fn default() -> Self {
+        Self {
+            alpha: 0.05,
+            resamples: 10_000,
+            seed: 42,
+        }
+    }
+}
+
+impl SignificanceTest {
+    /// Creates a test at alpha 0.05 with 10,000 bootstrap resamples.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the significance level; the bootstrap interval has confidence `1 - alpha`.
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Sets the number of bootstrap resamples.
+    pub fn with_resamples(mut self, resamples: usize) -> Self {
+        self.resamples = resamples.max(1);
+        self
+    }
+
+    /// Sets the bootstrap seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Significance level.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Compares `candidate` against `baseline` on every cell both ran, in
+    /// order of first appearance in `manifest`.
+    pub fn compare(
+        &self,
+        manifest: &RunManifest,
+        baseline: &str,
+        candidate: &str,
+    ) -> Result<Vec<Comparison>, SignificanceError> {
+        for name in [baseline, candidate] {
+            if !manifest.cells.iter().any(|cell| cell.executor_name == name) {
+                return Err(SignificanceError(format!("executor {} has no cells in the manifest", name)));
+            }
+        }
+
+        let mut keys: Vec<(String, Option<String>)> = Vec::new();
+        for cell in &manifest.cells {
+            if cell.executor_name != baseline && cell.executor_name != candidate {
+                continue;
+            }
+            let label = &cell.hot_accounts_label;
+            let key = (label.clone(), cell.group.clone());
+            if cell.group.is_some() {
+                // A grouped cell replaces the ungrouped key of its label
+                keys.retain(|existing| !(existing.0 == *label && existing.1.is_none()));
+            } else if keys.iter().any(|existing| existing.0 == *label) {
+                continue;
+            }
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        let mut comparisons = Vec::new();
+        for (label, group) in keys {
+            let samples = |name: &str| -> Vec<f64> {
+                manifest
+                    .cells
+                    .iter()
+                    .filter(|cell| cell.executor_name == name && pairs_with(cell, &label, &group))
+                    .map(|cell| cell.throughput_tps)
+                    .collect()
+            };
+            let (base, cand) = (samples(baseline), samples(candidate));
+            if base.is_empty() || cand.is_empty() {
+                continue;
+            }
+
+            let mann_whitney = mann_whitney_u(&cand, &base);
+            let interval_pct = bootstrap_change_pct(&base, &cand, self.resamples, 1.0 - self.alpha, self.seed);
+            let (baseline_mean_tps, candidate_mean_tps) = (mean(&base), mean(&cand));
+            comparisons.push(Comparison {
+                hot_accounts_label: label,
+                group,
+                baseline_samples: base.len(),
+                candidate_samples: cand.len(),
+                baseline_mean_tps,
+                candidate_mean_tps,
+                change_pct: (candidate_mean_tps / baseline_mean_tps - 1.0) * 100.0,
+                mann_whitney,
+                interval_pct,
+                significant: mann_whitney.p_value < self.alpha && (interval_pct.0 > 0.0 || interval_pct.1 < 0.0),
+            });
+        }
+
+        if comparisons.is_empty() {
+            return Err(SignificanceError(format!(
+                "executors {} and {} never ran on the same cell",
+                baseline, candidate
+            )));
+        }
+        Ok(comparisons)
+    }
+}
+
+/// Whether `cell` belongs to the comparison of `label` within `group`.
+fn pairs_with(cell: &CellRecord, label: &str, group: &Option<String>) -> bool {
+    cell.hot_accounts_label == label && (cell.group.is_none() || cell.group == *group)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    fn cells(executor_name: &str, group: Option<&str>, throughputs: &[f64]) -> Vec<CellRecord> {
+        throughputs
+            .iter()
+            .enumerate()
+            .map(|(repetition, &throughput_tps)| CellRecord {
+                index: 0,
+                repetition,
+                section: executor_name.to_string(),
+                group: group.map(str::to_string),
+                executor_name: executor_name.to_string(),
+                hot_accounts_label: "10 accounts".to_string(),
+                preserves_order: true,
+                successful: 100,
+                failed: 0,
+                duration_ms: 100_000.0 / throughput_tps,
+                throughput_tps,
+                result_hash: B256::ZERO,
+                details: Vec::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_separates_real_differences_from_noise() {
+        // Complete separation of 5 vs 5 samples: exact p = 2 / C(10, 5)
+        let test = mann_whitney_u(&[1.0, 2.0, 3.0, 4.0, 5.0], &[6.0, 7.0, 8.0, 9.0, 10.0]);
+        assert!(test.exact);
+        assert_eq!(test.u, 0.0);
+        assert!((test.p_value - 2.0 / 252.0).abs() < 1e-12);
+
+        // Ties fall back to the normal approximation
+        let tied = mann_whitney_u(&[1.0, 1.0, 2.0], &[1.0, 2.0, 2.0]);
+        assert!(!tied.exact);
+        assert!(tied.p_value > 0.5);
+
+        let mut manifest = RunManifest::default();
+        manifest.cells.extend(cells("sequential", None, &[1000.0, 1010.0, 990.0, 1005.0, 995.0, 1002.0]));
+        manifest
+            .cells
+            .extend(cells("parallel", Some("2 threads"), &[1001.0, 996.0, 1012.0, 989.0, 1004.0, 998.0]));
+        manifest
+            .cells
+            .extend(cells("parallel", Some("4 threads"), &[1500.0, 1480.0, 1530.0, 1510.0, 1495.0, 1520.0]));
+
+        let comparisons = SignificanceTest::new().compare(&manifest, "sequential", "parallel").unwrap();
+        assert_eq!(comparisons.len(), 2);
+
+        let noise = &comparisons[0];
+        assert_eq!(noise.group.as_deref(), Some("2 threads"));
+        assert_eq!((noise.baseline_samples, noise.candidate_samples), (6, 6));
+        assert!(!noise.significant);
+        assert!(noise.interval_pct.0 < 0.0 && noise.interval_pct.1 > 0.0);
+
+        let speedup = &comparisons[1];
+        assert_eq!(speedup.group.as_deref(), Some("4 threads"));
+        assert!(speedup.significant);
+        assert!((speedup.change_pct - 50.3).abs() < 0.5);
+        assert!(speedup.interval_pct.0 > 40.0 && speedup.interval_pct.1 < 60.0);
+
+        assert!(SignificanceTest::new().compare(&manifest, "sequential", "missing").is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Long-running soak tests.
+//!
+//! A benchmark cell runs a few blocks on a fresh backend, too briefly for
+//! effects that build up over time to show: B-tree page splits and free-list
+//! fragmentation, compaction debt, caches outgrowing memory, or leaks. A
+//! [`Soak`] instead runs rounds of a workload back to back against one
+//! persistent backend for a fixed time, each round generated with
+//! [`Workload::next_round`] so it continues the state the previous round
+//! left, and samples throughput, database size and process memory at a
+//! fixed interval. Comparing the first and last samples shows the drift.
+//!
+//! Only execution is timed. Generating and signing the next round happens
+//! between executions and counts toward the soak's wall time, not its TPS.
+
+use crate::executor::ExecutionResult;
+use crate::footprint::disk_bytes;
+use crate::Workload;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Throughput and resource use over one sampling interval of a soak.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoakSample {
+    /// Wall time from the start of the soak to the end of the interval.
+    pub elapsed: Duration,
+    /// Rounds completed since the start of the soak.
+    pub rounds: usize,
+    /// Transactions executed in the interval.
+    pub transactions: usize,
+    /// Transactions that failed in the interval.
+    pub failed: usize,
+    /// Transactions per second of execution time in the interval.
+    pub throughput_tps: f64,
+    /// Bytes the backend's files take on disk at the end of the interval.
+    pub db_bytes: u64,
+    /// Resident memory of the process at the end of the interval, where the
+    /// platform reports it.
+    pub rss_bytes: Option<u64>,
+}
+
+/// Every sample of a soak, in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoakReport {
+    pub samples: Vec<SoakSample>,
+}
+
+impl SoakReport {
+    /// Change in TPS from the first sample to the last, in percent.
+    pub fn tps_drift_pct(&self) -> Option<f64> {
+        let (first, last) = (self.samples.first()?, self.samples.last()?);
+        (first.throughput_tps > 0.0).then(|| (last.throughput_tps / first.throughput_tps - 1.0) * 100.0)
+    }
+
+    /// Growth of the backend's files from the first sample to the last.
+    pub fn db_growth_bytes(&self) -> Option<i64> {
+        let (first, last) = (self.samples.first()?, self.samples.last()?);
+        Some(last.db_bytes as i64 - first.db_bytes as i64)
+    }
+
+    /// Growth of resident memory from the first sample to the last.
+    pub fn rss_growth_bytes(&self) -> Option<i64> {
+        let (first, last) = (self.samples.first()?, self.samples.last()?);
+        Some(last.rss_bytes? as i64 - first.rss_bytes? as i64)
+    }
+}
+
+/// Runs workload rounds against one backend for a fixed time.
+#[derive(Debug, Clone)]
+pub struct Soak {
+    duration: Duration,
+    sample_interval: Duration,
+}
+
+impl Soak {
+    /// Creates a soak lasting `duration`, sampled every minute.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            sample_interval: Duration::from_secs(60),
+        }
+    }
+
+    /// Samples every `interval` instead of every minute.
+    pub fn with_sample_interval(mut self, interval: Duration) -> Self {
+        self.sample_interval = interval.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Runs rounds starting with `first` until the soak's duration has
+    /// passed, always finishing the round in progress.
+    ///
+    /// `execute` runs one round given the number of its first block; it sees
+    /// block 0 only for `first`, so it knows when to initialize the backend.
+    /// `db_path` is the backend's file or directory, measured after every
+    /// interval, and `on_sample` sees each sample as it is taken. The last
+    /// interval may be shorter than the others.
+    pub fn run<E>(
+        &self,
+        first: &Workload,
+        db_path: &Path,
+        mut execute: impl FnMut(&Workload, u64) -> Result<ExecutionResult, E>,
+        mut on_sample: impl FnMut(&SoakSample),
+    ) -> Result<SoakReport, E> {
+        let start = Instant::now();
+        let mut report = SoakReport::default();
+        let mut round = first.clone();
+        let mut next_block = 0u64;
+        let mut rounds = 0;
+
+        let mut interval_start = start;
+        let mut transactions = 0;
+        let mut failed = 0;
+        let mut execution_time = Duration::ZERO;
+
+        loop {
+            let round_start = Instant::now();
+            let result = execute(&round, next_block)?;
+            execution_time += round_start.elapsed();
+            transactions += round.transactions.len();
+            failed += result.failed;
+            next_block += round.num_blocks() as u64;
+            rounds += 1;
+
+            let done = start.elapsed() >= self.duration;
+            if done || interval_start.elapsed() >= self.sample_interval {
+                let sample = SoakSample {
+                    elapsed: start.elapsed(),
+                    rounds,
+                    transactions,
+                    failed,
+                    throughput_tps: transactions as f64 / execution_time.as_secs_f64().max(f64::EPSILON),
+                    db_bytes: disk_bytes(db_path),
+                    rss_bytes: resident_memory_bytes(),
+                };
+                on_sample(&sample);
+                report.samples.push(sample);
+                interval_start = Instant::now();
+                transactions = 0;
+                failed = 0;
+                execution_time = Duration::ZERO;
+            }
+            if done {
+                return Ok(report);
+            }
+            round = round.next_round();
+        }
+    }
+}
+
+/// Resident memory of this process, from `/proc/self/status`; `None` where
+/// it is unavailable.
+pub fn resident_memory_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkloadConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_soak_continues_rounds_and_samples() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            hot_accounts: 10,
+            transactions_per_block: 10,
+            fake_signatures: true,
+            ..Default::default()
+        });
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db");
+
+        let mut first_blocks = Vec::new();
+        let mut seen = 0;
+        let report = Soak::new(Duration::from_millis(50))
+            .with_sample_interval(Duration::from_millis(10))
+            .run(
+                &workload,
+                dir.path(),
+                |round, first_block| {
+                    first_blocks.push((first_block, round.config.seed));
+                    // Each round grows the "database" by one byte per transaction
+                    let len = fs::metadata(&file).map_or(0, |metadata| metadata.len());
+                    fs::write(&file, vec![0u8; len as usize + round.transactions.len()]).unwrap();
+                    std::thread::sleep(Duration::from_millis(2));
+                    Ok::<_, std::io::Error>(ExecutionResult::new(round.transactions.len() - 1, 1))
+                },
+                |_| seen += 1,
+            )
+            .unwrap();
+
+        // Rounds are numbered on from the previous one's blocks, with advancing seeds
+        assert!(first_blocks.len() >= 2);
+        for (round, &(first_block, seed)) in first_blocks.iter().enumerate() {
+            assert_eq!(first_block, round as u64 * 2);
+            assert_eq!(seed, workload.config.seed + round as u64);
+        }
+
+        assert_eq!(seen, report.samples.len());
+        let last = report.samples.last().unwrap();
+        assert_eq!(last.rounds, first_blocks.len());
+        assert!(last.elapsed >= Duration::from_millis(50));
+        let transactions: usize = report.samples.iter().map(|sample| sample.transactions).sum();
+        let failed: usize = report.samples.iter().map(|sample| sample.failed).sum();
+        assert_eq!((transactions, failed), (20 * first_blocks.len(), first_blocks.len()));
+        assert!(last.db_bytes >= 20 * first_blocks.len() as u64);
+        assert!(report.db_growth_bytes().unwrap() >= 0);
+        assert!(report.tps_drift_pct().is_some());
+    }
+}
@@ -0,0 +1,223 @@
+//! State commitment over hashed accounts.
+//!
+//! This module computes a binary Merkle root over all accounts, ordered by
+//! `keccak256(address)`, and produces inclusion proofs against that root. It is
+//! deliberately simpler than Ethereum's Merkle-Patricia trie: the goal is to
+//! measure the cost of committing to state and serving proofs from each backend,
+//! not to reproduce mainnet state roots.
+//!
+//! # Tree Layout
+//!
+//! - Leaves are `keccak256(hashed_address ++ nonce ++ balance)`, sorted by hashed address
+//! - Each level pairs adjacent nodes as `keccak256(left ++ right)`
+//! - An unpaired node at the end of a level is promoted unchanged
+//! - The root of an empty state is [`EMPTY_ROOT`]
+//!
+//! The [`parallel`] submodule computes the same root across worker threads.
+
+pub mod parallel;
+
+use alloy_primitives::{b256, keccak256, Address, B256, U256};
+
+/// Root of a state with no accounts (`keccak256([])`).
+pub const EMPTY_ROOT: B256 =
+    b256!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+
+/// Computes the leaf hash for an account.
+pub fn leaf_hash(hashed_address: B256, nonce: u64, balance: U256) -> B256 {
+    let mut data = Vec::with_capacity(32 + 8 + 32);
+    data.extend_from_slice(hashed_address.as_slice());
+    data.extend_from_slice(&nonce.to_be_bytes());
+    data.extend_from_slice(&balance.to_be_bytes::<32>());
+    keccak256(&data)
+}
+
+/// Hashes two sibling nodes into their parent.
+pub fn hash_pair(left: B256, right: B256) -> B256 {
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(left.as_slice());
+    data[32..].copy_from_slice(right.as_slice());
+    keccak256(data)
+}
+
+/// Computes the next level of the tree from the current one.
+pub(crate) fn next_level(level: &[B256]) -> Vec<B256> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_pair(*left, *right),
+            [single] => *single,
+            _ => unreachable!("chunks(2) yields one or two nodes"),
+        })
+        .collect()
+}
+
+/// Computes the root over leaves that are already sorted by hashed address.
+pub fn compute_root(leaves: &[B256]) -> B256 {
+    if leaves.is_empty() {
+        return EMPTY_ROOT;
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Computes the root over an unordered set of account states.
+pub fn state_root<I>(accounts: I) -> B256
+where
+    I: IntoIterator<Item = (Address, u64, U256)>,
+{
+    compute_root(&sorted_leaves(accounts))
+}
+
+/// Hashes and sorts account states into leaf order.
+pub fn sorted_leaves<I>(accounts: I) -> Vec<B256>
+where
+    I: IntoIterator<Item = (Address, u64, U256)>,
+{
+    let mut hashed: Vec<(B256, u64, U256)> = accounts
+        .into_iter()
+        .map(|(address, nonce, balance)| (keccak256(address), nonce, balance))
+        .collect();
+    hashed.sort_unstable_by_key(|(hashed_address, _, _)| *hashed_address);
+
+    hashed
+        .into_iter()
+        .map(|(hashed_address, nonce, balance)| leaf_hash(hashed_address, nonce, balance))
+        .collect()
+}
+
+/// Returns the sibling path from the leaf at `index` up to the root.
+///
+/// Levels where the node is promoted without a sibling contribute no entry.
+pub fn sibling_path(leaves: &[B256], mut index: usize) -> Vec<B256> {
+    let mut siblings = Vec::new();
+    let mut level = leaves.to_vec();
+
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            siblings.push(level[sibling]);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+
+    siblings
+}
+
+/// Inclusion proof for a single account against a state root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProof {
+    /// The account address.
+    pub address: Address,
+    /// The account nonce.
+    pub nonce: u64,
+    /// The account balance.
+    pub balance: U256,
+    /// Position of the account's leaf in sorted order.
+    pub leaf_index: usize,
+    /// Total number of leaves in the tree.
+    pub num_leaves: usize,
+    /// Sibling hashes from the leaf up to the root.
+    pub siblings: Vec<B256>,
+    /// The root this proof was generated against.
+    pub root: B256,
+}
+
+impl AccountProof {
+    /// Verifies the proof against its recorded root.
+    pub fn verify(&self) -> bool {
+        self.verify_against(self.root)
+    }
+
+    /// Verifies the proof against an externally supplied root.
+    pub fn verify_against(&self, root: B256) -> bool {
+        if self.leaf_index >= self.num_leaves {
+            return false;
+        }
+
+        let mut node = leaf_hash(keccak256(self.address), self.nonce, self.balance);
+        let mut index = self.leaf_index;
+        let mut width = self.num_leaves;
+        let mut siblings = self.siblings.iter();
+
+        while width > 1 {
+            let sibling = index ^ 1;
+            if sibling < width {
+                let Some(&hash) = siblings.next() else {
+                    return false;
+                };
+                node = if index % 2 == 0 {
+                    hash_pair(node, hash)
+                } else {
+                    hash_pair(hash, node)
+                };
+            }
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+
+        siblings.next().is_none() && node == root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts(n: u8) -> Vec<(Address, u64, U256)> {
+        (0..n)
+            .map(|i| (Address::with_last_byte(i), i as u64, U256::from(1000 + i as u64)))
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_root() {
+        assert_eq!(compute_root(&[]), EMPTY_ROOT);
+        assert_eq!(EMPTY_ROOT, keccak256([]));
+    }
+
+    #[test]
+    fn test_root_is_order_independent() {
+        let mut state = accounts(7);
+        let root = state_root(state.clone());
+        state.reverse();
+        assert_eq!(state_root(state), root);
+    }
+
+    #[test]
+    fn test_proofs_verify_for_every_leaf() {
+        for n in 1..=9 {
+            let state = accounts(n);
+            let leaves = sorted_leaves(state.clone());
+            let root = compute_root(&leaves);
+
+            for (address, nonce, balance) in &state {
+                let hashed = keccak256(address);
+                let leaf_index = leaves
+                    .iter()
+                    .position(|leaf| *leaf == leaf_hash(hashed, *nonce, *balance))
+                    .unwrap();
+
+                let proof = AccountProof {
+                    address: *address,
+                    nonce: *nonce,
+                    balance: *balance,
+                    leaf_index,
+                    num_leaves: leaves.len(),
+                    siblings: sibling_path(&leaves, leaf_index),
+                    root,
+                };
+                assert!(proof.verify());
+
+                let mut forged = proof.clone();
+                forged.balance += U256::from(1);
+                assert!(!forged.verify());
+            }
+        }
+    }
+}
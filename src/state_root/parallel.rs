@@ -0,0 +1,108 @@
+//! Multi-threaded state root computation.
+//!
+//! Leaves are split into contiguous, power-of-two sized subtrees that are hashed
+//! on separate threads. Because pairing in the tree is aligned, each subtree root
+//! is exactly the node the sequential algorithm produces at that height, so the
+//! final root is identical to [`super::compute_root`].
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use std::thread;
+
+use super::{leaf_hash, EMPTY_ROOT};
+
+/// Computes the root over sorted leaves using up to `num_threads` threads.
+pub fn compute_root(leaves: &[B256], num_threads: usize) -> B256 {
+    if leaves.is_empty() {
+        return EMPTY_ROOT;
+    }
+
+    let num_threads = num_threads.max(1);
+    if num_threads == 1 || leaves.len() < 2 * num_threads {
+        return super::compute_root(leaves);
+    }
+
+    // Subtrees must be aligned power-of-two chunks for their roots to match the
+    // sequential tree.
+    let subtree_size = leaves.len().div_ceil(num_threads).next_power_of_two();
+
+    let subtree_roots: Vec<B256> = thread::scope(|scope| {
+        let handles: Vec<_> = leaves
+            .chunks(subtree_size)
+            .map(|chunk| scope.spawn(move || super::compute_root(chunk)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("state root worker panicked"))
+            .collect()
+    });
+
+    super::compute_root(&subtree_roots)
+}
+
+/// Hashes and sorts account states into leaf order using up to `num_threads` threads.
+pub fn sorted_leaves<I>(accounts: I, num_threads: usize) -> Vec<B256>
+where
+    I: IntoIterator<Item = (Address, u64, U256)>,
+{
+    let accounts: Vec<(Address, u64, U256)> = accounts.into_iter().collect();
+    let chunk_size = accounts.len().div_ceil(num_threads.max(1)).max(1);
+
+    let mut hashed: Vec<(B256, B256)> = thread::scope(|scope| {
+        let handles: Vec<_> = accounts
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&(address, nonce, balance)| {
+                            let hashed_address = keccak256(address);
+                            (hashed_address, leaf_hash(hashed_address, nonce, balance))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("leaf hashing worker panicked"))
+            .collect()
+    });
+
+    hashed.sort_unstable_by_key(|(hashed_address, _)| *hashed_address);
+    hashed.into_iter().map(|(_, leaf)| leaf).collect()
+}
+
+/// Computes the root over an unordered set of account states using up to `num_threads` threads.
+pub fn state_root<I>(accounts: I, num_threads: usize) -> B256
+where
+    I: IntoIterator<Item = (Address, u64, U256)>,
+{
+    compute_root(&sorted_leaves(accounts, num_threads), num_threads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_root_matches_sequential() {
+        for n in [0u64, 1, 2, 3, 7, 16, 33, 100, 257] {
+            let accounts: Vec<_> = (0..n)
+                .map(|i| (Address::from_word(B256::from(U256::from(i))), i, U256::from(i * 3)))
+                .collect();
+            let expected = super::super::state_root(accounts.clone());
+
+            for threads in [1, 2, 3, 4, 8] {
+                assert_eq!(
+                    state_root(accounts.clone(), threads),
+                    expected,
+                    "n = {}, threads = {}",
+                    n,
+                    threads
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Storage-heavy workloads.
+//!
+//! DeFi protocols concentrate conflicts in one contract's storage: every swap
+//! reads and writes the same pool slots even when its senders and receivers
+//! are disjoint. [`StorageLayout`] gives a few contract accounts thousands of
+//! storage slots and assigns each transaction of a workload slot reads and
+//! writes, most of them on a handful of hot slots of the first contract.
+//!
+//! Executors only move balances so far, so nothing executes these accesses
+//! yet. The layout is already measurable, though:
+//! [`StorageLayout::max_chain_length`] shows how far the storage hotspot
+//! serializes a workload that looks parallel by its accounts alone.
+
+use crate::Workload;
+use alloy_primitives::{keccak256, Address, B256, U256};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+/// Shape of the contract storage a workload's transactions access.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StorageConfig {
+    /// Number of contract accounts with storage.
+    pub contracts: usize,
+    /// Storage slots held by each contract.
+    pub slots_per_contract: usize,
+    /// Slot accesses per transaction.
+    pub accesses_per_tx: usize,
+    /// Slots of the first contract that form the hotspot.
+    pub hot_slots: usize,
+    /// Fraction of accesses that hit a hot slot.
+    pub hot_fraction: f64,
+    /// Fraction of accesses that are writes.
+    pub write_fraction: f64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            contracts: 1,
+            slots_per_contract: 4096,
+            accesses_per_tx: 2,
+            hot_slots: 4,
+            hot_fraction: 0.9,
+            write_fraction: 0.5,
+        }
+    }
+}
+
+/// One storage slot read or written by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotAccess {
+    pub contract: Address,
+    pub slot: B256,
+    pub write: bool,
+}
+
+/// Contract storage and the slot accesses of every transaction of a workload.
+#[derive(Debug, Clone)]
+pub struct StorageLayout {
+    pub config: StorageConfig,
+    /// Contract addresses; the first holds the hot slots.
+    pub contracts: Vec<Address>,
+    /// Slot accesses per transaction, in `workload.transactions` order.
+    pub accesses: Vec<Vec<SlotAccess>>,
+}
+
+impl StorageLayout {
+    /// Generates accesses for every transaction of `workload`, seeded by its config.
+    pub fn generate(workload: &Workload, config: StorageConfig) -> Self {
+        let mut rng = StdRng::seed_from_u64(workload.config.seed ^ 0x5107_a6e5);
+        let contracts: Vec<Address> = (0..config.contracts)
+            .map(|index| {
+                let mut preimage = b"storage-contract".to_vec();
+                preimage.extend_from_slice(&workload.config.seed.to_be_bytes());
+                preimage.extend_from_slice(&(index as u64).to_be_bytes());
+                Address::from_slice(&keccak256(preimage)[12..])
+            })
+            .collect();
+        let slots = config.slots_per_contract.max(1);
+        let hot_slots = config.hot_slots.clamp(1, slots);
+
+        let accesses = if contracts.is_empty() {
+            vec![Vec::new(); workload.transactions.len()]
+        } else {
+            workload
+                .transactions
+                .iter()
+                .map(|_| {
+                    (0..config.accesses_per_tx)
+                        .map(|_| {
+                            let (contract, slot) = if rng.gen_bool(config.hot_fraction) {
+                                (contracts[0], rng.gen_range(0..hot_slots))
+                            } else {
+                                (contracts[rng.gen_range(0..contracts.len())], rng.gen_range(0..slots))
+                            };
+                            SlotAccess {
+                                contract,
+                                slot: Self::slot_key(slot),
+                                write: rng.gen_bool(config.write_fraction),
+                            }
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+
+        Self {
+            config,
+            contracts,
+            accesses,
+        }
+    }
+
+    /// Storage key of the `index`-th slot of a contract.
+    pub fn slot_key(index: usize) -> B256 {
+        B256::from(U256::from(index))
+    }
+
+    /// Every contract's initial slots as `(contract, slot, value)`, for
+    /// pre-populating a backend.
+    pub fn initial_slots(&self) -> impl Iterator<Item = (Address, B256, U256)> + '_ {
+        let slots = self.config.slots_per_contract;
+        self.contracts.iter().flat_map(move |&contract| {
+            (0..slots).map(move |index| (contract, Self::slot_key(index), U256::from(index + 1)))
+        })
+    }
+
+    /// Longest chain of transactions of `workload` that must run in order,
+    /// counting both account and storage conflicts.
+    ///
+    /// Transfers write their sender and receiver; two storage accesses
+    /// conflict unless both are reads. Every transaction is assumed to commit.
+    pub fn max_chain_length(&self, workload: &Workload) -> usize {
+        let mut accounts: HashMap<Address, usize> = HashMap::new();
+        // Per slot: depth of the last writer and deepest reader since
+        let mut slots: HashMap<(Address, B256), (usize, usize)> = HashMap::new();
+        let mut max_chain_length = 0;
+
+        for (tx, accesses) in workload.transactions.iter().zip(&self.accesses) {
+            let mut depth = accounts.get(&tx.from).max(accounts.get(&tx.to)).copied().unwrap_or(0);
+            for access in accesses {
+                let (written, read) = slots.get(&(access.contract, access.slot)).copied().unwrap_or_default();
+                depth = depth.max(if access.write { written.max(read) } else { written });
+            }
+            let depth = depth + 1;
+
+            accounts.insert(tx.from, depth);
+            accounts.insert(tx.to, depth);
+            for access in accesses {
+                let (written, read) = slots.entry((access.contract, access.slot)).or_default();
+                if access.write {
+                    *written = depth;
+                } else {
+                    *read = (*read).max(depth);
+                }
+            }
+            max_chain_length = max_chain_length.max(depth);
+        }
+
+        max_chain_length
+    }
+
+    /// Share of all accesses that hit the hottest slot.
+    pub fn hottest_slot_share(&self) -> f64 {
+        let mut counts: HashMap<(Address, B256), usize> = HashMap::new();
+        for access in self.accesses.iter().flatten() {
+            *counts.entry((access.contract, access.slot)).or_default() += 1;
+        }
+        let total: usize = counts.values().sum();
+        match counts.values().max() {
+            Some(&max) => max as f64 / total as f64,
+            None => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkloadConfig;
+
+    #[test]
+    fn test_hot_slot_writes_serialize() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 100,
+            num_transactions: 50,
+            transactions_per_block: 10,
+            hot_accounts: 100,
+            fake_signatures: true,
+            ..Default::default()
+        });
+
+        let reads = StorageLayout::generate(
+            &workload,
+            StorageConfig {
+                write_fraction: 0.0,
+                ..Default::default()
+            },
+        );
+        assert_eq!(reads.accesses.len(), 50);
+        assert_eq!(reads.initial_slots().count(), 4096);
+        // Shared reads add no conflicts beyond the accounts'
+        let empty = StorageLayout::generate(&workload, StorageConfig { contracts: 0, ..Default::default() });
+        assert_eq!(reads.max_chain_length(&workload), empty.max_chain_length(&workload));
+        assert!(reads.max_chain_length(&workload) < 50);
+
+        let hotspot = StorageLayout::generate(
+            &workload,
+            StorageConfig {
+                hot_slots: 1,
+                hot_fraction: 1.0,
+                write_fraction: 1.0,
+                ..Default::default()
+            },
+        );
+        assert_eq!(hotspot.hottest_slot_share(), 1.0);
+        assert_eq!(hotspot.max_chain_length(&workload), 50);
+    }
+}
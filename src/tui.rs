@@ -0,0 +1,418 @@
+//! Live terminal dashboard for benchmark runs.
+//!
+//! [`Dashboard`] is a [`RunObserver`]: attached to a [`BenchRunner`], it
+//! takes over the terminal and redraws every [`FRAME_INTERVAL`] with
+//!
+//! - overall progress across every cell run;
+//! - a grid of cells with their status and TPS: live for the running cell
+//!   when its executor reports [live progress](crate::live), the mean of the
+//!   finished repetitions otherwise;
+//! - per-worker utilization of the running cell, for executors that report it;
+//! - a log pane with each finished run and its detail lines, which
+//!   `--tui` shows here instead of printing.
+//!
+//! Press `q` or Ctrl-C to abort the benchmark. The terminal is restored when
+//! the dashboard [finishes](Dashboard::finish) or the process panics.
+//!
+//! [`BenchRunner`]: crate::harness::BenchRunner
+
+use crate::harness::{CellReport, RunObserver};
+use crate::live::{LiveProgress, LiveSnapshot};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Cell, Gauge, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Time between redraws.
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Log lines kept for the log pane.
+const LOG_LINES: usize = 500;
+
+/// One plan cell in the grid.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CellRow {
+    index: usize,
+    section: String,
+    group: Option<String>,
+    /// Executor and hot-account label, known once a run finished.
+    executor_name: Option<String>,
+    hot_accounts_label: Option<String>,
+    throughputs: Vec<f64>,
+    failed: usize,
+    unstable: bool,
+}
+
+impl CellRow {
+    fn mean_tps(&self) -> Option<f64> {
+        (!self.throughputs.is_empty()).then(|| self.throughputs.iter().sum::<f64>() / self.throughputs.len() as f64)
+    }
+}
+
+/// What the dashboard shows, updated by runner events.
+#[derive(Debug, Default)]
+struct DashboardState {
+    rows: Vec<CellRow>,
+    repetitions: usize,
+    /// Row and repetition of the running cell.
+    running: Option<(usize, usize)>,
+    finished_runs: usize,
+    log: VecDeque<String>,
+}
+
+impl DashboardState {
+    fn total_runs(&self) -> usize {
+        self.rows.len() * self.repetitions
+    }
+
+    fn row(&self, index: usize) -> Option<usize> {
+        self.rows.iter().position(|row| row.index == index)
+    }
+
+    fn log(&mut self, line: String) {
+        if self.log.len() == LOG_LINES {
+            self.log.pop_front();
+        }
+        self.log.push_back(line);
+    }
+
+    fn run_started(&mut self, cells: &[(usize, &str, Option<&str>)], repetitions: usize) {
+        self.rows = cells
+            .iter()
+            .map(|&(index, section, group)| CellRow {
+                index,
+                section: section.to_string(),
+                group: group.map(str::to_string),
+                ..Default::default()
+            })
+            .collect();
+        self.repetitions = repetitions;
+        self.log(format!("Running {} cells × {} repetitions", cells.len(), repetitions));
+    }
+
+    fn cell_started(&mut self, index: usize, repetition: usize) {
+        self.running = self.row(index).map(|row| (row, repetition));
+    }
+
+    fn cell_finished(&mut self, report: &CellReport) {
+        self.running = None;
+        self.finished_runs += 1;
+        let Some(position) = self.row(report.index) else {
+            return;
+        };
+
+        let result = &report.result;
+        let row = &mut self.rows[position];
+        row.executor_name = Some(result.executor_name.clone());
+        row.hot_accounts_label = Some(result.hot_accounts_label.clone());
+        row.throughputs.push(result.throughput_tps);
+        row.failed += result.failed;
+        row.unstable |= report.unstable;
+
+        self.log(format!(
+            "#{} rep {}: {} @ {}: {:.0} TPS, {} ok / {} failed, {:.2} ms",
+            report.index,
+            report.repetition + 1,
+            result.executor_name,
+            result.hot_accounts_label,
+            result.throughput_tps,
+            result.successful,
+            result.failed,
+            result.duration_ms
+        ));
+        for detail in &report.details {
+            self.log(format!("    {}", detail));
+        }
+        if report.unstable {
+            self.log("    ⚠️  CPU frequency varied beyond the threshold".to_string());
+        }
+    }
+}
+
+/// State shared with the render thread.
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<DashboardState>,
+    progress: Arc<LiveProgress>,
+    done: AtomicBool,
+}
+
+/// Live terminal dashboard; see the [module docs](self).
+#[derive(Debug)]
+pub struct Dashboard {
+    shared: Arc<Shared>,
+    render: Mutex<Option<thread::JoinHandle<io::Result<()>>>>,
+}
+
+impl Dashboard {
+    /// Takes over the terminal and starts redrawing, reading live progress from `progress`.
+    pub fn start(progress: Arc<LiveProgress>) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(DashboardState::default()),
+            progress,
+            done: AtomicBool::new(false),
+        });
+        let render = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                let mut terminal = ratatui::init();
+                let result = render_loop(&mut terminal, &shared);
+                ratatui::restore();
+                result
+            })
+        };
+        Self {
+            shared,
+            render: Mutex::new(Some(render)),
+        }
+    }
+
+    /// Stops redrawing and restores the terminal.
+    pub fn finish(&self) -> io::Result<()> {
+        self.shared.done.store(true, Ordering::Release);
+        match self.render.lock().unwrap().take() {
+            Some(render) => render.join().unwrap_or_else(|_| Err(io::Error::other("dashboard thread panicked"))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl RunObserver for Dashboard {
+    fn run_started(&self, cells: &[(usize, &str, Option<&str>)], repetitions: usize) {
+        self.shared.state.lock().unwrap().run_started(cells, repetitions);
+    }
+
+    fn cell_started(&self, index: usize, repetition: usize) {
+        self.shared.progress.reset();
+        self.shared.state.lock().unwrap().cell_started(index, repetition);
+    }
+
+    fn cell_finished(&self, report: &CellReport) {
+        self.shared.state.lock().unwrap().cell_finished(report);
+    }
+}
+
+fn render_loop(terminal: &mut ratatui::DefaultTerminal, shared: &Shared) -> io::Result<()> {
+    let mut previous = LiveSnapshot::default();
+    let mut utilization = Vec::new();
+
+    while !shared.done.load(Ordering::Acquire) {
+        let live = shared.progress.snapshot();
+        // A cell started since the last frame if its clock went backwards
+        if live.elapsed >= previous.elapsed {
+            utilization = live.utilization_since(&previous);
+        } else {
+            utilization.clear();
+        }
+        previous = live.clone();
+
+        {
+            let state = shared.state.lock().unwrap();
+            terminal.draw(|frame| draw(frame, &state, &live, &utilization))?;
+        }
+
+        if event::poll(FRAME_INTERVAL)? {
+            if let Event::Key(key) = event::read()? {
+                let interrupt = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.kind == KeyEventKind::Press && (key.code == KeyCode::Char('q') || interrupt) {
+                    ratatui::restore();
+                    eprintln!("Benchmark aborted from the dashboard");
+                    std::process::exit(130);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn draw(frame: &mut Frame, state: &DashboardState, live: &LiveSnapshot, utilization: &[f64]) {
+    let [progress_area, middle, log_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(8),
+        Constraint::Length(12),
+    ])
+    .areas(frame.area());
+    let [grid_area, workers_area] =
+        Layout::horizontal([Constraint::Percentage(70), Constraint::Percentage(30)]).areas(middle);
+
+    let total = state.total_runs().max(1);
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::bordered().title(" Benchmark (q to abort) "))
+            .gauge_style(Style::default().fg(Color::Green))
+            .ratio(state.finished_runs.min(total) as f64 / total as f64)
+            .label(format!("{}/{} cell runs", state.finished_runs, state.total_runs())),
+        progress_area,
+    );
+
+    draw_grid(frame, grid_area, state, live);
+    draw_workers(frame, workers_area, utilization);
+
+    let visible = log_area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = state
+        .log
+        .iter()
+        .skip(state.log.len().saturating_sub(visible))
+        .map(|line| Line::raw(line.as_str()))
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Log ")), log_area);
+}
+
+fn draw_grid(frame: &mut Frame, area: Rect, state: &DashboardState, live: &LiveSnapshot) {
+    let rows = state.rows.iter().enumerate().map(|(position, row)| {
+        let running = state.running.filter(|&(running, _)| running == position);
+        let (status, style) = match running {
+            Some((_, repetition)) => (
+                format!("running {}/{}", repetition + 1, state.repetitions),
+                Style::default().fg(Color::Yellow),
+            ),
+            None if row.throughputs.len() >= state.repetitions => ("done".to_string(), Style::default().fg(Color::Green)),
+            None if row.throughputs.is_empty() => ("pending".to_string(), Style::default().fg(Color::DarkGray)),
+            None => (
+                format!("{}/{}", row.throughputs.len(), state.repetitions),
+                Style::default(),
+            ),
+        };
+        let tps = match (running, row.mean_tps()) {
+            (Some(_), _) if live.committed > 0 => format!("{:.0} live", live.throughput_tps()),
+            (_, Some(mean)) => format!("{:.0}", mean),
+            (_, None) => "-".to_string(),
+        };
+        let mut flags = String::new();
+        if row.failed > 0 {
+            flags.push_str(&format!("{} failed ", row.failed));
+        }
+        if row.unstable {
+            flags.push_str("⚠️ freq");
+        }
+
+        Row::new(vec![
+            Cell::from(row.index.to_string()),
+            Cell::from(row.section.clone()),
+            Cell::from(row.group.clone().unwrap_or_default()),
+            Cell::from(row.executor_name.clone().unwrap_or_default()),
+            Cell::from(row.hot_accounts_label.clone().unwrap_or_default()),
+            Cell::from(status),
+            Cell::from(tps),
+            Cell::from(flags),
+        ])
+        .style(style)
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(4),
+            Constraint::Fill(3),
+            Constraint::Length(11),
+            Constraint::Fill(2),
+            Constraint::Length(14),
+            Constraint::Length(12),
+            Constraint::Length(14),
+            Constraint::Length(16),
+        ],
+    )
+    .header(
+        Row::new(["#", "Section", "Group", "Executor", "Hot accounts", "Status", "TPS", "Flags"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(" Cells "))
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    let mut table_state = TableState::default().with_selected(state.running.map(|(position, _)| position));
+    frame.render_stateful_widget(table, area, &mut table_state);
+}
+
+fn draw_workers(frame: &mut Frame, area: Rect, utilization: &[f64]) {
+    let block = Block::bordered().title(" Worker utilization ");
+    if utilization.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No live worker data for this executor").block(block),
+            area,
+        );
+        return;
+    }
+
+    let bars: Vec<Bar> = utilization
+        .iter()
+        .enumerate()
+        .map(|(worker, &busy)| {
+            let percent = (busy * 100.0).round() as u64;
+            Bar::default()
+                .label(Line::from(format!("w{}", worker)))
+                .value(percent)
+                .text_value(format!("{}%", percent))
+        })
+        .collect();
+    frame.render_widget(
+        BarChart::default()
+            .block(block)
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(4)
+            .bar_gap(1)
+            .max(100),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::BenchResult;
+
+    fn report(index: usize, repetition: usize, throughput_tps: f64) -> CellReport {
+        CellReport {
+            index,
+            section: "Block-STM".to_string(),
+            group: Some("4 threads".to_string()),
+            repetition,
+            result: BenchResult {
+                hot_accounts_label: "10 accounts".to_string(),
+                executor_name: "block_stm_parallel".to_string(),
+                preserves_order: true,
+                successful: 99,
+                failed: 1,
+                duration_ms: 10.0,
+                throughput_tps,
+            },
+            details: vec!["parallelism: 3.2".to_string()],
+            frequency: None,
+            unstable: false,
+        }
+    }
+
+    #[test]
+    fn test_state_follows_runner_events() {
+        let mut state = DashboardState::default();
+        state.run_started(&[(0, "Sequential", None), (3, "Block-STM", Some("4 threads"))], 2);
+        assert_eq!(state.total_runs(), 4);
+
+        state.cell_started(3, 1);
+        assert_eq!(state.running, Some((1, 1)));
+
+        state.cell_finished(&report(3, 0, 1000.0));
+        state.cell_finished(&report(3, 1, 3000.0));
+        assert_eq!(state.running, None);
+        assert_eq!(state.finished_runs, 2);
+
+        let row = &state.rows[1];
+        assert_eq!(row.executor_name.as_deref(), Some("block_stm_parallel"));
+        assert_eq!(row.mean_tps(), Some(2000.0));
+        assert_eq!(row.failed, 2);
+        assert_eq!(state.rows[0].mean_tps(), None);
+        assert_eq!(state.log.back().map(String::as_str), Some("    parallelism: 3.2"));
+
+        for _ in 0..LOG_LINES {
+            state.log("line".to_string());
+        }
+        assert_eq!(state.log.len(), LOG_LINES);
+    }
+}
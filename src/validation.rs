@@ -0,0 +1,470 @@
+//! Transaction and workload validation.
+//!
+//! [`validate_stateless`] and [`validate_stateful`] are the rejection rules
+//! every executor applies before a transfer, so executors differ only in how
+//! they react to a [`ValidationError`]: sequential executors fail the
+//! transaction, while parallel ones may wait for or retry errors that earlier
+//! transactions can still fix (see [`ValidationError::may_become_valid`]).
+//! The Block-STM engine crate cannot depend on this one and mirrors the same
+//! checks in its own transaction execution.
+//!
+//! [`Workload::validate`] checks a generated workload without executing it, so
+//! a broken generator change fails in seconds instead of surfacing as odd
+//! benchmark numbers hours later. Injected replays (see
+//! [`Workload::replay_mask`]) are expected to be rejected and are skipped by
+//! the nonce and balance checks. Signatures are not checked for workloads
+//! generated with [`WorkloadConfig::fake_signatures`](crate::WorkloadConfig::fake_signatures).
+
+use crate::recovery::RecoveryBackend;
+use crate::{SignedTransaction, TokenId, Workload};
+use alloy_primitives::{Address, B256, U256};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Why an executor rejects a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The signature does not recover to `tx.from`.
+    InvalidSignature,
+    /// The sender has no account.
+    UnknownSender,
+    /// The sender's nonce is already past the transaction's, so it can never apply.
+    NonceTooLow { expected: u64, found: u64 },
+    /// The sender's nonce has not reached the transaction's yet.
+    NonceTooHigh { expected: u64, found: u64 },
+    /// The sender cannot afford the transfer.
+    InsufficientBalance { balance: U256, value: U256 },
+}
+
+impl ValidationError {
+    /// Whether transactions applied first could still make this one valid,
+    /// so a parallel executor may wait for them instead of failing it.
+    pub fn may_become_valid(&self) -> bool {
+        matches!(self, Self::NonceTooHigh { .. } | Self::InsufficientBalance { .. })
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSignature => f.write_str("signature does not recover to the sender"),
+            Self::UnknownSender => f.write_str("sender has no account"),
+            Self::NonceTooLow { expected, found } => write!(f, "nonce {} is below the sender's {}", found, expected),
+            Self::NonceTooHigh { expected, found } => write!(f, "nonce {} is above the sender's {}", found, expected),
+            Self::InsufficientBalance { balance, value } => {
+                write!(f, "sender has {} wei, sends {}", balance, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// The sender's account as checked by [`validate_stateful`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SenderState {
+    /// Next nonce, or `None` for nonce-less transactions, whose ids are
+    /// checked against a seen-set instead.
+    pub nonce: Option<u64>,
+    /// Balance of the asset the transaction moves.
+    pub balance: U256,
+}
+
+impl SenderState {
+    /// A sender whose next nonce is `nonce`.
+    pub fn new(nonce: u64, balance: U256) -> Self {
+        Self {
+            nonce: Some(nonce),
+            balance,
+        }
+    }
+
+    /// The sender of a nonce-less transaction.
+    pub fn nonceless(balance: U256) -> Self {
+        Self { nonce: None, balance }
+    }
+}
+
+/// Checks what needs only the transaction itself: its signature must
+/// recover to `tx.from` with `recovery`.
+pub fn validate_stateless(tx: &SignedTransaction, recovery: RecoveryBackend) -> Result<(), ValidationError> {
+    if recovery.verify(tx) {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidSignature)
+    }
+}
+
+/// Checks the transaction against its sender, `None` if the sender has no
+/// account: the nonce must be the sender's next one, then the balance must
+/// cover the value.
+pub fn validate_stateful(tx: &SignedTransaction, sender: Option<SenderState>) -> Result<(), ValidationError> {
+    let sender = sender.ok_or(ValidationError::UnknownSender)?;
+    if let Some(expected) = sender.nonce {
+        let found = tx.nonce;
+        match found.cmp(&expected) {
+            Ordering::Less => return Err(ValidationError::NonceTooLow { expected, found }),
+            Ordering::Greater => return Err(ValidationError::NonceTooHigh { expected, found }),
+            Ordering::Equal => {}
+        }
+    }
+    if sender.balance < tx.value {
+        return Err(ValidationError::InsufficientBalance {
+            balance: sender.balance,
+            value: tx.value,
+        });
+    }
+    Ok(())
+}
+
+/// A single problem found in a workload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ValidationIssue {
+    /// A sender's nonces are not consecutive from zero.
+    NonceGap {
+        index: usize,
+        sender: Address,
+        expected: u64,
+        found: u64,
+    },
+    /// The signature does not recover to `tx.from`.
+    InvalidSignature { index: usize, tx_hash: B256 },
+    /// `tx.tx_hash` does not match the transaction fields.
+    HashMismatch { index: usize, tx_hash: B256 },
+    /// The sender is not one of the workload's pre-funded accounts.
+    UnknownSender { index: usize, sender: Address },
+    /// The sender cannot afford the transfer at this point in the workload.
+    InsufficientBalance {
+        index: usize,
+        sender: Address,
+        balance: U256,
+        value: U256,
+    },
+    /// The receiver's balance would overflow, as with
+    /// [`near_max_balance`](crate::WorkloadConfig::near_max_balance).
+    BalanceOverflow {
+        index: usize,
+        receiver: Address,
+        balance: U256,
+        value: U256,
+    },
+    /// `blocks` does not hold exactly `transactions`, in order.
+    BlockContentMismatch { block: usize },
+    /// A block other than the last one is not `transactions_per_block` long.
+    BlockSize {
+        block: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonceGap {
+                index,
+                sender,
+                expected,
+                found,
+            } => write!(f, "tx {}: sender {} expected nonce {}, found {}", index, sender, expected, found),
+            Self::InvalidSignature { index, tx_hash } => {
+                write!(f, "tx {}: signature of {} does not recover to the sender", index, tx_hash)
+            }
+            Self::HashMismatch { index, tx_hash } => {
+                write!(f, "tx {}: hash {} does not match transaction fields", index, tx_hash)
+            }
+            Self::UnknownSender { index, sender } => {
+                write!(f, "tx {}: sender {} is not a workload account", index, sender)
+            }
+            Self::InsufficientBalance {
+                index,
+                sender,
+                balance,
+                value,
+            } => write!(f, "tx {}: sender {} has {} wei, sends {}", index, sender, balance, value),
+            Self::BalanceOverflow {
+                index,
+                receiver,
+                balance,
+                value,
+            } => write!(f, "tx {}: receiver {} has {} wei, receiving {} overflows", index, receiver, balance, value),
+            Self::BlockContentMismatch { block } => {
+                write!(f, "block {}: transactions differ from the flat transaction list", block)
+            }
+            Self::BlockSize {
+                block,
+                expected,
+                found,
+            } => write!(f, "block {}: expected {} transactions, found {}", block, expected, found),
+        }
+    }
+}
+
+/// Result of [`Workload::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidationReport {
+    /// Transactions checked.
+    pub num_transactions: usize,
+    /// Distinct senders seen.
+    pub num_senders: usize,
+    /// Injected replays, skipped by the nonce and balance checks.
+    pub num_replays: usize,
+    /// Every problem found, in transaction order per check.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Whether no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Workload {
+    /// Checks nonce continuity, signatures, balance feasibility, and block
+    /// layout without executing the workload.
+    ///
+    /// Nonce continuity is not checked for
+    /// [`nonceless`](crate::WorkloadConfig::nonceless) workloads.
+    pub fn validate(&self) -> ValidationReport {
+        let replay_mask = self.replay_mask();
+        let mut issues = Vec::new();
+
+        // Accounts are pre-funded with the same native and token balance by
+        // every backend
+        let initial_balance = self.initial_balance();
+        let funded: HashSet<Address> = self.accounts.iter().map(|account| account.address).collect();
+        let opening_balance = |address: Address, token: Option<TokenId>| {
+            let has_asset = token.map_or(true, |token| (token as usize) < self.config.tokens);
+            if funded.contains(&address) && has_asset {
+                initial_balance
+            } else {
+                U256::ZERO
+            }
+        };
+        let mut balances: HashMap<(Address, Option<TokenId>), U256> = HashMap::new();
+        let mut next_nonces: HashMap<Address, u64> = HashMap::new();
+
+        for (index, (tx, &replay)) in self.transactions.iter().zip(&replay_mask).enumerate() {
+            let expected_hash = SignedTransaction::compute_tx_hash(
+                tx.from,
+                tx.to,
+                tx.value,
+                tx.token,
+                tx.nonce,
+                self.config.chain_id,
+            );
+            if expected_hash != tx.tx_hash {
+                issues.push(ValidationIssue::HashMismatch {
+                    index,
+                    tx_hash: tx.tx_hash,
+                });
+            }
+            if !self.config.fake_signatures && !tx.verify() {
+                issues.push(ValidationIssue::InvalidSignature {
+                    index,
+                    tx_hash: tx.tx_hash,
+                });
+            }
+
+            let expected = next_nonces.entry(tx.from).or_insert(0);
+            if replay {
+                continue;
+            }
+            // Nonce-less ids carry no sequence; the replay mask already checked them
+            if !self.config.nonceless {
+                if tx.nonce != *expected {
+                    issues.push(ValidationIssue::NonceGap {
+                        index,
+                        sender: tx.from,
+                        expected: *expected,
+                        found: tx.nonce,
+                    });
+                }
+                *expected = tx.nonce + 1;
+            }
+
+            if !funded.contains(&tx.from) {
+                issues.push(ValidationIssue::UnknownSender {
+                    index,
+                    sender: tx.from,
+                });
+                continue;
+            }
+
+            let sender_balance = *balances
+                .entry((tx.from, tx.token))
+                .or_insert_with(|| opening_balance(tx.from, tx.token));
+            if sender_balance < tx.value {
+                issues.push(ValidationIssue::InsufficientBalance {
+                    index,
+                    sender: tx.from,
+                    balance: sender_balance,
+                    value: tx.value,
+                });
+                continue;
+            }
+            // A self-transfer nets to zero and cannot overflow
+            let receiver_balance = if tx.from == tx.to {
+                sender_balance - tx.value
+            } else {
+                *balances.entry((tx.to, tx.token)).or_insert_with(|| opening_balance(tx.to, tx.token))
+            };
+            match receiver_balance.checked_add(tx.value) {
+                Some(receiver_balance) => {
+                    balances.insert((tx.from, tx.token), sender_balance - tx.value);
+                    balances.insert((tx.to, tx.token), receiver_balance);
+                }
+                None => issues.push(ValidationIssue::BalanceOverflow {
+                    index,
+                    receiver: tx.to,
+                    balance: receiver_balance,
+                    value: tx.value,
+                }),
+            }
+        }
+
+        self.validate_blocks(&mut issues);
+
+        ValidationReport {
+            num_transactions: self.transactions.len(),
+            num_senders: next_nonces.len(),
+            num_replays: replay_mask.into_iter().filter(|&replay| replay).count(),
+            issues,
+        }
+    }
+
+    fn validate_blocks(&self, issues: &mut Vec<ValidationIssue>) {
+        let block_size = self.config.transactions_per_block;
+        let mut offset = 0;
+
+        for (block, txs) in self.blocks.iter().enumerate() {
+            let is_last = block + 1 == self.blocks.len();
+            if (!is_last && txs.len() != block_size) || txs.is_empty() || txs.len() > block_size {
+                issues.push(ValidationIssue::BlockSize {
+                    block,
+                    expected: block_size,
+                    found: txs.len(),
+                });
+            }
+
+            let flat = self.transactions.get(offset..offset + txs.len()).unwrap_or_default();
+            let matches = flat.len() == txs.len()
+                && flat.iter().zip(txs).all(|(a, b)| a.tx_hash == b.tx_hash);
+            if !matches {
+                issues.push(ValidationIssue::BlockContentMismatch { block });
+            }
+            offset += txs.len();
+        }
+
+        if offset != self.transactions.len() {
+            issues.push(ValidationIssue::BlockContentMismatch {
+                block: self.blocks.len(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComputeCost, WorkloadConfig};
+
+    fn workload() -> Workload {
+        Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 20,
+            hot_accounts: 10,
+            seed: 7,
+            chain_id: 1,
+            transactions_per_block: 5,
+            duplicate_transactions: 2,
+            double_spends: 2,
+            fake_signatures: false,
+            tokens: 0,
+            nonceless: false,
+            compute_cost: ComputeCost::default(),
+            near_max_balance: None,
+            hot_probability: 1.0,
+        })
+    }
+
+    #[test]
+    fn test_generated_workload_is_valid() {
+        let report = workload().validate();
+        assert!(report.is_valid(), "{:?}", report.issues);
+        assert_eq!(report.num_transactions, 24);
+        assert_eq!(report.num_replays, 4);
+    }
+
+    #[test]
+    fn test_detects_tampering() {
+        let mut workload = workload();
+        let later = workload.replay_mask().iter().rposition(|&replay| !replay).unwrap();
+        workload.transactions[later].nonce += 5;
+        workload.transactions[1].from = Address::with_last_byte(1);
+        workload.blocks[0].pop();
+
+        let issues = workload.validate().issues;
+        assert!(issues.iter().any(|issue| matches!(issue, ValidationIssue::NonceGap { index, .. } if *index == later)));
+        assert!(issues.contains(&ValidationIssue::InvalidSignature {
+            index: 1,
+            tx_hash: workload.transactions[1].tx_hash,
+        }));
+        assert!(issues.iter().any(|issue| matches!(issue, ValidationIssue::UnknownSender { index: 1, .. })));
+        assert!(issues.contains(&ValidationIssue::BlockSize {
+            block: 0,
+            expected: 5,
+            found: 4,
+        }));
+    }
+
+    #[test]
+    fn test_validate_transaction() {
+        let workload = workload();
+        let tx = &workload.transactions[0];
+        assert_eq!(validate_stateless(tx, RecoveryBackend::K256), Ok(()));
+        let mut forged = tx.clone();
+        forged.from = Address::with_last_byte(1);
+        assert_eq!(validate_stateless(&forged, RecoveryBackend::K256), Err(ValidationError::InvalidSignature));
+
+        let balance = tx.value;
+        assert_eq!(validate_stateful(tx, Some(SenderState::new(tx.nonce, balance))), Ok(()));
+        assert_eq!(validate_stateful(tx, Some(SenderState::nonceless(balance))), Ok(()));
+        assert_eq!(validate_stateful(tx, None), Err(ValidationError::UnknownSender));
+
+        let too_low = validate_stateful(tx, Some(SenderState::new(tx.nonce + 1, balance))).unwrap_err();
+        assert_eq!(too_low, ValidationError::NonceTooLow { expected: tx.nonce + 1, found: tx.nonce });
+        assert!(!too_low.may_become_valid());
+
+        let mut ahead = tx.clone();
+        ahead.nonce += 3;
+        let too_high = validate_stateful(&ahead, Some(SenderState::new(tx.nonce, balance))).unwrap_err();
+        assert!(matches!(too_high, ValidationError::NonceTooHigh { .. }));
+        assert!(too_high.may_become_valid());
+
+        // The nonce is checked before the balance
+        let poor = SenderState::new(tx.nonce, balance - U256::from(1));
+        assert!(matches!(validate_stateful(tx, Some(poor)), Err(ValidationError::InsufficientBalance { .. })));
+        assert!(matches!(validate_stateful(&ahead, Some(poor)), Err(ValidationError::NonceTooHigh { .. })));
+    }
+
+    #[test]
+    fn test_detects_balance_overflow() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 2,
+            num_transactions: 4,
+            hot_accounts: 2,
+            near_max_balance: Some(0),
+            ..Default::default()
+        });
+
+        // Both accounts start at U256::MAX, so every credit overflows
+        let issues = workload.validate().issues;
+        assert_eq!(issues.len(), 4);
+        assert!(issues.iter().all(|issue| matches!(issue, ValidationIssue::BalanceOverflow { balance, .. } if *balance == U256::MAX)));
+    }
+}
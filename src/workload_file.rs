@@ -0,0 +1,586 @@
+//! Workload files.
+//!
+//! [`save_workloads`] writes generated workloads, keys included, so the exact
+//! same transactions can be analyzed, benchmarked or shared without
+//! regenerating them (and without the signing cost of doing so).
+//! [`load_workloads`] reads them back.
+//!
+//! The file is tab-separated text, one record per line, with the record kind
+//! in the first field: a `workload` record carrying the configuration starts
+//! each workload and is followed by its `account` and `tx` records, which end
+//! with the token id, empty for native transfers, and the compute cost. Lines
+//! starting with `#` are comments. Account keys are stored in the clear, so
+//! only write workloads whose accounts are throwaway.
+//!
+//! For large workloads the `serde` feature adds a compact binary form,
+//! written by `save_workloads_binary` and `Workload::save` and read back by
+//! `Workload::load`: fixed-width
+//! big-endian fields after a magic header, about half the size of the text
+//! and loaded without parsing decimal and hex, with each configuration
+//! bincode-encoded. It also records each block's length, so irregular blocks,
+//! like those of an [imported block file](crate::rlp_blocks), survive a round
+//! trip. [`load_workloads`] recognizes either form.
+
+use crate::{Account, ComputeCost, SignedTransaction, Workload, WorkloadConfig};
+use alloy_primitives::{Address, Signature, B256, U256};
+use k256::ecdsa::SigningKey;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Workload file format version written to the `version` record, which must
+/// precede the workloads. Version 1 files, which have fewer `workload` and
+/// `tx` fields, are still read.
+pub const WORKLOAD_FILE_VERSION: u32 = 2;
+
+/// First bytes of a binary workload file, followed by its format version.
+const BINARY_MAGIC: &[u8; 8] = b"dbtwkld\0";
+
+/// Binary workload file format version.
+pub const BINARY_WORKLOAD_FILE_VERSION: u32 = 2;
+
+/// Error reading or writing a workload file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkloadFileError(pub String);
+
+impl fmt::Display for WorkloadFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for WorkloadFileError {}
+
+/// Formats `config` as tab-separated fields, in declaration order; a missing
+/// `near_max_balance` is an empty field.
+pub(crate) fn format_config(config: &WorkloadConfig) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        config.num_accounts,
+        config.num_transactions,
+        config.transactions_per_block,
+        config.hot_accounts,
+        config.seed,
+        config.chain_id,
+        config.duplicate_transactions,
+        config.double_spends,
+        config.fake_signatures,
+        config.tokens,
+        config.nonceless,
+        config.compute_cost,
+        config.near_max_balance.map(|headroom| headroom.to_string()).unwrap_or_default(),
+        config.hot_probability
+    )
+}
+
+/// Parses the fields written by [`format_config`] in workload file version
+/// `version`. Version 1 wrote only the first nine, so its workloads have no
+/// tokens, nonce-less transactions, compute costs or near-max balances, and
+/// all of their traffic is hot.
+pub(crate) fn parse_config(fields: &[&str], version: u32) -> Option<WorkloadConfig> {
+    let (fields, tokens, nonceless, compute_cost, near_max_balance, hot_probability) = match (version, fields) {
+        (1, fields) => (fields, 0, false, ComputeCost::default(), None, 1.0),
+        (2, [fields @ .., tokens, nonceless, compute_cost, near_max_balance, hot_probability]) => (
+            fields,
+            tokens.parse().ok()?,
+            nonceless.parse().ok()?,
+            compute_cost.parse().ok()?,
+            match *near_max_balance {
+                "" => None,
+                headroom => Some(headroom.parse().ok()?),
+            },
+            hot_probability.parse().ok()?,
+        ),
+        _ => return None,
+    };
+    let [num_accounts, num_transactions, transactions_per_block, hot_accounts, seed, chain_id, duplicate_transactions, double_spends, fake_signatures] =
+        fields
+    else {
+        return None;
+    };
+    Some(WorkloadConfig {
+        num_accounts: num_accounts.parse().ok()?,
+        num_transactions: num_transactions.parse().ok()?,
+        transactions_per_block: transactions_per_block.parse().ok()?,
+        hot_accounts: hot_accounts.parse().ok()?,
+        seed: seed.parse().ok()?,
+        chain_id: chain_id.parse().ok()?,
+        duplicate_transactions: duplicate_transactions.parse().ok()?,
+        double_spends: double_spends.parse().ok()?,
+        fake_signatures: fake_signatures.parse().ok()?,
+        tokens,
+        nonceless,
+        compute_cost,
+        near_max_balance,
+        hot_probability,
+    })
+}
+
+/// Serializes `workloads`.
+pub fn workloads_to_text(workloads: &[Workload]) -> String {
+    let mut lines = vec![
+        "# db-test workloads".to_string(),
+        format!("version\t{}", WORKLOAD_FILE_VERSION),
+    ];
+    for workload in workloads {
+        lines.push(format!("workload\t{}", format_config(&workload.config)));
+        for account in &workload.accounts {
+            let key = B256::from_slice(&account.signing_key.to_bytes());
+            lines.push(format!("account\t{}\t{}", account.address, key));
+        }
+        for tx in &workload.transactions {
+            lines.push(format!(
+                "tx\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                tx.from,
+                tx.to,
+                tx.value,
+                tx.nonce,
+                tx.signature.r(),
+                tx.signature.s(),
+                tx.signature.v(),
+                tx.tx_hash,
+                tx.token.map(|token| token.to_string()).unwrap_or_default(),
+                tx.compute_cost
+            ));
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Parses workloads written by [`workloads_to_text`].
+///
+/// Blocks are rebuilt from `transactions_per_block`, and `num_transactions`
+/// and `num_accounts` are taken from the records, so mutated workloads load
+/// as they were saved.
+pub fn workloads_from_text(text: &str) -> Result<Vec<Workload>, WorkloadFileError> {
+    let mut workloads: Vec<Workload> = Vec::new();
+    let mut version = None;
+    for (number, line) in text.lines().enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = || WorkloadFileError(format!("line {}: invalid record '{}'", number + 1, line));
+        let fields: Vec<&str> = line.split('\t').collect();
+
+        match fields[0] {
+            "version" => match fields[1..] {
+                [number] => match number.parse() {
+                    Ok(number @ 1..=WORKLOAD_FILE_VERSION) => version = Some(number),
+                    _ => return Err(WorkloadFileError(format!("unsupported workload file version {}", number))),
+                },
+                _ => return Err(invalid()),
+            },
+            "workload" => workloads.push(Workload {
+                accounts: Vec::new(),
+                transactions: Vec::new(),
+                blocks: Vec::new(),
+                config: parse_config(&fields[1..], version.ok_or_else(invalid)?).ok_or_else(invalid)?,
+                genesis: OnceLock::new(),
+                signers: OnceLock::new(),
+            }),
+            "account" => {
+                let workload = workloads.last_mut().ok_or_else(invalid)?;
+                let [_, address, key] = fields[..] else {
+                    return Err(invalid());
+                };
+                let key: B256 = key.parse().map_err(|_| invalid())?;
+                workload.accounts.push(Account {
+                    signing_key: SigningKey::from_bytes(&key.0.into()).map_err(|_| invalid())?,
+                    address: address.parse().map_err(|_| invalid())?,
+                });
+            }
+            "tx" => {
+                let workload = workloads.last_mut().ok_or_else(invalid)?;
+                let (token, compute_cost) = match (version, &fields[..]) {
+                    (Some(1), [_, _, _, _, _, _, _, _, _]) => (None, 0),
+                    (Some(2), [.., token, compute_cost]) if fields.len() == 11 => (
+                        Some(token)
+                            .filter(|token| !token.is_empty())
+                            .map(|token| token.parse())
+                            .transpose()
+                            .map_err(|_| invalid())?,
+                        compute_cost.parse().map_err(|_| invalid())?,
+                    ),
+                    _ => return Err(invalid()),
+                };
+                let [_, from, to, value, nonce, r, s, y_parity, tx_hash] = fields[..9] else {
+                    return Err(invalid());
+                };
+                let parse_u256 = |field: &str| field.parse::<U256>().map_err(|_| invalid());
+                workload.transactions.push(SignedTransaction {
+                    from: from.parse::<Address>().map_err(|_| invalid())?,
+                    to: to.parse::<Address>().map_err(|_| invalid())?,
+                    value: parse_u256(value)?,
+                    token,
+                    nonce: nonce.parse().map_err(|_| invalid())?,
+                    signature: Signature::new(parse_u256(r)?, parse_u256(s)?, y_parity.parse().map_err(|_| invalid())?),
+                    tx_hash: tx_hash.parse().map_err(|_| invalid())?,
+                    compute_cost,
+                });
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    for workload in &mut workloads {
+        let block_size = workload.config.transactions_per_block.max(1);
+        workload.blocks = workload.transactions.chunks(block_size).map(|chunk| chunk.to_vec()).collect();
+        workload.config.num_transactions = workload.transactions.len();
+        workload.config.num_accounts = workload.accounts.len();
+    }
+    Ok(workloads)
+}
+
+/// Serializes `workloads` in the binary form.
+///
+/// Each workload is its bincode-encoded configuration, then its
+/// accounts (address, key), its transactions (from, to, value, nonce, r, s,
+/// y parity, hash, token, compute cost) and its block lengths, each list
+/// preceded by its length.
+#[cfg(feature = "serde")]
+pub fn workloads_to_bytes(workloads: &[Workload]) -> Vec<u8> {
+    let transactions: usize = workloads.iter().map(|workload| workload.transactions.len()).sum();
+    let mut out = Vec::with_capacity(16 + transactions * 190);
+    out.extend_from_slice(BINARY_MAGIC);
+    out.extend_from_slice(&BINARY_WORKLOAD_FILE_VERSION.to_be_bytes());
+    out.extend_from_slice(&(workloads.len() as u32).to_be_bytes());
+    for workload in workloads {
+        let config = bincode::serialize(&workload.config).expect("workload configurations always serialize");
+        out.extend_from_slice(&(config.len() as u32).to_be_bytes());
+        out.extend_from_slice(&config);
+
+        out.extend_from_slice(&(workload.accounts.len() as u32).to_be_bytes());
+        for account in &workload.accounts {
+            out.extend_from_slice(account.address.as_slice());
+            out.extend_from_slice(&account.signing_key.to_bytes());
+        }
+
+        out.extend_from_slice(&(workload.transactions.len() as u32).to_be_bytes());
+        for tx in &workload.transactions {
+            out.extend_from_slice(tx.from.as_slice());
+            out.extend_from_slice(tx.to.as_slice());
+            out.extend_from_slice(&tx.value.to_be_bytes::<32>());
+            out.extend_from_slice(&tx.nonce.to_be_bytes());
+            out.extend_from_slice(&tx.signature.r().to_be_bytes::<32>());
+            out.extend_from_slice(&tx.signature.s().to_be_bytes::<32>());
+            out.push(tx.signature.v() as u8);
+            out.extend_from_slice(tx.tx_hash.as_slice());
+            out.push(tx.token.is_some() as u8);
+            out.extend_from_slice(&tx.token.unwrap_or_default().to_be_bytes());
+            out.extend_from_slice(&tx.compute_cost.to_be_bytes());
+        }
+
+        out.extend_from_slice(&(workload.blocks.len() as u32).to_be_bytes());
+        for block in &workload.blocks {
+            out.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        }
+    }
+    out
+}
+
+/// Parses workloads written by [`workloads_to_bytes`].
+#[cfg(feature = "serde")]
+pub fn workloads_from_bytes(bytes: &[u8]) -> Result<Vec<Workload>, WorkloadFileError> {
+    let mut reader = ByteReader(bytes);
+    if reader.array::<8>()? != *BINARY_MAGIC {
+        return Err(WorkloadFileError("not a binary workload file".to_string()));
+    }
+    let version = reader.u32()?;
+    if version != BINARY_WORKLOAD_FILE_VERSION {
+        return Err(WorkloadFileError(format!("unsupported binary workload file version {}", version)));
+    }
+
+    let num_workloads = reader.u32()?;
+    let mut workloads = Vec::new();
+    for index in 0..num_workloads {
+        let invalid = |what: &str| WorkloadFileError(format!("workload {}: invalid {}", index, what));
+
+        let config_len = reader.u32()? as usize;
+        let config = bincode::deserialize(reader.take(config_len)?).map_err(|_| invalid("configuration"))?;
+
+        let num_accounts = reader.u32()?;
+        let mut accounts = Vec::new();
+        for _ in 0..num_accounts {
+            let address = Address::from(reader.array::<20>()?);
+            let key = reader.array::<32>()?;
+            accounts.push(Account {
+                signing_key: SigningKey::from_bytes(&key.into()).map_err(|_| invalid("account key"))?,
+                address,
+            });
+        }
+
+        let num_transactions = reader.u32()?;
+        let mut transactions = Vec::new();
+        for _ in 0..num_transactions {
+            let from = Address::from(reader.array::<20>()?);
+            let to = Address::from(reader.array::<20>()?);
+            let value = U256::from_be_bytes(reader.array::<32>()?);
+            let nonce = reader.u64()?;
+            let r = U256::from_be_bytes(reader.array::<32>()?);
+            let s = U256::from_be_bytes(reader.array::<32>()?);
+            let y_parity = reader.array::<1>()?[0] != 0;
+            let tx_hash = B256::from(reader.array::<32>()?);
+            let has_token = reader.array::<1>()?[0] != 0;
+            let token = reader.u32()?;
+            transactions.push(SignedTransaction {
+                from,
+                to,
+                value,
+                token: has_token.then_some(token),
+                nonce,
+                signature: Signature::new(r, s, y_parity),
+                tx_hash,
+                compute_cost: reader.u64()?,
+            });
+        }
+
+        let num_blocks = reader.u32()?;
+        let mut blocks = Vec::new();
+        let mut remaining = transactions.as_slice();
+        for _ in 0..num_blocks {
+            let len = reader.u32()? as usize;
+            if len > remaining.len() {
+                return Err(invalid("block layout"));
+            }
+            let (block, rest) = remaining.split_at(len);
+            blocks.push(block.to_vec());
+            remaining = rest;
+        }
+        if !remaining.is_empty() {
+            return Err(invalid("block layout"));
+        }
+
+        workloads.push(Workload {
+            accounts,
+            transactions,
+            blocks,
+            config,
+            genesis: OnceLock::new(),
+            signers: OnceLock::new(),
+        });
+    }
+    if !reader.0.is_empty() {
+        return Err(WorkloadFileError("trailing bytes after the last workload".to_string()));
+    }
+    Ok(workloads)
+}
+
+/// Reads fixed-width fields off the front of a byte slice.
+#[cfg(feature = "serde")]
+struct ByteReader<'a>(&'a [u8]);
+
+#[cfg(feature = "serde")]
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], WorkloadFileError> {
+        if self.0.len() < len {
+            return Err(WorkloadFileError("truncated binary workload file".to_string()));
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], WorkloadFileError> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+
+    fn u32(&mut self) -> Result<u32, WorkloadFileError> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, WorkloadFileError> {
+        self.array().map(u64::from_be_bytes)
+    }
+}
+
+/// Writes `workloads` to `path`.
+pub fn save_workloads(path: impl AsRef<Path>, workloads: &[Workload]) -> Result<(), WorkloadFileError> {
+    let path = path.as_ref();
+    fs::write(path, workloads_to_text(workloads))
+        .map_err(|err| WorkloadFileError(format!("failed to write {}: {}", path.display(), err)))
+}
+
+/// Writes `workloads` to `path` in the binary form.
+#[cfg(feature = "serde")]
+pub fn save_workloads_binary(path: impl AsRef<Path>, workloads: &[Workload]) -> Result<(), WorkloadFileError> {
+    let path = path.as_ref();
+    fs::write(path, workloads_to_bytes(workloads))
+        .map_err(|err| WorkloadFileError(format!("failed to write {}: {}", path.display(), err)))
+}
+
+/// Reads the workloads in `path`, in either form.
+pub fn load_workloads(path: impl AsRef<Path>) -> Result<Vec<Workload>, WorkloadFileError> {
+    let path = path.as_ref();
+    let bytes =
+        fs::read(path).map_err(|err| WorkloadFileError(format!("failed to read {}: {}", path.display(), err)))?;
+    if bytes.starts_with(BINARY_MAGIC) {
+        #[cfg(feature = "serde")]
+        return workloads_from_bytes(&bytes);
+        #[cfg(not(feature = "serde"))]
+        return Err(WorkloadFileError(format!(
+            "{} is a binary workload file, which requires rebuilding with --features serde",
+            path.display()
+        )));
+    }
+    let text = String::from_utf8(bytes)
+        .map_err(|_| WorkloadFileError(format!("{} is neither a text nor a binary workload file", path.display())))?;
+    workloads_from_text(&text)
+}
+
+impl Workload {
+    /// Writes this workload, keys included, to `path` in the binary form.
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), WorkloadFileError> {
+        save_workloads_binary(path, std::slice::from_ref(self))
+    }
+
+    /// Reads the workload in `path`, which must hold exactly one, in either
+    /// form.
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WorkloadFileError> {
+        let path = path.as_ref();
+        let mut workloads = load_workloads(path)?;
+        match workloads.len() {
+            1 => Ok(workloads.remove(0)),
+            count => Err(WorkloadFileError(format!("{} holds {} workloads, expected one", path.display(), count))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let workloads: Vec<Workload> = [false, true]
+            .into_iter()
+            .map(|fake_signatures| {
+                Workload::generate(WorkloadConfig {
+                    num_accounts: 10,
+                    num_transactions: 25,
+                    transactions_per_block: 10,
+                    hot_accounts: 5,
+                    double_spends: 2,
+                    fake_signatures,
+                    tokens: if fake_signatures { 3 } else { 0 },
+                    nonceless: fake_signatures,
+                    compute_cost: if fake_signatures {
+                        ComputeCost::Uniform { min: 0, max: 100 }
+                    } else {
+                        ComputeCost::default()
+                    },
+                    near_max_balance: fake_signatures.then_some(64),
+                    hot_probability: if fake_signatures { 0.25 } else { 1.0 },
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("workloads.tsv");
+        save_workloads(&path, &workloads).unwrap();
+        let loaded = load_workloads(&path).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        for (original, loaded) in workloads.iter().zip(&loaded) {
+            assert_eq!(loaded.config, original.config);
+            assert_eq!(loaded.blocks.len(), original.blocks.len());
+            let addresses = |workload: &Workload| workload.accounts.iter().map(|account| account.address).collect::<Vec<_>>();
+            assert_eq!(addresses(loaded), addresses(original));
+            let hashes = |workload: &Workload| workload.transactions.iter().map(|tx| tx.tx_hash).collect::<Vec<_>>();
+            assert_eq!(hashes(loaded), hashes(original));
+            let costs = |workload: &Workload| workload.transactions.iter().map(|tx| tx.compute_cost).collect::<Vec<_>>();
+            assert_eq!(costs(loaded), costs(original));
+        }
+        assert!(loaded[0].validate().is_valid());
+        assert!(loaded[1].transactions.iter().any(|tx| tx.token.is_some()));
+
+        assert!(workloads_from_text("tx\t0x00").is_err());
+    }
+
+    #[test]
+    fn test_version_1_files_load() {
+        let workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 25,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            ..Default::default()
+        });
+        // Version 1 wrote only the first nine configuration fields and the
+        // first nine transaction fields
+        let text = workloads_to_text(std::slice::from_ref(&workload))
+            .lines()
+            .map(|line| match line.split('\t').next() {
+                Some("version") => "version\t1".to_string(),
+                Some("workload") => line.split('\t').take(10).collect::<Vec<_>>().join("\t"),
+                Some("tx") => line.split('\t').take(9).collect::<Vec<_>>().join("\t"),
+                _ => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let loaded = workloads_from_text(&text).unwrap();
+        assert_eq!(loaded[0].config, workload.config);
+        assert_eq!(loaded[0].transactions.len(), 25);
+
+        // Layouts are picked by version, not by field count
+        let current = workloads_to_text(std::slice::from_ref(&workload));
+        assert!(workloads_from_text(&current.replace("version\t2", "version\t1")).is_err());
+        assert!(workloads_from_text(&text.replace("version\t1", "version\t3")).is_err());
+        assert!(workloads_from_text(&text.replace("version\t1\n", "")).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_binary_round_trip() {
+        let mut workload = Workload::generate(WorkloadConfig {
+            num_accounts: 10,
+            num_transactions: 25,
+            transactions_per_block: 10,
+            hot_accounts: 5,
+            double_spends: 2,
+            tokens: 2,
+            compute_cost: ComputeCost::Uniform { min: 0, max: 100 },
+            ..Default::default()
+        });
+        // Irregular blocks are kept as they are
+        let last = workload.blocks.pop().unwrap();
+        workload.blocks.last_mut().unwrap().extend(last);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("workload.bin");
+        workload.save(&path).unwrap();
+        let loaded = Workload::load(&path).unwrap();
+
+        assert_eq!(loaded.config, workload.config);
+        let addresses = |workload: &Workload| workload.accounts.iter().map(|account| account.address).collect::<Vec<_>>();
+        assert_eq!(addresses(&loaded), addresses(&workload));
+        let fields = |workload: &Workload| {
+            workload
+                .transactions
+                .iter()
+                .map(|tx| (tx.from, tx.to, tx.value, tx.token, tx.nonce, tx.signature, tx.tx_hash, tx.compute_cost))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fields(&loaded), fields(&workload));
+        let block_lengths = |workload: &Workload| workload.blocks.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(block_lengths(&loaded), vec![10, 17]);
+        assert_eq!(loaded.accounts[0].signing_key.to_bytes(), workload.accounts[0].signing_key.to_bytes());
+
+        // Text files still load, and the binary form is smaller
+        let text_path = dir.path().join("workload.tsv");
+        save_workloads(&text_path, std::slice::from_ref(&workload)).unwrap();
+        assert_eq!(Workload::load(&text_path).unwrap().transactions.len(), 27);
+        assert!(fs::metadata(&path).unwrap().len() < fs::metadata(&text_path).unwrap().len());
+
+        let bytes = workloads_to_bytes(&[workload.clone(), workload]);
+        assert_eq!(workloads_from_bytes(&bytes).unwrap().len(), 2);
+        assert!(workloads_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        save_workloads_binary(&path, &workloads_from_bytes(&bytes).unwrap()).unwrap();
+        assert!(Workload::load(&path).is_err());
+    }
+}